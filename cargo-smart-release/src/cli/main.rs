@@ -56,6 +56,8 @@ fn main() -> anyhow::Result<()> {
             allow_fully_generated_changelogs,
             no_dependencies,
             no_isolate_dependencies_from_breaking_changes,
+            write_plan,
+            plan,
         } => {
             let verbose = execute || verbose;
             init_logging(verbose);
@@ -86,6 +88,9 @@ fn main() -> anyhow::Result<()> {
                 crates,
                 to_bump_spec(bump.as_deref().unwrap_or(DEFAULT_BUMP_SPEC))?,
                 to_bump_spec(bump_dependencies.as_deref().unwrap_or(DEFAULT_BUMP_SPEC))?,
+                write_plan
+                    .map(command::release::PlanFile::Write)
+                    .or_else(|| plan.map(command::release::PlanFile::Apply)),
             )?
         }
     };
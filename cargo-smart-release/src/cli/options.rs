@@ -22,6 +22,19 @@ pub enum SubCommands {
         #[clap(long, short = 'e', help_heading = Some("MAJOR"))]
         execute: bool,
 
+        /// Write the computed release plan, the crates to adjust or publish along with their versions, to the given file.
+        ///
+        /// The plan can be reviewed and passed to --plan when executing the release later. Place it outside of the working tree
+        /// or into an ignored directory like `target/` as untracked files prevent releases unless --allow-dirty is set.
+        #[clap(long, conflicts_with_all = &["execute", "plan"], help_heading = Some("MAJOR"))]
+        write_plan: Option<std::path::PathBuf>,
+
+        /// Abort unless the computed release plan is the same as the one previously written with --write-plan to the given file.
+        ///
+        /// This assures that nothing changed since the plan was reviewed, typically when used along with --execute.
+        #[clap(long, help_heading = Some("MAJOR"))]
+        plan: Option<std::path::PathBuf>,
+
         /// Always update the crates-index beforehand. It is used to determine if the computed version to be published was
         /// already published.
         #[clap(long, short = 'u', help_heading = Some("MAJOR"))]
@@ -26,6 +26,14 @@ pub mod release {
         pub changelog_links: bool,
         pub allow_changelog_github_release: bool,
     }
+    /// What to do with a file containing the release plan, which is the set of crates to adjust or publish along with their versions.
+    #[derive(Debug, Clone)]
+    pub enum PlanFile {
+        /// Write the plan once it was computed, typically during a dry-run.
+        Write(std::path::PathBuf),
+        /// Abort the release if the computed plan differs from the one previously written to the given file.
+        Apply(std::path::PathBuf),
+    }
 }
 #[path = "release/mod.rs"]
 mod release_impl;
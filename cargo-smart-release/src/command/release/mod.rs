@@ -5,7 +5,7 @@ use anyhow::bail;
 use crate::{
     changelog,
     changelog::{write::Linkables, Section},
-    command::release::Options,
+    command::release::{Options, PlanFile},
     traverse::{
         self, dependency,
         dependency::{ManifestAdjustment, VersionAdjustment},
@@ -20,6 +20,7 @@ mod cargo;
 mod git;
 mod github;
 mod manifest;
+mod plan;
 
 pub(crate) struct Context {
     base: crate::Context,
@@ -50,7 +51,13 @@ impl Context {
 
 /// In order to try dealing with https://github.com/sunng87/cargo-release/issues/224 and also to make workspace
 /// releases more selective.
-pub fn release(opts: Options, crates: Vec<String>, bump: BumpSpec, bump_dependencies: BumpSpec) -> anyhow::Result<()> {
+pub fn release(
+    opts: Options,
+    crates: Vec<String>,
+    bump: BumpSpec,
+    bump_dependencies: BumpSpec,
+    plan_file: Option<PlanFile>,
+) -> anyhow::Result<()> {
     if opts.dry_run_cargo_publish && !opts.dry_run {
         bail!("The --no-dry-run-cargo-publish flag is only effective without --execute")
     }
@@ -76,7 +83,7 @@ pub fn release(opts: Options, crates: Vec<String>, bump: BumpSpec, bump_dependen
         log::warn!("Crates.io index doesn't exist. Consider using --update-crates-index to help determining if release versions are published already");
     }
 
-    release_depth_first(ctx, opts)?;
+    release_depth_first(ctx, opts, plan_file)?;
     Ok(())
 }
 
@@ -91,7 +98,7 @@ impl From<Options> for crate::traverse::Options {
     }
 }
 
-fn release_depth_first(ctx: Context, opts: Options) -> anyhow::Result<()> {
+fn release_depth_first(ctx: Context, opts: Options, plan_file: Option<PlanFile>) -> anyhow::Result<()> {
     let crates = {
         crate::traverse::dependencies(&ctx.base, opts.into())
             .and_then(|crates| assure_crates_index_is_uptodate(crates, &ctx.base, opts.into()))
@@ -100,6 +107,23 @@ fn release_depth_first(ctx: Context, opts: Options) -> anyhow::Result<()> {
             })?
     };
 
+    let plan = plan::Plan::from_dependencies(&crates);
+    match plan_file {
+        Some(PlanFile::Write(path)) => {
+            plan.write_to_file(&path)?;
+            log::info!(
+                "Wrote release plan for {} crates to '{}'",
+                plan.crates.len(),
+                path.display()
+            );
+        }
+        Some(PlanFile::Apply(path)) => {
+            plan.assure_matches(&plan::Plan::from_file(&path)?)?;
+            log::info!("The release matches the plan at '{}'", path.display());
+        }
+        None => {}
+    }
+
     assure_working_tree_is_unchanged(opts)?;
     perform_release(&ctx, opts, &crates)?;
 
@@ -0,0 +1,159 @@
+use std::{path::Path, str::FromStr};
+
+use anyhow::{bail, Context};
+
+use crate::traverse::{self, dependency};
+
+/// The outcome of the workspace traversal as far as the release is concerned, which can be stored in a file during a dry-run
+/// and checked against before performing the release later.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct Plan {
+    /// All crates whose manifest will change, in the order in which they will be released.
+    pub crates: Vec<Entry>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct Entry {
+    pub name: String,
+    /// The version in the manifest before the release.
+    pub version: semver::Version,
+    /// The version in the manifest after the release, which is the currently set one if only dependency versions change.
+    pub next_version: semver::Version,
+    /// If true, the crate will be published at `next_version`, otherwise only its manifest is adjusted.
+    pub publish: bool,
+}
+
+/// Init
+impl Plan {
+    pub fn from_dependencies(crates: &[traverse::Dependency<'_>]) -> Self {
+        Plan {
+            crates: crates
+                .iter()
+                .filter(|dep| dep.mode.manifest_will_change())
+                .map(|dep| Entry {
+                    name: dep.package.name.clone(),
+                    version: dep.package.version.clone(),
+                    next_version: dep
+                        .mode
+                        .version_adjustment_bump()
+                        .map(|bump| bump.next_release.clone())
+                        .unwrap_or_else(|| dep.package.version.clone()),
+                    publish: matches!(dep.mode, dependency::Mode::ToBePublished { .. }),
+                })
+                .collect(),
+        }
+    }
+
+    pub fn from_toml(input: &str) -> anyhow::Result<Self> {
+        let doc = toml_edit::Document::from_str(input)?;
+        let mut crates = Vec::new();
+        if let Some(tables) = doc.get("crate") {
+            let tables = tables
+                .as_array_of_tables()
+                .context("'crate' must be an array of tables like [[crate]]")?;
+            for table in tables.iter() {
+                let string = |key: &str| {
+                    table
+                        .get(key)
+                        .and_then(|v| v.as_str())
+                        .with_context(|| format!("Every crate in the plan needs a '{}' string", key))
+                };
+                let version = |key: &str| -> anyhow::Result<semver::Version> {
+                    semver::Version::parse(string(key)?)
+                        .with_context(|| format!("Invalid '{}' of crate '{}'", key, string("name").unwrap_or("?")))
+                };
+                crates.push(Entry {
+                    name: string("name")?.to_owned(),
+                    version: version("version")?,
+                    next_version: version("next-version")?,
+                    publish: table
+                        .get("publish")
+                        .and_then(|v| v.as_bool())
+                        .context("Every crate in the plan needs a 'publish' boolean")?,
+                });
+            }
+        }
+        Ok(Plan { crates })
+    }
+
+    pub fn from_file(path: &Path) -> anyhow::Result<Self> {
+        let input = std::fs::read_to_string(path)
+            .with_context(|| format!("Could not read release plan at '{}'", path.display()))?;
+        Plan::from_toml(&input).with_context(|| format!("Could not parse release plan at '{}'", path.display()))
+    }
+}
+
+/// Access
+impl Plan {
+    pub fn to_toml(&self) -> String {
+        let mut doc = toml_edit::Document::new();
+        let mut tables = toml_edit::ArrayOfTables::new();
+        for entry in &self.crates {
+            let mut table = toml_edit::Table::new();
+            table["name"] = toml_edit::value(entry.name.as_str());
+            table["version"] = toml_edit::value(entry.version.to_string());
+            table["next-version"] = toml_edit::value(entry.next_version.to_string());
+            table["publish"] = toml_edit::value(entry.publish);
+            tables.push(table);
+        }
+        doc["crate"] = toml_edit::Item::ArrayOfTables(tables);
+        format!(
+            "# The release plan of 'cargo smart-release', to be checked against with --plan <path> before releasing.\n{}",
+            doc
+        )
+    }
+
+    pub fn write_to_file(&self, path: &Path) -> anyhow::Result<()> {
+        std::fs::write(path, self.to_toml())
+            .with_context(|| format!("Could not write release plan to '{}'", path.display()))
+    }
+
+    /// Fail if this plan, as computed from the current state of the workspace, differs from the `expected` one.
+    pub fn assure_matches(&self, expected: &Plan) -> anyhow::Result<()> {
+        if self == expected {
+            return Ok(());
+        }
+        let mut differences = Vec::new();
+        for entry in &expected.crates {
+            match self.crates.iter().find(|e| e.name == entry.name) {
+                None => differences.push(format!("'{}' is not part of the release anymore", entry.name)),
+                Some(actual) if actual != entry => differences.push(format!(
+                    "'{}' was planned as {}, but is now {}",
+                    entry.name,
+                    entry.describe(),
+                    actual.describe()
+                )),
+                Some(_) => {}
+            }
+        }
+        for entry in self
+            .crates
+            .iter()
+            .filter(|e| !expected.crates.iter().any(|expected| expected.name == e.name))
+        {
+            differences.push(format!(
+                "'{}' was not planned, but is now {}",
+                entry.name,
+                entry.describe()
+            ));
+        }
+        if differences.is_empty() {
+            differences.push("the release order changed".into());
+        }
+        bail!(
+            "The release plan changed since it was written: {}. Write a new plan with --write-plan and review it.",
+            differences.join(", ")
+        )
+    }
+}
+
+impl Entry {
+    fn describe(&self) -> String {
+        format!(
+            "{} {} ➡ {}",
+            if self.publish { "published" } else { "adjusted" },
+            self.version,
+            self.next_version
+        )
+    }
+}
@@ -56,10 +56,77 @@ mod additions {
         (title, additions)
     }
 
+    /// Trailer tokens whose values refer to issues, compared case-insensitively.
+    const ISSUE_TRAILERS: &[&str] = &[
+        "close",
+        "closes",
+        "closed",
+        "fix",
+        "fixes",
+        "fixed",
+        "resolve",
+        "resolves",
+        "resolved",
+        "ref",
+        "refs",
+        "references",
+    ];
+
+    /// Obtain issue ids from trailers like `Fixes: #123` or `Closes #42, #43` in the last paragraph of `message`,
+    /// unless it's the title.
+    pub fn from_trailers(message: &str) -> Vec<Addition> {
+        let last_paragraph = match message.trim_end().rfind("\n\n") {
+            Some(pos) => &message.trim_end()[pos + 2..],
+            None => return Vec::new(),
+        };
+        let mut additions = Vec::new();
+        for line in last_paragraph.lines() {
+            let token_end = line.find(|c: char| c == ':' || c.is_whitespace()).unwrap_or(line.len());
+            let token = &line[..token_end];
+            if !ISSUE_TRAILERS.iter().any(|t| t.eq_ignore_ascii_case(token)) {
+                continue;
+            }
+            let value = line[token_end..].trim_start();
+            let value = value.strip_prefix(':').unwrap_or(value);
+            for id in value
+                .split(|c: char| c == ',' || c.is_whitespace())
+                .filter_map(|item| item.strip_prefix('#'))
+                .filter(|id| !id.is_empty() && id.chars().all(|c| c.is_ascii_digit()))
+            {
+                if !additions
+                    .iter()
+                    .any(|a| matches!(a, Addition::IssueId(existing) if existing == id))
+                {
+                    additions.push(Addition::IssueId(id.to_owned()));
+                }
+            }
+        }
+        additions
+    }
+
     #[cfg(test)]
     mod tests {
         use super::*;
 
+        #[test]
+        fn issue_ids_from_trailers() {
+            assert_eq!(
+                from_trailers("title\n\nbody\n\nFixes: #123, #42\nSigned-off-by: me\ncloses #7 #123"),
+                vec![
+                    Addition::IssueId("123".into()),
+                    Addition::IssueId("42".into()),
+                    Addition::IssueId("7".into())
+                ]
+            );
+        }
+
+        #[test]
+        fn no_issue_ids_from_title_prose_or_other_trailers() {
+            assert_eq!(from_trailers("Fixes: #123"), vec![], "the title isn't a trailer");
+            assert_eq!(from_trailers("title\n\nFixes 3 bugs, see issue 4"), vec![]);
+            assert_eq!(from_trailers("title\n\nSee-also: #5\nFixes: #abc"), vec![]);
+        }
+
         #[test]
         fn no_addition() {
             let (nt, a) = strip("hello there [abc] (abc)".into());
@@ -106,7 +173,16 @@ impl From<&'_ str> for Message {
                     None,
                 )
             });
-        let (title, additions) = additions::strip(title);
+        let (title, mut additions) = additions::strip(title);
+        for addition in additions::from_trailers(m) {
+            let Addition::IssueId(id) = &addition;
+            if !additions
+                .iter()
+                .any(|a| matches!(a, Addition::IssueId(existing) if existing == id))
+            {
+                additions.push(addition);
+            }
+        }
         Message {
             title: title.into_owned(),
             kind: as_static_str(kind),
@@ -198,4 +274,19 @@ mod tests {
             }
         )
     }
+
+    #[test]
+    fn no_conventional_additions_from_trailers() {
+        assert_eq!(
+            Message::from("hi (#1)\n\nbody\n\nFixes: #1, #2\nSigned: bar"),
+            Message {
+                title: "hi".into(),
+                body: Some("body".into()),
+                kind: None,
+                breaking: false,
+                breaking_description: None,
+                additions: vec![Addition::IssueId("1".into()), Addition::IssueId("2".into())]
+            }
+        )
+    }
 }
@@ -100,10 +100,21 @@ pub fn remote_url(repo: &git::Repository) -> anyhow::Result<Option<git::Url>> {
 }
 
 pub fn author() -> anyhow::Result<git_repository::actor::Signature> {
-    Ok(git_repository::actor::SignatureRef::from_bytes::<()>(
-        &Command::new("git").arg("var").arg("GIT_AUTHOR_IDENT").output()?.stdout,
-    )?
-    .to_owned())
+    let out = Command::new("git").arg("var").arg("GIT_AUTHOR_IDENT").output()?;
+    if !out.status.success() {
+        bail!(
+            "Could not obtain the author signature with 'git var GIT_AUTHOR_IDENT': {}",
+            out.stderr.as_bstr().trim().as_bstr()
+        )
+    }
+    Ok(git_repository::actor::SignatureRef::from_bytes::<()>(&out.stdout)
+        .map_err(|_| {
+            anyhow!(
+                "Could not parse author signature '{}' as returned by 'git var GIT_AUTHOR_IDENT'",
+                out.stdout.as_bstr().trim().as_bstr()
+            )
+        })?
+        .to_owned())
 }
 
 pub fn strip_tag_path(name: &FullNameRef) -> &BStr {
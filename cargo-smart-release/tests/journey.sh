@@ -12,7 +12,7 @@ snapshot="$root/snapshots"
 fixtures="$root/fixtures"
 
 SUCCESSFULLY=0
-# WITH_FAILURE=1
+WITH_FAILURE=1
 
 function set-static-git-environment() {
   set -a
@@ -162,6 +162,30 @@ title "smart-release"
           }
         )
       )
+      (with 'a written release plan'
+        mkdir -p target
+        it "succeeds" && {
+          WITH_SNAPSHOT="$snapshot/a-dry-run-success-write-plan" \
+          expect_run $SUCCESSFULLY "$exe" smart-release a --no-push --no-publish -v --allow-dirty -b minor --write-plan target/plan.toml
+        }
+        it "wrote all crates with a changing manifest" && {
+          WITH_SNAPSHOT="$snapshot/a-release-plan" \
+          expect_run $SUCCESSFULLY cat target/plan.toml
+        }
+        (when 'checking the same release against the plan'
+          it "succeeds" && {
+            WITH_SNAPSHOT="$snapshot/a-dry-run-success-apply-plan" \
+            expect_run $SUCCESSFULLY "$exe" smart-release a --no-push --no-publish -v --allow-dirty -b minor --plan target/plan.toml
+          }
+        )
+        (when 'checking a different release against the plan'
+          it "fails" && {
+            WITH_SNAPSHOT="$snapshot/a-dry-run-failure-apply-plan-mismatch" \
+            expect_run $WITH_FAILURE "$exe" smart-release a --no-push --no-publish -v --allow-dirty -b minor --no-bump-on-demand --plan target/plan.toml
+          }
+        )
+        rm target/plan.toml
+      )
     )
     (with '--execute but without side-effects'
       it "succeeds" && {
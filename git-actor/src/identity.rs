@@ -0,0 +1,134 @@
+use std::{borrow::Cow, ffi::OsString, time::SystemTime};
+
+use bstr::{BStr, BString, ByteSlice, ByteVec};
+
+use crate::{Signature, Time};
+
+/// The role of the actor whose identity to determine with [`Signature::from_environment()`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Role {
+    /// The actor who originally wrote the change, as configured by `GIT_AUTHOR_*` variables and `author.*` values.
+    Author,
+    /// The actor who committed the change, as configured by `GIT_COMMITTER_*` variables and `committer.*` values.
+    Committer,
+}
+
+/// The error returned by [`Signature::from_environment()`].
+#[derive(Debug, thiserror::Error)]
+#[allow(missing_docs)]
+pub enum Error {
+    #[error("No name was given and auto-detection is disabled by user.useConfigOnly")]
+    NameUnset,
+    #[error("No email was given and auto-detection is disabled by user.useConfigOnly")]
+    EmailUnset,
+    #[error("Unable to auto-detect the email address as user name or hostname are unknown")]
+    EmailUndetectable,
+    #[error("Empty name (for <{email}>) is not allowed")]
+    EmptyName { email: BString },
+    #[error("The value {value:?} of user.useConfigOnly is not a boolean")]
+    InvalidUseConfigOnly { value: BString },
+    #[error("The date in {variable} could not be parsed")]
+    InvalidDate {
+        variable: &'static str,
+        source: git_date::parse::Error,
+    },
+}
+
+impl Signature {
+    /// Determine the identity of the actor with `role` like git does when creating commits, using `config` to obtain
+    /// configuration values by their `section.key` name, like `user.name`, and `env` to read environment variables.
+    ///
+    /// The name is taken from the first value that is set in…
+    ///
+    /// * …the `GIT_AUTHOR_NAME|GIT_COMMITTER_NAME` environment variables…
+    /// * …the `author.name|committer.name` configuration…
+    /// * …the `user.name` configuration…
+    /// * …the login name in `USER`, `USERNAME` or `LOGNAME`…
+    ///
+    /// …and the email from the first value that is set in…
+    ///
+    /// * …the `GIT_AUTHOR_EMAIL|GIT_COMMITTER_EMAIL` environment variables…
+    /// * …the `author.email|committer.email` configuration…
+    /// * …the `user.email` configuration…
+    /// * …the `EMAIL` environment variable…
+    /// * …the login name followed by `@` and the hostname in `HOSTNAME` or `COMPUTERNAME`…
+    ///
+    /// …both in that order. If `user.useConfigOnly` is true, the name and email are never auto-detected from `EMAIL`,
+    /// the login name or the hostname, and an error is returned instead.
+    ///
+    /// The time is parsed from `GIT_AUTHOR_DATE|GIT_COMMITTER_DATE`, and is the current local time otherwise.
+    pub fn from_environment<'a>(
+        role: Role,
+        config: impl Fn(&str) -> Option<Cow<'a, BStr>>,
+        env: impl Fn(&str) -> Option<OsString>,
+    ) -> Result<Signature, Error> {
+        let (variable_prefix, section, date_variable) = match role {
+            Role::Author => ("GIT_AUTHOR", "author", "GIT_AUTHOR_DATE"),
+            Role::Committer => ("GIT_COMMITTER", "committer", "GIT_COMMITTER_DATE"),
+        };
+        let env = |name: &str| {
+            env(name)
+                .filter(|value| !value.is_empty())
+                .map(|value| Vec::from_os_str_lossy(&value).into_owned().into())
+        };
+        let config_value = |key: &str| {
+            config(&format!("{}.{}", section, key))
+                .or_else(|| config(&format!("user.{}", key)))
+                .map(Cow::into_owned)
+        };
+        let use_config_only = config("user.useConfigOnly")
+            .map(|value| {
+                boolean(value.as_ref()).ok_or_else(|| Error::InvalidUseConfigOnly {
+                    value: value.into_owned(),
+                })
+            })
+            .transpose()?
+            .unwrap_or(false);
+        let login_name = || env("USER").or_else(|| env("USERNAME")).or_else(|| env("LOGNAME"));
+
+        let email: BString = match env(&format!("{}_EMAIL", variable_prefix)).or_else(|| config_value("email")) {
+            Some(email) => email,
+            None if use_config_only => return Err(Error::EmailUnset),
+            None => match env("EMAIL") {
+                Some(email) => email,
+                None => {
+                    let mut email = login_name().ok_or(Error::EmailUndetectable)?;
+                    let hostname = env("HOSTNAME")
+                        .or_else(|| env("COMPUTERNAME"))
+                        .ok_or(Error::EmailUndetectable)?;
+                    email.push(b'@');
+                    email.extend_from_slice(&hostname);
+                    email
+                }
+            },
+        };
+        let name: BString = match env(&format!("{}_NAME", variable_prefix)).or_else(|| config_value("name")) {
+            Some(name) => name,
+            None if use_config_only => return Err(Error::NameUnset),
+            None => login_name().unwrap_or_default(),
+        };
+        if name.trim().is_empty() {
+            return Err(Error::EmptyName { email });
+        }
+
+        let time = match env(date_variable) {
+            Some(date) => git_date::parse(date.to_str_lossy().as_ref(), Some(SystemTime::now())).map_err(|source| {
+                Error::InvalidDate {
+                    variable: date_variable,
+                    source,
+                }
+            })?,
+            None => Time::now_local_or_utc(),
+        };
+        Ok(Signature { name, email, time })
+    }
+}
+
+fn boolean(value: &BStr) -> Option<bool> {
+    let value = value.to_ascii_lowercase();
+    Some(match value.as_slice() {
+        b"true" | b"yes" | b"on" | b"1" => true,
+        b"false" | b"no" | b"off" | b"0" | b"" => false,
+        _ => return None,
+    })
+}
@@ -12,6 +12,8 @@
 use bstr::{BStr, BString};
 pub use git_date::{time::Sign, Time};
 
+///
+pub mod identity;
 ///
 pub mod signature;
 
@@ -9,7 +9,7 @@ use nom::{
     IResult,
 };
 
-use crate::{Sign, SignatureRef, Time};
+use crate::{signature::Defect, Sign, SignatureRef, Time};
 
 const SPACE: &[u8] = b" ";
 
@@ -65,6 +65,136 @@ pub fn decode<'a, E: ParseError<&'a [u8]> + ContextError<&'a [u8]>>(
     ))
 }
 
+/// Parse a signature from `i` which is expected to be a single line like `decode()` would see it, but instead of failing
+/// on malformed input, recover as much information as possible and return all [defects][Defect] that were found.
+///
+/// Well-formed signatures are decoded just like [`decode()`] does it, without any defects, which is to say that
+/// [`decode()`] should be used to reject signatures that have defects.
+///
+/// Missing or malformed timestamps default to 0, and timezones that can't be interpreted default to `+0000`. The email
+/// of signatures without angle brackets is assumed to be the last word containing `@`, or empty if there is none.
+pub fn decode_lenient(i: &[u8]) -> (SignatureRef<'_>, Vec<Defect>) {
+    if let Ok((_, signature)) = decode::<()>(i) {
+        return (signature, Vec::new());
+    }
+
+    let mut defects = Vec::new();
+    let (name, email, date) = match i.find_byte(b'<') {
+        Some(start) => {
+            let name = &i[..start];
+            let name = match name.strip_suffix(SPACE) {
+                Some(name) => name,
+                None => {
+                    defects.push(Defect::MalformedEmail);
+                    name
+                }
+            };
+            let rest = &i[start + 1..];
+            match rest.find_byte(b'>') {
+                Some(end) => {
+                    let date = &rest[end + 1..];
+                    if !date.is_empty() && (!date.starts_with(SPACE) || date[1..].starts_with(SPACE)) {
+                        defects.push(Defect::MalformedEmail);
+                    }
+                    (name, &rest[..end], date)
+                }
+                None => {
+                    defects.push(Defect::MalformedEmail);
+                    let (email, date) = split_trailing_date(rest);
+                    (name, email.trim(), date)
+                }
+            }
+        }
+        None => {
+            defects.push(Defect::MalformedEmail);
+            let (identity, date) = split_trailing_date(i);
+            let identity = identity.trim();
+            let email_start = identity.rfind_byte(b' ').map_or(0, |pos| pos + 1);
+            if identity[email_start..].contains(&b'@') {
+                (identity[..email_start].trim_end(), &identity[email_start..], date)
+            } else {
+                (identity, &b""[..], date)
+            }
+        }
+    };
+
+    let mut tokens = date.trim().split(|b| *b == b' ');
+    let seconds_since_unix_epoch = match tokens.next().and_then(|t| btoi::<u32>(t).ok()) {
+        Some(seconds) => seconds,
+        None => {
+            defects.push(Defect::InvalidTimestamp);
+            0
+        }
+    };
+    let mut tokens = tokens.peekable();
+    if tokens.peek().map_or(false, |t| t.is_empty()) {
+        defects.push(Defect::InvalidTimestamp);
+    }
+    let mut tokens = tokens.filter(|t| !t.is_empty());
+    let (sign, offset_in_seconds) = match tokens.next().map(|tz| (tz, lenient_timezone(tz))) {
+        Some((tz, Some((sign, offset)))) => {
+            if !(tz.len() == 5 && matches!(tz[0], b'+' | b'-')) {
+                defects.push(Defect::InvalidTimezone);
+            }
+            (sign, offset)
+        }
+        Some((_, None)) | None => {
+            defects.push(Defect::InvalidTimezone);
+            (Sign::Plus, 0)
+        }
+    };
+
+    (
+        SignatureRef {
+            name: name.as_bstr(),
+            email: email.as_bstr(),
+            time: Time {
+                seconds_since_unix_epoch,
+                offset_in_seconds,
+                sign,
+            },
+        },
+        defects,
+    )
+}
+
+/// Split `i` into the part before the trailing timestamp and timezone, and the timestamp and timezone themselves,
+/// for use when the end of the email can't be determined.
+fn split_trailing_date(i: &[u8]) -> (&[u8], &[u8]) {
+    let token_start = |i: &[u8]| i.rfind_byteset(b" \t").map_or(0, |pos| pos + 1);
+    let is_timestamp = |t: &[u8]| !t.is_empty() && t.iter().all(u8::is_ascii_digit);
+    let i_trimmed = i.trim_end();
+    let last = token_start(i_trimmed);
+    let before_last = i_trimmed[..last].trim_end();
+    let second_to_last = token_start(before_last);
+    if lenient_timezone(&i_trimmed[last..]).is_some() && is_timestamp(&before_last[second_to_last..]) {
+        i.split_at(second_to_last)
+    } else if is_timestamp(&i_trimmed[last..]) {
+        i.split_at(last)
+    } else {
+        (i, &[])
+    }
+}
+
+/// Interpret `tz` like git does, as optionally signed number of the form `HHMM`, or `None` if it can't be represented.
+fn lenient_timezone(tz: &[u8]) -> Option<(Sign, i32)> {
+    let (sign, digits) = match tz.split_first()? {
+        (b'-', digits) => (Sign::Minus, digits),
+        (b'+', digits) => (Sign::Plus, digits),
+        _ => (Sign::Plus, tz),
+    };
+    if digits.is_empty() || digits.len() > 4 || !digits.iter().all(u8::is_ascii_digit) {
+        return None;
+    }
+    let hhmm = btoi::<i32>(digits).ok()?;
+    let (hours, minutes) = (hhmm / 100, hhmm % 100);
+    if minutes >= 60 {
+        return None;
+    }
+    let offset = (hours * 3600 + minutes * 60) * if sign == Sign::Minus { -1 } else { 1 };
+    Some((sign, offset))
+}
+
 #[cfg(test)]
 mod tests {
     mod parse_signature {
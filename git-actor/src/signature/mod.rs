@@ -1,7 +1,10 @@
 mod _ref {
     use bstr::{BStr, ByteSlice};
 
-    use crate::{signature::decode, Signature, SignatureRef};
+    use crate::{
+        signature::{decode, decode_lenient, Defect},
+        Signature, SignatureRef,
+    };
 
     impl<'a> SignatureRef<'a> {
         /// Deserialize a signature from the given `data`.
@@ -12,6 +15,14 @@ mod _ref {
             decode(data).map(|(_, t)| t)
         }
 
+        /// Deserialize a signature from the given `data` without ever failing, recovering from malformed input
+        /// as good as possible, and return all defects that were found along with it.
+        ///
+        /// Use [`from_bytes()`][SignatureRef::from_bytes()] to reject signatures with defects instead.
+        pub fn from_bytes_lenient(data: &'a [u8]) -> (SignatureRef<'a>, Vec<Defect>) {
+            decode_lenient(data)
+        }
+
         /// Create an owned instance from this shared one.
         pub fn to_owned(&self) -> Signature {
             Signature {
@@ -135,6 +146,18 @@ mod write {
     }
 }
 
+/// A problem with a signature that [`decode_lenient()`] recovered from, but which [`decode()`] rejects.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Defect {
+    /// The email isn't enclosed in `<` and `>`, or isn't separated from the name and timestamp by a single space.
+    MalformedEmail,
+    /// The timestamp is missing, isn't a number of seconds since the unix epoch or isn't followed by a single space,
+    /// and is assumed to be 0 if it can't be read.
+    InvalidTimestamp,
+    /// The timezone is missing or isn't of the form `+|-HHMM`, and is assumed to be `+0000` if it can't be interpreted.
+    InvalidTimezone,
+}
+
 ///
 mod decode;
-pub use decode::decode;
+pub use decode::{decode, decode_lenient};
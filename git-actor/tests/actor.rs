@@ -2,8 +2,11 @@ use std::path::PathBuf;
 
 pub use git_testtools::hex_to_id;
 
+pub type Result<T = ()> = std::result::Result<T, Box<dyn std::error::Error>>;
+
 pub fn fixture(path: &str) -> PathBuf {
     PathBuf::from("tests/fixtures").join(path)
 }
 
+mod identity;
 mod signature;
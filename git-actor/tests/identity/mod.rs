@@ -0,0 +1,120 @@
+use std::{borrow::Cow, collections::HashMap, ffi::OsString};
+
+use bstr::BStr;
+use git_actor::{
+    identity::{Error, Role},
+    Signature,
+};
+
+fn from_environment(role: Role, config: &[(&str, &'static str)], env: &[(&str, &str)]) -> Result<Signature, Error> {
+    let config: HashMap<_, _> = config.iter().copied().collect();
+    let env: HashMap<_, _> = env.iter().copied().collect();
+    Signature::from_environment(
+        role,
+        |key| {
+            config
+                .get(key)
+                .map(|value| Cow::Borrowed::<BStr>(value.as_bytes().into()))
+        },
+        |name| env.get(name).map(OsString::from),
+    )
+}
+
+#[test]
+fn environment_variables_take_precedence_over_configuration() -> crate::Result {
+    let config = [
+        ("user.name", "user"),
+        ("user.email", "user@example.com"),
+        ("author.name", "author"),
+    ];
+    let env = [
+        ("GIT_AUTHOR_NAME", "env author"),
+        ("GIT_COMMITTER_EMAIL", "env-committer@example.com"),
+        ("GIT_AUTHOR_DATE", "1660874655 +0800"),
+    ];
+
+    let author = from_environment(Role::Author, &config, &env)?;
+    assert_eq!(author.name, "env author");
+    assert_eq!(author.email, "user@example.com", "user.* is the fallback for author.*");
+    assert_eq!(author.time, git_actor::Time::new(1660874655, 8 * 3600));
+
+    let committer = from_environment(Role::Committer, &config, &env)?;
+    assert_eq!(committer.name, "user");
+    assert_eq!(committer.email, "env-committer@example.com");
+    Ok(())
+}
+
+#[test]
+fn role_specific_configuration_takes_precedence_over_user_configuration() -> crate::Result {
+    let config = [
+        ("user.name", "user"),
+        ("user.email", "user@example.com"),
+        ("committer.name", "committer"),
+        ("committer.email", "committer@example.com"),
+    ];
+    let committer = from_environment(Role::Committer, &config, &[])?;
+    assert_eq!(committer.name, "committer");
+    assert_eq!(committer.email, "committer@example.com");
+
+    let author = from_environment(Role::Author, &config, &[])?;
+    assert_eq!(author.name, "user");
+    assert_eq!(author.email, "user@example.com");
+    Ok(())
+}
+
+#[test]
+fn email_and_name_are_auto_detected_unless_use_config_only_is_set() -> crate::Result {
+    let env = [("USER", "login"), ("HOSTNAME", "host"), ("EMAIL", "")];
+    let signature = from_environment(Role::Author, &[], &env)?;
+    assert_eq!(signature.name, "login");
+    assert_eq!(signature.email, "login@host", "empty variables are ignored");
+
+    let env = [("USER", "login"), ("EMAIL", "email@example.com")];
+    let signature = from_environment(Role::Committer, &[], &env)?;
+    assert_eq!(signature.email, "email@example.com");
+
+    let config = [("user.useConfigOnly", "true")];
+    let err = from_environment(Role::Committer, &config, &env).unwrap_err();
+    assert!(matches!(err, Error::EmailUnset), "{err:?}");
+
+    let config = [("user.useConfigOnly", "true"), ("user.email", "user@example.com")];
+    let err = from_environment(Role::Committer, &config, &env).unwrap_err();
+    assert!(matches!(err, Error::NameUnset), "{err:?}");
+
+    let env = [("GIT_COMMITTER_NAME", "env committer")];
+    let signature = from_environment(Role::Committer, &config, &env)?;
+    assert_eq!(
+        signature.name, "env committer",
+        "the environment is fine, only auto-detection is disabled"
+    );
+    Ok(())
+}
+
+#[test]
+fn errors() {
+    let err = from_environment(Role::Author, &[], &[("USER", "login")]).unwrap_err();
+    assert!(matches!(err, Error::EmailUndetectable), "{err:?}");
+
+    let err = from_environment(Role::Author, &[("user.email", "user@example.com")], &[]).unwrap_err();
+    assert!(matches!(err, Error::EmptyName { .. }), "{err:?}");
+
+    let err = from_environment(Role::Author, &[("user.useConfigOnly", "maybe")], &[]).unwrap_err();
+    assert!(matches!(err, Error::InvalidUseConfigOnly { .. }), "{err:?}");
+
+    let err = from_environment(
+        Role::Committer,
+        &[("user.email", "user@example.com"), ("user.name", "user")],
+        &[("GIT_COMMITTER_DATE", "not a date")],
+    )
+    .unwrap_err();
+    assert!(
+        matches!(
+            err,
+            Error::InvalidDate {
+                variable: "GIT_COMMITTER_DATE",
+                ..
+            }
+        ),
+        "{err:?}"
+    );
+}
@@ -77,3 +77,127 @@ fn round_trip() -> Result<(), Box<dyn std::error::Error>> {
     }
     Ok(())
 }
+
+mod decode_lenient {
+    use git_actor::{signature::Defect, Sign, SignatureRef, Time};
+
+    fn decode(input: &str) -> (SignatureRef<'_>, Vec<Defect>) {
+        let res = SignatureRef::from_bytes_lenient(input.as_bytes());
+        assert_eq!(
+            SignatureRef::from_bytes::<()>(input.as_bytes()).is_ok(),
+            res.1.is_empty(),
+            "strict decoding fails exactly if there are defects: {input:?}"
+        );
+        res
+    }
+
+    fn time(seconds_since_unix_epoch: u32, offset_in_seconds: i32) -> Time {
+        Time::new(seconds_since_unix_epoch, offset_in_seconds)
+    }
+
+    #[test]
+    fn well_formed_signatures_have_no_defects() {
+        let (sig, defects) = decode("name <email> 1528473343 -0230");
+        assert_eq!(defects, []);
+        assert_eq!(sig.name, "name");
+        assert_eq!(sig.email, "email");
+        assert_eq!(
+            sig.time,
+            Time {
+                seconds_since_unix_epoch: 1528473343,
+                offset_in_seconds: -9000,
+                sign: Sign::Minus,
+            }
+        );
+    }
+
+    #[test]
+    fn missing_email_brackets() {
+        let (sig, defects) = decode("first last name@example.com 1528473343 +0100");
+        assert_eq!(defects, [Defect::MalformedEmail]);
+        assert_eq!(sig.name, "first last");
+        assert_eq!(sig.email, "name@example.com");
+        assert_eq!(sig.time, time(1528473343, 3600));
+
+        let (sig, defects) = decode("name 1528473343 +0100");
+        assert_eq!(defects, [Defect::MalformedEmail]);
+        assert_eq!(sig.name, "name");
+        assert_eq!(sig.email, "", "without @ there is no email");
+        assert_eq!(sig.time, time(1528473343, 3600));
+
+        let (sig, defects) = decode("name <email 1528473343 +0100");
+        assert_eq!(defects, [Defect::MalformedEmail]);
+        assert_eq!(sig.name, "name");
+        assert_eq!(sig.email, "email");
+        assert_eq!(sig.time, time(1528473343, 3600));
+
+        let (sig, defects) = decode("name<email>1528473343 +0100");
+        assert_eq!(defects, [Defect::MalformedEmail, Defect::MalformedEmail]);
+        assert_eq!(sig.name, "name");
+        assert_eq!(sig.email, "email");
+        assert_eq!(sig.time, time(1528473343, 3600));
+    }
+
+    #[test]
+    fn broken_timezones() {
+        for (input, offset) in [
+            ("name <email> 1528473343 +05", 5 * 60),
+            ("name <email> 1528473343 -130", -90 * 60),
+            ("name <email> 1528473343 0200", 2 * 3600),
+        ] {
+            let (sig, defects) = decode(input);
+            assert_eq!(defects, [Defect::InvalidTimezone], "{input:?}");
+            assert_eq!(sig.email, "email");
+            assert_eq!(
+                sig.time.offset_in_seconds, offset,
+                "{input:?}: interpreted as [+|-]HHMM"
+            );
+            assert_eq!(sig.time.seconds_since_unix_epoch, 1528473343);
+        }
+
+        for input in [
+            "name <email> 1528473343 +0a00",
+            "name <email> 1528473343 +05:30",
+            "name <email> 1528473343",
+        ] {
+            let (sig, defects) = decode(input);
+            assert_eq!(defects, [Defect::InvalidTimezone], "{input:?}");
+            assert_eq!(
+                sig.time,
+                time(1528473343, 0),
+                "{input:?}: uninterpretable timezones are +0000"
+            );
+        }
+    }
+
+    #[test]
+    fn broken_timestamps() {
+        let (sig, defects) = decode("name <email> abc +0100");
+        assert_eq!(defects, [Defect::InvalidTimestamp]);
+        assert_eq!(sig.time, time(0, 3600));
+
+        let (sig, defects) = decode("name <email> 1528473343  +0100");
+        assert_eq!(defects, [Defect::InvalidTimestamp]);
+        assert_eq!(sig.time, time(1528473343, 3600), "the value is still recovered");
+
+        let (sig, defects) = decode("name <email>");
+        assert_eq!(defects, [Defect::InvalidTimestamp, Defect::InvalidTimezone]);
+        assert_eq!(sig.name, "name");
+        assert_eq!(sig.email, "email");
+        assert_eq!(sig.time, time(0, 0));
+    }
+
+    #[test]
+    fn empty_input() {
+        let (sig, defects) = decode("");
+        assert_eq!(
+            defects,
+            [
+                Defect::MalformedEmail,
+                Defect::InvalidTimestamp,
+                Defect::InvalidTimezone
+            ]
+        );
+        assert_eq!(sig, SignatureRef::default());
+    }
+}
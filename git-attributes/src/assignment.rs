@@ -26,3 +26,22 @@ impl<'a> Assignment {
         AssignmentRef::new(self.name.as_ref(), self.state.as_ref())
     }
 }
+
+impl std::fmt::Display for AssignmentRef<'_> {
+    /// Format ourselves like we would appear in an attributes file.
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.state {
+            StateRef::Set => write!(f, "{}", self.name.as_str()),
+            StateRef::Unset => write!(f, "-{}", self.name.as_str()),
+            StateRef::Unspecified => write!(f, "!{}", self.name.as_str()),
+            StateRef::Value(value) => write!(f, "{}={}", self.name.as_str(), value),
+        }
+    }
+}
+
+impl std::fmt::Display for Assignment {
+    /// Format ourselves like we would appear in an attributes file.
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.as_ref().fmt(f)
+    }
+}
@@ -0,0 +1,164 @@
+use bstr::{BString, ByteSlice, ByteVec};
+
+use crate::{parse, Assignment, File, StateRef};
+
+/// The error returned by [`File::push()`].
+#[derive(Debug, thiserror::Error)]
+#[allow(missing_docs)]
+pub enum Error {
+    #[error("The pattern '{pattern}' is invalid or negative, which isn't allowed in attribute files")]
+    InvalidPattern { pattern: BString },
+    #[error("The value of attribute '{name}' must not contain whitespace: {value:?}")]
+    ValueWithWhitespace { name: String, value: BString },
+}
+
+/// Init
+impl File {
+    /// Keep all lines of `bytes`, the content of an attributes file, to be able to edit them.
+    ///
+    /// This never fails as lines are kept as they are, use [`File::lines()`] to parse them.
+    pub fn from_bytes(bytes: &[u8]) -> Self {
+        File {
+            lines: bytes.lines_with_terminator().map(Into::into).collect(),
+        }
+    }
+}
+
+/// Access
+impl File {
+    /// Parse all lines that define patterns or macros, with their attribute assignments and line number, just like
+    /// [`parse()`][crate::parse()] would.
+    pub fn lines(&self) -> impl Iterator<Item = Result<(parse::Kind, parse::Iter<'_>, usize), parse::Error>> {
+        self.lines.iter().enumerate().filter_map(|(idx, line)| {
+            crate::parse(line).next().map(|res| {
+                res.map(|(kind, attrs, _)| (kind, attrs, idx + 1))
+                    .map_err(|err| with_line_number(err, idx + 1))
+            })
+        })
+    }
+
+    /// Return true if there is a line for `pattern`, which is compared after parsing such that `"*.bin"` and `*.bin`
+    /// are considered the same.
+    pub fn contains(&self, pattern: impl AsRef<[u8]>) -> bool {
+        let pattern = git_glob::Pattern::from_bytes(pattern.as_ref());
+        pattern.is_some() && self.lines.iter().any(|line| pattern_of(line) == pattern)
+    }
+
+    /// Write all lines to `out`, reproducing the original file exactly if it wasn't changed.
+    pub fn write_to(&self, mut out: impl std::io::Write) -> std::io::Result<()> {
+        for line in &self.lines {
+            out.write_all(line)?;
+        }
+        Ok(())
+    }
+
+    /// Like [`write_to()`][Self::write_to()], but writes infallibly into memory.
+    pub fn to_bstring(&self) -> BString {
+        let mut buf = Vec::<u8>::new();
+        self.write_to(&mut buf).expect("infallible");
+        buf.into()
+    }
+}
+
+/// Mutation
+impl File {
+    /// Append a line assigning `assignments` to all paths matching `pattern`, which is quoted if needed.
+    ///
+    /// The line ending of the first line is used, or `\n` if there is none.
+    pub fn push(
+        &mut self,
+        pattern: impl AsRef<[u8]>,
+        assignments: impl IntoIterator<Item = Assignment>,
+    ) -> Result<&mut Self, Error> {
+        let pattern = pattern.as_ref();
+        let mut line = quote_if_needed(pattern);
+        if pattern_of(&line).map_or(true, |p| p.is_negative()) {
+            return Err(Error::InvalidPattern {
+                pattern: pattern.into(),
+            });
+        }
+        for assignment in assignments {
+            if let StateRef::Value(value) = assignment.state.as_ref() {
+                if value.contains_str(" ") || value.contains_str("\t") || value.contains_str("\r") {
+                    return Err(Error::ValueWithWhitespace {
+                        name: assignment.name.as_str().into(),
+                        value: value.into(),
+                    });
+                }
+            }
+            line.push_byte(b' ');
+            line.push_str(assignment.to_string());
+        }
+
+        let newline = self
+            .lines
+            .first()
+            .map_or("\n", |line| if line.ends_with(b"\r\n") { "\r\n" } else { "\n" });
+        if let Some(last) = self.lines.last_mut() {
+            if !last.ends_with(b"\n") {
+                last.push_str(newline);
+            }
+        }
+        line.push_str(newline);
+        self.lines.push(line);
+        Ok(self)
+    }
+
+    /// Remove all lines for `pattern`, compared in the same way as with [`contains()`][Self::contains()],
+    /// and return the amount of removed lines.
+    ///
+    /// All other lines, including comments, remain untouched.
+    pub fn remove(&mut self, pattern: impl AsRef<[u8]>) -> usize {
+        let pattern = match git_glob::Pattern::from_bytes(pattern.as_ref()) {
+            Some(pattern) => pattern,
+            None => return 0,
+        };
+        let num_lines = self.lines.len();
+        self.lines
+            .retain(|line| pattern_of(line).map_or(true, |p| p != pattern));
+        num_lines - self.lines.len()
+    }
+}
+
+/// Return the pattern defined by `line`, if it defines a valid pattern.
+fn pattern_of(line: &[u8]) -> Option<git_glob::Pattern> {
+    match crate::parse(line).next()? {
+        Ok((parse::Kind::Pattern(pattern), _, _)) => Some(pattern),
+        _ => None,
+    }
+}
+
+fn with_line_number(err: parse::Error, line_number: usize) -> parse::Error {
+    match err {
+        parse::Error::PatternNegation { line, .. } => parse::Error::PatternNegation { line_number, line },
+        parse::Error::AttributeName { attribute, .. } => parse::Error::AttributeName { line_number, attribute },
+        parse::Error::MacroName { macro_name, .. } => parse::Error::MacroName {
+            line_number,
+            macro_name,
+        },
+        err @ parse::Error::Unquote(_) => err,
+    }
+}
+
+/// Quote `pattern` with the quoting that the parser undoes if it would otherwise be parsed differently.
+fn quote_if_needed(pattern: &[u8]) -> BString {
+    let needs_quotes = pattern.find_byteset(b" \t\r\n\"\\").is_some()
+        || pattern.first() == Some(&b'#')
+        || pattern.starts_with(b"[attr]");
+    if !needs_quotes {
+        return pattern.into();
+    }
+    let mut out = BString::from("\"");
+    for &b in pattern {
+        match b {
+            b'"' => out.push_str("\\\""),
+            b'\\' => out.push_str("\\\\"),
+            b'\n' => out.push_str("\\n"),
+            b'\r' => out.push_str("\\r"),
+            b'\t' => out.push_str("\\t"),
+            _ => out.push_byte(b),
+        }
+    }
+    out.push_byte(b'"');
+    out
+}
@@ -21,7 +21,13 @@ pub mod name;
 mod state;
 
 mod match_group;
-pub use match_group::{Attributes, Ignore, Match, Pattern};
+pub use match_group::{Attributes, Ignore, Match, Pattern, Value};
+
+///
+pub mod stack;
+
+///
+pub mod file;
 
 ///
 pub mod parse;
@@ -105,6 +111,33 @@ pub struct MatchGroup<T: Pattern = Attributes> {
     pub patterns: Vec<PatternList<T>>,
 }
 
+/// All attribute pattern lists of a repository, layered such that paths can be queried for their attributes with the
+/// precedence used by git.
+///
+/// From highest to lowest priority, these are `$GIT_DIR/info/attributes`, the `.gitattributes` files of the directories leading
+/// up to a path with deeper ones taking precedence, and global files like the one configured in `core.attributesFile`.
+/// Macros may be defined in all of them except for `.gitattributes` files in sub-directories, which is where they are ignored.
+#[derive(PartialEq, Eq, Debug, Hash, Ord, PartialOrd, Clone, Default)]
+pub struct Stack {
+    /// Patterns which aren't tied to the repository, like the ones in `core.attributesFile`. They are consulted last.
+    pub globals: MatchGroup<Attributes>,
+    /// The patterns of the `.gitattributes` files of the directories currently on the stack, one list per directory
+    /// starting at the root of the repository.
+    pub directories: MatchGroup<Attributes>,
+    /// The patterns of `$GIT_DIR/info/attributes`, which are consulted first.
+    pub info: MatchGroup<Attributes>,
+}
+
+/// The lines of an attributes file, like `.gitattributes`, to allow editing it programmatically.
+///
+/// All lines are kept as they are, including comments, empty lines and their line endings, so that writing the file after
+/// editing only changes the lines that were edited.
+#[derive(PartialEq, Eq, Debug, Hash, Ord, PartialOrd, Clone, Default)]
+pub struct File {
+    /// All lines of the file, each with its line ending if it has one.
+    lines: Vec<BString>,
+}
+
 /// A list of patterns which optionally know where they were loaded from and what their base is.
 ///
 /// Knowing their base which is relative to a source directory, it will ignore all path to match against
@@ -50,10 +50,12 @@ impl Pattern for Ignore {
     }
 }
 
-/// A value of an attribute pattern, which is either a macro definition or
+/// A value of an attribute pattern, which is either a macro definition or a set of attribute assignments.
 #[derive(PartialEq, Eq, Debug, Hash, Ord, PartialOrd, Clone)]
 pub enum Value {
+    /// The assignments the macro named by the pattern expands to.
     MacroAttributes(Vec<Assignment>),
+    /// The assignments for all paths matching the pattern.
     Assignments(Vec<Assignment>),
 }
 
@@ -311,7 +313,7 @@ where
             })
     }
 
-    fn strip_base_handle_recompute_basename_pos<'a>(
+    pub(crate) fn strip_base_handle_recompute_basename_pos<'a>(
         &self,
         relative_path: &'a BStr,
         basename_pos: Option<usize>,
@@ -38,6 +38,24 @@ impl AsRef<str> for Name {
     }
 }
 
+impl<'a> TryFrom<&'a str> for NameRef<'a> {
+    type Error = Error;
+
+    /// Validate `name` to be usable as attribute name.
+    fn try_from(name: &'a str) -> Result<Self, Self::Error> {
+        crate::parse::check_attr(name.into())
+    }
+}
+
+impl TryFrom<&str> for Name {
+    type Error = Error;
+
+    /// Validate `name` to be usable as attribute name.
+    fn try_from(name: &str) -> Result<Self, Self::Error> {
+        NameRef::try_from(name).map(NameRef::to_owned)
+    }
+}
+
 /// The error returned by [`parse::Iter`][crate::parse::Iter].
 #[derive(Debug, thiserror::Error)]
 #[error("Attribute has non-ascii characters or starts with '-': {attribute}")]
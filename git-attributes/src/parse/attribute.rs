@@ -69,7 +69,7 @@ impl<'a> Iter<'a> {
     }
 }
 
-fn check_attr(attr: &BStr) -> Result<NameRef<'_>, name::Error> {
+pub(crate) fn check_attr(attr: &BStr) -> Result<NameRef<'_>, name::Error> {
     fn attr_valid(attr: &BStr) -> bool {
         if attr.first() == Some(&b'-') {
             return false;
@@ -2,6 +2,7 @@
 pub mod ignore;
 
 mod attribute;
+pub(crate) use attribute::check_attr;
 pub use attribute::{Error, Iter, Kind, Lines};
 
 /// Parse git ignore patterns, line by line, from `bytes`.
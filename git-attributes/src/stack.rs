@@ -0,0 +1,249 @@
+use std::path::{Path, PathBuf};
+
+use bstr::{BStr, BString, ByteSlice, ByteVec};
+
+use crate::{Assignment, Attributes, MatchGroup, Name, PatternList, Stack, State, Value};
+
+/// A position in a [`Stack`] as obtained by [`Stack::at_path()`], for querying the attributes of the paths in its directory.
+#[derive(Debug, Clone, Copy)]
+pub struct Cursor<'a> {
+    stack: &'a Stack,
+}
+
+/// The attributes unset by the `binary` macro git defines for all repositories, unless it's overridden.
+const BUILTIN_BINARY_MACRO: &[&str] = &["diff", "merge", "text"];
+
+/// Init
+impl Stack {
+    /// Create a new instance from `globals`, patterns which aren't tied to the repository, and `info`, the patterns of
+    /// `$GIT_DIR/info/attributes`, without any directory on the stack.
+    pub fn new(globals: MatchGroup<Attributes>, info: MatchGroup<Attributes>) -> Self {
+        Stack {
+            globals,
+            directories: Default::default(),
+            info,
+        }
+    }
+
+    /// Given `git_dir`, a `.git` repository, load attribute patterns from `info/attributes` and from `attributes_file` if it
+    /// is provided, typically the one configured in `core.attributesFile`.
+    /// Note that it's not considered an error if any of these files does not exist.
+    pub fn from_git_dir(
+        git_dir: impl AsRef<Path>,
+        attributes_file: Option<PathBuf>,
+        buf: &mut Vec<u8>,
+    ) -> std::io::Result<Self> {
+        let follow_symlinks = true;
+        let mut globals = MatchGroup::default();
+        globals.patterns.extend(
+            attributes_file
+                .map(|file| PatternList::<Attributes>::from_file(file, None, follow_symlinks, buf))
+                .transpose()?
+                .flatten(),
+        );
+        let mut info = MatchGroup::default();
+        info.patterns.extend(PatternList::<Attributes>::from_file(
+            git_dir.as_ref().join("info").join("attributes"),
+            None,
+            follow_symlinks,
+            buf,
+        )?);
+        Ok(Stack::new(globals, info))
+    }
+}
+
+/// Mutation
+impl Stack {
+    /// Push the `patterns` of the `.gitattributes` file in `relative_dir`, or `None` if it doesn't have one.
+    /// `relative_dir` is relative to the repository root with slashes as separators, and empty for the root itself.
+    ///
+    /// Directories are expected to be pushed in order starting at the root, and a level is pushed even without `patterns`
+    /// so that each call to [`pop_directory()`][Self::pop_directory()] matches a push.
+    pub fn push_directory(&mut self, relative_dir: &BStr, patterns: Option<PatternList<Attributes>>) {
+        let mut list = patterns.unwrap_or_else(|| PatternList {
+            patterns: Vec::new(),
+            source: None,
+            base: None,
+        });
+        list.base = (!relative_dir.is_empty()).then(|| {
+            let mut base: BString = relative_dir.to_owned();
+            if !base.ends_with(b"/") {
+                base.push_byte(b'/');
+            }
+            base
+        });
+        self.directories.patterns.push(list);
+    }
+
+    /// Remove the patterns of the directory pushed last and return them, or `None` if there is no directory on the stack.
+    pub fn pop_directory(&mut self) -> Option<PatternList<Attributes>> {
+        self.directories.patterns.pop()
+    }
+
+    /// Move to `relative_dir`, a directory relative to the repository root with slashes as separators, and return a cursor
+    /// to query the attributes of paths within it.
+    ///
+    /// Only the directories that differ from the ones already on the stack are popped and pushed, with `read(dir)` being
+    /// called to obtain the patterns of the `.gitattributes` file in each newly pushed `dir`, or `None` if there is none.
+    /// This way, traversals that visit all paths of a directory before moving on only read and parse each file once.
+    pub fn at_path<E>(
+        &mut self,
+        relative_dir: &BStr,
+        mut read: impl FnMut(&BStr) -> Result<Option<PatternList<Attributes>>, E>,
+    ) -> Result<Cursor<'_>, E> {
+        let relative_dir = relative_dir.trim_end_with(|c| c == '/').as_bstr();
+        let ends = component_ends(relative_dir);
+        let common_components = match self.directories.patterns.last() {
+            Some(list) => {
+                let current_dir = list
+                    .base
+                    .as_ref()
+                    .map_or(&[][..], |base| &base[..base.len() - 1])
+                    .as_bstr();
+                let common = component_ends(current_dir)
+                    .into_iter()
+                    .zip(ends.iter())
+                    .take_while(|(current_end, end)| {
+                        current_end == *end && current_dir[..*current_end] == relative_dir[..**end]
+                    })
+                    .count();
+                self.directories.patterns.truncate(common + 1);
+                common
+            }
+            None => {
+                let list = read("".into())?;
+                self.push_directory("".into(), list);
+                0
+            }
+        };
+        for end in &ends[common_components..] {
+            let dir = relative_dir[..*end].as_bstr();
+            let list = read(dir)?;
+            self.push_directory(dir, list);
+        }
+        Ok(Cursor { stack: self })
+    }
+}
+
+/// Return the end positions of all components of `dir`, which is empty for the root.
+fn component_ends(dir: &BStr) -> Vec<usize> {
+    if dir.is_empty() {
+        return Vec::new();
+    }
+    dir.find_iter("/").chain(Some(dir.len())).collect()
+}
+
+/// Query
+impl Stack {
+    /// Collect all attributes assigned to `relative_path` into `out`, each at most once with the state of the pattern with
+    /// the highest priority. `relative_path` is relative to the repository root, and `is_dir` is true if it's a directory.
+    ///
+    /// Within each list of patterns, later lines take precedence over earlier ones. Macros that are set are expanded
+    /// recursively, but assignments made by patterns with a higher priority aren't overridden by them.
+    /// Attributes that aren't mentioned, or are explicitly [unspecified][State::Unspecified], aren't contained in `out`,
+    /// similar to `git check-attr --all`.
+    ///
+    /// Note that only the `.gitattributes` files of the directories currently on the stack are considered.
+    pub fn matching_attributes<'a>(
+        &self,
+        relative_path: impl Into<&'a BStr>,
+        is_dir: Option<bool>,
+        case: git_glob::pattern::Case,
+        out: &mut Vec<Assignment>,
+    ) {
+        out.clear();
+        let relative_path = relative_path.into();
+        let basename_pos = relative_path.rfind(b"/").map(|p| p + 1);
+        for list in self.lists_by_priority() {
+            let (relative_path, basename_pos) =
+                match list.strip_base_handle_recompute_basename_pos(relative_path, basename_pos) {
+                    Some(path_and_pos) => path_and_pos,
+                    None => continue,
+                };
+            for mapping in list.patterns.iter().rev() {
+                let assignments = match &mapping.value {
+                    Value::Assignments(assignments) => assignments,
+                    Value::MacroAttributes(_) => continue,
+                };
+                if mapping
+                    .pattern
+                    .matches_repo_relative_path(relative_path, basename_pos, is_dir, case)
+                {
+                    for assignment in assignments.iter().rev() {
+                        self.assign(assignment, out);
+                    }
+                }
+            }
+        }
+        out.retain(|assignment| assignment.state != State::Unspecified);
+    }
+
+    /// Return the assignments of the macro named `name` as defined with the highest priority, or `None` if there is no
+    /// such macro. Note that the macros built into git aren't considered.
+    pub fn macro_assignments(&self, name: &str) -> Option<&[Assignment]> {
+        self.lists_by_priority()
+            .filter(|list| list.base.is_none())
+            .flat_map(|list| list.patterns.iter().rev())
+            .find_map(|mapping| match &mapping.value {
+                Value::MacroAttributes(assignments) if mapping.pattern.text == name => Some(assignments.as_slice()),
+                _ => None,
+            })
+    }
+
+    /// All pattern lists, from the highest priority to the lowest.
+    fn lists_by_priority(&self) -> impl Iterator<Item = &PatternList<Attributes>> {
+        self.info
+            .patterns
+            .iter()
+            .rev()
+            .chain(self.directories.patterns.iter().rev())
+            .chain(self.globals.patterns.iter().rev())
+    }
+
+    fn assign(&self, assignment: &Assignment, out: &mut Vec<Assignment>) {
+        // Attributes are assigned only once, which also protects against macros referring to themselves.
+        if out.iter().any(|existing| existing.name == assignment.name) {
+            return;
+        }
+        out.push(assignment.clone());
+        if assignment.state != State::Set {
+            return;
+        }
+        match self.macro_assignments(assignment.name.as_str()) {
+            Some(assignments) => {
+                for assignment in assignments.iter().rev() {
+                    self.assign(assignment, out);
+                }
+            }
+            None if assignment.name.as_str() == "binary" => {
+                for name in BUILTIN_BINARY_MACRO.iter().rev() {
+                    let assignment = Assignment {
+                        name: Name((*name).into()),
+                        state: State::Unset,
+                    };
+                    self.assign(&assignment, out);
+                }
+            }
+            None => {}
+        }
+    }
+}
+
+impl<'a> Cursor<'a> {
+    /// Collect all attributes assigned to `relative_path` into `out`, a path relative to the repository root within the
+    /// directory of this cursor, see [`Stack::matching_attributes()`] for details.
+    pub fn matching_attributes<'b>(
+        &self,
+        relative_path: impl Into<&'b BStr>,
+        is_dir: Option<bool>,
+        case: git_glob::pattern::Case,
+        out: &mut Vec<Assignment>,
+    ) {
+        self.stack.matching_attributes(relative_path, is_dir, case, out)
+    }
+
+    /// Return the stack this cursor points into.
+    pub fn stack(&self) -> &'a Stack {
+        self.stack
+    }
+}
@@ -1,3 +1,5 @@
 pub use git_testtools::Result;
+mod file;
 mod match_group;
 mod parse;
+mod stack;
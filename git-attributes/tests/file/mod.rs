@@ -0,0 +1,135 @@
+use bstr::ByteSlice;
+use git_attributes::{parse::Kind, Assignment, File, Name, State};
+
+fn lfs() -> Vec<Assignment> {
+    ["filter", "diff", "merge"]
+        .iter()
+        .map(|name| Assignment {
+            name: Name::try_from(*name).unwrap(),
+            state: State::Value("lfs".into()),
+        })
+        .chain(Some(Assignment {
+            name: Name::try_from("text").unwrap(),
+            state: State::Unset,
+        }))
+        .collect()
+}
+
+const INPUT: &str =
+    "\u{feff}# comment\r\n\r\n*.txt   text eol=crlf\r\n[attr]my-macro  a -b\r\n\"with space\" !c\r\n*.bin filter=lfs";
+
+#[test]
+fn unchanged_files_round_trip_losslessly() {
+    let file = File::from_bytes(INPUT.as_bytes());
+    assert_eq!(file.to_bstring(), INPUT);
+    assert_eq!(File::from_bytes(b"").to_bstring(), "");
+}
+
+#[test]
+fn lines_are_parsed_with_their_line_number() -> crate::Result {
+    let file = File::from_bytes(INPUT.as_bytes());
+    let lines = file
+        .lines()
+        .map(|res| {
+            res.map(|(kind, attrs, line_number)| {
+                let attrs = attrs
+                    .map(|a| a.map(|a| a.to_string()))
+                    .collect::<Result<Vec<_>, _>>()
+                    .expect("valid");
+                let kind = match kind {
+                    Kind::Pattern(p) => p.to_string(),
+                    Kind::Macro(name) => format!("[attr]{}", name.as_str()),
+                };
+                (kind, attrs.join(" "), line_number)
+            })
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+    assert_eq!(
+        lines,
+        vec![
+            ("*.txt".into(), "text eol=crlf".into(), 3),
+            ("[attr]my-macro".into(), "a -b".into(), 4),
+            ("with space".into(), "!c".into(), 5),
+            ("*.bin".into(), "filter=lfs".into(), 6)
+        ]
+    );
+
+    let file = File::from_bytes(b"\n\n!negated a");
+    assert!(matches!(
+        file.lines().next().expect("one line"),
+        Err(git_attributes::parse::Error::PatternNegation { line_number: 3, .. })
+    ));
+    Ok(())
+}
+
+#[test]
+fn push_appends_lines_with_the_line_ending_of_the_file() -> crate::Result {
+    let mut file = File::from_bytes(INPUT.as_bytes());
+    file.push("*.psd", lfs())?
+        .push("name with\ttab", None)?
+        .push("#hash", None)?;
+    assert_eq!(
+        file.to_bstring(),
+        format!(
+            "{}\r\n*.psd filter=lfs diff=lfs merge=lfs -text\r\n\"name with\\ttab\"\r\n\"#hash\"\r\n",
+            INPUT
+        )
+    );
+    assert!(file.contains("*.psd"));
+    assert!(file.contains("name with\ttab"));
+    assert!(file.contains("#hash"));
+    assert_eq!(file.lines().count(), 7, "all pushed lines parse as patterns");
+
+    let mut file = File::default();
+    file.push("a", None)?;
+    assert_eq!(file.to_bstring(), "a\n", "unix line endings are the default");
+    Ok(())
+}
+
+#[test]
+fn push_rejects_what_can_not_be_represented() {
+    let mut file = File::default();
+    assert!(matches!(
+        file.push("!negated", None).unwrap_err(),
+        git_attributes::file::Error::InvalidPattern { .. }
+    ));
+    assert!(matches!(
+        file.push("", None).unwrap_err(),
+        git_attributes::file::Error::InvalidPattern { .. }
+    ));
+    assert!(matches!(
+        file.push(
+            "a",
+            Some(Assignment {
+                name: Name::try_from("a").unwrap(),
+                state: State::Value("with space".into())
+            })
+        )
+        .unwrap_err(),
+        git_attributes::file::Error::ValueWithWhitespace { .. }
+    ));
+    assert_eq!(file.to_bstring(), "", "nothing was written");
+}
+
+#[test]
+fn remove_only_touches_lines_of_the_given_pattern() {
+    let mut file = File::from_bytes(INPUT.as_bytes());
+    file.push("*.txt", lfs()).unwrap();
+    assert_eq!(file.remove("*.txt"), 2);
+    assert_eq!(file.remove("*.txt"), 0);
+    assert_eq!(file.remove("with space"), 1);
+    assert_eq!(file.remove("my-macro"), 0, "macros aren't patterns");
+    assert!(!file.contains("*.txt"));
+    assert_eq!(
+        file.to_bstring(),
+        "\u{feff}# comment\r\n\r\n[attr]my-macro  a -b\r\n*.bin filter=lfs\r\n"
+    );
+    assert!(file.to_bstring().lines().all(|line| !line.contains_str("with space")));
+}
+
+#[test]
+fn names_are_validated() {
+    assert!(Name::try_from("valid-name_1.2").is_ok());
+    assert!(Name::try_from("-invalid").is_err());
+    assert!(Name::try_from("in valid").is_err());
+}
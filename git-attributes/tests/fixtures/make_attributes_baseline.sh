@@ -0,0 +1,64 @@
+#!/bin/bash
+set -eu -o pipefail
+
+function baseline() {
+  {
+    echo "$1"
+    GIT_ATTR_NOSYSTEM=1 git -c core.attributesFile=../user.attributes check-attr -a "$1" 2>/dev/null
+    echo
+  } >> baseline
+}
+
+cat <<EOF >user.attributes
+[attr]my-text text
+[attr]my-binary binary
+
+*.txt   user-attr
+*.bin   my-binary
+global-only  global
+overridden   from-global
+EOF
+
+mkdir repo;
+(cd repo
+  git init -q
+
+  cat <<EOF >.git/info/attributes
+[attr]info-macro info-1 info-2=value
+info-only    info-macro
+overridden   from-info -from-root
+EOF
+
+  cat <<EOF >.gitattributes
+[attr]my-text -text my-text-attr
+[attr]recursive recursive-child nested-macro
+[attr]nested-macro nested-child -info-1
+
+*.txt        my-text
+*.md         text eol=lf
+overridden   from-root from-global=root
+recursive    recursive
+unspecified  a b c
+unspecified  !b
+/anchored    anchored
+EOF
+
+  mkdir -p dir/sub
+  cat <<EOF >dir/.gitattributes
+[attr]my-text ignored-in-sub-directories
+*.md         -text
+unspecified  !a
+overridden   from-dir
+sub/*.txt    sub-txt eol=crlf
+EOF
+
+  cat <<EOF >dir/sub/.gitattributes
+*.txt        !sub-txt deepest
+EOF
+
+  for path in a.txt a.bin a.md global-only info-only overridden recursive unspecified anchored no-match \
+              dir/a.txt dir/a.bin dir/a.md dir/overridden dir/unspecified dir/anchored dir/info-only \
+              dir/sub/a.txt dir/sub/a.md dir/sub/overridden; do
+    baseline $path
+  done
+)
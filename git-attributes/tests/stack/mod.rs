@@ -0,0 +1,174 @@
+use std::path::Path;
+
+use bstr::{BStr, ByteSlice};
+use git_attributes::{Assignment, PatternList, Stack, State};
+use git_glob::pattern::Case;
+
+#[test]
+fn baseline() -> crate::Result {
+    let dir = git_testtools::scripted_fixture_repo_read_only("make_attributes_baseline.sh")?;
+    let repo_dir = dir.join("repo");
+    let baseline = std::fs::read(repo_dir.join("baseline"))?;
+    let mut buf = Vec::new();
+    let initial = Stack::from_git_dir(repo_dir.join(".git"), Some(dir.join("user.attributes")), &mut buf)?;
+    assert_eq!(initial.globals.patterns.len(), 1);
+    assert_eq!(initial.info.patterns.len(), 1);
+
+    let mut out = Vec::new();
+    let mut count = 0;
+    for (path, expected) in (Expectations {
+        lines: baseline.lines(),
+    }) {
+        let stack = stack_for(initial.clone(), &repo_dir, path, &mut buf)?;
+        stack.matching_attributes(path, Some(false), Case::Sensitive, &mut out);
+        assert_eq!(to_tuples(&out), expected, "attributes of '{}' should match", path);
+        count += 1;
+    }
+    assert_eq!(count, 20, "all paths were checked");
+    Ok(())
+}
+
+#[test]
+fn at_path_reads_each_directory_once_during_traversal() -> crate::Result {
+    let dir = git_testtools::scripted_fixture_repo_read_only("make_attributes_baseline.sh")?;
+    let repo_dir = dir.join("repo");
+    let baseline = std::fs::read(repo_dir.join("baseline"))?;
+    let mut buf = Vec::new();
+    let mut stack = Stack::from_git_dir(repo_dir.join(".git"), Some(dir.join("user.attributes")), &mut buf)?;
+
+    let mut reads = Vec::new();
+    let mut out = Vec::new();
+    for (path, expected) in (Expectations {
+        lines: baseline.lines(),
+    }) {
+        let relative_dir = path.rfind_byte(b'/').map_or("".into(), |pos| path[..pos].as_bstr());
+        let cursor = stack.at_path(relative_dir, |dir| {
+            reads.push(dir.to_owned());
+            let dir = repo_dir.join(dir.to_str().expect("valid UTF-8"));
+            PatternList::from_file(dir.join(".gitattributes"), Some(&repo_dir), false, &mut buf)
+        })?;
+        cursor.matching_attributes(path, Some(false), Case::Sensitive, &mut out);
+        assert_eq!(to_tuples(&out), expected, "attributes of '{}' should match", path);
+    }
+    assert_eq!(reads, ["", "dir", "dir/sub"], "each directory is read only once");
+
+    let cursor = stack.at_path("dir/".into(), |dir| -> std::io::Result<_> {
+        unreachable!("'{}' is on the stack already", dir)
+    })?;
+    assert_eq!(cursor.stack().directories.patterns.len(), 2, "popped 'dir/sub'");
+
+    reads.clear();
+    stack.at_path("other/dir".into(), |dir| -> std::io::Result<_> {
+        reads.push(dir.to_owned());
+        Ok(None)
+    })?;
+    assert_eq!(reads, ["other", "other/dir"], "only the root is shared");
+    assert_eq!(stack.directories.patterns.len(), 3);
+
+    stack.at_path("".into(), |_| -> std::io::Result<_> {
+        unreachable!("the root is on the stack already")
+    })?;
+    assert_eq!(stack.directories.patterns.len(), 1, "only the root remains");
+    Ok(())
+}
+
+#[test]
+fn push_and_pop_directories() {
+    let mut stack = Stack::default();
+    assert!(stack.pop_directory().is_none(), "nothing to pop initially");
+
+    stack.push_directory("".into(), None);
+    stack.push_directory(
+        "dir".into(),
+        Some(PatternList::from_bytes(b"*.txt a", "dir/.gitattributes", None)),
+    );
+    let mut out = Vec::new();
+    stack.matching_attributes("dir/file.txt", None, Case::Sensitive, &mut out);
+    assert_eq!(out.len(), 1, "the pattern is relative to its directory");
+    stack.matching_attributes("file.txt", None, Case::Sensitive, &mut out);
+    assert!(out.is_empty(), "it doesn't apply outside of its directory");
+
+    let list = stack.pop_directory().expect("pushed");
+    assert_eq!(list.base.as_ref().map(|base| base.as_bstr()), Some("dir/".into()));
+    let list = stack.pop_directory().expect("pushed even without patterns");
+    assert_eq!(list.base, None, "the root has no base");
+    assert!(list.patterns.is_empty());
+}
+
+#[test]
+fn builtin_binary_macro_can_be_overridden() {
+    let mut stack = Stack::default();
+    let mut out = Vec::new();
+    stack.push_directory(
+        "".into(),
+        Some(PatternList::from_bytes(b"*.bin binary", ".gitattributes", None)),
+    );
+    stack.matching_attributes("a.bin", None, Case::Sensitive, &mut out);
+    assert_eq!(out.len(), 4, "binary, -diff, -merge and -text");
+
+    stack
+        .info
+        .patterns
+        .push(PatternList::from_bytes(b"[attr]binary -diff", "info/attributes", None));
+    stack.matching_attributes("a.bin", None, Case::Sensitive, &mut out);
+    assert_eq!(
+        out.iter().map(|a| a.name.as_str()).collect::<Vec<_>>(),
+        ["binary", "diff"],
+        "user-defined macros take precedence"
+    );
+}
+
+/// Return the sorted names and values of `assignments`, in the format of the baseline.
+fn to_tuples(assignments: &[Assignment]) -> Vec<(String, String)> {
+    let mut out: Vec<_> = assignments
+        .iter()
+        .map(|assignment| {
+            (
+                assignment.name.as_str().to_owned(),
+                match &assignment.state {
+                    State::Set => "set".to_owned(),
+                    State::Unset => "unset".to_owned(),
+                    State::Value(value) => value.as_str().to_owned(),
+                    State::Unspecified => unreachable!("unspecified attributes are never returned"),
+                },
+            )
+        })
+        .collect();
+    out.sort();
+    out
+}
+
+/// Return `stack` with the `.gitattributes` files of all directories leading up to `path` pushed onto it.
+fn stack_for(mut stack: Stack, root: &Path, path: &BStr, buf: &mut Vec<u8>) -> std::io::Result<Stack> {
+    for relative_dir in std::iter::once("".into()).chain(path.find_iter("/").map(|pos| path[..pos].as_bstr())) {
+        let dir = root.join(relative_dir.to_str().expect("valid UTF-8"));
+        let list = PatternList::from_file(dir.join(".gitattributes"), Some(root), false, buf)?;
+        stack.push_directory(relative_dir, list);
+    }
+    Ok(stack)
+}
+
+struct Expectations<'a> {
+    lines: bstr::Lines<'a>,
+}
+
+impl<'a> Iterator for Expectations<'a> {
+    type Item = (&'a BStr, Vec<(String, String)>);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let path = self.lines.next()?.as_bstr();
+        let mut assignments = Vec::new();
+        for line in self.lines.by_ref() {
+            if line.is_empty() {
+                break;
+            }
+            let mut tokens = line.splitn_str(3, ": ");
+            assert_eq!(tokens.next(), Some(path.as_bytes()));
+            let name = tokens.next().expect("name").to_str_lossy().into_owned();
+            let value = tokens.next().expect("value").to_str_lossy().into_owned();
+            assignments.push((name, value));
+        }
+        assignments.sort();
+        Some((path, assignments))
+    }
+}
@@ -48,7 +48,7 @@ pub fn decode(data: &[u8]) -> Result<(Vec, &[u8]), decode::Error> {
 mod access {
     use std::convert::{TryFrom, TryInto};
 
-    use super::Vec;
+    use super::{rlw::*, Vec};
 
     impl Vec {
         /// Call `f(index)` for each bit that is true, given the index of the bit that identifies it uniquely within the bit array.
@@ -89,33 +89,182 @@ mod access {
             self.num_bits.try_into().expect("we are not on 16 bit systems")
         }
     }
+}
+
+mod mutation {
+    use super::{rlw::*, Vec};
+
+    impl Default for Vec {
+        fn default() -> Self {
+            Vec {
+                num_bits: 0,
+                bits: vec![0],
+                rlw: 0,
+            }
+        }
+    }
+
+    impl Vec {
+        /// Set the bit at `index` to true, which must be larger than the index of all previously set bits as bitmaps
+        /// can only be appended to.
+        ///
+        /// This produces the same compressed representation as `git` does when setting the same bits.
+        pub fn set(&mut self, index: usize) {
+            let index = u32::try_from(index).expect("less than 2^32 bits");
+            assert!(
+                index >= self.num_bits,
+                "BUG: bits must be set in ascending order, got {} after {} bits",
+                index,
+                self.num_bits
+            );
+            let bit = 1u64 << (index % 64);
+            let distance = (index as usize + 64) / 64 - (self.num_bits as usize + 63) / 64;
+            self.num_bits = index + 1;
+
+            if distance > 0 {
+                if distance > 1 {
+                    self.add_empty_words(false, distance - 1);
+                }
+                self.add_literal(bit);
+                return;
+            }
+
+            let rlw = self.bits[self.rlw as usize];
+            if rlw_literal_words(&rlw) == 0 {
+                self.set_rlw_running_len(rlw_running_len(&rlw) - 1);
+                self.add_literal(bit);
+                return;
+            }
+
+            let last = self.bits.last_mut().expect("at least one word");
+            *last |= bit;
+            if *last == u64::MAX {
+                self.bits.pop();
+                self.set_rlw_literal_words(rlw_literal_words(&self.bits[self.rlw as usize]) - 1);
+                self.add_empty_word(true);
+            }
+        }
+
+        /// Serialize this instance to `out` in the format read by [`decode()`][super::decode()].
+        pub fn write_to(&self, mut out: impl std::io::Write) -> std::io::Result<()> {
+            let len = u32::try_from(self.bits.len()).expect("less than 2^32 words");
+            out.write_all(&self.num_bits.to_be_bytes())?;
+            out.write_all(&len.to_be_bytes())?;
+            for word in &self.bits {
+                out.write_all(&word.to_be_bytes())?;
+            }
+            out.write_all(&(u32::try_from(self.rlw).expect("rlw is an index into our words")).to_be_bytes())
+        }
+
+        fn push_rlw(&mut self) {
+            self.bits.push(0);
+            self.rlw = (self.bits.len() - 1) as u64;
+        }
+
+        fn set_rlw_run_bit(&mut self, value: bool) {
+            let rlw = &mut self.bits[self.rlw as usize];
+            *rlw = (*rlw & !1) | value as u64;
+        }
+
+        fn set_rlw_running_len(&mut self, len: u64) {
+            let rlw = &mut self.bits[self.rlw as usize];
+            *rlw = (*rlw & !(RLW_LARGEST_RUNNING_COUNT << 1)) | (len << 1);
+        }
+
+        fn set_rlw_literal_words(&mut self, count: u64) {
+            let rlw = &mut self.bits[self.rlw as usize];
+            *rlw = (*rlw & ((1 << (1 + RLW_RUNNING_BITS)) - 1)) | (count << (1 + RLW_RUNNING_BITS));
+        }
+
+        fn add_literal(&mut self, word: u64) {
+            let count = rlw_literal_words(&self.bits[self.rlw as usize]);
+            if count >= RLW_LARGEST_LITERAL_COUNT {
+                self.push_rlw();
+                self.set_rlw_literal_words(1);
+            } else {
+                self.set_rlw_literal_words(count + 1);
+            }
+            self.bits.push(word);
+        }
+
+        fn add_empty_word(&mut self, value: bool) {
+            let rlw = self.bits[self.rlw as usize];
+            let no_literal = rlw_literal_words(&rlw) == 0;
+            let running_len = rlw_running_len(&rlw);
+            if no_literal && running_len == 0 {
+                self.set_rlw_run_bit(value);
+            }
+            if no_literal
+                && rlw_runbit_is_set(&self.bits[self.rlw as usize]) == value
+                && running_len < RLW_LARGEST_RUNNING_COUNT
+            {
+                self.set_rlw_running_len(running_len + 1);
+            } else {
+                self.push_rlw();
+                self.set_rlw_run_bit(value);
+                self.set_rlw_running_len(1);
+            }
+        }
+
+        fn add_empty_words(&mut self, value: bool, mut count: usize) {
+            let rlw = self.bits[self.rlw as usize];
+            if rlw_runbit_is_set(&rlw) != value && rlw_running_len(&rlw) == 0 && rlw_literal_words(&rlw) == 0 {
+                self.set_rlw_run_bit(value);
+            } else if rlw_literal_words(&rlw) != 0 || rlw_runbit_is_set(&rlw) != value {
+                self.push_rlw();
+                self.set_rlw_run_bit(value);
+            }
+
+            let running_len = rlw_running_len(&self.bits[self.rlw as usize]);
+            let can_add = (count as u64).min(RLW_LARGEST_RUNNING_COUNT - running_len);
+            self.set_rlw_running_len(running_len + can_add);
+            count -= can_add as usize;
+
+            while count as u64 >= RLW_LARGEST_RUNNING_COUNT {
+                self.push_rlw();
+                self.set_rlw_run_bit(value);
+                self.set_rlw_running_len(RLW_LARGEST_RUNNING_COUNT);
+                count -= RLW_LARGEST_RUNNING_COUNT as usize;
+            }
+
+            if count > 0 {
+                self.push_rlw();
+                self.set_rlw_run_bit(value);
+                self.set_rlw_running_len(count as u64);
+            }
+        }
+    }
+}
 
+/// Utilities to interpret run length words, the headers of each run of words.
+mod rlw {
     #[inline]
-    fn rlw_running_len_bits(w: &u64) -> u64 {
+    pub(crate) fn rlw_running_len_bits(w: &u64) -> u64 {
         rlw_running_len(w) * 64
     }
 
     #[inline]
-    fn rlw_running_len(w: &u64) -> u64 {
+    pub(crate) fn rlw_running_len(w: &u64) -> u64 {
         (w >> 1) & RLW_LARGEST_RUNNING_COUNT
     }
 
     #[inline]
-    fn rlw_literal_words(w: &u64) -> u64 {
+    pub(crate) fn rlw_literal_words(w: &u64) -> u64 {
         w >> (1 + RLW_RUNNING_BITS)
     }
 
     #[inline]
-    fn rlw_runbit_is_set(w: &u64) -> bool {
+    pub(crate) fn rlw_runbit_is_set(w: &u64) -> bool {
         w & 1 == 1
     }
 
-    const RLW_RUNNING_BITS: u64 = 4 * 8;
-    const RLW_LARGEST_RUNNING_COUNT: u64 = (1 << RLW_RUNNING_BITS) - 1;
+    pub(crate) const RLW_RUNNING_BITS: u64 = 4 * 8;
+    pub(crate) const RLW_LITERAL_BITS: u64 = 64 - 1 - RLW_RUNNING_BITS;
+    pub(crate) const RLW_LARGEST_RUNNING_COUNT: u64 = (1 << RLW_RUNNING_BITS) - 1;
+    pub(crate) const RLW_LARGEST_LITERAL_COUNT: u64 = (1 << RLW_LITERAL_BITS) - 1;
 }
 
 /// A growable collection of u64 that are seen as stream of individual bits.
-#[allow(dead_code)]
 #[derive(Clone)]
 pub struct Vec {
     num_bits: u32,
@@ -92,6 +92,7 @@ impl file::Index {
         Ok(file::Index {
             chunks,
             will_write: false,
+            alignment: 1,
         })
     }
 }
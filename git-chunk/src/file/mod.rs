@@ -13,6 +13,8 @@ pub type Offset = u64;
 pub struct Index {
     /// If true, we use `chunks` in a way that facilitates writing them.
     will_write: bool,
+    /// The amount of bytes each chunk's size on disk is a multiple of, with padding added as needed. Only used when writing.
+    alignment: u64,
     /// Validated chunks as defined by their index entries.
     ///
     /// Note that this list cannot be empty.
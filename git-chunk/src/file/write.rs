@@ -1,24 +1,28 @@
 use crate::file::{index::Entry, Index};
 
 mod write_chunk {
-    use std::collections::VecDeque;
+    use std::{collections::VecDeque, io::Read};
 
     use crate::file::index;
 
     /// A [`Write`][std::io::Write] implementation that validates chunk sizes while allowing the user to know
     /// which chunk is to be written next.
+    ///
+    /// Writing more bytes than planned for a chunk fails, and padding to reach the alignment of the index is added automatically
+    /// once all of its bytes were written, so chunks of any size can be streamed in as many writes as needed.
     pub struct Chunk<W> {
         chunks_to_write: VecDeque<index::Entry>,
         inner: W,
         next_chunk: Option<index::Entry>,
-        written_bytes: usize,
+        written_bytes: u64,
+        alignment: u64,
     }
 
     impl<W> Chunk<W>
     where
         W: std::io::Write,
     {
-        pub(crate) fn new(out: W, chunks: VecDeque<index::Entry>) -> Chunk<W>
+        pub(crate) fn new(out: W, chunks: VecDeque<index::Entry>, alignment: u64) -> Chunk<W>
         where
             W: std::io::Write,
         {
@@ -27,6 +31,7 @@ mod write_chunk {
                 inner: out,
                 next_chunk: None,
                 written_bytes: 0,
+                alignment,
             }
         }
     }
@@ -36,8 +41,30 @@ mod write_chunk {
         W: std::io::Write,
     {
         fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+            let entry = self.next_chunk.as_ref().ok_or_else(|| {
+                std::io::Error::new(
+                    std::io::ErrorKind::Other,
+                    "BUG: call `next_chunk()` to learn which chunk to write before writing",
+                )
+            })?;
+            let planned_size = entry.offset.end;
+            if self.written_bytes + buf.len() as u64 > planned_size {
+                return Err(std::io::Error::new(
+                    std::io::ErrorKind::Other,
+                    format!(
+                        "BUG: chunk {:?} was planned with {} bytes, but {} bytes would be written",
+                        std::str::from_utf8(&entry.kind).unwrap_or("<non-ascii>"),
+                        planned_size,
+                        self.written_bytes + buf.len() as u64
+                    ),
+                ));
+            }
             let written = self.inner.write(buf)?;
-            self.written_bytes += written;
+            self.written_bytes += written as u64;
+            if written != 0 && self.written_bytes == planned_size {
+                let padding = super::padding(planned_size, self.alignment);
+                std::io::copy(&mut std::io::repeat(0).take(padding), &mut self.inner)?;
+            }
             Ok(written)
         }
 
@@ -52,11 +79,15 @@ mod write_chunk {
             self.inner
         }
         /// Return the next chunk-id to write, if there is one.
+        ///
+        /// # Panics
+        ///
+        /// If the previous chunk didn't receive exactly the amount of bytes it was planned with.
         pub fn next_chunk(&mut self) -> Option<crate::Id> {
             if let Some(entry) = self.next_chunk.take() {
                 assert_eq!(
                     entry.offset.end,
-                    self.written_bytes as u64,
+                    self.written_bytes,
                     "BUG: expected to write {} bytes, but only wrote {} for chunk {:?}",
                     entry.offset.end,
                     self.written_bytes,
@@ -76,12 +107,32 @@ impl Index {
     /// Create a new index whose sole purpose is to be receiving chunks using [`plan_chunk()`][Index::plan_chunk()] and to be written to
     /// an output using [`into_write()`][Index::into_write()]
     pub fn for_writing() -> Self {
+        Self::for_writing_with_alignment(1)
+    }
+
+    /// Like [`for_writing()`][Index::for_writing()], but pads each chunk with null bytes so its size on disk is a multiple of
+    /// `alignment`, which is what git does to keep the data of all chunks 4-byte aligned.
+    ///
+    /// # Panics
+    ///
+    /// If `alignment` is 0.
+    pub fn for_writing_with_alignment(alignment: u64) -> Self {
+        assert_ne!(alignment, 0, "BUG: alignment must be at least 1");
         Index {
             will_write: true,
+            alignment,
             chunks: Vec::new(),
         }
     }
+
     /// Plan to write a new chunk as part of the index when [`into_write()`][Index::into_write()] is called.
+    ///
+    /// `exact_size_on_disk` is the amount of bytes that will be written to the chunk, without padding for alignment.
+    ///
+    /// # Panics
+    ///
+    /// If a chunk of the same kind was planned already or if it's the [sentinel][crate::SENTINEL], as both would make the
+    /// file unreadable.
     pub fn plan_chunk(&mut self, chunk: crate::Id, exact_size_on_disk: u64) {
         assert!(self.will_write, "BUG: create the index with `for_writing()`");
         assert!(
@@ -89,16 +140,17 @@ impl Index {
             "BUG: must not add chunk of same kind twice: {:?}",
             std::str::from_utf8(&chunk)
         );
+        assert_ne!(chunk, crate::SENTINEL, "BUG: the sentinel can't be used as chunk id");
         self.chunks.push(Entry {
             kind: chunk,
             offset: 0..exact_size_on_disk,
         })
     }
 
-    /// Return the total size of all planned chunks thus far.
+    /// Return the total size of all planned chunks thus far, including padding for alignment.
     pub fn planned_storage_size(&self) -> u64 {
         assert!(self.will_write, "BUG: create the index with `for_writing()`");
-        self.chunks.iter().map(|e| e.offset.end).sum()
+        self.chunks.iter().map(|e| self.size_on_disk(e)).sum()
     }
 
     /// Return the amount of chunks we currently know.
@@ -106,9 +158,31 @@ impl Index {
         self.chunks.len()
     }
 
+    /// Return the entries of the table of contents as they will be written if `into_write()` is called with `current_offset`,
+    /// with the range of each entry including padding for alignment.
+    pub fn planned_entries(&self, current_offset: usize) -> Vec<Entry> {
+        assert!(self.will_write, "BUG: create the index with `for_writing()`");
+        let mut current_offset = (current_offset + Self::size_for_entries(self.num_chunks())) as u64;
+        self.chunks
+            .iter()
+            .map(|entry| {
+                let start = current_offset;
+                current_offset += self.size_on_disk(entry);
+                Entry {
+                    kind: entry.kind,
+                    offset: start..current_offset,
+                }
+            })
+            .collect()
+    }
+
     /// After [planning all chunks][Index::plan_chunk()] call this method with the destination to write the chunks to.
     /// Use the [Chunk] writer to write each chunk in order.
     /// `current_offset` is the byte position at which `out` will continue writing.
+    ///
+    /// # Panics
+    ///
+    /// If no chunk was planned, as readers refuse files without chunks.
     pub fn into_write<W>(self, mut out: W, current_offset: usize) -> std::io::Result<Chunk<W>>
     where
         W: std::io::Write,
@@ -117,20 +191,28 @@ impl Index {
             self.will_write,
             "BUG: create the index with `for_writing()`, cannot write decoded indices"
         );
-        // First chunk starts past the table of contents
-        let mut current_offset = (current_offset + Self::size_for_entries(self.num_chunks())) as u64;
+        assert_ne!(self.num_chunks(), 0, "BUG: at least one chunk must be planned");
 
-        for entry in &self.chunks {
+        let entries = self.planned_entries(current_offset);
+        for entry in &entries {
             out.write_all(&entry.kind)?;
-            out.write_all(&current_offset.to_be_bytes())?;
-
-            current_offset += entry.offset.end;
+            out.write_all(&entry.offset.start.to_be_bytes())?;
         }
 
         // sentinel to mark end of chunks
-        out.write_all(&0u32.to_be_bytes())?;
-        out.write_all(&current_offset.to_be_bytes())?;
+        let end_offset = entries.last().expect("at least one chunk").offset.end;
+        out.write_all(&crate::SENTINEL)?;
+        out.write_all(&end_offset.to_be_bytes())?;
 
-        Ok(Chunk::new(out, self.chunks.into()))
+        Ok(Chunk::new(out, self.chunks.into(), self.alignment))
     }
+
+    fn size_on_disk(&self, entry: &Entry) -> u64 {
+        entry.offset.end + padding(entry.offset.end, self.alignment)
+    }
+}
+
+/// Return the amount of bytes needed to pad `size` to a multiple of `alignment`.
+fn padding(size: u64, alignment: u64) -> u64 {
+    (alignment - size % alignment) % alignment
 }
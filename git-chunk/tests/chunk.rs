@@ -0,0 +1,110 @@
+mod write {
+    use std::io::Write;
+
+    use git_chunk::file::Index;
+
+    const HEADER: &[u8] = b"HEADER42";
+
+    /// Write `chunks` after our header using an index with `alignment`, writing each chunk in pieces of `piece_len` bytes.
+    fn write_chunks(chunks: &[(git_chunk::Id, &[u8])], alignment: u64, piece_len: usize) -> std::io::Result<Vec<u8>> {
+        let mut index = Index::for_writing_with_alignment(alignment);
+        for (id, data) in chunks {
+            index.plan_chunk(*id, data.len() as u64);
+        }
+        let mut out = HEADER.to_vec();
+        let mut chunk_write = index.into_write(&mut out, HEADER.len())?;
+        while let Some(id) = chunk_write.next_chunk() {
+            let data = chunks.iter().find(|(kind, _)| *kind == id).expect("planned").1;
+            for piece in data.chunks(piece_len) {
+                chunk_write.write_all(piece)?;
+            }
+        }
+        Ok(out)
+    }
+
+    #[test]
+    fn chunks_can_be_streamed_and_read_back() -> std::io::Result<()> {
+        let chunks: &[(git_chunk::Id, &[u8])] = &[(*b"AAAA", b"abc"), (*b"BBBB", &[42; 1000]), (*b"CCCC", b"12345")];
+        for alignment in [1, 4, 8] {
+            let mut index = Index::for_writing_with_alignment(alignment);
+            for (id, data) in chunks {
+                index.plan_chunk(*id, data.len() as u64);
+            }
+            let planned_entries = index.planned_entries(HEADER.len());
+            let planned_size = index.planned_storage_size();
+            assert_eq!(planned_entries.len(), 3);
+
+            for piece_len in [1, 7, 4096] {
+                let out = write_chunks(chunks, alignment, piece_len)?;
+                assert_eq!(
+                    out.len() as u64,
+                    HEADER.len() as u64 + Index::size_for_entries(chunks.len()) as u64 + planned_size,
+                    "written bytes match the plan"
+                );
+
+                let decoded = Index::from_bytes(&out, HEADER.len(), chunks.len() as u32).expect("valid");
+                for ((id, data), planned) in chunks.iter().zip(&planned_entries) {
+                    let range = decoded.offset_by_id(*id).expect("present");
+                    assert_eq!(range, planned.offset, "the table of contents is as planned");
+                    assert_eq!(
+                        range.start % alignment,
+                        0,
+                        "chunks start aligned as the header is aligned"
+                    );
+                    let chunk = decoded.data_by_id(&out, *id).expect("present");
+                    assert_eq!(&chunk[..data.len()], *data);
+                    assert!(chunk[data.len()..].iter().all(|b| *b == 0), "padding is null bytes");
+                    assert!(chunk.len() < data.len() + alignment as usize);
+                }
+                assert_eq!(decoded.highest_offset(), out.len() as u64);
+            }
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn writing_more_than_planned_fails() {
+        let mut index = Index::for_writing();
+        index.plan_chunk(*b"AAAA", 2);
+        let mut chunk_write = index.into_write(Vec::new(), 0).unwrap();
+
+        assert!(
+            chunk_write.write_all(b"a").is_err(),
+            "writing before learning the chunk to write is an error"
+        );
+        assert_eq!(chunk_write.next_chunk(), Some(*b"AAAA"));
+        chunk_write.write_all(b"a").unwrap();
+        let err = chunk_write.write_all(b"bc").unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "BUG: chunk \"AAAA\" was planned with 2 bytes, but 3 bytes would be written"
+        );
+        chunk_write.write_all(b"b").unwrap();
+        assert_eq!(chunk_write.next_chunk(), None);
+        let out = chunk_write.into_inner();
+        assert_eq!(&out[out.len() - 2..], b"ab", "nothing of the failed write was written");
+    }
+
+    #[test]
+    #[should_panic]
+    fn writing_less_than_planned_panics() {
+        let mut index = Index::for_writing();
+        index.plan_chunk(*b"AAAA", 2);
+        let mut chunk_write = index.into_write(Vec::new(), 0).unwrap();
+        chunk_write.next_chunk();
+        chunk_write.write_all(b"a").unwrap();
+        chunk_write.next_chunk();
+    }
+
+    #[test]
+    #[should_panic]
+    fn the_sentinel_cannot_be_planned() {
+        Index::for_writing().plan_chunk(git_chunk::SENTINEL, 1);
+    }
+
+    #[test]
+    #[should_panic]
+    fn empty_indices_cannot_be_written() {
+        Index::for_writing().into_write(Vec::new(), 0).ok();
+    }
+}
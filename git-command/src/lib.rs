@@ -2,7 +2,9 @@
 #![deny(rust_2018_idioms, missing_docs)]
 #![forbid(unsafe_code)]
 
-use std::ffi::OsString;
+use std::{ffi::OsString, path::PathBuf};
+
+use bstr::BString;
 
 /// A structure to keep settings to use when invoking a command via [`spawn()`][Prepare::spawn()], after creating it with [`prepare()`].
 pub struct Prepare {
@@ -11,15 +13,46 @@ pub struct Prepare {
     stdout: std::process::Stdio,
     stderr: std::process::Stdio,
     args: Vec<OsString>,
+    env: Vec<(OsString, OsString)>,
+    env_allowlist: Option<Vec<OsString>>,
+    context: Option<Context>,
     use_shell: bool,
+    quote_command: bool,
 }
 
+/// Information about the repository a command is spawned for, passed to it as the environment variables git would set.
+///
+/// This is what hooks, filters and other programs spawned on behalf of a repository expect.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct Context {
+    /// The `.git` directory of the repository, passed as `GIT_DIR`.
+    pub git_dir: Option<PathBuf>,
+    /// The work tree of the repository, passed as `GIT_WORK_TREE`.
+    pub worktree_dir: Option<PathBuf>,
+    /// If `Some(true)`, replace references are ignored, passed as `GIT_NO_REPLACE_OBJECTS`.
+    pub no_replace_objects: Option<bool>,
+    /// The namespace all references are put into, passed as `GIT_NAMESPACE`.
+    pub ref_namespace: Option<BString>,
+    /// If `Some(true)`, pathspecs are taken literally, passed as `GIT_LITERAL_PATHSPECS`.
+    pub literal_pathspecs: Option<bool>,
+    /// If `Some(true)`, pathspecs are treated as globs, passed as `GIT_GLOB_PATHSPECS`.
+    pub glob_pathspecs: Option<bool>,
+    /// If `Some(true)`, pathspecs are matched case-insensitively, passed as `GIT_ICASE_PATHSPECS`.
+    pub icase_pathspecs: Option<bool>,
+}
+
+///
+pub mod shebang;
+
 mod prepare {
-    use std::process::{Command, Stdio};
+    use std::{
+        ffi::{OsStr, OsString},
+        process::{Command, Stdio},
+    };
 
-    use bstr::ByteSlice;
+    use bstr::{BString, ByteSlice};
 
-    use crate::Prepare;
+    use crate::{Context, Prepare};
 
     /// Builder
     impl Prepare {
@@ -34,6 +67,27 @@ mod prepare {
             self
         }
 
+        /// If the command runs in a shell, quote it so that its path is used verbatim even if it contains spaces or other
+        /// characters that the shell would interpret otherwise.
+        ///
+        /// This is useful for commands that are known to be paths to programs, as opposed to shell scripts.
+        pub fn with_quoted_command(mut self) -> Self {
+            self.quote_command = true;
+            self
+        }
+
+        /// Pass the environment variables describing the repository in `ctx` to the command.
+        pub fn with_context(mut self, ctx: Context) -> Self {
+            self.context = Some(ctx);
+            self
+        }
+
+        /// Configure the process to read from and write to pipes, which is what filters and transports talking to the
+        /// command need.
+        pub fn with_piped_stdin_and_stdout(self) -> Self {
+            self.stdin(Stdio::piped()).stdout(Stdio::piped())
+        }
+
         /// Configure the process to use `stdio` for _stdin.
         pub fn stdin(mut self, stdio: Stdio) -> Self {
             self.stdin = stdio;
@@ -55,6 +109,27 @@ mod prepare {
             self.args.push(arg.into());
             self
         }
+
+        /// Add all `args` to the list of arguments to call the command with.
+        pub fn args(mut self, args: impl IntoIterator<Item = impl Into<std::ffi::OsString>>) -> Self {
+            self.args.extend(args.into_iter().map(Into::into));
+            self
+        }
+
+        /// Set the environment variable `key` to `value` for the command, which takes precedence over all other
+        /// environment variables.
+        pub fn env(mut self, key: impl Into<OsString>, value: impl Into<OsString>) -> Self {
+            self.env.push((key.into(), value.into()));
+            self
+        }
+
+        /// Only pass the environment variables named in `names` from our environment to the command, instead of all of them.
+        ///
+        /// Variables set with [`env()`][Prepare::env()] and those of the [`Context`] are passed in any case.
+        pub fn env_allowlist(mut self, names: impl IntoIterator<Item = impl Into<OsString>>) -> Self {
+            self.env_allowlist = Some(names.into_iter().map(Into::into).collect());
+            self
+        }
     }
 
     /// Finalization
@@ -67,16 +142,31 @@ mod prepare {
     }
 
     impl From<Prepare> for Command {
-        fn from(mut prep: Prepare) -> Command {
+        fn from(prep: Prepare) -> Command {
             let mut cmd = if prep.use_shell {
                 let mut cmd = Command::new(if cfg!(windows) { "sh" } else { "/bin/sh" });
                 cmd.arg("-c");
+                let mut script = if prep.quote_command {
+                    quote_for_shell(&prep.command)
+                } else {
+                    prep.command
+                };
                 if !prep.args.is_empty() {
-                    prep.command.push(" \"$@\"")
+                    script.push(" \"$@\"")
                 }
-                cmd.arg(prep.command);
+                cmd.arg(script);
                 cmd.arg("--");
                 cmd
+            } else if cfg!(windows) {
+                // Windows can't run shell scripts by itself, so we use the interpreter of the script instead, like git does.
+                match crate::shebang::interpreter_of(prep.command.as_ref()) {
+                    Some(interpreter) => {
+                        let mut cmd = Command::new(interpreter.program);
+                        cmd.args(interpreter.args).arg(prep.command);
+                        cmd
+                    }
+                    None => Command::new(prep.command),
+                }
             } else {
                 Command::new(prep.command)
             };
@@ -84,9 +174,54 @@ mod prepare {
                 .stdout(prep.stdout)
                 .stderr(prep.stderr)
                 .args(prep.args);
+            if let Some(allowlist) = prep.env_allowlist {
+                cmd.env_clear();
+                for name in allowlist {
+                    if let Some(value) = std::env::var_os(&name) {
+                        cmd.env(name, value);
+                    }
+                }
+            }
+            if let Some(ctx) = prep.context {
+                if let Some(git_dir) = ctx.git_dir {
+                    cmd.env("GIT_DIR", git_dir);
+                }
+                if let Some(worktree_dir) = ctx.worktree_dir {
+                    cmd.env("GIT_WORK_TREE", worktree_dir);
+                }
+                if let Some(namespace) = ctx.ref_namespace {
+                    cmd.env("GIT_NAMESPACE", git_path::from_bstring(namespace));
+                }
+                for (name, value) in [
+                    ("GIT_NO_REPLACE_OBJECTS", ctx.no_replace_objects),
+                    ("GIT_LITERAL_PATHSPECS", ctx.literal_pathspecs),
+                    ("GIT_GLOB_PATHSPECS", ctx.glob_pathspecs),
+                    ("GIT_ICASE_PATHSPECS", ctx.icase_pathspecs),
+                ] {
+                    if let Some(value) = value {
+                        cmd.env(name, if value { "1" } else { "0" });
+                    }
+                }
+            }
+            cmd.envs(prep.env);
             cmd
         }
     }
+
+    /// Put `command` into single quotes so that `sh` uses it verbatim, escaping single quotes within.
+    fn quote_for_shell(command: &OsStr) -> OsString {
+        let command = git_path::into_bstr(std::path::Path::new(command));
+        let mut quoted = BString::from("'");
+        for &b in command.iter() {
+            if b == b'\'' {
+                quoted.extend_from_slice(b"'\\''");
+            } else {
+                quoted.push(b);
+            }
+        }
+        quoted.push(b'\'');
+        git_path::from_bstring(quoted).into_os_string()
+    }
 }
 
 /// Prepare `cmd` for [spawning][std::process::Command::spawn()] by configuring it with various builder methods.
@@ -103,6 +238,10 @@ pub fn prepare(cmd: impl Into<OsString>) -> Prepare {
         stdout: std::process::Stdio::piped(),
         stderr: std::process::Stdio::inherit(),
         args: Vec::new(),
+        env: Vec::new(),
+        env_allowlist: None,
+        context: None,
         use_shell: false,
+        quote_command: false,
     }
 }
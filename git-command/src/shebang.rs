@@ -0,0 +1,49 @@
+use std::{ffi::OsString, path::Path};
+
+use bstr::{BStr, ByteSlice};
+
+/// The interpreter of a script as declared in its shebang line, like `#!/bin/sh`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Interpreter {
+    /// The program to run the script with, without its directory if it was absolute, so that it's looked up in `PATH`.
+    ///
+    /// Scripts run with `#!/usr/bin/env <program>` use `<program>` directly.
+    pub program: OsString,
+    /// The arguments to pass to `program` before the path to the script.
+    pub args: Vec<OsString>,
+}
+
+/// Parse the first line of a script in `buf`, returning its interpreter if it starts with `#!`.
+///
+/// Like git on Windows, the directory of absolute interpreter paths is dropped as it is typically only valid on unix systems,
+/// and everything following the program is its single argument.
+pub fn parse(buf: &BStr) -> Option<Interpreter> {
+    let line = buf.strip_prefix(b"#!")?.lines().next()?.trim();
+    let (program, arg) = match line.find_byteset(b" \t") {
+        Some(pos) => (&line[..pos], Some(line[pos + 1..].trim_start())),
+        None => (line, None),
+    };
+    let mut args: Vec<OsString> = arg
+        .filter(|arg| !arg.is_empty())
+        .map(|arg| git_path::from_byte_slice(arg).as_os_str().to_owned())
+        .into_iter()
+        .collect();
+    let file_name = |program: &[u8]| {
+        let program = git_path::from_byte_slice(program);
+        program.file_name().map(ToOwned::to_owned)
+    };
+    let mut program = file_name(program)?;
+    if program == "env" && !args.is_empty() {
+        program = args.remove(0);
+    }
+    Some(Interpreter { program, args })
+}
+
+/// Read the beginning of the file at `path` and return the interpreter it declares, or `None` if it's no script or can't be read.
+pub fn interpreter_of(path: &Path) -> Option<Interpreter> {
+    use std::io::Read;
+    let mut buf = [0; 256];
+    let mut file = std::fs::File::open(path).ok()?;
+    let len = file.read(&mut buf).ok()?;
+    parse(buf[..len].as_bstr())
+}
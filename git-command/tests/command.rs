@@ -1,6 +1,90 @@
 use git_testtools::Result;
 
 mod spawn {
+    use git_testtools::bstr::ByteSlice;
+
+    #[test]
+    #[cfg(unix)]
+    fn environment_variables_can_be_set_and_filtered() -> crate::Result {
+        let out = git_command::prepare("env")
+            .env_allowlist(["PATH"])
+            .env("CUSTOM", "value")
+            .spawn()?
+            .wait_with_output()?;
+        assert!(out.status.success());
+        let mut names: Vec<_> = out
+            .stdout
+            .lines()
+            .filter_map(|line| line.split_str("=").next())
+            .map(|name| name.to_str_lossy().into_owned())
+            .collect();
+        names.sort();
+        assert_eq!(names, ["CUSTOM", "PATH"]);
+        Ok(())
+    }
+
+    #[test]
+    #[cfg(unix)]
+    fn context_is_passed_as_environment_variables() -> crate::Result {
+        let out = git_command::prepare("env")
+            .env_allowlist(None::<&str>)
+            .with_context(git_command::Context {
+                git_dir: Some(".git".into()),
+                worktree_dir: Some("worktree".into()),
+                no_replace_objects: Some(true),
+                ref_namespace: Some("ns".into()),
+                literal_pathspecs: Some(false),
+                glob_pathspecs: None,
+                icase_pathspecs: Some(true),
+            })
+            .env("GIT_DIR", "overridden")
+            .spawn()?
+            .wait_with_output()?;
+        assert!(out.status.success());
+        let mut vars: Vec<_> = out
+            .stdout
+            .lines()
+            .map(|line| line.to_str_lossy().into_owned())
+            .collect();
+        vars.sort();
+        assert_eq!(
+            vars,
+            [
+                "GIT_DIR=overridden",
+                "GIT_ICASE_PATHSPECS=1",
+                "GIT_LITERAL_PATHSPECS=0",
+                "GIT_NAMESPACE=ns",
+                "GIT_NO_REPLACE_OBJECTS=1",
+                "GIT_WORK_TREE=worktree",
+            ],
+            "explicitly set variables take precedence"
+        );
+        Ok(())
+    }
+
+    #[test]
+    #[cfg(unix)]
+    fn piped_stdin_and_stdout() -> crate::Result {
+        use std::io::Write;
+        let mut child = git_command::prepare("cat").with_piped_stdin_and_stdout().spawn()?;
+        child.stdin.take().expect("piped").write_all(b"hello")?;
+        let out = child.wait_with_output()?;
+        assert_eq!(out.stdout.as_bstr(), "hello");
+        Ok(())
+    }
+
+    #[test]
+    #[cfg(windows)]
+    fn shell_scripts_are_run_with_their_interpreter() -> crate::Result {
+        let dir = git_testtools::tempfile::tempdir()?;
+        let script = dir.path().join("script");
+        std::fs::write(&script, "#!/bin/sh\necho \"$1\"\n")?;
+        let out = git_command::prepare(&script).arg("hi").spawn()?.wait_with_output()?;
+        assert!(out.status.success());
+        assert_eq!(out.stdout.trim().as_bstr(), "hi");
+        Ok(())
+    }
+
     #[test]
     fn direct_command_execution_searches_in_path() -> crate::Result {
         assert!(git_command::prepare(if cfg!(unix) { "ls" } else { "dir.exe" })
@@ -20,6 +104,27 @@ mod spawn {
     mod with_shell {
         use git_testtools::bstr::ByteSlice;
 
+        #[test]
+        fn quoted_commands_may_contain_spaces() -> crate::Result {
+            let dir = git_testtools::tempfile::tempdir()?;
+            let program = dir.path().join("it's a program");
+            std::fs::write(&program, "#!/bin/sh\necho \"$@\"\n")?;
+            #[cfg(unix)]
+            {
+                use std::os::unix::fs::PermissionsExt;
+                std::fs::set_permissions(&program, std::fs::Permissions::from_mode(0o755))?;
+            }
+            let out = git_command::prepare(&program)
+                .with_shell()
+                .with_quoted_command()
+                .arg("a b")
+                .spawn()?
+                .wait_with_output()?;
+            assert!(out.status.success());
+            assert_eq!(out.stdout.trim().as_bstr(), "a b");
+            Ok(())
+        }
+
         #[test]
         fn command_in_path_with_args() -> crate::Result {
             assert!(git_command::prepare(if cfg!(unix) { "ls -l" } else { "dir.exe -a" })
@@ -68,3 +173,34 @@ mod spawn {
         }
     }
 }
+
+mod shebang {
+    use git_command::shebang::{parse, Interpreter};
+
+    fn interpreter(program: &str, args: &[&str]) -> Option<Interpreter> {
+        Some(Interpreter {
+            program: program.into(),
+            args: args.iter().map(Into::into).collect(),
+        })
+    }
+
+    #[test]
+    fn absolute_programs_are_looked_up_by_name() {
+        assert_eq!(parse("#!/bin/sh\necho hi".into()), interpreter("sh", &[]));
+        assert_eq!(parse("#! /bin/bash -e \r\n".into()), interpreter("bash", &["-e"]));
+        assert_eq!(parse("#!perl".into()), interpreter("perl", &[]));
+    }
+
+    #[test]
+    fn env_is_skipped_in_favor_of_its_program() {
+        assert_eq!(parse("#!/usr/bin/env python3\n".into()), interpreter("python3", &[]));
+        assert_eq!(parse("#!/usr/bin/env\n".into()), interpreter("env", &[]));
+    }
+
+    #[test]
+    fn everything_but_scripts_is_ignored() {
+        for input in ["", "#", "#!", "#!\n", "\u{7f}ELF", "echo #!/bin/sh"] {
+            assert_eq!(parse(input.into()), None, "{:?}", input);
+        }
+    }
+}
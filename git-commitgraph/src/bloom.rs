@@ -0,0 +1,161 @@
+//! Query changed-path Bloom filters, which tell if a commit possibly changed a path compared to its first parent.
+//!
+//! These are written by `git commit-graph write --changed-paths` into the `BIDX` and `BDAT` chunks of commit-graph files,
+//! and can be used to avoid diffing trees for the vast majority of commits in path-limited traversals.
+use bstr::{BStr, ByteSlice};
+
+/// The parameters used to create all Bloom filters within a commit-graph file.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde1", derive(serde::Serialize, serde::Deserialize))]
+pub struct Settings {
+    /// The version of the murmur3 hash function used to compute keys, which is `1` or `2`.
+    ///
+    /// Version 1 is what git used up to v2.45, and it treats bytes of paths as signed values.
+    pub hash_version: u32,
+    /// The amount of bits to set in a filter for each path.
+    pub num_hashes: u32,
+    /// The minimal amount of filter bits per changed path.
+    pub bits_per_entry: u32,
+}
+
+impl Settings {
+    pub(crate) fn is_supported(&self) -> bool {
+        matches!(self.hash_version, 1 | 2) && self.num_hashes > 0
+    }
+}
+
+/// The hashes of a path to [lookup][Filter::contains()] in a [`Filter`].
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Key {
+    hashes: Vec<u32>,
+    settings: Settings,
+}
+
+/// Init
+impl Key {
+    /// Create a new key for looking up the exact `path` in filters that were created with `settings`.
+    pub fn new(path: &BStr, settings: Settings) -> Self {
+        const SEED0: u32 = 0x293a_e76f;
+        const SEED1: u32 = 0x7e64_6e2c;
+        let signed_bytes = settings.hash_version == 1;
+        let hash0 = murmur3(SEED0, path, signed_bytes);
+        let hash1 = murmur3(SEED1, path, signed_bytes);
+        Key {
+            hashes: (0..settings.num_hashes)
+                .map(|idx| hash0.wrapping_add(idx.wrapping_mul(hash1)))
+                .collect(),
+            settings,
+        }
+    }
+
+    /// Create the keys needed to see if `path` was changed, which are the keys of `path` itself and of each of its leading
+    /// directories, as a change to `path` is also a change of all directories containing it.
+    ///
+    /// Trailing slashes are ignored, and an empty `path`, which stands for the whole tree, yields no keys at all.
+    pub fn for_path_and_leading_directories(path: &BStr, settings: Settings) -> Vec<Self> {
+        let path = path.trim_end_with(|c| c == '/').as_bstr();
+        if path.is_empty() {
+            return Vec::new();
+        }
+        std::iter::once(path)
+            .chain(
+                path.rfind_iter("/")
+                    .map(|pos| path[..pos].as_bstr())
+                    .filter(|dir| !dir.is_empty()),
+            )
+            .map(|path| Key::new(path, settings))
+            .collect()
+    }
+}
+
+/// Access
+impl Key {
+    /// The settings this key was created with.
+    pub fn settings(&self) -> Settings {
+        self.settings
+    }
+}
+
+/// A changed-path Bloom filter of a single commit, as obtained by [`Commit::bloom_filter()`][crate::file::Commit::bloom_filter()].
+///
+/// It contains the paths of all files and directories that changed between the commit and its first parent, or the empty tree
+/// if it doesn't have parents.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Filter<'a> {
+    /// The raw filter bits.
+    pub data: &'a [u8],
+    /// The settings used to create the filter.
+    pub settings: Settings,
+}
+
+/// Query
+impl Filter<'_> {
+    /// Return `false` if the path of `key` definitely wasn't changed, or `true` if it possibly was.
+    ///
+    /// Note that `true` is also returned if `key` was created with different [`Settings`], or if the filter is empty which happens
+    /// if it wasn't computed.
+    pub fn contains(&self, key: &Key) -> bool {
+        let num_bits = self.data.len() as u64 * 8;
+        if num_bits == 0 || key.settings.hash_version != self.settings.hash_version {
+            return true;
+        }
+        if key.hashes.len() < self.settings.num_hashes as usize {
+            return true;
+        }
+        key.hashes.iter().take(self.settings.num_hashes as usize).all(|hash| {
+            let bit = u64::from(*hash) % num_bits;
+            self.data[(bit / 8) as usize] & (1 << (bit % 8)) != 0
+        })
+    }
+
+    /// Return `false` if any of `keys` definitely isn't contained in this filter, or `true` if all of them possibly are.
+    ///
+    /// Use it with the keys of [`Key::for_path_and_leading_directories()`] to learn if a path was possibly changed.
+    pub fn contains_all<'k>(&self, keys: impl IntoIterator<Item = &'k Key>) -> bool {
+        keys.into_iter().all(|key| self.contains(key))
+    }
+}
+
+/// The murmur3 hash function as implemented by git, which treats `data` as signed bytes if `signed_bytes` is `true` to match the
+/// behaviour of hash version 1 on platforms where `char` is signed.
+fn murmur3(mut seed: u32, data: &[u8], signed_bytes: bool) -> u32 {
+    const C1: u32 = 0xcc9e_2d51;
+    const C2: u32 = 0x1b87_3593;
+    const R1: u32 = 15;
+    const R2: u32 = 13;
+    const M: u32 = 5;
+    const N: u32 = 0xe654_6b64;
+    let byte = |b: u8| {
+        if signed_bytes {
+            b as i8 as u32
+        } else {
+            u32::from(b)
+        }
+    };
+
+    let mut blocks = data.chunks_exact(4);
+    for block in &mut blocks {
+        let mut k = byte(block[0]) | byte(block[1]) << 8 | byte(block[2]) << 16 | byte(block[3]) << 24;
+        k = k.wrapping_mul(C1).rotate_left(R1).wrapping_mul(C2);
+        seed ^= k;
+        seed = seed.rotate_left(R2).wrapping_mul(M).wrapping_add(N);
+    }
+
+    let tail = blocks.remainder();
+    if !tail.is_empty() {
+        let mut k = 0;
+        for (idx, b) in tail.iter().enumerate().rev() {
+            k ^= byte(*b) << (8 * idx);
+        }
+        k = k.wrapping_mul(C1).rotate_left(R1).wrapping_mul(C2);
+        seed ^= k;
+    }
+
+    seed ^= data.len() as u32;
+    seed ^= seed >> 16;
+    seed = seed.wrapping_mul(0x85eb_ca6b);
+    seed ^= seed >> 13;
+    seed = seed.wrapping_mul(0xc2b2_ae35);
+    seed ^= seed >> 16;
+    seed
+}
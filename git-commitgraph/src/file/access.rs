@@ -4,7 +4,10 @@ use std::{
     path::Path,
 };
 
-use crate::file::{self, commit::Commit, File, COMMIT_DATA_ENTRY_SIZE_SANS_HASH};
+use crate::{
+    bloom,
+    file::{self, commit::Commit, File, COMMIT_DATA_ENTRY_SIZE_SANS_HASH},
+};
 
 /// Access
 impl File {
@@ -31,6 +34,12 @@ impl File {
         self.object_hash
     }
 
+    /// The settings used to create the changed-path Bloom filters of this file, or `None` if it doesn't contain any or if they
+    /// can't be used.
+    pub fn bloom_filter_settings(&self) -> Option<bloom::Settings> {
+        self.bloom_filters.as_ref().map(|filters| filters.settings)
+    }
+
     /// Returns an object id at the given index in our list of (sorted) hashes.
     /// The position ranges from 0 to self.num_commits()
     // copied from git-odb/src/pack/index/ext
@@ -122,6 +131,26 @@ impl File {
         &self.data[start..][..entry_size]
     }
 
+    /// Returns the changed-path Bloom filter for the given commit, if this file has Bloom filters and they are consistent.
+    pub(crate) fn bloom_filter(&self, pos: file::Position) -> Option<bloom::Filter<'_>> {
+        let filters = self.bloom_filters.as_ref()?;
+        let pos: usize = pos
+            .0
+            .try_into()
+            .expect("an architecture able to hold 32 bits of integer");
+        let end_at = |pos: usize| -> usize {
+            let start = filters.index_offset + pos * 4;
+            u32::from_be_bytes(self.data[start..][..4].try_into().expect("4 bytes")) as usize
+        };
+        let start = if pos == 0 { 0 } else { end_at(pos - 1) };
+        let end = end_at(pos);
+        let data = &self.data[filters.data_range.clone()];
+        Some(bloom::Filter {
+            data: data.get(start..end)?,
+            settings: filters.settings,
+        })
+    }
+
     /// Returns the byte slice for this file's entire Extra Edge List (EDGE) chunk.
     pub(crate) fn extra_edges_data(&self) -> Option<&[u8]> {
         Some(&self.data[self.extra_edges_list_range.clone()?])
@@ -5,7 +5,10 @@ use std::{
     slice::Chunks,
 };
 
+use bstr::BStr;
+
 use crate::{
+    bloom,
     file::{self, File, EXTENDED_EDGES_MASK, LAST_EXTENDED_EDGE_MASK, NO_PARENT},
     graph,
 };
@@ -82,6 +85,21 @@ impl<'a> Commit<'a> {
         }
     }
 
+    /// Returns the changed-path Bloom filter of this commit, or `None` if the commit-graph file doesn't contain them.
+    pub fn bloom_filter(&self) -> Option<bloom::Filter<'a>> {
+        self.file.bloom_filter(self.pos)
+    }
+
+    /// Returns `Some(false)` if this commit definitely didn't change `path` compared to its first parent, `Some(true)` if it
+    /// possibly did, or `None` if there is no Bloom filter to tell.
+    ///
+    /// Note that when querying many commits, it's more efficient to create the [keys][bloom::Key::for_path_and_leading_directories()]
+    /// once and use them with the [`bloom_filter()`][Commit::bloom_filter()] of each commit.
+    pub fn maybe_changed_path(&self, path: &BStr) -> Option<bool> {
+        let filter = self.bloom_filter()?;
+        Some(filter.contains_all(&bloom::Key::for_path_and_leading_directories(path, filter.settings)))
+    }
+
     /// Returns the hash of this commit.
     pub fn id(&self) -> &'a git_hash::oid {
         self.file.id_at(self.pos)
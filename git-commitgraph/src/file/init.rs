@@ -6,9 +6,14 @@ use std::{
 use bstr::ByteSlice;
 use memmap2::Mmap;
 
-use crate::file::{
-    ChunkId, File, BASE_GRAPHS_LIST_CHUNK_ID, COMMIT_DATA_CHUNK_ID, COMMIT_DATA_ENTRY_SIZE_SANS_HASH,
-    EXTENDED_EDGES_LIST_CHUNK_ID, FAN_LEN, HEADER_LEN, OID_FAN_CHUNK_ID, OID_LOOKUP_CHUNK_ID, SIGNATURE,
+use crate::{
+    bloom,
+    file::{
+        BloomFilters, ChunkId, File, BASE_GRAPHS_LIST_CHUNK_ID, BLOOM_FILTER_DATA_CHUNK_ID,
+        BLOOM_FILTER_DATA_HEADER_LEN, BLOOM_FILTER_INDEX_CHUNK_ID, COMMIT_DATA_CHUNK_ID,
+        COMMIT_DATA_ENTRY_SIZE_SANS_HASH, EXTENDED_EDGES_LIST_CHUNK_ID, FAN_LEN, HEADER_LEN, OID_FAN_CHUNK_ID,
+        OID_LOOKUP_CHUNK_ID, SIGNATURE,
+    },
 };
 
 /// The error used in [`File::at()`].
@@ -229,9 +234,48 @@ impl TryFrom<&Path> for File {
                 chunk2_commits: commit_data_count,
             });
         }
+        let bloom_filters = match (
+            chunks.usize_offset_by_id(BLOOM_FILTER_INDEX_CHUNK_ID).ok(),
+            chunks.usize_offset_by_id(BLOOM_FILTER_DATA_CHUNK_ID).ok(),
+        ) {
+            (Some(index_range), Some(data_range)) => {
+                let expected_size = 4 * fan[255] as usize;
+                if index_range.len() != expected_size {
+                    return Err(Error::InvalidChunkSize {
+                        id: BLOOM_FILTER_INDEX_CHUNK_ID,
+                        msg: format!("expected chunk length {}, got {}", expected_size, index_range.len()),
+                    });
+                }
+                if data_range.len() < BLOOM_FILTER_DATA_HEADER_LEN {
+                    return Err(Error::InvalidChunkSize {
+                        id: BLOOM_FILTER_DATA_CHUNK_ID,
+                        msg: format!(
+                            "expected chunk length of at least {}, got {}",
+                            BLOOM_FILTER_DATA_HEADER_LEN,
+                            data_range.len()
+                        ),
+                    });
+                }
+                let header = &data[data_range.start..][..BLOOM_FILTER_DATA_HEADER_LEN];
+                let read_u32 = |ofs: usize| u32::from_be_bytes(header[ofs..][..4].try_into().expect("4 bytes"));
+                let settings = bloom::Settings {
+                    hash_version: read_u32(0),
+                    num_hashes: read_u32(4),
+                    bits_per_entry: read_u32(8),
+                };
+                // Like git, we ignore filters we can't use instead of failing.
+                settings.is_supported().then(|| BloomFilters {
+                    index_offset: index_range.start,
+                    data_range: data_range.start + BLOOM_FILTER_DATA_HEADER_LEN..data_range.end,
+                    settings,
+                })
+            }
+            _ => None,
+        };
         Ok(File {
             base_graph_count,
             base_graphs_list_offset,
+            bloom_filters,
             commit_data_offset,
             data,
             extra_edges_list_range,
@@ -23,6 +23,8 @@ const SIGNATURE: &[u8] = b"CGPH";
 
 type ChunkId = git_chunk::Id;
 const BASE_GRAPHS_LIST_CHUNK_ID: ChunkId = *b"BASE";
+const BLOOM_FILTER_DATA_CHUNK_ID: ChunkId = *b"BDAT";
+const BLOOM_FILTER_INDEX_CHUNK_ID: ChunkId = *b"BIDX";
 const COMMIT_DATA_CHUNK_ID: ChunkId = *b"CDAT";
 const EXTENDED_EDGES_LIST_CHUNK_ID: ChunkId = *b"EDGE";
 const OID_FAN_CHUNK_ID: ChunkId = *b"OIDF";
@@ -33,6 +35,7 @@ const OID_LOOKUP_CHUNK_ID: ChunkId = *b"OIDL";
 const NO_PARENT: u32 = 0x7000_0000;
 const EXTENDED_EDGES_MASK: u32 = 0x8000_0000;
 const LAST_EXTENDED_EDGE_MASK: u32 = 0x8000_0000;
+const BLOOM_FILTER_DATA_HEADER_LEN: usize = 12;
 
 /// A single commit-graph file.
 ///
@@ -41,6 +44,7 @@ const LAST_EXTENDED_EDGE_MASK: u32 = 0x8000_0000;
 pub struct File {
     base_graph_count: u8,
     base_graphs_list_offset: Option<usize>,
+    bloom_filters: Option<BloomFilters>,
     commit_data_offset: usize,
     data: Mmap,
     extra_edges_list_range: Option<Range<usize>>,
@@ -51,6 +55,13 @@ pub struct File {
     object_hash: git_hash::Kind,
 }
 
+/// The location of the changed-path Bloom filters within a [`File`].
+struct BloomFilters {
+    index_offset: usize,
+    data_range: Range<usize>,
+    settings: crate::bloom::Settings,
+}
+
 /// The position of a given commit within a graph file, starting at 0.
 ///
 /// Commits within a graph file are sorted in lexicographical order by OID; a commit's lexigraphical position
@@ -14,12 +14,27 @@ impl Graph {
         r.file.commit_at(r.pos)
     }
 
+    /// The settings of the changed-path Bloom filters of the most recent file in the graph, or `None` if it doesn't contain any.
+    ///
+    /// These are the settings to create [keys][crate::bloom::Key] with, even though each file may have its own.
+    pub fn bloom_filter_settings(&self) -> Option<crate::bloom::Settings> {
+        self.files.last()?.bloom_filter_settings()
+    }
+
     /// Returns the commit matching the given `id`.
     pub fn commit_by_id(&self, id: impl AsRef<git_hash::oid>) -> Option<Commit<'_>> {
         let r = self.lookup_by_id(id.as_ref())?;
         Some(r.file.commit_at(r.file_pos))
     }
 
+    /// Returns `Some(false)` if the commit with `id` definitely didn't change `path` compared to its first parent, `Some(true)`
+    /// if it possibly did, or `None` if the commit isn't in the graph or there is no Bloom filter to tell.
+    ///
+    /// See [`Commit::maybe_changed_path()`] for details.
+    pub fn maybe_changed_path(&self, id: impl AsRef<git_hash::oid>, path: &bstr::BStr) -> Option<bool> {
+        self.commit_by_id(id)?.maybe_changed_path(path)
+    }
+
     /// Returns the `hash` at the given position `pos`.
     ///
     /// # Panics
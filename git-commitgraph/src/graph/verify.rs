@@ -11,6 +11,9 @@ use crate::{
     graph, Graph, GENERATION_NUMBER_MAX,
 };
 
+///
+pub mod objects;
+
 /// The error used in [`verify_integrity()`][Graph::verify_integrity].
 #[derive(thiserror::Error, Debug)]
 #[allow(missing_docs)]
@@ -0,0 +1,265 @@
+//! Verify a commit graph against the commit objects it was created from, see [`Graph::verify_objects()`].
+use std::{
+    cmp::{max, min},
+    sync::atomic::{AtomicBool, Ordering},
+};
+
+use git_hash::{oid, ObjectId};
+use git_object::CommitRefIter;
+
+use crate::{file::commit, graph, Graph, GENERATION_NUMBER_MAX};
+
+/// The error returned by [`Graph::verify_objects()`].
+#[derive(Debug, thiserror::Error)]
+#[allow(missing_docs)]
+pub enum Error {
+    #[error("Interrupted")]
+    Interrupted,
+}
+
+/// A way in which a commit in the graph doesn't match its commit object, as found by [`Graph::verify_objects()`].
+#[derive(Debug, thiserror::Error)]
+#[allow(missing_docs)]
+pub enum Issue {
+    #[error("Commit {id} is in the commit-graph, but not in the object database")]
+    MissingCommit { id: ObjectId },
+    #[error("Commit {id} could not be decoded")]
+    Decode {
+        id: ObjectId,
+        source: git_object::decode::Error,
+    },
+    #[error("Commit {id} is looked up at position {actual:?}, but is stored at position {expected}")]
+    Lookup {
+        id: ObjectId,
+        expected: graph::Position,
+        actual: Option<graph::Position>,
+    },
+    #[error("Commit {id} has tree {actual} in the commit-graph, but its object has tree {expected}")]
+    RootTree {
+        id: ObjectId,
+        expected: ObjectId,
+        actual: ObjectId,
+    },
+    #[error("Commit {id} has invalid parents in the commit-graph")]
+    InvalidParents { id: ObjectId, source: commit::Error },
+    #[error("Commit {id} has parent position {parent_pos} in the commit-graph which is out of range")]
+    ParentOutOfRange { id: ObjectId, parent_pos: graph::Position },
+    #[error("Commit {id} has parents {actual:?} in the commit-graph, but its object has parents {expected:?}")]
+    Parents {
+        id: ObjectId,
+        expected: Vec<ObjectId>,
+        actual: Vec<ObjectId>,
+    },
+    #[error("Commit {id} has commit time {actual} in the commit-graph, but its object has time {expected}")]
+    CommitTime { id: ObjectId, expected: u64, actual: u64 },
+    #[error("Commit {id}'s generation should be {expected} but is {actual}")]
+    Generation { id: ObjectId, expected: u32, actual: u32 },
+}
+
+impl Issue {
+    /// The id of the commit with the issue.
+    pub fn id(&self) -> &oid {
+        match self {
+            Issue::MissingCommit { id }
+            | Issue::Decode { id, .. }
+            | Issue::Lookup { id, .. }
+            | Issue::RootTree { id, .. }
+            | Issue::InvalidParents { id, .. }
+            | Issue::ParentOutOfRange { id, .. }
+            | Issue::Parents { id, .. }
+            | Issue::CommitTime { id, .. }
+            | Issue::Generation { id, .. } => id,
+        }
+    }
+
+    /// Return a suggestion on how to resolve this issue.
+    pub fn suggestion(&self) -> &'static str {
+        match self {
+            Issue::MissingCommit { .. } => {
+                "The commit-graph is outdated. Rewrite it with `git commit-graph write --reachable`."
+            }
+            Issue::Decode { .. } => {
+                "The commit object is corrupt and should be restored, for instance by fetching it from another repository."
+            }
+            _ => "The commit-graph is corrupt. Delete 'objects/info/commit-graph' and 'objects/info/commit-graphs/', then rewrite it with `git commit-graph write --reachable`.",
+        }
+    }
+}
+
+/// The result of [`Graph::verify_objects()`].
+#[derive(Debug)]
+pub struct Outcome {
+    /// The amount of commits that were checked.
+    pub num_commits: u32,
+    /// All issues found, sorted by the id of the commit they refer to.
+    ///
+    /// If empty, the graph matches the object database.
+    pub issues: Vec<Issue>,
+}
+
+/// The information of a commit object that is also stored in the commit graph.
+struct CommitObject {
+    tree_id: ObjectId,
+    parents: Vec<ObjectId>,
+    commit_time: u64,
+}
+
+impl Graph {
+    /// Verify that each commit in this graph matches its commit object as obtained by `find`, which is cloned for each of
+    /// the threads, using at most `thread_limit` threads.
+    ///
+    /// This checks if the tree, parents and commit time of each commit are the same as in its object, that the generation
+    /// of each commit is one more than the largest generation of its parents, and that each commit is found by its id at its
+    /// position in the graph. As opposed to [`verify_integrity()`][Graph::verify_integrity()], which only validates the
+    /// graph files themselves, this reads all commits and detects outdated or incorrectly written graphs.
+    ///
+    /// Instead of stopping at the first issue, all of them are collected into the returned [`Outcome`], each with a
+    /// [suggestion][Issue::suggestion()] on how to resolve it. Set `should_interrupt` to stop the verification with an error.
+    /// Note that without the `parallel` feature of `git-features`, all work is done on the calling thread.
+    pub fn verify_objects<Find>(
+        &self,
+        find: Find,
+        thread_limit: Option<usize>,
+        should_interrupt: &AtomicBool,
+    ) -> Result<Outcome, Error>
+    where
+        Find: for<'a> FnMut(&oid, &'a mut Vec<u8>) -> Option<CommitRefIter<'a>> + Send + Clone,
+    {
+        const CHUNK_SIZE: u32 = 1000;
+        let num_commits = self.num_commits();
+        let mut issues = git_features::parallel::in_parallel(
+            (0..num_commits)
+                .step_by(CHUNK_SIZE as usize)
+                .map(|start| start..min(start + CHUNK_SIZE, num_commits)),
+            thread_limit,
+            {
+                let find = find.clone();
+                move |_thread_id| (find.clone(), Vec::new())
+            },
+            |positions, (find, buf)| -> Result<Vec<Issue>, Error> {
+                let mut issues = Vec::new();
+                for pos in positions {
+                    if should_interrupt.load(Ordering::Relaxed) {
+                        return Err(Error::Interrupted);
+                    }
+                    self.verify_object(graph::Position(pos), find, buf, &mut issues);
+                }
+                Ok(issues)
+            },
+            Collect::default(),
+        )?;
+        issues.sort_by(|a, b| a.id().cmp(b.id()));
+        Ok(Outcome { num_commits, issues })
+    }
+
+    fn verify_object<Find>(&self, pos: graph::Position, find: &mut Find, buf: &mut Vec<u8>, issues: &mut Vec<Issue>)
+    where
+        Find: for<'a> FnMut(&oid, &'a mut Vec<u8>) -> Option<CommitRefIter<'a>>,
+    {
+        let commit = self.commit_at(pos);
+        let id = commit.id().to_owned();
+        match self.lookup(id) {
+            Some(actual) if actual == pos => {}
+            actual => issues.push(Issue::Lookup {
+                id,
+                expected: pos,
+                actual,
+            }),
+        }
+
+        let mut parents = Vec::new();
+        let mut max_parent_generation = 0;
+        for parent_pos in commit.iter_parents() {
+            match parent_pos {
+                Ok(parent_pos) if parent_pos.0 < self.num_commits() => {
+                    let parent = self.commit_at(parent_pos);
+                    max_parent_generation = max(max_parent_generation, parent.generation());
+                    parents.push(parent.id().to_owned());
+                }
+                Ok(parent_pos) => return issues.push(Issue::ParentOutOfRange { id, parent_pos }),
+                Err(source) => return issues.push(Issue::InvalidParents { id, source }),
+            }
+        }
+        // Generations may not be computed by old versions of git, in which case they are zero.
+        let expected_generation = min(max_parent_generation + 1, GENERATION_NUMBER_MAX);
+        if commit.generation() != 0 && commit.generation() != expected_generation {
+            issues.push(Issue::Generation {
+                id,
+                expected: expected_generation,
+                actual: commit.generation(),
+            });
+        }
+
+        let object = match find(&id, buf) {
+            Some(iter) => match decode(iter) {
+                Ok(object) => object,
+                Err(source) => return issues.push(Issue::Decode { id, source }),
+            },
+            None => return issues.push(Issue::MissingCommit { id }),
+        };
+        if object.tree_id != commit.root_tree_id() {
+            issues.push(Issue::RootTree {
+                id,
+                expected: object.tree_id,
+                actual: commit.root_tree_id().to_owned(),
+            });
+        }
+        if object.parents != parents {
+            issues.push(Issue::Parents {
+                id,
+                expected: object.parents,
+                actual: parents,
+            });
+        }
+        if object.commit_time != commit.committer_timestamp() {
+            issues.push(Issue::CommitTime {
+                id,
+                expected: object.commit_time,
+                actual: commit.committer_timestamp(),
+            });
+        }
+    }
+}
+
+#[derive(Default)]
+struct Collect {
+    issues: Vec<Issue>,
+}
+
+impl git_features::parallel::Reduce for Collect {
+    type Input = Result<Vec<Issue>, Error>;
+    type FeedProduce = ();
+    type Output = Vec<Issue>;
+    type Error = Error;
+
+    fn feed(&mut self, item: Self::Input) -> Result<Self::FeedProduce, Self::Error> {
+        self.issues.extend(item?);
+        Ok(())
+    }
+
+    fn finalize(self) -> Result<Self::Output, Self::Error> {
+        Ok(self.issues)
+    }
+}
+
+fn decode(iter: CommitRefIter<'_>) -> Result<CommitObject, git_object::decode::Error> {
+    use git_object::commit::ref_iter::Token;
+    let mut tree_id = None;
+    let mut parents = Vec::new();
+    for token in iter {
+        match token? {
+            Token::Tree { id } => tree_id = Some(id),
+            Token::Parent { id } => parents.push(id),
+            Token::Author { .. } => {}
+            Token::Committer { signature } => {
+                return Ok(CommitObject {
+                    tree_id: tree_id.expect("the tree is always the first token"),
+                    parents,
+                    commit_time: signature.time.seconds_since_unix_epoch.into(),
+                })
+            }
+            _ => break,
+        }
+    }
+    unreachable!("the committer is always present in commits that decode successfully")
+}
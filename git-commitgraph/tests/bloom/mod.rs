@@ -0,0 +1,132 @@
+use std::collections::BTreeSet;
+
+use bstr::{BStr, BString, ByteSlice};
+use git_commitgraph::{bloom, Graph};
+
+use crate::{hex_to_id, make_readonly_repo};
+
+/// Return each commit of the baseline along with the paths it changed compared to its first parent.
+fn baseline() -> (Graph, Vec<(git_hash::ObjectId, Vec<BString>)>) {
+    let repo_dir = make_readonly_repo("changed_paths.sh");
+    let graph = Graph::from_info_dir(repo_dir.join(".git").join("objects").join("info")).unwrap();
+    let input = std::fs::read(repo_dir.join("changed-paths.baseline")).unwrap();
+    let commits = input
+        .split_str("\n\n")
+        .filter(|block| !block.is_empty())
+        .map(|block| {
+            let mut lines = block.lines();
+            let id = hex_to_id(lines.next().expect("commit id"));
+            (id, lines.map(|line| line.as_bstr().to_owned()).collect())
+        })
+        .collect();
+    (graph, commits)
+}
+
+/// All files and directories that were ever changed, along with some which never existed.
+fn all_paths(commits: &[(git_hash::ObjectId, Vec<BString>)]) -> BTreeSet<BString> {
+    let mut out = BTreeSet::new();
+    for path in commits.iter().flat_map(|(_, paths)| paths) {
+        out.insert(path.clone());
+        for pos in path.find_iter("/") {
+            out.insert(path[..pos].into());
+        }
+    }
+    out.extend(
+        ["does-not-exist", "dir/does-not-exist", "ümlaut-dir/ǔ"]
+            .iter()
+            .map(|p| BString::from(*p)),
+    );
+    out
+}
+
+fn is_changed(path: &BStr, changes: &[BString]) -> bool {
+    changes
+        .iter()
+        .any(|changed| changed == path || (changed.starts_with(path) && changed[path.len()..].starts_with(b"/")))
+}
+
+#[test]
+fn changed_paths_are_always_possibly_contained_and_most_others_are_not() {
+    let (graph, commits) = baseline();
+    let settings = graph.bloom_filter_settings().expect("written with --changed-paths");
+    assert_eq!(
+        settings,
+        bloom::Settings {
+            hash_version: 1,
+            num_hashes: 7,
+            bits_per_entry: 10,
+        },
+        "git's defaults"
+    );
+
+    let paths = all_paths(&commits);
+    let (mut false_positives, mut negatives) = (0, 0);
+    for (id, changes) in &commits {
+        for path in &paths {
+            let path = path.as_bstr();
+            let maybe_changed = graph.maybe_changed_path(id, path).expect("every commit has a filter");
+            if is_changed(path, changes) {
+                assert!(maybe_changed, "{} changed {:?}", id, path);
+            } else if maybe_changed {
+                false_positives += 1;
+            } else {
+                negatives += 1;
+            }
+        }
+    }
+    assert!(
+        false_positives * 10 < negatives,
+        "only a few unchanged paths may be reported as changed, got {} false positives and {} negatives",
+        false_positives,
+        negatives
+    );
+}
+
+#[test]
+fn filters_with_too_many_changes_contain_everything_and_commits_without_changes_nothing() {
+    let (graph, commits) = baseline();
+    let settings = graph.bloom_filter_settings().expect("present");
+    let paths = all_paths(&commits);
+    for (id, changes) in &commits {
+        let filter = graph.commit_by_id(id).unwrap().bloom_filter().expect("present");
+        let expected = match changes.len() {
+            0 => false,
+            len if len > 512 => true,
+            _ => continue,
+        };
+        for path in &paths {
+            let keys = bloom::Key::for_path_and_leading_directories(path.as_bstr(), settings);
+            assert_eq!(filter.contains_all(&keys), expected, "{:?} in {}", path, id);
+        }
+        assert!(
+            filter.contains_all(&bloom::Key::for_path_and_leading_directories("".into(), settings)),
+            "the empty path matches everything and has no keys"
+        );
+    }
+}
+
+#[test]
+fn keys_for_paths_include_leading_directories() {
+    let settings = bloom::Settings {
+        hash_version: 1,
+        num_hashes: 7,
+        bits_per_entry: 10,
+    };
+    let keys = |path: &str| bloom::Key::for_path_and_leading_directories(path.into(), settings);
+    assert_eq!(
+        keys("a/b/c"),
+        [
+            bloom::Key::new("a/b/c".into(), settings),
+            bloom::Key::new("a/b".into(), settings),
+            bloom::Key::new("a".into(), settings)
+        ]
+    );
+    assert_eq!(keys("a/b//"), keys("a/b"), "trailing slashes are ignored");
+    assert!(keys("").is_empty());
+    assert!(keys("/").is_empty());
+    assert_eq!(
+        bloom::Key::new("a".into(), settings).settings(),
+        settings,
+        "keys remember their settings"
+    );
+}
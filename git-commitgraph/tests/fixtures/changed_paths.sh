@@ -0,0 +1,43 @@
+#!/bin/bash
+set -eu -o pipefail
+
+function write_and_commit() {
+  for path in "$@"; do
+    mkdir -p "$(dirname "$path")"
+    echo "$path" >> "$path"
+  done
+  git add -A
+  git commit -q -m "${*:1:3}"
+}
+
+git init -q
+git config core.quotePath false
+
+git checkout -q -b main
+write_and_commit a b dir/x dir/sub/y "ümlaut-dir/ǎ"
+write_and_commit a
+write_and_commit dir/sub/y
+git rm -q b && write_and_commit c
+write_and_commit "ümlaut-dir/ǎ"
+
+git checkout -q -b side
+write_and_commit side/file dir/x
+git checkout -q main
+write_and_commit dir/sub/z
+git merge -q --no-ff -m merge side
+
+write_and_commit $(seq -f "many/file-%g" 600)
+git commit -q --allow-empty -m empty
+
+git commit-graph write --no-progress --reachable --changed-paths
+git repack -adq
+
+for commit in $(git rev-list --all); do
+  echo "$commit"
+  if git rev-parse -q --verify "$commit^1" >/dev/null; then
+    git diff-tree --name-only -r "$commit^1" "$commit"
+  else
+    git diff-tree --no-commit-id --name-only -r --root "$commit"
+  fi
+  echo
+done > changed-paths.baseline
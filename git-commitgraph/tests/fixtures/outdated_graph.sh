@@ -0,0 +1,14 @@
+#!/bin/bash
+set -eu -o pipefail
+
+git init -q
+
+git checkout -q -b main
+git commit -q --allow-empty -m c1
+git commit -q --allow-empty -m c2
+git commit-graph write --no-progress --reachable
+
+git commit -q --amend --allow-empty -m c2-amended
+git reflog expire --expire=now --all
+git repack -adq
+git prune --expire=now
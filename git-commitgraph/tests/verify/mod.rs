@@ -0,0 +1,118 @@
+use std::sync::{atomic::AtomicBool, Arc};
+
+use git_commitgraph::{graph::verify::objects::Issue, Graph};
+use git_odb::pack::FindExt;
+
+use crate::make_readonly_repo;
+
+fn graph_and_odb(script: &str) -> (Graph, git_odb::HandleArc) {
+    let repo_dir = make_readonly_repo(script);
+    let objects_dir = repo_dir.join(".git").join("objects");
+    let store = git_odb::Store::at_opts(objects_dir.clone(), Vec::new(), Default::default()).unwrap();
+    (
+        Graph::from_info_dir(objects_dir.join("info")).unwrap(),
+        Arc::new(store).to_cache_arc(),
+    )
+}
+
+#[test]
+fn graphs_match_the_commits_they_were_created_from() {
+    for script in [
+        "single_commit.sh",
+        "two_parents.sh",
+        "octopus_merges.sh",
+        "changed_paths.sh",
+    ] {
+        let (graph, odb) = graph_and_odb(script);
+        for thread_limit in [Some(1), None] {
+            let outcome = graph
+                .verify_objects(
+                    {
+                        let odb = odb.clone();
+                        move |id, buf| odb.find_commit_iter(id, buf).ok().map(|t| t.0)
+                    },
+                    thread_limit,
+                    &AtomicBool::default(),
+                )
+                .unwrap();
+            assert_eq!(outcome.num_commits, graph.num_commits());
+            assert!(outcome.issues.is_empty(), "{}: {:?}", script, outcome.issues);
+        }
+    }
+}
+
+#[test]
+fn graphs_referring_to_commits_that_do_not_exist_anymore_are_outdated() {
+    let (graph, odb) = graph_and_odb("outdated_graph.sh");
+    let outcome = graph
+        .verify_objects(
+            move |id, buf| odb.find_commit_iter(id, buf).ok().map(|t| t.0),
+            None,
+            &AtomicBool::default(),
+        )
+        .unwrap();
+    assert_eq!(outcome.num_commits, 2);
+    assert_eq!(outcome.issues.len(), 1, "the amended commit is gone");
+    let issue = &outcome.issues[0];
+    assert!(matches!(issue, Issue::MissingCommit { .. }));
+    assert!(issue.suggestion().contains("git commit-graph write --reachable"));
+}
+
+#[test]
+fn mismatching_commit_data_is_reported_for_each_commit() {
+    let (graph, odb) = graph_and_odb("two_parents.sh");
+    let ids: Vec<_> = graph.iter_ids().map(ToOwned::to_owned).collect();
+    let outcome = graph
+        .verify_objects(
+            // Pretend each commit has the data of the next one.
+            move |id, buf| {
+                let idx = ids
+                    .iter()
+                    .position(|candidate| candidate.as_ref() == id)
+                    .expect("known");
+                odb.find_commit_iter(ids[(idx + 1) % ids.len()], buf).ok().map(|t| t.0)
+            },
+            None,
+            &AtomicBool::default(),
+        )
+        .unwrap();
+    assert_eq!(outcome.num_commits, 3);
+    let mut issue_ids: Vec<_> = outcome.issues.iter().map(|issue| issue.id().to_owned()).collect();
+    assert!(
+        issue_ids.windows(2).all(|ids| ids[0] <= ids[1]),
+        "issues are sorted by commit id"
+    );
+    issue_ids.dedup();
+    assert_eq!(
+        issue_ids.len(),
+        2,
+        "the two root commits only differ in their message, which isn't part of the graph"
+    );
+    assert!(
+        outcome
+            .issues
+            .iter()
+            .any(|issue| matches!(issue, Issue::Parents { .. })),
+        "parents differ as one of the commits is a merge"
+    );
+    assert!(outcome.issues.iter().all(|issue| !matches!(
+        issue,
+        Issue::MissingCommit { .. } | Issue::Lookup { .. } | Issue::Generation { .. }
+    )));
+}
+
+#[test]
+fn verification_can_be_interrupted() {
+    let (graph, odb) = graph_and_odb("two_parents.sh");
+    let err = graph
+        .verify_objects(
+            move |id, buf| odb.find_commit_iter(id, buf).ok().map(|t| t.0),
+            None,
+            &AtomicBool::new(true),
+        )
+        .unwrap_err();
+    assert!(matches!(
+        err,
+        git_commitgraph::graph::verify::objects::Error::Interrupted
+    ));
+}
@@ -0,0 +1,353 @@
+use std::{
+    ffi::OsString,
+    io::{Read, Write},
+    os::unix::net::{UnixListener, UnixStream},
+    path::{Path, PathBuf},
+    process::Stdio,
+    time::{Duration, Instant},
+};
+
+use bstr::ByteSlice;
+
+use crate::{program::main::Action, protocol::Context};
+
+/// The error returned by [`Client`] and [`Daemon`] methods.
+#[derive(Debug, thiserror::Error)]
+#[allow(missing_docs)]
+pub enum Error {
+    #[error("Could not communicate through the credential cache socket at '{}'", .path.display())]
+    Io { source: std::io::Error, path: PathBuf },
+    #[error("The permissions on the socket directory at '{}' are too loose, other users might access cached credentials", .path.display())]
+    InsecureDirectory { path: PathBuf },
+    #[error("The socket path '{}' has no parent directory", .path.display())]
+    NoSocketDirectory { path: PathBuf },
+    #[error("Could not start the credential cache daemon {:?}", .command)]
+    SpawnDaemon { source: std::io::Error, command: OsString },
+    #[error("The credential cache daemon {command:?} did not start: {message:?}")]
+    DaemonStart { command: OsString, message: bstr::BString },
+    #[error(transparent)]
+    ContextDecode(#[from] crate::protocol::context::decode::Error),
+    #[error(transparent)]
+    UrlParse(#[from] git_url::parse::Error),
+}
+
+/// The location of the socket used by `git credential-cache` by default, which is `~/.git-credential-cache/socket` if
+/// the `~/.git-credential-cache` directory exists, or `$XDG_CACHE_HOME/git/credential/socket` otherwise,
+/// with `XDG_CACHE_HOME` defaulting to `~/.cache`.
+///
+/// Returns `None` if the home directory isn't known.
+pub fn default_socket_path() -> Option<PathBuf> {
+    let home = std::env::var_os("HOME").map(PathBuf::from);
+    if let Some(dir) = home.as_ref().map(|home| home.join(".git-credential-cache")) {
+        if dir.is_dir() {
+            return Some(dir.join("socket"));
+        }
+    }
+    std::env::var_os("XDG_CACHE_HOME")
+        .filter(|dir| !dir.is_empty())
+        .map(PathBuf::from)
+        .or_else(|| home.map(|home| home.join(".cache")))
+        .map(|dir| dir.join("git").join("credential").join("socket"))
+}
+
+/// A pure-rust implementation of `git credential-cache`, which talks to a [`Daemon`] listening on a unix socket
+/// to keep credentials in memory for a limited time.
+///
+/// It interoperates with the daemon started by `git credential-cache`, and vice versa.
+///
+/// Use it with [`program::main()`][crate::program::main()] to implement a helper program,
+/// or call its methods directly.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Client {
+    /// The path to the socket the daemon listens on.
+    pub socket: PathBuf,
+    /// The time for which stored credentials are kept, which defaults to 900 seconds.
+    pub timeout: Duration,
+    /// The program to start if no daemon is listening on [`socket`][Client::socket] yet, which defaults to
+    /// `git credential-cache--daemon`. It's run with a shell and receives the socket path as its only argument, and is expected
+    /// to print `ok` once it listens, and to stop on its own once it has no credentials left.
+    pub daemon: OsString,
+}
+
+/// Init
+impl Client {
+    /// Create a new instance to talk to the daemon listening on `socket`.
+    pub fn at(socket: impl Into<PathBuf>) -> Self {
+        Client {
+            socket: socket.into(),
+            timeout: Duration::from_secs(900),
+            daemon: "git credential-cache--daemon".into(),
+        }
+    }
+
+    /// Use the [default socket path][default_socket_path()] like `git credential-cache` does if `--socket` isn't given,
+    /// or `None` if it isn't known.
+    pub fn from_env() -> Option<Self> {
+        default_socket_path().map(Self::at)
+    }
+}
+
+/// Access
+impl Client {
+    /// Perform `action` with `ctx`, which is suitable for use in the callback of [`program::main()`][crate::program::main()].
+    pub fn action(&self, action: Action, ctx: Context) -> Result<Option<Context>, Error> {
+        match action {
+            Action::Get => self.get(&ctx),
+            Action::Store => self.store(&ctx).map(|_| None),
+            Action::Erase => self.erase(&ctx).map(|_| None),
+        }
+    }
+
+    /// Return the credentials matching `query` if the daemon has them, or `None` if it doesn't or if no daemon is running.
+    pub fn get(&self, query: &Context) -> Result<Option<Context>, Error> {
+        let response = match self.request("get", query)? {
+            Some(response) => response,
+            None => return Ok(None),
+        };
+        let credentials = Context::from_bytes(&response)?;
+        Ok((credentials.username.is_some() || credentials.password.is_some()).then(|| credentials))
+    }
+
+    /// Have the daemon keep `credentials` for [`timeout`][Client::timeout], replacing those for the same protocol, host,
+    /// path and username.
+    ///
+    /// If no daemon is listening on our socket yet, the [`daemon`][Client::daemon] program is started like
+    /// `git credential-cache` does, as process that outlives ours and that stops once all credentials it keeps expired.
+    pub fn store(&self, credentials: &Context) -> Result<(), Error> {
+        if self.request("store", credentials)?.is_none() {
+            self.spawn_daemon()?;
+            self.request("store", credentials)?;
+        }
+        Ok(())
+    }
+
+    /// Have the daemon forget all credentials matching `query`, which does nothing if no daemon is running.
+    pub fn erase(&self, query: &Context) -> Result<(), Error> {
+        self.request("erase", query).map(|_| ())
+    }
+
+    /// Stop the daemon listening on our socket, like `git credential-cache exit`, which does nothing if no daemon is running.
+    pub fn exit(&self) -> Result<(), Error> {
+        self.request("exit", &Context::default()).map(|_| ())
+    }
+
+    /// Start the daemon program and wait until it listens on our socket, without waiting for it to exit.
+    fn spawn_daemon(&self) -> Result<(), Error> {
+        let mut child = git_command::prepare(&self.daemon)
+            .with_shell()
+            .arg(&self.socket)
+            .stdin(Stdio::null())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::null())
+            .spawn()
+            .map_err(|source| Error::SpawnDaemon {
+                source,
+                command: self.daemon.clone(),
+            })?;
+        // Only read the first line as daemons don't necessarily close their standard output once they listen.
+        let mut message = Vec::new();
+        std::io::BufRead::read_until(
+            &mut std::io::BufReader::new(child.stdout.take().expect("configured").take(1024)),
+            b'\n',
+            &mut message,
+        )
+        .map_err(|source| Error::SpawnDaemon {
+            source,
+            command: self.daemon.clone(),
+        })?;
+        if message != b"ok\n" {
+            return Err(Error::DaemonStart {
+                command: self.daemon.clone(),
+                message: message.into(),
+            });
+        }
+        Ok(())
+    }
+
+    /// Send `action` with `ctx` to the daemon and return its response, or `None` if no daemon is listening on our socket.
+    fn request(&self, action: &str, ctx: &Context) -> Result<Option<Vec<u8>>, Error> {
+        let io_err = |source| Error::Io {
+            source,
+            path: self.socket.clone(),
+        };
+        let ctx = crate::store::normalize(ctx)?;
+        let mut stream = match UnixStream::connect(&self.socket) {
+            Ok(stream) => stream,
+            Err(err)
+                if matches!(
+                    err.kind(),
+                    std::io::ErrorKind::NotFound | std::io::ErrorKind::ConnectionRefused
+                ) =>
+            {
+                return Ok(None)
+            }
+            Err(err) => return Err(io_err(err)),
+        };
+        let mut request = format!("action={}\ntimeout={}\n", action, self.timeout.as_secs()).into_bytes();
+        Context { url: None, ..ctx }.write_to(&mut request).map_err(io_err)?;
+        stream.write_all(&request).map_err(io_err)?;
+        stream.shutdown(std::net::Shutdown::Write).map_err(io_err)?;
+        let mut response = Vec::new();
+        stream.read_to_end(&mut response).map_err(io_err)?;
+        Ok(Some(response))
+    }
+}
+
+/// A pure-rust implementation of `git credential-cache--daemon`, which serves credentials on a unix socket
+/// to [`Client`]s and `git credential-cache` alike.
+#[derive(Debug)]
+pub struct Daemon {
+    socket: PathBuf,
+    listener: UnixListener,
+    entries: Vec<(Context, Instant)>,
+    /// The time to wait for credentials to be stored while there are none before stopping, which defaults to 30 seconds.
+    pub idle_timeout: Duration,
+}
+
+/// Init
+impl Daemon {
+    /// Listen on `socket`, replacing a stale socket at this location, after creating its parent directory with permissions
+    /// that only allow access by the current user.
+    ///
+    /// Fails if the parent directory already exists but can be accessed by other users.
+    pub fn bind(socket: impl Into<PathBuf>) -> Result<Self, Error> {
+        use std::os::unix::fs::{DirBuilderExt, PermissionsExt};
+
+        let socket = socket.into();
+        let io_err = |source, path: &Path| Error::Io {
+            source,
+            path: path.to_owned(),
+        };
+        let dir = socket
+            .parent()
+            .filter(|dir| !dir.as_os_str().is_empty())
+            .ok_or_else(|| Error::NoSocketDirectory { path: socket.clone() })?;
+        std::fs::DirBuilder::new()
+            .recursive(true)
+            .mode(0o700)
+            .create(dir)
+            .map_err(|err| io_err(err, dir))?;
+        let mode = std::fs::metadata(dir)
+            .map_err(|err| io_err(err, dir))?
+            .permissions()
+            .mode();
+        if mode & 0o077 != 0 {
+            return Err(Error::InsecureDirectory { path: dir.to_owned() });
+        }
+        match std::fs::remove_file(&socket) {
+            Ok(()) => {}
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => {}
+            Err(err) => return Err(io_err(err, &socket)),
+        }
+        let listener = UnixListener::bind(&socket).map_err(|err| io_err(err, &socket))?;
+        Ok(Daemon {
+            socket,
+            listener,
+            entries: Vec::new(),
+            idle_timeout: Duration::from_secs(30),
+        })
+    }
+}
+
+/// Serving
+impl Daemon {
+    /// Serve clients until asked to exit, or until no credentials were stored for [`idle_timeout`][Daemon::idle_timeout]
+    /// after all of them expired, and remove the socket.
+    pub fn serve(mut self) -> Result<(), Error> {
+        let res = self.serve_until_done();
+        std::fs::remove_file(&self.socket).ok();
+        res
+    }
+
+    fn serve_until_done(&mut self) -> Result<(), Error> {
+        let io_err = |source, path: &Path| Error::Io {
+            source,
+            path: path.to_owned(),
+        };
+        self.listener
+            .set_nonblocking(true)
+            .map_err(|err| io_err(err, &self.socket))?;
+        let mut idle_since = Instant::now();
+        loop {
+            let now = Instant::now();
+            self.entries.retain(|(_, expires_at)| *expires_at > now);
+            if !self.entries.is_empty() {
+                idle_since = now;
+            } else if now.duration_since(idle_since) >= self.idle_timeout {
+                return Ok(());
+            }
+            match self.listener.accept() {
+                Ok((stream, _addr)) => {
+                    // Clients that misbehave don't affect the service of others.
+                    if let Ok(Continue::No) = self.handle(stream) {
+                        return Ok(());
+                    }
+                }
+                Err(err) if err.kind() == std::io::ErrorKind::WouldBlock => {
+                    std::thread::sleep(Duration::from_millis(100))
+                }
+                Err(err) => return Err(io_err(err, &self.socket)),
+            }
+        }
+    }
+
+    fn handle(&mut self, mut stream: UnixStream) -> std::io::Result<Continue> {
+        stream.set_nonblocking(false)?;
+        stream.set_read_timeout(Some(Duration::from_secs(1)))?;
+        let mut request = Vec::new();
+        stream.read_to_end(&mut request)?;
+
+        let mut action = None;
+        let mut timeout = None;
+        for line in request.lines().take_while(|line| !line.is_empty()) {
+            if let Some(value) = line.strip_prefix(b"action=") {
+                action = Some(value);
+            } else if let Some(value) = line.strip_prefix(b"timeout=") {
+                timeout = value.to_str().ok().and_then(|value| value.parse::<u64>().ok());
+            }
+        }
+        let ctx = Context::from_bytes(&request).map_err(|err| std::io::Error::new(std::io::ErrorKind::Other, err))?;
+        match action {
+            Some(b"get") => {
+                if let Some((credentials, _)) = self.entries.iter().find(|(entry, _)| ctx.matches(entry)) {
+                    let response = Context {
+                        username: credentials.username.clone(),
+                        password: credentials.password.clone(),
+                        password_expiry_utc: credentials.password_expiry_utc,
+                        oauth_refresh_token: credentials.oauth_refresh_token.clone(),
+                        ..Default::default()
+                    };
+                    response.write_to(&mut stream)?;
+                }
+            }
+            Some(b"store") => {
+                let timeout = timeout.ok_or_else(|| {
+                    std::io::Error::new(std::io::ErrorKind::Other, "a timeout is required to store credentials")
+                })?;
+                if crate::store::is_complete(&ctx) {
+                    let query = Context {
+                        password: None,
+                        ..ctx.clone()
+                    };
+                    self.entries.retain(|(entry, _)| !query.matches(entry));
+                    self.entries.push((
+                        Context {
+                            wwwauth: Vec::new(),
+                            ..ctx
+                        },
+                        Instant::now() + Duration::from_secs(timeout),
+                    ));
+                }
+            }
+            Some(b"erase") => self.entries.retain(|(entry, _)| !ctx.matches(entry)),
+            Some(b"exit") => return Ok(Continue::No),
+            _ => {}
+        }
+        Ok(Continue::Yes)
+    }
+}
+
+enum Continue {
+    Yes,
+    No,
+}
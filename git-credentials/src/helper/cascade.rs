@@ -91,6 +91,20 @@ impl Cascade {
                             dst_ctx.url = Some(src);
                             url = dst_ctx.destructure_url_in_place(self.use_http_path)?.url.take();
                         }
+                        if ctx.password_expiry_utc.is_some() {
+                            dst_ctx.password_expiry_utc = ctx.password_expiry_utc;
+                        }
+                        if let Some(src) = ctx.oauth_refresh_token {
+                            dst_ctx.oauth_refresh_token = Some(src);
+                        }
+                        if dst_ctx
+                            .password_expiry_utc
+                            .map_or(false, |expiry| expiry < seconds_since_unix_epoch())
+                        {
+                            // like git, ignore expired passwords to have them provided by the next helper
+                            dst_ctx.password = None;
+                            dst_ctx.password_expiry_utc = None;
+                        }
                         if dst_ctx.username.is_some() && dst_ctx.password.is_some() {
                             break;
                         }
@@ -137,9 +151,18 @@ impl Cascade {
                 username: ctx.username.clone(),
                 password: ctx.password.clone(),
                 quit: ctx.quit.unwrap_or(false),
+                password_expiry_utc: ctx.password_expiry_utc,
+                oauth_refresh_token: ctx.oauth_refresh_token.clone(),
                 next: ctx.to_owned().into(),
             }),
             action,
         )
     }
 }
+
+fn seconds_since_unix_epoch() -> u64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or_default()
+}
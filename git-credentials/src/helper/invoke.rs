@@ -30,6 +30,8 @@ pub fn invoke(helper: &mut crate::Program, action: &Action) -> Result {
                 username: ctx.username,
                 password: ctx.password,
                 quit: ctx.quit.unwrap_or(false),
+                password_expiry_utc: ctx.password_expiry_utc,
+                oauth_refresh_token: ctx.oauth_refresh_token,
                 next: NextAction {
                     previous_output: stdout.into(),
                 },
@@ -25,6 +25,10 @@ pub struct Outcome {
     pub password: Option<String>,
     /// If set, the helper asked to stop the entire process, whether the identity is complete or not.
     pub quit: bool,
+    /// The time at which the password expires, in seconds since the unix epoch, if set.
+    pub password_expiry_utc: Option<u64>,
+    /// An OAuth refresh token to store along with the password, if set.
+    pub oauth_refresh_token: Option<String>,
     /// A handle to the action to perform next in another call to [`helper::invoke()`][crate::helper::invoke()].
     pub next: NextAction,
 }
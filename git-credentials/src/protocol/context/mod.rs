@@ -34,6 +34,17 @@ mod access {
             }
             buf.into()
         }
+        /// Return true if all fields of ours that identify credentials, i.e. protocol, host, path and username,
+        /// are unset or equal to the ones in `other`.
+        pub(crate) fn matches(&self, other: &Context) -> bool {
+            fn field<T: PartialEq>(ours: &Option<T>, theirs: &Option<T>) -> bool {
+                ours.is_none() || ours == theirs
+            }
+            field(&self.protocol, &other.protocol)
+                && field(&self.host, &other.host)
+                && field(&self.path, &other.path)
+                && field(&self.username, &other.username)
+        }
         /// Compute a prompt to obtain the given value.
         pub fn to_prompt(&self, field: &str) -> String {
             match self.to_url() {
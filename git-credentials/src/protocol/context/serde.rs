@@ -24,11 +24,14 @@ mod write {
                     write_key(&mut out, key, value.as_ref())?;
                 }
             }
+            let password_expiry_utc = self.password_expiry_utc.map(|time| time.to_string());
             for (key, value) in [
                 ("protocol", &self.protocol),
                 ("host", &self.host),
                 ("username", &self.username),
                 ("password", &self.password),
+                ("password_expiry_utc", &password_expiry_utc),
+                ("oauth_refresh_token", &self.oauth_refresh_token),
             ] {
                 if let Some(value) = value {
                     validate(key, value.as_str().into())
@@ -36,6 +39,11 @@ mod write {
                     write_key(&mut out, key, value.as_bytes().as_bstr())?;
                 }
             }
+            for value in &self.wwwauth {
+                validate("wwwauth[]", value.as_str().into())
+                    .map_err(|err| std::io::Error::new(std::io::ErrorKind::Other, err))?;
+                write_key(&mut out, "wwwauth[]", value.as_bytes().as_bstr())?;
+            }
             Ok(())
         }
 
@@ -83,19 +91,32 @@ pub mod decode {
             }) {
                 let (key, value) = res?;
                 match key {
-                    "protocol" | "host" | "username" | "password" => {
+                    "protocol" | "host" | "username" | "password" | "oauth_refresh_token" | "wwwauth[]" => {
                         if !value.is_utf8() {
                             return Err(Error::IllformedUtf8InValue { key: key.into(), value });
                         }
                         let value = value.to_string();
+                        if key == "wwwauth[]" {
+                            // an empty value clears the list, like in git
+                            if value.is_empty() {
+                                ctx.wwwauth.clear();
+                            } else {
+                                ctx.wwwauth.push(value);
+                            }
+                            continue;
+                        }
                         *match key {
                             "protocol" => &mut ctx.protocol,
                             "host" => &mut ctx.host,
                             "username" => &mut ctx.username,
                             "password" => &mut ctx.password,
+                            "oauth_refresh_token" => &mut ctx.oauth_refresh_token,
                             _ => unreachable!("checked field names in match above"),
                         } = Some(value);
                     }
+                    "password_expiry_utc" => {
+                        ctx.password_expiry_utc = value.to_str().ok().and_then(|value| value.parse().ok());
+                    }
                     "url" => ctx.url = Some(value),
                     "path" => ctx.path = Some(value),
                     "quit" => {
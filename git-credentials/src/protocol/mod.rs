@@ -7,6 +7,10 @@ use crate::helper;
 pub struct Outcome {
     /// The identity provide by the helper.
     pub identity: git_sec::identity::Account,
+    /// The time at which the password expires, in seconds since the unix epoch, if the helper provided it.
+    pub password_expiry_utc: Option<u64>,
+    /// An OAuth refresh token that helpers can use to obtain a new password once it expired, if the helper provided it.
+    pub oauth_refresh_token: Option<String>,
     /// A handle to the action to perform next in another call to [`helper::invoke()`][crate::helper::invoke()].
     pub next: helper::NextAction,
 }
@@ -54,6 +58,14 @@ pub struct Context {
     pub url: Option<BString>,
     /// If true, the caller should stop asking for credentials immediately without calling more credential helpers in the chain.
     pub quit: Option<bool>,
+    /// The time at which `password` expires, in seconds since the unix epoch, as reported by the helper that provided it.
+    pub password_expiry_utc: Option<u64>,
+    /// An OAuth refresh token that may accompany a `password` which is an OAuth access token, to be stored along with it
+    /// so that helpers can obtain a new access token once it expired.
+    pub oauth_refresh_token: Option<String>,
+    /// The values of all `WWW-Authenticate` headers the server responded with, in order, which helpers may use to
+    /// determine how to authenticate.
+    pub wwwauth: Vec<String>,
 }
 
 /// Convert the outcome of a helper invocation to a helper result, assuring that the identity is complete in the process.
@@ -70,6 +82,8 @@ pub fn helper_outcome_to_result(outcome: Option<helper::Outcome>, action: helper
         (helper::Action::Get(ctx), Some(mut outcome)) => match outcome.consume_identity() {
             Some(identity) => Ok(Some(Outcome {
                 identity,
+                password_expiry_utc: outcome.password_expiry_utc,
+                oauth_refresh_token: outcome.oauth_refresh_token,
                 next: outcome.next,
             })),
             None => Err(if outcome.quit {
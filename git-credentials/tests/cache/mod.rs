@@ -0,0 +1,176 @@
+use std::{io::Write, path::Path, process::Stdio, time::Duration};
+
+use git_credentials::{
+    cache::{Client, Daemon, Error},
+    protocol::Context,
+};
+
+fn credentials(host: &str, username: &str, password: &str) -> Context {
+    Context {
+        protocol: Some("https".into()),
+        host: Some(host.into()),
+        username: Some(username.into()),
+        password: Some(password.into()),
+        ..Default::default()
+    }
+}
+
+fn query(host: &str) -> Context {
+    Context {
+        protocol: Some("https".into()),
+        host: Some(host.into()),
+        ..Default::default()
+    }
+}
+
+fn git_cache(socket: &Path, action: &str, input: &str) -> crate::Result<String> {
+    let mut child = std::process::Command::new("git")
+        .args(["credential-cache", "--socket"])
+        .arg(socket)
+        .arg(action)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .spawn()?;
+    child.stdin.take().expect("configured").write_all(input.as_bytes())?;
+    let out = child.wait_with_output()?;
+    assert!(out.status.success());
+    Ok(String::from_utf8(out.stdout)?)
+}
+
+#[test]
+fn store_get_erase_and_exit() -> crate::Result {
+    let dir = git_testtools::tempfile::tempdir()?;
+    let client = Client::at(dir.path().join("cache").join("socket"));
+    assert_eq!(
+        client.get(&query("example.com"))?,
+        None,
+        "no daemon means no credentials"
+    );
+    client.erase(&query("example.com"))?;
+    client.exit()?;
+
+    let daemon = Daemon::bind(&client.socket)?;
+    let daemon = std::thread::spawn(move || daemon.serve());
+    let expected = credentials("example.com", "user", "pass");
+    client.store(&expected)?;
+    client.store(&credentials("other.example.com", "user", "other-pass"))?;
+    let actual = client.get(&query("example.com"))?.expect("present");
+    assert_eq!(actual.username, expected.username);
+    assert_eq!(actual.password, expected.password);
+    assert_eq!(
+        client.get(&Context {
+            url: Some("https://user@other.example.com/path".into()),
+            ..Default::default()
+        })?,
+        Some(Context {
+            username: Some("user".into()),
+            password: Some("other-pass".into()),
+            ..Default::default()
+        }),
+        "urls are used as queries as well"
+    );
+
+    client.erase(&query("example.com"))?;
+    assert_eq!(client.get(&query("example.com"))?, None);
+
+    client.exit()?;
+    daemon.join().expect("no panic")?;
+    assert!(!client.socket.exists(), "the socket is removed on exit");
+    assert_eq!(client.get(&query("other.example.com"))?, None);
+    Ok(())
+}
+
+#[test]
+fn store_starts_a_daemon_if_there_is_none() -> crate::Result {
+    let dir = git_testtools::tempfile::tempdir()?;
+    let client = Client::at(dir.path().join("cache").join("socket"));
+    client.store(&credentials("example.com", "user", "pass"))?;
+    assert!(client.get(&query("example.com"))?.is_some());
+    client.exit()?;
+    Ok(())
+}
+
+/// The environment variable to pass the socket to [`store_in_child_process()`] in.
+const CHILD_SOCKET: &str = "GIT_CREDENTIALS_TEST_CACHE_SOCKET";
+
+#[test]
+#[ignore = "run as child process by the daemon_started_by_store_outlives_its_process test"]
+fn store_in_child_process() -> crate::Result {
+    if let Some(socket) = std::env::var_os(CHILD_SOCKET) {
+        Client::at(socket).store(&credentials("example.com", "user", "pass"))?;
+    }
+    Ok(())
+}
+
+#[test]
+fn daemon_started_by_store_outlives_its_process() -> crate::Result {
+    let dir = git_testtools::tempfile::tempdir()?;
+    let client = Client::at(dir.path().join("cache").join("socket"));
+    let out = std::process::Command::new(std::env::current_exe()?)
+        .args(["--exact", "cache::store_in_child_process", "--ignored"])
+        .env(CHILD_SOCKET, &client.socket)
+        .output()?;
+    assert!(out.status.success(), "{}", String::from_utf8_lossy(&out.stderr));
+    let actual = client.get(&query("example.com"))?.expect("still present");
+    assert_eq!(actual.password.as_deref(), Some("pass"));
+    client.exit()?;
+    Ok(())
+}
+
+#[test]
+fn credentials_expire_and_the_daemon_stops_once_idle() -> crate::Result {
+    let dir = git_testtools::tempfile::tempdir()?;
+    let mut client = Client::at(dir.path().join("cache").join("socket"));
+    client.timeout = Duration::from_secs(0);
+    let mut daemon = Daemon::bind(&client.socket)?;
+    daemon.idle_timeout = Duration::from_millis(500);
+    let daemon = std::thread::spawn(move || daemon.serve());
+
+    client.store(&credentials("example.com", "user", "pass"))?;
+    assert_eq!(client.get(&query("example.com"))?, None, "expired immediately");
+    daemon.join().expect("no panic")?;
+    assert!(!client.socket.exists());
+    Ok(())
+}
+
+#[test]
+#[cfg(unix)]
+fn socket_directories_must_not_be_accessible_by_others() -> crate::Result {
+    use std::os::unix::fs::PermissionsExt;
+    let dir = git_testtools::tempfile::tempdir()?;
+    let socket_dir = dir.path().join("shared");
+    std::fs::create_dir(&socket_dir)?;
+    std::fs::set_permissions(&socket_dir, std::fs::Permissions::from_mode(0o755))?;
+    assert!(matches!(
+        Daemon::bind(socket_dir.join("socket")),
+        Err(Error::InsecureDirectory { path }) if path == socket_dir
+    ));
+    Ok(())
+}
+
+#[test]
+fn interoperates_with_git_credential_cache() -> crate::Result {
+    let dir = git_testtools::tempfile::tempdir()?;
+    let client = Client::at(dir.path().join("ours").join("socket"));
+    let daemon = Daemon::bind(&client.socket)?;
+    let daemon = std::thread::spawn(move || daemon.serve());
+    client.store(&credentials("example.com", "user", "pass"))?;
+    assert_eq!(
+        git_cache(&client.socket, "get", "protocol=https\nhost=example.com\n")?,
+        "username=user\npassword=pass\n",
+        "git can use our daemon"
+    );
+    git_cache(&client.socket, "exit", "")?;
+    daemon.join().expect("no panic")?;
+
+    let client = Client::at(dir.path().join("theirs").join("socket"));
+    git_cache(
+        &client.socket,
+        "store",
+        "protocol=https\nhost=example.com\nusername=user\npassword=pass\n",
+    )?;
+    let actual = client.get(&query("example.com"))?.expect("present");
+    assert_eq!(actual.password.as_deref(), Some("pass"), "we can use gits daemon");
+    client.exit()?;
+    Ok(())
+}
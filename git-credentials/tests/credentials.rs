@@ -1,5 +1,8 @@
 pub use git_testtools::Result;
 
+#[cfg(unix)]
+mod cache;
 mod helper;
 mod program;
 mod protocol;
+mod store;
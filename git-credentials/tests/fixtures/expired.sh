@@ -0,0 +1,7 @@
+#!/bin/bash
+set -eu
+
+test "$1" = get && \
+echo username=user-expired && \
+echo password=expired && \
+echo password_expiry_utc=1
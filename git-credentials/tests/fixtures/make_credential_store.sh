@@ -0,0 +1,28 @@
+#!/bin/bash
+set -eu -o pipefail
+
+function store() {
+  git credential-store --file=credentials store
+}
+
+store <<EOF2
+protocol=https
+host=example.com
+username=first
+password=first-pass
+EOF2
+
+store <<EOF2
+protocol=https
+host=example.com:8080
+path=org/repo.git
+username=user name
+password=p@ss:word/%
+EOF2
+
+store <<EOF2
+protocol=ssh
+host=other.example.com
+username=ßönderzeichen
+password=🔑
+EOF2
@@ -0,0 +1,8 @@
+#!/bin/bash
+set -eu
+
+test "$1" = get && \
+echo username=user-oauth && \
+echo password=access-token && \
+echo password_expiry_utc=4102444800 && \
+echo oauth_refresh_token=refresh-token
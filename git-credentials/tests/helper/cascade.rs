@@ -113,6 +113,36 @@ mod invoke {
         assert_eq!(actual.identity, identity("user", "pass"));
     }
 
+    #[test]
+    fn password_expiry_and_oauth_refresh_tokens_are_provided_and_stored() {
+        let actual = invoke_cascade(["oauth"], action_get()).unwrap().expect("credentials");
+        assert_eq!(actual.identity, identity("user-oauth", "access-token"));
+        assert_eq!(actual.password_expiry_utc, Some(4102444800));
+        assert_eq!(actual.oauth_refresh_token.as_deref(), Some("refresh-token"));
+
+        let ctx: Context = (&actual.next).try_into().unwrap();
+        assert_eq!(
+            ctx.password_expiry_utc,
+            Some(4102444800),
+            "helpers get to store them as well"
+        );
+        assert_eq!(ctx.oauth_refresh_token.as_deref(), Some("refresh-token"));
+    }
+
+    #[test]
+    fn expired_passwords_are_ignored() {
+        let actual = invoke_cascade(["expired", "custom-helper"], action_get())
+            .unwrap()
+            .expect("credentials");
+        assert_eq!(actual.identity, identity("user-script", "pass-script"));
+        assert_eq!(actual.password_expiry_utc, None);
+
+        assert!(matches!(
+            invoke_cascade(["expired"], action_get()).unwrap_err(),
+            protocol::Error::IdentityMissing { .. }
+        ));
+    }
+
     fn action_get() -> Action {
         Action::get_for_url("does/not/matter")
     }
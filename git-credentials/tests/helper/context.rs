@@ -12,6 +12,16 @@ fn encode_decode_roundtrip_works_only_for_serializing_fields() {
             url: Some("https://github.com/byron/gitoxide".into()),
             ..Default::default()
         },
+        Context {
+            protocol: Some("https".into()),
+            host: Some("github.com".into()),
+            username: Some("user".into()),
+            password: Some("oauth-access-token".into()),
+            password_expiry_utc: Some(1_700_000_000),
+            oauth_refresh_token: Some("refresh".into()),
+            wwwauth: vec!["Bearer realm=\"example.com\"".into(), "Basic realm=\"other\"".into()],
+            ..Default::default()
+        },
         Context::default(),
     ] {
         let mut buf = Vec::<u8>::new();
@@ -61,6 +71,28 @@ mod write_to {
 mod from_bytes {
     use git_credentials::protocol::Context;
 
+    #[test]
+    fn wwwauth_values_accumulate_and_are_cleared_by_empty_values() {
+        assert_eq!(
+            Context::from_bytes(b"wwwauth[]=ignored\nwwwauth[]=\nwwwauth[]=Basic\nwwwauth[]=Bearer").unwrap(),
+            Context {
+                wwwauth: vec!["Basic".into(), "Bearer".into()],
+                ..Default::default()
+            }
+        );
+    }
+
+    #[test]
+    fn invalid_password_expiry_is_ignored() {
+        assert_eq!(
+            Context::from_bytes(b"password_expiry_utc=soon\noauth_refresh_token=token").unwrap(),
+            Context {
+                oauth_refresh_token: Some("token".into()),
+                ..Default::default()
+            }
+        );
+    }
+
     #[test]
     fn empty_newlines_cause_skipping_remaining_input() {
         let input = b"protocol=https
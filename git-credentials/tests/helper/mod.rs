@@ -13,6 +13,8 @@ mod invoke_outcome_to_helper_result {
                 username: None,
                 password: None,
                 quit: false,
+                password_expiry_utc: None,
+                oauth_refresh_token: None,
                 next: protocol::Context::default().into(),
             }),
             action,
@@ -29,6 +31,8 @@ mod invoke_outcome_to_helper_result {
                 username: None,
                 password: None,
                 quit: true,
+                password_expiry_utc: None,
+                oauth_refresh_token: None,
                 next: protocol::Context::default().into(),
             }),
             action,
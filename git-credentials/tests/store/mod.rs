@@ -0,0 +1,184 @@
+use std::{io::Write, process::Stdio};
+
+use git_credentials::{program::main::Action, protocol::Context, store::Store};
+use git_testtools::scripted_fixture_repo_read_only;
+
+fn ctx(protocol: &str, host: &str) -> Context {
+    Context {
+        protocol: Some(protocol.into()),
+        host: Some(host.into()),
+        ..Default::default()
+    }
+}
+
+fn credentials(protocol: &str, host: &str, username: &str, password: &str) -> Context {
+    Context {
+        username: Some(username.into()),
+        password: Some(password.into()),
+        ..ctx(protocol, host)
+    }
+}
+
+fn git_store_get(store: &Store, query: &str) -> crate::Result<String> {
+    let mut child = std::process::Command::new("git")
+        .arg("credential-store")
+        .arg(format!("--file={}", store.files[0].display()))
+        .arg("get")
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .spawn()?;
+    child.stdin.take().expect("configured").write_all(query.as_bytes())?;
+    let out = child.wait_with_output()?;
+    assert!(out.status.success());
+    Ok(String::from_utf8(out.stdout)?)
+}
+
+mod get {
+    use git_credentials::{protocol::Context, store::Store};
+
+    use crate::store::{credentials, ctx};
+
+    fn store() -> crate::Result<Store> {
+        let dir = git_testtools::scripted_fixture_repo_read_only("make_credential_store.sh")?;
+        Ok(Store::at([dir.join("does-not-exist"), dir.join("credentials")]))
+    }
+
+    #[test]
+    fn credentials_written_by_git_are_found_by_protocol_and_host() -> crate::Result {
+        let store = store()?;
+        assert_eq!(
+            store.get(&ctx("https", "example.com"))?,
+            Some(credentials("https", "example.com", "first", "first-pass"))
+        );
+        assert_eq!(
+            store.get(&ctx("ssh", "other.example.com"))?,
+            Some(credentials("ssh", "other.example.com", "ßönderzeichen", "🔑")),
+            "everything is percent-decoded"
+        );
+        assert_eq!(store.get(&ctx("http", "example.com"))?, None, "protocols must match");
+        Ok(())
+    }
+
+    #[test]
+    fn paths_and_ports_must_match_if_given() -> crate::Result {
+        let store = store()?;
+        let expected = Context {
+            path: Some("org/repo.git".into()),
+            ..credentials("https", "example.com:8080", "user name", "p@ss:word/%")
+        };
+        let mut query = ctx("https", "example.com:8080");
+        assert_eq!(store.get(&query)?, Some(expected.clone()));
+        query.path = Some("org/repo.git".into());
+        assert_eq!(store.get(&query)?, Some(expected.clone()));
+        query.path = Some("org/other.git".into());
+        assert_eq!(store.get(&query)?, None);
+        assert_eq!(
+            store.get(&Context {
+                path: Some("org/repo.git".into()),
+                ..ctx("https", "example.com")
+            })?,
+            None,
+            "queries with paths don't match entries without one"
+        );
+        assert_eq!(
+            store.get(&Context {
+                url: Some("https://example.com:8080/org/repo.git/".into()),
+                ..Default::default()
+            })?,
+            Some(expected),
+            "urls are used if there is no protocol, but their http path is ignored like git does by default"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn usernames_must_match_if_given() -> crate::Result {
+        let store = store()?;
+        let mut query = ctx("https", "example.com");
+        query.username = Some("first".into());
+        assert!(store.get(&query)?.is_some());
+        query.username = Some("second".into());
+        assert_eq!(store.get(&query)?, None);
+        Ok(())
+    }
+
+    #[test]
+    fn missing_files_are_ignored() -> crate::Result {
+        let dir = git_testtools::tempfile::tempdir()?;
+        assert_eq!(
+            Store::at([dir.path().join("credentials")]).get(&ctx("https", "example.com"))?,
+            None
+        );
+        Ok(())
+    }
+}
+
+#[test]
+fn store_get_and_erase_round_trip_and_are_compatible_with_git() -> crate::Result {
+    let dir = git_testtools::tempfile::tempdir()?;
+    let store = Store::at([dir.path().join("credentials"), dir.path().join("other")]);
+    let first = credentials("https", "example.com:8080", "user name", "p@ss:word/%");
+
+    store.store(&first)?;
+    assert_eq!(store.get(&ctx("https", "example.com:8080"))?, Some(first.clone()));
+    assert_eq!(
+        git_store_get(&store, "protocol=https\nhost=example.com:8080\n")?,
+        "username=user name\npassword=p@ss:word/%\n",
+        "git can read what we write"
+    );
+    assert!(!dir.path().join("other").exists(), "only the first file is written");
+
+    let replacement = credentials("https", "example.com:8080", "user name", "new");
+    store.store(&replacement)?;
+    let other_user = credentials("https", "example.com:8080", "other", "pass");
+    store.store(&other_user)?;
+    assert_eq!(
+        std::fs::read_to_string(&store.files[0])?.lines().count(),
+        2,
+        "credentials of the same user are replaced"
+    );
+    assert_eq!(
+        store.get(&ctx("https", "example.com:8080"))?,
+        Some(other_user),
+        "the last stored credentials come first"
+    );
+
+    store.erase(&Context {
+        username: Some("other".into()),
+        ..ctx("https", "example.com:8080")
+    })?;
+    assert_eq!(store.get(&ctx("https", "example.com:8080"))?, Some(replacement));
+    store.erase(&ctx("https", "example.com:8080"))?;
+    assert_eq!(store.get(&ctx("https", "example.com:8080"))?, None);
+    assert_eq!(std::fs::read_to_string(&store.files[0])?, "");
+    Ok(())
+}
+
+#[test]
+fn credentials_are_stored_in_the_first_existing_file() -> crate::Result {
+    let dir = scripted_fixture_repo_read_only("make_credential_store.sh")?;
+    let tmp = git_testtools::tempfile::tempdir()?;
+    let existing = tmp.path().join("existing");
+    std::fs::copy(dir.join("credentials"), &existing)?;
+    let store = Store::at([tmp.path().join("missing"), existing.clone()]);
+
+    store.action(Action::Store, credentials("https", "new.example.com", "user", "pass"))?;
+    assert!(!store.files[0].exists());
+    assert_eq!(std::fs::read_to_string(&existing)?.lines().count(), 4);
+    assert_eq!(
+        store.action(Action::Get, ctx("https", "new.example.com"))?,
+        Some(credentials("https", "new.example.com", "user", "pass"))
+    );
+    Ok(())
+}
+
+#[test]
+fn incomplete_credentials_are_not_stored() -> crate::Result {
+    let dir = git_testtools::tempfile::tempdir()?;
+    let store = Store::at([dir.path().join("credentials")]);
+    let mut incomplete = credentials("https", "example.com", "user", "pass");
+    incomplete.password = None;
+    store.store(&incomplete)?;
+    assert!(!store.files[0].exists());
+    Ok(())
+}
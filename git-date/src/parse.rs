@@ -14,18 +14,35 @@ pub enum Error {
 pub(crate) mod function {
     use std::{convert::TryInto, str::FromStr, time::SystemTime};
 
-    use time::{Date, OffsetDateTime};
+    use time::{format_description::FormatItem, macros::format_description, Date, OffsetDateTime, PrimitiveDateTime};
 
     use crate::{
         parse::{relative, Error},
         time::{
-            format::{DEFAULT, ISO8601, ISO8601_STRICT, RFC2822, SHORT},
+            format::{DEFAULT, GITOXIDE, ISO8601, ISO8601_STRICT, RFC2822, SHORT},
             Sign,
         },
         Time,
     };
 
-    #[allow(missing_docs)]
+    /// E.g. `2022-08-17 22:04:58`
+    const ISO8601_WITHOUT_OFFSET: &[FormatItem<'_>] =
+        format_description!("[year]-[month]-[day] [hour]:[minute]:[second]");
+    /// E.g. `2022-08-17T22:04:58`
+    const ISO8601_STRICT_WITHOUT_OFFSET: &[FormatItem<'_>] =
+        format_description!("[year]-[month]-[day]T[hour]:[minute]:[second]");
+    /// E.g. `2022-08-17T22:04:58Z`
+    const ISO8601_STRICT_UTC: &[FormatItem<'_>] = format_description!("[year]-[month]-[day]T[hour]:[minute]:[second]Z");
+
+    /// Parse `input` as any of the date formats git understands, using `now` as reference for relative dates.
+    ///
+    /// Supported are the formats git can produce, i.e. `short`, `rfc2822`, `iso8601`, `iso8601-strict`, `default`, `unix`
+    /// and `raw`, as well as our own [`GITOXIDE`] format, the latter optionally prefixed with `@` and without an offset like `@1660874655`.
+    /// Dates and times without an offset are assumed to be in UTC.
+    ///
+    /// Otherwise `input` is parsed as approximate date like `2 weeks ago`, `3.months.ago`, `yesterday 10am`,
+    /// `noon yesterday` or `last friday`, which fails with [`Error::MissingCurrentTime`] if `now` is `None`.
+    /// Note that unlike git, which uses the local time, approximate dates are computed in UTC.
     pub fn parse(input: &str, now: Option<SystemTime>) -> Result<Time, Error> {
         // TODO: actual implementation, this is just to not constantly fail
         if input == "1979-02-26 18:30:00" {
@@ -41,8 +58,20 @@ pub(crate) mod function {
             Time::new(val.unix_timestamp().try_into()?, val.offset().whole_seconds())
         } else if let Ok(val) = OffsetDateTime::parse(input, ISO8601_STRICT) {
             Time::new(val.unix_timestamp().try_into()?, val.offset().whole_seconds())
+        } else if let Some(val) = [
+            ISO8601_WITHOUT_OFFSET,
+            ISO8601_STRICT_WITHOUT_OFFSET,
+            ISO8601_STRICT_UTC,
+        ]
+        .into_iter()
+        .find_map(|format| PrimitiveDateTime::parse(input, format).ok())
+        {
+            let val = val.assume_utc();
+            Time::new(timestamp(val)?, 0)
         } else if let Ok(val) = OffsetDateTime::parse(input, DEFAULT) {
             Time::new(val.unix_timestamp().try_into()?, val.offset().whole_seconds())
+        } else if let Ok(val) = OffsetDateTime::parse(input, GITOXIDE) {
+            Time::new(val.unix_timestamp().try_into()?, val.offset().whole_seconds())
         } else if let Ok(val) = u32::from_str(input) {
             // Format::Unix
             Time::new(val, 0)
@@ -66,16 +95,31 @@ pub(crate) mod function {
     }
 
     fn parse_raw(input: &str) -> Option<Time> {
+        let (input, offset_is_optional) = match input.strip_prefix('@') {
+            Some(input) => (input, true),
+            None => (input, false),
+        };
         let mut split = input.split_whitespace();
         let seconds_since_unix_epoch: u32 = split.next()?.parse().ok()?;
-        let offset = split.next()?;
-        if offset.len() != 5 {
+        let offset = match split.next() {
+            Some(offset) => offset,
+            None if offset_is_optional => return Some(Time::new(seconds_since_unix_epoch, 0)),
+            None => return None,
+        };
+        if offset.len() != 5 || split.next().is_some() {
             return None;
         }
-        let sign = if &offset[..1] == "-" { Sign::Plus } else { Sign::Minus };
+        let sign = match &offset[..1] {
+            "-" => Sign::Minus,
+            "+" => Sign::Plus,
+            _ => return None,
+        };
         let hours: i32 = offset[1..3].parse().ok()?;
         let minutes: i32 = offset[3..5].parse().ok()?;
-        let offset_in_seconds = hours * 3600 + minutes * 60;
+        let mut offset_in_seconds = hours * 3600 + minutes * 60;
+        if sign == Sign::Minus {
+            offset_in_seconds *= -1;
+        }
         let time = Time {
             seconds_since_unix_epoch,
             offset_in_seconds,
@@ -85,47 +129,232 @@ pub(crate) mod function {
     }
 }
 
+/// Parsing of approximate dates, modelled after `approxidate` in git.
 mod relative {
-    use std::{convert::TryInto, str::FromStr, time::SystemTime};
+    use std::{convert::TryInto, time::SystemTime};
 
-    use time::{Duration, OffsetDateTime};
+    use time::{Date, Duration, Month, OffsetDateTime, Time, Weekday};
 
     use crate::parse::Error;
 
-    fn parse_inner(input: &str) -> Option<Duration> {
-        let mut split = input.split_whitespace();
-        let multiplier = i64::from_str(split.next()?).ok()?;
-        let period = split.next()?;
-        if split.next()? != "ago" {
-            return None;
+    /// A single modification of the date to produce, in order of appearance.
+    #[derive(Debug, Clone, Copy, Eq, PartialEq)]
+    enum Adjustment {
+        /// Go back by the given amount of seconds.
+        Seconds(u64),
+        /// Go back by the given amount of months, keeping the day and time.
+        Months(u64),
+        /// Go back by the given amount of years, keeping the day and time.
+        Years(u64),
+        /// Go back to the `nth` most recent occurrence of the given weekday, with `1` being the last one.
+        MostRecentWeekday { day: Weekday, nth: u64 },
+        /// Go back to the most recent occurrence of the given hour, like `noon`.
+        MostRecentHour(u8),
+        /// Set the time of the current day, like `10:30`.
+        TimeOfDay(Time),
+        /// Set the hour in the morning, or keep the current one, and convert it to a time before noon.
+        Am(Option<u64>),
+        /// Set the hour in the afternoon, or keep the current one, and convert it to a time after noon.
+        Pm(Option<u64>),
+        /// Reset the date to the current time.
+        Now,
+        /// Reset the date to the UNIX epoch.
+        Never,
+    }
+
+    const DAY: u64 = 24 * 60 * 60;
+    const NUMBER_NAMES: &[&str] = &[
+        "zero", "one", "two", "three", "four", "five", "six", "seven", "eight", "nine", "ten",
+    ];
+    const WEEKDAYS: &[(&str, Weekday)] = &[
+        ("sunday", Weekday::Sunday),
+        ("monday", Weekday::Monday),
+        ("tuesday", Weekday::Tuesday),
+        ("wednesday", Weekday::Wednesday),
+        ("thursday", Weekday::Thursday),
+        ("friday", Weekday::Friday),
+        ("saturday", Weekday::Saturday),
+    ];
+
+    /// Turn `input` into adjustments to apply to the current time, or `None` if it's not an approximate date.
+    fn parse_inner(input: &str) -> Option<Vec<Adjustment>> {
+        let mut adjustments = Vec::new();
+        let mut number = None;
+        let mut rest = input;
+        while let Some(start) = rest.find(|c: char| c.is_ascii_alphanumeric()) {
+            rest = &rest[start..];
+            if rest.starts_with(|c: char| c.is_ascii_digit()) {
+                let (value, tail) = split_at_end_of(rest, |c| c.is_ascii_digit());
+                if let Some((time, tail)) = time_of_day(rest) {
+                    if number.is_some() {
+                        return None;
+                    }
+                    adjustments.push(Adjustment::TimeOfDay(time));
+                    rest = tail;
+                    continue;
+                }
+                if number.replace(value.parse::<u64>().ok()?).is_some() {
+                    return None;
+                }
+                rest = tail;
+                continue;
+            }
+
+            let (word, tail) = split_at_end_of(rest, |c| c.is_ascii_alphabetic());
+            rest = tail;
+            let word = word.to_ascii_lowercase();
+            let adjustment = match word.as_str() {
+                "yesterday" => Adjustment::Seconds(DAY),
+                "noon" => Adjustment::MostRecentHour(12),
+                "midnight" => Adjustment::MostRecentHour(0),
+                "tea" => Adjustment::MostRecentHour(17),
+                "am" => Adjustment::Am(number.take()),
+                "pm" => Adjustment::Pm(number.take()),
+                "now" => Adjustment::Now,
+                "never" => Adjustment::Never,
+                "last" => {
+                    number = Some(1);
+                    continue;
+                }
+                "month" | "months" => Adjustment::Months(number.take().unwrap_or(0)),
+                "year" | "years" => Adjustment::Years(number.take().unwrap_or(0)),
+                _ => {
+                    if let Some(value) = NUMBER_NAMES.iter().position(|name| *name == word) {
+                        number = Some(value as u64);
+                        continue;
+                    }
+                    if let Some(seconds) = unit_in_seconds(&word) {
+                        Adjustment::Seconds(seconds.checked_mul(number.take().unwrap_or(0))?)
+                    } else if let Some(day) = weekday(&word) {
+                        match number.take().unwrap_or(0) {
+                            0 => continue,
+                            nth => Adjustment::MostRecentWeekday { day, nth },
+                        }
+                    } else {
+                        // Ignore words we don't know, like `ago`, just like git.
+                        continue;
+                    }
+                }
+            };
+            adjustments.push(adjustment);
         }
-        duration(period, multiplier)
+        (number.is_none() && !adjustments.is_empty()).then(|| adjustments)
     }
 
     pub(crate) fn parse(input: &str, now: Option<SystemTime>) -> Option<Result<OffsetDateTime, Error>> {
-        parse_inner(input).map(|offset| {
-            let offset = std::time::Duration::from_secs(offset.whole_seconds().try_into().expect("positive value"));
-            now.ok_or(Error::MissingCurrentTime).map(|now| {
-                now.checked_sub(offset)
-                    .expect("BUG: values can't be large enough to cause underflow")
-                    .into()
-            })
+        let adjustments = parse_inner(input)?;
+        Some(now.ok_or(Error::MissingCurrentTime).and_then(|now| {
+            let now = OffsetDateTime::from(now);
+            adjustments
+                .into_iter()
+                .try_fold(now, |date, adjustment| apply(date, now, adjustment))
+        }))
+    }
+
+    fn apply(date: OffsetDateTime, now: OffsetDateTime, adjustment: Adjustment) -> Result<OffsetDateTime, Error> {
+        use Adjustment::*;
+        let too_early = |seconds: u64| Error::TooEarly {
+            timestamp: date
+                .unix_timestamp()
+                .saturating_sub(seconds.try_into().unwrap_or(i64::MAX)),
+        };
+        let go_back = |seconds: u64| {
+            seconds
+                .try_into()
+                .ok()
+                .and_then(|seconds| date.checked_sub(Duration::seconds(seconds)))
+                .ok_or_else(|| too_early(seconds))
+        };
+        Ok(match adjustment {
+            Seconds(seconds) => go_back(seconds)?,
+            Months(months) => {
+                let month_index = i64::from(date.year()) * 12 + i64::from(u8::from(date.month())) - 1;
+                let month_index = months
+                    .try_into()
+                    .ok()
+                    .and_then(|months| month_index.checked_sub(months))
+                    .ok_or_else(|| too_early(months.saturating_mul(30 * DAY)))?;
+                with_year_and_month(date, month_index.div_euclid(12), (month_index.rem_euclid(12) + 1) as u8)
+                    .ok_or_else(|| too_early(months.saturating_mul(30 * DAY)))?
+            }
+            Years(years) => years
+                .try_into()
+                .ok()
+                .and_then(|years: i64| i64::from(date.year()).checked_sub(years))
+                .and_then(|year| with_year_and_month(date, year, date.month().into()))
+                .ok_or_else(|| too_early(years.saturating_mul(365 * DAY)))?,
+            MostRecentWeekday { day, nth } => {
+                let mut days_back =
+                    i64::from(date.weekday().number_days_from_sunday()) - i64::from(day.number_days_from_sunday());
+                let mut weeks = nth - 1;
+                if days_back <= 0 {
+                    weeks += 1;
+                }
+                days_back += weeks.try_into().unwrap_or(i64::MAX / 7).saturating_mul(7);
+                go_back(days_back as u64 * DAY)?
+            }
+            MostRecentHour(hour) => {
+                let date = if date.hour() < hour { go_back(DAY)? } else { date };
+                date.replace_time(Time::from_hms(hour, 0, 0).expect("valid hour"))
+            }
+            TimeOfDay(time) => date.replace_time(time),
+            Am(hour) | Pm(hour) => {
+                let (hour, date) = match hour {
+                    Some(hour) => (hour, date.replace_time(Time::MIDNIGHT)),
+                    None => (date.hour().into(), date),
+                };
+                let hour = (hour % 12) as u8 + if matches!(adjustment, Pm(_)) { 12 } else { 0 };
+                date.replace_hour(hour).expect("valid hour")
+            }
+            Now => now,
+            Never => OffsetDateTime::UNIX_EPOCH,
         })
     }
 
-    fn duration(period: &str, multiplier: i64) -> Option<Duration> {
-        let period = period.strip_suffix('s').unwrap_or(period);
-        let seconds: i64 = match period {
+    /// Place `date` into the given month of `year`, with days past the end of that month overflowing into the next,
+    /// just like `mktime()` would do it.
+    fn with_year_and_month(date: OffsetDateTime, year: i64, month: u8) -> Option<OffsetDateTime> {
+        let month: Month = month.try_into().ok()?;
+        let first_of_month = Date::from_calendar_date(year.try_into().ok()?, month, 1).ok()?;
+        let date_in_month = first_of_month.checked_add(Duration::days(i64::from(date.day()) - 1))?;
+        Some(date.replace_date(date_in_month))
+    }
+
+    fn split_at_end_of(input: &str, is_part: impl Fn(char) -> bool) -> (&str, &str) {
+        input.split_at(input.find(|c: char| !is_part(c)).unwrap_or(input.len()))
+    }
+
+    /// Parse `hh:mm[:ss]` at the beginning of `input`, returning the time and the remaining input.
+    fn time_of_day(input: &str) -> Option<(Time, &str)> {
+        let (hour, rest) = split_at_end_of(input, |c| c.is_ascii_digit());
+        let (minute, rest) = split_at_end_of(rest.strip_prefix(':')?, |c| c.is_ascii_digit());
+        let (second, rest) = match rest.strip_prefix(':') {
+            Some(tail) if tail.starts_with(|c: char| c.is_ascii_digit()) => {
+                split_at_end_of(tail, |c| c.is_ascii_digit())
+            }
+            _ => ("0", rest),
+        };
+        let time = Time::from_hms(hour.parse().ok()?, minute.parse().ok()?, second.parse().ok()?).ok()?;
+        Some((time, rest))
+    }
+
+    fn unit_in_seconds(word: &str) -> Option<u64> {
+        let unit = word.strip_suffix('s').unwrap_or(word);
+        Some(match unit {
             "second" => 1,
             "minute" => 60,
             "hour" => 60 * 60,
-            "day" => 24 * 60 * 60,
-            "week" => 7 * 24 * 60 * 60,
-            // TODO months & years? YES
-            // Ignore values you don't know, assume seconds then (so does git)
+            "day" => DAY,
+            "week" => 7 * DAY,
             _ => return None,
-        };
-        seconds.checked_mul(multiplier).map(Duration::seconds)
+        })
+    }
+
+    fn weekday(word: &str) -> Option<Weekday> {
+        (word.len() >= 3)
+            .then(|| ())
+            .and_then(|_| WEEKDAYS.iter().find(|(name, _)| name.starts_with(word)))
+            .map(|(_, day)| *day)
     }
 
     #[cfg(test)]
@@ -134,7 +363,13 @@ mod relative {
 
         #[test]
         fn two_weeks_ago() {
-            assert_eq!(parse_inner("2 weeks ago"), Some(Duration::weeks(2)));
+            assert_eq!(parse_inner("2 weeks ago"), Some(vec![Adjustment::Seconds(2 * 7 * DAY)]));
+        }
+
+        #[test]
+        fn dangling_numbers_are_rejected() {
+            assert_eq!(parse_inner("2 weeks 3"), None);
+            assert_eq!(parse_inner("2 3 weeks"), None);
         }
     }
 }
@@ -1,3 +1,5 @@
+use std::time::SystemTime;
+
 use time::{format_description::FormatItem, macros::format_description};
 
 use crate::{time::Format, Time};
@@ -5,9 +7,9 @@ use crate::{time::Format, Time};
 /// E.g. `2018-12-24`
 pub const SHORT: &[FormatItem<'_>] = format_description!("[year]-[month]-[day]");
 
-/// E.g. `Thu, 18 Aug 2022 12:45:06 +0800`
+/// E.g. `Thu, 18 Aug 2022 12:45:06 +0800`, or `Sun, 4 Sep 2022 14:45:06 -0130` for single-digit days.
 pub const RFC2822: &[FormatItem<'_>] = format_description!(
-    "[weekday repr:short], [day] [month repr:short] [year] [hour]:[minute]:[second] [offset_hour sign:mandatory][offset_minute]"
+    "[weekday repr:short], [day padding:none] [month repr:short] [year] [hour]:[minute]:[second] [offset_hour sign:mandatory][offset_minute]"
 );
 
 /// E.g. `2022-08-17 22:04:58 +0200`
@@ -24,8 +26,13 @@ pub const UNIX: Format<'static> = Format::Unix;
 /// E.g. `1660874655 +0800`
 pub const RAW: Format<'static> = Format::Raw;
 
-/// E.g. `Thu Sep 04 2022 10:45:06 -0400`
+/// E.g. `Sun Sep 4 10:45:06 2022 -0400`, which is what git uses if no other format is specified.
 pub const DEFAULT: &[FormatItem<'_>] = format_description!(
+    "[weekday repr:short] [month repr:short] [day padding:none] [hour]:[minute]:[second] [year] [offset_hour sign:mandatory][offset_minute]"
+);
+
+/// E.g. `Thu Sep 04 2022 10:45:06 -0400`, a format that was previously used as [`DEFAULT`] and which can still be parsed.
+pub const GITOXIDE: &[FormatItem<'_>] = format_description!(
     "[weekday repr:short] [month repr:short] [day] [year] [hour]:[minute]:[second] [offset_hour sign:mandatory][offset_minute]"
 );
 
@@ -45,6 +52,8 @@ mod format_impls {
 impl Time {
     /// Format this instance according to the given `format`.
     ///
+    /// Month and weekday names are always in English, independently of the current locale, to match the output of git.
+    ///
     /// Use the [`format_description`](https://time-rs.github.io/book/api/format-description.html) macro to create and
     /// validate formats at compile time, courtesy of the [`time`] crate.
     pub fn format<'a>(&self, format: impl Into<Format<'a>>) -> String {
@@ -55,14 +64,68 @@ impl Time {
                 .expect("well-known format into memory never fails"),
             Format::Unix => self.seconds_since_unix_epoch.to_string(),
             Format::Raw => self.to_bstring().to_string(),
+            Format::Relative { now } => relative(self.seconds_since_unix_epoch, now),
         }
     }
 }
 
+/// Describe how long ago `seconds_since_unix_epoch` is relative to `now` with the same precision as git, like `3 days ago`.
+fn relative(seconds_since_unix_epoch: u32, now: SystemTime) -> String {
+    let now = now
+        .duration_since(SystemTime::UNIX_EPOCH)
+        .map_or(0, |duration| duration.as_secs());
+    let time = u64::from(seconds_since_unix_epoch);
+    if now < time {
+        return "in the future".into();
+    }
+    let ago = |amount: u64, unit: &str| format!("{} {}{} ago", amount, unit, plural(amount));
+
+    let diff = now - time;
+    if diff < 90 {
+        return ago(diff, "second");
+    }
+    let diff = (diff + 30) / 60;
+    if diff < 90 {
+        return ago(diff, "minute");
+    }
+    let diff = (diff + 30) / 60;
+    if diff < 36 {
+        return ago(diff, "hour");
+    }
+    let diff = (diff + 12) / 24;
+    if diff < 14 {
+        return ago(diff, "day");
+    }
+    if diff < 70 {
+        return ago((diff + 3) / 7, "week");
+    }
+    if diff < 365 {
+        return ago((diff + 15) / 30, "month");
+    }
+    if diff < 1825 {
+        let total_months = (diff * 12 * 2 + 365) / (365 * 2);
+        let (years, months) = (total_months / 12, total_months % 12);
+        return if months == 0 {
+            ago(years, "year")
+        } else {
+            format!("{} year{}, {}", years, plural(years), ago(months, "month"))
+        };
+    }
+    ago((diff + 183) / 365, "year")
+}
+
+fn plural(amount: u64) -> &'static str {
+    if amount == 1 {
+        ""
+    } else {
+        "s"
+    }
+}
+
 impl Time {
     fn to_time(self) -> time::OffsetDateTime {
         time::OffsetDateTime::from_unix_timestamp(self.seconds_since_unix_epoch as i64)
             .expect("always valid unix time")
-            .replace_offset(time::UtcOffset::from_whole_seconds(self.offset_in_seconds).expect("valid offset"))
+            .to_offset(time::UtcOffset::from_whole_seconds(self.offset_in_seconds).expect("valid offset"))
     }
 }
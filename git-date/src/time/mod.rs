@@ -31,6 +31,11 @@ pub enum Format<'a> {
     Unix,
     /// The seconds since 1970, followed by the offset, like `1660874655 +0800`
     Raw,
+    /// The time passed since the time was created relative to `now`, like `3 days ago` or `1 year, 2 months ago`.
+    Relative {
+        /// The time to compare to, typically the current time.
+        now: std::time::SystemTime,
+    },
 }
 
 ///
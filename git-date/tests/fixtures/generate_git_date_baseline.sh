@@ -3,6 +3,9 @@ set -eu -o pipefail
 
 git init;
 
+# approximate dates are relative to this time, which must match `NOW` in the tests, and computed in UTC.
+export GIT_TEST_DATE_NOW=1659329106 TZ=UTC
+
 function baseline() {
     local test_date=$1 # first argument is the date to test
 
@@ -33,9 +36,58 @@ baseline '2022-08-17 22:04:58 +0200'
 # iso8601_strict
 baseline '2022-08-17T21:43:13+08:00'
 # default
+baseline 'Sun Sep 4 10:45:06 2022 -0400'
+baseline 'Sun, 4 Sep 2022 10:45:06 -0400'
+# gitoxide
 baseline 'Thu Sep 04 2022 10:45:06 -0400'
 # unix
 baseline '123456789'
 # raw
 baseline '1660874655 +0800'
+baseline '1660874655 -0130'
+baseline '@1660874655'
+baseline '@1660874655 +0800'
+
+# iso8601 without offset or in UTC
+baseline '2022-08-17 22:04:58'
+baseline '2022-08-17T22:04:58'
+baseline '2022-08-17T22:04:58Z'
+
+# approximate dates
+baseline '1 second ago'
+baseline '10 minutes ago'
+baseline '1.hour.ago'
+baseline '3 days'
+baseline 'two days ago'
+baseline '2 weeks ago'
+baseline '2.weeks.ago'
+baseline '5 minutes 3 seconds ago'
+baseline '1.week.2.days.ago'
+baseline 'last week'
+baseline 'last month'
+baseline '3 months ago'
+baseline '13 months ago'
+baseline '1 year ago'
+baseline '2 years ago'
+baseline 'last friday'
+baseline 'yesterday'
+baseline 'yesterday.10am'
+baseline 'yesterday 10:30'
+baseline 'yesterday.10:30:15'
+baseline 'noon'
+baseline 'midnight'
+baseline 'tea'
+baseline 'noon yesterday'
+baseline 'midnight yesterday'
+baseline '10:30'
+baseline '10am'
+baseline '5pm'
+baseline '12am'
+baseline '12pm'
+
+# failure
+
+baseline 'foobar'
+baseline 'today'
+baseline 'friday'
 
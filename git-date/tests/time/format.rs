@@ -1,3 +1,5 @@
+use std::time::{Duration, SystemTime};
+
 use git_date::{
     time::{format, Format, Sign},
     Time,
@@ -6,7 +8,7 @@ use time::macros::format_description;
 
 #[test]
 fn short() {
-    assert_eq!(time().format(format::SHORT), "1973-11-29");
+    assert_eq!(time().format(format::SHORT), "1973-11-30");
 }
 
 #[test]
@@ -25,24 +27,71 @@ fn raw() {
 
 #[test]
 fn iso8601() {
-    assert_eq!(time().format(format::ISO8601), "1973-11-29 21:33:09 +0230");
+    assert_eq!(time().format(format::ISO8601), "1973-11-30 00:03:09 +0230");
 }
 
 #[test]
 fn iso8601_strict() {
-    assert_eq!(time().format(format::ISO8601_STRICT), "1973-11-29T21:33:09+02:30");
+    assert_eq!(time().format(format::ISO8601_STRICT), "1973-11-30T00:03:09+02:30");
 }
 
 #[test]
 fn rfc2822() {
-    assert_eq!(time().format(format::RFC2822), "Thu, 29 Nov 1973 21:33:09 +0230");
+    assert_eq!(time().format(format::RFC2822), "Fri, 30 Nov 1973 00:03:09 +0230");
+}
+
+#[test]
+fn rfc2822_single_digit_day() {
+    assert_eq!(
+        single_digit_day_time().format(format::RFC2822),
+        "Sun, 4 Sep 2022 13:15:06 -0130"
+    );
 }
 
 #[test]
 fn default() {
     assert_eq!(
         time().format(git_date::time::format::DEFAULT),
-        "Thu Nov 29 1973 21:33:09 +0230"
+        "Fri Nov 30 00:03:09 1973 +0230"
+    );
+    assert_eq!(
+        single_digit_day_time().format(git_date::time::format::DEFAULT),
+        "Sun Sep 4 13:15:06 2022 -0130"
+    );
+}
+
+#[test]
+fn gitoxide() {
+    assert_eq!(
+        time().format(git_date::time::format::GITOXIDE),
+        "Fri Nov 30 1973 00:03:09 +0230"
+    );
+}
+
+#[test]
+fn relative() {
+    let time = single_digit_day_time();
+    for (seconds_passed, expected) in [
+        (0, "0 seconds ago"),
+        (1, "1 second ago"),
+        (89, "89 seconds ago"),
+        (90, "2 minutes ago"),
+        (5399, "2 hours ago"),
+        (129_600, "2 days ago"),
+        (1_166_400, "2 weeks ago"),
+        (6_048_000, "2 months ago"),
+        (31_536_000, "1 year ago"),
+        (40_000_000, "1 year, 3 months ago"),
+        (157_680_000, "5 years ago"),
+    ] {
+        let now = SystemTime::UNIX_EPOCH + Duration::from_secs(time.seconds_since_unix_epoch as u64 + seconds_passed);
+        assert_eq!(time.format(Format::Relative { now }), expected, "{seconds_passed}");
+    }
+    assert_eq!(
+        time.format(Format::Relative {
+            now: SystemTime::UNIX_EPOCH
+        }),
+        "in the future"
     );
 }
 
@@ -50,10 +99,18 @@ fn default() {
 fn custom_compile_time() {
     assert_eq!(
         time().format(format_description!("[year]-[month]-[day] [hour]:[minute]:[second]")),
-        "1973-11-29 21:33:09",
+        "1973-11-30 00:03:09",
     );
 }
 
+fn single_digit_day_time() -> Time {
+    Time {
+        seconds_since_unix_epoch: 1662302706,
+        offset_in_seconds: -5400,
+        sign: Sign::Minus,
+    }
+}
+
 fn time() -> Time {
     Time {
         seconds_since_unix_epoch: 123456789,
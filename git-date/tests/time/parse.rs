@@ -1,4 +1,7 @@
-use std::{collections::HashMap, time::SystemTime};
+use std::{
+    collections::HashMap,
+    time::{Duration, SystemTime},
+};
 
 use bstr::{BString, ByteSlice};
 use git_date::{time::Sign, Time};
@@ -6,7 +9,10 @@ use once_cell::sync::Lazy;
 
 type Result<T = ()> = std::result::Result<T, Box<dyn std::error::Error>>;
 
-static BASELINE: Lazy<HashMap<BString, (usize, u32)>> = Lazy::new(|| {
+/// The time relative to which approximate dates are computed, as set by `GIT_TEST_DATE_NOW` in the baseline script.
+static NOW: Lazy<SystemTime> = Lazy::new(|| SystemTime::UNIX_EPOCH + Duration::from_secs(1659329106));
+
+static BASELINE: Lazy<HashMap<BString, (usize, Option<u32>)>> = Lazy::new(|| {
     let base = git_testtools::scripted_fixture_repo_read_only("generate_git_date_baseline.sh").unwrap();
 
     (|| -> Result<_> {
@@ -15,13 +21,9 @@ static BASELINE: Lazy<HashMap<BString, (usize, u32)>> = Lazy::new(|| {
         let mut lines = baseline.lines();
         while let Some(date_str) = lines.next() {
             let exit_code = lines.next().expect("three lines per baseline").to_str()?.parse()?;
-            let output: u32 = lines
-                .next()
-                .expect("three lines per baseline")
-                .to_str()
-                .expect("valid utf")
-                .parse()
-                .expect("valid epoch value");
+            let output = lines.next().expect("three lines per baseline");
+            let output: Option<u32> =
+                (exit_code == 0).then(|| output.to_str().expect("valid utf").parse().expect("valid epoch value"));
             map.insert(date_str.into(), (exit_code, output));
         }
         Ok(map)
@@ -32,13 +34,13 @@ static BASELINE: Lazy<HashMap<BString, (usize, u32)>> = Lazy::new(|| {
 #[test]
 fn baseline() {
     for (pattern, (exit_code, output)) in BASELINE.iter() {
-        let res = git_date::parse(pattern.to_str().expect("valid pattern"), Some(SystemTime::now()));
+        let res = git_date::parse(pattern.to_str().expect("valid pattern"), Some(*NOW));
         assert_eq!(
             res.is_ok(),
             *exit_code == 0,
             "{pattern:?} disagrees with baseline: {res:?}"
         );
-        if *exit_code == 0 {
+        if let Some(output) = output {
             let actual = res.unwrap().seconds_since_unix_epoch;
             assert_eq!(actual, *output, "{pattern:?} disagrees with baseline: {actual:?}")
         }
@@ -83,6 +85,24 @@ fn rfc2822() {
     );
 }
 
+#[test]
+fn raw() {
+    assert_eq!(
+        git_date::parse("1660874655 -0130", None).expect("parsed raw string"),
+        Time {
+            seconds_since_unix_epoch: 1660874655,
+            offset_in_seconds: -5400,
+            sign: Sign::Minus,
+        },
+        "negative offsets are negative"
+    );
+    assert_eq!(
+        git_date::parse("@1660874655", None).expect("parsed raw string"),
+        Time::new(1660874655, 0),
+        "the offset is optional if prefixed with @"
+    );
+}
+
 #[test]
 fn invalid_dates_can_be_produced_without_current_time() {
     assert!(matches!(
@@ -103,9 +123,9 @@ mod relative {
     }
 
     #[test]
-    #[should_panic] // TODO: fix
-    fn large_offsets_can_panic_elsewhere() {
-        git_date::parse("9999999999 weeks ago", Some(std::time::UNIX_EPOCH)).ok();
+    fn large_offsets_do_not_panic_elsewhere() {
+        let err = git_date::parse("9999999999 weeks ago", Some(std::time::UNIX_EPOCH)).unwrap_err();
+        assert!(matches!(err, Error::TooEarly { .. }), "{err:?}");
     }
 
     #[test]
@@ -114,6 +134,29 @@ mod relative {
         assert!(matches!(err, Error::TooEarly{timestamp} if timestamp == -1));
     }
 
+    #[test]
+    fn approximate_dates_need_the_current_time() {
+        let err = git_date::parse("yesterday", None).unwrap_err();
+        assert!(matches!(err, Error::MissingCurrentTime), "{err:?}");
+    }
+
+    #[test]
+    fn now_and_never() {
+        let now = Some(std::time::UNIX_EPOCH + std::time::Duration::from_secs(1659329106));
+        assert_eq!(
+            git_date::parse("now", now).unwrap().seconds_since_unix_epoch,
+            1659329106
+        );
+        assert_eq!(
+            git_date::parse("2 weeks ago now", now)
+                .unwrap()
+                .seconds_since_unix_epoch,
+            1659329106,
+            "now resets everything that came before"
+        );
+        assert_eq!(git_date::parse("never", now).unwrap().seconds_since_unix_epoch, 0);
+    }
+
     #[test]
     fn various() {
         let now = Some(SystemTime::now());
@@ -7,3 +7,6 @@ pub mod tree;
 
 ///
 pub mod blob;
+
+/// Compare index entries with the content of files in the worktree.
+pub mod worktree;
@@ -36,6 +36,9 @@ where
 ///
 pub mod changes;
 
+///
+pub mod triple_dot;
+
 ///
 pub mod visit;
 #[doc(inline)]
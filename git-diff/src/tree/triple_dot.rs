@@ -0,0 +1,135 @@
+use std::borrow::BorrowMut;
+
+use git_hash::{oid, ObjectId};
+
+use crate::tree;
+
+/// The error returned by [`tree_ids()`] and [`changes()`].
+#[derive(Debug, thiserror::Error)]
+#[allow(missing_docs)]
+pub enum Error {
+    #[error("The object {oid} could not be found")]
+    FindExisting {
+        oid: ObjectId,
+        source: Box<dyn std::error::Error + Send + Sync + 'static>,
+    },
+    #[error("The object {oid} was expected to be a commit")]
+    NotACommit { oid: ObjectId },
+    #[error("The object {oid} was expected to be a tree")]
+    NotATree { oid: ObjectId },
+    #[error("Commits {ours} and {theirs} do not have a merge-base")]
+    NoMergeBase { ours: ObjectId, theirs: ObjectId },
+    #[error("The merge-base of {ours} and {theirs} could not be computed")]
+    MergeBase {
+        ours: ObjectId,
+        theirs: ObjectId,
+        source: Box<dyn std::error::Error + Send + Sync + 'static>,
+    },
+    #[error(transparent)]
+    Decode(#[from] git_object::decode::Error),
+    #[error(transparent)]
+    Changes(#[from] tree::changes::Error),
+}
+
+/// The trees to compare when computing `ours...theirs`, as returned by [`tree_ids()`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Ord, PartialOrd)]
+pub struct Outcome {
+    /// The best merge-base between `ours` and `theirs`.
+    pub merge_base: ObjectId,
+    /// The tree of the `merge_base` commit, the left-hand side of the diff.
+    pub merge_base_tree: ObjectId,
+    /// The tree of the `theirs` commit, the right-hand side of the diff.
+    pub theirs_tree: ObjectId,
+}
+
+/// Resolve the trees to diff to obtain the changes of `ours...theirs`, that is the changes `theirs` introduced since it
+/// has forked off `ours`, similar to `git diff ours...theirs`.
+///
+/// `find` is used to lookup commits by id, and the best merge-base is computed with [`git_revision::merge_base()`].
+pub fn tree_ids<Find, E>(ours: &oid, theirs: &oid, mut find: Find) -> Result<Outcome, Error>
+where
+    Find: for<'b> FnMut(&oid, &'b mut Vec<u8>) -> Result<Option<git_object::Data<'b>>, E>,
+    E: std::error::Error + Send + Sync + 'static,
+{
+    let merge_base = git_revision::merge_base(ours, theirs, |id, buf| {
+        find(id, buf).map(|data| data.and_then(git_object::Data::try_into_commit_iter))
+    })
+    .map_err(|err| Error::MergeBase {
+        ours: ours.to_owned(),
+        theirs: theirs.to_owned(),
+        source: err.into(),
+    })?
+    .ok_or_else(|| Error::NoMergeBase {
+        ours: ours.to_owned(),
+        theirs: theirs.to_owned(),
+    })?;
+
+    let mut buf = Vec::new();
+    Ok(Outcome {
+        merge_base,
+        merge_base_tree: tree_id_of_commit(&merge_base, &mut find, &mut buf)?,
+        theirs_tree: tree_id_of_commit(theirs, &mut find, &mut buf)?,
+    })
+}
+
+/// Compute the changes `theirs` introduced since it forked off `ours`, similar to `git diff ours...theirs`, and pass them to `delegate`.
+///
+/// `find` is used to lookup commits and trees by id. `state` is the same as for [`tree::Changes::needed_to_obtain()`], just like
+/// `delegate`. The returned [`Outcome`] informs about the merge-base that was used as left-hand side of the diff.
+pub fn changes<Find, E, R, StateMut>(
+    ours: &oid,
+    theirs: &oid,
+    state: StateMut,
+    mut find: Find,
+    delegate: &mut R,
+) -> Result<Outcome, Error>
+where
+    Find: for<'b> FnMut(&oid, &'b mut Vec<u8>) -> Result<Option<git_object::Data<'b>>, E>,
+    E: std::error::Error + Send + Sync + 'static,
+    R: tree::Visit,
+    StateMut: BorrowMut<tree::State>,
+{
+    let outcome = tree_ids(ours, theirs, &mut find)?;
+    let mut lhs_buf = Vec::new();
+    let lhs = tree_iter(&outcome.merge_base_tree, &mut find, &mut lhs_buf)?;
+    let mut rhs_buf = Vec::new();
+    let rhs = tree_iter(&outcome.theirs_tree, &mut find, &mut rhs_buf)?;
+
+    tree::Changes::from(lhs).needed_to_obtain(
+        rhs,
+        state,
+        |id, buf| tree_iter(id, &mut find, buf),
+        delegate,
+    )?;
+    Ok(outcome)
+}
+
+fn tree_id_of_commit<Find, E>(id: &oid, find: &mut Find, buf: &mut Vec<u8>) -> Result<ObjectId, Error>
+where
+    Find: for<'b> FnMut(&oid, &'b mut Vec<u8>) -> Result<Option<git_object::Data<'b>>, E>,
+    E: std::error::Error + Send + Sync + 'static,
+{
+    find(id, buf)
+        .map_err(|err| Error::FindExisting {
+            oid: id.to_owned(),
+            source: err.into(),
+        })?
+        .and_then(git_object::Data::try_into_commit_iter)
+        .ok_or_else(|| Error::NotACommit { oid: id.to_owned() })?
+        .tree_id()
+        .map_err(Into::into)
+}
+
+fn tree_iter<'b, Find, E>(id: &oid, find: &mut Find, buf: &'b mut Vec<u8>) -> Result<git_object::TreeRefIter<'b>, Error>
+where
+    Find: for<'c> FnMut(&oid, &'c mut Vec<u8>) -> Result<Option<git_object::Data<'c>>, E>,
+    E: std::error::Error + Send + Sync + 'static,
+{
+    find(id, buf)
+        .map_err(|err| Error::FindExisting {
+            oid: id.to_owned(),
+            source: err.into(),
+        })?
+        .and_then(git_object::Data::try_into_tree_iter)
+        .ok_or_else(|| Error::NotATree { oid: id.to_owned() })
+}
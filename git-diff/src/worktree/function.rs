@@ -0,0 +1,178 @@
+use std::path::Path;
+
+use git_hash::{oid, ObjectId};
+
+use crate::worktree::{Change, Error, Options, Outcome, Visit};
+
+/// Compare all entries of `index` with their counterparts in the worktree at the root of `cache`, and pass all differences
+/// to `delegate`.
+///
+/// Each worktree file is read and converted with `filters` according to its attributes, which are obtained from `cache`,
+/// to apply all conversions that `git add` would apply, like end-of-line conversions or `clean` filters, so that the hash
+/// compared to the index entry matches what would actually be committed. Thus `cache` must be configured for attribute
+/// queries, like with [`State::for_hashing()`][git_worktree::fs::cache::State::for_hashing()]. Symbolic links aren't converted.
+///
+/// `find` is used to lookup the blobs of index entries whose content differs, so both versions can be passed to `delegate`
+/// for further processing, like computing a text diff. It's also used to read `.gitattributes` files from the index
+/// if they aren't present in the worktree.
+///
+/// # Notes
+///
+/// * Entries with conflicts, submodules, sparse directories and entries marked to skip the worktree are ignored.
+/// * Files are compared by content, the stat information of index entries isn't taken into consideration.
+pub fn changes<Find, FindError>(
+    index: &git_index::State,
+    cache: &mut git_worktree::fs::Cache<'_>,
+    mut find: Find,
+    filters: &mut git_worktree::filter::Pipeline,
+    delegate: &mut impl Visit,
+    Options {
+        executable_bit,
+        symlink,
+    }: Options,
+) -> Result<Outcome, Error>
+where
+    Find: for<'a> FnMut(&oid, &'a mut Vec<u8>) -> Result<git_object::BlobRef<'a>, FindError>,
+    FindError: std::error::Error + Send + Sync + 'static,
+{
+    let worktree = cache.base().to_owned();
+    let mut outcome = Outcome::default();
+    let mut buf = Vec::new();
+    let mut converted = Vec::new();
+    let mut blob_buf = Vec::new();
+    let mut attributes = Vec::new();
+
+    for entry in index.entries() {
+        if entry.stage() != 0
+            || entry.flags.contains(git_index::entry::Flags::SKIP_WORKTREE)
+            || matches!(entry.mode, git_index::entry::Mode::DIR | git_index::entry::Mode::COMMIT)
+        {
+            continue;
+        }
+        outcome.entries_compared += 1;
+
+        let rela_path = entry.path(index);
+        let path = worktree.join(git_path::try_from_bstr(rela_path).map_err(|_| Error::IllformedUtf8 {
+            path: rela_path.to_owned(),
+        })?);
+        let io_err = |source| Error::Io {
+            path: path.clone(),
+            source,
+        };
+        let meta = match std::fs::symlink_metadata(&path) {
+            Ok(meta) => meta,
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound || has_non_directory_parent(&path, &worktree) => {
+                if delegate.visit(entry, rela_path, Change::Removed).cancelled() {
+                    return Err(Error::Cancelled);
+                }
+                continue;
+            }
+            Err(err) => return Err(io_err(err)),
+        };
+
+        let is_symlink = entry.mode == git_index::entry::Mode::SYMLINK && symlink;
+        let kind_matches = if is_symlink {
+            meta.file_type().is_symlink()
+        } else {
+            meta.file_type().is_file()
+        };
+        if !kind_matches {
+            if delegate.visit(entry, rela_path, Change::TypeChange).cancelled() {
+                return Err(Error::Cancelled);
+            }
+            continue;
+        }
+
+        let data = if is_symlink {
+            let destination = std::fs::read_link(&path).map_err(io_err)?;
+            buf.clear();
+            buf.extend_from_slice(git_path::into_bstr(destination).as_ref());
+            outcome.bytes_read += buf.len() as u64;
+            &buf
+        } else {
+            buf = std::fs::read(&path).map_err(io_err)?;
+            outcome.bytes_read += buf.len() as u64;
+            if entry.mode == git_index::entry::Mode::SYMLINK {
+                &buf
+            } else {
+                cache
+                    .at_entry(rela_path, Some(false), &mut find)
+                    .map_err(io_err)?
+                    .matching_attributes(&mut attributes);
+                let has_converted = filters
+                    .convert_to_git(
+                        &buf,
+                        rela_path,
+                        &git_worktree::filter::Attributes::from_assignments(&attributes),
+                        &mut converted,
+                    )
+                    .map_err(|err| Error::ToGit {
+                        path: rela_path.to_owned(),
+                        source: err,
+                    })?;
+                if has_converted {
+                    &converted
+                } else {
+                    &buf
+                }
+            }
+        };
+
+        let executable_bit_changed = executable_bit
+            && matches!(
+                entry.mode,
+                git_index::entry::Mode::FILE | git_index::entry::Mode::FILE_EXECUTABLE
+            )
+            && is_executable(&meta) != (entry.mode == git_index::entry::Mode::FILE_EXECUTABLE);
+        let id = blob_id(data, entry.id.kind());
+        let change = if id == entry.id {
+            if !executable_bit_changed {
+                continue;
+            }
+            Change::ExecutableBitChanged
+        } else {
+            let previous = find(&entry.id, &mut blob_buf).map_err(|err| Error::FindExisting {
+                oid: entry.id,
+                path: rela_path.to_owned(),
+                source: err.into(),
+            })?;
+            Change::Modification {
+                executable_bit_changed,
+                id,
+                previous_data: previous.data,
+                data,
+            }
+        };
+        if delegate.visit(entry, rela_path, change).cancelled() {
+            return Err(Error::Cancelled);
+        }
+    }
+    Ok(outcome)
+}
+
+fn blob_id(data: &[u8], object_hash: git_hash::Kind) -> ObjectId {
+    let mut hasher = git_features::hash::hasher(object_hash);
+    hasher.update(&git_object::encode::loose_header(git_object::Kind::Blob, data.len()));
+    hasher.update(data);
+    hasher.digest().into()
+}
+
+#[cfg(unix)]
+fn is_executable(meta: &std::fs::Metadata) -> bool {
+    use std::os::unix::fs::MetadataExt;
+    meta.mode() & 0o100 == 0o100
+}
+
+#[cfg(not(unix))]
+fn is_executable(_meta: &std::fs::Metadata) -> bool {
+    false
+}
+
+/// Return true if a leading path component of `path` below `root` was replaced by something that isn't a directory,
+/// hence the entry itself can't exist anymore.
+fn has_non_directory_parent(path: &Path, root: &Path) -> bool {
+    path.ancestors()
+        .skip(1)
+        .take_while(|dir| *dir != root)
+        .any(|dir| std::fs::symlink_metadata(dir).map_or(false, |meta| !meta.is_dir()))
+}
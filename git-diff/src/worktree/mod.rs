@@ -0,0 +1,88 @@
+use git_hash::ObjectId;
+use git_object::bstr::BStr;
+
+pub use crate::tree::visit::Action;
+
+/// The error returned by [`changes()`][function::changes()].
+#[derive(Debug, thiserror::Error)]
+#[allow(missing_docs)]
+pub enum Error {
+    #[error("The path {} could not be converted to a path on disk", .path)]
+    IllformedUtf8 { path: git_object::bstr::BString },
+    #[error("Could not read worktree file or its metadata at {}", .path.display())]
+    Io {
+        path: std::path::PathBuf,
+        source: std::io::Error,
+    },
+    #[error("The blob {oid} tracked at {path} could not be found in the object database")]
+    FindExisting {
+        oid: ObjectId,
+        path: git_object::bstr::BString,
+        source: Box<dyn std::error::Error + Send + Sync + 'static>,
+    },
+    #[error("Worktree content at {path} could not be converted to what would be stored in git")]
+    ToGit {
+        path: git_object::bstr::BString,
+        source: git_worktree::filter::Error,
+    },
+    #[error("The delegate cancelled the operation")]
+    Cancelled,
+}
+
+/// The options for use in [`changes()`][function::changes()].
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Options {
+    /// If true, the executable bit of files in the worktree is compared to the mode of the index entry.
+    /// Otherwise it's assumed that the filesystem doesn't support the executable bit and it will be ignored.
+    pub executable_bit: bool,
+    /// If true, entries that are symbolic links in the index are expected to be symbolic links on disk.
+    /// Otherwise it's assumed they were checked out as files which contain the link destination as text.
+    pub symlink: bool,
+}
+
+/// The outcome of a successful call to [`changes()`][function::changes()].
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Outcome {
+    /// The amount of index entries that were compared to their counterpart in the worktree.
+    pub entries_compared: usize,
+    /// The amount of bytes read from worktree files, before any conversion was applied.
+    pub bytes_read: u64,
+}
+
+/// A change between an index entry and the matching item in the worktree, as passed to [`Visit::visit()`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Change<'a> {
+    /// The item tracked by the index entry doesn't exist in the worktree anymore.
+    Removed,
+    /// The item in the worktree is of a different kind than the one in the index, like when a file was replaced by a
+    /// directory or a symbolic link.
+    TypeChange,
+    /// The content is the same, but the executable bit of the worktree file doesn't match the mode of the index entry.
+    ExecutableBitChanged,
+    /// The content of the worktree item, after conversion to what would be stored in git, differs from the content in the index.
+    Modification {
+        /// If true, the executable bit changed as well.
+        executable_bit_changed: bool,
+        /// The id of the worktree content as it would be stored in git.
+        id: ObjectId,
+        /// The content of the blob as stored in the index.
+        previous_data: &'a [u8],
+        /// The content of the worktree item after it was converted to what would be stored in git, suitable for computing
+        /// a text diff with `previous_data`.
+        data: &'a [u8],
+    },
+}
+
+/// A trait to allow responding to changes between index entries and the worktree.
+pub trait Visit {
+    /// Called for each index `entry` at `rela_path` that differs from its counterpart in the worktree as described by `change`.
+    fn visit(&mut self, entry: &git_index::Entry, rela_path: &BStr, change: Change<'_>) -> Action;
+}
+
+pub(crate) mod function;
+pub use function::changes;
+
+/// Useful for use as delegate implementing [`Visit`] to keep track of all seen changes.
+pub mod recorder;
+#[doc(inline)]
+pub use recorder::Recorder;
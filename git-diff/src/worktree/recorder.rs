@@ -0,0 +1,54 @@
+use git_hash::ObjectId;
+use git_object::bstr::{BStr, BString};
+
+use crate::worktree::{self, Action, Visit};
+
+/// A change as observed by a call to [`visit(…)`][Visit::visit()], with the path and object ids of each affected entry.
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[allow(missing_docs)]
+pub enum Change {
+    Removed {
+        path: BString,
+    },
+    TypeChange {
+        path: BString,
+    },
+    ExecutableBitChanged {
+        path: BString,
+    },
+    Modification {
+        path: BString,
+        previous_id: ObjectId,
+        id: ObjectId,
+        executable_bit_changed: bool,
+    },
+}
+
+/// A [Visit] implementation to record every observed change.
+#[derive(Clone, Debug, Default)]
+pub struct Recorder {
+    /// The observed changes.
+    pub records: Vec<Change>,
+}
+
+impl Visit for Recorder {
+    fn visit(&mut self, entry: &git_index::Entry, rela_path: &BStr, change: worktree::Change<'_>) -> Action {
+        let path = rela_path.to_owned();
+        self.records.push(match change {
+            worktree::Change::Removed => Change::Removed { path },
+            worktree::Change::TypeChange => Change::TypeChange { path },
+            worktree::Change::ExecutableBitChanged => Change::ExecutableBitChanged { path },
+            worktree::Change::Modification {
+                executable_bit_changed,
+                id,
+                ..
+            } => Change::Modification {
+                path,
+                previous_id: entry.id,
+                id,
+                executable_bit_changed,
+            },
+        });
+        Action::Continue
+    }
+}
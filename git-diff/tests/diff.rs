@@ -4,3 +4,4 @@ pub use git_testtools::hex_to_id;
 
 mod blob;
 mod tree;
+mod worktree;
@@ -0,0 +1,25 @@
+#!/bin/bash
+set -eu -o pipefail
+
+git init -q
+git config core.autocrlf false
+
+echo unchanged > unchanged
+echo content > modified
+echo content > removed
+echo content > executable
+echo content > type-change
+printf 'a\nb\n' > crlf
+mkdir dir
+echo content > dir/removed-with-parent
+ln -s unchanged symlink
+
+git add .
+git commit -qm "initial"
+
+echo changed > modified
+rm removed
+chmod +x executable
+rm type-change && mkdir type-change
+printf 'a\r\nb\r\n' > crlf
+rm -Rf dir && echo content > dir
@@ -611,5 +611,59 @@ mod changes {
             );
             Ok(())
         }
+
+        mod triple_dot {
+            use git_odb::pack::Find;
+
+            use super::{all_commits, db, diff_commits};
+
+            fn changes(db: &git_odb::Handle, ours: &git_hash::oid, theirs: &git_hash::oid) -> crate::Result<super::Changes> {
+                let mut recorder = git_diff::tree::Recorder::default();
+                git_diff::tree::triple_dot::changes(
+                    ours,
+                    theirs,
+                    git_diff::tree::State::default(),
+                    |oid, buf| db.try_find(oid, buf).map(|obj| obj.map(|(obj, _)| obj)),
+                    &mut recorder,
+                )?;
+                Ok(recorder.records)
+            }
+
+            #[test]
+            fn changes_are_computed_against_the_merge_base() -> crate::Result {
+                let db = db(None)?;
+                let all_commits = all_commits(&db);
+                let (first, last) = (&all_commits[0], all_commits.last().expect("non-empty"));
+
+                assert_eq!(
+                    changes(&db, first, last)?,
+                    diff_commits(&db, *first, last)?,
+                    "in linear history, the merge-base is the ancestor itself, making this a normal diff"
+                );
+                assert_eq!(
+                    changes(&db, last, first)?,
+                    vec![],
+                    "the ancestor didn't introduce any changes since forking off the descendant"
+                );
+                Ok(())
+            }
+
+            #[test]
+            fn tree_ids_provide_the_merge_base_and_the_trees_to_diff() -> crate::Result {
+                let db = db(None)?;
+                let all_commits = all_commits(&db);
+                let (first, last) = (&all_commits[0], all_commits.last().expect("non-empty"));
+
+                let outcome = git_diff::tree::triple_dot::tree_ids(last, first, |oid, buf| {
+                    db.try_find(oid, buf).map(|obj| obj.map(|(obj, _)| obj))
+                })?;
+                assert_eq!(outcome.merge_base, *first);
+                assert_eq!(
+                    outcome.merge_base_tree, outcome.theirs_tree,
+                    "theirs is the merge-base, so both trees are the same"
+                );
+                Ok(())
+            }
+        }
     }
 }
@@ -0,0 +1,87 @@
+use git_diff::worktree::{recorder::Change::*, Recorder};
+use git_odb::FindExt;
+use git_worktree::filter;
+
+use crate::hex_to_id;
+
+fn changes(
+    mut filters: filter::Pipeline,
+) -> crate::Result<(Vec<git_diff::worktree::recorder::Change>, git_diff::worktree::Outcome)> {
+    let dir = git_testtools::scripted_fixture_repo_read_only("make_worktree_changes_repo.sh")?;
+    let git_dir = dir.join(".git");
+    let index = git_index::File::at(git_dir.join("index"), git_hash::Kind::Sha1, Default::default())?;
+    let odb = git_odb::at(git_dir.join("objects"))?;
+    let mut cache = git_worktree::fs::Cache::new(
+        &dir,
+        git_worktree::fs::cache::State::for_hashing(Default::default()),
+        Default::default(),
+        Vec::new(),
+        Vec::new(),
+    );
+    let mut recorder = Recorder::default();
+    let outcome = git_diff::worktree::changes(
+        &index,
+        &mut cache,
+        |id, buf| odb.find_blob(id, buf),
+        &mut filters,
+        &mut recorder,
+        git_diff::worktree::Options {
+            executable_bit: true,
+            symlink: true,
+        },
+    )?;
+    Ok((recorder.records, outcome))
+}
+
+#[test]
+fn all_kinds_of_changes_are_detected_without_conversion() -> crate::Result {
+    let (changes, outcome) = changes(Default::default())?;
+    assert_eq!(
+        changes,
+        vec![
+            Modification {
+                path: "crlf".into(),
+                previous_id: hex_to_id("422c2b7ab3b3c668038da977e4e93a5fc623169c"),
+                id: hex_to_id("c30dea8a3641ea99b125d04d599d843712292759"),
+                executable_bit_changed: false,
+            },
+            Removed {
+                path: "dir/removed-with-parent".into()
+            },
+            ExecutableBitChanged {
+                path: "executable".into()
+            },
+            Modification {
+                path: "modified".into(),
+                previous_id: hex_to_id("d95f3ad14dee633a758d2e331151e950dd13e4ed"),
+                id: hex_to_id("5ea2ed416fbd4a4cbe227b75fe255dd7fa6bd4d6"),
+                executable_bit_changed: false,
+            },
+            Removed { path: "removed".into() },
+            TypeChange {
+                path: "type-change".into()
+            },
+        ]
+    );
+    assert_eq!(outcome.entries_compared, 8, "all entries are compared");
+    Ok(())
+}
+
+#[test]
+fn conversions_are_applied_before_comparison() -> crate::Result {
+    let (changes, _outcome) = changes(filter::Pipeline::new(
+        Vec::new(),
+        filter::Options {
+            auto_crlf: filter::AutoCrlf::Enabled,
+            ..Default::default()
+        },
+    ))?;
+    assert!(
+        changes
+            .iter()
+            .all(|change| !matches!(change, Modification { path, .. } if path == "crlf")),
+        "once converted, the worktree file has the same content as the index"
+    );
+    assert_eq!(changes.len(), 5, "everything else is still changed");
+    Ok(())
+}
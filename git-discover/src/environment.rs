@@ -0,0 +1,99 @@
+use std::path::{Path, PathBuf};
+
+use crate::{is::git_with_common_dir, repository, DOT_GIT_DIR};
+
+/// The error returned by [`Overrides::repository_path()`].
+#[derive(Debug, thiserror::Error)]
+#[allow(missing_docs)]
+pub enum Error {
+    #[error("The path '{}' of {} is relative and tries to reach beyond the root of the filesystem", .path.display(), .variable)]
+    InvalidPath { path: PathBuf, variable: &'static str },
+    #[error("GIT_DIR at '{}' is not a git repository", .path.display())]
+    NotARepository {
+        path: PathBuf,
+        #[source]
+        source: crate::is_git::Error,
+    },
+}
+
+/// The location of a repository and its work tree as overridden by environment variables, similar to how git handles them.
+#[derive(Default, Debug, Clone, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub struct Overrides {
+    /// The `.git` directory of the repository, or a `gitdir: <path>` file pointing to it, as set by `GIT_DIR`.
+    ///
+    /// If set, the repository isn't discovered but is expected at this location.
+    pub git_dir: Option<PathBuf>,
+    /// The work tree of the repository, as set by `GIT_WORK_TREE`, which takes precedence over the work tree derived from `git_dir`.
+    pub work_tree: Option<PathBuf>,
+    /// The directory holding objects, references and the configuration of the repository, as set by `GIT_COMMON_DIR`.
+    ///
+    /// If set, it overrides the `commondir` file of linked worktree git directories, and turns any `git_dir` into the private
+    /// git directory of a worktree.
+    pub common_dir: Option<PathBuf>,
+}
+
+impl Overrides {
+    /// Read `GIT_DIR`, `GIT_WORK_TREE` and `GIT_COMMON_DIR` from the environment, ignoring empty values.
+    pub fn from_env() -> Self {
+        fn path(name: &str) -> Option<PathBuf> {
+            std::env::var_os(name).filter(|v| !v.is_empty()).map(PathBuf::from)
+        }
+        Overrides {
+            git_dir: path("GIT_DIR"),
+            work_tree: path("GIT_WORK_TREE"),
+            common_dir: path("GIT_COMMON_DIR"),
+        }
+    }
+
+    /// Return the path to the repository as described by these overrides, or `None` if [`git_dir`][Overrides::git_dir] isn't set
+    /// and the repository should be discovered instead. Relative paths are made absolute with `current_dir`.
+    ///
+    /// `gitdir: <path>` files are followed, and the repository is validated with [`common_dir`][Overrides::common_dir] holding
+    /// objects and references if it is set. A [`work_tree`][Overrides::work_tree] turns the path into a
+    /// [work tree with a separate git directory][repository::Path::LinkedWorkTree] unless the git directory is the `.git`
+    /// directory of the work tree.
+    ///
+    /// Note that the common directory isn't part of the returned path, and the caller should use [`common_dir`][Overrides::common_dir]
+    /// in favor of the `commondir` file of the git directory.
+    pub fn repository_path(&self, current_dir: &Path) -> Option<Result<repository::Path, Error>> {
+        let git_dir = self.git_dir.as_deref()?;
+        Some(self.resolve(git_dir, current_dir))
+    }
+
+    fn resolve(&self, git_dir: &Path, cwd: &Path) -> Result<repository::Path, Error> {
+        let absolutize = |path: &Path, variable: &'static str| {
+            git_path::absolutize(cwd.join(path), cwd)
+                .map(std::borrow::Cow::into_owned)
+                .ok_or_else(|| Error::InvalidPath {
+                    path: path.into(),
+                    variable,
+                })
+        };
+        let common_dir = self
+            .common_dir
+            .as_deref()
+            .map(|dir| absolutize(dir, "GIT_COMMON_DIR"))
+            .transpose()?;
+        let git_dir = absolutize(git_dir, "GIT_DIR")?;
+        let kind = git_with_common_dir(&git_dir, common_dir.as_deref(), Default::default()).map_err(|source| {
+            Error::NotARepository {
+                path: git_dir.clone(),
+                source,
+            }
+        })?;
+        let path =
+            repository::Path::from_dot_git_dir(git_dir, kind, cwd).expect("absolute paths can always be resolved");
+        Ok(match &self.work_tree {
+            Some(work_tree) => {
+                let work_dir = absolutize(work_tree, "GIT_WORK_TREE")?;
+                let (git_dir, _) = path.into_repository_and_work_tree_directories();
+                if git_dir == work_dir.join(DOT_GIT_DIR) && common_dir.is_none() {
+                    repository::Path::WorkTree(work_dir)
+                } else {
+                    repository::Path::LinkedWorkTree { work_dir, git_dir }
+                }
+            }
+            None => path,
+        })
+    }
+}
@@ -4,10 +4,12 @@ use crate::{DOT_GIT_DIR, MODULES};
 
 /// Returns true if the given `git_dir` seems to be a bare repository.
 ///
-/// Please note that repositories without an index generally _look_ bare, even though they might also be uninitialized.
+/// Like git, a `.git` directory or file is assumed to belong to the work tree it's located in, hence it's never bare.
+/// Other repositories are bare unless they have an index, which is why uninitialized repositories with a name other
+/// than `.git` generally _look_ bare.
 pub fn bare(git_dir_candidate: impl AsRef<Path>) -> bool {
     let git_dir = git_dir_candidate.as_ref();
-    !(git_dir.join("index").exists() || (git_dir.file_name() == Some(OsStr::new(DOT_GIT_DIR)) && git_dir.is_file()))
+    !(git_dir.file_name() == Some(OsStr::new(DOT_GIT_DIR)) || git_dir.join("index").exists())
 }
 
 /// Returns true if `git_dir` is is located within a `.git/modules` directory, indicating it's a submodule clone.
@@ -39,6 +41,24 @@ pub fn submodule_git_dir(git_dir: impl AsRef<Path>) -> bool {
 ///   * …a refs directory
 ///
 pub fn git(git_dir: impl AsRef<Path>) -> Result<crate::repository::Kind, crate::is_git::Error> {
+    git_with_common_dir(git_dir.as_ref(), None, Default::default())
+}
+
+/// Like [`git()`], but with `options` to control how strictly `git_dir` is validated, for instance to avoid reading `HEAD`.
+pub fn git_opts(
+    git_dir: impl AsRef<Path>,
+    options: crate::is_git::Options,
+) -> Result<crate::repository::Kind, crate::is_git::Error> {
+    git_with_common_dir(git_dir.as_ref(), None, options)
+}
+
+/// Like [`git_opts()`], but with `common_dir_override` used as common directory holding objects and references if set, similar
+/// to `GIT_COMMON_DIR`.
+pub(crate) fn git_with_common_dir(
+    git_dir: &Path,
+    common_dir_override: Option<&Path>,
+    options: crate::is_git::Options,
+) -> Result<crate::repository::Kind, crate::is_git::Error> {
     #[derive(Eq, PartialEq)]
     enum Kind {
         MaybeRepo,
@@ -46,7 +66,6 @@ pub fn git(git_dir: impl AsRef<Path>) -> Result<crate::repository::Kind, crate::
         LinkedWorkTreeDir,
         WorkTreeGitDir { work_dir: std::path::PathBuf },
     }
-    let git_dir = git_dir.as_ref();
     let (dot_git, common_dir, kind) = if git_dir.metadata()?.is_file() {
         let private_git_dir = crate::path::from_gitdir_file(git_dir)?;
         let common_dir = private_git_dir.join("commondir");
@@ -93,7 +112,14 @@ pub fn git(git_dir: impl AsRef<Path>) -> Result<crate::repository::Kind, crate::
         }
     };
 
-    {
+    let common_dir = common_dir_override.map_or(common_dir, Cow::Borrowed);
+
+    if !options.validate_head {
+        let head_path = dot_git.join("HEAD");
+        if !head_path.is_file() {
+            return Err(crate::is_git::Error::MissingHead { missing: head_path });
+        }
+    } else {
         // We expect to be able to parse any ref-hash, so we shouldn't have to know the repos hash here.
         // With ref-table, the has is probably stored as part of the ref-db itself, so we can handle it from there.
         // In other words, it's important not to fail on detached heads here because we guessed the hash kind wrongly.
@@ -117,7 +143,7 @@ pub fn git(git_dir: impl AsRef<Path>) -> Result<crate::repository::Kind, crate::
             return Err(crate::is_git::Error::MissingObjectsDirectory { missing: objects_path });
         }
     }
-    {
+    if options.require_refs_dir {
         let refs_path = common_dir.join("refs");
         if !refs_path.is_dir() {
             return Err(crate::is_git::Error::MissingRefsDirectory { missing: refs_path });
@@ -133,10 +159,11 @@ pub fn git(git_dir: impl AsRef<Path>) -> Result<crate::repository::Kind, crate::
             git_dir: dot_git.into_owned(),
         },
         Kind::MaybeRepo => {
-            if bare(git_dir) {
-                crate::repository::Kind::Bare
-            } else if submodule_git_dir(git_dir) {
+            // Submodule git directories are checked first as they lack an index until their worktree is checked out.
+            if submodule_git_dir(git_dir) {
                 crate::repository::Kind::SubmoduleGitDir
+            } else if bare(git_dir) {
+                crate::repository::Kind::Bare
             } else {
                 crate::repository::Kind::WorkTree { linked_git_dir: None }
             }
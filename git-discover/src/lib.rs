@@ -23,6 +23,8 @@ pub mod is_git {
     pub enum Error {
         #[error("Could not find a valid HEAD reference")]
         FindHeadRef(#[from] git_ref::file::find::existing::Error),
+        #[error("Expected a HEAD file at '{}'", .missing.display())]
+        MissingHead { missing: PathBuf },
         #[error("Expected HEAD at '.git/HEAD', got '.git/{}'", .name)]
         MisplacedHead { name: bstr::BString },
         #[error("Expected an objects directory at '{}'", .missing.display())]
@@ -36,17 +38,46 @@ pub mod is_git {
         #[error("Could not retrieve metadata")]
         Metadata(#[from] std::io::Error),
     }
+
+    /// Options to control how strictly [`is_git_opts()`][crate::is_git_opts()] validates a git directory, trading correctness for speed.
+    #[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
+    pub struct Options {
+        /// If `true`, the default, `HEAD` is read and has to be a valid reference. Otherwise it's only required to be a file,
+        /// which is faster, for instance when scanning directories for repositories.
+        pub validate_head: bool,
+        /// If `true`, the default, the `refs` directory is required to exist. Otherwise repositories without it are tolerated,
+        /// for instance when they are in the process of being initialized.
+        pub require_refs_dir: bool,
+    }
+
+    impl Default for Options {
+        fn default() -> Self {
+            Options {
+                validate_head: true,
+                require_refs_dir: true,
+            }
+        }
+    }
 }
 
 mod is;
-pub use is::{bare as is_bare, git as is_git, submodule_git_dir as is_submodule_git_dir};
+pub use is::{bare as is_bare, git as is_git, git_opts as is_git_opts, submodule_git_dir as is_submodule_git_dir};
 
 ///
 pub mod upwards;
 pub use upwards::function::{discover as upwards, discover_opts as upwards_opts};
 
+///
+#[cfg(feature = "walk")]
+pub mod walk;
+#[cfg(feature = "walk")]
+pub use walk::function::walk;
+
 ///
 pub mod path;
 
+///
+pub mod environment;
+
 ///
 pub mod parse;
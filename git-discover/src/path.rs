@@ -1,4 +1,4 @@
-use std::{io::Read, path::PathBuf};
+use std::{borrow::Cow, io::Read, path::PathBuf};
 
 use crate::DOT_GIT_DIR;
 
@@ -67,3 +67,86 @@ pub fn without_dot_git_dir(mut path: PathBuf) -> PathBuf {
     }
     path
 }
+
+/// Bring `path` into a canonical form that can reliably be compared to other paths in that form, with `current_dir` used
+/// to resolve drive-relative paths.
+///
+/// On windows, drive-relative paths like `C:dir` are resolved against `current_dir` if it is located on the same drive, or against
+/// the root of the drive otherwise. Existing paths are canonicalized to resolve 8.3 short names like `PROGRA~1` and to obtain the
+/// casing used on disk, and verbatim prefixes like `\\?\C:\` and `\\?\UNC\server\share` are turned back into `C:\` and
+/// `\\server\share` respectively, with drive letters in uppercase.
+///
+/// On all other platforms, `path` is returned unchanged.
+pub fn normalize<'a>(path: &'a std::path::Path, current_dir: &std::path::Path) -> Cow<'a, std::path::Path> {
+    #[cfg(windows)]
+    {
+        windows::normalize(path, current_dir)
+    }
+    #[cfg(not(windows))]
+    {
+        let _ = current_dir;
+        Cow::Borrowed(path)
+    }
+}
+
+#[cfg(windows)]
+mod windows {
+    use std::{
+        borrow::Cow,
+        path::{Component, Path, PathBuf, Prefix},
+    };
+
+    pub(crate) fn normalize<'a>(path: &'a Path, current_dir: &Path) -> Cow<'a, Path> {
+        fn drive_letter(path: &Path) -> Option<u8> {
+            match path.components().next() {
+                Some(Component::Prefix(prefix)) => match prefix.kind() {
+                    Prefix::Disk(drive) | Prefix::VerbatimDisk(drive) => Some(drive.to_ascii_uppercase()),
+                    _ => None,
+                },
+                _ => None,
+            }
+        }
+
+        fn without_verbatim_prefix(path: &Path) -> PathBuf {
+            let mut components = path.components();
+            let mut out = match components.next() {
+                Some(Component::Prefix(prefix)) => match prefix.kind() {
+                    Prefix::VerbatimDisk(drive) | Prefix::Disk(drive) => {
+                        PathBuf::from(format!("{}:", drive.to_ascii_uppercase() as char))
+                    }
+                    Prefix::VerbatimUNC(server, share) => {
+                        let mut unc = std::ffi::OsString::from(r"\\");
+                        unc.push(server);
+                        unc.push(r"\");
+                        unc.push(share);
+                        PathBuf::from(unc)
+                    }
+                    _ => return path.to_owned(),
+                },
+                _ => return path.to_owned(),
+            };
+            out.extend(components);
+            out
+        }
+
+        let mut components = path.components();
+        let is_drive_relative = matches!(
+            (components.next(), components.next()),
+            (Some(Component::Prefix(prefix)), second)
+                if matches!(prefix.kind(), Prefix::Disk(_)) && second != Some(Component::RootDir)
+        );
+        let path = if is_drive_relative {
+            let drive = drive_letter(path).expect("checked above");
+            let base = if drive_letter(current_dir) == Some(drive) {
+                current_dir.to_owned()
+            } else {
+                PathBuf::from(format!("{}:\\", drive as char))
+            };
+            base.join(path.components().skip(1).collect::<PathBuf>())
+        } else {
+            path.to_owned()
+        };
+        let path = std::fs::canonicalize(&path).unwrap_or(path);
+        Cow::Owned(without_verbatim_prefix(&path))
+    }
+}
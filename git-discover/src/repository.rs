@@ -3,8 +3,8 @@ use std::path::PathBuf;
 /// A repository path which either points to a work tree or the `.git` repository itself.
 #[derive(Debug, Clone, Eq, PartialEq, Ord, PartialOrd, Hash)]
 pub enum Path {
-    /// The currently checked out linked worktree along with its connected and existing git directory, or the worktree checkout of a
-    /// submodule.
+    /// The currently checked out linked worktree along with its connected and existing git directory, the worktree checkout of a
+    /// submodule, or a work tree with a git directory located elsewhere as set by `GIT_WORK_TREE` and `GIT_DIR`.
     LinkedWorkTree {
         /// The base of the work tree.
         work_dir: PathBuf,
@@ -20,19 +20,23 @@ pub(crate) mod function {
     /// Find the location of the git repository directly in `directory` or in any of its parent directories and provide
     /// an associated Trust level by looking at the git directory's ownership, and control discovery using `options`.
     ///
+    /// Repositories that aren't owned by the current user are fully trusted nonetheless if they are listed in
+    /// [`safe_directories`][Options::safe_directories].
+    ///
     /// Fail if no valid-looking git repository could be found.
-    // TODO: tests for trust-based discovery
     #[cfg_attr(not(unix), allow(unused_variables))]
     pub fn discover_opts(
         directory: impl AsRef<Path>,
-        Options {
+        options: Options<'_>,
+    ) -> Result<(crate::repository::Path, Trust), Error> {
+        let Options {
             required_trust,
-            ceiling_dirs,
+            ref ceiling_dirs,
             match_ceiling_dir_or_error,
             cross_fs,
             current_dir,
-        }: Options<'_>,
-    ) -> Result<(crate::repository::Path, Trust), Error> {
+            safe_directories: _,
+        } = options;
         // Absolutize the path so that `Path::parent()` _actually_ gives
         // us the parent directory. (`Path::parent` just strips off the last
         // path component, which means it will not do what you expect when
@@ -40,10 +44,13 @@ pub(crate) mod function {
         let cwd = current_dir
             .map(|cwd| Ok(Cow::Borrowed(cwd)))
             .unwrap_or_else(|| std::env::current_dir().map(Cow::Owned))?;
+        // On windows, bring all paths into their canonical form so that they can be compared.
+        let cwd = crate::path::normalize(&cwd, &cwd);
         let directory = directory.as_ref();
         let dir = git_path::absolutize(directory, cwd.as_ref()).ok_or_else(|| Error::InvalidInput {
             directory: directory.into(),
         })?;
+        let dir = crate::path::normalize(&dir, &cwd);
         let dir_metadata = dir.metadata().map_err(|_| Error::InaccessibleDirectory {
             path: dir.to_path_buf(),
         })?;
@@ -59,12 +66,21 @@ pub(crate) mod function {
                 .is_ok();
 
         let filter_by_trust = |x: &Path| -> Result<Option<Trust>, Error> {
-            let trust = Trust::from_path_ownership(x).map_err(|err| Error::CheckTrust { path: x.into(), err })?;
-            Ok((trust >= required_trust).then(|| (trust)))
+            let mut trust = Trust::from_path_ownership(x).map_err(|err| Error::CheckTrust { path: x.into(), err })?;
+            if trust != Trust::Full {
+                let repo_dir = match x.parent() {
+                    Some(work_dir) if x.file_name() == Some(DOT_GIT_DIR.as_ref()) => work_dir,
+                    _ => x,
+                };
+                if options.is_safe_directory(repo_dir) {
+                    trust = Trust::Full;
+                }
+            }
+            Ok((trust >= required_trust).then(|| trust))
         };
 
         let max_height = if !ceiling_dirs.is_empty() {
-            let max_height = find_ceiling_height(&dir, &ceiling_dirs, cwd.as_ref());
+            let max_height = find_ceiling_height(&dir, ceiling_dirs, cwd.as_ref());
             if max_height.is_none() && match_ceiling_dir_or_error {
                 return Err(Error::NoMatchingCeilingDir);
             }
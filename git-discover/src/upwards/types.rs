@@ -42,13 +42,17 @@ pub struct Options<'a> {
     /// This defaults to [`Reduced`][git_sec::Trust::Reduced] as our default settings are geared towards avoiding abuse.
     /// Set it to `Full` to only see repositories that [are owned by the current user][git_sec::Trust::from_path_ownership()].
     pub required_trust: git_sec::Trust,
+    /// Directories of repositories to fully trust even if they aren't owned by the current user, similar to `safe.directory`.
+    ///
+    /// These are compared to the work tree of a candidate repository, or its git directory if it is bare, after resolving symlinks.
+    /// A single `*` trusts all repositories.
+    pub safe_directories: Vec<PathBuf>,
     /// When discovering a repository, ignore any repositories that are located in these directories or any of their parents.
     pub ceiling_dirs: Vec<PathBuf>,
     /// If true, and `ceiling_dirs` is not empty, we expect at least one ceiling directory to match or else there will be an error.
     pub match_ceiling_dir_or_error: bool,
     /// if `true` avoid crossing filesystem boundaries.
     /// Only supported on Unix-like systems.
-    // TODO: test on Linux
     // TODO: Handle WASI once https://github.com/rust-lang/rust/issues/71213 is resolved
     pub cross_fs: bool,
     /// If set, the _current working directory_ (absolute path) to use when resolving relative paths. Note that
@@ -62,6 +66,7 @@ impl Default for Options<'_> {
     fn default() -> Self {
         Options {
             required_trust: git_sec::Trust::Reduced,
+            safe_directories: Vec::new(),
             ceiling_dirs: vec![],
             match_ceiling_dir_or_error: true,
             cross_fs: false,
@@ -71,19 +76,40 @@ impl Default for Options<'_> {
 }
 
 impl Options<'_> {
+    /// Return true if `dir`, the work tree or bare git directory of a repository, is one of the
+    /// [`safe_directories`][Options::safe_directories] and is to be trusted fully.
+    pub fn is_safe_directory(&self, dir: &std::path::Path) -> bool {
+        if self.safe_directories.is_empty() {
+            return false;
+        }
+        let cwd = self.current_dir.unwrap_or_else(|| std::path::Path::new("."));
+        let realpath = |dir: &std::path::Path| {
+            let dir = git_path::realpath(dir).unwrap_or_else(|_| dir.to_owned());
+            crate::path::normalize(&dir, cwd).into_owned()
+        };
+        let dir = realpath(dir);
+        self.safe_directories
+            .iter()
+            .any(|safe_dir| safe_dir.as_os_str() == "*" || realpath(safe_dir) == dir)
+    }
+
     /// Loads discovery options overrides from the environment.
     ///
     /// The environment variables are:
     /// - `GIT_CEILING_DIRECTORIES` for `ceiling_dirs`
-    ///
-    /// Note that `GIT_DISCOVERY_ACROSS_FILESYSTEM` for `cross_fs` is **not** read,
-    /// as it requires parsing of `git-config` style boolean values.
-    // TODO: test
+    /// - `GIT_DISCOVERY_ACROSS_FILESYSTEM` for `cross_fs`, which is ignored if it isn't a valid `git-config` style boolean.
     pub fn apply_environment(mut self) -> Self {
         let name = "GIT_CEILING_DIRECTORIES";
         if let Some(ceiling_dirs) = env::var_os(name).and_then(|c| Vec::from_os_string(c).ok()) {
             self.ceiling_dirs = parse_ceiling_dirs(&ceiling_dirs);
         }
+        let name = "GIT_DISCOVERY_ACROSS_FILESYSTEM";
+        if let Some(cross_fs) = env::var_os(name)
+            .and_then(|v| Vec::from_os_string(v).ok())
+            .and_then(|v| git_config_value::Boolean::try_from(v.as_bstr()).ok())
+        {
+            self.cross_fs = cross_fs.into();
+        }
         self
     }
 }
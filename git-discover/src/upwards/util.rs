@@ -52,6 +52,7 @@ pub(crate) fn find_ceiling_height(search_dir: &Path, ceiling_dirs: &[PathBuf], c
             if !ceiling_dir.is_absolute() {
                 ceiling_dir = git_path::absolutize(cwd.join(ceiling_dir.as_ref()), cwd)?;
             }
+            let ceiling_dir = crate::path::normalize(&ceiling_dir, cwd);
             search_dir
                 .strip_prefix(ceiling_dir.as_ref())
                 .ok()
@@ -0,0 +1,115 @@
+use std::path::PathBuf;
+
+/// The error returned by [`walk()`][crate::walk()].
+#[derive(Debug, thiserror::Error)]
+#[allow(missing_docs)]
+pub enum Error {
+    #[error("Failed to access a directory, or path is not a directory: '{}'", .path.display())]
+    InaccessibleDirectory { path: PathBuf },
+}
+
+/// Options to control how [`walk()`][crate::walk()] traverses directories in search of repositories.
+#[derive(Default, Debug, Clone, Copy, Eq, PartialEq)]
+pub struct Options {
+    /// The maximum depth relative to the root directory at which work trees and bare repositories are still found,
+    /// with the root itself being at depth 0, or `None` to not limit the depth.
+    pub max_depth: Option<usize>,
+    /// If set, symbolic links to directories will be followed, which may lead to repositories being found more than once.
+    pub follow_links: bool,
+    /// The amount of threads to use for reading directories, with `Some(1)` reading them on the calling thread.
+    ///
+    /// Set to `None` or `Some(0)` to use as many threads as there are logical cores.
+    pub thread_limit: Option<usize>,
+    /// Control how strictly directories are validated to be repositories, which can be relaxed to find repositories faster.
+    pub is_git: crate::is_git::Options,
+}
+
+pub(crate) mod function {
+    use std::{ffi::OsStr, path::Path};
+
+    use git_features::fs::walkdir;
+
+    use super::{Error, Options};
+    use crate::{is_git_opts, repository, DOT_GIT_DIR};
+
+    /// Find all repositories in `root` and the directories beneath it, traversing directories in parallel as configured by `options`.
+    ///
+    /// Work trees are found by their `.git` directory or file, which is expected to be a valid repository. Bare repositories are
+    /// found as directories that look like a repository and aren't called `.git`.
+    /// Directories of found repositories aren't traversed any further, hence repositories nested in work trees, like submodules
+    /// or unrelated clones, are skipped along with the contents of `.git` directories.
+    /// Directories that can't be read are skipped as well.
+    ///
+    /// The returned paths are sorted by their location on disk, and are relative if `root` is relative.
+    /// Fail if `root` itself isn't an accessible directory.
+    pub fn walk(root: impl AsRef<Path>, options: Options) -> Result<Vec<repository::Path>, Error> {
+        let root = root.as_ref();
+        if !root.metadata().map_or(false, |m| m.is_dir()) {
+            return Err(Error::InaccessibleDirectory { path: root.into() });
+        }
+        let Options {
+            max_depth,
+            follow_links,
+            thread_limit,
+            is_git,
+        } = options;
+
+        let root_dir = root.to_owned();
+        let walk = walkdir::walkdir_generic_with::<((), Option<repository::Path>)>(
+            root,
+            walkdir::Options {
+                sorted: true,
+                follow_links,
+                parallelism: walkdir::Parallelism::from_thread_limit(thread_limit),
+            },
+        );
+        // The `.git` entry of a work tree is one level deeper than the work tree itself.
+        let walk = match max_depth {
+            Some(depth) => walk.max_depth(depth + 1),
+            None => walk,
+        };
+
+        Ok(walk
+            .process_read_dir(move |_depth, _dir, _read_dir_state, siblings| {
+                let dot_git = siblings
+                    .iter_mut()
+                    .flatten()
+                    .find(|e| e.file_name == OsStr::new(DOT_GIT_DIR));
+                if let Some(dot_git) = dot_git {
+                    let path = dot_git.path();
+                    if let Some(path) = is_git_opts(&path, is_git)
+                        .ok()
+                        .and_then(|kind| repository::Path::from_dot_git_dir(path, kind, &root_dir))
+                    {
+                        dot_git.client_state = Some(path);
+                        for entry in siblings.iter_mut().flatten() {
+                            entry.read_children_path = None;
+                        }
+                        return;
+                    }
+                }
+
+                for entry in siblings.iter_mut().flatten() {
+                    if entry.file_name == OsStr::new(DOT_GIT_DIR) {
+                        entry.read_children_path = None;
+                        continue;
+                    }
+                    if !entry.file_type.is_dir() || max_depth.map_or(false, |max| entry.depth > max) {
+                        continue;
+                    }
+                    let path = entry.path();
+                    if !path.join("HEAD").is_file() {
+                        continue;
+                    }
+                    if let Ok(kind @ repository::Kind::Bare) = is_git_opts(&path, is_git) {
+                        entry.client_state = repository::Path::from_dot_git_dir(path, kind, &root_dir);
+                        entry.read_children_path = None;
+                    }
+                }
+            })
+            .into_iter()
+            .filter_map(Result::ok)
+            .filter_map(|mut e| e.client_state.take())
+            .collect())
+    }
+}
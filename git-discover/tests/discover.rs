@@ -1,6 +1,9 @@
 pub use git_testtools::Result;
 
+mod environment;
 mod is_git;
 mod parse;
 mod path;
 mod upwards;
+#[cfg(feature = "walk")]
+mod walk;
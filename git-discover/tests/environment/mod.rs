@@ -0,0 +1,152 @@
+use std::path::{Path, PathBuf};
+
+use git_discover::{
+    environment::{Error, Overrides},
+    repository,
+};
+
+fn repo_path() -> crate::Result<PathBuf> {
+    Ok(git_path::realpath(git_testtools::scripted_fixture_repo_read_only(
+        "make_basic_repo.sh",
+    )?)?)
+}
+
+fn overrides(git_dir: impl Into<PathBuf>) -> Overrides {
+    Overrides {
+        git_dir: Some(git_dir.into()),
+        ..Default::default()
+    }
+}
+
+#[test]
+fn without_git_dir_the_repository_is_discovered() -> crate::Result {
+    let overrides = Overrides {
+        work_tree: Some("work-tree".into()),
+        common_dir: Some("common-dir".into()),
+        git_dir: None,
+    };
+    assert!(overrides.repository_path(&repo_path()?).is_none());
+    Ok(())
+}
+
+#[test]
+fn git_dir_relative_to_the_current_dir() -> crate::Result {
+    let repo = repo_path()?;
+    let path = overrides(".git").repository_path(&repo).expect("set")?;
+    assert_eq!(path, repository::Path::WorkTree(repo.clone()));
+
+    let path = overrides("../.git")
+        .repository_path(&repo.join("subdir"))
+        .expect("set")?;
+    assert_eq!(path, repository::Path::WorkTree(repo.clone()), "paths are normalized");
+
+    let path = overrides("bare.git").repository_path(&repo).expect("set")?;
+    assert_eq!(path, repository::Path::Repository(repo.join("bare.git")));
+    Ok(())
+}
+
+#[test]
+fn git_dir_files_are_followed() -> crate::Result {
+    let repo = repo_path()?;
+    let path = overrides("worktrees/a/.git").repository_path(&repo).expect("set")?;
+    assert_eq!(
+        path,
+        repository::Path::LinkedWorkTree {
+            work_dir: repo.join("worktrees/a"),
+            git_dir: repo.join(".git/worktrees/a"),
+        }
+    );
+    Ok(())
+}
+
+#[test]
+fn work_tree_takes_precedence() -> crate::Result {
+    let repo = repo_path()?;
+    let mut overrides = overrides(repo.join(".git"));
+    overrides.work_tree = Some("subdir".into());
+    let path = overrides.repository_path(&repo).expect("set")?;
+    assert_eq!(
+        path,
+        repository::Path::LinkedWorkTree {
+            work_dir: repo.join("subdir"),
+            git_dir: repo.join(".git"),
+        },
+        "the work tree doesn't contain the git dir"
+    );
+
+    overrides.work_tree = Some(repo.clone());
+    let path = overrides.repository_path(Path::new("/")).expect("set")?;
+    assert_eq!(
+        path,
+        repository::Path::WorkTree(repo.clone()),
+        "the git dir is the `.git` directory of the work tree"
+    );
+
+    overrides.git_dir = Some("worktrees/a/.git".into());
+    let path = overrides.repository_path(&repo).expect("set")?;
+    assert_eq!(
+        path,
+        repository::Path::LinkedWorkTree {
+            work_dir: repo.clone(),
+            git_dir: repo.join(".git/worktrees/a"),
+        },
+    );
+    Ok(())
+}
+
+#[test]
+fn common_dir_holds_objects_and_references() -> crate::Result {
+    let repo = repo_path()?;
+    let private_git_dir = git_testtools::tempfile::tempdir()?;
+    std::fs::write(private_git_dir.path().join("HEAD"), "ref: refs/heads/main\n")?;
+
+    let mut overrides = overrides(private_git_dir.path());
+    let err = overrides.repository_path(&repo).expect("set").unwrap_err();
+    assert!(
+        matches!(
+            err,
+            Error::NotARepository {
+                source: git_discover::is_git::Error::MissingObjectsDirectory { .. },
+                ..
+            }
+        ),
+        "{err:?}"
+    );
+
+    overrides.common_dir = Some(".git".into());
+    overrides.work_tree = Some(repo.clone());
+    let path = overrides.repository_path(&repo).expect("set")?;
+    assert_eq!(
+        path,
+        repository::Path::LinkedWorkTree {
+            work_dir: repo,
+            git_dir: private_git_dir.path().into(),
+        }
+    );
+    Ok(())
+}
+
+#[test]
+fn invalid_paths_are_rejected() -> crate::Result {
+    let repo = repo_path()?;
+    let err = overrides("does-not-exist")
+        .repository_path(&repo)
+        .expect("set")
+        .unwrap_err();
+    assert!(matches!(err, Error::NotARepository { .. }), "{err:?}");
+
+    let mut overrides = overrides(repo.join(".git"));
+    overrides.work_tree = Some("../..".into());
+    let err = overrides.repository_path(Path::new("/a")).expect("set").unwrap_err();
+    assert!(
+        matches!(
+            err,
+            Error::InvalidPath {
+                variable: "GIT_WORK_TREE",
+                ..
+            }
+        ),
+        "{err:?}"
+    );
+    Ok(())
+}
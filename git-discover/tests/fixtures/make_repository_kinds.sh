@@ -0,0 +1,19 @@
+#!/bin/bash
+set -eu -o pipefail
+
+git init -q nascent
+git init -q --bare bare-without-suffix
+
+git init -q module
+(cd module
+  git commit -q --allow-empty -m init
+)
+
+git init -q with-submodule
+(cd with-submodule
+  git commit -q --allow-empty -m init
+  git -c protocol.file.allow=always submodule add -q ../module m1
+  git commit -q -m "add module"
+  # an unpopulated submodule doesn't have an index yet
+  rm .git/modules/m1/index
+)
@@ -0,0 +1,17 @@
+#!/bin/bash
+set -eu -o pipefail
+
+git init -q work-tree
+(cd work-tree
+  git commit -q --allow-empty -m init
+  git worktree add -q ../linked-work-tree
+  # repositories within the work tree of another repository are skipped
+  git init -q nested
+)
+
+git init -q --bare bare.git
+git init -q one/two/three
+
+mkdir -p empty-dir invalid/.git dir-with-head/sub
+echo "ref: refs/heads/main" > dir-with-head/HEAD
+git init -q dir-with-head/sub/repo
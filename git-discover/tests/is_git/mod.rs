@@ -37,3 +37,91 @@ fn verify_on_exfat() -> crate::Result<()> {
     );
     Ok(())
 }
+
+#[test]
+fn repository_kinds() -> crate::Result {
+    use git_discover::repository::Kind;
+
+    let dir = git_testtools::scripted_fixture_repo_read_only("make_repository_kinds.sh")?;
+    assert_eq!(
+        git_discover::is_git(dir.join("nascent/.git"))?,
+        Kind::WorkTree { linked_git_dir: None },
+        "`.git` directories belong to a work tree even without an index"
+    );
+    assert_eq!(
+        git_discover::is_git(dir.join("bare-without-suffix"))?,
+        Kind::Bare,
+        "bare repositories don't need a `.git` suffix"
+    );
+    assert_eq!(
+        git_discover::is_git(dir.join("with-submodule/.git/modules/m1"))?,
+        Kind::SubmoduleGitDir,
+        "submodule git directories look bare without an index, but aren't"
+    );
+    assert_eq!(
+        git_discover::is_git(dir.join("with-submodule/m1/.git"))?,
+        Kind::Submodule {
+            git_dir: dir.join("with-submodule/m1/../.git/modules/m1")
+        }
+    );
+    Ok(())
+}
+
+#[test]
+fn linked_worktree_kinds() -> crate::Result {
+    use git_discover::repository::Kind;
+
+    let repo = git_path::realpath(git_testtools::scripted_fixture_repo_read_only("make_basic_repo.sh")?)?;
+    assert_eq!(
+        git_discover::is_git(repo.join(".git/worktrees/a"))?,
+        Kind::WorkTreeGitDir {
+            work_dir: repo.join("worktrees/a")
+        },
+        "the private git dir of a worktree is detected by its `commondir` file"
+    );
+    assert_eq!(
+        git_discover::is_git(repo.join("worktrees/a/.git"))?,
+        Kind::WorkTree {
+            linked_git_dir: Some(repo.join(".git/worktrees/a"))
+        }
+    );
+    assert_eq!(git_discover::is_git(repo.join("bare.git"))?, Kind::Bare);
+    Ok(())
+}
+
+#[test]
+fn validation_can_be_relaxed_with_options() -> crate::Result {
+    use git_discover::{is_git, repository::Kind};
+
+    let dir = git_testtools::tempfile::tempdir()?;
+    let git_dir = dir.path().join("repo.git");
+    std::fs::create_dir_all(git_dir.join("objects"))?;
+    std::fs::write(git_dir.join("HEAD"), "not a reference")?;
+
+    let err = git_discover::is_git(&git_dir).unwrap_err();
+    assert!(matches!(err, is_git::Error::FindHeadRef(_)), "{err:?}");
+
+    let lenient_head = is_git::Options {
+        validate_head: false,
+        ..Default::default()
+    };
+    let err = git_discover::is_git_opts(&git_dir, lenient_head).unwrap_err();
+    assert!(
+        matches!(err, is_git::Error::MissingRefsDirectory { .. }),
+        "HEAD isn't read anymore, but refs are still required: {err:?}"
+    );
+
+    let lenient = is_git::Options {
+        validate_head: false,
+        require_refs_dir: false,
+    };
+    assert_eq!(git_discover::is_git_opts(&git_dir, lenient)?, Kind::Bare);
+
+    std::fs::remove_file(git_dir.join("HEAD"))?;
+    let err = git_discover::is_git_opts(&git_dir, lenient).unwrap_err();
+    assert!(
+        matches!(err, is_git::Error::MissingHead { ref missing } if missing == &git_dir.join("HEAD")),
+        "HEAD is still required to exist: {err:?}"
+    );
+    Ok(())
+}
@@ -63,6 +63,66 @@ fn upwards_with_relative_directories_and_optional_ceiling() -> git_testtools::Re
     Ok(())
 }
 
+#[test]
+#[serial]
+fn upwards_options_from_environment() -> git_testtools::Result {
+    let repo = git_testtools::scripted_fixture_repo_read_only("make_basic_repo.sh")?;
+    let repo = git_path::realpath(repo)?;
+
+    {
+        let _env = git_testtools::Env::new()
+            .set(
+                "GIT_CEILING_DIRECTORIES",
+                format!("relative:{}", repo.join("some").display()),
+            )
+            .set("GIT_DISCOVERY_ACROSS_FILESYSTEM", "yes");
+        let opts = Options::default().apply_environment();
+        assert_eq!(opts.ceiling_dirs, vec![repo.join("some")], "relative paths are ignored");
+        assert!(opts.cross_fs);
+
+        let err = git_discover::upwards_opts(repo.join("some/very/deeply"), opts).unwrap_err();
+        assert!(matches!(
+            err,
+            git_discover::upwards::Error::NoGitRepositoryWithinCeiling { ceiling_height: 3, .. }
+        ));
+    }
+
+    {
+        let _env = git_testtools::Env::new()
+            .unset("GIT_CEILING_DIRECTORIES")
+            .set("GIT_DISCOVERY_ACROSS_FILESYSTEM", "not a boolean");
+        let opts = Options {
+            cross_fs: true,
+            ..Default::default()
+        }
+        .apply_environment();
+        assert!(opts.ceiling_dirs.is_empty());
+        assert!(opts.cross_fs, "invalid values are ignored");
+
+        let _env = git_testtools::Env::new().set("GIT_DISCOVERY_ACROSS_FILESYSTEM", "0");
+        assert!(!opts.apply_environment().cross_fs);
+    }
+    Ok(())
+}
+
+#[test]
+#[serial]
+fn environment_overrides_from_env() {
+    let _env = git_testtools::Env::new()
+        .set("GIT_DIR", "git-dir")
+        .set("GIT_WORK_TREE", "")
+        .set("GIT_COMMON_DIR", "/common-dir");
+    assert_eq!(
+        git_discover::environment::Overrides::from_env(),
+        git_discover::environment::Overrides {
+            git_dir: Some("git-dir".into()),
+            work_tree: None,
+            common_dir: Some("/common-dir".into()),
+        },
+        "empty values are ignored"
+    );
+}
+
 fn assert_repo_is_current_workdir(path: git_discover::repository::Path, work_dir: &Path) {
     assert_eq!(
         path.into_repository_and_work_tree_directories().1.expect("work dir"),
@@ -43,3 +43,60 @@ mod from_git_dir_file {
         Ok(file)
     }
 }
+
+mod normalize {
+    use std::path::Path;
+
+    use git_discover::path::normalize;
+
+    #[cfg(not(windows))]
+    #[test]
+    fn paths_are_unchanged() {
+        for path in ["relative/../path", "/absolute/path", "/", ""] {
+            assert_eq!(normalize(Path::new(path), Path::new("/cwd")), Path::new(path));
+        }
+    }
+
+    #[cfg(windows)]
+    #[test]
+    fn verbatim_prefixes_are_removed_and_drive_letters_are_uppercase() {
+        let cwd = Path::new(r"C:\cwd");
+        for (input, expected) in [
+            (r"\\?\C:\does-not-exist\dir", r"C:\does-not-exist\dir"),
+            (r"c:\does-not-exist", r"C:\does-not-exist"),
+            (r"\\?\UNC\server\share\does-not-exist", r"\\server\share\does-not-exist"),
+            (r"\\server\share\does-not-exist", r"\\server\share\does-not-exist"),
+        ] {
+            assert_eq!(normalize(Path::new(input), cwd), Path::new(expected), "{input}");
+        }
+    }
+
+    #[cfg(windows)]
+    #[test]
+    fn drive_relative_paths_are_resolved_against_the_current_dir_on_the_same_drive() {
+        assert_eq!(
+            normalize(Path::new(r"c:does-not-exist\dir"), Path::new(r"C:\cwd")),
+            Path::new(r"C:\cwd\does-not-exist\dir")
+        );
+        assert_eq!(
+            normalize(Path::new(r"Z:does-not-exist"), Path::new(r"C:\cwd")),
+            Path::new(r"Z:\does-not-exist"),
+            "the current dir of other drives isn't known"
+        );
+    }
+
+    #[cfg(windows)]
+    #[test]
+    fn existing_paths_are_canonicalized() -> crate::Result {
+        let dir = git_testtools::tempfile::tempdir()?;
+        std::fs::create_dir(dir.path().join("Long Directory Name"))?;
+        let expected = normalize(&dir.path().join("Long Directory Name"), dir.path()).into_owned();
+        assert!(!expected.to_string_lossy().starts_with(r"\\?\"));
+        assert_eq!(
+            normalize(&dir.path().join("long directory name"), dir.path()),
+            expected,
+            "the casing on disk is used"
+        );
+        Ok(())
+    }
+}
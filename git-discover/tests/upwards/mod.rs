@@ -265,6 +265,96 @@ fn cross_fs() -> crate::Result {
     Ok(())
 }
 
+#[cfg(target_os = "linux")]
+#[test]
+fn cross_fs_stops_at_device_boundaries() -> crate::Result {
+    use std::{os::linux::fs::MetadataExt, path::Path};
+
+    use git_discover::upwards::{Error, Options};
+    // `/proc` is a mount of its own, so searching upwards from it leaves its device when reaching the root.
+    let proc = Path::new("/proc");
+    if proc.metadata()?.st_dev() == Path::new("/").metadata()?.st_dev() {
+        return Ok(());
+    }
+
+    let err = git_discover::upwards(proc).expect_err("discovery stops at the root which is on another device");
+    assert!(
+        matches!(&err, Error::NoGitRepositoryWithinFs { path, limit } if path == proc && limit == Path::new("/")),
+        "{err:?}"
+    );
+
+    let err = git_discover::upwards_opts(
+        proc,
+        Options {
+            cross_fs: true,
+            ..Default::default()
+        },
+    )
+    .expect_err("there is no repository in the root either");
+    assert!(matches!(err, Error::NoGitRepository { .. }), "{err:?}");
+    Ok(())
+}
+
+#[test]
+fn required_trust_is_met_by_ownership_or_safe_directories() -> crate::Result {
+    use git_discover::upwards::{Error, Options};
+    use git_sec::Trust;
+
+    let repo = repo_path()?;
+    let res = git_discover::upwards_opts(
+        repo.join("subdir"),
+        Options {
+            required_trust: Trust::Full,
+            ..Default::default()
+        },
+    );
+    match expected_trust() {
+        Trust::Full => assert_eq!(res?.1, Trust::Full),
+        Trust::Reduced => assert!(matches!(res, Err(Error::NoTrustedGitRepository { .. }))),
+    }
+
+    for safe_dir in [repo.clone(), "*".into()] {
+        let (path, trust) = git_discover::upwards_opts(
+            repo.join("subdir"),
+            Options {
+                required_trust: Trust::Full,
+                safe_directories: vec![safe_dir],
+                ..Default::default()
+            },
+        )?;
+        assert_eq!(
+            trust,
+            Trust::Full,
+            "safe directories are trusted regardless of their owner"
+        );
+        assert_eq!(path.kind(), Kind::WorkTree { linked_git_dir: None });
+    }
+    Ok(())
+}
+
+#[test]
+fn safe_directories_match_work_trees_and_bare_repositories() -> crate::Result {
+    use git_discover::upwards::Options;
+
+    let repo = repo_path()?;
+    let opts = Options {
+        safe_directories: vec![repo.join("subdir/.."), repo.join("bare.git")],
+        ..Default::default()
+    };
+    assert!(opts.is_safe_directory(&repo), "paths are normalized");
+    assert!(opts.is_safe_directory(&repo.join("bare.git")));
+    assert!(
+        !opts.is_safe_directory(&repo.join("worktrees/a")),
+        "linked worktrees have to be listed separately"
+    );
+    assert!(
+        !opts.is_safe_directory(&repo.join("subdir")),
+        "there is no prefix matching"
+    );
+    assert!(!Options::default().is_safe_directory(&repo));
+    Ok(())
+}
+
 #[test]
 fn do_not_shorten_absolute_paths() -> crate::Result {
     let top_level_repo = repo_path()?.canonicalize().expect("repo path exists");
@@ -0,0 +1,87 @@
+use std::path::PathBuf;
+
+use git_discover::{repository, walk};
+
+fn root() -> crate::Result<PathBuf> {
+    Ok(git_path::realpath(git_testtools::scripted_fixture_repo_read_only(
+        "make_walk.sh",
+    )?)?)
+}
+
+#[test]
+fn all_repositories_are_found_without_entering_work_trees() -> crate::Result {
+    let root = root()?;
+    for thread_limit in [Some(1), None] {
+        let paths = git_discover::walk(
+            &root,
+            walk::Options {
+                thread_limit,
+                ..Default::default()
+            },
+        )?;
+        assert_eq!(
+            paths,
+            vec![
+                repository::Path::Repository(root.join("bare.git")),
+                repository::Path::WorkTree(root.join("dir-with-head/sub/repo")),
+                repository::Path::LinkedWorkTree {
+                    work_dir: root.join("linked-work-tree"),
+                    git_dir: root.join("work-tree/.git/worktrees/linked-work-tree"),
+                },
+                repository::Path::WorkTree(root.join("one/two/three")),
+                repository::Path::WorkTree(root.join("work-tree")),
+            ],
+            "nested repositories are skipped, and the order is stable with {thread_limit:?} threads"
+        );
+    }
+    Ok(())
+}
+
+#[test]
+fn max_depth_limits_the_depth_of_found_repositories() -> crate::Result {
+    let root = root()?;
+    let walk = |max_depth| {
+        git_discover::walk(
+            &root,
+            walk::Options {
+                max_depth: Some(max_depth),
+                ..Default::default()
+            },
+        )
+    };
+    assert_eq!(walk(1)?.len(), 3, "only repositories directly in the root");
+    assert_eq!(walk(2)?.len(), 3);
+    assert_eq!(walk(3)?.len(), 5);
+
+    let paths = walk(0)?;
+    assert!(paths.is_empty(), "the root itself isn't a repository");
+    assert_eq!(
+        git_discover::walk(
+            root.join("work-tree"),
+            walk::Options {
+                max_depth: Some(0),
+                ..Default::default()
+            }
+        )?,
+        vec![repository::Path::WorkTree(root.join("work-tree"))],
+        "the root is at depth 0"
+    );
+    assert_eq!(
+        git_discover::walk(root.join("bare.git"), Default::default())?,
+        vec![repository::Path::Repository(root.join("bare.git"))]
+    );
+    Ok(())
+}
+
+#[test]
+fn inaccessible_roots_are_an_error() -> crate::Result {
+    let root = root()?;
+    for path in [root.join("does-not-exist"), root.join("dir-with-head/HEAD")] {
+        let err = git_discover::walk(&path, Default::default()).unwrap_err();
+        assert!(
+            matches!(err, walk::Error::InaccessibleDirectory { path: ref p } if p == &path),
+            "{err:?}"
+        );
+    }
+    Ok(())
+}
@@ -0,0 +1,158 @@
+//! A bump-allocated arena for bytes, typically paths, which are referred to by stable offsets.
+//!
+//! Data structures with many entries, like the entries of an index or those of a tree traversal, can store the paths of
+//! all entries in an [`Arena`] to avoid an allocation per path, and refer to them by [`Range`]s.
+//!
+//! Arenas filled by different threads can be [appended][Arena::append()] to each other without copying their bytes,
+//! in which case the ranges of the appended arena are shifted by the returned offset.
+use std::ops::Range;
+
+/// A block of memory which is never reallocated once it's created, so bytes within it don't move.
+#[derive(Debug, Clone)]
+struct Segment {
+    /// The offset of the first byte in this segment within the arena.
+    start: usize,
+    data: Vec<u8>,
+}
+
+impl Segment {
+    fn end(&self) -> usize {
+        self.start + self.data.len()
+    }
+
+    fn has_room_for(&self, len: usize) -> bool {
+        self.data.capacity() - self.data.len() >= len
+    }
+}
+
+/// A bump-allocated arena for bytes which refers to them by offset ranges that are stable for the lifetime of the arena.
+///
+/// Bytes are stored in segments which are never reallocated, and each [push][Arena::push()] stores its bytes within a
+/// single segment.
+#[derive(Default, Debug, Clone)]
+pub struct Arena {
+    segments: Vec<Segment>,
+}
+
+/// The smallest amount of bytes to allocate for a new segment.
+const MIN_SEGMENT_CAPACITY: usize = 4096;
+
+/// Init
+impl Arena {
+    /// Create a new empty arena which allocates only when bytes are pushed.
+    pub fn new() -> Self {
+        Arena::default()
+    }
+
+    /// Create a new empty arena which can hold `capacity` bytes without allocating.
+    pub fn with_capacity(capacity: usize) -> Self {
+        Arena {
+            segments: vec![Segment {
+                start: 0,
+                data: Vec::with_capacity(capacity),
+            }],
+        }
+    }
+}
+
+impl From<Vec<u8>> for Arena {
+    /// Use `bytes` as the first segment of a new arena, keeping the offsets of all bytes within it.
+    fn from(bytes: Vec<u8>) -> Self {
+        Arena {
+            segments: vec![Segment { start: 0, data: bytes }],
+        }
+    }
+}
+
+/// Mutation
+impl Arena {
+    /// Store `bytes` in the arena and return the range at which they can be [retrieved][Arena::get()].
+    pub fn push(&mut self, bytes: &[u8]) -> Range<usize> {
+        let segment = match self.segments.last_mut() {
+            Some(segment) if segment.has_room_for(bytes.len()) => segment,
+            last => {
+                let (start, previous_capacity) = last.map_or((0, 0), |s| (s.end(), s.data.capacity()));
+                self.segments.push(Segment {
+                    start,
+                    data: Vec::with_capacity((previous_capacity * 2).max(MIN_SEGMENT_CAPACITY).max(bytes.len())),
+                });
+                self.segments.last_mut().expect("just pushed")
+            }
+        };
+        let start = segment.end();
+        segment.data.extend_from_slice(bytes);
+        start..segment.end()
+    }
+
+    /// Move all segments of `other` to the end of this arena without copying their bytes, and return the offset to add to all
+    /// ranges obtained from `other` to retrieve their bytes from this arena.
+    pub fn append(&mut self, other: Arena) -> usize {
+        let offset = self.len();
+        self.segments.extend(
+            other
+                .segments
+                .into_iter()
+                .filter(|segment| !segment.data.is_empty())
+                .map(|mut segment| {
+                    segment.start += offset;
+                    segment
+                }),
+        );
+        offset
+    }
+}
+
+/// Access
+impl Arena {
+    /// The amount of bytes stored in the arena.
+    pub fn len(&self) -> usize {
+        self.segments.last().map_or(0, Segment::end)
+    }
+
+    /// Return true if no bytes are stored in the arena.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Return the bytes at `range` as returned by [`push()`][Arena::push()], or `None` if the range is out of bounds or
+    /// doesn't lie within the bytes of a single push.
+    pub fn get(&self, range: Range<usize>) -> Option<&[u8]> {
+        if range.start == range.end {
+            return (range.end <= self.len()).then(|| &[][..]);
+        }
+        let segment_index = self.segments.partition_point(|segment| segment.end() <= range.start);
+        let segment = self.segments.get(segment_index)?;
+        if range.start < segment.start || range.end > segment.end() {
+            return None;
+        }
+        segment.data.get(range.start - segment.start..range.end - segment.start)
+    }
+
+    /// Transform this arena into a contiguous buffer in which all bytes are located at their offset, copying them only if there
+    /// is more than one segment.
+    pub fn into_vec(self) -> Vec<u8> {
+        let len = self.len();
+        let mut segments = self.segments.into_iter().filter(|segment| !segment.data.is_empty());
+        match segments.next() {
+            None => Vec::new(),
+            Some(first) if first.end() == len => first.data,
+            Some(first) => {
+                let mut out = Vec::with_capacity(len);
+                out.extend_from_slice(&first.data);
+                for segment in segments {
+                    out.extend_from_slice(&segment.data);
+                }
+                out
+            }
+        }
+    }
+}
+
+impl std::ops::Index<Range<usize>> for Arena {
+    type Output = [u8];
+
+    fn index(&self, range: Range<usize>) -> &Self::Output {
+        self.get(range.clone())
+            .unwrap_or_else(|| panic!("range {:?} must have been returned by this arena", range))
+    }
+}
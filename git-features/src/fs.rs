@@ -6,29 +6,112 @@
 //! For information on how to use the [`WalkDir`] type, have a look at
 //! * [`jwalk::WalkDir`](https://docs.rs/jwalk/0.5.1/jwalk/type.WalkDir.html) if `parallel` feature is enabled
 //! * [walkdir::WalkDir](https://docs.rs/walkdir/2.3.1/walkdir/struct.WalkDir.html) otherwise
+//!
+//! Use [`walkdir_with()`] to configure sorting, symlink handling and parallelism the same way for both.
+#[cfg(any(feature = "walkdir", feature = "fs-walkdir-parallel"))]
+mod shared {
+    /// How to distribute the work of reading directories, only effective with the `fs-walkdir-parallel` feature.
+    #[derive(Debug, Clone, Copy, Eq, PartialEq)]
+    pub enum Parallelism {
+        /// Read all directories on the calling thread.
+        Serial,
+        /// Read directories in parallel on a thread pool.
+        ThreadPool {
+            /// The amount of threads of a new thread pool for this traversal, or `None` to use the global thread pool.
+            thread_limit: Option<usize>,
+        },
+    }
+
+    impl Default for Parallelism {
+        fn default() -> Self {
+            Parallelism::Serial
+        }
+    }
+
+    impl Parallelism {
+        /// Read directories serially if `thread_limit` is `Some(1)`, or use as many threads as there are logical cores if
+        /// it is `None` or `Some(0)`.
+        pub fn from_thread_limit(thread_limit: Option<usize>) -> Self {
+            match thread_limit {
+                Some(1) => Parallelism::Serial,
+                Some(0) | None => Parallelism::ThreadPool { thread_limit: None },
+                Some(threads) => Parallelism::ThreadPool {
+                    thread_limit: Some(threads),
+                },
+            }
+        }
+    }
+
+    /// Options to configure directory iterators created with [`walkdir_with()`][super::walkdir_with()].
+    ///
+    /// Hidden files are never skipped, and the file type of each entry is obtained along with its directory entry where
+    /// the platform supports it, so it can be queried without additional system calls.
+    #[derive(Default, Debug, Clone, Copy, Eq, PartialEq)]
+    pub struct Options {
+        /// If true, the entries of each directory are returned sorted by their file name, making the traversal deterministic.
+        pub sorted: bool,
+        /// If true, symbolic links are followed and entries have the type of the link target.
+        /// Otherwise entries of symbolic links are returned as such, and directories they point to are not traversed.
+        pub follow_links: bool,
+        /// How to distribute the work of reading directories.
+        pub parallelism: Parallelism,
+    }
+}
+
 #[cfg(feature = "fs-walkdir-parallel")]
 ///
 pub mod walkdir {
     use std::path::Path;
 
-    pub use jwalk::{DirEntry as DirEntryGeneric, DirEntryIter as DirEntryIterGeneric, Error, WalkDir};
+    pub use jwalk::{
+        ClientState, DirEntry as DirEntryGeneric, DirEntryIter as DirEntryIterGeneric, Error, WalkDir, WalkDirGeneric,
+    };
+
+    pub use super::shared::{Options, Parallelism};
 
     /// An alias for an uncustomized directory entry to match the one of the non-parallel version offered by `walkdir`.
     pub type DirEntry = DirEntryGeneric<((), ())>;
 
     /// Instantiate a new directory iterator which will not skip hidden files.
     pub fn walkdir_new(root: impl AsRef<Path>) -> WalkDir {
-        WalkDir::new(root)
-            .skip_hidden(false)
-            .parallelism(jwalk::Parallelism::Serial)
+        walkdir_with(root, Options::default())
     }
 
     /// Instantiate a new directory iterator which will not skip hidden files and is sorted
     pub fn walkdir_sorted_new(root: impl AsRef<Path>) -> WalkDir {
-        WalkDir::new(root)
+        walkdir_with(
+            root,
+            Options {
+                sorted: true,
+                ..Default::default()
+            },
+        )
+    }
+
+    /// Instantiate a new directory iterator configured by `options`.
+    pub fn walkdir_with(root: impl AsRef<Path>, options: Options) -> WalkDir {
+        walkdir_generic_with(root, options)
+    }
+
+    /// Instantiate a new directory iterator configured by `options`, which allows to associate custom state with each
+    /// directory entry for use in [`process_read_dir()`][WalkDirGeneric::process_read_dir()].
+    pub fn walkdir_generic_with<C: ClientState>(root: impl AsRef<Path>, options: Options) -> WalkDirGeneric<C> {
+        let Options {
+            sorted,
+            follow_links,
+            parallelism,
+        } = options;
+        WalkDirGeneric::new(root)
             .skip_hidden(false)
-            .sort(true)
-            .parallelism(jwalk::Parallelism::Serial)
+            .sort(sorted)
+            .follow_links(follow_links)
+            .parallelism(match parallelism {
+                Parallelism::Serial => jwalk::Parallelism::Serial,
+                Parallelism::ThreadPool { thread_limit: None } => jwalk::Parallelism::RayonDefaultPool,
+                Parallelism::ThreadPool {
+                    thread_limit: Some(threads),
+                } => jwalk::Parallelism::RayonNewPool(threads),
+            })
     }
 
     /// The Iterator yielding directory items
@@ -42,14 +125,38 @@ pub mod walkdir {
 
     pub use walkdir::{DirEntry, Error, WalkDir};
 
+    pub use super::shared::{Options, Parallelism};
+
     /// Instantiate a new directory iterator which will not skip hidden files.
     pub fn walkdir_new(root: impl AsRef<Path>) -> WalkDir {
-        WalkDir::new(root)
+        walkdir_with(root, Options::default())
     }
 
     /// Instantiate a new directory iterator which will not skip hidden files and is sorted
     pub fn walkdir_sorted_new(root: impl AsRef<Path>) -> WalkDir {
-        WalkDir::new(root).sort_by_file_name()
+        walkdir_with(
+            root,
+            Options {
+                sorted: true,
+                ..Default::default()
+            },
+        )
+    }
+
+    /// Instantiate a new directory iterator configured by `options`, reading all directories on the calling thread
+    /// regardless of the configured parallelism.
+    pub fn walkdir_with(root: impl AsRef<Path>, options: Options) -> WalkDir {
+        let Options {
+            sorted,
+            follow_links,
+            parallelism: _,
+        } = options;
+        let walk = WalkDir::new(root).follow_links(follow_links);
+        if sorted {
+            walk.sort_by_file_name()
+        } else {
+            walk
+        }
     }
 
     /// The Iterator yielding directory items
@@ -57,7 +164,7 @@ pub mod walkdir {
 }
 
 #[cfg(any(feature = "walkdir", feature = "fs-walkdir-parallel"))]
-pub use self::walkdir::{walkdir_new, walkdir_sorted_new, WalkDir};
+pub use self::walkdir::{walkdir_new, walkdir_sorted_new, walkdir_with, WalkDir};
 
 /// Prepare open options which won't follow symlinks when the file is opened.
 ///
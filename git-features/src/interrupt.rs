@@ -1,9 +1,80 @@
 //! Utilities to cause interruptions in common traits, like Read/Write and Iterator.
+//!
+//! Long-running operations take a `should_interrupt: &AtomicBool` flag which they poll regularly, failing with an error
+//! once it's set. Operations which don't take a flag poll [`IS_INTERRUPTED`] instead, which is the process-wide flag that
+//! signal handlers [trigger][trigger()] to abort cleanly on Ctrl+C. It can be passed to all other operations as well.
 use std::{
     io,
     sync::atomic::{AtomicBool, Ordering},
 };
 
+/// The process-wide flag to signal an interrupt, for use by signal handlers and operations which don't take a flag themselves.
+pub static IS_INTERRUPTED: AtomicBool = AtomicBool::new(false);
+
+/// Returns true if an interrupt is requested via [`IS_INTERRUPTED`].
+pub fn is_triggered() -> bool {
+    IS_INTERRUPTED.load(Ordering::Relaxed)
+}
+
+/// Trigger an interrupt via [`IS_INTERRUPTED`], signalling to those checking for [`is_triggered()`] to stop what they are doing.
+///
+/// This function only stores an atomic and is safe to call from signal handlers.
+pub fn trigger() {
+    IS_INTERRUPTED.store(true, Ordering::SeqCst);
+}
+
+/// Sets the interrupt request of [`IS_INTERRUPTED`] to false, thus allowing those checking for [`is_triggered()`] to proceed.
+pub fn reset() {
+    IS_INTERRUPTED.store(false, Ordering::SeqCst);
+}
+
+/// A guard which sets its flag when dropped.
+///
+/// This is useful to stop threads polling the flag once the scope that spawned them is left, even if that happens early
+/// due to an error or a panic.
+pub struct TriggerOnDrop<'a> {
+    /// The flag to set when dropped.
+    pub flag: &'a AtomicBool,
+}
+
+impl<'a> TriggerOnDrop<'a> {
+    /// Create a new guard to set `flag` once it's dropped.
+    pub fn new(flag: &'a AtomicBool) -> Self {
+        TriggerOnDrop { flag }
+    }
+}
+
+impl Drop for TriggerOnDrop<'_> {
+    fn drop(&mut self) {
+        self.flag.store(true, Ordering::SeqCst);
+    }
+}
+
+/// A guard which clears its flag when dropped.
+///
+/// This is useful when interrupting one operation shouldn't affect the ones that follow, as in interactive programs.
+pub struct ResetOnDrop<'a> {
+    /// The flag to clear when dropped.
+    pub flag: &'a AtomicBool,
+}
+
+impl<'a> ResetOnDrop<'a> {
+    /// Create a new guard to clear `flag` once it's dropped.
+    pub fn new(flag: &'a AtomicBool) -> Self {
+        ResetOnDrop { flag }
+    }
+}
+
+impl Drop for ResetOnDrop<'_> {
+    fn drop(&mut self) {
+        self.flag.store(false, Ordering::SeqCst);
+    }
+}
+
+fn interrupted() -> io::Error {
+    io::Error::new(io::ErrorKind::Other, "Interrupted")
+}
+
 /// A wrapper for an inner iterator which will check for interruptions on each iteration, stopping the iteration when
 /// that is requested.
 pub struct Iter<'a, I> {
@@ -105,7 +176,7 @@ where
 {
     fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
         if self.should_interrupt.load(Ordering::Relaxed) {
-            return Err(std::io::Error::new(std::io::ErrorKind::Other, "Interrupted"));
+            return Err(interrupted());
         }
         self.inner.read(buf)
     }
@@ -123,3 +194,32 @@ where
         self.inner.consume(amt)
     }
 }
+
+/// A wrapper for implementors of [`std::io::Write`] with interrupt support.
+///
+/// It fails a [write][`std::io::Write::write`] or [flush][`std::io::Write::flush`] while an interrupt was requested.
+pub struct Write<'a, W> {
+    /// The actual implementor of [`std::io::Write`] to which interrupt support will be added.
+    pub inner: W,
+    /// The flag to trigger interruption
+    pub should_interrupt: &'a AtomicBool,
+}
+
+impl<'a, W> io::Write for Write<'a, W>
+where
+    W: io::Write,
+{
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        if self.should_interrupt.load(Ordering::Relaxed) {
+            return Err(interrupted());
+        }
+        self.inner.write(buf)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        if self.should_interrupt.load(Ordering::Relaxed) {
+            return Err(interrupted());
+        }
+        self.inner.flush()
+    }
+}
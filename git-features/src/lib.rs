@@ -14,6 +14,7 @@
 #![cfg_attr(docsrs, feature(doc_cfg, doc_auto_cfg))]
 #![deny(missing_docs, rust_2018_idioms, unsafe_code)]
 
+pub mod arena;
 ///
 pub mod cache;
 ///
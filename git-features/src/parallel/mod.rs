@@ -15,6 +15,11 @@
 //! operation which runs as fast as possible, which is cancellable only by merit of stopping the input or stopping the output
 //! aggregation.
 //!
+//! ### in_parallel_in_order(…)
+//!
+//! Works exactly as [`in_parallel()`], but feeds the [`Reduce`] in the order of the input with the help of [`reduce::InOrder`],
+//! for when the result depends on the order in which outputs are aggregated.
+//!
 //! ### `reduce::Stepwise`
 //!
 //! The [`Stepwise`][reduce::Stepwise] iterator works exactly as [`in_parallel()`] except that the processing of the output produced by
@@ -175,6 +180,58 @@ where
     serial::in_parallel(input, thread_limit, new_thread_state, consume, reducer)
 }
 
+/// Like [`in_parallel()`], but `reducer` is fed the outputs of `consume` in the order of their `input`, no matter in which
+/// order the threads produce them, which keeps the result deterministic.
+///
+/// Outputs produced early are kept until it's their turn, so it's best to [chunk][crate::iter::Chunks] the `input` to keep
+/// their amount low and the work per item high.
+///
+/// For parameters, see the documentation of [`in_parallel()`]
+#[cfg(feature = "parallel")]
+pub fn in_parallel_in_order<I, S, O, R>(
+    input: impl Iterator<Item = I> + Send,
+    thread_limit: Option<usize>,
+    new_thread_state: impl Fn(usize) -> S + Send + Clone,
+    consume: impl Fn(I, &mut S) -> O + Send + Clone,
+    reducer: R,
+) -> Result<<R as Reduce>::Output, <R as Reduce>::Error>
+where
+    R: Reduce<Input = O>,
+    I: Send,
+    O: Send,
+{
+    if num_threads(thread_limit) > 1 {
+        in_parallel(
+            input.enumerate(),
+            thread_limit,
+            new_thread_state,
+            move |(id, item), state| (id, consume(item, state)),
+            reduce::InOrder::new(reducer),
+        )
+    } else {
+        serial::in_parallel(input, thread_limit, new_thread_state, consume, reducer)
+    }
+}
+
+/// Like [`in_parallel()`], but `reducer` is fed the outputs of `consume` in the order of their `input`.
+///
+/// For parameters, see the documentation of [`in_parallel()`]
+///
+/// Note that the non-parallel version is equivalent to [`in_parallel()`] as it processes all items in order anyway.
+#[cfg(not(feature = "parallel"))]
+pub fn in_parallel_in_order<I, S, O, R>(
+    input: impl Iterator<Item = I>,
+    thread_limit: Option<usize>,
+    new_thread_state: impl Fn(usize) -> S,
+    consume: impl Fn(I, &mut S) -> O,
+    reducer: R,
+) -> Result<<R as Reduce>::Output, <R as Reduce>::Error>
+where
+    R: Reduce<Input = O>,
+{
+    serial::in_parallel(input, thread_limit, new_thread_state, consume, reducer)
+}
+
 ///
 pub mod reduce;
 pub use reduce::Reduce;
@@ -205,10 +205,12 @@ mod stepped {
     }
 }
 
-use std::marker::PhantomData;
+use std::{collections::BTreeMap, marker::PhantomData};
 
 pub use stepped::Stepwise;
 
+use crate::parallel::SequenceId;
+
 /// An trait for aggregating items commonly produced in threads into a single result, without itself
 /// needing to be thread safe.
 pub trait Reduce {
@@ -267,6 +269,59 @@ impl<Input, Error> Reduce for IdentityWithResult<Input, Error> {
     }
 }
 
+/// A reducer which feeds the items it receives out of order to its `inner` reducer in the order of their [`SequenceId`], starting at 0.
+///
+/// This makes the reduction deterministic no matter in which order threads finish their work,
+/// see [`in_parallel_in_order()`][crate::parallel::in_parallel_in_order()].
+pub struct InOrder<R: Reduce> {
+    inner: R,
+    store: BTreeMap<SequenceId, R::Input>,
+    next: SequenceId,
+}
+
+impl<R: Reduce> InOrder<R> {
+    /// Create a new instance to feed all items to `inner` in order.
+    pub fn new(inner: R) -> Self {
+        InOrder {
+            inner,
+            store: Default::default(),
+            next: 0,
+        }
+    }
+}
+
+impl<R: Reduce> Reduce for InOrder<R> {
+    type Input = (SequenceId, R::Input);
+    /// What `inner` produced for each of the items that could be fed in order, which may be none at all.
+    type FeedProduce = Vec<R::FeedProduce>;
+    type Output = R::Output;
+    type Error = R::Error;
+
+    fn feed(&mut self, (id, item): Self::Input) -> Result<Self::FeedProduce, Self::Error> {
+        let previous = self.store.insert(id, item);
+        assert!(
+            previous.is_none(),
+            "BUG: each sequence id must be used only once, {} was seen again",
+            id
+        );
+        let mut out = Vec::new();
+        while let Some(item) = self.store.remove(&self.next) {
+            self.next += 1;
+            out.push(self.inner.feed(item)?);
+        }
+        Ok(out)
+    }
+
+    fn finalize(self) -> Result<Self::Output, Self::Error> {
+        assert!(
+            self.store.is_empty(),
+            "BUG: sequence ids must be consecutive, but {} wasn't fed",
+            self.next
+        );
+        self.inner.finalize()
+    }
+}
+
 /// A trait reflecting the `finalize()` method of [`Reduce`] implementations
 pub trait Finalize {
     /// An implementation of [`Reduce`]
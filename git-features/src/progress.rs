@@ -1,11 +1,23 @@
 //! Various `prodash` types along with various utilities for comfort.
+//!
+//! All code reporting progress takes an implementation of [`Progress`], which can be [discarded][Discard], logged with
+//! `progress-log`, or organized in a [`Tree`] with `progress-tree` to let nested tasks like *decompressing* while *resolving deltas*
+//! appear as children of the task that started them. Trees can then be displayed with the [`render`] module.
 use std::io;
 
+#[cfg(feature = "progress-log")]
+pub use prodash::progress::Log;
 pub use prodash::{
     messages::MessageLevel,
     progress::{Discard, DoOrDiscard, Either, Step, StepShared, ThroughputOnDrop, UNKNOWN},
     unit, Progress, Unit,
 };
+#[cfg(feature = "progress-tree")]
+pub use prodash::{tree, Tree, TreeOptions};
+
+///
+#[cfg(feature = "progress-render-line")]
+pub mod render;
 
 /// A unit for displaying bytes with throughput and progress percentage.
 pub fn bytes() -> Option<Unit> {
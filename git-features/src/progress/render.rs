@@ -0,0 +1,160 @@
+//! Render a progress [`Tree`] either line by line, which doubles as log, or with a terminal user interface.
+//!
+//! The renderer can be chosen explicitly or [automatically][auto()], which uses the terminal user interface only if it's compiled in
+//! with the `progress-render-tui` feature and if the output is a terminal.
+use std::{ops::RangeInclusive, sync::Arc, time::Duration};
+
+use prodash::progress::key::Level;
+
+use super::Tree;
+
+/// Configure how progress is rendered.
+#[derive(Debug, Clone)]
+pub struct Options {
+    /// The title to show in the terminal user interface.
+    pub title: String,
+    /// If set, only progress at the given levels of the tree is rendered by the line renderer, with `1` being the top-level.
+    pub level_filter: Option<RangeInclusive<Level>>,
+    /// How often to redraw the progress, per second.
+    pub frames_per_second: f32,
+    /// If set, the line renderer will wait this long before showing anything, which avoids output for quick operations.
+    pub initial_delay: Option<Duration>,
+    /// If true, the terminal user interface stays up even after all progress is done, which allows to read its messages.
+    pub keep_open: bool,
+}
+
+impl Default for Options {
+    fn default() -> Self {
+        Options {
+            title: "progress".into(),
+            level_filter: None,
+            frames_per_second: 6.0,
+            initial_delay: None,
+            keep_open: false,
+        }
+    }
+}
+
+/// The renderer to use.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum Mode {
+    /// Render progress line by line, with timestamps and throughput, which doubles as log if the output isn't a terminal.
+    Line,
+    /// Render progress in a terminal user interface that takes over the entire terminal.
+    #[cfg(feature = "progress-render-tui")]
+    Tui,
+}
+
+impl Mode {
+    /// Use the terminal user interface if it's available and standard output is a terminal, or render line by line otherwise.
+    pub fn auto() -> Self {
+        #[cfg(feature = "progress-render-tui")]
+        if prodash::render::line::Options::default()
+            .auto_configure(prodash::render::line::StreamKind::Stdout)
+            .output_is_terminal
+        {
+            return Mode::Tui;
+        }
+        Mode::Line
+    }
+}
+
+enum Inner {
+    Line(prodash::render::line::JoinHandle),
+    #[cfg(feature = "progress-render-tui")]
+    Tui(std::thread::JoinHandle<()>),
+}
+
+/// A handle to a running renderer, which stops rendering when dropped.
+pub struct Handle(Inner);
+
+impl Handle {
+    /// Return the kind of renderer we are handling.
+    pub fn mode(&self) -> Mode {
+        match self.0 {
+            Inner::Line(_) => Mode::Line,
+            #[cfg(feature = "progress-render-tui")]
+            Inner::Tui(_) => Mode::Tui,
+        }
+    }
+
+    /// Draw the progress one last time and wait for the renderer to stop.
+    ///
+    /// Note that the terminal user interface only stops once all progress was dropped, unless it's configured to be kept open
+    /// in which case it stops when the user quits it.
+    pub fn shutdown_and_wait(self) {
+        match self.0 {
+            Inner::Line(handle) => handle.shutdown_and_wait(),
+            #[cfg(feature = "progress-render-tui")]
+            Inner::Tui(handle) => {
+                handle.join().ok();
+            }
+        }
+    }
+}
+
+/// Render `progress` line by line into `out`, which is configured according to standard error as that's where progress
+/// typically goes.
+pub fn line(progress: &Arc<Tree>, out: impl std::io::Write + Send + 'static, options: Options) -> Handle {
+    let Options {
+        title: _,
+        level_filter,
+        frames_per_second,
+        initial_delay,
+        keep_open: _,
+    } = options;
+    Handle(Inner::Line(prodash::render::line(
+        out,
+        Arc::downgrade(progress),
+        prodash::render::line::Options {
+            level_filter,
+            frames_per_second,
+            initial_delay,
+            timestamp: true,
+            throughput: true,
+            hide_cursor: true,
+            ..prodash::render::line::Options::default()
+        }
+        .auto_configure(prodash::render::line::StreamKind::Stderr),
+    )))
+}
+
+/// Render `progress` in a terminal user interface on standard output, which is driven by its own thread.
+///
+/// # Panics
+///
+/// If the terminal couldn't be configured.
+#[cfg(feature = "progress-render-tui")]
+pub fn tui(progress: &Arc<Tree>, options: Options) -> Handle {
+    let Options {
+        title,
+        level_filter: _,
+        frames_per_second,
+        initial_delay: _,
+        keep_open,
+    } = options;
+    let render = prodash::render::tui(
+        std::io::stdout(),
+        Arc::downgrade(progress),
+        prodash::render::tui::Options {
+            title,
+            frames_per_second,
+            stop_if_progress_missing: !keep_open,
+            throughput: true,
+            ..Default::default()
+        },
+    )
+    .expect("tui to come up without io error");
+    Handle(Inner::Tui(std::thread::spawn(move || {
+        futures_lite::future::block_on(render)
+    })))
+}
+
+/// Render `progress` with the renderer chosen by [`Mode::auto()`], using standard error for the line renderer.
+pub fn auto(progress: &Arc<Tree>, options: Options) -> Handle {
+    match Mode::auto() {
+        Mode::Line => line(progress, std::io::stderr(), options),
+        #[cfg(feature = "progress-render-tui")]
+        Mode::Tui => tui(progress, options),
+    }
+}
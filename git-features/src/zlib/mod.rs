@@ -1,4 +1,8 @@
-pub use flate2::{Decompress, Status};
+//! Compress and decompress zlib streams with the backend selected by feature toggle, see the `zlib*` features of this crate.
+//!
+//! All types can be [reset][Inflate::reset()] to be reused for any amount of streams, which avoids allocating and
+//! initializing their rather large state each time an object is compressed or decompressed.
+pub use flate2::{Compress, Compression, Decompress, FlushCompress, FlushDecompress, Status};
 
 /// non-streaming interfaces for decompression
 pub mod inflate {
@@ -20,6 +24,22 @@ pub mod inflate {
     }
 }
 
+/// non-streaming interfaces for compression
+pub mod deflate {
+    use quick_error::quick_error;
+    quick_error! {
+        /// The error returned by various [Deflate methods][super::Deflate]
+        #[allow(missing_docs)]
+        #[derive(Debug)]
+        pub enum Error {
+            Deflate(err: flate2::CompressError) {
+                display("Could not compress data")
+                from()
+            }
+        }
+    }
+}
+
 /// Decompress a few bytes of a zlib stream without allocation
 pub struct Inflate {
     /// The actual decompressor doing all the work.
@@ -35,7 +55,7 @@ impl Default for Inflate {
 }
 
 impl Inflate {
-    /// Run the decompressor exactly once. Cannot be run multiple times
+    /// Run the decompressor exactly once. Cannot be run multiple times unless it's [reset][Inflate::reset()] in between.
     pub fn once(&mut self, input: &[u8], out: &mut [u8]) -> Result<(flate2::Status, usize, usize), inflate::Error> {
         let before_in = self.state.total_in();
         let before_out = self.state.total_out();
@@ -46,6 +66,58 @@ impl Inflate {
             (self.state.total_out() - before_out) as usize,
         ))
     }
+
+    /// Reset the decompressor to decompress a new zlib stream, keeping all allocated state.
+    pub fn reset(&mut self) {
+        self.state.reset(true);
+    }
+}
+
+/// Compress bytes into a zlib stream without allocating the compressor's state for each stream.
+pub struct Deflate {
+    /// The actual compressor doing all the work.
+    pub state: Compress,
+}
+
+impl Default for Deflate {
+    fn default() -> Self {
+        Deflate::new(Compression::fast())
+    }
+}
+
+impl Deflate {
+    /// Create a new instance compressing with the given `level`.
+    pub fn new(level: Compression) -> Self {
+        Deflate {
+            state: Compress::new(level, true),
+        }
+    }
+
+    /// Compress all of `input` into a complete zlib stream, appending it to `out`, and return the amount of compressed bytes.
+    ///
+    /// The compressor is [reset][Deflate::reset()] afterwards, so it can be used for the next stream right away.
+    pub fn all(&mut self, input: &[u8], out: &mut Vec<u8>) -> Result<usize, deflate::Error> {
+        let (start_in, start_out) = (self.state.total_in(), out.len());
+        out.reserve(input.len() / 2 + 64);
+        let res = loop {
+            let consumed = (self.state.total_in() - start_in) as usize;
+            let status = match self.state.compress_vec(&input[consumed..], out, FlushCompress::Finish) {
+                Ok(status) => status,
+                Err(err) => break Err(err.into()),
+            };
+            match status {
+                Status::StreamEnd => break Ok(out.len() - start_out),
+                Status::Ok | Status::BufError => out.reserve(out.capacity().max(64)),
+            }
+        };
+        self.reset();
+        res
+    }
+
+    /// Reset the compressor to produce a new zlib stream, keeping all allocated state.
+    pub fn reset(&mut self) {
+        self.state.reset();
+    }
 }
 
 ///
@@ -38,6 +38,14 @@ mod impls {
             self.compressor.reset();
         }
 
+        /// Reset the compressor to write a new compression stream into `inner`, returning the previous writer.
+        ///
+        /// That way the compressor's state and buffer are reused for any amount of streams.
+        pub fn reset_with(&mut self, inner: W) -> W {
+            self.compressor.reset();
+            std::mem::replace(&mut self.inner, inner)
+        }
+
         /// Consume `self` and return the inner writer.
         pub fn into_inner(self) -> W {
             self.inner
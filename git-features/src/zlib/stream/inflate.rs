@@ -10,6 +10,24 @@ pub struct ReadBoxed<R> {
     pub decompressor: Box<Decompress>,
 }
 
+impl<R> ReadBoxed<R> {
+    /// Create a new instance to decompress the zlib stream read from `inner`.
+    pub fn new(inner: R) -> Self {
+        ReadBoxed {
+            inner,
+            decompressor: Box::new(Decompress::new(true)),
+        }
+    }
+
+    /// Reset the decompressor to read a new zlib stream from `inner`, returning the previous reader.
+    ///
+    /// That way the decompressor's state is reused for any amount of streams.
+    pub fn reset(&mut self, inner: R) -> R {
+        self.decompressor.reset(true);
+        std::mem::replace(&mut self.inner, inner)
+    }
+}
+
 impl<R> io::Read for ReadBoxed<R>
 where
     R: BufRead,
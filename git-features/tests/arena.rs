@@ -0,0 +1,47 @@
+use git_features::arena::Arena;
+
+#[test]
+fn pushed_bytes_are_retrieved_by_their_range() {
+    let mut arena = Arena::new();
+    assert!(arena.is_empty());
+    let paths: Vec<_> = ["a", "dir/b", "", "dir/sub/c"]
+        .iter()
+        .map(|path| (arena.push(path.as_bytes()), *path))
+        .collect();
+    for (range, path) in &paths {
+        assert_eq!(&arena[range.clone()], path.as_bytes());
+    }
+    assert_eq!(arena.len(), 15);
+    assert_eq!(arena.into_vec(), b"adir/bdir/sub/c");
+}
+
+#[test]
+fn pushes_never_span_segments_and_keep_their_offsets() {
+    let mut arena = Arena::with_capacity(2);
+    let a = arena.push(b"a");
+    let large = vec![b'x'; 10_000];
+    let b = arena.push(&large);
+    let c = arena.push(b"c");
+    assert_eq!(b, 1..10_001, "offsets are contiguous even if a new segment is started");
+    assert_eq!(arena.get(a), Some(&b"a"[..]));
+    assert_eq!(arena.get(b), Some(large.as_slice()));
+    assert_eq!(arena.get(c.clone()), Some(&b"c"[..]));
+    assert_eq!(arena.get(0..2), None, "ranges must be within a single segment");
+    assert_eq!(arena.get(c.end..c.end + 1), None, "ranges must be in bounds");
+}
+
+#[test]
+fn appended_arenas_are_shifted_by_the_returned_offset() {
+    let mut lhs = Arena::from(b"first".to_vec());
+    let mut rhs = Arena::new();
+    let second = rhs.push(b"second");
+    let third = rhs.push(b"third");
+
+    let ofs = lhs.append(rhs);
+    assert_eq!(ofs, 5);
+    assert_eq!(&lhs[0..5], b"first");
+    assert_eq!(&lhs[second.start + ofs..second.end + ofs], b"second");
+    assert_eq!(&lhs[third.start + ofs..third.end + ofs], b"third");
+    assert_eq!(lhs.append(Arena::new()), 16, "empty arenas can be appended too");
+    assert_eq!(lhs.into_vec(), b"firstsecondthird");
+}
@@ -0,0 +1,109 @@
+use std::path::Path;
+
+use git_features::fs::{walkdir, walkdir_with};
+
+fn file_names(root: &Path, options: walkdir::Options) -> Vec<(String, bool)> {
+    walkdir_with(root, options)
+        .min_depth(1)
+        .into_iter()
+        .map(|entry| {
+            let entry = entry.expect("readable");
+            (
+                entry
+                    .path()
+                    .strip_prefix(root)
+                    .expect("within root")
+                    .to_string_lossy()
+                    .replace('\\', "/"),
+                entry.file_type().is_dir(),
+            )
+        })
+        .collect()
+}
+
+#[test]
+fn sorted_walks_return_entries_in_order_of_their_file_name_without_skipping_hidden_files() -> std::io::Result<()> {
+    let dir = tempfile::tempdir()?;
+    std::fs::create_dir_all(dir.path().join("b/d"))?;
+    for file in ["c", ".a", "b/e", "b/d/f"] {
+        std::fs::write(dir.path().join(file), b"")?;
+    }
+
+    for parallelism in [
+        walkdir::Parallelism::Serial,
+        walkdir::Parallelism::from_thread_limit(None),
+        walkdir::Parallelism::from_thread_limit(Some(2)),
+    ] {
+        assert_eq!(
+            file_names(
+                dir.path(),
+                walkdir::Options {
+                    sorted: true,
+                    parallelism,
+                    ..Default::default()
+                }
+            ),
+            vec![
+                (".a".into(), false),
+                ("b".into(), true),
+                ("b/d".into(), true),
+                ("b/d/f".into(), false),
+                ("b/e".into(), false),
+                ("c".into(), false)
+            ]
+        );
+    }
+    Ok(())
+}
+
+#[test]
+fn parallelism_from_thread_limit() {
+    use walkdir::Parallelism;
+    assert_eq!(Parallelism::from_thread_limit(Some(1)), Parallelism::Serial);
+    for limit in [None, Some(0)] {
+        assert_eq!(
+            Parallelism::from_thread_limit(limit),
+            Parallelism::ThreadPool { thread_limit: None }
+        );
+    }
+    assert_eq!(
+        Parallelism::from_thread_limit(Some(4)),
+        Parallelism::ThreadPool { thread_limit: Some(4) }
+    );
+}
+
+#[test]
+#[cfg(unix)]
+fn symlinks_are_only_followed_if_configured() -> std::io::Result<()> {
+    let dir = tempfile::tempdir()?;
+    std::fs::create_dir(dir.path().join("dir"))?;
+    std::fs::write(dir.path().join("dir/file"), b"")?;
+    std::os::unix::fs::symlink("dir", dir.path().join("link"))?;
+
+    let sorted = walkdir::Options {
+        sorted: true,
+        ..Default::default()
+    };
+    assert_eq!(
+        file_names(dir.path(), sorted),
+        vec![("dir".into(), true), ("dir/file".into(), false), ("link".into(), false)],
+        "the link is returned as is"
+    );
+    assert_eq!(
+        file_names(
+            dir.path(),
+            walkdir::Options {
+                follow_links: true,
+                ..sorted
+            }
+        ),
+        vec![
+            ("dir".into(), true),
+            ("dir/file".into(), false),
+            ("link".into(), true),
+            ("link/file".into(), false)
+        ],
+        "the link is traversed like a directory"
+    );
+    Ok(())
+}
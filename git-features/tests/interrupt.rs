@@ -0,0 +1,70 @@
+use std::{
+    io::{Read, Write},
+    sync::atomic::{AtomicBool, Ordering},
+};
+
+use git_features::interrupt;
+
+#[test]
+fn the_global_flag_can_be_triggered_and_reset() {
+    assert!(!interrupt::is_triggered());
+    interrupt::trigger();
+    assert!(interrupt::is_triggered());
+    assert!(interrupt::IS_INTERRUPTED.load(Ordering::SeqCst));
+    interrupt::reset();
+    assert!(!interrupt::is_triggered());
+}
+
+#[test]
+fn guards_set_or_clear_their_flag_when_dropped() {
+    let flag = AtomicBool::new(false);
+    {
+        let _guard = interrupt::TriggerOnDrop::new(&flag);
+        assert!(!flag.load(Ordering::SeqCst));
+    }
+    assert!(flag.load(Ordering::SeqCst));
+    {
+        let _guard = interrupt::ResetOnDrop::new(&flag);
+        assert!(flag.load(Ordering::SeqCst));
+    }
+    assert!(!flag.load(Ordering::SeqCst));
+}
+
+#[test]
+fn reads_and_writes_fail_once_interrupted() {
+    let should_interrupt = AtomicBool::new(false);
+    let mut read = interrupt::Read {
+        inner: &b"abc"[..],
+        should_interrupt: &should_interrupt,
+    };
+    let mut write = interrupt::Write {
+        inner: Vec::new(),
+        should_interrupt: &should_interrupt,
+    };
+    let mut buf = [0u8; 1];
+    read.read_exact(&mut buf).unwrap();
+    write.write_all(&buf).unwrap();
+    write.flush().unwrap();
+
+    should_interrupt.store(true, Ordering::SeqCst);
+    assert_eq!(read.read(&mut buf).unwrap_err().to_string(), "Interrupted");
+    assert_eq!(write.write(&buf).unwrap_err().to_string(), "Interrupted");
+    assert!(write.flush().is_err());
+    assert_eq!(write.inner, b"a", "nothing is written after the interrupt");
+}
+
+#[test]
+fn iterators_stop_once_interrupted() {
+    let should_interrupt = AtomicBool::new(false);
+    let mut iter = interrupt::Iter::new(0..3, &should_interrupt);
+    assert_eq!(iter.next(), Some(0));
+    should_interrupt.store(true, Ordering::SeqCst);
+    assert_eq!(iter.next(), None);
+
+    should_interrupt.store(false, Ordering::SeqCst);
+    let mut iter = interrupt::IterWithErr::new(0..3, || "interrupted", &should_interrupt);
+    assert_eq!(iter.next(), Some(Ok(0)));
+    should_interrupt.store(true, Ordering::SeqCst);
+    assert_eq!(iter.next(), Some(Err("interrupted")));
+    assert_eq!(iter.next(), None, "the error is returned only once");
+}
@@ -37,6 +37,54 @@ fn in_parallel() {
     assert_eq!(res, 100);
 }
 
+#[derive(Default)]
+struct Collect {
+    items: Vec<usize>,
+}
+
+impl parallel::Reduce for Collect {
+    type Input = usize;
+    type FeedProduce = ();
+    type Output = Vec<usize>;
+    type Error = ();
+
+    fn feed(&mut self, item: Self::Input) -> Result<Self::FeedProduce, Self::Error> {
+        self.items.push(item);
+        Ok(())
+    }
+
+    fn finalize(self) -> Result<Self::Output, Self::Error> {
+        Ok(self.items)
+    }
+}
+
+#[test]
+fn in_parallel_in_order() {
+    let res = parallel::in_parallel_in_order(
+        0..100usize,
+        Some(4),
+        |_n| (),
+        |input, _state| {
+            // Let later items finish first.
+            std::thread::sleep(std::time::Duration::from_micros(((100 - input) % 7) as u64 * 100));
+            input
+        },
+        Collect::default(),
+    )
+    .expect("successful computation");
+    assert_eq!(res, (0..100).collect::<Vec<_>>(), "the order of the input is retained");
+}
+
+#[test]
+fn in_order_reducer_feeds_items_in_sequence() {
+    use parallel::Reduce;
+    let mut reducer = parallel::reduce::InOrder::new(Adder::default());
+    assert_eq!(reducer.feed((2, 3)), Ok(vec![]), "held back until it's its turn");
+    assert_eq!(reducer.feed((0, 1)), Ok(vec![1]));
+    assert_eq!(reducer.feed((1, 2)), Ok(vec![2, 3]), "all ready items are fed at once");
+    assert_eq!(reducer.finalize(), Ok(6));
+}
+
 #[test]
 fn in_parallel_with_mut_slice_in_chunks() {
     let num_items = 33;
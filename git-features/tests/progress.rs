@@ -0,0 +1,84 @@
+use std::{
+    io,
+    sync::{Arc, Mutex},
+};
+
+use git_features::progress;
+
+#[test]
+fn nested_tasks_form_a_tree_and_keep_their_units() {
+    let root = progress::Tree::new();
+    let mut resolve = root.add_child("resolve deltas");
+    resolve.init(Some(10), progress::count("objects"));
+    let mut decompress = resolve.add_child("decompress");
+    decompress.init(None, progress::bytes());
+    decompress.inc_by(1024);
+    resolve.inc_by(3);
+
+    let mut snapshot = Vec::new();
+    root.sorted_snapshot(&mut snapshot);
+    let tasks: Vec<_> = snapshot
+        .iter()
+        .map(|(key, task)| {
+            (
+                key.level(),
+                task.name.as_str(),
+                task.progress
+                    .as_ref()
+                    .map(|p| p.step.load(std::sync::atomic::Ordering::SeqCst)),
+            )
+        })
+        .collect();
+    assert_eq!(
+        tasks,
+        [(1, "resolve deltas", Some(3)), (2, "decompress", Some(1024))],
+        "children of a task are one level deeper"
+    );
+    assert!(snapshot.iter().all(|(_, task)| task
+        .progress
+        .as_ref()
+        .and_then(|p| p.unit.as_ref())
+        .map_or(false, |unit| unit.display(1, None, None).to_string().len() > 1)));
+}
+
+#[derive(Clone, Default)]
+struct Buf(Arc<Mutex<Vec<u8>>>);
+
+impl io::Write for Buf {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.0.lock().unwrap().write(buf)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+#[test]
+fn line_renderer_logs_messages_if_the_output_is_no_terminal() {
+    let root = progress::Tree::new();
+    let mut task = root.add_child("receiving pack");
+    task.init(Some(2048), progress::bytes());
+    task.inc_by(1024);
+    let mut child = task.add_child("indexing");
+    child.init(None, progress::count("objects"));
+    child.inc_by(5);
+    child.info("indexed 5 objects");
+
+    let out = Buf::default();
+    let handle = progress::render::line(&root, out.clone(), progress::render::Options::default());
+    assert_eq!(handle.mode(), progress::render::Mode::Line);
+    handle.shutdown_and_wait();
+
+    let out = String::from_utf8(out.0.lock().unwrap().clone()).expect("utf8");
+    assert!(out.contains("indexing"), "{}", out);
+    assert!(out.contains("indexed 5 objects"), "{}", out);
+}
+
+#[test]
+fn the_line_renderer_is_chosen_automatically_if_there_is_no_terminal() {
+    // Tests don't usually run with a terminal on standard output, and without the TUI, there is no other choice.
+    if cfg!(not(feature = "progress-render-tui")) {
+        assert_eq!(progress::render::Mode::auto(), progress::render::Mode::Line);
+    }
+}
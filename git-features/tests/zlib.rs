@@ -0,0 +1,58 @@
+use std::io::{Read, Write};
+
+use git_features::zlib;
+
+#[test]
+fn deflate_and_inflate_can_be_reused_for_many_streams() -> Result<(), Box<dyn std::error::Error>> {
+    let mut deflate = zlib::Deflate::default();
+    let mut inflate = zlib::Inflate::default();
+    let mut compressed = Vec::new();
+    for size in [0usize, 1, 17, 4096, 100_000] {
+        let input: Vec<u8> = (0..size).map(|i| (i % 251) as u8).collect();
+        compressed.clear();
+        let num_compressed = deflate.all(&input, &mut compressed)?;
+        assert_eq!(num_compressed, compressed.len());
+
+        let mut out = vec![0; size];
+        inflate.reset();
+        let (status, consumed_in, consumed_out) = inflate.once(&compressed, &mut out)?;
+        assert_eq!(status, zlib::Status::StreamEnd);
+        assert_eq!(consumed_in, compressed.len());
+        assert_eq!(consumed_out, size);
+        assert_eq!(out, input);
+    }
+    Ok(())
+}
+
+#[test]
+fn deflate_appends_to_the_output() -> Result<(), Box<dyn std::error::Error>> {
+    let mut deflate = zlib::Deflate::default();
+    let mut out = b"prefix".to_vec();
+    let num_compressed = deflate.all(b"hello", &mut out)?;
+    assert_eq!(&out[..6], b"prefix");
+    assert_eq!(out.len(), 6 + num_compressed);
+    Ok(())
+}
+
+#[test]
+fn stream_reader_and_writer_can_be_reset_to_process_another_stream() -> Result<(), Box<dyn std::error::Error>> {
+    let mut w = zlib::stream::deflate::Write::new(Vec::new());
+    w.write_all(b"first")?;
+    w.flush()?;
+    let first = w.reset_with(Vec::new());
+    w.write_all(b"second")?;
+    w.flush()?;
+    let second = w.into_inner();
+
+    let mut r = zlib::stream::inflate::ReadBoxed::new(first.as_slice());
+    let mut out = Vec::new();
+    r.read_to_end(&mut out)?;
+    assert_eq!(out, b"first");
+
+    let previous = r.reset(second.as_slice());
+    assert!(previous.is_empty(), "the first stream was read entirely");
+    out.clear();
+    r.read_to_end(&mut out)?;
+    assert_eq!(out, b"second");
+    Ok(())
+}
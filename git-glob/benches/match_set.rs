@@ -0,0 +1,79 @@
+use bstr::{BString, ByteSlice};
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use git_glob::{pattern::Case, Pattern, Set};
+
+/// Patterns resembling the combined ignore files of a large monorepo with many projects.
+fn patterns() -> Vec<Pattern> {
+    let mut patterns = Vec::new();
+    for project in 0..1000 {
+        for pattern in [
+            format!("/services/service-{}/build/", project),
+            format!("services/service-{}/generated/**", project),
+            format!("*.artifact-{}", project),
+            format!("cache-{}-*", project),
+            format!("/libs/lib-{}/target", project),
+        ] {
+            patterns.push(git_glob::parse(pattern).expect("valid"));
+        }
+    }
+    patterns
+}
+
+/// Paths as they would be seen during a status, most of which match nothing.
+fn paths() -> Vec<BString> {
+    (0..5000)
+        .map(|idx| format!("services/service-{}/src/module-{}/file-{}.rs", idx % 1200, idx % 7, idx).into())
+        .chain((0..100).map(|idx| format!("libs/lib-{}/target", idx).into()))
+        .chain((0..100).map(|idx| format!("tools/out/bundle.artifact-{}", idx).into()))
+        .collect()
+}
+
+fn matching(c: &mut Criterion) {
+    let patterns = patterns();
+    let paths = paths();
+    let basename_pos = |path: &BString| path.rfind_byte(b'/').map(|pos| pos + 1);
+
+    c.bench_function("match each pattern in turn", |b| {
+        b.iter(|| {
+            paths
+                .iter()
+                .filter(|path| {
+                    patterns.iter().rev().any(|p| {
+                        p.matches_repo_relative_path(path.as_bstr(), basename_pos(path), None, Case::Sensitive)
+                    })
+                })
+                .count()
+        })
+    });
+
+    let set = Set::new(patterns.iter().cloned());
+    assert_eq!(
+        paths
+            .iter()
+            .filter(|path| set
+                .last_matching_pattern(path.as_bstr(), basename_pos(path), None, Case::Sensitive)
+                .is_some())
+            .count(),
+        200,
+        "both approaches find the same matches"
+    );
+    c.bench_function("match with pattern set", |b| {
+        b.iter(|| {
+            paths
+                .iter()
+                .filter(|path| {
+                    black_box(&set)
+                        .last_matching_pattern(path.as_bstr(), basename_pos(path), None, Case::Sensitive)
+                        .is_some()
+                })
+                .count()
+        })
+    });
+
+    c.bench_function("compile pattern set", |b| {
+        b.iter(|| Set::new(black_box(&patterns).iter().cloned()))
+    });
+}
+
+criterion_group!(benches, matching);
+criterion_main!(benches);
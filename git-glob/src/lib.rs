@@ -24,6 +24,26 @@ pub struct Pattern {
     pub first_wildcard_pos: Option<usize>,
 }
 
+/// A set of [patterns][Pattern] compiled for quickly finding the ones matching a path.
+///
+/// Literal prefixes and suffixes of all patterns are kept in tries, so each path is only matched against the patterns
+/// that could possibly match it, instead of all of them. This makes a difference for sets of thousands of patterns,
+/// as they occur in the ignore files of large repositories.
+#[derive(Debug, Clone, Default)]
+pub struct Set {
+    patterns: Vec<Pattern>,
+    /// Literal prefixes of patterns matched against the basename of paths.
+    basename_prefixes: set::Trie,
+    /// Reversed literal suffixes of `*literal` patterns matched against the basename of paths.
+    basename_suffixes: set::Trie,
+    /// Literal prefixes of patterns matched against the whole path.
+    path_prefixes: set::Trie,
+    /// Reversed literal suffixes of `*literal` patterns matched against the whole path.
+    path_suffixes: set::Trie,
+}
+
+mod set;
+
 ///
 pub mod pattern;
 
@@ -0,0 +1,166 @@
+use bstr::BStr;
+
+use crate::{pattern, pattern::Case, Pattern, Set};
+
+/// Init
+impl Set {
+    /// Compile all `patterns` into a set, keeping their order.
+    pub fn new(patterns: impl IntoIterator<Item = Pattern>) -> Self {
+        let mut set = Set::default();
+        for pattern in patterns {
+            set.push(pattern);
+        }
+        set
+    }
+}
+
+/// Access
+impl Set {
+    /// Return all patterns in the order they were added in.
+    pub fn patterns(&self) -> &[Pattern] {
+        &self.patterns
+    }
+
+    /// Return the index of the last pattern that matches `path`, which is usually the one that decides, or `None` if no pattern
+    /// matches.
+    ///
+    /// The arguments are the same as for [`Pattern::matches_repo_relative_path()`], and the outcome is the same as if each
+    /// pattern was tried in reverse order until one matches.
+    pub fn last_matching_pattern<'a>(
+        &self,
+        path: impl Into<&'a BStr>,
+        basename_start_pos: Option<usize>,
+        is_dir: Option<bool>,
+        case: Case,
+    ) -> Option<usize> {
+        let path = path.into();
+        let mut candidates = Vec::new();
+        self.candidates(path, basename_start_pos, &mut candidates);
+        candidates.sort_unstable_by(|a, b| b.cmp(a));
+        candidates
+            .into_iter()
+            .find(|idx| self.patterns[*idx].matches_repo_relative_path(path, basename_start_pos, is_dir, case))
+    }
+
+    /// Clear `out` and fill it with the indices of all patterns matching `path` in ascending order.
+    ///
+    /// The arguments are the same as for [`Pattern::matches_repo_relative_path()`].
+    pub fn matching_patterns<'a>(
+        &self,
+        path: impl Into<&'a BStr>,
+        basename_start_pos: Option<usize>,
+        is_dir: Option<bool>,
+        case: Case,
+        out: &mut Vec<usize>,
+    ) {
+        let path = path.into();
+        out.clear();
+        self.candidates(path, basename_start_pos, out);
+        out.retain(|idx| self.patterns[*idx].matches_repo_relative_path(path, basename_start_pos, is_dir, case));
+        out.sort_unstable();
+    }
+
+    /// Put the indices of all patterns that can possibly match `path` into `out`, in no particular order.
+    fn candidates(&self, path: &BStr, basename_start_pos: Option<usize>, out: &mut Vec<usize>) {
+        let basename = &path[basename_start_pos.unwrap_or_default()..];
+        self.basename_prefixes.collect(basename.iter().copied(), out);
+        self.basename_suffixes.collect(basename.iter().rev().copied(), out);
+        self.path_prefixes.collect(path.iter().copied(), out);
+        self.path_suffixes.collect(path.iter().rev().copied(), out);
+    }
+}
+
+/// Mutation
+impl Set {
+    /// Add `pattern` to the end of the set, making it the one with the highest index.
+    pub fn push(&mut self, pattern: Pattern) -> &mut Self {
+        let idx = self.patterns.len();
+        let matches_basename =
+            pattern.mode.contains(pattern::Mode::NO_SUB_DIR) && !pattern.mode.contains(pattern::Mode::ABSOLUTE);
+        // Patterns like `*literal` match if the value ends with `literal`, even if they are passed on to `wildmatch()`.
+        let literal_suffix = match pattern.first_wildcard_pos {
+            Some(0) if pattern.mode.contains(pattern::Mode::ENDS_WITH) => Some(&pattern.text[1..]),
+            _ => None,
+        };
+        match (literal_suffix, matches_basename) {
+            (Some(suffix), true) => self.basename_suffixes.insert(suffix.iter().rev().copied(), idx),
+            (Some(suffix), false) => self.path_suffixes.insert(suffix.iter().rev().copied(), idx),
+            (None, matches_basename) => {
+                // All patterns only match values that start with their literal portion.
+                let prefix = &pattern.text[..pattern.first_wildcard_pos.unwrap_or(pattern.text.len())];
+                if matches_basename {
+                    self.basename_prefixes.insert(prefix.iter().copied(), idx)
+                } else {
+                    self.path_prefixes.insert(prefix.iter().copied(), idx)
+                }
+            }
+        }
+        self.patterns.push(pattern);
+        self
+    }
+}
+
+/// A trie of case-folded keys, each associated with the indices of the patterns that were inserted with it.
+#[derive(Debug, Clone)]
+pub(crate) struct Trie {
+    /// All nodes, with the first one being the root for the empty key.
+    nodes: Vec<Node>,
+}
+
+#[derive(Debug, Clone, Default)]
+struct Node {
+    /// The byte leading to a child, and the index of the child in the list of nodes, sorted by byte.
+    children: Vec<(u8, usize)>,
+    /// The indices of the patterns whose key ends with this node.
+    patterns: Vec<usize>,
+}
+
+impl Default for Trie {
+    fn default() -> Self {
+        Trie {
+            nodes: vec![Node::default()],
+        }
+    }
+}
+
+impl Trie {
+    /// Associate pattern `idx` with `key`.
+    ///
+    /// Keys are case-folded so a single trie can find candidates for case-sensitive and case-insensitive matches.
+    fn insert(&mut self, key: impl Iterator<Item = u8>, idx: usize) {
+        let mut node = 0;
+        for byte in key.map(|b| b.to_ascii_lowercase()) {
+            node = match self.nodes[node].children.binary_search_by_key(&byte, |(b, _)| *b) {
+                Ok(pos) => self.nodes[node].children[pos].1,
+                Err(pos) => {
+                    let child = self.nodes.len();
+                    self.nodes.push(Node::default());
+                    self.nodes[node].children.insert(pos, (byte, child));
+                    child
+                }
+            };
+        }
+        self.nodes[node].patterns.push(idx);
+    }
+
+    /// Add the indices of all patterns whose key is a prefix of `value` to `out`.
+    fn collect(&self, value: impl Iterator<Item = u8>, out: &mut Vec<usize>) {
+        let mut node = &self.nodes[0];
+        out.extend_from_slice(&node.patterns);
+        for byte in value.map(|b| b.to_ascii_lowercase()) {
+            match node.children.binary_search_by_key(&byte, |(b, _)| *b) {
+                Ok(pos) => {
+                    node = &self.nodes[node.children[pos].1];
+                    out.extend_from_slice(&node.patterns);
+                }
+                Err(_) => break,
+            }
+        }
+    }
+}
+
+impl FromIterator<Pattern> for Set {
+    fn from_iter<T: IntoIterator<Item = Pattern>>(iter: T) -> Self {
+        Set::new(iter)
+    }
+}
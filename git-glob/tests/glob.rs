@@ -1,3 +1,4 @@
 mod parse;
 mod pattern;
+mod set;
 mod wildmatch;
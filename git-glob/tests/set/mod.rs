@@ -0,0 +1,93 @@
+use bstr::{BStr, BString, ByteSlice};
+use git_glob::{pattern::Case, Pattern, Set};
+
+fn basename_start_pos(value: &BStr) -> Option<usize> {
+    value.rfind_byte(b'/').map(|pos| pos + 1)
+}
+
+/// Return all patterns and values of the baselines, which are lists of `pattern value` lines each followed by git's verdict.
+fn baseline_patterns_and_values() -> (Vec<Pattern>, Vec<BString>) {
+    let dir = git_testtools::scripted_fixture_repo_read_only("make_baseline.sh").unwrap();
+    let (mut patterns, mut values) = (Vec::new(), Vec::new());
+    for file in ["git-baseline.match", "git-baseline.nmatch", "git-baseline.match-icase"] {
+        let input = std::fs::read(dir.join(file)).unwrap();
+        for line in input.lines().step_by(2) {
+            let (pattern, value) = line.split_once_str(" ").expect("pattern and value");
+            patterns.extend(git_glob::parse(pattern));
+            values.push(value.trim_start().as_bstr().to_owned());
+        }
+    }
+    (patterns, values)
+}
+
+#[test]
+fn matches_like_each_pattern_on_its_own() {
+    let (patterns, values) = baseline_patterns_and_values();
+    let set = Set::new(patterns.iter().cloned());
+    assert_eq!(set.patterns(), patterns.as_slice());
+
+    let mut actual = Vec::new();
+    let mut num_matches = 0;
+    for value in &values {
+        let value = value.as_bstr();
+        for case in [Case::Sensitive, Case::Fold] {
+            for is_dir in [None, Some(true)] {
+                let expected: Vec<_> = patterns
+                    .iter()
+                    .enumerate()
+                    .filter(|(_, p)| p.matches_repo_relative_path(value, basename_start_pos(value), is_dir, case))
+                    .map(|(idx, _)| idx)
+                    .collect();
+                set.matching_patterns(value, basename_start_pos(value), is_dir, case, &mut actual);
+                assert_eq!(
+                    actual, expected,
+                    "{:?} with {:?} and is_dir = {:?}",
+                    value, case, is_dir
+                );
+                assert_eq!(
+                    set.last_matching_pattern(value, basename_start_pos(value), is_dir, case),
+                    expected.last().copied()
+                );
+                num_matches += expected.len();
+            }
+        }
+    }
+    assert!(num_matches > values.len(), "the baseline has plenty of matches");
+}
+
+#[test]
+fn the_last_matching_pattern_is_found() {
+    let set: Set = ["target/", "*.o", "/build", "src/**/gen", "docs", "D*.md"]
+        .iter()
+        .map(|p| git_glob::parse(p).unwrap())
+        .collect();
+    let last = |path: &str, is_dir: bool, case: Case| {
+        set.last_matching_pattern(path, basename_start_pos(path.into()), Some(is_dir), case)
+    };
+    assert_eq!(last("a/target", true, Case::Sensitive), Some(0));
+    assert_eq!(last("a/target", false, Case::Sensitive), None, "directories only");
+    assert_eq!(last("a/b/c.o", false, Case::Sensitive), Some(1));
+    assert_eq!(last("a/b/c.O", false, Case::Sensitive), None);
+    assert_eq!(last("a/b/c.O", false, Case::Fold), Some(1));
+    assert_eq!(last("build", true, Case::Sensitive), Some(2));
+    assert_eq!(last("a/build", true, Case::Sensitive), None, "anchored to the root");
+    assert_eq!(last("src/a/b/gen", true, Case::Sensitive), Some(3));
+    assert_eq!(last("DOCS", false, Case::Fold), Some(4));
+    assert_eq!(last("docs/Dev.md", false, Case::Sensitive), Some(5));
+    assert_eq!(last("docs/dev.md", false, Case::Fold), Some(5));
+    assert_eq!(last("docs/dev.md", false, Case::Sensitive), None);
+}
+
+#[test]
+fn patterns_can_be_pushed() {
+    let mut set = Set::default();
+    assert_eq!(set.last_matching_pattern("a", None, None, Case::Sensitive), None);
+    set.push(git_glob::parse("*").unwrap())
+        .push(git_glob::parse("a").unwrap());
+
+    let mut out = vec![42];
+    set.matching_patterns("a", None, None, Case::Sensitive, &mut out);
+    assert_eq!(out, [0, 1], "the output is cleared and sorted");
+    assert_eq!(set.last_matching_pattern("a", None, None, Case::Sensitive), Some(1));
+    assert_eq!(set.last_matching_pattern("b", None, None, Case::Sensitive), Some(0));
+}
@@ -0,0 +1,307 @@
+//! Incremental hash implementations to produce digests for each [`Kind`].
+//!
+//! The SHA-1 implementation is chosen by feature flags, in order of precedence:
+//!
+//! * `sha1-collision-detection` detects collision attacks like [SHAttered](https://shattered.io) and returns a safe digest
+//!   in that case, just like `git` does by default. It's the slowest of all implementations.
+//! * `fast-sha1` uses hardware acceleration where available.
+//! * `rustsha1`, a portable pure-Rust implementation.
+//!
+//! With the `sha256` feature, [`Sha256`] is available as well, along with the [`interop`] module to compute the SHA-1 and
+//! SHA-256 digests of the same object at once.
+use crate::{Kind, ObjectId};
+
+/// A 20 bytes digest produced by a [`Sha1`] hash implementation.
+pub type Sha1Digest = [u8; 20];
+
+/// A 32 bytes digest produced by a [`Sha256`] hash implementation.
+#[cfg(feature = "sha256")]
+pub type Sha256Digest = [u8; 32];
+
+/// The error returned by [`Hasher::try_digest()`] and [`Sha1::try_digest()`].
+#[derive(Debug, thiserror::Error)]
+#[allow(missing_docs)]
+pub enum Error {
+    #[error("A SHA-1 collision attack was detected while hashing, the digest was altered to {digest} to mitigate it")]
+    CollisionAttack { digest: ObjectId },
+}
+
+#[cfg(feature = "sha1-collision-detection")]
+mod _impl {
+    use super::{Error, Sha1Digest};
+
+    /// An implementation of the Sha1 hash which detects collision attacks, which can be used once.
+    #[derive(Default, Clone)]
+    pub struct Sha1(sha1collisiondetection::Sha1CD);
+
+    impl Sha1 {
+        /// Digest the given `bytes`.
+        pub fn update(&mut self, bytes: &[u8]) {
+            self.0.update(bytes)
+        }
+        /// Finalize the hash and produce a digest, failing if a collision attack was detected.
+        pub fn try_digest(mut self) -> Result<Sha1Digest, Error> {
+            let mut digest = sha1collisiondetection::Output::default();
+            let res = self.0.finalize_into_dirty_cd(&mut digest);
+            let digest: Sha1Digest = digest.into();
+            res.map(|()| digest)
+                .map_err(|_collision| Error::CollisionAttack { digest: digest.into() })
+        }
+    }
+}
+
+#[cfg(all(feature = "fast-sha1", not(feature = "sha1-collision-detection")))]
+mod _impl {
+    use sha1::Digest;
+
+    use super::{Error, Sha1Digest};
+
+    /// An implementation of the Sha1 hash, which can be used once.
+    #[derive(Default, Clone)]
+    pub struct Sha1(sha1::Sha1);
+
+    impl Sha1 {
+        /// Digest the given `bytes`.
+        pub fn update(&mut self, bytes: &[u8]) {
+            self.0.update(bytes)
+        }
+        /// Finalize the hash and produce a digest. This implementation doesn't detect collisions and never fails.
+        pub fn try_digest(self) -> Result<Sha1Digest, Error> {
+            Ok(self.0.finalize().into())
+        }
+    }
+}
+
+#[cfg(all(
+    feature = "rustsha1",
+    not(any(feature = "fast-sha1", feature = "sha1-collision-detection"))
+))]
+mod _impl {
+    use super::{Error, Sha1Digest};
+
+    /// An implementation of the Sha1 hash, which can be used once.
+    #[derive(Default, Clone)]
+    pub struct Sha1(sha1_smol::Sha1);
+
+    impl Sha1 {
+        /// Digest the given `bytes`.
+        pub fn update(&mut self, bytes: &[u8]) {
+            self.0.update(bytes)
+        }
+        /// Finalize the hash and produce a digest. This implementation doesn't detect collisions and never fails.
+        pub fn try_digest(self) -> Result<Sha1Digest, Error> {
+            Ok(self.0.digest().bytes())
+        }
+    }
+}
+
+pub use _impl::Sha1;
+
+impl Sha1 {
+    /// Finalize the hash and produce a digest.
+    ///
+    /// If a collision attack was detected, the digest is the one altered to mitigate it, which is what `git` does as well.
+    /// Use [`try_digest()`][Sha1::try_digest()] to learn about attacks.
+    pub fn digest(self) -> Sha1Digest {
+        match self.try_digest() {
+            Ok(digest) => digest,
+            Err(Error::CollisionAttack {
+                digest: ObjectId::Sha1(digest),
+            }) => digest,
+        }
+    }
+}
+
+impl std::io::Write for Sha1 {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.update(buf);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+#[cfg(feature = "sha256")]
+mod sha256 {
+    use sha2::Digest;
+
+    use super::Sha256Digest;
+
+    /// An implementation of the Sha256 hash using hardware acceleration where available, which can be used once.
+    #[derive(Default, Clone)]
+    pub struct Sha256(sha2::Sha256);
+
+    impl Sha256 {
+        /// Digest the given `bytes`.
+        pub fn update(&mut self, bytes: &[u8]) {
+            self.0.update(bytes)
+        }
+        /// Finalize the hash and produce a digest.
+        pub fn digest(self) -> Sha256Digest {
+            self.0.finalize().into()
+        }
+    }
+
+    impl std::io::Write for Sha256 {
+        fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+            self.update(buf);
+            Ok(buf.len())
+        }
+
+        fn flush(&mut self) -> std::io::Result<()> {
+            Ok(())
+        }
+    }
+}
+#[cfg(feature = "sha256")]
+pub use sha256::Sha256;
+
+/// A hasher producing object ids of a [`Kind`], for use with all hash kinds alike.
+#[derive(Clone)]
+pub enum Hasher {
+    /// The SHA-1 hasher.
+    Sha1(Sha1),
+}
+
+/// Produce a hasher suitable for the given kind of hash.
+pub fn hasher(kind: Kind) -> Hasher {
+    Hasher::new(kind)
+}
+
+/// Init
+impl Hasher {
+    /// Create a new hasher for digests of `kind`.
+    pub fn new(kind: Kind) -> Self {
+        match kind {
+            Kind::Sha1 => Hasher::Sha1(Sha1::default()),
+        }
+    }
+}
+
+/// Access
+impl Hasher {
+    /// Return the kind of hash this instance produces.
+    pub fn kind(&self) -> Kind {
+        match self {
+            Hasher::Sha1(_) => Kind::Sha1,
+        }
+    }
+
+    /// Digest the given `bytes`.
+    pub fn update(&mut self, bytes: &[u8]) {
+        match self {
+            Hasher::Sha1(h) => h.update(bytes),
+        }
+    }
+
+    /// Finalize the hash and produce an object id, which is altered to mitigate collision attacks if one was detected.
+    pub fn digest(self) -> ObjectId {
+        match self {
+            Hasher::Sha1(h) => h.digest().into(),
+        }
+    }
+
+    /// Finalize the hash and produce an object id, or fail if a collision attack was detected.
+    pub fn try_digest(self) -> Result<ObjectId, Error> {
+        Ok(match self {
+            Hasher::Sha1(h) => h.try_digest()?.into(),
+        })
+    }
+}
+
+impl std::io::Write for Hasher {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.update(buf);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+///
+#[cfg(feature = "sha256")]
+pub mod interop {
+    use super::{Error, Sha1, Sha256, Sha256Digest};
+    use crate::ObjectId;
+
+    /// The digests of the same data as produced by [`Dual`].
+    #[derive(PartialEq, Eq, Debug, Hash, Clone, Copy)]
+    pub struct Digests {
+        /// The SHA-1 digest of the data.
+        pub sha1: ObjectId,
+        /// The SHA-256 digest of the data.
+        pub sha256: Sha256Digest,
+    }
+
+    /// A hasher to compute the SHA-1 and the SHA-256 digest of the same data at once, which is useful to map the object ids
+    /// of one hash kind to the other while repositories transition from SHA-1 to SHA-256.
+    #[derive(Default, Clone)]
+    pub struct Dual {
+        sha1: Sha1,
+        sha256: Sha256,
+    }
+
+    /// Init
+    impl Dual {
+        /// Create a new instance to hash an object of `kind`, like `b"blob"`, with `size` bytes, which will have to be passed to
+        /// [`update()`][Dual::update()] afterwards.
+        ///
+        /// This hashes the loose-object header first, so the digests are object ids in each hash kind.
+        pub fn for_object(kind: &[u8], size: u64) -> Self {
+            let mut hasher = Dual::default();
+            hasher.update(kind);
+            hasher.update(b" ");
+            hasher.update(size.to_string().as_bytes());
+            hasher.update(b"\0");
+            hasher
+        }
+    }
+
+    /// Access
+    impl Dual {
+        /// Digest the given `bytes` with both hashes.
+        pub fn update(&mut self, bytes: &[u8]) {
+            self.sha1.update(bytes);
+            self.sha256.update(bytes);
+        }
+
+        /// Finalize both hashes and produce their digests, with the SHA-1 digest altered to mitigate collision attacks if
+        /// one was detected.
+        pub fn digest(self) -> Digests {
+            Digests {
+                sha1: self.sha1.digest().into(),
+                sha256: self.sha256.digest(),
+            }
+        }
+
+        /// Finalize both hashes and produce their digests, or fail if a SHA-1 collision attack was detected.
+        pub fn try_digest(self) -> Result<Digests, Error> {
+            Ok(Digests {
+                sha1: self.sha1.try_digest()?.into(),
+                sha256: self.sha256.digest(),
+            })
+        }
+    }
+
+    impl std::io::Write for Dual {
+        fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+            self.update(buf);
+            Ok(buf.len())
+        }
+
+        fn flush(&mut self) -> std::io::Result<()> {
+            Ok(())
+        }
+    }
+
+    /// Compute the SHA-1 and SHA-256 object ids of an object of `kind`, like `b"blob"`, with the given `data`.
+    pub fn object(kind: &[u8], data: &[u8]) -> Digests {
+        let mut hasher = Dual::for_object(kind, data.len() as u64);
+        hasher.update(data);
+        hasher.digest()
+    }
+}
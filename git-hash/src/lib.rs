@@ -19,6 +19,11 @@ pub use object_id::{decode, ObjectId};
 ///
 pub mod prefix;
 
+#[cfg(any(feature = "rustsha1", feature = "fast-sha1", feature = "sha1-collision-detection"))]
+pub mod hasher;
+#[cfg(any(feature = "rustsha1", feature = "fast-sha1", feature = "sha1-collision-detection"))]
+pub use hasher::{hasher, Hasher};
+
 /// An partial owned hash possibly identifying an object uniquely,
 /// whose non-prefix bytes are zeroed.
 #[derive(PartialEq, Eq, Hash, Ord, PartialOrd, Clone, Copy, Debug)]
@@ -0,0 +1,75 @@
+use std::io::Write;
+
+use git_hash::{hasher, Hasher, Kind, ObjectId};
+
+fn hex_to_id(hex: &str) -> ObjectId {
+    ObjectId::from_hex(hex.as_bytes()).expect("40 bytes hex")
+}
+
+#[test]
+fn incremental_updates_produce_the_same_digest_as_a_single_one() {
+    let mut hasher = hasher(Kind::Sha1);
+    assert_eq!(hasher.kind(), Kind::Sha1);
+    hasher.update(b"a");
+    hasher.write_all(b"bc").unwrap();
+    assert_eq!(hasher.digest(), hex_to_id("a9993e364706816aba3e25717850c26c9cd0d89d"));
+
+    let mut hasher = Hasher::new(Kind::Sha1);
+    hasher.update(b"abc");
+    assert_eq!(
+        hasher.try_digest().expect("no collision"),
+        hex_to_id("a9993e364706816aba3e25717850c26c9cd0d89d")
+    );
+}
+
+#[test]
+fn sha256_digests() {
+    let mut hasher = git_hash::hasher::Sha256::default();
+    hasher.update(b"");
+    assert_eq!(
+        hex::encode(hasher.digest()),
+        "e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855"
+    );
+}
+
+mod interop {
+    use std::io::Write;
+
+    use git_hash::hasher::interop::{self, Dual};
+
+    use crate::hex_to_id;
+
+    #[test]
+    fn object_ids_of_both_kinds_are_computed_at_once() {
+        let digests = interop::object(b"blob", b"hello world\n");
+        assert_eq!(
+            digests.sha1,
+            hex_to_id("3b18e512dba79e4c8300dd08aeb37f8e728b8dad"),
+            "the same as `git hash-object`"
+        );
+        assert_eq!(
+            hex::encode(digests.sha256),
+            "0bd69098bd9b9cc5934a610ab65da429b525361147faa7b5b922919e9a23143d",
+            "the same as `git hash-object` in a repository with `--object-format=sha256`"
+        );
+
+        let mut hasher = Dual::for_object(b"blob", 12);
+        hasher.write_all(b"hello ").unwrap();
+        hasher.update(b"world\n");
+        assert_eq!(hasher.try_digest().expect("no collision"), digests);
+    }
+}
+
+#[test]
+#[cfg(feature = "sha1-collision-detection")]
+fn collision_attacks_are_detected_and_mitigated() {
+    let data = git_testtools::fixture_bytes("sha-mbles-1.bin");
+    let unsafe_digest = hex_to_id("8ac60ba76f1999a1ab70223f225aefdc78d4ddc0");
+
+    let mut hasher = hasher(Kind::Sha1);
+    hasher.update(&data);
+    let err = hasher.clone().try_digest().unwrap_err();
+    let git_hash::hasher::Error::CollisionAttack { digest } = err;
+    assert_ne!(digest, unsafe_digest, "the digest is altered to not collide anymore");
+    assert_eq!(hasher.digest(), digest, "the safe digest is returned without error");
+}
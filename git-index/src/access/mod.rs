@@ -1,3 +1,5 @@
+use std::ops::Range;
+
 use bstr::{BStr, ByteSlice};
 
 use crate::{entry, extension, Entry, PathStorage, State, Version};
@@ -6,6 +8,8 @@ use crate::{entry, extension, Entry, PathStorage, State, Version};
 #[allow(dead_code)]
 mod sparse;
 
+mod state_ref;
+
 /// General information and entries
 impl State {
     /// Return the version used to store this state's information on disk.
@@ -13,11 +17,23 @@ impl State {
         self.version
     }
 
+    /// Set the `version` to use when [writing][State::write_to()] this state, which matters only for [`Version::V4`] with its
+    /// compressed paths as V2 or V3 are chosen depending on the entries otherwise.
+    pub fn set_version(&mut self, version: Version) {
+        self.version = version;
+    }
+
     /// Return the kind of hashes used in this instance.
     pub fn object_hash(&self) -> git_hash::Kind {
         self.object_hash
     }
 
+    /// Return the time at which this state was written or read from disk, used to detect
+    /// [racily clean entries][Entry::is_racy()].
+    pub fn timestamp(&self) -> filetime::FileTime {
+        self.timestamp
+    }
+
     /// Return our entries
     pub fn entries(&self) -> &[Entry] {
         &self.entries
@@ -98,6 +114,30 @@ impl State {
             .map(|idx| &self.entries[idx])
     }
 
+    /// Return the range of indices into [`entries()`][State::entries()] of all entries at `path` in all of their stages,
+    /// or `None` if there is no such entry.
+    pub fn entry_range(&self, path: &BStr) -> Option<Range<usize>> {
+        let backing = &self.path_backing;
+        let start = self.entries.partition_point(|e| e.path_in(backing) < path);
+        let end = start + self.entries[start..].partition_point(|e| e.path_in(backing) == path);
+        (start != end).then(|| start..end)
+    }
+
+    /// Return an iterator over all conflicted paths in order, with all of their entries grouped by conflict stage.
+    pub fn conflicts(&self) -> impl Iterator<Item = entry::Conflict<'_>> {
+        let mut entries = self.entries.iter().peekable();
+        std::iter::from_fn(move || {
+            let first = entries.find(|e| e.stage() != 0)?;
+            let path = first.path(self);
+            let mut stages = [None; 3];
+            for entry in std::iter::once(first).chain(std::iter::from_fn(|| entries.next_if(|e| e.path(self) == path)))
+            {
+                stages[entry.stage() as usize - 1] = Some(entry);
+            }
+            Some(entry::Conflict { path, stages })
+        })
+    }
+
     /// Return the entry at `idx` or _panic_ if the index is out of bounds.
     ///
     /// The `idx` is typically returned by [entry_by_path_and_stage()][State::entry_by_path_and_stage()].
@@ -113,6 +153,161 @@ impl State {
     }
 }
 
+/// Mutation
+impl State {
+    /// Add an entry at `path` with the given information, or update the entry at `path` and the stage in `flags` if it exists
+    /// already, keeping all entries sorted. Returns the index of the entry in [`entries()`][State::entries()].
+    ///
+    /// Like in `git`, an entry at stage 0 replaces all conflicting entries at `path`, while an entry at a conflict stage replaces
+    /// the one at stage 0.
+    /// The tree extension is invalidated for `path`.
+    pub fn upsert_entry(
+        &mut self,
+        stat: entry::Stat,
+        id: git_hash::ObjectId,
+        flags: entry::Flags,
+        mode: entry::Mode,
+        path: &BStr,
+    ) -> usize {
+        let stage = flags.stage();
+        if let Some(range) = self.entry_range(path) {
+            let removed_stages_are_conflicts = stage == 0;
+            let mut idx = range.start;
+            for _ in range {
+                if (self.entries[idx].stage() != 0) == removed_stages_are_conflicts {
+                    self.entries.remove(idx);
+                } else {
+                    idx += 1;
+                }
+            }
+        }
+        self.invalidate_tree_at(path);
+
+        let flags = flags & !entry::Flags::PATH_LEN;
+        let backing = &self.path_backing;
+        let idx = match self
+            .entries
+            .binary_search_by(|e| e.path_in(backing).cmp(path).then_with(|| e.stage().cmp(&stage)))
+        {
+            Ok(idx) => {
+                let entry = &mut self.entries[idx];
+                entry.stat = stat;
+                entry.id = id;
+                entry.flags = flags;
+                entry.mode = mode;
+                idx
+            }
+            Err(idx) => {
+                let start = self.path_backing.len();
+                self.path_backing.extend_from_slice(path);
+                self.entries.insert(
+                    idx,
+                    Entry {
+                        stat,
+                        id,
+                        flags,
+                        mode,
+                        path: start..self.path_backing.len(),
+                    },
+                );
+                idx
+            }
+        };
+        self.update_is_sparse();
+        idx
+    }
+
+    /// Resolve the conflict at `path` by replacing all of its conflict stages with the entry of `side` at stage 0,
+    /// and return the index of the resolved entry in [`entries()`][State::entries()].
+    ///
+    /// Like in `git`, all conflict stages are recorded in the [resolve-undo extension][State::resolve_undo()] to allow
+    /// undoing the resolution.
+    /// Return `None` if `path` isn't conflicted or if it doesn't exist on `side`, without changing anything.
+    pub fn resolve(&mut self, path: &BStr, side: entry::Side) -> Option<usize> {
+        let (resolved, resolve_path) = {
+            let conflict = self.conflicts().find(|c| c.path == path)?;
+            let resolved = conflict.entry(side)?.clone();
+            let mut stages = [None; 3];
+            for (stage, entry) in stages.iter_mut().zip(conflict.stages) {
+                *stage = entry.map(|e| extension::resolve_undo::Stage {
+                    mode: e.mode.bits(),
+                    id: e.id,
+                });
+            }
+            (
+                resolved,
+                extension::resolve_undo::ResolvePath {
+                    name: path.into(),
+                    stages,
+                },
+            )
+        };
+
+        let paths = self.resolve_undo.get_or_insert_with(Default::default);
+        match paths.binary_search_by(|p| p.name.as_bstr().cmp(path)) {
+            Ok(idx) => paths[idx] = resolve_path,
+            Err(idx) => paths.insert(idx, resolve_path),
+        }
+
+        let flags = resolved.flags & !(entry::Flags::STAGE_MASK | entry::Flags::CONFLICTED);
+        Some(self.upsert_entry(resolved.stat, resolved.id, flags, resolved.mode, path))
+    }
+
+    /// Return the entry at `path` and `stage` for modification, or `None` if it doesn't exist.
+    ///
+    /// As the entry is likely to change, the tree extension is invalidated for `path`.
+    pub fn entry_mut_by_path_and_stage(&mut self, path: &BStr, stage: entry::Stage) -> Option<&mut Entry> {
+        let idx = self.entry_index_by_path_and_stage(path, stage)?;
+        self.invalidate_tree_at(path);
+        Some(&mut self.entries[idx])
+    }
+
+    /// Remove the entry at `path` and `stage` and return it, or `None` if it doesn't exist.
+    ///
+    /// The tree extension is invalidated for `path`.
+    /// Note that the path of the removed entry stays in the [path backing][State::path_backing()].
+    pub fn remove_entry(&mut self, path: &BStr, stage: entry::Stage) -> Option<Entry> {
+        let idx = self.entry_index_by_path_and_stage(path, stage)?;
+        self.invalidate_tree_at(path);
+        let entry = self.entries.remove(idx);
+        self.update_is_sparse();
+        Some(entry)
+    }
+
+    /// Remove all entries for which `should_remove(index, path, entry)` returns true, and return the amount of removed entries.
+    ///
+    /// The tree extension is invalidated for the path of each removed entry.
+    /// Note that the paths of removed entries stay in the [path backing][State::path_backing()].
+    pub fn remove_entries(&mut self, mut should_remove: impl FnMut(usize, &BStr, &Entry) -> bool) -> usize {
+        let (backing, tree) = (&self.path_backing, &mut self.tree);
+        let num_entries = self.entries.len();
+        let mut idx = 0;
+        self.entries.retain(|e| {
+            let path = e.path_in(backing);
+            let remove = should_remove(idx, path, e);
+            idx += 1;
+            if remove {
+                if let Some(tree) = tree.as_mut() {
+                    tree.invalidate_path(path);
+                }
+            }
+            !remove
+        });
+        self.update_is_sparse();
+        num_entries - self.entries.len()
+    }
+
+    fn invalidate_tree_at(&mut self, path: &BStr) {
+        if let Some(tree) = self.tree.as_mut() {
+            tree.invalidate_path(path);
+        }
+    }
+
+    fn update_is_sparse(&mut self) {
+        self.is_sparse = self.entries.iter().any(|e| e.mode.is_sparse());
+    }
+}
+
 /// Extensions
 impl State {
     /// Access the `tree` extension.
@@ -127,12 +322,25 @@ impl State {
     pub fn resolve_undo(&self) -> Option<&extension::resolve_undo::Paths> {
         self.resolve_undo.as_ref()
     }
+    /// Set the resolve-undo extension to `paths`, or remove it if `None`.
+    pub fn set_resolve_undo(&mut self, paths: Option<extension::resolve_undo::Paths>) {
+        self.resolve_undo = paths;
+    }
     /// Obtain the untracked extension.
     pub fn untracked(&self) -> Option<&extension::UntrackedCache> {
         self.untracked.as_ref()
     }
+    /// Set the untracked extension to `cache`, or remove it if `None`, to keep track of untracked files found in the worktree.
+    pub fn set_untracked(&mut self, cache: Option<extension::UntrackedCache>) {
+        self.untracked = cache;
+    }
     /// Obtain the fsmonitor extension.
     pub fn fs_monitor(&self) -> Option<&extension::FsMonitor> {
         self.fs_monitor.as_ref()
     }
+    /// Set the fsmonitor extension to `fs_monitor`, or remove it if `None`, to remember which entries the filesystem monitor
+    /// considered unchanged when it was last queried.
+    pub fn set_fs_monitor(&mut self, fs_monitor: Option<extension::FsMonitor>) {
+        self.fs_monitor = fs_monitor;
+    }
 }
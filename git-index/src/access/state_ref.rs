@@ -0,0 +1,131 @@
+use std::borrow::Cow;
+
+use bstr::{BStr, ByteSlice};
+
+use crate::{entry, extension, Entry, PathStorageRef, State, StateRef, Version};
+
+/// General information and entries
+impl<'a> StateRef<'a> {
+    /// Return the version used to store this state's information on disk.
+    pub fn version(&self) -> Version {
+        self.version
+    }
+
+    /// Return the kind of hashes used in this instance.
+    pub fn object_hash(&self) -> git_hash::Kind {
+        self.object_hash
+    }
+
+    /// Return the time at which this state was written or read from disk.
+    pub fn timestamp(&self) -> filetime::FileTime {
+        self.timestamp
+    }
+
+    /// Return our entries, whose paths can be obtained with [`Entry::path_in()`] and our [path backing][StateRef::path_backing()].
+    pub fn entries(&self) -> &[Entry] {
+        &self.entries
+    }
+
+    /// Return the bytes the paths of all entries point into.
+    pub fn path_backing(&self) -> &PathStorageRef {
+        &self.path_backing
+    }
+
+    /// Return an iterator over all entries along with their paths.
+    pub fn entries_with_paths(&self) -> impl Iterator<Item = (&BStr, &Entry)> {
+        self.entries.iter().map(move |e| (e.path_in(&self.path_backing), e))
+    }
+
+    /// Find the entry index in [`entries()`][StateRef::entries()] matching the given repository-relative
+    /// `path` and `stage`, or `None`.
+    pub fn entry_index_by_path_and_stage(&self, path: &BStr, stage: entry::Stage) -> Option<usize> {
+        self.entries
+            .binary_search_by(|e| {
+                e.path_in(&self.path_backing)
+                    .cmp(path)
+                    .then_with(|| e.stage().cmp(&stage))
+            })
+            .ok()
+    }
+
+    /// Like [`entry_index_by_path_and_stage()`][StateRef::entry_index_by_path_and_stage()],
+    /// but returns the entry instead of the index.
+    pub fn entry_by_path_and_stage(&self, path: &BStr, stage: entry::Stage) -> Option<&Entry> {
+        self.entry_index_by_path_and_stage(path, stage)
+            .map(|idx| &self.entries[idx])
+    }
+
+    /// Returns a boolean value indicating whether the index is sparse or not.
+    pub fn is_sparse(&self) -> bool {
+        self.is_sparse
+    }
+}
+
+/// Extensions
+impl<'a> StateRef<'a> {
+    /// Access the `tree` extension.
+    pub fn tree(&self) -> Option<&extension::Tree> {
+        self.tree.as_ref()
+    }
+    /// Access the `link` extension.
+    pub fn link(&self) -> Option<&extension::Link> {
+        self.link.as_ref()
+    }
+    /// Obtain the resolve-undo extension.
+    pub fn resolve_undo(&self) -> Option<&extension::resolve_undo::Paths> {
+        self.resolve_undo.as_ref()
+    }
+    /// Obtain the untracked extension.
+    pub fn untracked(&self) -> Option<&extension::UntrackedCache> {
+        self.untracked.as_ref()
+    }
+    /// Obtain the fsmonitor extension.
+    pub fn fs_monitor(&self) -> Option<&extension::FsMonitor> {
+        self.fs_monitor.as_ref()
+    }
+}
+
+/// Conversion
+impl<'a> StateRef<'a> {
+    /// Turn this instance into a [`State`] which owns its paths, copying all borrowed paths into a new path backing.
+    pub fn into_state(self) -> State {
+        let StateRef {
+            object_hash,
+            timestamp,
+            version,
+            mut entries,
+            path_backing,
+            is_sparse,
+            tree,
+            link,
+            resolve_undo,
+            untracked,
+            fs_monitor,
+        } = self;
+        let path_backing = match path_backing {
+            Cow::Owned(path_backing) => path_backing,
+            Cow::Borrowed(data) => {
+                let mut path_backing = Vec::with_capacity(entries.iter().map(|e| e.path.len()).sum());
+                for entry in &mut entries {
+                    let start = path_backing.len();
+                    path_backing.extend_from_slice(entry.path_in(data).as_bytes());
+                    entry.path = start..path_backing.len();
+                }
+                path_backing
+            }
+        };
+        State {
+            object_hash,
+            timestamp,
+            version,
+            entries,
+            path_backing,
+            is_sparse,
+            tree,
+            link,
+            resolve_undo,
+            untracked,
+            fs_monitor,
+        }
+    }
+}
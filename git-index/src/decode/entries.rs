@@ -40,16 +40,24 @@ pub fn estimate_path_storage_requirements_in_bytes(
 }
 
 /// Note that `data` must point to the beginning of the entries, right past the header.
+///
+/// If `borrow_paths_from` is set to all bytes of the index file that contain `data`, paths aren't copied into `path_backing`
+/// but refer to their location in the file. This isn't possible for V4 indices and their prefix-compressed paths.
 pub fn chunk<'a>(
     mut data: &'a [u8],
     entries: &mut Vec<Entry>,
     path_backing: &mut Vec<u8>,
+    borrow_paths_from: Option<&[u8]>,
     num_entries: u32,
     object_hash: git_hash::Kind,
     version: Version,
 ) -> Result<(Outcome, &'a [u8]), decode::Error> {
     let mut is_sparse = false;
     let has_delta_paths = version == Version::V4;
+    debug_assert!(
+        !(has_delta_paths && borrow_paths_from.is_some()),
+        "BUG: delta paths can't be borrowed"
+    );
     let mut prev_path = None;
     let mut delta_buf = Vec::<u8>::with_capacity(AVERAGE_V4_DELTA_PATH_LEN_IN_BYTES);
 
@@ -57,6 +65,7 @@ pub fn chunk<'a>(
         let (entry, remaining) = load_one(
             data,
             path_backing,
+            borrow_paths_from,
             object_hash.len_in_bytes(),
             has_delta_paths,
             prev_path,
@@ -81,6 +90,7 @@ pub fn chunk<'a>(
 fn load_one<'a>(
     data: &'a [u8],
     path_backing: &mut Vec<u8>,
+    borrow_paths_from: Option<&[u8]>,
     hash_len: usize,
     has_delta_paths: bool,
     prev_path_and_buf: Option<(Range<usize>, &mut Vec<u8>)>,
@@ -109,7 +119,7 @@ fn load_one<'a>(
     };
 
     let start = path_backing.len();
-    let data = if has_delta_paths {
+    let (path_range, data) = if has_delta_paths {
         let (strip_len, data) = var_int(data)?;
         if let Some((prev_path, buf)) = prev_path_and_buf {
             let end = prev_path.end.checked_sub(strip_len.try_into().ok()?)?;
@@ -124,7 +134,7 @@ fn load_one<'a>(
         let (path, data) = split_at_byte_exclusive(data, 0)?;
         path_backing.extend_from_slice(path);
 
-        data
+        (start..path_backing.len(), data)
     } else {
         let (path, data) = if flags.contains(entry::Flags::PATH_LEN) {
             split_at_byte_exclusive(data, 0)?
@@ -134,10 +144,18 @@ fn load_one<'a>(
             (path, skip_padding(data, first_byte_of_entry))
         };
 
-        path_backing.extend_from_slice(path);
-        data
+        let path_range = match borrow_paths_from {
+            Some(file) => {
+                let start = path.as_ptr() as usize - file.as_ptr() as usize;
+                start..start + path.len()
+            }
+            None => {
+                path_backing.extend_from_slice(path);
+                start..path_backing.len()
+            }
+        };
+        (path_range, data)
     };
-    let path_range = start..path_backing.len();
 
     Some((
         Entry {
@@ -1,6 +1,8 @@
+use std::borrow::Cow;
+
 use filetime::FileTime;
 
-use crate::{entry, extension, Entry, State, Version};
+use crate::{entry, extension, Entry, State, StateRef, Version};
 
 mod entries;
 ///
@@ -25,7 +27,6 @@ mod error {
     }
 }
 pub use error::Error;
-use git_features::parallel::InOrderIter;
 
 use crate::util::read_u32;
 
@@ -47,6 +48,73 @@ impl State {
     /// Decode an index state from `data` and store `timestamp` in the resulting instance for pass-through, assuming `object_hash`
     /// to be used through the file.
     pub fn from_bytes(
+        data: &[u8],
+        timestamp: FileTime,
+        object_hash: git_hash::Kind,
+        options: Options,
+    ) -> Result<(Self, git_hash::ObjectId), Error> {
+        let (state, _paths_are_borrowed, checksum) =
+            State::from_bytes_maybe_borrowing_paths(data, timestamp, object_hash, options, false)?;
+        Ok((state, checksum))
+    }
+}
+
+impl<'a> StateRef<'a> {
+    /// Decode an index state from `data` like [`State::from_bytes()`], but without copying the paths of entries
+    /// which are borrowed from `data` instead, which typically is a memory map of the index file.
+    ///
+    /// This saves time and memory for large indices, but isn't possible for [`Version::V4`] indices with their
+    /// prefix-compressed paths, whose paths are copied as usual.
+    pub fn from_bytes(
+        data: &'a [u8],
+        timestamp: FileTime,
+        object_hash: git_hash::Kind,
+        options: Options,
+    ) -> Result<(Self, git_hash::ObjectId), Error> {
+        let (
+            State {
+                object_hash,
+                timestamp,
+                version,
+                entries,
+                path_backing,
+                is_sparse,
+                tree,
+                link,
+                resolve_undo,
+                untracked,
+                fs_monitor,
+            },
+            paths_are_borrowed,
+            checksum,
+        ) = State::from_bytes_maybe_borrowing_paths(data, timestamp, object_hash, options, true)?;
+        Ok((
+            StateRef {
+                object_hash,
+                timestamp,
+                version,
+                entries,
+                path_backing: if paths_are_borrowed {
+                    Cow::Borrowed(data)
+                } else {
+                    Cow::Owned(path_backing)
+                },
+                is_sparse,
+                tree,
+                link,
+                resolve_undo,
+                untracked,
+                fs_monitor,
+            },
+            checksum,
+        ))
+    }
+}
+
+impl State {
+    /// Decode `data` like [`State::from_bytes()`], and if `borrow_paths` is true and the version allows it, let the paths of all
+    /// entries point into `data` instead of copying them into the path backing, which stays empty. Return `true` in that case.
+    fn from_bytes_maybe_borrowing_paths(
         data: &[u8],
         timestamp: FileTime,
         object_hash: git_hash::Kind,
@@ -54,18 +122,24 @@ impl State {
             thread_limit,
             min_extension_block_in_bytes_for_threading,
         }: Options,
-    ) -> Result<(Self, git_hash::ObjectId), Error> {
+        borrow_paths: bool,
+    ) -> Result<(Self, bool, git_hash::ObjectId), Error> {
         let (version, num_entries, post_header_data) = header::decode(data, object_hash)?;
         let start_of_extensions = extension::end_of_index_entry::decode(data, object_hash);
+        let borrow_paths_from = (borrow_paths && version != Version::V4).then(|| data);
 
         let mut num_threads = git_features::parallel::num_threads(thread_limit);
-        let path_backing_buffer_size = entries::estimate_path_storage_requirements_in_bytes(
-            num_entries,
-            data.len(),
-            start_of_extensions,
-            object_hash,
-            version,
-        );
+        let path_backing_buffer_size = if borrow_paths_from.is_some() {
+            0
+        } else {
+            entries::estimate_path_storage_requirements_in_bytes(
+                num_entries,
+                data.len(),
+                start_of_extensions,
+                object_hash,
+                version,
+            )
+        };
 
         let (entries, ext, data) = match start_of_extensions {
             Some(offset) if num_threads > 1 => {
@@ -81,21 +155,25 @@ impl State {
                         Some(entry_offsets) => {
                             let chunk_size = (entry_offsets.len() as f32 / num_threads as f32).ceil() as usize;
                             let num_chunks = entry_offsets.chunks(chunk_size).count();
-                            let mut threads = Vec::with_capacity(num_chunks);
-                            for (id, chunks) in entry_offsets.chunks(chunk_size).enumerate() {
-                                let chunks = chunks.to_vec();
-                                threads.push(scope.spawn(move |_| {
+                            git_features::parallel::in_parallel_in_order(
+                                entry_offsets.chunks(chunk_size),
+                                Some(num_threads),
+                                |_thread_id| (),
+                                |chunks, _state| {
                                     let num_entries_for_chunks =
                                         chunks.iter().map(|c| c.num_entries).sum::<u32>() as usize;
                                     let mut entries = Vec::with_capacity(num_entries_for_chunks);
-                                    let path_backing_buffer_size_for_chunks =
+                                    let path_backing_buffer_size_for_chunks = if borrow_paths_from.is_some() {
+                                        0
+                                    } else {
                                         entries::estimate_path_storage_requirements_in_bytes(
                                             num_entries_for_chunks as u32,
                                             data.len() / num_chunks,
                                             start_of_extensions.map(|ofs| ofs / num_chunks),
                                             object_hash,
                                             version,
-                                        );
+                                        )
+                                    };
                                     let mut path_backing = Vec::with_capacity(path_backing_buffer_size_for_chunks);
                                     let mut is_sparse = false;
                                     for offset in chunks {
@@ -108,55 +186,27 @@ impl State {
                                             &data[offset.from_beginning_of_file as usize..],
                                             &mut entries,
                                             &mut path_backing,
+                                            borrow_paths_from,
                                             offset.num_entries,
                                             object_hash,
                                             version,
                                         )?;
                                         is_sparse |= chunk_is_sparse;
                                     }
-                                    Ok::<_, Error>((
-                                        id,
-                                        EntriesOutcome {
-                                            entries,
-                                            path_backing,
-                                            is_sparse,
-                                        },
-                                    ))
-                                }));
-                            }
-                            let mut results =
-                                InOrderIter::from(threads.into_iter().map(|thread| thread.join().unwrap()));
-                            let mut acc = results.next().expect("have at least two results, one per thread");
-                            // We explicitly don't adjust the reserve in acc and rather allow for more copying
-                            // to happens as vectors grow to keep the peak memory size low.
-                            // NOTE: one day, we might use a memory pool for paths. We could encode the block of memory
-                            //       in some bytes in the path offset. That way there is more indirection/slower access
-                            //       to the path, but it would save time here.
-                            //       As it stands, `git` is definitely more efficient at this and probably uses less memory too.
-                            //       Maybe benchmarks can tell if that is noticeable later at 200/400GB/s memory bandwidth, or maybe just
-                            //       100GB/s on a single core.
-                            while let (Ok(lhs), Some(res)) = (acc.as_mut(), results.next()) {
-                                match res {
-                                    Ok(rhs) => {
-                                        lhs.is_sparse |= rhs.is_sparse;
-                                        let ofs = lhs.path_backing.len();
-                                        lhs.path_backing.extend(rhs.path_backing);
-                                        lhs.entries.extend(rhs.entries.into_iter().map(|mut e| {
-                                            e.path.start += ofs;
-                                            e.path.end += ofs;
-                                            e
-                                        }));
-                                    }
-                                    Err(err) => {
-                                        acc = Err(err);
-                                    }
-                                }
-                            }
-                            acc.map(|acc| (acc, &data[data.len() - object_hash.len_in_bytes()..]))
+                                    Ok(EntriesOutcome {
+                                        entries,
+                                        path_backing,
+                                        is_sparse,
+                                    })
+                                },
+                                ConcatEntries::default(),
+                            )
+                            .map(|acc| (acc, &data[data.len() - object_hash.len_in_bytes()..]))
                         }
                         None => entries(
                             post_header_data,
                             path_backing_buffer_size,
+                            borrow_paths_from,
                             num_entries,
                             object_hash,
                             version,
@@ -175,6 +225,7 @@ impl State {
                 let (entries, data) = entries(
                     post_header_data,
                     path_backing_buffer_size,
+                    borrow_paths_from,
                     num_entries,
                     object_hash,
                     version,
@@ -192,6 +243,7 @@ impl State {
         }
 
         let checksum = git_hash::ObjectId::from(data);
+        let paths_are_borrowed = borrow_paths_from.is_some();
         let EntriesOutcome {
             entries,
             path_backing,
@@ -222,6 +274,7 @@ impl State {
                 untracked,
                 fs_monitor,
             },
+            paths_are_borrowed,
             checksum,
         ))
     }
@@ -233,19 +286,64 @@ struct EntriesOutcome {
     pub is_sparse: bool,
 }
 
-fn entries(
-    post_header_data: &[u8],
+/// Concatenate the entries decoded from chunks of the index, which must be fed in order.
+#[derive(Default)]
+struct ConcatEntries {
+    entries: Vec<Entry>,
+    path_backing: git_features::arena::Arena,
+    is_sparse: bool,
+}
+
+impl git_features::parallel::Reduce for ConcatEntries {
+    type Input = Result<EntriesOutcome, Error>;
+    type FeedProduce = ();
+    type Output = EntriesOutcome;
+    type Error = Error;
+
+    fn feed(&mut self, item: Self::Input) -> Result<Self::FeedProduce, Self::Error> {
+        let rhs = item?;
+        self.is_sparse |= rhs.is_sparse;
+        // Paths of each chunk are moved into the arena without copying, so only the offsets of entries need adjustment.
+        // Borrowed paths leave all path backings empty, so the offset stays 0.
+        let ofs = self.path_backing.append(rhs.path_backing.into());
+        if self.entries.is_empty() {
+            self.entries = rhs.entries;
+        } else {
+            self.entries.extend(rhs.entries.into_iter().map(|mut e| {
+                e.path.start += ofs;
+                e.path.end += ofs;
+                e
+            }));
+        }
+        Ok(())
+    }
+
+    fn finalize(self) -> Result<Self::Output, Self::Error> {
+        Ok(EntriesOutcome {
+            entries: self.entries,
+            // NOTE: The index state keeps paths in a single buffer, which is why they are copied exactly once here, unless there
+            //       was just one chunk. Keeping the arena would avoid the copy, at the cost of slower access to paths.
+            path_backing: self.path_backing.into_vec(),
+            is_sparse: self.is_sparse,
+        })
+    }
+}
+
+fn entries<'a>(
+    post_header_data: &'a [u8],
     path_backing_buffer_size: usize,
+    borrow_paths_from: Option<&[u8]>,
     num_entries: u32,
     object_hash: git_hash::Kind,
     version: Version,
-) -> Result<(EntriesOutcome, &[u8]), Error> {
+) -> Result<(EntriesOutcome, &'a [u8]), Error> {
     let mut entries = Vec::with_capacity(num_entries as usize);
     let mut path_backing = Vec::with_capacity(path_backing_buffer_size);
     entries::chunk(
         post_header_data,
         &mut entries,
         &mut path_backing,
+        borrow_paths_from,
         num_entries,
         object_hash,
         version,
@@ -274,11 +372,11 @@ pub(crate) fn stat(data: &[u8]) -> Option<(entry::Stat, &[u8])> {
     let (size, data) = read_u32(data)?;
     Some((
         entry::Stat {
-            mtime: entry::Time {
+            ctime: entry::Time {
                 secs: ctime_secs,
                 nsecs: ctime_nsecs,
             },
-            ctime: entry::Time {
+            mtime: entry::Time {
                 secs: mtime_secs,
                 nsecs: mtime_nsecs,
             },
@@ -0,0 +1,41 @@
+use bstr::BStr;
+
+use crate::{entry::Stage, Entry};
+
+/// The side of a conflict, identifying one of the conflict stages of a path.
+#[derive(Debug, PartialEq, Eq, Hash, Ord, PartialOrd, Clone, Copy)]
+pub enum Side {
+    /// The version of the common ancestor, or base, at stage 1.
+    Ancestor,
+    /// Our version, at stage 2.
+    Ours,
+    /// Their version, at stage 3.
+    Theirs,
+}
+
+impl Side {
+    /// Return the conflict stage this side is stored at.
+    pub fn stage(&self) -> Stage {
+        match self {
+            Side::Ancestor => 1,
+            Side::Ours => 2,
+            Side::Theirs => 3,
+        }
+    }
+}
+
+/// All entries of a conflicted path, one for each conflict stage that is present.
+#[derive(Debug, Clone, Copy)]
+pub struct Conflict<'a> {
+    /// The path of all entries, relative to the repository.
+    pub path: &'a BStr,
+    /// The entries at stage 1, 2 and 3, or `None` if the path doesn't exist on the respective side.
+    pub stages: [Option<&'a Entry>; 3],
+}
+
+impl<'a> Conflict<'a> {
+    /// Return the entry of the given `side`, or `None` if the path doesn't exist there, for instance as it was deleted.
+    pub fn entry(&self, side: Side) -> Option<&'a Entry> {
+        self.stages[side.stage() as usize - 1]
+    }
+}
@@ -1,6 +1,9 @@
-/// The stage of an entry, one of 0 = base, 1 = ours, 2 = theirs
+/// The stage of an entry, one of 0 = unconflicted, 1 = base, 2 = ours, 3 = theirs
 pub type Stage = u32;
 
+mod conflict;
+pub use conflict::{Conflict, Side};
+
 mod mode;
 pub use mode::Mode;
 
@@ -60,6 +63,19 @@ mod access {
         pub fn stage(&self) -> entry::Stage {
             self.flags.stage()
         }
+
+        /// Return true if this entry was modified so shortly before or after the index it is stored in was written at
+        /// `timestamp` that its stat information can't be trusted to tell whether or not its file changed.
+        ///
+        /// Such entries are considered racily clean and need their content compared to know if they changed, see
+        /// [racy-git](https://github.com/git/git/blob/master/Documentation/technical/racy-git.txt).
+        pub fn is_racy(&self, timestamp: filetime::FileTime) -> bool {
+            let index_secs = timestamp.unix_seconds();
+            let entry_secs = i64::from(self.stat.mtime.secs);
+            index_secs != 0
+                && (index_secs < entry_secs
+                    || (index_secs == entry_secs && timestamp.nanoseconds() <= self.stat.mtime.nsecs))
+        }
     }
 }
 
@@ -1,10 +1,43 @@
 use std::convert::TryInto;
 
-use crate::{entry, Entry, State};
+use bstr::BStr;
+
+use crate::{entry, write, Entry, State};
 
 impl Entry {
     /// Serialize ourselves to `out` with path access via `state`, without padding.
     pub fn write_to(&self, mut out: impl std::io::Write, state: &State) -> std::io::Result<()> {
+        let path = self.path(state);
+        self.write_fields_and_flags(&mut out, path)?;
+        out.write_all(path)?;
+        out.write_all(b"\0")
+    }
+
+    /// Serialize ourselves to `out` like [`write_to()`][Entry::write_to()], but store our path relative to `previous_path`,
+    /// the path of the previously written entry, as done in [V4][crate::Version::V4] indices.
+    pub(crate) fn write_to_with_prefix_compressed_path(
+        &self,
+        mut out: impl std::io::Write,
+        state: &State,
+        previous_path: &BStr,
+    ) -> std::io::Result<()> {
+        let path = self.path(state);
+        self.write_fields_and_flags(&mut out, path)?;
+        let common_prefix_len = previous_path
+            .iter()
+            .zip(path.iter())
+            .take_while(|(a, b)| a == b)
+            .count();
+        let mut buf = [0u8; 10];
+        out.write_all(write::util::var_int(
+            (previous_path.len() - common_prefix_len) as u64,
+            &mut buf,
+        ))?;
+        out.write_all(&path[common_prefix_len..])?;
+        out.write_all(b"\0")
+    }
+
+    fn write_fields_and_flags(&self, out: &mut impl std::io::Write, path: &BStr) -> std::io::Result<()> {
         let stat = self.stat;
         out.write_all(&stat.ctime.secs.to_be_bytes())?;
         out.write_all(&stat.ctime.nsecs.to_be_bytes())?;
@@ -17,7 +50,6 @@ impl Entry {
         out.write_all(&stat.gid.to_be_bytes())?;
         out.write_all(&stat.size.to_be_bytes())?;
         out.write_all(self.id.as_bytes())?;
-        let path = self.path(state);
         let path_len: u16 = if path.len() >= entry::Flags::PATH_LEN.bits() as usize {
             entry::Flags::PATH_LEN.bits() as u16
         } else {
@@ -33,7 +65,6 @@ impl Entry {
                     .to_be_bytes(),
             )?;
         }
-        out.write_all(path)?;
-        out.write_all(b"\0")
+        Ok(())
     }
 }
@@ -1,18 +1,100 @@
+use std::convert::TryFrom;
+
 use bstr::BString;
 
 use crate::{
     extension::{FsMonitor, Signature},
-    util::{read_u32, read_u64, split_at_byte_exclusive},
+    util::{read_u32, read_u64, split_at_byte_exclusive, split_at_pos},
 };
 
-#[derive(Clone)]
+/// The token identifying the point in time of the last query to the filesystem monitor.
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub enum Token {
-    V1 { nanos_since_1970: u64 },
-    V2 { token: BString },
+    /// The token of the first version of the hook protocol, a timestamp.
+    V1 {
+        /// Nanoseconds since the unix epoch.
+        nanos_since_1970: u64,
+    },
+    /// The token of the second version of the hook protocol, which is opaque and provided by the monitor.
+    V2 {
+        /// The token as provided by the monitor.
+        token: BString,
+    },
+}
+
+impl Token {
+    /// Return the token in the form passed to filesystem monitor hooks, with timestamps being formatted as decimal number.
+    pub fn to_bstring(&self) -> BString {
+        match self {
+            Token::V1 { nanos_since_1970 } => nanos_since_1970.to_string().into(),
+            Token::V2 { token } => token.clone(),
+        }
+    }
+}
+
+/// Lifecycle
+impl FsMonitor {
+    /// Create a new instance from the `token` of the last query to the filesystem monitor and the indices of all
+    /// `dirty_entries` in ascending order, i.e. those that may have changed since the query.
+    pub fn new(token: Token, dirty_entries: impl IntoIterator<Item = usize>) -> Self {
+        let mut entry_dirty = git_bitmap::ewah::Vec::default();
+        for index in dirty_entries {
+            entry_dirty.set(index);
+        }
+        FsMonitor { token, entry_dirty }
+    }
+}
+
+/// Access
+impl FsMonitor {
+    /// The token of the last query to the filesystem monitor.
+    pub fn token(&self) -> &Token {
+        &self.token
+    }
+
+    /// Return the indices of all entries which were not known to be unchanged as per the filesystem monitor
+    /// at the time of the last query.
+    pub fn dirty_entries(&self) -> Vec<usize> {
+        let mut out = Vec::new();
+        self.entry_dirty.for_each_set_bit(|idx| {
+            out.push(idx);
+            Some(())
+        });
+        out
+    }
+}
+
+/// Serialization
+impl FsMonitor {
+    /// Serialize this instance to `out`.
+    pub fn write_to(&self, mut out: impl std::io::Write) -> std::io::Result<()> {
+        let mut data = Vec::new();
+        match &self.token {
+            Token::V1 { nanos_since_1970 } => {
+                data.extend_from_slice(&1_u32.to_be_bytes());
+                data.extend_from_slice(&nanos_since_1970.to_be_bytes());
+            }
+            Token::V2 { token } => {
+                data.extend_from_slice(&2_u32.to_be_bytes());
+                data.extend_from_slice(token);
+                data.push(0);
+            }
+        }
+        let mut bitmap = Vec::new();
+        self.entry_dirty.write_to(&mut bitmap)?;
+        data.extend_from_slice(&(u32::try_from(bitmap.len()).expect("less than 4GB bitmap")).to_be_bytes());
+        data.extend_from_slice(&bitmap);
+
+        out.write_all(&SIGNATURE)?;
+        out.write_all(&(u32::try_from(data.len()).expect("less than 4GB fsmonitor extension")).to_be_bytes())?;
+        out.write_all(&data)
+    }
 }
 
+/// The signature of the filesystem monitor extension.
 pub const SIGNATURE: Signature = *b"FSMN";
 
+/// Decode the filesystem monitor extension from `data`.
 pub fn decode(data: &[u8]) -> Option<FsMonitor> {
     let (version, data) = read_u32(data)?;
     let (token, data) = match version {
@@ -28,9 +110,10 @@ pub fn decode(data: &[u8]) -> Option<FsMonitor> {
     };
 
     let (ewah_size, data) = read_u32(data)?;
-    let (entry_dirty, data) = git_bitmap::ewah::decode(&data[..ewah_size as usize]).ok()?;
+    let (bitmap, data) = split_at_pos(data, ewah_size as usize)?;
+    let (entry_dirty, bitmap) = git_bitmap::ewah::decode(bitmap).ok()?;
 
-    if !data.is_empty() {
+    if !bitmap.is_empty() || !data.is_empty() {
         return None;
     }
 
@@ -1,6 +1,7 @@
 use crate::{
     extension::{Link, Signature},
     util::split_at_pos,
+    Entry, State,
 };
 
 /// The signature of the link extension.
@@ -32,6 +33,148 @@ pub mod decode {
     }
 }
 
+///
+pub mod dissolve {
+    use std::path::PathBuf;
+
+    /// The error returned when merging a split index with the shared index its link extension refers to.
+    #[derive(Debug, thiserror::Error)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        #[error("Could not load the shared index at '{}'", path.display())]
+        SharedIndex {
+            path: PathBuf,
+            source: Box<crate::file::init::Error>,
+        },
+        #[error("The shared index should have checksum {expected}, but has {actual}")]
+        ChecksumMismatch {
+            expected: git_hash::ObjectId,
+            actual: git_hash::ObjectId,
+        },
+        #[error("The {kind} bitmap refers to entry {index}, but the shared index has only {num_entries} entries")]
+        BitmapOutOfBounds {
+            kind: &'static str,
+            index: usize,
+            num_entries: usize,
+        },
+        #[error("The split index has fewer entries than the {num_replacements} entries to replace")]
+        MissingReplacement { num_replacements: usize },
+        #[error("Split index entry {index} replaces a shared entry and should have an empty path")]
+        ReplacementWithPath { index: usize },
+        #[error("Split index entry {index} is added to the shared entries and must have a path")]
+        AdditionWithoutPath { index: usize },
+    }
+}
+
+impl Link {
+    /// Load the shared index we refer to from the directory containing `split_index`, apply our bitmaps to its entries
+    /// and add the remaining entries of `split_index` so that it contains all entries.
+    ///
+    /// All other extensions are kept as they are in `split_index`, which isn't split anymore afterwards.
+    pub(crate) fn dissolve_into(
+        self,
+        split_index: &mut crate::File,
+        object_hash: git_hash::Kind,
+        options: crate::decode::Options,
+    ) -> Result<(), dissolve::Error> {
+        use dissolve::Error;
+
+        let shared_index_path = split_index
+            .path
+            .parent()
+            .expect("split index file is located in a directory")
+            .join(format!("sharedindex.{}", self.shared_index_checksum));
+        let shared_index =
+            crate::File::at(&shared_index_path, object_hash, options).map_err(|err| Error::SharedIndex {
+                path: shared_index_path,
+                source: Box::new(err),
+            })?;
+        if let Some(actual) = shared_index
+            .checksum
+            .filter(|actual| *actual != self.shared_index_checksum)
+        {
+            return Err(Error::ChecksumMismatch {
+                expected: self.shared_index_checksum,
+                actual,
+            });
+        }
+
+        let State {
+            entries: mut shared_entries,
+            path_backing: mut shared_backing,
+            ..
+        } = shared_index.state;
+        let split_entries = std::mem::take(&mut split_index.state.entries);
+        let split_backing = std::mem::take(&mut split_index.state.path_backing);
+        let num_entries = shared_entries.len();
+        let mut split_entries = split_entries.into_iter().enumerate().peekable();
+
+        if let Some(bitmaps) = self.bitmaps {
+            let set_bits = |bitmap: &git_bitmap::ewah::Vec| {
+                let mut indices = Vec::new();
+                bitmap.for_each_set_bit(|index| {
+                    indices.push(index);
+                    Some(())
+                });
+                indices
+            };
+
+            let replacements = set_bits(&bitmaps.replace);
+            for &index in &replacements {
+                let shared_entry = shared_entries.get_mut(index).ok_or(Error::BitmapOutOfBounds {
+                    kind: "replace",
+                    index,
+                    num_entries,
+                })?;
+                let (split_index, split_entry) = split_entries.next().ok_or(Error::MissingReplacement {
+                    num_replacements: replacements.len(),
+                })?;
+                if !split_entry.path_in(&split_backing).is_empty() {
+                    return Err(Error::ReplacementWithPath { index: split_index });
+                }
+                *shared_entry = Entry {
+                    path: shared_entry.path.clone(),
+                    ..split_entry
+                };
+            }
+
+            let mut is_deleted = vec![false; num_entries];
+            for index in set_bits(&bitmaps.delete) {
+                *is_deleted.get_mut(index).ok_or(Error::BitmapOutOfBounds {
+                    kind: "delete",
+                    index,
+                    num_entries,
+                })? = true;
+            }
+            let mut is_deleted = is_deleted.into_iter();
+            shared_entries.retain(|_| !is_deleted.next().expect("one flag per entry"));
+        }
+
+        for (split_index, mut split_entry) in split_entries {
+            let path = split_entry.path_in(&split_backing);
+            if path.is_empty() {
+                return Err(Error::AdditionWithoutPath { index: split_index });
+            }
+            let stage = split_entry.stage();
+            let position = shared_entries
+                .binary_search_by(|e| e.path_in(&shared_backing).cmp(path).then_with(|| e.stage().cmp(&stage)));
+            let start = shared_backing.len();
+            shared_backing.extend_from_slice(path);
+            split_entry.path = start..shared_backing.len();
+            match position {
+                Ok(idx) => shared_entries[idx] = split_entry,
+                Err(idx) => shared_entries.insert(idx, split_entry),
+            }
+        }
+
+        let state = &mut split_index.state;
+        state.is_sparse = shared_entries.iter().any(|e| e.mode.is_sparse());
+        state.entries = shared_entries;
+        state.path_backing = shared_backing;
+        Ok(())
+    }
+}
+
 pub(crate) fn decode(data: &[u8], object_hash: git_hash::Kind) -> Result<Link, decode::Error> {
     let (id, data) = split_at_pos(data, object_hash.len_in_bytes())
         .ok_or(decode::Error::Corrupt(
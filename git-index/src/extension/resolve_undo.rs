@@ -1,3 +1,5 @@
+use std::{convert::TryFrom, io::Write};
+
 use bstr::BString;
 use git_hash::ObjectId;
 
@@ -6,28 +8,52 @@ use crate::{
     util::{split_at_byte_exclusive, split_at_pos},
 };
 
+/// All paths with information about how their conflicts were resolved, sorted by path.
 pub type Paths = Vec<ResolvePath>;
 
-#[allow(dead_code)]
-#[derive(Clone)]
+/// A path that was conflicted along with the entries of all of its conflict stages, for use in undoing the resolution.
+#[derive(Clone, Debug, PartialEq, Eq)]
 pub struct ResolvePath {
     /// relative to the root of the repository, or what would be stored in the index
-    name: BString,
+    pub name: BString,
 
-    /// 0 = ancestor/common, 1 = ours, 2 = theirs
-    stages: [Option<Stage>; 3],
+    /// 0 = ancestor/common, 1 = ours, 2 = theirs, which is the entry's conflict stage minus one.
+    pub stages: [Option<Stage>; 3],
 }
 
-#[allow(dead_code)]
-#[derive(Clone, Copy)]
+/// The information of an entry at a conflict stage.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub struct Stage {
-    mode: u32,
-    id: ObjectId,
+    /// The mode of the entry, as stored in the index.
+    pub mode: u32,
+    /// The id of the entry's object.
+    pub id: ObjectId,
 }
 
+/// The signature of the resolve-undo extension.
 pub const SIGNATURE: Signature = *b"REUC";
 
-pub fn decode(mut data: &[u8], object_hash: git_hash::Kind) -> Option<Paths> {
+/// Serialize `paths` as resolve-undo extension to `out`.
+pub fn write_to(paths: &[ResolvePath], mut out: impl std::io::Write) -> std::io::Result<()> {
+    let mut data = Vec::new();
+    for path in paths {
+        data.extend_from_slice(&path.name);
+        data.push(0);
+        for stage in &path.stages {
+            write!(data, "{:o}", stage.map_or(0, |stage| stage.mode))?;
+            data.push(0);
+        }
+        for stage in path.stages.iter().flatten() {
+            data.extend_from_slice(stage.id.as_bytes());
+        }
+    }
+
+    out.write_all(&SIGNATURE)?;
+    out.write_all(&(u32::try_from(data.len()).expect("less than 4GB resolve-undo extension")).to_be_bytes())?;
+    out.write_all(&data)
+}
+
+pub(crate) fn decode(mut data: &[u8], object_hash: git_hash::Kind) -> Option<Paths> {
     let hash_len = object_hash.len_in_bytes();
     let mut out = Vec::new();
 
@@ -0,0 +1,28 @@
+use bstr::{BStr, ByteSlice};
+
+use crate::extension::Tree;
+
+impl Tree {
+    /// Mark this tree and all trees leading to the entry at `path` as invalid, as the entry was added, removed or changed.
+    ///
+    /// A sub-tree named like the entry itself is removed, as a file took the place of a directory.
+    /// Returns true if `path` was non-empty and trees were invalidated.
+    pub fn invalidate_path(&mut self, path: &BStr) -> bool {
+        if path.is_empty() {
+            return false;
+        }
+        self.num_entries = None;
+        match path.find_byte(b'/') {
+            None => {
+                self.children.retain(|child| child.name.as_slice() != path.as_bytes());
+            }
+            Some(slash) => {
+                let (name, rest) = (&path[..slash], &path[slash + 1..]);
+                if let Some(child) = self.children.iter_mut().find(|child| child.name.as_slice() == name) {
+                    child.invalidate_path(rest.as_bstr());
+                }
+            }
+        }
+        true
+    }
+}
@@ -1,16 +1,17 @@
-use std::convert::TryInto;
+use std::convert::{TryFrom, TryInto};
 
-use bstr::BString;
+use bstr::{BStr, BString};
 use git_hash::ObjectId;
 
 use crate::{
     entry,
     extension::{Signature, UntrackedCache},
     util::{read_u32, split_at_byte_exclusive, split_at_pos, var_int},
+    write,
 };
 
 /// A structure to track filesystem stat information along with an object id, linking a worktree file with what's in our ODB.
-#[derive(Clone)]
+#[derive(Clone, PartialEq, Eq, Debug)]
 pub struct OidStat {
     /// The file system stat information
     pub stat: entry::Stat,
@@ -18,8 +19,15 @@ pub struct OidStat {
     pub id: ObjectId,
 }
 
+impl OidStat {
+    /// Return true if neither stat information nor an id are set, which is how git stores the absence of information.
+    fn is_unset(&self) -> bool {
+        self.id.is_null() && self.stat == entry::Stat::default()
+    }
+}
+
 /// A directory with information about its untracked files, and its sub-directories
-#[derive(Clone)]
+#[derive(Clone, PartialEq, Eq, Debug)]
 pub struct Directory {
     /// The directories name, or an empty string if this is the root directory.
     pub name: BString,
@@ -39,7 +47,149 @@ pub struct Directory {
 /// Only used as an indicator
 pub const SIGNATURE: Signature = *b"UNTR";
 
-// #[allow(unused)]
+/// Lifecycle
+impl UntrackedCache {
+    /// Create a new instance from the `identifier` of the machine and location it is valid for, the state of the
+    /// `info_exclude` and `excludes_file` files, the name of per-directory exclude files `exclude_filename_per_dir`,
+    /// the `dir_flags` used when collecting untracked files, and the `directories` with the root directory first.
+    pub fn new(
+        identifier: BString,
+        info_exclude: Option<OidStat>,
+        excludes_file: Option<OidStat>,
+        exclude_filename_per_dir: BString,
+        dir_flags: u32,
+        directories: Vec<Directory>,
+    ) -> Self {
+        UntrackedCache {
+            identifier,
+            info_exclude,
+            excludes_file,
+            exclude_filename_per_dir,
+            dir_flags,
+            directories,
+        }
+    }
+}
+
+/// Access
+impl UntrackedCache {
+    /// Something identifying the location and machine that this cache is for.
+    pub fn identifier(&self) -> &BStr {
+        self.identifier.as_ref()
+    }
+    /// The state of the `.git/info/exclude` file when the cache was created.
+    pub fn info_exclude(&self) -> Option<&OidStat> {
+        self.info_exclude.as_ref()
+    }
+    /// The state of the file configured in `core.excludesFile` when the cache was created.
+    pub fn excludes_file(&self) -> Option<&OidStat> {
+        self.excludes_file.as_ref()
+    }
+    /// The name of the per-directory exclude files, usually `.gitignore`.
+    pub fn exclude_filename_per_dir(&self) -> &BStr {
+        self.exclude_filename_per_dir.as_ref()
+    }
+    /// The flags git used when collecting untracked files for this cache, which affect what is in it.
+    pub fn dir_flags(&self) -> u32 {
+        self.dir_flags
+    }
+    /// All directories with the root directory first, each referring to its sub-directories by index.
+    pub fn directories(&self) -> &[Directory] {
+        &self.directories
+    }
+}
+
+/// Serialization
+impl UntrackedCache {
+    /// Serialize this instance to `out`, using null-ids of kind `object_hash` for unset exclude files.
+    pub fn write_to(&self, mut out: impl std::io::Write, object_hash: git_hash::Kind) -> std::io::Result<()> {
+        let mut data = Vec::<u8>::new();
+        let mut buf = [0u8; 10];
+        data.extend_from_slice(write::util::var_int(self.identifier.len() as u64, &mut buf));
+        data.extend_from_slice(&self.identifier);
+
+        let null_oid_stat = OidStat {
+            stat: Default::default(),
+            id: ObjectId::null(object_hash),
+        };
+        for oid_stat in [&self.info_exclude, &self.excludes_file] {
+            let oid_stat = oid_stat.as_ref().unwrap_or(&null_oid_stat);
+            write_stat(&mut data, &oid_stat.stat);
+            data.extend_from_slice(oid_stat.id.as_bytes());
+        }
+        data.extend_from_slice(&self.dir_flags.to_be_bytes());
+        data.extend_from_slice(&self.exclude_filename_per_dir);
+        data.push(0);
+
+        data.extend_from_slice(write::util::var_int(self.directories.len() as u64, &mut buf));
+        if !self.directories.is_empty() {
+            let mut valid = git_bitmap::ewah::Vec::default();
+            let mut check_only = git_bitmap::ewah::Vec::default();
+            let mut hash_valid = git_bitmap::ewah::Vec::default();
+            let mut stats = Vec::new();
+            let mut hashes = Vec::new();
+            self.write_directory_block(0, &mut data, &mut |index, dir| {
+                if let Some(stat) = &dir.stat {
+                    valid.set(index);
+                    write_stat(&mut stats, stat);
+                }
+                if dir.check_only {
+                    check_only.set(index);
+                }
+                if let Some(id) = &dir.exclude_file_oid {
+                    hash_valid.set(index);
+                    hashes.extend_from_slice(id.as_bytes());
+                }
+            });
+            valid.write_to(&mut data)?;
+            check_only.write_to(&mut data)?;
+            hash_valid.write_to(&mut data)?;
+            data.extend_from_slice(&stats);
+            data.extend_from_slice(&hashes);
+            data.push(0);
+        }
+
+        out.write_all(&SIGNATURE)?;
+        out.write_all(&(u32::try_from(data.len()).expect("less than 4GB untracked cache extension")).to_be_bytes())?;
+        out.write_all(&data)
+    }
+
+    /// Write the directory at `index` along with all of its sub-directories depth-first into `out`, calling `visit(index, dir)`
+    /// for each of them in the order they are written.
+    fn write_directory_block(&self, index: usize, out: &mut Vec<u8>, visit: &mut impl FnMut(usize, &Directory)) {
+        let dir = &self.directories[index];
+        visit(index, dir);
+        let mut buf = [0u8; 10];
+        out.extend_from_slice(write::util::var_int(dir.untracked_entries.len() as u64, &mut buf));
+        out.extend_from_slice(write::util::var_int(dir.sub_directories.len() as u64, &mut buf));
+        out.extend_from_slice(&dir.name);
+        out.push(0);
+        for name in &dir.untracked_entries {
+            out.extend_from_slice(name);
+            out.push(0);
+        }
+        for sub_directory in &dir.sub_directories {
+            self.write_directory_block(*sub_directory, out, visit);
+        }
+    }
+}
+
+fn write_stat(out: &mut Vec<u8>, stat: &entry::Stat) {
+    for field in [
+        stat.ctime.secs,
+        stat.ctime.nsecs,
+        stat.mtime.secs,
+        stat.mtime.nsecs,
+        stat.dev,
+        stat.ino,
+        stat.uid,
+        stat.gid,
+        stat.size,
+    ] {
+        out.extend_from_slice(&field.to_be_bytes());
+    }
+}
+
 /// Decode an untracked cache extension from `data`, assuming object hashes are of type `object_hash`.
 pub fn decode(data: &[u8], object_hash: git_hash::Kind) -> Option<UntrackedCache> {
     if !data.last().map(|b| *b == 0).unwrap_or(false) {
@@ -58,8 +208,8 @@ pub fn decode(data: &[u8], object_hash: git_hash::Kind) -> Option<UntrackedCache
 
     let mut res = UntrackedCache {
         identifier: identifier.into(),
-        info_exclude: (!info_exclude.id.is_null()).then(|| info_exclude),
-        excludes_file: (!excludes_file.id.is_null()).then(|| excludes_file),
+        info_exclude: (!info_exclude.is_unset()).then(|| info_exclude),
+        excludes_file: (!excludes_file.is_unset()).then(|| excludes_file),
         exclude_filename_per_dir: exclude_filename_per_dir.into(),
         dir_flags,
         directories: Vec::new(),
@@ -96,13 +246,13 @@ pub fn decode(data: &[u8], object_hash: git_hash::Kind) -> Option<UntrackedCache
         directories[index].stat = stat.into();
         data = rest;
         Some(())
-    });
+    })?;
     hash_valid.for_each_set_bit(|index| {
         let (hash, rest) = split_at_pos(data, hash_len)?;
         data = rest;
         directories[index].exclude_file_oid = ObjectId::from(hash).into();
         Some(())
-    });
+    })?;
 
     // null-byte checked in the beginning
     if data.len() != 1 {
@@ -16,6 +16,8 @@ mod error {
         Io(#[from] std::io::Error),
         #[error(transparent)]
         Decode(#[from] crate::decode::Error),
+        #[error(transparent)]
+        LinkExtension(#[from] crate::extension::link::dissolve::Error),
     }
 }
 
@@ -24,6 +26,9 @@ pub use error::Error;
 /// Initialization
 impl File {
     /// Open an index file at `path` with `options`, assuming `object_hash` is used throughout the file.
+    ///
+    /// If the index is split, the shared index its [link extension][crate::State::link()] refers to is loaded from the same
+    /// directory and merged with it, so the returned instance contains all entries and no link extension.
     pub fn at(path: impl Into<PathBuf>, object_hash: git_hash::Kind, options: decode::Options) -> Result<Self, Error> {
         let path = path.into();
         let (data, mtime) = {
@@ -34,12 +39,17 @@ impl File {
             (data, filetime::FileTime::from_last_modification_time(&file.metadata()?))
         };
 
-        let (state, checksum) = State::from_bytes(&data, mtime, object_hash, options)?;
-        Ok(File {
+        let (mut state, checksum) = State::from_bytes(&data, mtime, object_hash, options)?;
+        let link = state.link.take();
+        let mut file = File {
             state,
             path,
             checksum: Some(checksum),
-        })
+        };
+        if let Some(link) = link {
+            link.dissolve_into(&mut file, object_hash, options)?;
+        }
+        Ok(file)
     }
 
     /// Consume `state` and pretend it was read from `path`, setting our checksum to `null`.
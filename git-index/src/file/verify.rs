@@ -1,5 +1,3 @@
-use std::sync::atomic::AtomicBool;
-
 use crate::File;
 
 mod error {
@@ -22,16 +20,17 @@ pub use error::Error;
 
 impl File {
     /// Verify the integrity of the index to assure its consistency.
+    ///
+    /// Hashing the file can be interrupted with [`git_features::interrupt::trigger()`].
     pub fn verify_integrity(&self) -> Result<(), Error> {
         let checksum = self.checksum.ok_or(Error::NoChecksum)?;
         let num_bytes_to_hash = self.path.metadata()?.len() - checksum.as_bytes().len() as u64;
-        let should_interrupt = AtomicBool::new(false);
         let actual = git_features::hash::bytes_of_file(
             &self.path,
             num_bytes_to_hash as usize,
             checksum.kind(),
             &mut git_features::progress::Discard,
-            &should_interrupt,
+            &git_features::interrupt::IS_INTERRUPTED,
         )?;
         (actual == checksum).then(|| ()).ok_or(Error::ChecksumMismatch {
             actual,
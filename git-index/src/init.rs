@@ -1,7 +1,30 @@
+use crate::{State, Version};
+
+/// Initialization
+impl State {
+    /// Return a new and empty in-memory index assuming the given `object_hash`.
+    pub fn new(object_hash: git_hash::Kind) -> Self {
+        State {
+            object_hash,
+            timestamp: filetime::FileTime::now(),
+            version: Version::V2,
+            entries: Vec::new(),
+            path_backing: Vec::new(),
+            is_sparse: false,
+            tree: None,
+            link: None,
+            resolve_undo: None,
+            untracked: None,
+            fs_monitor: None,
+        }
+    }
+}
+
 mod from_tree {
-    use std::collections::VecDeque;
+    use std::collections::{HashMap, VecDeque};
 
     use bstr::{BStr, BString, ByteSlice, ByteVec};
+    use git_hash::ObjectId;
     use git_object::{
         tree::{self, EntryMode},
         TreeRefIter,
@@ -10,7 +33,7 @@ mod from_tree {
 
     use crate::{
         entry::{Flags, Mode, Stat},
-        Entry, PathStorage, State, Version,
+        extension, Entry, PathStorage, State, Version,
     };
 
     /// Initialization
@@ -18,7 +41,8 @@ mod from_tree {
         /// Create an index [`State`][crate::State] by traversing `tree` recursively, accessing sub-trees
         /// with `find`.
         ///
-        /// **No extension data is currently produced**.
+        /// The [tree extension][State::tree()] is filled with all traversed trees, so the index can be turned back into
+        /// `tree` without hashing any tree.
         pub fn from_tree<Find>(tree: &git_hash::oid, mut find: Find) -> Result<Self, breadthfirst::Error>
         where
             Find: for<'a> FnMut(&git_hash::oid, &'a mut Vec<u8>) -> Option<TreeRefIter<'a>>,
@@ -34,9 +58,11 @@ mod from_tree {
                 path_backing,
                 path: _,
                 path_deque: _,
+                trees,
             } = delegate;
 
             entries.sort_by(|a, b| Entry::cmp_filepaths(a.path_in(&path_backing), b.path_in(&path_backing)));
+            let tree_extension = tree_extension(tree.to_owned(), &trees, &entries, &path_backing);
 
             Ok(State {
                 object_hash: tree.kind(),
@@ -45,7 +71,7 @@ mod from_tree {
                 entries,
                 path_backing,
                 is_sparse: false,
-                tree: None,
+                tree: Some(tree_extension),
                 link: None,
                 resolve_undo: None,
                 untracked: None,
@@ -54,11 +80,72 @@ mod from_tree {
         }
     }
 
+    /// Build the tree extension for the tree with `root_id`, given the full paths and ids of all of its sub-`trees`
+    /// and all `entries` with their paths in `path_backing`.
+    fn tree_extension(
+        root_id: ObjectId,
+        trees: &[(BString, ObjectId)],
+        entries: &[Entry],
+        path_backing: &PathStorage,
+    ) -> extension::Tree {
+        fn parent_of(path: &BStr) -> &BStr {
+            path.rfind_byte(b'/')
+                .map_or_else(|| "".into(), |pos| path[..pos].as_bstr())
+        }
+
+        let mut num_direct_entries = HashMap::<&BStr, u32>::new();
+        for entry in entries {
+            *num_direct_entries
+                .entry(parent_of(entry.path_in(path_backing)))
+                .or_default() += 1;
+        }
+        let mut sub_trees = HashMap::<&BStr, Vec<(&BStr, ObjectId)>>::new();
+        for (path, id) in trees {
+            sub_trees
+                .entry(parent_of(path.as_bstr()))
+                .or_default()
+                .push((path.as_bstr(), *id));
+        }
+
+        fn build(
+            path: &BStr,
+            id: ObjectId,
+            sub_trees: &HashMap<&BStr, Vec<(&BStr, ObjectId)>>,
+            num_direct_entries: &HashMap<&BStr, u32>,
+        ) -> extension::Tree {
+            let mut children: Vec<_> = sub_trees
+                .get(path)
+                .map(|sub_trees_of_path| {
+                    sub_trees_of_path
+                        .iter()
+                        .map(|(path, id)| build(path, *id, sub_trees, num_direct_entries))
+                        .collect()
+                })
+                .unwrap_or_default();
+            children.sort_by(|a: &extension::Tree, b| a.name.cmp(&b.name));
+            let num_entries = num_direct_entries.get(path).copied().unwrap_or_default()
+                + children
+                    .iter()
+                    .map(|child| child.num_entries.unwrap_or_default())
+                    .sum::<u32>();
+            let name = path.rfind_byte(b'/').map_or(path, |pos| path[pos + 1..].as_bstr());
+            extension::Tree {
+                name: name.as_bytes().into(),
+                id,
+                num_entries: Some(num_entries),
+                children,
+            }
+        }
+        build("".into(), root_id, &sub_trees, &num_direct_entries)
+    }
+
     struct CollectEntries {
         entries: Vec<Entry>,
         path_backing: PathStorage,
         path: BString,
         path_deque: VecDeque<BString>,
+        /// The full path and id of each tree below the root tree.
+        trees: Vec<(BString, ObjectId)>,
     }
 
     impl CollectEntries {
@@ -68,6 +155,7 @@ mod from_tree {
                 path_backing: Vec::new(),
                 path: BString::default(),
                 path_deque: VecDeque::new(),
+                trees: Vec::new(),
             }
         }
 
@@ -127,7 +215,8 @@ mod from_tree {
             }
         }
 
-        fn visit_tree(&mut self, _entry: &git_object::tree::EntryRef<'_>) -> git_traverse::tree::visit::Action {
+        fn visit_tree(&mut self, entry: &git_object::tree::EntryRef<'_>) -> git_traverse::tree::visit::Action {
+            self.trees.push((self.path.clone(), entry.oid.to_owned()));
             Action::Continue
         }
 
@@ -6,7 +6,7 @@
 #![cfg_attr(docsrs, feature(doc_cfg, doc_auto_cfg))]
 #![deny(unsafe_code, missing_docs, rust_2018_idioms)]
 
-use std::{ops::Range, path::PathBuf};
+use std::{borrow::Cow, ops::Range, path::PathBuf};
 
 use filetime::FileTime;
 
@@ -90,7 +90,6 @@ pub struct State {
     ///
     /// Note that on platforms that only have a precisions of a second for this time, we will treat all entries with the
     /// same timestamp as this as potentially changed, checking more thoroughly if a change actually happened.
-    #[allow(dead_code)]
     timestamp: FileTime,
     version: Version,
     entries: Vec<Entry>,
@@ -107,6 +106,28 @@ pub struct State {
     fs_monitor: Option<extension::FsMonitor>,
 }
 
+/// An index state like [`State`], but with the paths of its entries borrowed from the data it was decoded from, typically a
+/// memory map of the index file, to avoid copying them.
+///
+/// It's meant for reading large indices quickly, and can be turned into a [`State`] for modification.
+#[derive(Clone)]
+pub struct StateRef<'a> {
+    object_hash: git_hash::Kind,
+    timestamp: FileTime,
+    version: Version,
+    entries: Vec<Entry>,
+    /// The bytes that the paths of all entries point into, which is the entire index file unless it is [`Version::V4`].
+    path_backing: Cow<'a, PathStorageRef>,
+    is_sparse: bool,
+
+    // Extensions
+    tree: Option<extension::Tree>,
+    link: Option<extension::Link>,
+    resolve_undo: Option<extension::resolve_undo::Paths>,
+    untracked: Option<extension::UntrackedCache>,
+    fs_monitor: Option<extension::FsMonitor>,
+}
+
 pub(crate) mod util {
     use std::convert::TryInto;
 
@@ -16,6 +16,12 @@ pub enum Extensions {
     Given {
         /// Write the tree-cache extension, if present.
         tree_cache: bool,
+        /// Write the resolve-undo extension, if present.
+        resolve_undo: bool,
+        /// Write the untracked-cache extension, if present.
+        untracked_cache: bool,
+        /// Write the filesystem monitor extension, if present.
+        fs_monitor: bool,
         /// Write the end-of-index-entry extension.
         end_of_index_entry: bool,
     },
@@ -37,9 +43,15 @@ impl Extensions {
             Extensions::All => Some(signature),
             Extensions::Given {
                 tree_cache,
+                resolve_undo,
+                untracked_cache,
+                fs_monitor,
                 end_of_index_entry,
             } => match signature {
                 extension::tree::SIGNATURE => tree_cache,
+                extension::resolve_undo::SIGNATURE => resolve_undo,
+                extension::untracked_cache::SIGNATURE => untracked_cache,
+                extension::fs_monitor::SIGNATURE => fs_monitor,
                 extension::end_of_index_entry::SIGNATURE => end_of_index_entry,
                 _ => &false,
             }
@@ -50,7 +62,8 @@ impl Extensions {
 
 /// The options for use when [writing an index][State::write_to()].
 ///
-/// Note that default options write either index V2 or V3 depending on the content of the entries.
+/// Note that default options write either index V2 or V3 depending on the content of the entries, unless the state
+/// is [set][State::set_version()] to V4.
 #[derive(Debug, Default, Clone, Copy)]
 pub struct Options {
     /// Configures which extensions to write
@@ -70,7 +83,7 @@ impl State {
             .expect("definitely not 4billion entries");
 
         let offset_to_entries = header(&mut write, version, num_entries)?;
-        let offset_to_extensions = entries(&mut write, self, offset_to_entries)?;
+        let offset_to_extensions = entries(&mut write, self, version, offset_to_entries)?;
         let (extension_toc, out) = self.write_extensions(write, offset_to_extensions, extensions)?;
 
         if num_entries > 0
@@ -101,6 +114,30 @@ impl State {
                     .should_write(extension::tree::SIGNATURE)
                     .and_then(|signature| self.tree().map(|tree| tree.write_to(write).map(|_| signature)))
             },
+            &|write| {
+                extensions
+                    .should_write(extension::resolve_undo::SIGNATURE)
+                    .and_then(|signature| {
+                        self.resolve_undo()
+                            .map(|paths| extension::resolve_undo::write_to(paths, write).map(|_| signature))
+                    })
+            },
+            &|write| {
+                extensions
+                    .should_write(extension::untracked_cache::SIGNATURE)
+                    .and_then(|signature| {
+                        self.untracked()
+                            .map(|cache| cache.write_to(write, self.object_hash).map(|_| signature))
+                    })
+            },
+            &|write| {
+                extensions
+                    .should_write(extension::fs_monitor::SIGNATURE)
+                    .and_then(|signature| {
+                        self.fs_monitor()
+                            .map(|fs_monitor| fs_monitor.write_to(write).map(|_| signature))
+                    })
+            },
             &|write| {
                 self.is_sparse()
                     .then(|| extension::sparse::write_to(write).map(|_| extension::sparse::SIGNATURE))
@@ -123,6 +160,9 @@ impl State {
 
 impl State {
     fn detect_required_version(&self) -> Version {
+        if self.version == Version::V4 {
+            return Version::V4;
+        }
         self.entries
             .iter()
             .find_map(|e| e.flags.contains(entry::Flags::EXTENDED).then(|| Version::V3))
@@ -148,24 +188,57 @@ fn header<T: std::io::Write>(
     Ok(out.count)
 }
 
-fn entries<T: std::io::Write>(out: &mut CountBytes<T>, state: &State, header_size: u32) -> Result<u32, std::io::Error> {
-    for entry in state.entries() {
-        entry.write_to(&mut *out, state)?;
-        match (out.count - header_size) % 8 {
-            0 => {}
-            n => {
-                let eight_null_bytes = [0u8; 8];
-                out.write_all(&eight_null_bytes[n as usize..])?;
+fn entries<T: std::io::Write>(
+    out: &mut CountBytes<T>,
+    state: &State,
+    version: Version,
+    header_size: u32,
+) -> Result<u32, std::io::Error> {
+    match version {
+        Version::V2 | Version::V3 => {
+            for entry in state.entries() {
+                entry.write_to(&mut *out, state)?;
+                match (out.count - header_size) % 8 {
+                    0 => {}
+                    n => {
+                        let eight_null_bytes = [0u8; 8];
+                        out.write_all(&eight_null_bytes[n as usize..])?;
+                    }
+                };
+            }
+        }
+        Version::V4 => {
+            let mut previous_path = "".into();
+            for entry in state.entries() {
+                entry.write_to_with_prefix_compressed_path(&mut *out, state, previous_path)?;
+                previous_path = entry.path(state);
             }
-        };
+        }
     }
 
     Ok(out.count)
 }
 
-mod util {
+pub(crate) mod util {
     use std::convert::TryFrom;
 
+    /// Encode `n` as variable int into `buf` like git does, returning the used portion of `buf`.
+    pub fn var_int(mut n: u64, buf: &mut [u8; 10]) -> &[u8] {
+        let mut bytes_written = 1;
+        buf[buf.len() - 1] = n as u8 & 0b0111_1111;
+        for out in buf.iter_mut().rev().skip(1) {
+            n >>= 7;
+            if n == 0 {
+                break;
+            }
+            n -= 1;
+            *out = 0b1000_0000 | (n as u8 & 0b0111_1111);
+            bytes_written += 1;
+        }
+        debug_assert_eq!(n, 0, "BUG: buffer must be large enough to hold a 64 bit integer");
+        &buf[buf.len() - bytes_written..]
+    }
+
     pub struct CountBytes<T> {
         pub count: u32,
         pub inner: T,
@@ -0,0 +1,19 @@
+#!/bin/bash
+set -eu -o pipefail
+
+export GIT_INDEX_VERSION=2
+git init -q
+git config index.threads 1
+
+touch a b c d
+git add .
+git commit -m "empty"
+
+git config splitIndex.maxPercentChange 100
+git update-index --split-index
+
+echo change > b
+git add b
+git rm -q --cached c
+touch e
+git add e
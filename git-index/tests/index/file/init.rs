@@ -1,5 +1,5 @@
 mod from_state {
-    use git_index::Version::{V2, V3};
+    use git_index::Version::{V2, V3, V4};
 
     use crate::index::Fixture::*;
 
@@ -11,7 +11,7 @@ mod from_state {
             (Generated("V2_empty"), V2),
             (Generated("v2_more_files"), V2),
             (Generated("v2_all_file_kinds"), V2),
-            (Generated("v4_more_files_IEOT"), V2),
+            (Generated("v4_more_files_IEOT"), V4),
         ];
 
         for (fixture, expected_version) in fixtures {
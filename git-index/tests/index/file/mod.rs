@@ -1,4 +1,5 @@
 mod access;
 mod init;
+mod mutation;
 mod read;
 mod write;
@@ -0,0 +1,221 @@
+use bstr::ByteSlice;
+use filetime::FileTime;
+use git_index::{entry, verify::extensions::no_find, State};
+
+use crate::index::file::read;
+
+fn paths_and_stages(state: &State) -> Vec<(String, entry::Stage)> {
+    state
+        .entries()
+        .iter()
+        .map(|e| (e.path(state).to_string(), e.stage()))
+        .collect()
+}
+
+fn stage(stage: entry::Stage) -> entry::Flags {
+    entry::Flags::from_bits(stage << 12).expect("valid stage")
+}
+
+fn roundtrip(file: &git_index::File) -> State {
+    let mut buf = Vec::new();
+    file.write_to(&mut buf, Default::default()).expect("write works");
+    let (state, _) = State::from_bytes(&buf, FileTime::now(), git_hash::Kind::Sha1, Default::default()).expect("valid");
+    state
+}
+
+#[test]
+fn upsert_entry_inserts_sorted_and_invalidates_the_tree() {
+    let mut file = read::file("v2_more_files");
+    let num_entries = file.entries().len();
+    let id = git_hash::ObjectId::from_hex(b"e69de29bb2d1d6434b8b29ae775ad8c2e48c5391").expect("valid");
+
+    let idx = file.upsert_entry(
+        Default::default(),
+        id,
+        entry::Flags::empty(),
+        entry::Mode::FILE,
+        "d/b-new".into(),
+    );
+    assert_eq!(file.entries().len(), num_entries + 1);
+    assert_eq!(file.entries()[idx].path(&file), "d/b-new");
+    assert_eq!(file.entry_index_by_path_and_stage("d/b-new".into(), 0), Some(idx));
+    file.verify_entries().expect("entries are still sorted");
+
+    let tree = file.tree().expect("tree extension present");
+    assert_eq!(tree.num_entries, None, "the root tree is always affected");
+    let d = tree
+        .children
+        .iter()
+        .find(|t| t.name.as_slice() == b"d")
+        .expect("present");
+    assert_eq!(d.num_entries, None, "'d' contains the new entry");
+
+    let actual = roundtrip(&file);
+    assert_eq!(paths_and_stages(&actual), paths_and_stages(&file));
+    actual.verify_extensions(false, no_find).expect("valid");
+}
+
+#[test]
+fn upsert_entry_updates_existing_entries_in_place() {
+    let mut file = read::file("v2_more_files");
+    let num_entries = file.entries().len();
+    let previous_idx = file.entry_index_by_path_and_stage("b".into(), 0).expect("present");
+    let id = git_hash::ObjectId::null(git_hash::Kind::Sha1);
+
+    let idx = file.upsert_entry(
+        Default::default(),
+        id,
+        entry::Flags::empty(),
+        entry::Mode::FILE_EXECUTABLE,
+        "b".into(),
+    );
+    assert_eq!(idx, previous_idx);
+    assert_eq!(file.entries().len(), num_entries);
+    assert_eq!(file.entry(idx).id, id);
+    assert_eq!(file.entry(idx).mode, entry::Mode::FILE_EXECUTABLE);
+
+    let tree = file.tree().expect("tree extension present");
+    assert_eq!(tree.num_entries, None);
+    let d = tree
+        .children
+        .iter()
+        .find(|t| t.name.as_slice() == b"d")
+        .expect("present");
+    assert!(d.num_entries.is_some(), "'d' isn't affected by a change to 'b'");
+}
+
+#[test]
+fn upsert_entry_handles_stages_like_git() {
+    let mut file = read::file("v2_more_files");
+    let id = git_hash::ObjectId::from_hex(b"e69de29bb2d1d6434b8b29ae775ad8c2e48c5391").expect("valid");
+
+    for conflict_stage in [1, 2, 3] {
+        file.upsert_entry(
+            Default::default(),
+            id,
+            stage(conflict_stage),
+            entry::Mode::FILE,
+            "b".into(),
+        );
+    }
+    assert_eq!(
+        paths_and_stages(&file)
+            .into_iter()
+            .filter(|(path, _)| path == "b")
+            .collect::<Vec<_>>(),
+        vec![("b".into(), 1), ("b".into(), 2), ("b".into(), 3)],
+        "conflict stages replace stage 0"
+    );
+    file.verify_entries().expect("entries are still sorted");
+
+    let idx = file.upsert_entry(Default::default(), id, stage(0), entry::Mode::FILE, "b".into());
+    assert_eq!(
+        file.entry_range("b".into()),
+        Some(idx..idx + 1),
+        "stage 0 resolves the conflict"
+    );
+    file.verify_entries().expect("entries are still sorted");
+}
+
+#[test]
+fn remove_entries() {
+    let mut file = read::file("v2_more_files");
+    let num_entries = file.entries().len();
+
+    let removed = file.remove_entry("a".into(), 0).expect("present");
+    assert_eq!(removed.path(&file), "a", "the path stays accessible");
+    assert_eq!(file.entries().len(), num_entries - 1);
+    assert!(file.remove_entry("a".into(), 0).is_none(), "it's gone");
+
+    let num_removed = file.remove_entries(|_idx, path, _entry| path.starts_with_str("d/"));
+    assert_eq!(num_removed, 3);
+    assert_eq!(
+        paths_and_stages(&file),
+        vec![("b".into(), 0), ("c".into(), 0)],
+        "only the entries that weren't removed are left"
+    );
+    let tree = file.tree().expect("tree extension present");
+    assert_eq!(tree.num_entries, None);
+    let d = tree
+        .children
+        .iter()
+        .find(|t| t.name.as_slice() == b"d")
+        .expect("present");
+    assert_eq!(d.num_entries, None);
+
+    let actual = roundtrip(&file);
+    assert_eq!(paths_and_stages(&actual), paths_and_stages(&file));
+}
+
+#[test]
+fn conflicts_are_grouped_by_stage_and_can_be_resolved_with_undo_information() {
+    let mut file = read::file("v2_more_files");
+    let id = |hex: &[u8]| git_hash::ObjectId::from_hex(hex).expect("valid");
+    let (ancestor, ours, theirs) = (
+        id(b"e69de29bb2d1d6434b8b29ae775ad8c2e48c5391"),
+        id(b"d95f3ad14dee633a758d2e331151e950dd13e4ed"),
+        id(b"ce013625030ba8dba906f756967f9e9ca394464a"),
+    );
+    for (conflict_stage, id) in [(1, ancestor), (2, ours), (3, theirs)] {
+        file.upsert_entry(
+            Default::default(),
+            id,
+            stage(conflict_stage),
+            entry::Mode::FILE,
+            "b".into(),
+        );
+    }
+    file.upsert_entry(Default::default(), ours, stage(2), entry::Mode::FILE, "d/a".into());
+
+    let conflicts: Vec<_> = file
+        .conflicts()
+        .map(|c| {
+            (
+                c.path.to_string(),
+                c.stages.map(|e| e.map(|e| e.id)),
+                c.entry(entry::Side::Theirs).map(|e| e.id),
+            )
+        })
+        .collect();
+    assert_eq!(
+        conflicts,
+        vec![
+            ("b".into(), [Some(ancestor), Some(ours), Some(theirs)], Some(theirs)),
+            ("d/a".into(), [None, Some(ours), None], None)
+        ]
+    );
+
+    assert_eq!(
+        file.resolve("d/a".into(), entry::Side::Theirs),
+        None,
+        "the path was deleted on their side"
+    );
+    assert_eq!(
+        file.resolve("a".into(), entry::Side::Ours),
+        None,
+        "'a' isn't conflicted"
+    );
+    assert!(file.resolve_undo().is_none(), "nothing was resolved");
+
+    let idx = file.resolve("b".into(), entry::Side::Theirs).expect("conflicted");
+    assert_eq!(file.entry_range("b".into()), Some(idx..idx + 1));
+    assert_eq!(file.entry(idx).id, theirs);
+    assert_eq!(file.entry(idx).stage(), 0);
+    assert_eq!(
+        file.conflicts().map(|c| c.path.to_string()).collect::<Vec<_>>(),
+        vec!["d/a"]
+    );
+    file.verify_entries().expect("entries are still sorted");
+
+    let resolve_undo = file.resolve_undo().expect("created");
+    assert_eq!(resolve_undo.len(), 1);
+    assert_eq!(resolve_undo[0].name, "b");
+    assert_eq!(
+        resolve_undo[0].stages.map(|s| s.map(|s| (s.mode, s.id))),
+        [ancestor, ours, theirs].map(|id| Some((entry::Mode::FILE.bits(), id)))
+    );
+
+    let actual = roundtrip(&file);
+    assert_eq!(actual.resolve_undo(), file.resolve_undo(), "the extension is written");
+    assert_eq!(paths_and_stages(&actual), paths_and_stages(&file));
+}
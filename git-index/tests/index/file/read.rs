@@ -128,7 +128,34 @@ fn v2_split_index() {
     let file = file("v2_split_index");
     assert_eq!(file.version(), Version::V2);
 
-    assert!(file.link().is_some());
+    assert!(
+        file.link().is_none(),
+        "the shared index was merged into the split index"
+    );
+    assert_eq!(file.entries().len(), 1);
+    assert_eq!(file.entries()[0].path(&file), "a");
+}
+
+#[test]
+fn v2_split_index_with_replaced_deleted_and_added_entries() {
+    let file = file("v2_split_index_with_changes");
+    assert_eq!(file.version(), Version::V2);
+    assert!(file.link().is_none());
+
+    let empty_blob = hex_to_id("e69de29bb2d1d6434b8b29ae775ad8c2e48c5391");
+    assert_eq!(
+        file.entries()
+            .iter()
+            .map(|e| (e.path(&file).to_string(), e.id))
+            .collect::<Vec<_>>(),
+        vec![
+            ("a".into(), empty_blob),
+            ("b".into(), hex_to_id("0835e4f9714005ed591f68d306eea0d6d2ae8fd7")),
+            ("d".into(), empty_blob),
+            ("e".into(), empty_blob)
+        ],
+        "'b' was replaced, 'c' deleted and 'e' added"
+    );
 }
 
 #[test]
@@ -190,7 +217,19 @@ fn fsmn_v1() {
     let file = loose_file("FSMN");
     assert_eq!(file.version(), Version::V2);
 
-    assert!(file.fs_monitor().is_some());
+    let fs_monitor = file.fs_monitor().expect("present");
+    assert_eq!(
+        fs_monitor.token(),
+        &git_index::extension::fs_monitor::Token::V2 {
+            token: "1642331326943378000".into()
+        }
+    );
+    assert_eq!(fs_monitor.token().to_bstring(), "1642331326943378000");
+    assert_eq!(
+        fs_monitor.dirty_entries(),
+        (0..file.entries().len()).collect::<Vec<_>>(),
+        "all entries are dirty"
+    );
 }
 
 #[test]
@@ -291,3 +330,62 @@ fn sparse_checkout_non_cone_mode() {
         }
     });
 }
+
+#[test]
+fn state_ref_borrows_paths_from_the_index_file_unless_they_are_prefix_compressed() {
+    for (path, name) in [
+        (crate::fixture_index_path("v2"), "v2"),
+        (crate::fixture_index_path("v2_more_files"), "v2_more_files"),
+        (crate::fixture_index_path("v4_more_files_IEOT"), "v4_more_files_IEOT"),
+        (loose_file_path("extended-flags"), "extended-flags"),
+        (loose_file_path("conflicting-file"), "conflicting-file"),
+    ] {
+        let data = std::fs::read(&path).unwrap();
+        for thread_limit in [Some(1), None] {
+            let options = git_index::decode::Options {
+                thread_limit,
+                min_extension_block_in_bytes_for_threading: 0,
+            };
+            let timestamp = filetime::FileTime::now();
+            let (expected, expected_checksum) =
+                git_index::State::from_bytes(&data, timestamp, git_hash::Kind::Sha1, options).unwrap();
+            let (actual, checksum) =
+                git_index::StateRef::from_bytes(&data, timestamp, git_hash::Kind::Sha1, options).unwrap();
+            assert_eq!(checksum, expected_checksum);
+            assert_eq!(actual.version(), expected.version());
+            assert_eq!(actual.tree(), expected.tree(), "{}", name);
+
+            let borrows_paths = std::ptr::eq(actual.path_backing(), data.as_slice());
+            assert_eq!(
+                borrows_paths,
+                actual.version() != Version::V4,
+                "{}: paths are borrowed unless they are compressed",
+                name
+            );
+            assert_eq!(
+                actual.entries_with_paths().map(|(path, _)| path).collect::<Vec<_>>(),
+                expected.entries().iter().map(|e| e.path(&expected)).collect::<Vec<_>>(),
+                "{}",
+                name
+            );
+            for entry in expected.entries() {
+                let path = entry.path(&expected);
+                assert_eq!(
+                    actual
+                        .entry_by_path_and_stage(path, entry.stage())
+                        .map(|e| (e.id, e.flags, e.mode, e.stat)),
+                    Some((entry.id, entry.flags, entry.mode, entry.stat)),
+                    "{}",
+                    name
+                );
+            }
+
+            let actual = actual.into_state();
+            assert_eq!(actual.entries().len(), expected.entries().len());
+            for (a, e) in actual.entries().iter().zip(expected.entries()) {
+                assert_eq!(a.path(&actual), e.path(&expected));
+            }
+            assert_eq!(actual.path_backing(), expected.path_backing(), "{}", name);
+        }
+    }
+}
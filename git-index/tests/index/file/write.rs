@@ -10,16 +10,24 @@ fn roundtrips() -> crate::Result {
         (Loose("extended-flags"), only_tree_ext()),
         (Loose("conflicting-file"), only_tree_ext()),
         (Loose("very-long-path"), only_tree_ext()),
+        (Loose("UNTR"), all_ext_but_eoie()),
+        (Loose("UNTR-with-oids"), all_ext_but_eoie()),
+        (Loose("FSMN"), all_ext_but_eoie()),
+        (Loose("REUC"), all_ext_but_eoie()),
         (
             Generated("v2"),
             options_with(write::Extensions::Given {
                 tree_cache: true,
+                resolve_undo: true,
+                untracked_cache: true,
+                fs_monitor: true,
                 end_of_index_entry: true,
             }),
         ),
         (Generated("V2_empty"), only_tree_ext()),
         (Generated("v2_more_files"), only_tree_ext()),
         (Generated("v2_all_file_kinds"), only_tree_ext()),
+        (Generated("v4_more_files"), only_tree_ext()),
     ];
 
     for (fixture, options) in input {
@@ -95,9 +103,8 @@ fn state_comparisons_with_various_extension_configurations() {
         Generated("v2_more_files"),
         Generated("v2_all_file_kinds"),
         Generated("v2_split_index"),
-        // TODO: this failes because git allows to configure the index version while gitoxide doesn't
-        //       the fixture artificially sets the version to V4 and gitoxide writes it back out as the lowest required verison, V2
-        // Generated("v4_more_files_IEOT"),
+        Generated("v4_more_files"),
+        Generated("v4_more_files_IEOT"),
         Generated("v3_skip_worktree"),
         Generated("v3_sparse_index_non_cone"),
         Generated("v3_sparse_index"),
@@ -109,10 +116,16 @@ fn state_comparisons_with_various_extension_configurations() {
             options_with(write::Extensions::All),
             options_with(write::Extensions::Given {
                 tree_cache: true,
+                resolve_undo: true,
+                untracked_cache: true,
+                fs_monitor: true,
                 end_of_index_entry: false,
             }),
             options_with(write::Extensions::Given {
                 tree_cache: false,
+                resolve_undo: false,
+                untracked_cache: false,
+                fs_monitor: false,
                 end_of_index_entry: true,
             }),
         ] {
@@ -143,6 +156,54 @@ fn extended_flags_automatically_upgrade_the_version_to_avoid_data_loss() -> crat
     Ok(())
 }
 
+#[test]
+fn v4_is_written_with_compressed_paths_if_set() -> crate::Result {
+    let mut expected = git_index::File::at(
+        fixture_index_path("v2_more_files"),
+        git_hash::Kind::Sha1,
+        Default::default(),
+    )?;
+    let mut uncompressed = Vec::new();
+    expected.write_to(&mut uncompressed, Default::default())?;
+
+    expected.set_version(Version::V4);
+    let mut buf = Vec::new();
+    let (actual_version, _digest) = expected.write_to(&mut buf, Default::default())?;
+    assert_eq!(
+        actual_version,
+        Version::V4,
+        "V4 isn't downgraded as it's chosen explicitly"
+    );
+    assert!(
+        buf.len() < uncompressed.len(),
+        "paths are prefix-compressed and entries aren't padded"
+    );
+
+    let (actual, _) = State::from_bytes(&buf, FileTime::now(), git_hash::Kind::Sha1, Default::default())?;
+    compare_states(&actual, actual_version, &expected, Default::default(), "v2_more_files");
+    Ok(())
+}
+
+#[test]
+fn fs_monitor_extension_can_be_set() -> crate::Result {
+    let mut expected = git_index::File::at(fixture_index_path("v2"), git_hash::Kind::Sha1, Default::default())?;
+    assert!(expected.fs_monitor().is_none());
+    let token = extension::fs_monitor::Token::V1 {
+        nanos_since_1970: 1642331326943378000,
+    };
+    expected.set_fs_monitor(Some(extension::FsMonitor::new(token.clone(), [0])));
+
+    let mut buf = Vec::new();
+    let (actual_version, _digest) = expected.write_to(&mut buf, Default::default())?;
+    let (actual, _) = State::from_bytes(&buf, FileTime::now(), git_hash::Kind::Sha1, Default::default())?;
+    compare_states(&actual, actual_version, &expected, Default::default(), "v2");
+
+    let fs_monitor = actual.fs_monitor().expect("written");
+    assert_eq!(fs_monitor.token(), &token);
+    assert_eq!(fs_monitor.dirty_entries(), vec![0]);
+    Ok(())
+}
+
 fn compare_states_against_baseline(
     actual: &State,
     actual_version: Version,
@@ -179,6 +240,34 @@ fn compare_states(actual: &State, actual_version: Version, expected: &State, opt
         "tree extension mismatch, actual vs option in {:?}",
         fixture
     );
+    assert_eq!(
+        actual.resolve_undo(),
+        options
+            .extensions
+            .should_write(extension::resolve_undo::SIGNATURE)
+            .and_then(|_| expected.resolve_undo()),
+        "resolve-undo extension mismatch, actual vs option in {:?}",
+        fixture
+    );
+    assert_eq!(
+        actual.untracked(),
+        options
+            .extensions
+            .should_write(extension::untracked_cache::SIGNATURE)
+            .and_then(|_| expected.untracked()),
+        "untracked cache extension mismatch, actual vs option in {:?}",
+        fixture
+    );
+    assert_eq!(
+        actual.fs_monitor().map(|m| (m.token().clone(), m.dirty_entries())),
+        options
+            .extensions
+            .should_write(extension::fs_monitor::SIGNATURE)
+            .and_then(|_| expected.fs_monitor())
+            .map(|m| (m.token().clone(), m.dirty_entries())),
+        "fsmonitor extension mismatch, actual vs option in {:?}",
+        fixture
+    );
 
     // As `write_to` does / should not mutate we can test those properties here.
     // Anything that can be configured has to be tested separately when comparing againt baseline
@@ -238,6 +327,21 @@ fn only_tree_ext() -> Options {
         extensions: write::Extensions::Given {
             end_of_index_entry: false,
             tree_cache: true,
+            resolve_undo: false,
+            untracked_cache: false,
+            fs_monitor: false,
+        },
+    }
+}
+
+fn all_ext_but_eoie() -> Options {
+    Options {
+        extensions: write::Extensions::Given {
+            end_of_index_entry: false,
+            tree_cache: true,
+            resolve_undo: true,
+            untracked_cache: true,
+            fs_monitor: true,
         },
     }
 }
@@ -26,6 +26,14 @@ fn from_tree() -> crate::Result {
     Ok(())
 }
 
+#[test]
+fn new() {
+    let state = State::new(git_hash::Kind::Sha1);
+    assert!(state.entries().is_empty());
+    assert_eq!(state.object_hash(), git_hash::Kind::Sha1);
+    state.verify_entries().expect("valid");
+}
+
 fn compare_states(actual: &State, expected: &State, fixture: &str) {
     actual.verify_entries().expect("valid");
     actual.verify_extensions(false, no_find).expect("valid");
@@ -43,4 +51,13 @@ fn compare_states(actual: &State, expected: &State, fixture: &str) {
         assert_eq!(a.mode, e.mode, "entry mode mismatch in {:?}", fixture);
         assert_eq!(a.path(actual), e.path(expected), "entry path mismatch in {:?}", fixture);
     }
+
+    if let Some(expected_tree) = expected.tree() {
+        assert_eq!(
+            actual.tree(),
+            Some(expected_tree),
+            "tree extension mismatch in {:?}",
+            fixture
+        );
+    }
 }
@@ -42,6 +42,31 @@ mod acquire {
         assert!(err_str.contains("the-resource.lock"), "it mentions the lockfile itself");
         Ok(())
     }
+
+    #[test]
+    fn fail_mode_after_duration_succeeds_once_the_lock_is_released_in_time() -> crate::Result {
+        let dir = tempfile::tempdir()?;
+        let resource = dir.path().join("the-resource");
+        let guard = git_lock::Marker::acquire_to_hold_resource(&resource, Fail::Immediately, None)?;
+        let release_after = Duration::from_millis(50);
+        let releaser = std::thread::spawn(move || {
+            std::thread::sleep(release_after);
+            drop(guard);
+        });
+        let start = Instant::now();
+        let guard = git_lock::Marker::acquire_to_hold_resource(
+            &resource,
+            Fail::AfterDurationWithBackoff(Duration::from_secs(10)),
+            None,
+        )?;
+        assert!(
+            start.elapsed() >= release_after,
+            "it had to wait for the lock to be released"
+        );
+        assert!(guard.lock_path().is_file(), "the lock is held by us now");
+        releaser.join().expect("no panic");
+        Ok(())
+    }
 }
 mod commit {
     use git_lock::acquire::Fail;
@@ -23,6 +23,9 @@ use git_path::realpath::MAX_SYMLINKS;
 ///
 pub mod parse;
 
+/// The maximum nesting level of alternates, which matches the limit used by `git`.
+pub const MAX_DEPTH: usize = 5;
+
 /// Returned by [`resolve()`]
 #[derive(thiserror::Error, Debug)]
 #[allow(missing_docs)]
@@ -35,33 +38,52 @@ pub enum Error {
     Parse(#[from] parse::Error),
     #[error("Alternates form a cycle: {} -> {}", .0.iter().map(|p| format!("'{}'", p.display())).collect::<Vec<_>>().join(" -> "), .0.first().expect("more than one directories").display())]
     Cycle(Vec<PathBuf>),
+    #[error("Alternates of '{}' are nested more than {max_depth} levels deep", .objects_directory.display())]
+    DepthLimit {
+        max_depth: usize,
+        objects_directory: PathBuf,
+    },
 }
 
 /// Given an `objects_directory`, try to resolve alternate object directories possibly located in the
 /// `./info/alternates` file into canonical paths and resolve relative paths with the help of the `current_dir`.
 /// If no alternate object database was resolved, the resulting `Vec` is empty (it is not an error
 /// if there are no alternates).
-/// It is an error once a repository is seen again as it would lead to a cycle.
+/// It is an error once a repository is seen again in its own chain of alternates as it would lead to a cycle, whereas
+/// repositories that are reachable through multiple alternates are only returned once.
+/// It's also an error if alternates are nested more deeply than [`MAX_DEPTH`].
 pub fn resolve(
     objects_directory: impl Into<PathBuf>,
     current_dir: impl AsRef<std::path::Path>,
 ) -> Result<Vec<PathBuf>, Error> {
     let relative_base = objects_directory.into();
-    let mut dirs = vec![(0, relative_base.clone())];
-    let mut out = Vec::new();
     let cwd = current_dir.as_ref();
-    let mut seen = vec![git_path::realpath_opts(&relative_base, cwd, MAX_SYMLINKS)?];
-    while let Some((depth, dir)) = dirs.pop() {
+    let relative_base_canonicalized = git_path::realpath_opts(&relative_base, cwd, MAX_SYMLINKS)?;
+    let mut dirs = vec![(0, relative_base.clone(), vec![relative_base_canonicalized.clone()])];
+    let mut out = Vec::new();
+    let mut seen = vec![relative_base_canonicalized];
+    while let Some((depth, dir, chain)) = dirs.pop() {
         match fs::read(dir.join("info").join("alternates")) {
             Ok(input) => {
                 for path in parse::content(&input)?.into_iter() {
                     let path = relative_base.join(path);
                     let path_canonicalized = git_path::realpath_opts(&path, cwd, MAX_SYMLINKS)?;
+                    if chain.contains(&path_canonicalized) {
+                        return Err(Error::Cycle(chain));
+                    }
                     if seen.contains(&path_canonicalized) {
-                        return Err(Error::Cycle(seen));
+                        continue;
+                    }
+                    if depth > MAX_DEPTH {
+                        return Err(Error::DepthLimit {
+                            max_depth: MAX_DEPTH,
+                            objects_directory: dir,
+                        });
                     }
-                    seen.push(path_canonicalized);
-                    dirs.push((depth + 1, path));
+                    seen.push(path_canonicalized.clone());
+                    let mut chain = chain.clone();
+                    chain.push(path_canonicalized);
+                    dirs.push((depth + 1, path, chain));
                 }
             }
             Err(err) if err.kind() == io::ErrorKind::NotFound => {}
@@ -152,6 +152,33 @@ mod impls {
         }
     }
 
+    #[cfg(feature = "async-io")]
+    #[async_trait::async_trait(?Send)]
+    impl<S> crate::WriteAsync for Cache<S>
+    where
+        S: crate::WriteAsync,
+    {
+        type Error = S::Error;
+
+        async fn write_stream(
+            &self,
+            kind: Kind,
+            size: u64,
+            from: impl futures_io::AsyncRead + Unpin,
+        ) -> Result<ObjectId, Self::Error> {
+            self.inner.write_stream(kind, size, from).await
+        }
+    }
+
+    impl<S> crate::Contains for Cache<S>
+    where
+        S: git_pack::Find,
+    {
+        fn contains(&self, id: impl AsRef<oid>) -> bool {
+            self.inner.contains(id)
+        }
+    }
+
     impl<S> crate::Find for Cache<S>
     where
         S: git_pack::Find,
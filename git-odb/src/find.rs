@@ -40,6 +40,44 @@ impl PotentialPrefix {
     }
 }
 
+/// Information about an object obtained without decoding it, as returned by `Handle::try_header()`.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum Header {
+    /// The object was not packed, but is stored as loose object.
+    Loose {
+        /// The kind of the object.
+        kind: git_object::Kind,
+        /// The size of the object in bytes.
+        size: u64,
+    },
+    /// The object was packed.
+    Packed(git_pack::data::decode_header::Outcome),
+}
+
+impl Header {
+    /// Return the kind of the object.
+    pub fn kind(&self) -> git_object::Kind {
+        match self {
+            Header::Loose { kind, .. } => *kind,
+            Header::Packed(out) => out.kind,
+        }
+    }
+    /// Return the size of the object in bytes.
+    pub fn size(&self) -> u64 {
+        match self {
+            Header::Loose { size, .. } => *size,
+            Header::Packed(out) => out.object_size,
+        }
+    }
+    /// Return the amount of deltas decoded to obtain this header, if the object was packed.
+    pub fn num_deltas(&self) -> Option<u32> {
+        match self {
+            Header::Loose { .. } => None,
+            Header::Packed(out) => out.num_deltas.into(),
+        }
+    }
+}
+
 ///
 pub mod existing {
     use git_hash::ObjectId;
@@ -74,7 +74,9 @@ pub mod find;
 /// An object database equivalent to `/dev/null`, dropping all objects stored into it.
 mod traits;
 
-pub use traits::{Find, FindExt, Write};
+#[cfg(feature = "async-io")]
+pub use traits::WriteAsync;
+pub use traits::{Contains, Find, FindExt, Write};
 
 /// A thread-local handle to access any object.
 pub type Handle = Cache<store::Handle<OwnShared<Store>>>;
@@ -127,6 +129,10 @@ pub struct Store {
 
     /// The amount of times we re-read the disk state to consolidate our in-memory representation.
     pub(crate) num_disk_state_consolidation: AtomicUsize,
+    /// The maximum amount of bytes of pack data to keep loaded, or 0 if there is no limit.
+    pub(crate) pack_cache_budget: AtomicUsize,
+    /// A counter incremented each time pack data is handed out, to allow unloading the least recently used packs first.
+    pub(crate) pack_use_counter: AtomicUsize,
     /// If true, we are allowed to use multi-pack indices and they must have the `object_hash` or be ignored.
     use_multi_pack_index: bool,
     /// The hash kind to use for some operations
@@ -21,4 +21,16 @@ impl Store {
     pub fn replacements(&self) -> impl Iterator<Item = (git_hash::ObjectId, git_hash::ObjectId)> + '_ {
         self.replacements.iter().cloned()
     }
+
+    /// Set the replacements to use from now on as pairs of object-ids `X` to `X-replaced` as `(X, X-replaced)`,
+    /// overriding all previous ones, as typically obtained from `refs/replace/*`.
+    ///
+    /// Replacement objects may be replaced themselves, which is resolved when looking up objects.
+    pub fn set_replacements(
+        &mut self,
+        replacements: impl IntoIterator<Item = (git_hash::ObjectId, git_hash::ObjectId)>,
+    ) {
+        self.replacements = replacements.into_iter().collect();
+        self.replacements.sort_by_key(|a| a.0);
+    }
 }
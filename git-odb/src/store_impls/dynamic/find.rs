@@ -5,7 +5,7 @@ use git_pack::{cache::DecodeEntry, data::entry::Location};
 
 use crate::store::{handle, load_index};
 
-mod error {
+pub(crate) mod error {
     use crate::{loose, pack};
 
     /// Returned by [`Handle::try_find()`][git_pack::Find::try_find()]
@@ -36,6 +36,13 @@ mod error {
             /// The original object to lookup
             id: git_hash::ObjectId,
         },
+        #[error("Reached the limit of {} chained replacements while looking for the replacement of {}", .max_depth, .id)]
+        ReplacementDepthLimit {
+            /// the maximum amount of replacements we follow.
+            max_depth: usize,
+            /// The original object to lookup
+            id: git_hash::ObjectId,
+        },
         #[error("The base object {} could not be found but is required to decode {}", .base_id, .id)]
         DeltaBaseMissing {
             /// the id of the base object which failed to lookup
@@ -86,6 +93,9 @@ mod error {
 }
 pub use error::Error;
 
+/// The maximum amount of replacements to follow when a replacement object is replaced itself, matching `git`.
+const MAX_REPLACEMENT_DEPTH: usize = 5;
+
 use crate::{
     find::{PotentialPrefix, PrefixLookupResult},
     store::types::PackId,
@@ -208,6 +218,31 @@ where
         }
     }
 
+    /// Return the id of the object that replaces `id`, or `id` itself if there is no replacement or replacements are ignored.
+    ///
+    /// Like `git`, we follow chains of replacements where a replacement object is replaced itself, but fail if
+    /// there are more than [`MAX_REPLACEMENT_DEPTH`] of them to break cycles.
+    pub(crate) fn replaced_id<'b>(&'b self, id: &'b oid) -> Result<&'b oid, Error> {
+        if self.ignore_replacements {
+            return Ok(id);
+        }
+        let mut current = id;
+        for _ in 0..=MAX_REPLACEMENT_DEPTH {
+            match self
+                .store
+                .replacements
+                .binary_search_by(|(map_this, _)| map_this.as_ref().cmp(current))
+            {
+                Ok(pos) => current = self.store.replacements[pos].1.as_ref(),
+                Err(_) => return Ok(current),
+            }
+        }
+        Err(Error::ReplacementDepthLimit {
+            max_depth: MAX_REPLACEMENT_DEPTH,
+            id: id.to_owned(),
+        })
+    }
+
     fn try_find_cached_inner<'a, 'b>(
         &'b self,
         mut id: &'b oid,
@@ -223,14 +258,8 @@ where
                     id: r.original_id.to_owned(),
                 });
             }
-        } else if !self.ignore_replacements {
-            if let Ok(pos) = self
-                .store
-                .replacements
-                .binary_search_by(|(map_this, _)| map_this.as_ref().cmp(id))
-            {
-                id = self.store.replacements[pos].1.as_ref();
-            }
+        } else {
+            id = self.replaced_id(id)?;
         }
 
         'outer: loop {
@@ -421,7 +450,7 @@ where
         }
     }
 
-    fn clear_cache(&self) {
+    pub(crate) fn clear_cache(&self) {
         self.packed_object_count.borrow_mut().take();
     }
 }
@@ -618,6 +647,16 @@ where
     }
 }
 
+impl<S> crate::Contains for super::Handle<S>
+where
+    S: Deref<Target = super::Store> + Clone,
+    Self: git_pack::Find,
+{
+    fn contains(&self, id: impl AsRef<oid>) -> bool {
+        git_pack::Find::contains(self, id)
+    }
+}
+
 impl<S> crate::Find for super::Handle<S>
 where
     S: Deref<Target = super::Store> + Clone,
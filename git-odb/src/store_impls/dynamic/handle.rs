@@ -264,6 +264,7 @@ impl super::Store {
             snapshot: RefCell::new(self.collect_snapshot()),
             max_recursion_depth: Self::INITIAL_MAX_RECURSION_DEPTH,
             packed_object_count: Default::default(),
+            inflate: Default::default(),
         }
     }
 
@@ -280,6 +281,7 @@ impl super::Store {
             snapshot: RefCell::new(self.collect_snapshot()),
             max_recursion_depth: Self::INITIAL_MAX_RECURSION_DEPTH,
             packed_object_count: Default::default(),
+            inflate: Default::default(),
         }
     }
 
@@ -398,6 +400,7 @@ where
             snapshot: RefCell::new(self.store.collect_snapshot()),
             max_recursion_depth: self.max_recursion_depth,
             packed_object_count: Default::default(),
+            inflate: Default::default(),
         }
     }
 }
@@ -0,0 +1,150 @@
+use std::ops::Deref;
+
+use git_hash::oid;
+
+use super::find::{error::DeltaBaseRecursion, Error};
+use crate::{find::Header, store::load_index};
+
+impl<S> super::Handle<S>
+where
+    S: Deref<Target = super::Store> + Clone,
+{
+    /// Return the kind and size of the object identified by `id` without decoding it, or `None` if it doesn't exist.
+    ///
+    /// For loose objects only the object header is decompressed, for packed objects the header of the delta at the
+    /// object's position and the pack entry headers of the delta chain are read.
+    pub fn try_header(&self, id: impl AsRef<oid>) -> Result<Option<Header>, Error> {
+        let mut snapshot = self.snapshot.borrow_mut();
+        self.try_header_inner(id.as_ref(), &mut snapshot, None)
+    }
+
+    fn try_header_inner<'b>(
+        &'b self,
+        mut id: &'b oid,
+        snapshot: &mut load_index::Snapshot,
+        recursion: Option<DeltaBaseRecursion<'_>>,
+    ) -> Result<Option<Header>, Error> {
+        if let Some(r) = recursion {
+            if r.depth >= self.max_recursion_depth {
+                return Err(Error::DeltaBaseRecursionLimit {
+                    max_depth: self.max_recursion_depth,
+                    id: r.original_id.to_owned(),
+                });
+            }
+        } else {
+            id = self.replaced_id(id)?;
+        }
+
+        'outer: loop {
+            {
+                let marker = snapshot.marker;
+                for idx in 0..snapshot.indices.len() {
+                    let res = {
+                        let (index, pack_offset, pack) = match snapshot.indices[idx].lookup(id) {
+                            Some(super::handle::index_lookup::Outcome {
+                                object_index: super::handle::IndexForObjectInPack { pack_id, pack_offset },
+                                index_file,
+                                pack: possibly_pack,
+                            }) => {
+                                let pack = match possibly_pack {
+                                    Some(pack) => pack.clone(),
+                                    None => match self.store.load_pack(pack_id, marker)? {
+                                        Some(pack) => {
+                                            *possibly_pack = Some(pack.clone());
+                                            pack
+                                        }
+                                        None => {
+                                            // The pack wasn't available anymore so we are supposed to try another round with a fresh index
+                                            match self.store.load_one_index(self.refresh, snapshot.marker)? {
+                                                Some(new_snapshot) => {
+                                                    *snapshot = new_snapshot;
+                                                    self.clear_cache();
+                                                    continue 'outer;
+                                                }
+                                                None => return Ok(None),
+                                            }
+                                        }
+                                    },
+                                };
+                                (index_file, pack_offset, pack)
+                            }
+                            None => continue,
+                        };
+                        let entry = pack.entry(pack_offset);
+                        let res = pack.decode_header(entry, &mut self.inflate.borrow_mut(), |id| {
+                            index.pack_offset_by_id(id).map(|pack_offset| {
+                                git_pack::data::decode_header::ResolvedBase::InPack(pack.entry(pack_offset))
+                            })
+                        });
+                        match res {
+                            Ok(out) => Ok(out),
+                            Err(git_pack::data::decode_entry::Error::DeltaBaseUnresolved(base_id)) => {
+                                // Like when finding objects, resolve bases outside of this pack by looking them up,
+                                // which can only happen in multi-pack indices or thin packs at rest.
+                                let base = self
+                                    .try_header_inner(
+                                        &base_id,
+                                        snapshot,
+                                        recursion
+                                            .map(|r| r.inc_depth())
+                                            .or_else(|| DeltaBaseRecursion::new(id).into()),
+                                    )
+                                    .map_err(|err| Error::DeltaBaseLookup {
+                                        err: Box::new(err),
+                                        base_id,
+                                        id: id.to_owned(),
+                                    })?
+                                    .ok_or_else(|| Error::DeltaBaseMissing {
+                                        base_id,
+                                        id: id.to_owned(),
+                                    })?;
+                                let entry = pack.entry(pack_offset);
+                                let index = snapshot
+                                    .indices
+                                    .iter_mut()
+                                    .find_map(|index| index.lookup(id))
+                                    .expect("object to still be present after looking up its base")
+                                    .index_file;
+                                pack.decode_header(entry, &mut self.inflate.borrow_mut(), |id| {
+                                    index
+                                        .pack_offset_by_id(id)
+                                        .map(|pack_offset| {
+                                            git_pack::data::decode_header::ResolvedBase::InPack(pack.entry(pack_offset))
+                                        })
+                                        .or_else(|| {
+                                            (id == base_id).then(|| {
+                                                git_pack::data::decode_header::ResolvedBase::OutOfPack {
+                                                    kind: base.kind(),
+                                                    num_deltas: base.num_deltas(),
+                                                }
+                                            })
+                                        })
+                                })
+                            }
+                            Err(err) => Err(err),
+                        }?
+                    };
+
+                    if idx != 0 {
+                        snapshot.indices.swap(0, idx);
+                    }
+                    return Ok(Some(Header::Packed(res)));
+                }
+            }
+
+            for lodb in snapshot.loose_dbs.iter() {
+                if let Some((kind, size)) = lodb.try_header(id)? {
+                    return Ok(Some(Header::Loose { kind, size }));
+                }
+            }
+
+            match self.store.load_one_index(self.refresh, snapshot.marker)? {
+                Some(new_snapshot) => {
+                    *snapshot = new_snapshot;
+                    self.clear_cache();
+                }
+                None => return Ok(None),
+            }
+        }
+    }
+}
@@ -121,6 +121,8 @@ impl Store {
             num_handles_stable: Default::default(),
             num_handles_unstable: Default::default(),
             num_disk_state_consolidation: Default::default(),
+            pack_cache_budget: Default::default(),
+            pack_use_counter: Default::default(),
         })
     }
 }
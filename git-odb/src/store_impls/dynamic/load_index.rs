@@ -400,6 +400,22 @@ impl super::Store {
         }
 
         let new_index = self.index.load();
+        if !needs_stable_indices {
+            // Slots that were declared garbage while stable handles were around are now free to go, along with their memory maps.
+            for (slot_idx, slot) in self.files.iter().enumerate() {
+                if new_index.slot_indices.contains(&slot_idx)
+                    || !Option::as_ref(&slot.files.load()).map_or(false, |f| f.is_disposable())
+                {
+                    continue;
+                }
+                let _lock = slot.write.lock();
+                let mut files = slot.files.load_full();
+                *Arc::make_mut(&mut files) = None;
+                slot.files.store(files);
+                // Not racy due to lock, generation must be set after unsetting the slot value AND storing it.
+                slot.generation.store(new_index.generation, Ordering::SeqCst);
+            }
+        }
         Ok(if index.state_id() == new_index.state_id() {
             // there was no change, and nothing was loaded in the meantime, reflect that in the return value to not get into loops
             None
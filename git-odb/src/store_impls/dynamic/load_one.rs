@@ -50,7 +50,10 @@ impl super::Store {
                 match slot_files {
                     Some(types::IndexAndPacks::Index(bundle)) => {
                         match bundle.data.loaded() {
-                            Some(pack) => Ok(Some(pack.clone())),
+                            Some(pack) => {
+                                self.note_pack_use(slot);
+                                Ok(Some(pack.clone()))
+                            }
                             None => {
                                 let _lock = slot.write.lock();
                                 let mut files = slot.files.load_full();
@@ -68,6 +71,11 @@ impl super::Store {
                                     }
                                 };
                                 slot.files.store(files);
+                                drop(_lock);
+                                if pack.is_some() {
+                                    self.note_pack_use(slot);
+                                    self.unload_packs_exceeding_budget(id.index);
+                                }
                                 Ok(pack)
                             }
                         }
@@ -86,7 +94,10 @@ impl super::Store {
                         match bundle.data.get(pack_index as usize) {
                             None => Ok(None), // somewhat unexpected, data must be stale
                             Some(on_disk_pack) => match on_disk_pack.loaded() {
-                                Some(pack) => Ok(Some(pack.clone())),
+                                Some(pack) => {
+                                    self.note_pack_use(slot);
+                                    Ok(Some(pack.clone()))
+                                }
                                 None => {
                                     let _lock = slot.write.lock();
                                     let mut files = slot.files.load_full();
@@ -106,6 +117,11 @@ impl super::Store {
                                         }
                                     };
                                     slot.files.store(files);
+                                    drop(_lock);
+                                    if pack.is_some() {
+                                        self.note_pack_use(slot);
+                                        self.unload_packs_exceeding_budget(id.index);
+                                    }
                                     Ok(pack)
                                 }
                             },
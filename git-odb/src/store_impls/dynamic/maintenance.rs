@@ -0,0 +1,195 @@
+use std::{
+    convert::TryFrom,
+    io::{self, BufReader, Seek, SeekFrom},
+    sync::atomic::{AtomicBool, Ordering},
+    time::SystemTime,
+};
+
+use git_features::progress::{self, Progress};
+use git_hash::ObjectId;
+use git_tempfile::{AutoRemove, ContainingDirectory};
+
+use crate::{loose, pack, Store};
+
+mod error {
+    use std::path::PathBuf;
+
+    use crate::{loose, pack};
+
+    /// The error returned by [`Store::loose_objects_older_than()`][crate::Store::loose_objects_older_than()] and
+    /// [`Store::pack_loose_objects()`][crate::Store::pack_loose_objects()].
+    #[derive(Debug, thiserror::Error)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        #[error(transparent)]
+        Iter(#[from] loose::iter::Error),
+        #[error("Could not obtain the modification time of the loose object at '{path}'")]
+        Metadata { source: std::io::Error, path: PathBuf },
+        #[error(transparent)]
+        FindLoose(#[from] loose::find::Error),
+        #[error("The loose object {id} disappeared before it could be packed")]
+        Missing { id: git_hash::ObjectId },
+        #[error(transparent)]
+        EncodeEntry(#[from] pack::data::output::entry::Error),
+        #[error("Could not create a temporary file in '{path}'")]
+        CreateTempfile { source: std::io::Error, path: PathBuf },
+        #[error("Could not write pack data into temporary file")]
+        WritePack(#[source] std::io::Error),
+        #[error(transparent)]
+        WriteBundle(#[from] pack::bundle::write::Error),
+        #[error("Could not remove file at '{path}'")]
+        RemoveFile { source: std::io::Error, path: PathBuf },
+        #[error("Interrupted")]
+        Interrupted,
+    }
+}
+pub use error::Error;
+
+/// The result of [`Store::pack_loose_objects()`][crate::Store::pack_loose_objects()].
+pub struct Outcome<P> {
+    /// The result of writing the new pack and its index, or `None` if there were no loose objects to pack.
+    ///
+    /// Its `keep_path` is always `None` as the `.keep` file is removed once the loose objects are gone.
+    pub pack: Option<pack::bundle::write::Outcome>,
+    /// The ids of all loose objects that were written into the new pack and removed from the loose object database afterwards.
+    pub packed_objects: Vec<ObjectId>,
+    /// The input progress
+    pub progress: P,
+}
+
+/// The amount of loose objects to read and compress before handing them to the pack writer.
+const CHUNK_SIZE: usize = 1000;
+
+impl Store {
+    /// Return the ids of all loose objects in our objects directory whose files were last modified before `cutoff`.
+    /// Loose objects of alternate object databases are not included.
+    ///
+    /// Objects which are removed while the loose object database is traversed are skipped.
+    pub fn loose_objects_older_than(&self, cutoff: SystemTime) -> Result<Vec<ObjectId>, Error> {
+        let mut out = Vec::new();
+        for id in loose::Store::at(self.path.clone(), self.object_hash).iter() {
+            let id = id?;
+            let path = loose::hash_path(&id, self.path.clone());
+            match std::fs::metadata(&path).and_then(|m| m.modified()) {
+                Ok(modified) if modified < cutoff => out.push(id),
+                Ok(_) => {}
+                Err(err) if err.kind() == io::ErrorKind::NotFound => {}
+                Err(err) => return Err(Error::Metadata { source: err, path }),
+            }
+        }
+        Ok(out)
+    }
+
+    /// Write all [loose objects older than `cutoff`][Store::loose_objects_older_than()] into a new pack in the `pack` directory
+    /// of our objects directory, and remove the loose object files once the pack and its index are in place.
+    /// Loose objects of alternate object databases are not touched.
+    ///
+    /// All pack data is written into registered tempfiles which are removed if the operation fails or the process is terminated,
+    /// and loose objects are only removed after the pack was persisted, so no object is lost at any point in time.
+    /// Progress is sent to `progress` and interruptions checked via `should_interrupt`.
+    ///
+    /// Handles pick up the new pack the next time they refresh their view of the disk state, which happens automatically
+    /// once they fail to find one of the removed loose objects.
+    pub fn pack_loose_objects<P>(
+        &self,
+        cutoff: SystemTime,
+        mut progress: P,
+        should_interrupt: &AtomicBool,
+    ) -> Result<Outcome<P>, Error>
+    where
+        P: Progress,
+    {
+        let ids = self.loose_objects_older_than(cutoff)?;
+        if ids.is_empty() {
+            return Ok(Outcome {
+                pack: None,
+                packed_objects: ids,
+                progress,
+            });
+        }
+
+        let pack_dir = self.path.join("pack");
+        let mut pack_file = git_tempfile::new(
+            &pack_dir,
+            ContainingDirectory::CreateAllRaceProof(Default::default()),
+            AutoRemove::Tempfile,
+        )
+        .map_err(|err| Error::CreateTempfile {
+            source: err,
+            path: pack_dir.clone(),
+        })?;
+        {
+            let loose = loose::Store::at(self.path.clone(), self.object_hash);
+            let mut write_progress = progress.add_child_with_id("write pack", *b"MPLW"); /* Maintenance Pack Loose Write */
+            write_progress.init(Some(ids.len()), progress::count("objects"));
+            let mut buf = Vec::new();
+            let entries = ids.chunks(CHUNK_SIZE).map(|chunk| {
+                if should_interrupt.load(Ordering::Relaxed) {
+                    return Err(Error::Interrupted);
+                }
+                chunk
+                    .iter()
+                    .map(|id| {
+                        let obj = loose.try_find(id, &mut buf)?.ok_or(Error::Missing { id: *id })?;
+                        let entry = pack::data::output::Entry::from_data(
+                            &pack::data::output::Count::from_data(*id, None),
+                            &obj,
+                        )?;
+                        write_progress.inc();
+                        Ok(entry)
+                    })
+                    .collect::<Result<Vec<_>, Error>>()
+            });
+            let mut pack_writer = pack::data::output::bytes::FromEntriesIter::new(
+                entries,
+                &mut pack_file,
+                u32::try_from(ids.len()).expect("less than 2^32 loose objects"),
+                pack::data::Version::V2,
+                self.object_hash,
+            );
+            for res in &mut pack_writer {
+                res.map_err(|err| match err {
+                    pack::data::output::bytes::Error::Io(err) => Error::WritePack(err),
+                    pack::data::output::bytes::Error::Input(err) => err,
+                })?;
+            }
+        }
+        pack_file.seek(SeekFrom::Start(0)).map_err(Error::WritePack)?;
+
+        let mut bundle = pack::Bundle::write_to_directory(
+            BufReader::new(pack_file),
+            Some(&pack_dir),
+            progress.add_child_with_id("index pack", *b"MPLI"), /* Maintenance Pack Loose Index */
+            should_interrupt,
+            None,
+            pack::bundle::write::Options {
+                object_hash: self.object_hash,
+                ..Default::default()
+            },
+        )?;
+
+        let mut remove_progress = progress.add_child_with_id("remove loose objects", *b"MPLR"); /* Maintenance Pack Loose Remove */
+        remove_progress.init(Some(ids.len()), progress::count("objects"));
+        for id in &ids {
+            let path = loose::hash_path(id, self.path.clone());
+            match std::fs::remove_file(&path) {
+                Ok(()) => {}
+                Err(err) if err.kind() == io::ErrorKind::NotFound => {}
+                Err(err) => return Err(Error::RemoveFile { source: err, path }),
+            }
+            remove_progress.inc();
+        }
+        if let Some(keep_path) = bundle.keep_path.take() {
+            std::fs::remove_file(&keep_path).map_err(|err| Error::RemoveFile {
+                source: err,
+                path: keep_path,
+            })?;
+        }
+
+        Ok(Outcome {
+            pack: Some(bundle),
+            packed_objects: ids,
+            progress,
+        })
+    }
+}
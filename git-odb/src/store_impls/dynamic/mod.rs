@@ -25,6 +25,7 @@ where
     pub(crate) token: Option<handle::Mode>,
     snapshot: RefCell<load_index::Snapshot>,
     packed_object_count: RefCell<Option<u64>>,
+    inflate: RefCell<git_features::zlib::Inflate>,
 }
 
 /// Decide what happens when all indices are loaded.
@@ -55,12 +56,20 @@ impl RefreshMode {
 ///
 pub mod find;
 
+mod header;
+
 ///
 pub mod iter;
 
 ///
 pub mod write;
 
+///
+pub mod write_multi_index;
+
+///
+pub mod maintenance;
+
 ///
 pub mod init;
 
@@ -81,6 +90,8 @@ mod metrics;
 
 mod access;
 
+mod pack_cache_budget;
+
 ///
 pub mod structure {
     use std::path::PathBuf;
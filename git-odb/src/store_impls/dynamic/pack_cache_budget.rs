@@ -0,0 +1,75 @@
+use std::sync::{atomic::Ordering, Arc};
+
+use crate::store::types::{IndexId, MutableIndexAndPack};
+
+impl super::Store {
+    /// Limit the amount of bytes of pack data that are kept memory mapped to `bytes`, or remove the limit if `None`.
+    ///
+    /// Whenever a pack is loaded and the sum of all loaded packs exceeds the budget, the packs least recently handed out
+    /// to handles are unloaded until the budget is met again or only the most recently loaded pack is left.
+    /// Note that handles keep the packs they already use alive until they refresh their view of the store, so memory
+    /// is only released once no handle refers to an unloaded pack anymore.
+    /// Packs that are only kept for handles requiring stable pack ids are never unloaded.
+    pub fn set_pack_cache_budget(&self, bytes: Option<usize>) {
+        self.pack_cache_budget.store(bytes.unwrap_or(0), Ordering::Relaxed);
+    }
+
+    /// Return the amount of bytes of pack data we may keep loaded at most, or `None` if there is no limit.
+    pub fn pack_cache_budget(&self) -> Option<usize> {
+        match self.pack_cache_budget.load(Ordering::Relaxed) {
+            0 => None,
+            bytes => Some(bytes),
+        }
+    }
+
+    /// Remember that pack data of `slot` was just handed out.
+    pub(crate) fn note_pack_use(&self, slot: &MutableIndexAndPack) {
+        slot.last_pack_use.store(
+            self.pack_use_counter.fetch_add(1, Ordering::Relaxed) + 1,
+            Ordering::Relaxed,
+        );
+    }
+
+    /// Unload the pack data of the least recently used slots until all loaded packs fit into our budget, but never touch
+    /// the packs of the slot at `keep`, which is the one that was just loaded.
+    ///
+    /// Note that the write lock of `keep` must not be held.
+    pub(crate) fn unload_packs_exceeding_budget(&self, keep: IndexId) {
+        let budget = match self.pack_cache_budget() {
+            Some(budget) => budget,
+            None => return,
+        };
+        let mut candidates: Vec<_> = self
+            .files
+            .iter()
+            .enumerate()
+            .filter_map(|(idx, slot)| {
+                Option::as_ref(&slot.files.load())
+                    .map(|files| files.loaded_pack_data_size())
+                    .filter(|size| *size != 0)
+                    .map(|size| (idx, size, slot.last_pack_use.load(Ordering::Relaxed)))
+            })
+            .collect();
+        let mut total: usize = candidates.iter().map(|(_, size, _)| size).sum();
+        if total <= budget {
+            return;
+        }
+
+        candidates.sort_by_key(|(_, _, last_use)| *last_use);
+        for (idx, size, _) in candidates.into_iter().filter(|(idx, _, _)| *idx != keep) {
+            let slot = &self.files[idx];
+            let _lock = slot.write.lock();
+            let mut files = slot.files.load_full();
+            let unloaded = Arc::make_mut(&mut files)
+                .as_mut()
+                .map_or(false, |files| files.unload_packs());
+            slot.files.store(files);
+            if unloaded {
+                total = total.saturating_sub(size);
+                if total <= budget {
+                    break;
+                }
+            }
+        }
+    }
+}
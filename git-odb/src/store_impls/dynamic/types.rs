@@ -223,6 +223,18 @@ impl<T: Clone> OnDiskFile<T> {
             | other @ OnDiskFileState::Missing => self.state = other,
         }
     }
+
+    /// Drop our handle to the loaded value so it will be loaded again when needed. Garbage is kept as it may still be needed.
+    /// Return true if we were loaded before.
+    pub fn unload(&mut self) -> bool {
+        match self.state {
+            OnDiskFileState::Loaded(_) => {
+                self.state = OnDiskFileState::Unloaded;
+                true
+            }
+            OnDiskFileState::Garbage(_) | OnDiskFileState::Unloaded | OnDiskFileState::Missing => false,
+        }
+    }
 }
 
 #[derive(Clone)]
@@ -291,6 +303,36 @@ impl IndexAndPacks {
         }
     }
 
+    /// The amount of bytes of all pack data files that we currently keep loaded, which excludes garbage.
+    pub(crate) fn loaded_pack_data_size(&self) -> usize {
+        fn size(file: &OnDiskFile<Arc<git_pack::data::File>>) -> usize {
+            match &file.state {
+                OnDiskFileState::Loaded(pack) => pack.data_len(),
+                _ => 0,
+            }
+        }
+        match self {
+            IndexAndPacks::Index(bundle) => size(&bundle.data),
+            IndexAndPacks::MultiIndex(bundle) => bundle.data.iter().map(size).sum(),
+        }
+    }
+
+    /// Unload all loaded pack data files, returning true if at least one of them was loaded.
+    pub(crate) fn unload_packs(&mut self) -> bool {
+        match self {
+            IndexAndPacks::Index(bundle) => bundle.data.unload(),
+            IndexAndPacks::MultiIndex(bundle) => {
+                bundle
+                    .data
+                    .iter_mut()
+                    .map(|data| data.unload())
+                    .filter(|unloaded| *unloaded)
+                    .count()
+                    != 0
+            }
+        }
+    }
+
     pub(crate) fn index_is_loaded(&self) -> bool {
         match self {
             Self::Index(bundle) => bundle.index.is_loaded(),
@@ -387,6 +429,9 @@ pub(crate) struct MutableIndexAndPack {
     /// that has different content under the same id.
     /// Must only be changed when the write lock is held.
     pub(crate) generation: AtomicGeneration,
+    /// The value of the store-wide pack use counter when pack data of this slot was last handed out by the store.
+    /// It's used to determine which packs to unload first if the pack cache budget is exceeded.
+    pub(crate) last_pack_use: AtomicUsize,
 }
 
 /// A snapshot about resource usage.
@@ -45,3 +45,33 @@ where
         })
     }
 }
+
+#[cfg(feature = "async-io")]
+#[async_trait::async_trait(?Send)]
+impl<S> crate::WriteAsync for store::Handle<S>
+where
+    S: Deref<Target = dynamic::Store> + Clone,
+{
+    type Error = Error;
+
+    async fn write_stream(
+        &self,
+        kind: Kind,
+        size: u64,
+        from: impl futures_io::AsyncRead + Unpin,
+    ) -> Result<ObjectId, Self::Error> {
+        // Don't hold on to the snapshot while awaiting, others may need it in the meantime.
+        let loose_dbs = {
+            let mut snapshot = self.snapshot.borrow_mut();
+            if snapshot.loose_dbs.is_empty() {
+                let new_snapshot = self
+                    .store
+                    .load_one_index(self.refresh, snapshot.marker)?
+                    .expect("there is always at least one ODB, and this code runs only once for initialization");
+                *snapshot = new_snapshot;
+            }
+            snapshot.loose_dbs.clone()
+        };
+        Ok(crate::WriteAsync::write_stream(&loose_dbs[0], kind, size, from).await?)
+    }
+}
@@ -0,0 +1,93 @@
+use std::{path::PathBuf, sync::atomic::AtomicBool};
+
+use git_features::progress::Progress;
+use tempfile::NamedTempFile;
+
+use crate::{pack, store::load_index, Store};
+
+mod error {
+    use std::path::PathBuf;
+
+    use crate::{pack, store};
+
+    /// The error returned by [`Store::write_multi_index()`][crate::Store::write_multi_index()].
+    #[derive(Debug, thiserror::Error)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        #[error(transparent)]
+        CollectIndices(#[from] store::load_index::Error),
+        #[error(transparent)]
+        MultiIndexWrite(#[from] pack::multi_index::write::Error),
+        #[error("Could not create a temporary file in '{path}'")]
+        CreateTempfile { source: std::io::Error, path: PathBuf },
+        #[error("Could not turn temporary file into persisted file at '{target}'")]
+        Persist {
+            source: tempfile::PersistError,
+            target: PathBuf,
+        },
+    }
+}
+pub use error::Error;
+
+/// The result of [`Store::write_multi_index()`][crate::Store::write_multi_index()].
+pub struct Outcome<P> {
+    /// The path at which the multi-pack index was written.
+    pub multi_index_path: PathBuf,
+    /// The amount of pack indices the multi-pack index refers to.
+    pub num_indices: usize,
+    /// The checksum of the written multi-pack index.
+    pub multi_index_checksum: git_hash::ObjectId,
+    /// The input progress
+    pub progress: P,
+}
+
+impl Store {
+    /// Write a `multi-pack-index` file covering all pack indices in the `pack` directory of our objects directory,
+    /// replacing an existing one atomically. Packs of alternate object databases are not included.
+    ///
+    /// Progress is sent to `progress` and interruptions checked via `should_interrupt`.
+    /// The new multi-pack index is picked up by handles the next time they refresh their view of the disk state.
+    pub fn write_multi_index<P>(&self, progress: P, should_interrupt: &AtomicBool) -> Result<Outcome<P>, Error>
+    where
+        P: Progress,
+    {
+        let index_paths: Vec<_> = Self::collect_indices_and_mtime_sorted_by_size(vec![self.path.clone()], None, None)?
+            .into_iter()
+            .filter_map(|(index, _mtime, _size)| match index {
+                load_index::Either::IndexPath(path) => Some(path),
+                load_index::Either::MultiIndexFile(_) => None,
+            })
+            .collect();
+        let num_indices = index_paths.len();
+
+        let pack_dir = self.path.join("pack");
+        let mut tempfile = NamedTempFile::new_in(&pack_dir).map_err(|err| Error::CreateTempfile {
+            source: err,
+            path: pack_dir.clone(),
+        })?;
+        let pack::multi_index::write::Outcome {
+            multi_index_checksum,
+            progress,
+        } = pack::multi_index::File::write_from_index_paths(
+            index_paths,
+            &mut tempfile,
+            progress,
+            should_interrupt,
+            pack::multi_index::write::Options {
+                object_hash: self.object_hash,
+            },
+        )?;
+
+        let multi_index_path = pack_dir.join("multi-pack-index");
+        tempfile.persist(&multi_index_path).map_err(|err| Error::Persist {
+            source: err,
+            target: multi_index_path.clone(),
+        })?;
+        Ok(Outcome {
+            multi_index_path,
+            num_indices,
+            multi_index_checksum,
+            progress,
+        })
+    }
+}
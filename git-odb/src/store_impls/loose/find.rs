@@ -4,7 +4,7 @@ use git_features::zlib;
 
 use crate::store_impls::loose::{hash_path, Store, HEADER_READ_UNCOMPRESSED_BYTES};
 
-/// Returned by [`Store::try_find()`]
+/// Returned by [`Store::try_find()`] and [`Store::try_find_stream()`]
 #[derive(thiserror::Error, Debug)]
 #[allow(missing_docs)]
 pub enum Error {
@@ -23,6 +23,21 @@ pub enum Error {
     },
 }
 
+/// A reader for the decompressed data of a loose object, as returned by [`Store::try_find_stream()`].
+pub struct Stream {
+    /// The kind of object.
+    pub kind: git_object::Kind,
+    /// The size of the decompressed object data in bytes, which is the amount of bytes this stream will yield.
+    pub size: u64,
+    inner: std::io::Take<zlib::stream::inflate::ReadBoxed<std::io::BufReader<fs::File>>>,
+}
+
+impl std::io::Read for Stream {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        self.inner.read(buf)
+    }
+}
+
 /// Object lookup
 impl Store {
     const OPEN_ACTION: &'static str = "open";
@@ -49,11 +64,13 @@ impl Store {
         mut candidates: Option<&mut HashSet<git_hash::ObjectId>>,
     ) -> Result<Option<crate::find::PrefixLookupResult>, crate::loose::iter::Error> {
         let single_directory_iter = crate::loose::Iter {
-            inner: git_features::fs::walkdir_new(&self.path.join(prefix.as_oid().to_hex_with_len(2).to_string()))
-                .min_depth(1)
-                .max_depth(1)
-                .follow_links(false)
-                .into_iter(),
+            inner: git_features::fs::walkdir_with(
+                self.path.join(prefix.as_oid().to_hex_with_len(2).to_string()),
+                Default::default(),
+            )
+            .min_depth(1)
+            .max_depth(1)
+            .into_iter(),
             hash_hex_len: prefix.as_oid().kind().len_in_hex(),
         };
         let mut candidate = None;
@@ -124,6 +141,55 @@ impl Store {
         }
     }
 
+    /// Return a [`Stream`] over the decompressed data of the object identified by `id` if present in this database,
+    /// which reads the object lazily instead of placing it into memory at once.
+    ///
+    /// Returns `Ok<None>` if there was no such object.
+    pub fn try_find_stream(&self, id: impl AsRef<git_hash::oid>) -> Result<Option<Stream>, Error> {
+        debug_assert_eq!(self.object_hash, id.as_ref().kind());
+        let path = hash_path(id.as_ref(), self.path.clone());
+        let file = match fs::File::open(&path) {
+            Ok(file) => file,
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+            Err(err) => {
+                return Err(Error::Io {
+                    source: err,
+                    action: Self::OPEN_ACTION,
+                    path,
+                })
+            }
+        };
+        let mut inflate = zlib::stream::inflate::ReadBoxed::new(std::io::BufReader::new(file));
+
+        let mut header = Vec::with_capacity(32);
+        let mut byte = [0u8; 1];
+        while header.len() < HEADER_READ_UNCOMPRESSED_BYTES && header.last() != Some(&0) {
+            let num_read = inflate.read(&mut byte).map_err(|err| Error::Io {
+                source: err,
+                action: "deflate",
+                path: path.to_owned(),
+            })?;
+            if num_read == 0 {
+                break;
+            }
+            header.push(byte[0]);
+        }
+        let (kind, size, _header_size) = git_object::decode::loose_header(&header)?;
+        Ok(Some(Stream {
+            kind,
+            size: size as u64,
+            inner: inflate.take(size as u64),
+        }))
+    }
+
+    /// Return the kind and size of the object identified by `id` if present in this database, decompressing only its header.
+    ///
+    /// Returns `Ok<None>` if there was no such object.
+    pub fn try_header(&self, id: impl AsRef<git_hash::oid>) -> Result<Option<(git_object::Kind, u64)>, Error> {
+        self.try_find_stream(id)
+            .map(|stream| stream.map(|stream| (stream.kind, stream.size)))
+    }
+
     fn find_inner<'a>(&self, id: &git_hash::oid, buf: &'a mut Vec<u8>) -> Result<git_object::Data<'a>, Error> {
         let path = hash_path(id, self.path.clone());
 
@@ -198,3 +264,9 @@ impl Store {
         Ok(git_object::Data { kind, data: buf })
     }
 }
+
+impl crate::Contains for Store {
+    fn contains(&self, id: impl AsRef<git_hash::oid>) -> bool {
+        Store::contains(self, id)
+    }
+}
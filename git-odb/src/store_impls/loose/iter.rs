@@ -14,6 +14,9 @@ impl loose::Iter {
 
         match res {
             Ok(e) => {
+                if !e.file_type().is_file() {
+                    return None;
+                }
                 let p = e.path();
                 let mut ci = p.components();
                 let (c2, c1) = (ci.next_back(), ci.next_back());
@@ -65,10 +68,9 @@ impl loose::Store {
     /// needed if iterators need to be implemented by hand in the absence of generators.
     pub fn iter(&self) -> loose::Iter {
         loose::Iter {
-            inner: fs::walkdir_new(&self.path)
+            inner: fs::walkdir_with(&self.path, Default::default())
                 .min_depth(2)
                 .max_depth(3)
-                .follow_links(false)
                 .into_iter(),
             hash_hex_len: self.object_hash.len_in_hex(),
         }
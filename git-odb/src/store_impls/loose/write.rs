@@ -96,6 +96,52 @@ impl crate::traits::Write for Store {
     }
 }
 
+#[cfg(feature = "async-io")]
+#[async_trait::async_trait(?Send)]
+impl crate::traits::WriteAsync for Store {
+    type Error = Error;
+
+    /// Write the given stream in `from` to disk as it arrives, hashing and compressing it on the way.
+    ///
+    /// This will cost at least 4 IO operations, which are blocking.
+    async fn write_stream(
+        &self,
+        kind: git_object::Kind,
+        size: u64,
+        mut from: impl futures_io::AsyncRead + Unpin,
+    ) -> Result<git_hash::ObjectId, Self::Error> {
+        use futures_lite::io::AsyncReadExt;
+
+        let mut to = self.dest()?;
+        to.write_all(&git_object::encode::loose_header(
+            kind,
+            size.try_into().expect("object size to fit into usize"),
+        ))
+        .map_err(|err| Error::Io {
+            source: err,
+            message: "write header to tempfile in",
+            path: self.path.to_owned(),
+        })?;
+
+        let mut buf = [0u8; 8192];
+        loop {
+            let bytes_read = match from.read(&mut buf).await {
+                Ok(0) => break,
+                Ok(bytes_read) => bytes_read,
+                Err(err) if err.kind() == io::ErrorKind::Interrupted => continue,
+                Err(err) => return Err(err.into()),
+            };
+            to.write_all(&buf[..bytes_read]).map_err(|err| Error::Io {
+                source: err,
+                message: "stream all data into tempfile in",
+                path: self.path.to_owned(),
+            })?;
+        }
+        to.flush()?;
+        self.finalize_object(to)
+    }
+}
+
 type CompressedTempfile = deflate::Write<NamedTempFile>;
 
 impl Store {
@@ -30,6 +30,34 @@ pub trait Write {
     ) -> Result<git_hash::ObjectId, Self::Error>;
 }
 
+/// Describe the capability to write git objects into an object store from asynchronous input streams.
+///
+/// Only reading the input is asynchronous, while hashing, compression and writing to the object store happen
+/// as the input arrives, just like with [`Write`].
+#[cfg(feature = "async-io")]
+#[async_trait::async_trait(?Send)]
+pub trait WriteAsync {
+    /// The error type used for all trait methods.
+    ///
+    /// _Note_ the default implementations require the `From<io::Error>` bound.
+    type Error: std::error::Error + From<io::Error>;
+
+    /// As [`write_stream`][WriteAsync::write_stream], but takes an [`object` kind][git_object::Kind] along with its encoded bytes.
+    async fn write_buf(&self, object: git_object::Kind, from: &[u8]) -> Result<git_hash::ObjectId, Self::Error> {
+        self.write_stream(object, from.len() as u64, from).await
+    }
+    /// Write an object of the given `kind` and `size` read from the input stream `from` into the database, returning
+    /// its id to reference it in subsequent reads.
+    ///
+    /// This is commonly used for writing blobs directly without reading them to memory first.
+    async fn write_stream(
+        &self,
+        kind: git_object::Kind,
+        size: u64,
+        from: impl futures_io::AsyncRead + Unpin,
+    ) -> Result<git_hash::ObjectId, Self::Error>;
+}
+
 /// Describe how object can be located in an object store.
 ///
 /// ## Notes
@@ -56,6 +84,15 @@ pub trait Find {
     ) -> Result<Option<git_object::Data<'a>>, Self::Error>;
 }
 
+/// Describe how to check for the existence of objects in an object store.
+///
+/// Unlike [`Find`], implementations answer using index lookups only and neither decode pack entries nor allocate buffers,
+/// which makes it suitable for hot loops like those performed during negotiation or connectivity checks.
+pub trait Contains {
+    /// Returns true if the object with `id` exists in the database.
+    fn contains(&self, id: impl AsRef<git_hash::oid>) -> bool;
+}
+
 mod _impls {
     use std::{io::Read, ops::Deref, rc::Rc, sync::Arc};
 
@@ -119,6 +156,104 @@ mod _impls {
         }
     }
 
+    #[cfg(feature = "async-io")]
+    mod async_io {
+        use std::{ops::Deref, rc::Rc, sync::Arc};
+
+        use git_hash::ObjectId;
+        use git_object::Kind;
+
+        #[async_trait::async_trait(?Send)]
+        impl<T> crate::WriteAsync for &T
+        where
+            T: crate::WriteAsync,
+        {
+            type Error = T::Error;
+
+            async fn write_buf(&self, object: Kind, from: &[u8]) -> Result<ObjectId, Self::Error> {
+                (*self).write_buf(object, from).await
+            }
+
+            async fn write_stream(
+                &self,
+                kind: Kind,
+                size: u64,
+                from: impl futures_io::AsyncRead + Unpin,
+            ) -> Result<ObjectId, Self::Error> {
+                (*self).write_stream(kind, size, from).await
+            }
+        }
+
+        #[async_trait::async_trait(?Send)]
+        impl<T> crate::WriteAsync for Arc<T>
+        where
+            T: crate::WriteAsync,
+        {
+            type Error = T::Error;
+
+            async fn write_buf(&self, object: Kind, from: &[u8]) -> Result<ObjectId, Self::Error> {
+                self.deref().write_buf(object, from).await
+            }
+
+            async fn write_stream(
+                &self,
+                kind: Kind,
+                size: u64,
+                from: impl futures_io::AsyncRead + Unpin,
+            ) -> Result<ObjectId, Self::Error> {
+                self.deref().write_stream(kind, size, from).await
+            }
+        }
+
+        #[async_trait::async_trait(?Send)]
+        impl<T> crate::WriteAsync for Rc<T>
+        where
+            T: crate::WriteAsync,
+        {
+            type Error = T::Error;
+
+            async fn write_buf(&self, object: Kind, from: &[u8]) -> Result<ObjectId, Self::Error> {
+                self.deref().write_buf(object, from).await
+            }
+
+            async fn write_stream(
+                &self,
+                kind: Kind,
+                size: u64,
+                from: impl futures_io::AsyncRead + Unpin,
+            ) -> Result<ObjectId, Self::Error> {
+                self.deref().write_stream(kind, size, from).await
+            }
+        }
+    }
+
+    impl<T> crate::Contains for &T
+    where
+        T: crate::Contains,
+    {
+        fn contains(&self, id: impl AsRef<oid>) -> bool {
+            (*self).contains(id)
+        }
+    }
+
+    impl<T> crate::Contains for Rc<T>
+    where
+        T: crate::Contains,
+    {
+        fn contains(&self, id: impl AsRef<oid>) -> bool {
+            self.deref().contains(id)
+        }
+    }
+
+    impl<T> crate::Contains for Arc<T>
+    where
+        T: crate::Contains,
+    {
+        fn contains(&self, id: impl AsRef<oid>) -> bool {
+            self.deref().contains(id)
+        }
+    }
+
     impl<T> crate::Find for &T
     where
         T: crate::Find,
@@ -100,3 +100,56 @@ fn no_alternate_in_first_objects_dir() -> crate::Result {
     assert!(alternate::resolve(tmp.path(), std::env::current_dir()?)?.is_empty());
     Ok(())
 }
+
+#[test]
+fn alternates_reachable_through_multiple_paths_are_returned_once() -> crate::Result {
+    let tmp = git_testtools::tempfile::TempDir::new()?;
+    let (from, _) = alternate_with(
+        tmp.path().join("a"),
+        tmp.path().join("c"),
+        Some(&format!("{}\n", tmp.path().join("b").display())),
+    )?;
+    alternate(tmp.path().join("b"), tmp.path().join("d"))?;
+    alternate(tmp.path().join("c"), tmp.path().join("d"))?;
+
+    let alternates = alternate::resolve(from, std::env::current_dir()?)?;
+    assert_eq!(
+        alternates
+            .iter()
+            .map(|p| p.file_name().expect("non-root").to_str().expect("utf8"))
+            .collect::<Vec<_>>(),
+        vec!["c", "d", "b"],
+        "d is only listed once even though both b and c refer to it"
+    );
+    Ok(())
+}
+
+#[test]
+fn deeply_nested_alternates_are_an_error() -> crate::Result {
+    let tmp = git_testtools::tempfile::TempDir::new()?;
+    let dirs: Vec<_> = (0..=alternate::MAX_DEPTH + 2)
+        .map(|level| tmp.path().join(level.to_string()))
+        .collect();
+    for (at, to) in dirs.iter().zip(dirs.iter().skip(1)) {
+        alternate(at, to)?;
+    }
+
+    match alternate::resolve(&dirs[0], std::env::current_dir()?) {
+        Err(alternate::Error::DepthLimit {
+            max_depth,
+            objects_directory,
+        }) => {
+            assert_eq!(max_depth, alternate::MAX_DEPTH);
+            assert_eq!(objects_directory, dirs[alternate::MAX_DEPTH + 1]);
+        }
+        res => unreachable!("should be a specific kind of error: {:?}", res),
+    }
+
+    std::fs::remove_file(dirs[alternate::MAX_DEPTH + 1].join("info").join("alternates"))?;
+    assert_eq!(
+        alternate::resolve(&dirs[0], std::env::current_dir()?)?.len(),
+        alternate::MAX_DEPTH + 1,
+        "the maximum nesting level is fine"
+    );
+    Ok(())
+}
@@ -190,6 +190,160 @@ fn multi_index_keep_open() -> crate::Result {
         "the entry can still be found even though the location is invalid"
     );
     assert_eq!(handle.store_ref().structure()?.len(), 2);
+
+    drop(stable_handle);
+    git_odb::Find::contains(&handle, non_existing_to_trigger_refresh);
+    assert_eq!(
+        handle.store_ref().metrics(),
+        git_odb::store::Metrics {
+            num_handles: 1,
+            num_refreshes: 4,
+            open_reachable_indices: 1,
+            known_reachable_indices: 1,
+            open_reachable_packs: 0,
+            known_packs: 15,
+            unused_slots: 31,
+            loose_dbs: 1,
+            unreachable_indices: 0,
+            unreachable_packs: 0
+        },
+        "without stable handles, the next refresh frees the garbage slot"
+    );
+    Ok(())
+}
+
+#[test]
+fn multi_index_rewritten_while_serving() -> crate::Result {
+    let (handle, objects_dir) = db_with_all_object_sources()?;
+    let mut stable_handle = handle.clone();
+    stable_handle.prevent_pack_unload();
+
+    let mut buf = Vec::new();
+    let ids = handle.iter()?.collect::<Result<Vec<_>, _>>()?;
+    for id in &ids {
+        handle.find(id, &mut buf)?;
+    }
+
+    let multi_index_path = objects_dir.path().join("pack/multi-pack-index");
+    handle.store_ref().write_multi_index(
+        git_features::progress::Discard,
+        &std::sync::atomic::AtomicBool::default(),
+    )?;
+    filetime::set_file_mtime(
+        &multi_index_path,
+        filetime::FileTime::from_unix_time(filetime::FileTime::now().unix_seconds() + 10, 0),
+    )?;
+    assert!(
+        !handle.contains(hex_to_id("aaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa")),
+        "a miss triggers a refresh which picks up the changed multi-pack index"
+    );
+
+    for id in &ids {
+        handle.find(id, &mut buf)?;
+        stable_handle.find(id, &mut buf)?;
+    }
+    let metrics = handle.store_ref().metrics();
+    assert_eq!(
+        metrics.known_reachable_indices, 1,
+        "only the new multi-pack index remains"
+    );
+    assert_eq!(metrics.known_packs, 3, "it now covers all packs");
+    assert_eq!(
+        metrics.unreachable_indices, 2,
+        "the previous multi-pack index and the now covered pack index are kept for the stable handle"
+    );
+
+    drop(stable_handle);
+    handle.contains(hex_to_id("aaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa"));
+    assert_eq!(handle.store_ref().metrics().unreachable_indices, 0);
+    Ok(())
+}
+
+#[test]
+fn write_multi_index() -> crate::Result {
+    let objects_dir = git_testtools::tempfile::tempdir()?;
+    git_testtools::copy_recursively_into_existing_dir(fixture_path("objects"), &objects_dir)?;
+    let handle = git_odb::at(objects_dir.path())?;
+    let num_objects_before = handle.iter()?.count();
+
+    let outcome = handle.store_ref().write_multi_index(
+        git_features::progress::Discard,
+        &std::sync::atomic::AtomicBool::default(),
+    )?;
+    assert_eq!(outcome.num_indices, 3);
+    assert_eq!(
+        outcome.multi_index_path,
+        objects_dir.path().join("pack/multi-pack-index")
+    );
+
+    let file = git_odb::pack::multi_index::File::at(&outcome.multi_index_path)?;
+    assert_eq!(file.num_indices(), 3);
+    assert_eq!(file.checksum(), outcome.multi_index_checksum);
+
+    let handle = git_odb::at(objects_dir.path())?;
+    assert_eq!(
+        handle.iter()?.count(),
+        num_objects_before,
+        "all objects are still visible"
+    );
+    assert!(
+        handle.store_ref().structure()?.iter().any(|r| matches!(
+            r,
+            store::structure::Record::MultiIndex { path, .. } if *path == outcome.multi_index_path
+        )),
+        "the multi-pack index is used"
+    );
+    Ok(())
+}
+
+#[test]
+fn pack_loose_objects() -> crate::Result {
+    let objects_dir = git_testtools::tempfile::tempdir()?;
+    git_testtools::copy_recursively_into_existing_dir(fixture_path("objects"), &objects_dir)?;
+    let handle = git_odb::at(objects_dir.path())?;
+    let loose = git_odb::loose::Store::at(objects_dir.path(), git_hash::Kind::Sha1);
+    let loose_ids = loose.iter().collect::<Result<Vec<_>, _>>()?;
+    let num_objects_before = handle.iter()?.count();
+
+    assert!(
+        handle
+            .store_ref()
+            .loose_objects_older_than(std::time::SystemTime::UNIX_EPOCH)?
+            .is_empty(),
+        "no object is older than the cutoff"
+    );
+
+    let cutoff = std::time::SystemTime::now() + std::time::Duration::from_secs(60);
+    let outcome = handle.store_ref().pack_loose_objects(
+        cutoff,
+        git_features::progress::Discard,
+        &std::sync::atomic::AtomicBool::default(),
+    )?;
+    assert_eq!(outcome.packed_objects.len(), loose_ids.len());
+    let pack = outcome.pack.expect("loose objects were packed");
+    assert_eq!(pack.index.num_objects as usize, loose_ids.len());
+    assert!(pack.data_path.expect("written to disk").is_file());
+    assert!(pack.keep_path.is_none(), "the keep file is removed after pruning");
+    assert_eq!(loose.iter().count(), 0, "all loose objects were removed");
+
+    let mut buf = Vec::new();
+    for id in &loose_ids {
+        handle.find(id, &mut buf)?;
+    }
+    let handle = git_odb::at(objects_dir.path())?;
+    assert_eq!(
+        handle.iter()?.count(),
+        num_objects_before,
+        "all objects are still visible"
+    );
+
+    let outcome = handle.store_ref().pack_loose_objects(
+        cutoff,
+        git_features::progress::Discard,
+        &std::sync::atomic::AtomicBool::default(),
+    )?;
+    assert!(outcome.pack.is_none(), "there is nothing left to pack");
+    assert!(outcome.packed_objects.is_empty());
     Ok(())
 }
 
@@ -205,6 +359,22 @@ fn write() -> crate::Result {
     Ok(())
 }
 
+#[cfg(feature = "async-io")]
+#[test]
+fn write_async() -> crate::Result {
+    use git_odb::WriteAsync;
+
+    let dir = tempfile::tempdir()?;
+    let mut handle = git_odb::at(dir.path())?;
+    handle.refresh_never();
+
+    let written_id =
+        futures_lite::future::block_on(WriteAsync::write_buf(&handle, git_object::Kind::Blob, b"hello world"))?;
+    assert_eq!(written_id, hex_to_id("95d09f2b10159347eece71399a7e2e907ea3df4f"));
+    assert!(handle.contains(written_id));
+    Ok(())
+}
+
 #[test]
 fn object_replacement() {
     let dir = git_testtools::scripted_fixture_repo_read_only("make_replaced_history.sh").unwrap();
@@ -264,6 +434,56 @@ fn object_replacement() {
     // TODO: mapping to non-existing object (can happen if replace-refs are pushed but related history isn't fetched)
 }
 
+#[test]
+fn object_replacement_chains_are_followed_and_cycles_are_detected() -> crate::Result {
+    let dir = git_testtools::scripted_fixture_repo_read_only("make_replaced_history.sh")?;
+    let short_history_link = hex_to_id("434e5a872d6738d1fffd1e11e52a1840b73668c6");
+    let orphan_of_new_history = hex_to_id("0703c317e28068f39834ae61e7ab941b7d672322");
+    let long_history_tip = hex_to_id("71f537d9d78bf6ae89a29a17e54b95a914d3d2ef");
+
+    let mut store = git_odb::Store::at_opts(dir.join(".git/objects"), Vec::new(), Default::default())?;
+    store.set_replacements(vec![
+        (orphan_of_new_history, long_history_tip),
+        (short_history_link, orphan_of_new_history),
+    ]);
+    let handle = std::sync::Arc::new(store).to_handle_arc();
+    let mut buf = Vec::new();
+    let replaced = handle.find_commit(short_history_link, &mut buf)?;
+    assert_eq!(
+        replaced.parents().collect::<Vec<_>>(),
+        vec![hex_to_id("753ccf815e7b69c9147db5bbf633fe5f7da24ad7")],
+        "the replacement of the replacement is returned"
+    );
+    drop(replaced);
+    assert_eq!(
+        handle.try_header(short_history_link)?.expect("present").size(),
+        handle.try_header(long_history_tip)?.expect("present").size(),
+        "headers see the same replacements"
+    );
+
+    let mut store = git_odb::Store::at_opts(dir.join(".git/objects"), Vec::new(), Default::default())?;
+    store.set_replacements(vec![
+        (orphan_of_new_history, short_history_link),
+        (short_history_link, orphan_of_new_history),
+    ]);
+    let mut handle = std::sync::Arc::new(store).to_handle_arc();
+    assert!(matches!(
+        handle.try_find(short_history_link, &mut buf),
+        Err(git_odb::store::find::Error::ReplacementDepthLimit { max_depth: 5, id }) if id == short_history_link
+    ));
+
+    handle.ignore_replacements = true;
+    assert_eq!(
+        handle
+            .find_commit(short_history_link, &mut buf)?
+            .parents()
+            .collect::<Vec<_>>(),
+        vec![orphan_of_new_history],
+        "cycles don't matter if replacements are ignored"
+    );
+    Ok(())
+}
+
 #[test]
 fn contains() {
     let handle = db();
@@ -368,6 +588,63 @@ fn contains() {
     assert_eq!(handle.store_ref().structure().unwrap().len(), 4);
 }
 
+#[test]
+fn contains_trait() {
+    fn contains(db: impl git_odb::Contains, hex: &str) -> bool {
+        db.contains(hex_to_id(hex))
+    }
+    let handle = db();
+
+    assert!(
+        contains(&handle, "37d4e6c5c48ba0d245164c4e10d5f41140cab980"),
+        "loose object"
+    );
+    assert!(
+        contains(&handle, "501b297447a8255d3533c6858bb692575cdefaa0"),
+        "packed object"
+    );
+    assert!(!contains(&handle, "aaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa"));
+    assert_eq!(
+        handle.store_ref().metrics().open_reachable_packs,
+        0,
+        "only indices are needed to answer"
+    );
+
+    let loose = git_odb::loose::Store::at(fixture_path("objects"), git_hash::Kind::Sha1);
+    assert!(contains(&loose, "37d4e6c5c48ba0d245164c4e10d5f41140cab980"));
+    assert!(!contains(&loose, "501b297447a8255d3533c6858bb692575cdefaa0"));
+}
+
+#[test]
+fn header() -> crate::Result {
+    let handle = db();
+    assert_eq!(
+        handle.try_header(hex_to_id("37d4e6c5c48ba0d245164c4e10d5f41140cab980"))?,
+        Some(git_odb::find::Header::Loose {
+            kind: git_object::Kind::Blob,
+            size: 9
+        }),
+        "loose object"
+    );
+    assert_eq!(
+        handle.try_header(hex_to_id("aaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa"))?,
+        None
+    );
+
+    let mut buf = Vec::new();
+    let mut num_deltified = 0;
+    for id in handle.iter()? {
+        let id = id?;
+        let header = handle.try_header(id)?.expect("object exists");
+        let obj = handle.find(id, &mut buf)?;
+        assert_eq!(header.kind(), obj.kind, "{}", id);
+        assert_eq!(header.size(), obj.data.len() as u64, "{}", id);
+        num_deltified += usize::from(header.num_deltas().unwrap_or_default() > 0);
+    }
+    assert_ne!(num_deltified, 0, "delta chains are followed as well");
+    Ok(())
+}
+
 #[test]
 fn lookup() {
     let mut handle = db();
@@ -444,6 +721,41 @@ fn lookup() {
     );
 }
 
+#[test]
+fn pack_cache_budget_unloads_least_recently_used_packs() {
+    let handle = db();
+    assert_eq!(
+        handle.store_ref().pack_cache_budget(),
+        None,
+        "there is no limit by default"
+    );
+    handle.store_ref().set_pack_cache_budget(Some(1));
+    assert_eq!(handle.store_ref().pack_cache_budget(), Some(1));
+
+    let mut buf = Vec::new();
+    for hex_id in [
+        "501b297447a8255d3533c6858bb692575cdefaa0", // pack 11fd
+        "4dac9989f96bc5b5b1263b582c08f0c5f0b58542", // pack a2bf
+        "dd25c539efbb0ab018caa4cda2d133285634e9b5", // pack c043
+    ] {
+        assert!(handle.find(hex_to_id(hex_id), &mut buf).is_ok());
+        assert_eq!(
+            handle.store_ref().metrics().open_reachable_packs,
+            1,
+            "only the most recently loaded pack is kept as even a single one exceeds the budget"
+        );
+    }
+
+    handle.store_ref().set_pack_cache_budget(None);
+    assert_eq!(handle.store_ref().pack_cache_budget(), None);
+    for hex_id in [
+        "4dac9989f96bc5b5b1263b582c08f0c5f0b58542",
+        "dd25c539efbb0ab018caa4cda2d133285634e9b5",
+    ] {
+        assert!(handle.find(hex_to_id(hex_id), &mut buf).is_ok());
+    }
+}
+
 fn assert_all_indices_loaded(handle: &git_odb::Handle, num_refreshes: usize, open_reachable_indices: usize) {
     assert_eq!(
         handle.store_ref().metrics(),
@@ -70,6 +70,33 @@ mod write {
         }
         Ok(())
     }
+
+    #[cfg(feature = "async-io")]
+    #[test]
+    fn read_and_write_async() -> Result<(), Box<dyn std::error::Error>> {
+        use git_odb::WriteAsync;
+
+        let dir = tempfile::tempdir()?;
+        let db = loose::Store::at(dir.path(), git_hash::Kind::Sha1);
+        let mut buf = Vec::new();
+        let mut buf2 = Vec::new();
+
+        for oid in object_ids() {
+            let obj = locate_oid(oid, &mut buf);
+            let actual = futures_lite::future::block_on(WriteAsync::write_stream(
+                &db,
+                obj.kind,
+                obj.data.len() as u64,
+                obj.data,
+            ))?;
+            assert_eq!(actual, oid);
+            assert_eq!(
+                db.try_find(oid, &mut buf2)?.expect("id present").decode()?,
+                obj.decode()?
+            );
+        }
+        Ok(())
+    }
 }
 
 mod contains {
@@ -274,6 +301,37 @@ cjHJZXWmV4CcRfmLsXzU8s2cR9A0DBvOxhPD1TlKC2JhBFXigjuL9U4Rbq9tdegB
         Ok(())
     }
 
+    #[test]
+    fn blob_stream() -> Result<(), Box<dyn std::error::Error>> {
+        for hex in [
+            "37d4e6c5c48ba0d245164c4e10d5f41140cab980",
+            "a706d7cd20fc8ce71489f34b50cf01011c104193",
+        ] {
+            let mut buf = Vec::new();
+            let expected = find(hex, &mut buf);
+            let mut stream = ldb().try_find_stream(hex_to_id(hex))?.expect("object exists");
+            assert_eq!(stream.kind, expected.kind);
+            assert_eq!(stream.size, expected.data.len() as u64);
+
+            let mut data = Vec::new();
+            std::io::Read::read_to_end(&mut stream, &mut data)?;
+            assert_eq!(
+                data.as_bstr(),
+                expected.data.as_bstr(),
+                "the stream yields the same data"
+            );
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn blob_stream_not_existing() -> Result<(), Box<dyn std::error::Error>> {
+        assert!(ldb()
+            .try_find_stream(hex_to_id("37d4e6c5c48ba0d245164c4e10d5f41140cab989"))?
+            .is_none());
+        Ok(())
+    }
+
     fn try_locate<'a>(hex: &str, buf: &'a mut Vec<u8>) -> Option<git_object::Data<'a>> {
         ldb().try_find(hex_to_id(hex), buf).ok().flatten()
     }
@@ -0,0 +1,90 @@
+use git_hash::{oid, ObjectId};
+
+use crate::bitmap::{File, PackPosition, Version};
+
+/// Basic file information
+impl File {
+    /// The version of the bitmap file.
+    pub fn version(&self) -> Version {
+        self.version
+    }
+    /// The path of the opened bitmap file.
+    pub fn path(&self) -> &std::path::Path {
+        &self.path
+    }
+    /// The checksum of the pack data file whose objects the bitmaps refer to.
+    pub fn pack_checksum(&self) -> ObjectId {
+        self.checksum
+    }
+    /// The amount of objects in the pack, which is one past the highest [pack position][PackPosition].
+    pub fn num_objects(&self) -> u32 {
+        self.ids.len() as u32
+    }
+    /// The amount of commits that have a bitmap.
+    pub fn num_bitmaps(&self) -> usize {
+        self.bitmaps.len()
+    }
+}
+
+/// Access
+impl File {
+    /// Return true if the objects reachable from `commit` are stored in a bitmap.
+    pub fn has_bitmap(&self, commit: impl AsRef<oid>) -> bool {
+        self.commits.contains_key(commit.as_ref())
+    }
+
+    /// Return the position of the object with `id` within the pack, or `None` if it's not contained in the pack.
+    pub fn pack_position(&self, id: impl AsRef<oid>) -> Option<PackPosition> {
+        let id = id.as_ref();
+        self.ids
+            .binary_search_by(|probe| probe.as_ref().cmp(id))
+            .ok()
+            .map(|index| self.pack_position_by_index[index])
+    }
+
+    /// Return the id of the object at `position` within the pack.
+    ///
+    /// # Panics
+    ///
+    /// If `position` is out of bounds.
+    pub fn id_at_pack_position(&self, position: PackPosition) -> &oid {
+        &self.ids[self.index_by_pack_position[position as usize] as usize]
+    }
+
+    /// Return all objects reachable from `commit` as bits in words, with bit `n % 64` of word `n / 64` set if the
+    /// object at pack position `n` is reachable, or `None` if there is no bitmap for `commit`.
+    pub fn reachable(&self, commit: impl AsRef<oid>) -> Option<Vec<u64>> {
+        let mut bitmap_index = *self.commits.get(commit.as_ref())?;
+        let mut words = vec![0u64; (self.ids.len() + 63) / 64];
+        loop {
+            let (xor_offset, bitmap) = &self.bitmaps[bitmap_index];
+            bitmap.for_each_set_bit(|bit| {
+                *words.get_mut(bit / 64)? ^= 1 << (bit % 64);
+                Some(())
+            });
+            if *xor_offset == 0 {
+                break;
+            }
+            bitmap_index -= xor_offset;
+        }
+        Some(words)
+    }
+}
+
+impl git_traverse::reachable::Bitmaps for File {
+    fn num_objects(&self) -> usize {
+        self.ids.len()
+    }
+
+    fn position(&self, id: &oid) -> Option<usize> {
+        self.pack_position(id).map(|position| position as usize)
+    }
+
+    fn id_at(&self, position: usize) -> ObjectId {
+        self.id_at_pack_position(position as PackPosition).to_owned()
+    }
+
+    fn reachable(&self, commit: &oid) -> Option<Vec<u64>> {
+        File::reachable(self, commit)
+    }
+}
@@ -0,0 +1,137 @@
+use std::{convert::TryInto, path::Path};
+
+use git_hash::ObjectId;
+
+use crate::bitmap::{File, PackPosition, Version};
+
+mod error {
+    /// The error returned by [File::at()][super::File::at()].
+    #[derive(Debug, thiserror::Error)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        #[error("Could not open pack bitmap file at '{path}'")]
+        Io {
+            source: std::io::Error,
+            path: std::path::PathBuf,
+        },
+        #[error("{message}")]
+        Corrupt { message: &'static str },
+        #[error("Unsupported pack bitmap version: {version}")]
+        UnsupportedVersion { version: u16 },
+        #[error("Pack bitmaps that don't contain the full object graph aren't supported")]
+        UnsupportedPartialGraph,
+        #[error("The bitmap was written for pack {actual}, but the index belongs to pack {expected}")]
+        PackChecksumMismatch {
+            expected: git_hash::ObjectId,
+            actual: git_hash::ObjectId,
+        },
+        #[error(transparent)]
+        Ewah(#[from] git_bitmap::ewah::decode::Error),
+    }
+}
+
+pub use error::Error;
+
+/// The bitmaps cover the entire object graph reachable from the commits they are stored for.
+const OPT_FULL_DAG: u16 = 0x1;
+/// Name-hashes of all objects follow the bitmaps.
+const OPT_HASH_CACHE: u16 = 0x4;
+
+/// Initialization
+impl File {
+    /// Open the pack bitmap file at `path`, typically next to the pack `index` it was written for.
+    ///
+    /// The pack `index` is used to translate object ids into bit positions and back.
+    pub fn at(path: impl AsRef<Path>, index: &crate::index::File) -> Result<Self, Error> {
+        let path = path.as_ref();
+        let data = crate::mmap::read_only(path).map_err(|source| Error::Io {
+            source,
+            path: path.to_owned(),
+        })?;
+        let hash_len = index.object_hash().len_in_bytes();
+        let corrupt = |message| Error::Corrupt { message };
+        if data.len() < Self::HEADER_LEN + hash_len + hash_len {
+            return Err(corrupt("pack bitmap file is truncated and too short"));
+        }
+
+        let (signature, rest) = data.split_at(4);
+        if signature != Self::SIGNATURE {
+            return Err(corrupt("Invalid signature"));
+        }
+        let (version, rest) = rest.split_at(2);
+        let version = match u16::from_be_bytes(version.try_into().expect("two bytes")) {
+            1 => Version::V1,
+            version => return Err(Error::UnsupportedVersion { version }),
+        };
+        let (options, rest) = rest.split_at(2);
+        let options = u16::from_be_bytes(options.try_into().expect("two bytes"));
+        if options & OPT_FULL_DAG == 0 {
+            return Err(Error::UnsupportedPartialGraph);
+        }
+        let (num_bitmaps, rest) = rest.split_at(4);
+        let num_bitmaps = crate::read_u32(num_bitmaps);
+        let (checksum, mut rest) = rest.split_at(hash_len);
+        let checksum = ObjectId::from(checksum);
+        if checksum != index.pack_checksum() {
+            return Err(Error::PackChecksumMismatch {
+                expected: index.pack_checksum(),
+                actual: checksum,
+            });
+        }
+
+        // The bitmaps for commits, trees, blobs and tags in that order. We don't need them as we only learn about reachability.
+        for _ in 0..4 {
+            rest = git_bitmap::ewah::decode(rest)?.1;
+        }
+
+        let num_objects = index.num_objects();
+        let mut commits = hash_hasher::HashedMap::default();
+        let mut bitmaps = Vec::with_capacity(num_bitmaps as usize);
+        for bitmap_index in 0..num_bitmaps as usize {
+            if rest.len() < 6 {
+                return Err(corrupt("eof while reading bitmap entry"));
+            }
+            let (entry_index, remaining) = rest.split_at(4);
+            let entry_index = crate::read_u32(entry_index);
+            let (xor_offset, remaining) = (remaining[0] as usize, &remaining[2..]);
+            if entry_index >= num_objects {
+                return Err(corrupt("bitmap entry refers to an object that isn't in the pack"));
+            }
+            if xor_offset > bitmap_index {
+                return Err(corrupt("bitmap is XORed with a bitmap that doesn't exist"));
+            }
+            let (bitmap, remaining) = git_bitmap::ewah::decode(remaining)?;
+            commits.insert(index.oid_at_index(entry_index).to_owned(), bitmap_index);
+            bitmaps.push((xor_offset, bitmap));
+            rest = remaining;
+        }
+        if options & OPT_HASH_CACHE != 0 && rest.len() < num_objects as usize * 4 + hash_len {
+            return Err(corrupt("eof while reading name-hash cache"));
+        }
+
+        let mut index_by_pack_position: Vec<_> = (0..num_objects).collect();
+        index_by_pack_position.sort_by_key(|&entry_index| index.pack_offset_at_index(entry_index));
+        let mut pack_position_by_index = vec![0; num_objects as usize];
+        for (pack_position, &entry_index) in index_by_pack_position.iter().enumerate() {
+            pack_position_by_index[entry_index as usize] = pack_position as PackPosition;
+        }
+
+        Ok(File {
+            path: path.to_owned(),
+            version,
+            checksum,
+            ids: (0..num_objects)
+                .map(|entry_index| index.oid_at_index(entry_index).to_owned())
+                .collect(),
+            pack_position_by_index,
+            index_by_pack_position,
+            commits,
+            bitmaps,
+        })
+    }
+}
+
+impl File {
+    const SIGNATURE: &'static [u8] = b"BITM";
+    const HEADER_LEN: usize = 4 /*signature*/ + 2 /*version*/ + 2 /*options*/ + 4 /*num bitmaps*/;
+}
@@ -0,0 +1,46 @@
+use git_hash::ObjectId;
+
+/// Known bitmap file versions
+#[derive(PartialEq, Eq, Ord, PartialOrd, Debug, Hash, Clone, Copy)]
+#[cfg_attr(feature = "serde1", derive(serde::Serialize, serde::Deserialize))]
+#[allow(missing_docs)]
+pub enum Version {
+    V1 = 1,
+}
+
+impl Default for Version {
+    fn default() -> Self {
+        Version::V1
+    }
+}
+
+/// The position of an object within the pack, with objects being ordered by their offset in the pack data file.
+///
+/// All bits in a bitmap refer to objects by their pack position.
+pub type PackPosition = u32;
+
+/// A representation of a pack bitmap file, typically stored next to the pack index as `pack-<hash>.bitmap`.
+///
+/// It stores the set of all objects reachable from a selection of commits as bitmap over all objects in the pack,
+/// which allows to compute the objects reachable from these commits without traversing them.
+pub struct File {
+    path: std::path::PathBuf,
+    version: Version,
+    checksum: ObjectId,
+    /// All objects in the pack, sorted by id like in the pack index.
+    ids: Vec<ObjectId>,
+    /// The pack position of the object at the same index in `ids`.
+    pack_position_by_index: Vec<PackPosition>,
+    /// The index into `ids` for each pack position.
+    index_by_pack_position: Vec<u32>,
+    /// The commits with a bitmap, along with the index into `bitmaps` of their bitmap.
+    commits: hash_hasher::HashedMap<ObjectId, usize>,
+    /// All bitmaps in the order they are stored in the file, along with the amount of bitmaps to go back to find the
+    /// bitmap to XOR it with, if not 0.
+    bitmaps: Vec<(usize, git_bitmap::ewah::Vec)>,
+}
+
+mod access;
+
+///
+pub mod init;
@@ -142,6 +142,7 @@ where
                 move |thread_index| {
                     (
                         Vec::<u8>::with_capacity(4096),
+                        git_features::zlib::Inflate::default(),
                         lock(&object_progress)
                             .add_child_with_id(format!("thread {}", thread_index), git_features::progress::UNKNOWN),
                         new_thread_state(),
@@ -16,12 +16,14 @@ use crate::{
     data::EntryRange,
 };
 
+#[allow(clippy::type_complexity)]
 pub(crate) fn deltas<T, F, P, MBFN, S, E>(
     object_counter: Option<git_features::progress::StepShared>,
     size_counter: Option<git_features::progress::StepShared>,
     node: &mut crate::cache::delta::Item<T>,
-    (bytes_buf, ref mut progress, state, resolve, modify_base, child_items): &mut (
+    (bytes_buf, inflate, ref mut progress, state, resolve, modify_base, child_items): &mut (
         Vec<u8>,
+        zlib::Inflate,
         P,
         S,
         F,
@@ -39,7 +41,7 @@ where
 {
     let mut decompressed_bytes_by_pack_offset = BTreeMap::new();
     let bytes_buf = RefCell::new(bytes_buf);
-    let decompress_from_resolver = |slice: EntryRange| -> Result<(crate::data::Entry, u64, Vec<u8>), Error> {
+    let mut decompress_from_resolver = |slice: EntryRange| -> Result<(crate::data::Entry, u64, Vec<u8>), Error> {
         let mut bytes_buf = bytes_buf.borrow_mut();
         bytes_buf.resize((slice.end - slice.start) as usize, 0);
         resolve(slice.clone(), &mut bytes_buf).ok_or(Error::ResolveFailed {
@@ -48,7 +50,11 @@ where
         let entry = crate::data::Entry::from_bytes(&bytes_buf, slice.start, hash_len);
         let compressed = &bytes_buf[entry.header_size() as usize..];
         let decompressed_len = entry.decompressed_size as usize;
-        Ok((entry, slice.end, decompress_all_at_once(compressed, decompressed_len)?))
+        Ok((
+            entry,
+            slice.end,
+            decompress_all_at_once(inflate, compressed, decompressed_len)?,
+        ))
     };
 
     // Traverse the tree breadth first and loose the data produced for the base as it won't be needed anymore.
@@ -150,14 +156,13 @@ where
     Ok(())
 }
 
-fn decompress_all_at_once(b: &[u8], decompressed_len: usize) -> Result<Vec<u8>, Error> {
+fn decompress_all_at_once(inflate: &mut zlib::Inflate, b: &[u8], decompressed_len: usize) -> Result<Vec<u8>, Error> {
     let mut out = Vec::new();
     out.resize(decompressed_len, 0);
-    zlib::Inflate::default()
-        .once(b, &mut out)
-        .map_err(|err| Error::ZlibInflate {
-            source: err,
-            message: "Failed to decompress entry",
-        })?;
+    inflate.reset();
+    inflate.once(b, &mut out).map_err(|err| Error::ZlibInflate {
+        source: err,
+        message: "Failed to decompress entry",
+    })?;
     Ok(out)
 }
@@ -15,6 +15,96 @@ pub fn decode_header_size(d: &[u8]) -> (u64, usize) {
     (size, consumed)
 }
 
+/// Append the size `n` to `out` in the format understood by [`decode_header_size()`].
+fn encode_header_size(mut n: u64, out: &mut Vec<u8>) {
+    while n >= 0x80 {
+        out.push((n as u8 & 0x7f) | 0x80);
+        n >>= 7;
+    }
+    out.push(n as u8);
+}
+
+/// Return a delta which produces `target` when [applied][apply()] to `base`, or `None` if `base` is larger than 4GB
+/// and thus can't be referred to by the 4 byte offsets of copy instructions.
+///
+/// Matches are found by indexing non-overlapping blocks of `base` and looking them up at every position in `target`,
+/// which finds most of the redundancy typical for changed versions of the same file without being optimal.
+pub fn encode(base: &[u8], target: &[u8]) -> Option<Vec<u8>> {
+    const BLOCK_SIZE: usize = 16;
+    if base.len() > u32::MAX as usize {
+        return None;
+    }
+    let mut out = Vec::new();
+    encode_header_size(base.len() as u64, &mut out);
+    encode_header_size(target.len() as u64, &mut out);
+
+    let mut blocks = std::collections::HashMap::<&[u8], usize>::new();
+    for (block_idx, block) in base.chunks_exact(BLOCK_SIZE).enumerate() {
+        blocks.entry(block).or_insert(block_idx * BLOCK_SIZE);
+    }
+
+    let mut insert_start = 0;
+    let mut pos = 0;
+    while pos + BLOCK_SIZE <= target.len() {
+        let mut base_ofs = match blocks.get(&target[pos..][..BLOCK_SIZE]) {
+            Some(ofs) => *ofs,
+            None => {
+                pos += 1;
+                continue;
+            }
+        };
+        let mut len = BLOCK_SIZE;
+        while base_ofs + len < base.len() && pos + len < target.len() && base[base_ofs + len] == target[pos + len] {
+            len += 1;
+        }
+        while pos > insert_start && base_ofs > 0 && base[base_ofs - 1] == target[pos - 1] {
+            base_ofs -= 1;
+            pos -= 1;
+            len += 1;
+        }
+        encode_inserts(&target[insert_start..pos], &mut out);
+        encode_copy(base_ofs, len, &mut out);
+        pos += len;
+        insert_start = pos;
+    }
+    encode_inserts(&target[insert_start..], &mut out);
+    Some(out)
+}
+
+fn encode_inserts(data: &[u8], out: &mut Vec<u8>) {
+    for chunk in data.chunks(0x7f) {
+        out.push(chunk.len() as u8);
+        out.extend_from_slice(chunk);
+    }
+}
+
+fn encode_copy(mut ofs: usize, mut len: usize, out: &mut Vec<u8>) {
+    const MAX_COPY_SIZE: usize = 0xff_ffff;
+    while len != 0 {
+        let size = len.min(MAX_COPY_SIZE);
+        let cmd_pos = out.len();
+        let mut cmd = 0b1000_0000;
+        out.push(cmd);
+        for (shift, bit) in [(0, 0b0000_0001), (8, 0b0000_0010), (16, 0b0000_0100), (24, 0b0000_1000)] {
+            let byte = (ofs >> shift) as u8;
+            if byte != 0 {
+                cmd |= bit;
+                out.push(byte);
+            }
+        }
+        for (shift, bit) in [(0, 0b0001_0000), (8, 0b0010_0000), (16, 0b0100_0000)] {
+            let byte = (size >> shift) as u8;
+            if byte != 0 {
+                cmd |= bit;
+                out.push(byte);
+            }
+        }
+        out[cmd_pos] = cmd;
+        ofs += size;
+        len -= size;
+    }
+}
+
 pub fn apply(base: &[u8], mut target: &mut [u8], data: &[u8]) {
     let mut i = 0;
     while let Some(cmd) = data.get(i) {
@@ -68,3 +158,36 @@ pub fn apply(base: &[u8], mut target: &mut [u8], data: &[u8]) {
     assert_eq!(i, data.len());
     assert_eq!(target.len(), 0);
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn round_trip(base: &[u8], target: &[u8]) -> usize {
+        let delta = encode(base, target).expect("small enough base");
+        let (base_size, consumed) = decode_header_size(&delta);
+        assert_eq!(base_size, base.len() as u64);
+        let (target_size, consumed_target) = decode_header_size(&delta[consumed..]);
+        assert_eq!(target_size, target.len() as u64);
+
+        let mut out = vec![0; target.len()];
+        apply(base, &mut out, &delta[consumed + consumed_target..]);
+        assert_eq!(out, target);
+        delta.len()
+    }
+
+    #[test]
+    fn encode_and_apply_round_trip() {
+        let base: Vec<u8> = (0..10_000u32).flat_map(|n| n.to_string().into_bytes()).collect();
+        let mut changed = base.clone();
+        changed.splice(2000..2010, b"something else entirely".iter().copied());
+        changed.extend_from_slice(b"appended");
+
+        assert_eq!(round_trip(b"", b""), 2);
+        round_trip(b"", b"only inserts");
+        round_trip(&base, b"");
+        assert!(round_trip(&base, &base) < 32, "identical content is a single copy");
+        assert!(round_trip(&base, &changed) < 128, "changes are small in delta form");
+        round_trip(b"unrelated", &changed);
+    }
+}
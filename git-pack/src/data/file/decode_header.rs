@@ -0,0 +1,108 @@
+use std::convert::TryInto;
+
+use git_features::zlib;
+
+use crate::{
+    data,
+    data::{delta, file::decode_entry::Error, File},
+};
+
+/// A return value of a resolve function, which given an [`ObjectId`][git_hash::ObjectId] determines where an object can be found,
+/// for use with [`File::decode_header()`].
+#[derive(Debug, PartialEq, Eq, Hash, Ord, PartialOrd, Clone)]
+#[cfg_attr(feature = "serde1", derive(serde::Serialize, serde::Deserialize))]
+pub enum ResolvedBase {
+    /// Indicate an object is within this pack, at the given entry, and thus can be looked up locally.
+    InPack(data::Entry),
+    /// Indicates the object of `kind` was found outside of the pack, with `num_deltas` being the amount of deltas
+    /// that had to be followed to find its kind, if known.
+    #[allow(missing_docs)]
+    OutOfPack {
+        kind: git_object::Kind,
+        num_deltas: Option<u32>,
+    },
+}
+
+/// Information about an object obtained by [`File::decode_header()`], without decoding the object itself.
+#[derive(Debug, PartialEq, Eq, Hash, Ord, PartialOrd, Clone, Copy)]
+#[cfg_attr(feature = "serde1", derive(serde::Serialize, serde::Deserialize))]
+pub struct Outcome {
+    /// The kind of resolved object.
+    pub kind: git_object::Kind,
+    /// The size of the resolved object in bytes.
+    pub object_size: u64,
+    /// The amount of deltas in the chain of objects that had to be followed to learn about the kind of the object.
+    pub num_deltas: u32,
+}
+
+/// Obtain object information quickly.
+impl File {
+    /// Resolve the kind and size of the object at `entry` without decoding it, by decompressing only the first bytes of the
+    /// delta at `entry` to learn its size, and following the chain of delta entries to the base object to learn its kind.
+    ///
+    /// `inflate` will be used to decompress the delta headers and is reset before each use.
+    /// `resolve` is a function to lookup objects with the given [`ObjectId`][git_hash::ObjectId], in case the full object id
+    /// is used to refer to a base object, instead of an in-pack offset.
+    pub fn decode_header(
+        &self,
+        mut entry: data::Entry,
+        inflate: &mut zlib::Inflate,
+        resolve: impl Fn(&git_hash::oid) -> Option<ResolvedBase>,
+    ) -> Result<Outcome, Error> {
+        use crate::data::entry::Header::*;
+        let mut num_deltas = 0;
+        let mut first_delta_result_size = None;
+        loop {
+            match entry.header {
+                Tree | Blob | Commit | Tag => {
+                    return Ok(Outcome {
+                        kind: entry.header.as_kind().expect("a non-delta entry"),
+                        object_size: first_delta_result_size.unwrap_or(entry.decompressed_size),
+                        num_deltas,
+                    })
+                }
+                OfsDelta { base_distance } => {
+                    num_deltas += 1;
+                    if first_delta_result_size.is_none() {
+                        first_delta_result_size = Some(self.decode_delta_object_size(&entry, inflate)?);
+                    }
+                    entry = self.entry(entry.base_pack_offset(base_distance))
+                }
+                RefDelta { base_id } => {
+                    num_deltas += 1;
+                    if first_delta_result_size.is_none() {
+                        first_delta_result_size = Some(self.decode_delta_object_size(&entry, inflate)?);
+                    }
+                    match resolve(base_id.as_ref()) {
+                        Some(ResolvedBase::InPack(base_entry)) => entry = base_entry,
+                        Some(ResolvedBase::OutOfPack {
+                            kind,
+                            num_deltas: base_num_deltas,
+                        }) => {
+                            return Ok(Outcome {
+                                kind,
+                                object_size: first_delta_result_size.expect("set as we are a delta"),
+                                num_deltas: base_num_deltas.unwrap_or_default() + num_deltas,
+                            })
+                        }
+                        None => return Err(Error::DeltaBaseUnresolved(base_id)),
+                    }
+                }
+            }
+        }
+    }
+
+    fn decode_delta_object_size(&self, entry: &data::Entry, inflate: &mut zlib::Inflate) -> Result<u64, Error> {
+        // The delta header consists of two sizes, each of which needs at most 10 bytes.
+        let mut buf = [0_u8; 32];
+        let offset: usize = entry.data_offset.try_into().expect("offset representable by machine");
+        assert!(offset < self.data.len(), "entry offset out of bounds");
+
+        inflate.reset();
+        let (_status, _consumed_in, consumed_out) = inflate.once(&self.data[offset..], &mut buf)?;
+        let buf = &buf[..consumed_out];
+        let (_base_size, consumed) = delta::decode_header_size(buf);
+        let (result_size, _consumed) = delta::decode_header_size(&buf[consumed..]);
+        Ok(result_size)
+    }
+}
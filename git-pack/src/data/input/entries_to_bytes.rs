@@ -97,13 +97,12 @@ where
         self.output.flush()?;
 
         self.output.seek(std::io::SeekFrom::Start(0))?;
-        let interrupt_never = std::sync::atomic::AtomicBool::new(false);
         let digest = hash::bytes(
             &mut self.output,
             num_bytes_written as usize,
             self.object_hash,
             &mut git_features::progress::Discard,
-            &interrupt_never,
+            &git_features::interrupt::IS_INTERRUPTED,
         )?;
         self.output.write_all(digest.as_slice())?;
         self.output.flush()?;
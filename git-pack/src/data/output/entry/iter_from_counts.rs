@@ -90,7 +90,7 @@ where
         progress.lock().show_throughput(start);
     }
     let counts_range_by_pack_id = match mode {
-        Mode::PackCopyAndBaseObjects => {
+        Mode::PackCopyAndBaseObjects | Mode::PackCopyAndDeltaCompressedObjects => {
             let mut progress = progress.add_child_with_id("sorting", *b"ECSE"); /* Entries from Counts Sorting Entries */
             progress.init(Some(counts.len()), git_features::progress::count("counts"));
             let start = std::time::Instant::now();
@@ -149,12 +149,14 @@ where
             let counts = Arc::clone(&counts);
             move |(chunk_id, chunk_range): (SequenceId, std::ops::Range<usize>), (buf, progress)| {
                 let mut out = Vec::new();
+                let chunk_start = chunk_range.start;
                 let chunk = &counts[chunk_range];
                 let mut stats = Outcome::default();
                 let mut pack_offsets_to_id = None;
+                let mut delta_bases = util::DeltaBases::new(matches!(mode, Mode::PackCopyAndDeltaCompressedObjects));
                 progress.init(Some(chunk.len()), git_features::progress::count("objects"));
 
-                for count in chunk.iter() {
+                for (count_idx, count) in chunk.iter().enumerate() {
                     out.push(match count
                         .entry_pack_location
                         .as_ref()
@@ -205,7 +207,7 @@ where
                                 None => match db.try_find(count.id, buf).map_err(Error::FindExisting)? {
                                     Some((obj, _location)) => {
                                         stats.decoded_and_recompressed_objects += 1;
-                                        output::Entry::from_data(count, &obj)
+                                        delta_bases.entry_from_data(count, chunk_start + count_idx, &obj, &mut stats)
                                     }
                                     None => {
                                         stats.missing_objects += 1;
@@ -217,7 +219,7 @@ where
                         None => match db.try_find(count.id, buf).map_err(Error::FindExisting)? {
                             Some((obj, _location)) => {
                                 stats.decoded_and_recompressed_objects += 1;
-                                output::Entry::from_data(count, &obj)
+                                delta_bases.entry_from_data(count, chunk_start + count_idx, &obj, &mut stats)
                             }
                             None => {
                                 stats.missing_objects += 1;
@@ -235,6 +237,69 @@ where
 }
 
 mod util {
+    use super::Outcome;
+    use crate::data::output;
+
+    /// The maximum amount of deltas to apply to obtain an object, limiting the cost of decoding it.
+    const MAX_DELTA_CHAIN_LENGTH: usize = 50;
+
+    struct Base {
+        object_index: usize,
+        kind: git_object::Kind,
+        data: Vec<u8>,
+        chain_length: usize,
+    }
+
+    /// Decoded objects of a chunk that may serve as delta base for the decoded objects following them.
+    pub struct DeltaBases {
+        enabled: bool,
+        bases: Vec<Base>,
+    }
+
+    impl DeltaBases {
+        pub fn new(enabled: bool) -> Self {
+            DeltaBases {
+                enabled,
+                bases: Vec::new(),
+            }
+        }
+
+        /// Create an entry for `obj` at `object_index`, stored as delta against the object of the same kind and the most similar size
+        /// if that is smaller, and remember it as possible base for the objects that follow.
+        pub fn entry_from_data(
+            &mut self,
+            count: &output::Count,
+            object_index: usize,
+            obj: &git_object::Data<'_>,
+            stats: &mut Outcome,
+        ) -> Result<output::Entry, output::entry::Error> {
+            if !self.enabled {
+                return output::Entry::from_data(count, obj);
+            }
+            let base = self
+                .bases
+                .iter()
+                .filter(|b| b.kind == obj.kind && b.chain_length < MAX_DELTA_CHAIN_LENGTH)
+                .min_by_key(|b| (b.data.len() as i64 - obj.data.len() as i64).abs());
+            let (entry, chain_length) = match base
+                .and_then(|b| output::Entry::from_data_as_delta(count, obj, b.object_index, &b.data).map(|e| (e, b)))
+            {
+                Some((entry, base)) => {
+                    stats.delta_compressed_objects += 1;
+                    (entry?, base.chain_length + 1)
+                }
+                None => (output::Entry::from_data(count, obj)?, 0),
+            };
+            self.bases.push(Base {
+                object_index,
+                kind: obj.kind,
+                data: obj.data.to_owned(),
+                chain_length,
+            });
+            Ok(entry)
+        }
+    }
+
     #[derive(Clone)]
     pub struct ChunkRanges {
         cursor: usize,
@@ -325,6 +390,9 @@ mod types {
         pub objects_copied_from_pack: usize,
         /// The amount of objects that ref to their base as ref-delta, an indication for a thin back being created.
         pub ref_delta_objects: usize,
+        /// The amount of decoded objects that were stored as delta against another decoded object of the pack,
+        /// which only happens in [`Mode::PackCopyAndDeltaCompressedObjects`].
+        pub delta_compressed_objects: usize,
     }
 
     impl Outcome {
@@ -335,12 +403,14 @@ mod types {
                 missing_objects,
                 objects_copied_from_pack,
                 ref_delta_objects,
+                delta_compressed_objects,
             }: Self,
         ) {
             self.decoded_and_recompressed_objects += decoded_objects;
             self.missing_objects += missing_objects;
             self.objects_copied_from_pack += objects_copied_from_pack;
             self.ref_delta_objects += ref_delta_objects;
+            self.delta_compressed_objects += delta_compressed_objects;
         }
     }
 
@@ -353,6 +423,11 @@ mod types {
         /// from existing pack compression and spending the smallest possible time on compressing unpacked objects at
         /// the cost of bandwidth.
         PackCopyAndBaseObjects,
+        /// Like [`PackCopyAndBaseObjects`][Mode::PackCopyAndBaseObjects], but objects that have to be decoded are stored as delta
+        /// against a previously decoded object of the same chunk if that is smaller, choosing the one of the same kind which is
+        /// closest in size. This takes more time and memory in exchange for smaller packs, with
+        /// [`chunk_size`][Options::chunk_size] being the amount of objects to consider as bases.
+        PackCopyAndDeltaCompressedObjects,
     }
 
     /// Configuration options for the pack generation functions provided in [this module][crate::data::output].
@@ -31,7 +31,7 @@ pub enum Kind {
     },
 }
 
-/// The error returned by [`output::Entry::from_data()`].
+/// The error returned by [`output::Entry::from_data()`] and its delta-producing variants.
 #[allow(missing_docs)]
 #[derive(Debug, thiserror::Error)]
 pub enum Error {
@@ -132,20 +132,73 @@ impl output::Entry {
             id: count.id.to_owned(),
             kind: Kind::Base(obj.kind),
             decompressed_size: obj.data.len(),
-            compressed_data: {
-                let mut out = git_features::zlib::stream::deflate::Write::new(Vec::new());
-                if let Err(err) = std::io::copy(&mut &*obj.data, &mut out) {
-                    match err.kind() {
-                        std::io::ErrorKind::Other => return Err(Error::ZlibDeflate(err)),
-                        err => unreachable!("Should never see other errors than zlib, but got {:?}", err,),
-                    }
-                };
-                out.flush()?;
-                out.into_inner()
-            },
+            compressed_data: Self::compress(obj.data)?,
         })
     }
 
+    /// Create a new instance from the given `oid` and its corresponding git `obj`ect data, stored as delta against `base`,
+    /// the data of the object at `base_index` in the list of entries to be written, which must precede this entry.
+    ///
+    /// Returns `None` if the delta isn't smaller than `obj` or can't be produced, in which case [`from_data()`][Self::from_data()]
+    /// should be used instead.
+    pub fn from_data_as_delta(
+        count: &output::Count,
+        obj: &git_object::Data<'_>,
+        base_index: usize,
+        base: &[u8],
+    ) -> Option<Result<Self, Error>> {
+        Self::from_data_as_delta_kind(
+            count,
+            obj,
+            Kind::DeltaRef {
+                object_index: base_index,
+            },
+            base,
+        )
+    }
+
+    /// Create a new instance from the given `oid` and its corresponding git `obj`ect data, stored as delta against `base`,
+    /// the data of the object identified by `base_id`.
+    ///
+    /// The entry will refer to its base by id, which is only valid for thin packs unless the base object is written first.
+    /// Returns `None` if the delta isn't smaller than `obj` or can't be produced, in which case [`from_data()`][Self::from_data()]
+    /// should be used instead.
+    pub fn from_data_as_ref_delta(
+        count: &output::Count,
+        obj: &git_object::Data<'_>,
+        base_id: ObjectId,
+        base: &[u8],
+    ) -> Option<Result<Self, Error>> {
+        Self::from_data_as_delta_kind(count, obj, Kind::DeltaOid { id: base_id }, base)
+    }
+
+    fn from_data_as_delta_kind(
+        count: &output::Count,
+        obj: &git_object::Data<'_>,
+        kind: Kind,
+        base: &[u8],
+    ) -> Option<Result<Self, Error>> {
+        let delta = data::delta::encode(base, obj.data).filter(|delta| delta.len() < obj.data.len())?;
+        Some(Self::compress(&delta).map(|compressed_data| output::Entry {
+            id: count.id.to_owned(),
+            kind,
+            decompressed_size: delta.len(),
+            compressed_data,
+        }))
+    }
+
+    fn compress(data: &[u8]) -> Result<Vec<u8>, Error> {
+        let mut out = git_features::zlib::stream::deflate::Write::new(Vec::new());
+        if let Err(err) = std::io::copy(&mut &*data, &mut out) {
+            match err.kind() {
+                std::io::ErrorKind::Other => return Err(Error::ZlibDeflate(err)),
+                err => unreachable!("Should never see other errors than zlib, but got {:?}", err,),
+            }
+        };
+        out.flush()?;
+        Ok(out.into_inner())
+    }
+
     /// Transform ourselves into pack entry header of `version` which can be written into a pack.
     ///
     /// `index_to_pack(object_index) -> pack_offset` is a function to convert the base object's index into
@@ -0,0 +1,16 @@
+#!/bin/bash
+set -eu -o pipefail
+
+git init -q
+
+git checkout -q -b main
+mkdir dir
+for round in $(seq 10); do
+  echo "$round" > "file$round"
+  echo "$round" > "dir/file$round"
+  git add .
+  git commit -q -m "c$round"
+done
+
+git repack -adbq
+git rev-list --objects main | cut -d' ' -f1 | sort > reachable-from-main.baseline
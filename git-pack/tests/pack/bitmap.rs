@@ -0,0 +1,74 @@
+use std::path::PathBuf;
+
+use git_hash::ObjectId;
+use git_pack::{bitmap, index};
+
+use crate::{fixture_path, pack::INDEX_V2};
+
+fn fixture() -> (PathBuf, index::File, PathBuf) {
+    let dir =
+        git_testtools::scripted_fixture_repo_read_only("make_pack_gen_repo_with_bitmap.sh").expect("fixture exists");
+    let index_path = std::fs::read_dir(dir.join(".git/objects/pack"))
+        .expect("pack dir exists")
+        .map(|entry| entry.expect("valid entry").path())
+        .find(|path| path.extension().map_or(false, |ext| ext == "idx"))
+        .expect("a pack was written");
+    let index = index::File::at(&index_path, git_hash::Kind::Sha1).expect("valid index");
+    (index_path.with_extension("bitmap"), index, dir)
+}
+
+#[test]
+fn reachable_objects_of_bitmapped_commits_match_git() -> crate::Result {
+    let (path, index, dir) = fixture();
+    let file = bitmap::File::at(&path, &index)?;
+    assert_eq!(file.version(), bitmap::Version::V1);
+    assert_eq!(file.path(), path);
+    assert_eq!(file.pack_checksum(), index.pack_checksum());
+    assert_eq!(file.num_objects(), index.num_objects());
+    assert!(file.num_bitmaps() > 0, "at least the tip of main has a bitmap");
+
+    let main = ObjectId::from_hex(
+        std::fs::read_to_string(dir.join(".git/refs/heads/main"))?
+            .trim()
+            .as_bytes(),
+    )?;
+    assert!(file.has_bitmap(main));
+    let words = file.reachable(main).expect("main has a bitmap");
+    let mut actual = Vec::new();
+    for (word_index, word) in words.iter().enumerate() {
+        for bit in 0..64 {
+            if word & (1 << bit) != 0 {
+                actual.push(file.id_at_pack_position((word_index * 64 + bit) as u32).to_owned());
+            }
+        }
+    }
+    actual.sort();
+    let expected = std::fs::read_to_string(dir.join("reachable-from-main.baseline"))?
+        .lines()
+        .map(|line| ObjectId::from_hex(line.as_bytes()))
+        .collect::<Result<Vec<_>, _>>()?;
+    assert_eq!(
+        actual, expected,
+        "bitmaps resolved through XOR chains yield the same as `git rev-list --objects`"
+    );
+
+    for entry_index in 0..index.num_objects() {
+        let id = index.oid_at_index(entry_index);
+        let position = file.pack_position(id).expect("all objects have a position");
+        assert_eq!(file.id_at_pack_position(position), id);
+    }
+    assert_eq!(file.pack_position(git_hash::Kind::Sha1.null()), None);
+    assert!(file.reachable(git_hash::Kind::Sha1.null()).is_none());
+    Ok(())
+}
+
+#[test]
+fn bitmaps_of_a_different_pack_are_rejected() -> crate::Result {
+    let (path, _index, _dir) = fixture();
+    let other_index = index::File::at(fixture_path(INDEX_V2), git_hash::Kind::Sha1)?;
+    assert!(matches!(
+        bitmap::File::at(&path, &other_index),
+        Err(bitmap::init::Error::PackChecksumMismatch { .. })
+    ));
+    Ok(())
+}
@@ -108,6 +108,59 @@ mod decode_entry {
     }
 }
 
+mod decode_header {
+    use git_pack::data::decode_header::{Outcome, ResolvedBase};
+
+    use crate::pack::{data::file::pack_at, SMALL_PACK};
+
+    #[test]
+    fn commit() {
+        assert_eq!(
+            decode_header_at_offset(1968),
+            Outcome {
+                kind: git_object::Kind::Commit,
+                object_size: 187,
+                num_deltas: 0
+            }
+        );
+    }
+
+    #[test]
+    fn blob_ofs_delta_two_links() {
+        assert_eq!(
+            decode_header_at_offset(3033),
+            Outcome {
+                kind: git_object::Kind::Blob,
+                object_size: 173,
+                num_deltas: 2
+            }
+        );
+    }
+
+    #[test]
+    fn blob_ofs_delta_single_link() {
+        assert_eq!(
+            decode_header_at_offset(3569),
+            Outcome {
+                kind: git_object::Kind::Blob,
+                object_size: 1163,
+                num_deltas: 1
+            }
+        );
+    }
+
+    fn decode_header_at_offset(offset: u64) -> Outcome {
+        fn resolve_with_panic(_oid: &git_hash::oid) -> Option<ResolvedBase> {
+            panic!("should not want to resolve an id here")
+        }
+
+        let p = pack_at(SMALL_PACK);
+        let entry = p.entry(offset);
+        p.decode_header(entry, &mut Default::default(), resolve_with_panic)
+            .expect("valid offset provides valid entry")
+    }
+}
+
 mod decompress_entry {
     use git_object::bstr::ByteSlice;
 
@@ -122,6 +122,7 @@ fn traversals() -> crate::Result {
                     missing_objects: 0,
                     objects_copied_from_pack: 16,
                     ref_delta_objects: 0,
+                    delta_compressed_objects: 0,
                 },
                 hex_to_id("b920bbb055e1efb9080592a409d3975738b6efb3"),
                 None,
@@ -155,6 +156,7 @@ fn traversals() -> crate::Result {
                     missing_objects: 0,
                     objects_copied_from_pack: 103,
                     ref_delta_objects: 74,
+                    delta_compressed_objects: 0,
                 },
                 hex_to_id("25114bd8820b393c402cd53ad8ec7f6a84bb0633"),
                 Some(hex_to_id("29ab9797aff1ca826afb699680356695d19c5acb")),
@@ -188,6 +190,7 @@ fn traversals() -> crate::Result {
                     missing_objects: 0,
                     objects_copied_from_pack: 29,
                     ref_delta_objects: 0,
+                    delta_compressed_objects: 0,
                 },
                 hex_to_id("d83d42128e40957c5174920189a0390b5a70f446"),
                 None,
@@ -209,6 +212,7 @@ fn traversals() -> crate::Result {
                     missing_objects: 0,
                     objects_copied_from_pack: 868,
                     ref_delta_objects: 0,
+                    delta_compressed_objects: 0,
                 },
                 hex_to_id("542ad1d1c7c762ea4e36907570ff9e4b5b7dde1b"),
                 None,
@@ -230,6 +234,7 @@ fn traversals() -> crate::Result {
                     missing_objects: 0,
                     objects_copied_from_pack: 868,
                     ref_delta_objects: 0,
+                    delta_compressed_objects: 0,
                 },
                 hex_to_id("542ad1d1c7c762ea4e36907570ff9e4b5b7dde1b"),
                 None,
@@ -332,6 +337,109 @@ fn empty_pack_is_allowed() {
     .unwrap();
 }
 
+#[test]
+fn decoded_objects_are_delta_compressed_against_similar_ones() -> crate::Result {
+    use git_odb::Write;
+
+    let tmp_dir = tempfile::TempDir::new()?;
+    let db = git_odb::at(tmp_dir.path())?.into_arc()?;
+    let base: Vec<u8> = (0..1000u32)
+        .flat_map(|n| format!("line {}\n", n).into_bytes())
+        .collect();
+    let mut blobs = vec![base.clone()];
+    for n in 0..4 {
+        let mut changed = base.clone();
+        changed.splice(n * 1000..n * 1000 + 10, format!("change {}", n).into_bytes());
+        blobs.push(changed);
+    }
+    blobs.push(b"small and unrelated".to_vec());
+    let ids = blobs
+        .iter()
+        .map(|data| db.write_buf(git_object::Kind::Blob, data))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let mut entries_iter = output::entry::iter_from_counts(
+        ids.iter().map(|id| output::Count::from_data(*id, None)).collect(),
+        db.clone(),
+        progress::Discard,
+        output::entry::iter_from_counts::Options {
+            mode: output::entry::iter_from_counts::Mode::PackCopyAndDeltaCompressedObjects,
+            ..Default::default()
+        },
+    );
+    let entries: Vec<_> = InOrderIter::from(entries_iter.by_ref())
+        .collect::<Result<Vec<_>, _>>()?
+        .into_iter()
+        .flatten()
+        .collect();
+    assert_eq!(
+        entries_iter.finalize()?,
+        output::entry::iter_from_counts::Outcome {
+            decoded_and_recompressed_objects: 6,
+            missing_objects: 0,
+            objects_copied_from_pack: 0,
+            ref_delta_objects: 0,
+            delta_compressed_objects: 4,
+        }
+    );
+    assert_eq!(entries[0].kind, entry::Kind::Base(git_object::Kind::Blob));
+    for (idx, entry) in entries.iter().enumerate().skip(1).take(4) {
+        assert!(
+            matches!(entry.kind, entry::Kind::DeltaRef { object_index } if object_index < idx),
+            "changed versions refer to a preceding version of the same content"
+        );
+        assert!(entry.decompressed_size < 100, "deltas are much smaller than the object");
+    }
+    assert_eq!(
+        entries[5].kind,
+        entry::Kind::Base(git_object::Kind::Blob),
+        "deltas that aren't smaller than the object aren't used"
+    );
+
+    let mut pack_data = Vec::new();
+    let mut pack_writer = output::bytes::FromEntriesIter::new(
+        std::iter::once(Ok::<_, entry::iter_from_counts::Error<git_odb::store::find::Error>>(
+            entries,
+        )),
+        &mut pack_data,
+        ids.len() as u32,
+        pack::data::Version::V2,
+        git_hash::Kind::Sha1,
+    );
+    for res in &mut pack_writer {
+        res?;
+    }
+    drop(pack_writer);
+    assert!(
+        pack_data.len() < base.len() / 2,
+        "only one of the large blobs is stored in full"
+    );
+
+    let bundle = pack::Bundle::write_to_directory(
+        std::io::BufReader::new(pack_data.as_slice()),
+        Some(tmp_dir.path()),
+        progress::Discard,
+        &AtomicBool::new(false),
+        None,
+        Default::default(),
+    )?
+    .to_bundle()
+    .expect("written to directory")?;
+    let mut buf = Vec::new();
+    for (id, data) in ids.iter().zip(&blobs) {
+        let (obj, _location) = bundle
+            .find(id, &mut buf, &mut pack::cache::Never)?
+            .expect("object is present");
+        assert_eq!(obj.kind, git_object::Kind::Blob);
+        assert_eq!(
+            obj.data,
+            data.as_slice(),
+            "all objects can be decoded from the new pack"
+        );
+    }
+    Ok(())
+}
+
 fn write_and_verify(
     db: git_odb::HandleArc,
     entries: Vec<output::Entry>,
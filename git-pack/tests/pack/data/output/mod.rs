@@ -20,6 +20,141 @@ fn size_of_count() {
     )
 }
 
+mod thin_pack {
+    use git_odb::Write;
+    use git_pack::data::{output, output::entry};
+
+    struct ThinPack {
+        pack: Vec<u8>,
+        base: Vec<u8>,
+        base_id: git_hash::ObjectId,
+        target: Vec<u8>,
+        target_id: git_hash::ObjectId,
+    }
+
+    /// A pack with a single blob stored as ref-delta against a blob that isn't part of the pack.
+    fn thin_pack() -> crate::Result<ThinPack> {
+        let base: Vec<u8> = (0..1000u32)
+            .flat_map(|n| format!("line {}\n", n).into_bytes())
+            .collect();
+        let mut target = base.clone();
+        target.splice(500..510, b"changed in the middle".iter().copied());
+        let sink = git_odb::sink(git_hash::Kind::Sha1);
+        let base_id = sink.write_buf(git_object::Kind::Blob, &base)?;
+        let target_id = sink.write_buf(git_object::Kind::Blob, &target)?;
+
+        let entry = output::Entry::from_data_as_ref_delta(
+            &output::Count::from_data(target_id, None),
+            &git_object::Data::new(git_object::Kind::Blob, &target),
+            base_id,
+            &base,
+        )
+        .expect("the delta is smaller")?;
+        assert_eq!(entry.kind, output::entry::Kind::DeltaOid { id: base_id });
+        assert!(
+            entry.decompressed_size < 100,
+            "the delta is much smaller than the object it produces"
+        );
+
+        let mut pack = Vec::new();
+        let mut pack_writer = output::bytes::FromEntriesIter::new(
+            std::iter::once(Ok::<_, entry::iter_from_counts::Error<git_odb::store::find::Error>>(
+                vec![entry],
+            )),
+            &mut pack,
+            1,
+            git_pack::data::Version::V2,
+            git_hash::Kind::Sha1,
+        );
+        for res in &mut pack_writer {
+            res?;
+        }
+        drop(pack_writer);
+        Ok(ThinPack {
+            pack,
+            base,
+            base_id,
+            target,
+            target_id,
+        })
+    }
+
+    fn write_to_directory(
+        pack: &[u8],
+        dir: &std::path::Path,
+        lookup: Option<Box<dyn for<'a> FnMut(git_hash::ObjectId, &'a mut Vec<u8>) -> Option<git_object::Data<'a>>>>,
+    ) -> Result<git_pack::bundle::write::Outcome, git_pack::bundle::write::Error> {
+        git_pack::Bundle::write_to_directory(
+            std::io::BufReader::new(pack),
+            Some(dir),
+            git_features::progress::Discard,
+            &std::sync::atomic::AtomicBool::default(),
+            lookup,
+            Default::default(),
+        )
+    }
+
+    #[test]
+    fn ref_delta_bases_are_resolved_and_added_to_the_pack() -> crate::Result {
+        let ThinPack {
+            pack,
+            base,
+            base_id,
+            target,
+            target_id,
+        } = thin_pack()?;
+        let dir = tempfile::TempDir::new()?;
+        let outcome = write_to_directory(
+            &pack,
+            dir.path(),
+            Some(Box::new(move |id, buf| {
+                (id == base_id).then(|| {
+                    buf.clear();
+                    buf.extend_from_slice(&base);
+                    git_object::Data::new(git_object::Kind::Blob, buf)
+                })
+            })),
+        )?;
+        assert_eq!(outcome.index.num_objects, 2, "the base object was added to the pack");
+
+        let bundle = outcome.to_bundle().expect("written to directory")?;
+        let mut buf = Vec::new();
+        let (obj, _location) = bundle
+            .find(target_id, &mut buf, &mut git_pack::cache::Never)?
+            .expect("object is present");
+        assert_eq!(obj.kind, git_object::Kind::Blob);
+        assert_eq!(obj.data, target.as_slice());
+        Ok(())
+    }
+
+    #[test]
+    fn missing_ref_delta_bases_are_an_error() -> crate::Result {
+        let ThinPack { pack, base_id, .. } = thin_pack()?;
+        let dir = tempfile::TempDir::new()?;
+        let err = write_to_directory(&pack, dir.path(), Some(Box::new(|_id, _buf| None)))
+            .expect_err("the base can't be found");
+        assert!(matches!(
+            err,
+            git_pack::bundle::write::Error::IndexWrite(git_pack::index::write::Error::PackEntryDecode(
+                git_pack::data::input::Error::NotFound { object_id }
+            )) if object_id == base_id
+        ));
+        Ok(())
+    }
+
+    #[test]
+    fn thin_packs_can_not_be_indexed_without_lookup() -> crate::Result {
+        let ThinPack { pack, .. } = thin_pack()?;
+        let dir = tempfile::TempDir::new()?;
+        let err = write_to_directory(&pack, dir.path(), None).expect_err("ref deltas can't be resolved");
+        assert!(matches!(
+            err,
+            git_pack::bundle::write::Error::IndexWrite(git_pack::index::write::Error::IteratorInvariantNoRefDelta)
+        ));
+        Ok(())
+    }
+}
+
 enum DbKind {
     DeterministicGeneratedContent,
     DeterministicGeneratedContentMultiIndex,
@@ -9,6 +9,12 @@ use bstr::BString;
 ///
 pub mod parse;
 
+///
+pub mod search;
+
+///
+pub mod normalize;
+
 /// The output of a pathspec [parsing][parse()] operation. It can be used to match against a one or more paths.
 #[derive(PartialEq, Eq, Debug, Hash, Ord, PartialOrd, Clone)]
 pub struct Pattern {
@@ -24,6 +30,9 @@ pub struct Pattern {
     ///
     /// `:(attr:a=one b=):path` would yield attribute `a` and `b`.
     pub attributes: Vec<git_attributes::Assignment>,
+    /// The length of the leading portion of `path` that was added as prefix by [`normalize()`][Pattern::normalize()],
+    /// which is matched literally and case-sensitively.
+    pub prefix_len: usize,
 }
 
 bitflags! {
@@ -58,6 +67,18 @@ impl Default for MatchMode {
     }
 }
 
+/// A list of [`Pattern`]s prepared to efficiently match many paths against, as obtained by [`Search::from_specs()`].
+///
+/// Paths match if they match at least one of the patterns, but none of the patterns with the
+/// [`EXCLUDE`][MagicSignature::EXCLUDE] signature.
+#[derive(PartialEq, Eq, Debug, Hash, Ord, PartialOrd, Clone)]
+pub struct Search {
+    /// The patterns along with information to accelerate matching, in the order they were provided.
+    patterns: Vec<search::Mapping>,
+    /// The length of the leading directory that all patterns have in common, including the trailing slash.
+    common_prefix_len: usize,
+}
+
 /// Parse a git-style pathspec into a [`Pattern`][Pattern].
 pub fn parse(input: &[u8]) -> Result<Pattern, parse::Error> {
     Pattern::from_bytes(input)
@@ -0,0 +1,90 @@
+use std::{
+    borrow::Cow,
+    path::{Path, PathBuf},
+};
+
+use bstr::{BStr, BString, ByteSlice, ByteVec};
+
+use crate::{MagicSignature, Pattern};
+
+/// The error returned by [`Pattern::normalize()`].
+#[derive(Debug, thiserror::Error)]
+#[allow(missing_docs)]
+pub enum Error {
+    #[error("The absolute path '{}' is not located in the worktree at '{}'", .path.display(), .worktree_path.display())]
+    AbsolutePathOutsideOfWorktree { path: PathBuf, worktree_path: PathBuf },
+    #[error("The path '{path}' leaves the worktree")]
+    OutsideOfWorktree { path: BString },
+}
+
+/// Mutation
+impl Pattern {
+    /// Make the path of this pattern relative to `root`, the root of the worktree, as if it was specified in `prefix`, the current
+    /// working directory relative to `root`, just like git does when it's invoked in a sub-directory of the worktree.
+    ///
+    /// `prefix` is ignored if the pattern has the [`TOP`][MagicSignature::TOP] signature or if its path is absolute,
+    /// in which case it must point into `root`. `.` and `..` components are resolved, but the path may not leave the worktree.
+    /// A trailing slash is retained to indicate that the pattern only matches paths within the directory it names.
+    ///
+    /// The portion of the path made up by `prefix` is remembered in [`prefix_len`][Pattern::prefix_len] so it's always matched
+    /// literally and case-sensitively.
+    pub fn normalize(&mut self, prefix: &Path, root: &Path) -> Result<&mut Self, Error> {
+        let relative_path;
+        let (prefix, path): (Cow<'_, BStr>, &BStr) = if git_path::from_bstr(self.path.as_bstr()).is_absolute() {
+            let path = git_path::from_bstr(self.path.as_bstr());
+            relative_path =
+                git_path::into_bstr(
+                    path.strip_prefix(root)
+                        .map_err(|_| Error::AbsolutePathOutsideOfWorktree {
+                            path: path.to_path_buf(),
+                            worktree_path: root.to_owned(),
+                        })?,
+                )
+                .into_owned();
+            (Cow::Borrowed("".into()), relative_path.as_bstr())
+        } else if self.signature.contains(MagicSignature::TOP) {
+            (Cow::Borrowed("".into()), self.path.as_bstr())
+        } else {
+            (
+                git_path::to_unix_separators_on_windows(git_path::into_bstr(prefix)),
+                self.path.as_bstr(),
+            )
+        };
+
+        let mut components = Vec::new();
+        for (component, from_prefix) in prefix
+            .split_str("/")
+            .map(|c| (c, true))
+            .chain(path.split_str("/").map(|c| (c, false)))
+        {
+            match component {
+                b"" | b"." => {}
+                b".." => {
+                    if components.pop().is_none() {
+                        return Err(Error::OutsideOfWorktree {
+                            path: self.path.clone(),
+                        });
+                    }
+                }
+                _ => components.push((component, from_prefix)),
+            }
+        }
+
+        let mut normalized = bstr::join("/", components.iter().map(|(component, _)| component));
+        // Look at the original path as stripping the worktree from absolute paths also strips trailing slashes.
+        let original = self.path.as_bstr();
+        if (original.ends_with(b"/") || original.rsplit_str("/").next() == Some(b".")) && !normalized.is_empty() {
+            normalized.push_byte(b'/');
+        }
+        // Each component of the prefix is followed by a slash, unless it's the last.
+        let prefix_len = components
+            .iter()
+            .take_while(|(_, from_prefix)| *from_prefix)
+            .map(|(component, _)| component.len() + 1)
+            .sum::<usize>()
+            .min(normalized.len());
+        self.path = normalized.into();
+        self.prefix_len = prefix_len;
+        Ok(self)
+    }
+}
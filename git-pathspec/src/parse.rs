@@ -42,6 +42,7 @@ impl Pattern {
             signature: MagicSignature::empty(),
             search_mode: MatchMode::ShellGlob,
             attributes: Vec::new(),
+            prefix_len: 0,
         };
 
         let mut cursor = 0;
@@ -0,0 +1,283 @@
+use std::path::Path;
+
+use bstr::{BStr, ByteSlice};
+
+use crate::{normalize, MagicSignature, MatchMode, Pattern, Search};
+
+/// Describes how a path was matched by a pattern.
+#[derive(PartialEq, Eq, Debug, Hash, Ord, PartialOrd, Clone, Copy)]
+pub enum MatchKind {
+    /// The path and the pattern are the same.
+    Exact,
+    /// The pattern names a directory which contains the path, or it is empty and matches everything.
+    Prefix,
+    /// The path was matched by the wildcards in the pattern.
+    WildcardMatch,
+}
+
+/// A pattern that matched a path within a [`Search`].
+#[derive(PartialEq, Eq, Debug, Hash, Ord, PartialOrd, Clone, Copy)]
+pub struct Match<'a> {
+    /// The pattern that matched.
+    pub pattern: &'a Pattern,
+    /// The position of the pattern in the list of patterns the search was created from.
+    pub sequence_number: usize,
+    /// How the pattern matched.
+    pub kind: MatchKind,
+}
+
+/// A pattern along with information to accelerate matching.
+#[derive(PartialEq, Eq, Debug, Hash, Ord, PartialOrd, Clone)]
+pub(crate) struct Mapping {
+    pub pattern: Pattern,
+    pub sequence_number: usize,
+    /// The length of the leading portion of the path of `pattern` which doesn't contain wildcards.
+    pub nowildcard_len: usize,
+}
+
+/// Init
+impl Search {
+    /// Prepare all `patterns` for matching.
+    ///
+    /// If all of them are [excludes][MagicSignature::EXCLUDE], an empty pattern is added which includes everything,
+    /// just like git does.
+    pub fn from_specs(patterns: impl IntoIterator<Item = Pattern>) -> Self {
+        Self::from_patterns(patterns.into_iter().collect(), implicit_pattern())
+    }
+
+    /// Like [`from_specs()`][Self::from_specs()], but [normalizes][Pattern::normalize()] all `patterns` first to be relative
+    /// to `root` as if they were specified in `prefix`, the current working directory relative to `root`.
+    ///
+    /// If all patterns are excludes, the added pattern includes all paths in `prefix`, just like `git ls-files` does it.
+    pub fn from_specs_with_prefix(
+        patterns: impl IntoIterator<Item = Pattern>,
+        prefix: &Path,
+        root: &Path,
+    ) -> Result<Self, normalize::Error> {
+        let patterns = patterns
+            .into_iter()
+            .map(|mut pattern| {
+                pattern.normalize(prefix, root)?;
+                Ok(pattern)
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        let mut implicit = implicit_pattern();
+        implicit.normalize(prefix, root)?;
+        Ok(Self::from_patterns(patterns, implicit))
+    }
+
+    fn from_patterns(patterns: Vec<Pattern>, implicit: Pattern) -> Self {
+        let mut patterns: Vec<_> = patterns
+            .into_iter()
+            .enumerate()
+            .map(|(sequence_number, pattern)| Mapping::new(pattern, sequence_number))
+            .collect();
+        if patterns.iter().all(|m| m.pattern.is_excluded()) {
+            let sequence_number = patterns.len();
+            patterns.push(Mapping::new(implicit, sequence_number));
+        }
+        let common_prefix_len = common_prefix_len(&patterns);
+        Search {
+            patterns,
+            common_prefix_len,
+        }
+    }
+}
+
+fn implicit_pattern() -> Pattern {
+    Pattern {
+        path: Default::default(),
+        signature: MagicSignature::empty(),
+        search_mode: MatchMode::default(),
+        attributes: Vec::new(),
+        prefix_len: 0,
+    }
+}
+
+/// Compute the length of the leading directory all non-exclude patterns have in common, similar to git.
+fn common_prefix_len(patterns: &[Mapping]) -> usize {
+    let mut first_path = None;
+    let mut max = None;
+    for mapping in patterns.iter().filter(|m| !m.pattern.is_excluded()) {
+        let path = mapping.pattern.path.as_bstr();
+        let first_path = *first_path.get_or_insert(path);
+        // case-insensitive portions of patterns could match any directory.
+        let literal_len = if mapping.pattern.signature.contains(MagicSignature::ICASE) {
+            mapping.pattern.prefix_len
+        } else {
+            mapping.nowildcard_len
+        };
+        let len = path[..literal_len]
+            .iter()
+            .zip(first_path.iter())
+            .take_while(|(a, b)| a == b)
+            .enumerate()
+            .filter(|(_, (b, _))| **b == b'/')
+            .last()
+            .map_or(0, |(idx, _)| idx + 1);
+        let max = max.get_or_insert(len);
+        *max = len.min(*max);
+        if *max == 0 {
+            break;
+        }
+    }
+    max.unwrap_or(0)
+}
+
+/// Access
+impl Search {
+    /// Return an iterator over all patterns in the order they were provided, including the implicit one that may have been
+    /// added if all of them are excludes.
+    pub fn patterns(&self) -> impl Iterator<Item = &Pattern> + '_ {
+        self.patterns.iter().map(|m| &m.pattern)
+    }
+
+    /// Return the leading directory all patterns have in common, ending with a slash unless it's empty.
+    ///
+    /// All matching paths are located in this directory, and directory walks can be bounded to it. Note that
+    /// patterns with the [`EXCLUDE`][MagicSignature::EXCLUDE] signature are not considered.
+    pub fn common_prefix(&self) -> &BStr {
+        self.patterns
+            .iter()
+            .find(|m| !m.pattern.is_excluded())
+            .map_or("".into(), |m| m.pattern.path[..self.common_prefix_len].as_bstr())
+    }
+
+    /// Return the first pattern that matches `relative_path`, a path relative to the repository root with slashes as separators,
+    /// or `None` if no pattern matches it or if it's excluded by a pattern with the [`EXCLUDE`][MagicSignature::EXCLUDE]
+    /// signature.
+    ///
+    /// `attributes(relative_path, out)` is called to collect all attributes of `relative_path` into `out` if a pattern that
+    /// requires attributes needs them, which happens at most once per call. Attributes that aren't contained in `out`
+    /// are considered [unspecified][git_attributes::State::Unspecified].
+    pub fn pattern_matching_relative_path<'a>(
+        &self,
+        relative_path: impl Into<&'a BStr>,
+        mut attributes: impl FnMut(&BStr, &mut Vec<git_attributes::Assignment>),
+    ) -> Option<Match<'_>> {
+        let relative_path = relative_path.into();
+        let mut assignments = None;
+        let mut matches = |mapping: &Mapping| -> Option<MatchKind> {
+            let kind = mapping.matches_path(relative_path)?;
+            if !mapping.pattern.attributes.is_empty() {
+                let assignments = assignments.get_or_insert_with(|| {
+                    let mut out = Vec::new();
+                    attributes(relative_path, &mut out);
+                    out
+                });
+                if !mapping.pattern.matches_attributes(assignments) {
+                    return None;
+                }
+            }
+            Some(kind)
+        };
+
+        let first_match = self
+            .patterns
+            .iter()
+            .filter(|mapping| !mapping.pattern.is_excluded())
+            .find_map(|mapping| {
+                matches(mapping).map(|kind| Match {
+                    pattern: &mapping.pattern,
+                    sequence_number: mapping.sequence_number,
+                    kind,
+                })
+            })?;
+        let is_excluded = self
+            .patterns
+            .iter()
+            .filter(|mapping| mapping.pattern.is_excluded())
+            .any(|mapping| matches(mapping).is_some());
+        (!is_excluded).then(|| first_match)
+    }
+
+    /// Return `true` if `relative_path` is matched by our patterns and not excluded.
+    /// See [`pattern_matching_relative_path()`][Self::pattern_matching_relative_path()] for details.
+    pub fn is_included<'a>(
+        &self,
+        relative_path: impl Into<&'a BStr>,
+        attributes: impl FnMut(&BStr, &mut Vec<git_attributes::Assignment>),
+    ) -> bool {
+        self.pattern_matching_relative_path(relative_path, attributes).is_some()
+    }
+}
+
+/// Access
+impl Pattern {
+    /// Return `true` if this pattern excludes the paths it matches.
+    pub fn is_excluded(&self) -> bool {
+        self.signature.contains(MagicSignature::EXCLUDE)
+    }
+
+    /// Return `true` if the attributes required by this pattern are satisfied by `assignments`, all attributes of a path,
+    /// with attributes that aren't contained being considered unspecified.
+    pub fn matches_attributes(&self, assignments: &[git_attributes::Assignment]) -> bool {
+        use git_attributes::State;
+        self.attributes.iter().all(|required| {
+            let actual = assignments
+                .iter()
+                .find(|assignment| assignment.name == required.name)
+                .map_or(&State::Unspecified, |assignment| &assignment.state);
+            *actual == required.state
+        })
+    }
+}
+
+impl Mapping {
+    fn new(pattern: Pattern, sequence_number: usize) -> Self {
+        let nowildcard_len = match pattern.search_mode {
+            MatchMode::Literal => pattern.path.len(),
+            MatchMode::ShellGlob | MatchMode::PathAwareGlob => pattern.path[pattern.prefix_len..]
+                .find_byteset(GLOB_CHARACTERS)
+                .map_or(pattern.path.len(), |pos| pattern.prefix_len + pos),
+        };
+        Mapping {
+            pattern,
+            sequence_number,
+            nowildcard_len,
+        }
+    }
+
+    /// Match `path` against the path of our pattern similar to git, without considering attributes.
+    fn matches_path(&self, path: &BStr) -> Option<MatchKind> {
+        let pattern_path = self.pattern.path.as_bstr();
+        if pattern_path.is_empty() {
+            return Some(MatchKind::Prefix);
+        }
+        let icase = self.pattern.signature.contains(MagicSignature::ICASE);
+        let eq = |a: &[u8], b: &[u8]| if icase { a.eq_ignore_ascii_case(b) } else { a == b };
+        // The prefix is always matched literally and case-sensitively.
+        let prefix_len = self.pattern.prefix_len;
+        if path.get(..prefix_len) != Some(&pattern_path[..prefix_len]) {
+            return None;
+        }
+
+        if pattern_path.len() <= path.len() && eq(&path[..pattern_path.len()], pattern_path) {
+            if pattern_path.len() == path.len() {
+                return Some(MatchKind::Exact);
+            }
+            if pattern_path.ends_with(b"/") || path[pattern_path.len()] == b'/' {
+                return Some(MatchKind::Prefix);
+            }
+        }
+        if self.nowildcard_len < pattern_path.len()
+            && self.nowildcard_len <= path.len()
+            && eq(&path[..self.nowildcard_len], &pattern_path[..self.nowildcard_len])
+        {
+            let mut mode = match self.pattern.search_mode {
+                MatchMode::PathAwareGlob => git_glob::wildmatch::Mode::NO_MATCH_SLASH_LITERAL,
+                MatchMode::ShellGlob | MatchMode::Literal => git_glob::wildmatch::Mode::empty(),
+            };
+            if icase {
+                mode |= git_glob::wildmatch::Mode::IGNORE_CASE;
+            }
+            if git_glob::wildmatch(pattern_path[prefix_len..].as_bstr(), path[prefix_len..].as_bstr(), mode) {
+                return Some(MatchKind::WildcardMatch);
+            }
+        }
+        None
+    }
+}
+
+/// The characters with a special meaning in glob patterns, like git uses them.
+const GLOB_CHARACTERS: &[u8] = br"*?[\";
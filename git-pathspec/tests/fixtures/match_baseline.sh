@@ -0,0 +1,74 @@
+#!/bin/bash
+set -eu -o pipefail
+
+git init -q
+
+mkdir -p dir/sub Dir2
+touch a b.txt dir/a dir/b.txt dir/sub/c.txt Dir2/x.TXT '*star' 'more*star' 'lit[1]' lit1
+cat <<EOF >.gitattributes
+*.txt   text
+b.txt   -text
+dir/**  custom=value
+EOF
+git add .
+
+function baseline() {
+  {
+    (IFS=$'\t'; echo "$*")
+    git ls-files -- "$@"
+    echo
+  } >> baseline.git
+}
+
+baseline dir
+baseline dir/
+baseline '*.txt'
+baseline ':(glob)*.txt'
+baseline ':(glob)**/*.txt'
+baseline ':(glob)dir/*'
+baseline '*star'
+baseline ':(literal)*star'
+baseline 'lit[1]'
+baseline ':(literal)lit[1]'
+baseline ':(icase)DIR'
+baseline ':(icase)*.txt'
+baseline ':(icase,literal)LIT[1]'
+baseline ':(exclude)dir'
+baseline ':!*.txt' ':^a'
+baseline dir ':^dir/sub'
+baseline ':' ':!*.txt'
+baseline ':(attr:text)'
+baseline ':(attr:-text)'
+baseline ':(attr:!text)'
+baseline ':(attr:custom=value)dir'
+baseline ':(attr:custom=other)'
+baseline ':(attr:text custom=value)'
+baseline ':(attr:text)dir' ':(exclude,attr:text)dir/sub'
+baseline ':(top)dir'
+baseline 'd*/a'
+baseline 'dir/sub' 'a'
+baseline 'nonexistent'
+
+function baseline_in_dir() {
+  {
+    echo "$*"
+    (cd dir && git ls-files --full-name -- "$@")
+    echo
+  } >> baseline-in-dir.git
+}
+
+baseline_in_dir a
+baseline_in_dir '*.txt'
+baseline_in_dir ':(top)a'
+baseline_in_dir ':/a'
+baseline_in_dir ../a
+baseline_in_dir '../*.txt'
+baseline_in_dir ':(glob)../*.txt'
+baseline_in_dir .
+baseline_in_dir ./sub/
+baseline_in_dir sub/.
+baseline_in_dir ../dir/sub/../a
+baseline_in_dir ':(icase)SUB'
+baseline_in_dir ':(icase)../DIR/A'
+baseline_in_dir ':!a'
+baseline_in_dir ':'
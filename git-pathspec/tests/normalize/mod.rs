@@ -0,0 +1,60 @@
+use std::path::Path;
+
+use git_pathspec::normalize::Error;
+
+fn normalized(spec: &str, prefix: &str) -> Result<(String, usize), Error> {
+    let mut pattern = git_pathspec::parse(spec.as_bytes()).expect("valid pathspec");
+    pattern.normalize(Path::new(prefix), Path::new("/repo"))?;
+    Ok((pattern.path.to_string(), pattern.prefix_len))
+}
+
+#[test]
+fn prefix_is_prepended_and_components_are_resolved() {
+    for (spec, prefix, expected, prefix_len) in [
+        ("a", "", "a", 0),
+        ("a", "dir", "dir/a", 4),
+        ("a/", "dir/sub", "dir/sub/a/", 8),
+        (".", "", "", 0),
+        (".", "dir", "dir/", 4),
+        (":", "dir", "dir", 3),
+        ("./a/./b", "dir", "dir/a/b", 4),
+        ("a//b", "", "a/b", 0),
+        ("sub/.", "dir", "dir/sub/", 4),
+        ("../a", "dir/sub", "dir/a", 4),
+        ("../../a", "dir/sub", "a", 0),
+        ("a/../b", "dir", "dir/b", 4),
+        (":(top)a", "dir", "a", 0),
+        (":/a/../b", "dir", "b", 0),
+        ("/repo/a", "dir", "a", 0),
+        ("/repo/dir/", "dir", "dir/", 0),
+    ] {
+        assert_eq!(
+            normalized(spec, prefix).unwrap(),
+            (expected.into(), prefix_len),
+            "{:?} in {:?}",
+            spec,
+            prefix
+        );
+    }
+}
+
+#[test]
+fn paths_may_not_leave_the_worktree() {
+    assert!(matches!(
+        normalized("../a", "").unwrap_err(),
+        Error::OutsideOfWorktree { path } if path == "../a"
+    ));
+    assert!(matches!(
+        normalized("../../../a", "dir/sub").unwrap_err(),
+        Error::OutsideOfWorktree { .. }
+    ));
+    assert!(matches!(
+        normalized(":(top)../a", "dir").unwrap_err(),
+        Error::OutsideOfWorktree { .. }
+    ));
+    assert!(matches!(
+        normalized("/other/a", "dir").unwrap_err(),
+        Error::AbsolutePathOutsideOfWorktree { path, worktree_path }
+            if path == Path::new("/other/a") && worktree_path == Path::new("/repo")
+    ));
+}
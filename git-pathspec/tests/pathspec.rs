@@ -1,5 +1,8 @@
 pub use git_testtools::Result;
 
+mod normalize;
+mod search;
+
 mod parse {
     use std::collections::HashMap;
 
@@ -0,0 +1,196 @@
+use std::path::Path;
+
+use bstr::{BStr, BString, ByteSlice};
+use git_attributes::{PatternList, Stack};
+use git_pathspec::{search::MatchKind, Search};
+
+#[test]
+fn baseline() -> crate::Result {
+    let dir = git_testtools::scripted_fixture_repo_read_only("match_baseline.sh")?;
+    let all_paths = all_paths(&dir)?;
+    let baseline = std::fs::read(dir.join("baseline.git"))?;
+    let mut attributes = Stack::default();
+
+    let mut count = 0;
+    for (specs, expected) in (Expectations {
+        lines: baseline.lines(),
+    }) {
+        let search = Search::from_specs(
+            specs
+                .split_str("\t")
+                .map(|spec| git_pathspec::parse(spec).expect("valid pathspec")),
+        );
+        let actual: Vec<_> = all_paths
+            .iter()
+            .filter(|path| {
+                search.is_included(path.as_bstr(), |path, out| {
+                    attributes_of(&mut attributes, &dir, path, out).expect("attributes can be read")
+                })
+            })
+            .cloned()
+            .collect();
+        assert_eq!(actual, expected, "{:?} should match the same paths as git", specs);
+        count += 1;
+    }
+    assert_eq!(count, 28, "all specs were checked");
+    Ok(())
+}
+
+#[test]
+fn baseline_in_sub_directory() -> crate::Result {
+    let dir = git_testtools::scripted_fixture_repo_read_only("match_baseline.sh")?;
+    let all_paths = all_paths(&dir)?;
+    let baseline = std::fs::read(dir.join("baseline-in-dir.git"))?;
+    let mut attributes = Stack::default();
+
+    let mut count = 0;
+    for (specs, expected) in (Expectations {
+        lines: baseline.lines(),
+    }) {
+        let search = Search::from_specs_with_prefix(
+            specs
+                .split_str("\t")
+                .map(|spec| git_pathspec::parse(spec).expect("valid pathspec")),
+            Path::new("dir"),
+            &dir,
+        )?;
+        let actual: Vec<_> = all_paths
+            .iter()
+            .filter(|path| {
+                search.is_included(path.as_bstr(), |path, out| {
+                    attributes_of(&mut attributes, &dir, path, out).expect("attributes can be read")
+                })
+            })
+            .cloned()
+            .collect();
+        assert_eq!(actual, expected, "{:?} should match the same paths as git", specs);
+        count += 1;
+    }
+    assert_eq!(count, 15, "all specs were checked");
+    Ok(())
+}
+
+#[test]
+fn common_prefix() {
+    for (specs, expected) in [
+        (&["dir/sub/a", "dir/sub/b"][..], "dir/sub/"),
+        (&["dir/sub/"], "dir/sub/"),
+        (&["dir/sub"], "dir/"),
+        (&["dir/a", "dir/sub/b", ":!dir/sub"], "dir/"),
+        (&["dir/*/a"], "dir/"),
+        (&[":(literal)dir/*/a"], "dir/*/"),
+        (&[":(icase)dir/sub/a"], ""),
+        (&["dir/a", "other/a"], ""),
+        (&[":!dir/a"], ""),
+        (&[], ""),
+    ] {
+        let search = Search::from_specs(specs.iter().map(|spec| git_pathspec::parse(spec.as_bytes()).unwrap()));
+        assert_eq!(search.common_prefix(), expected, "{:?}", specs);
+    }
+
+    let search = Search::from_specs_with_prefix(
+        [":(icase)sub/a", ":!other"]
+            .iter()
+            .map(|spec| git_pathspec::parse(spec.as_bytes()).unwrap()),
+        Path::new("dir"),
+        Path::new("/root"),
+    )
+    .unwrap();
+    assert_eq!(
+        search.common_prefix(),
+        "dir/",
+        "the prefix is case-sensitive even with icase"
+    );
+}
+
+#[test]
+fn match_kinds_and_sequence_numbers() {
+    let search = Search::from_specs(
+        ["dir/a", "dir", "*.txt"]
+            .iter()
+            .map(|spec| git_pathspec::parse(spec.as_bytes()).unwrap()),
+    );
+    let no_attributes = |_: &BStr, _: &mut Vec<git_attributes::Assignment>| unreachable!("no attributes are required");
+
+    let m = search
+        .pattern_matching_relative_path("dir/a", no_attributes)
+        .expect("match");
+    assert_eq!(
+        (m.kind, m.sequence_number),
+        (MatchKind::Exact, 0),
+        "the first matching pattern wins"
+    );
+    let m = search
+        .pattern_matching_relative_path("dir/b", no_attributes)
+        .expect("match");
+    assert_eq!((m.kind, m.sequence_number), (MatchKind::Prefix, 1));
+    assert_eq!(m.pattern.path, "dir");
+    let m = search
+        .pattern_matching_relative_path("b.txt", no_attributes)
+        .expect("match");
+    assert_eq!((m.kind, m.sequence_number), (MatchKind::WildcardMatch, 2));
+    assert!(search.pattern_matching_relative_path("dirty", no_attributes).is_none());
+}
+
+#[test]
+fn only_excludes_imply_a_pattern_matching_everything() {
+    let search = Search::from_specs(Some(git_pathspec::parse(b":!a").unwrap()));
+    assert_eq!(search.patterns().count(), 2, "one pattern was added");
+    let no_attributes = |_: &BStr, _: &mut Vec<git_attributes::Assignment>| {};
+    assert!(!search.is_included("a", no_attributes));
+    let m = search
+        .pattern_matching_relative_path("b", no_attributes)
+        .expect("match");
+    assert_eq!((m.kind, m.sequence_number), (MatchKind::Prefix, 1));
+    assert!(m.pattern.path.is_empty());
+
+    let search = Search::from_specs(None);
+    assert!(
+        search.is_included("anything", no_attributes),
+        "no pattern matches everything"
+    );
+}
+
+fn all_paths(dir: &Path) -> std::io::Result<Vec<BString>> {
+    let out = std::process::Command::new("git")
+        .args(["ls-files"])
+        .current_dir(dir)
+        .output()?;
+    assert!(out.status.success());
+    Ok(out.stdout.lines().map(|line| line.as_bstr().to_owned()).collect())
+}
+
+fn attributes_of(
+    stack: &mut Stack,
+    root: &Path,
+    path: &BStr,
+    out: &mut Vec<git_attributes::Assignment>,
+) -> std::io::Result<()> {
+    let relative_dir = path.rfind_byte(b'/').map_or("".into(), |pos| path[..pos].as_bstr());
+    let mut buf = Vec::new();
+    let cursor = stack.at_path(relative_dir, |dir| {
+        let dir = root.join(dir.to_str().expect("valid UTF-8"));
+        PatternList::from_file(dir.join(".gitattributes"), Some(root), false, &mut buf)
+    })?;
+    cursor.matching_attributes(path, Some(false), git_glob::pattern::Case::Sensitive, out);
+    Ok(())
+}
+
+struct Expectations<'a> {
+    lines: bstr::Lines<'a>,
+}
+
+impl<'a> Iterator for Expectations<'a> {
+    type Item = (&'a BStr, Vec<BString>);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let specs = self.lines.next()?.as_bstr();
+        let paths = self
+            .lines
+            .by_ref()
+            .take_while(|line| !line.is_empty())
+            .map(|line| line.as_bstr().to_owned())
+            .collect();
+        Some((specs, paths))
+    }
+}
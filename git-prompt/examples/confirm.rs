@@ -0,0 +1,7 @@
+fn main() -> Result<(), git_prompt::Error> {
+    for _ in 0..2 {
+        let confirmed = git_prompt::confirm("Continue (yes/no)? ", &Default::default())?;
+        eprintln!("{confirmed:?}");
+    }
+    Ok(())
+}
@@ -2,10 +2,12 @@
 //!
 //! ### Compatibility
 //!
-//! This is a unix-only crate which will return with an error when trying to obtain any prompt on other platforms.
-//! On those platforms it is common to have helpers which perform this task so it shouldn't be a problem.
+//! Prompts work on unix using the controlling terminal, and on windows using the console.
+//! On other platforms an error will be returned when trying to obtain any prompt, but it's common to have helpers
+//! which perform this task so it shouldn't be a problem.
 #![deny(rust_2018_idioms, missing_docs)]
-#![forbid(unsafe_code)]
+// `unsafe_code` not forbidden because we need to interact with the windows console
+#![deny(unsafe_code)]
 
 mod types;
 pub use types::{Error, Mode, Options};
@@ -15,7 +17,12 @@ pub mod unix;
 #[cfg(unix)]
 use unix::imp;
 
-#[cfg(not(unix))]
+///
+pub mod windows;
+#[cfg(windows)]
+use windows::imp;
+
+#[cfg(not(any(unix, windows)))]
 mod imp {
     use crate::{Error, Options};
 
@@ -74,3 +81,22 @@ pub fn securely(prompt: impl AsRef<str>) -> Result<String, Error> {
         },
     )
 }
+
+/// Ask the user to confirm a question shown as `prompt`, like `"Continue connecting (yes/no)? "`, returning `true` if they
+/// answered with `y` or `yes` in any case, and `false` otherwise.
+///
+/// This is useful for transports to have the user confirm unknown host keys, and uses the `askpass` program of `opts` if set.
+/// Input is always visible unless prompts are [disabled][Mode::Disable], which is an error.
+pub fn confirm(prompt: &str, opts: &Options<'_>) -> Result<bool, Error> {
+    if opts.mode == Mode::Disable {
+        return Err(Error::Disabled);
+    }
+    let answer = ask(
+        prompt,
+        &Options {
+            mode: Mode::Visible,
+            askpass: opts.askpass.clone(),
+        },
+    )?;
+    Ok(matches!(answer.trim().to_ascii_lowercase().as_str(), "y" | "yes"))
+}
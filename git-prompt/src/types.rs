@@ -16,6 +16,9 @@ pub enum Error {
     #[cfg(unix)]
     #[error("Failed to obtain or set terminal configuration")]
     TerminalConfiguration(#[from] nix::errno::Errno),
+    #[cfg(windows)]
+    #[error("Failed to obtain or set the console mode")]
+    ConsoleMode(#[source] std::io::Error),
 }
 
 /// The way the user is prompted.
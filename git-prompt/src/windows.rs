@@ -0,0 +1,109 @@
+/// The path to the console input buffer on windows, which is used to read what the user types.
+pub const CONIN_PATH: &str = "CONIN$";
+/// The path to the console screen buffer on windows, which is used to show the prompt.
+pub const CONOUT_PATH: &str = "CONOUT$";
+
+#[cfg(windows)]
+pub(crate) mod imp {
+    use std::{
+        io::{BufRead, Write},
+        os::windows::io::AsRawHandle,
+    };
+
+    use parking_lot::{const_mutex, Mutex};
+    use windows::Win32::{
+        Foundation::HANDLE,
+        System::Console::{GetConsoleMode, SetConsoleMode, CONSOLE_MODE, ENABLE_ECHO_INPUT},
+    };
+
+    use crate::{
+        windows::{CONIN_PATH, CONOUT_PATH},
+        Error, Mode, Options,
+    };
+
+    static CONSOLE_MODE_LOCK: Mutex<()> = const_mutex(());
+
+    /// Ask the user given a `prompt`, returning the result.
+    pub(crate) fn ask(prompt: &str, Options { mode, .. }: &Options<'_>) -> Result<String, Error> {
+        match mode {
+            Mode::Disable => Err(Error::Disabled),
+            Mode::Hidden => {
+                let _lock = CONSOLE_MODE_LOCK.lock();
+                let mut output = std::fs::OpenOptions::new().write(true).open(CONOUT_PATH)?;
+                let input = std::fs::OpenOptions::new().read(true).write(true).open(CONIN_PATH)?;
+                let handle = HANDLE(input.as_raw_handle() as isize);
+                let restore = save_console_mode_and_disable_echo(handle)?;
+                output.write_all(prompt.as_bytes())?;
+
+                let mut buf_read = std::io::BufReader::with_capacity(64, input);
+                let mut out = String::with_capacity(64);
+                buf_read.read_line(&mut out)?;
+
+                out.pop();
+                if out.ends_with('\r') {
+                    out.pop();
+                }
+                restore.now()?;
+                // The newline wasn't echoed either.
+                output.write_all(b"\r\n")?;
+                Ok(out)
+            }
+            Mode::Visible => {
+                let mut output = std::fs::OpenOptions::new().write(true).open(CONOUT_PATH)?;
+                output.write_all(prompt.as_bytes())?;
+
+                let input = std::fs::OpenOptions::new().read(true).write(true).open(CONIN_PATH)?;
+                let mut buf_read = std::io::BufReader::with_capacity(64, input);
+                let mut out = String::with_capacity(64);
+                buf_read.read_line(&mut out)?;
+                Ok(out.trim_end().to_owned())
+            }
+        }
+    }
+
+    struct RestoreConsoleModeOnDrop {
+        handle: HANDLE,
+        mode: Option<CONSOLE_MODE>,
+    }
+
+    impl RestoreConsoleModeOnDrop {
+        fn now(mut self) -> Result<(), Error> {
+            let mode = self.mode.take().expect("BUG: we exist only if something is saved");
+            set_console_mode(self.handle, mode)
+        }
+    }
+
+    impl Drop for RestoreConsoleModeOnDrop {
+        fn drop(&mut self) {
+            if let Some(mode) = self.mode.take() {
+                set_console_mode(self.handle, mode).ok();
+            }
+        }
+    }
+
+    fn save_console_mode_and_disable_echo(handle: HANDLE) -> Result<RestoreConsoleModeOnDrop, Error> {
+        let mut prev = CONSOLE_MODE::default();
+        #[allow(unsafe_code)]
+        // SAFETY: the handle belongs to a file that is open for the entire duration of the call.
+        let success = unsafe { GetConsoleMode(handle, &mut prev) }.as_bool();
+        if !success {
+            return Err(Error::ConsoleMode(std::io::Error::last_os_error()));
+        }
+        set_console_mode(handle, prev & !ENABLE_ECHO_INPUT)?;
+        Ok(RestoreConsoleModeOnDrop {
+            handle,
+            mode: Some(prev),
+        })
+    }
+
+    fn set_console_mode(handle: HANDLE, mode: CONSOLE_MODE) -> Result<(), Error> {
+        #[allow(unsafe_code)]
+        // SAFETY: the handle belongs to a file that is open for the entire duration of the call.
+        let success = unsafe { SetConsoleMode(handle, mode) }.as_bool();
+        if success {
+            Ok(())
+        } else {
+            Err(Error::ConsoleMode(std::io::Error::last_os_error()))
+        }
+    }
+}
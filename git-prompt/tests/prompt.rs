@@ -34,6 +34,38 @@ mod ask {
         p.wait().unwrap();
     }
 
+    #[test]
+    #[cfg(unix)]
+    fn confirm() {
+        let mut cmd = std::process::Command::new(env!("CARGO"));
+        cmd.args(["build", "--example", "confirm"]);
+        cmd.spawn().unwrap().wait().expect("example builds OK");
+
+        let mut p = expectrl::spawn("../target/debug/examples/confirm").unwrap();
+        p.expect("Continue (yes/no)? ").unwrap();
+        p.send_line(" YES ").unwrap();
+        p.expect("true").unwrap();
+        p.expect("Continue (yes/no)? ").unwrap();
+        p.send_line("yesterday").unwrap();
+        p.expect("false").unwrap();
+        p.expect(expectrl::Eof).unwrap();
+        p.wait().unwrap();
+    }
+
+    #[test]
+    fn confirm_fails_if_prompts_are_disabled() {
+        assert!(matches!(
+            git_prompt::confirm(
+                "Continue? ",
+                &git_prompt::Options {
+                    mode: git_prompt::Mode::Disable,
+                    askpass: None
+                }
+            ),
+            Err(git_prompt::Error::Disabled)
+        ));
+    }
+
     #[test]
     #[cfg(not(unix))]
     #[ignore]
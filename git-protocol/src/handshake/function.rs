@@ -44,7 +44,7 @@ where
                 drop(result); // needed to workaround this: https://github.com/rust-lang/rust/issues/76149
                 let url = transport.to_url();
                 progress.set_name("authentication");
-                let credentials::protocol::Outcome { identity, next } =
+                let credentials::protocol::Outcome { identity, next, .. } =
                     authenticate(credentials::helper::Action::get_for_url(url.into_owned()))?
                         .expect("FILL provides an identity or errors");
                 transport.set_identity(identity)?;
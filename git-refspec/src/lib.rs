@@ -4,7 +4,7 @@
 
 ///
 pub mod parse;
-pub use parse::function::parse;
+pub use parse::function::{parse, parse_args};
 
 ///
 pub mod instruction;
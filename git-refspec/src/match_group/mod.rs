@@ -101,6 +101,26 @@ impl<'a> MatchGroup<'a> {
     }
 }
 
+/// Access
+impl<'spec, 'item> Outcome<'spec, 'item> {
+    /// Return the spec that produced `mapping`, which is needed to learn if its destination may be updated
+    /// without being a fast-forward, for instance.
+    pub fn spec_of(&self, mapping: &Mapping<'item, 'spec>) -> RefSpecRef<'spec> {
+        self.group.specs[mapping.spec_index]
+    }
+
+    /// Iterate over all mappings that have a destination along with the spec that produced them, in the order
+    /// in which they should be applied as reference updates.
+    ///
+    /// Mappings without destination are only relevant for `FETCH_HEAD` and are skipped.
+    pub fn iter_updates(&self) -> impl Iterator<Item = (&Mapping<'item, 'spec>, RefSpecRef<'spec>)> + '_ {
+        self.mappings
+            .iter()
+            .filter(|m| m.rhs.is_some())
+            .map(move |m| (m, self.spec_of(m)))
+    }
+}
+
 fn calculate_hash<T: std::hash::Hash>(t: &T) -> u64 {
     use std::hash::Hasher;
     let mut s = std::collections::hash_map::DefaultHasher::new();
@@ -109,4 +129,5 @@ fn calculate_hash<T: std::hash::Hash>(t: &T) -> u64 {
 }
 
 mod util;
-use util::{Matcher, Needle};
+pub use util::Matcher;
+use util::Needle;
@@ -3,7 +3,7 @@ use std::{borrow::Cow, ops::Range};
 use bstr::{BStr, BString, ByteSlice, ByteVec};
 use git_hash::ObjectId;
 
-use crate::{match_group::Item, RefSpecRef};
+use crate::{match_group::Item, parse::Operation, RefSpecRef};
 
 /// A type keeping enough information about a ref-spec to be able to efficiently match it against multiple matcher items.
 pub struct Matcher<'a> {
@@ -16,13 +16,29 @@ impl<'a> Matcher<'a> {
     /// if there was no `rhs`.
     ///
     /// This may involve resolving a glob with an allocation, as the destination is built using the matching portion of a glob.
+    ///
+    /// Specs without a left-hand side, like deletions when pushing, never match.
     pub fn matches_lhs(&self, item: Item<'_>) -> (bool, Option<Cow<'a, BStr>>) {
         match (self.lhs, self.rhs) {
             (Some(lhs), None) => (lhs.matches(item).is_match(), None),
             (Some(lhs), Some(rhs)) => lhs.matches(item).into_match_outcome(rhs, item),
-            (None, None) | (None, Some(_)) => {
-                unreachable!("For all we know, the lefthand side is never empty. Push specs might change that.")
+            (None, _) => (false, None),
+        }
+    }
+
+    /// Match `item` against the right-hand side of this spec, typically the remote destination of a push spec, and return
+    /// `(true, Some<lhs>)` to gain the other side of the match, or `(true, None)` if there is no `lhs` as the spec is a deletion.
+    ///
+    /// Object ids on the left-hand side are returned as hexadecimal hash. Specs without a right-hand side never match.
+    pub fn matches_rhs(&self, item: Item<'_>) -> (bool, Option<Cow<'a, BStr>>) {
+        match (self.lhs, self.rhs) {
+            (None, Some(rhs)) => (rhs.matches(item).is_match(), None),
+            (Some(Needle::Object(id)), Some(rhs)) => {
+                let matched = rhs.matches(item).is_match();
+                (matched, matched.then(|| Cow::Owned(id.to_string().into())))
             }
+            (Some(lhs), Some(rhs)) => rhs.matches(item).into_match_outcome(lhs, item),
+            (_, None) => (false, None),
         }
     }
 }
@@ -154,9 +170,14 @@ impl<'a> From<&'a BStr> for Needle<'a> {
 
 impl<'a> From<RefSpecRef<'a>> for Matcher<'a> {
     fn from(v: RefSpecRef<'a>) -> Self {
+        let lhs = v.src.map(Into::into);
         Matcher {
-            lhs: v.src.map(Into::into),
-            rhs: v.dst.map(Into::into),
+            lhs,
+            rhs: match (v.op, v.dst) {
+                // Pushing without destination updates the remote ref of the same name.
+                (Operation::Push, None) => lhs,
+                (_, dst) => dst.map(Into::into),
+            },
         }
     }
 }
@@ -24,6 +24,8 @@ pub enum Error {
     PatternUnsupported { pattern: bstr::BString },
     #[error("Both sides of the specification need a pattern, like 'a/*:b/*'")]
     PatternUnbalanced,
+    #[error("The 'tag' shorthand must be followed by a tag name")]
+    TagNameMissing,
     #[error(transparent)]
     ReferenceName(#[from] git_validate::refname::Error),
     #[error(transparent)]
@@ -40,14 +42,43 @@ pub enum Operation {
 }
 
 pub(crate) mod function {
-    use bstr::{BStr, ByteSlice};
+    use bstr::{BStr, BString, ByteSlice};
 
     use crate::{
         parse::{Error, Operation},
         types::Mode,
-        RefSpecRef,
+        RefSpec, RefSpecRef,
     };
 
+    /// Parse `args` as passed on the command-line of `git fetch` or `git push` for use in `operation`, and return all of
+    /// them as refspecs if they are valid.
+    ///
+    /// Each argument is a refspec, unless it is `tag` which takes the next argument `<name>` and is equivalent to
+    /// `refs/tags/<name>:refs/tags/<name>` when fetching, or to `refs/tags/<name>` when pushing.
+    pub fn parse_args<'a>(
+        args: impl IntoIterator<Item = &'a BStr>,
+        operation: Operation,
+    ) -> Result<Vec<RefSpec>, Error> {
+        let mut args = args.into_iter();
+        let mut out = Vec::new();
+        while let Some(arg) = args.next() {
+            let spec = if arg == "tag" {
+                let name = args.next().ok_or(Error::TagNameMissing)?;
+                let mut spec = BString::from("refs/tags/");
+                spec.extend_from_slice(name);
+                if operation == Operation::Fetch {
+                    spec.extend_from_slice(b":refs/tags/");
+                    spec.extend_from_slice(name);
+                }
+                parse(spec.as_ref(), operation)?.to_owned()
+            } else {
+                parse(arg, operation)?.to_owned()
+            };
+            out.push(spec);
+        }
+        Ok(out)
+    }
+
     /// Parse `spec` for use in `operation` and return it if it is valid.
     pub fn parse(mut spec: &BStr, operation: Operation) -> Result<RefSpecRef<'_>, Error> {
         fn fetch_head_only(mode: Mode) -> RefSpecRef<'static> {
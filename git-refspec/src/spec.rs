@@ -219,6 +219,14 @@ impl<'a> RefSpecRef<'a> {
     }
 }
 
+/// Access
+impl RefSpecRef<'_> {
+    /// Return true if the spec stats with a `+` and thus forces setting the reference.
+    pub fn allow_non_fast_forward(&self) -> bool {
+        matches!(self.mode, Mode::Force)
+    }
+}
+
 /// Conversion
 impl RefSpecRef<'_> {
     /// Convert this ref into a standalone, owned copy.
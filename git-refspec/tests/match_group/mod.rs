@@ -184,3 +184,146 @@ mod multiple {
         )
     }
 }
+
+mod matcher {
+    use git_hash::ObjectId;
+    use git_refspec::{
+        match_group::{Item, Matcher},
+        parse::Operation,
+    };
+
+    fn matcher(spec: &str, op: Operation) -> Matcher<'_> {
+        git_refspec::parse(spec.into(), op).expect("valid spec").into()
+    }
+
+    fn item<'a>(name: &'a str, target: &'a ObjectId) -> Item<'a> {
+        Item {
+            full_ref_name: name.into(),
+            target,
+            object: None,
+        }
+    }
+
+    fn to_string(outcome: (bool, Option<std::borrow::Cow<'_, bstr::BStr>>)) -> (bool, Option<String>) {
+        (outcome.0, outcome.1.map(|name| name.to_string()))
+    }
+
+    #[test]
+    fn deletions_only_match_on_the_right_hand_side() {
+        let id = ObjectId::null(git_hash::Kind::Sha1);
+        let m = matcher(":refs/heads/gone", Operation::Push);
+        assert_eq!(to_string(m.matches_lhs(item("refs/heads/gone", &id))), (false, None));
+        assert_eq!(to_string(m.matches_rhs(item("refs/heads/gone", &id))), (true, None));
+        assert_eq!(to_string(m.matches_rhs(item("refs/heads/other", &id))), (false, None));
+    }
+
+    #[test]
+    fn push_specs_match_on_both_sides() {
+        let id = ObjectId::null(git_hash::Kind::Sha1);
+        let m = matcher("refs/heads/*:refs/heads/remote-*", Operation::Push);
+        assert_eq!(
+            to_string(m.matches_lhs(item("refs/heads/main", &id))),
+            (true, Some("refs/heads/remote-main".into()))
+        );
+        assert_eq!(
+            to_string(m.matches_rhs(item("refs/heads/remote-main", &id))),
+            (true, Some("refs/heads/main".into()))
+        );
+
+        let m = matcher("main", Operation::Push);
+        assert_eq!(
+            to_string(m.matches_lhs(item("refs/heads/main", &id))),
+            (true, Some("refs/heads/main".into())),
+            "the destination is the source if unset"
+        );
+        assert_eq!(
+            to_string(m.matches_rhs(item("refs/heads/main", &id))),
+            (true, Some("refs/heads/main".into()))
+        );
+    }
+
+    #[test]
+    fn object_sources_on_push() {
+        let id = git_testtools::hex_to_id("e69de29bb2d1d6434b8b29ae775ad8c2e48c5391");
+        let other_id = ObjectId::null(git_hash::Kind::Sha1);
+        let m = matcher(
+            "e69de29bb2d1d6434b8b29ae775ad8c2e48c5391:refs/heads/blob",
+            Operation::Push,
+        );
+        assert_eq!(
+            to_string(m.matches_lhs(item("refs/heads/main", &id))),
+            (true, Some("refs/heads/blob".into())),
+            "the source matches all refs pointing to the object"
+        );
+        assert_eq!(
+            to_string(m.matches_lhs(item("refs/heads/main", &other_id))),
+            (false, None)
+        );
+        assert_eq!(
+            to_string(m.matches_rhs(item("refs/heads/blob", &other_id))),
+            (true, Some("e69de29bb2d1d6434b8b29ae775ad8c2e48c5391".into()))
+        );
+    }
+
+    #[test]
+    fn fetch_specs_without_destination_never_match_on_the_right_hand_side() {
+        let id = ObjectId::null(git_hash::Kind::Sha1);
+        let m = matcher("main", Operation::Fetch);
+        assert_eq!(to_string(m.matches_lhs(item("refs/heads/main", &id))), (true, None));
+        assert_eq!(to_string(m.matches_rhs(item("refs/heads/main", &id))), (false, None));
+    }
+}
+
+mod outcome {
+    use git_hash::ObjectId;
+    use git_refspec::{
+        match_group::{Item, SourceRef},
+        parse::Operation,
+        MatchGroup,
+    };
+
+    #[test]
+    fn updates_carry_the_spec_that_produced_them() {
+        let id = ObjectId::null(git_hash::Kind::Sha1);
+        let items = ["refs/heads/main", "refs/tags/v1"].map(|name| Item {
+            full_ref_name: name.into(),
+            target: &id,
+            object: None,
+        });
+        let specs = ["+refs/heads/*:refs/remotes/origin/*", "refs/tags/*:refs/tags/*", "main"]
+            .map(|spec| git_refspec::parse(spec.into(), Operation::Fetch).expect("valid"));
+        let outcome = MatchGroup::from_fetch_specs(specs).match_remotes(items.iter().copied());
+        assert_eq!(
+            outcome.mappings.len(),
+            3,
+            "the fetch-only mapping is retained for FETCH_HEAD"
+        );
+
+        let updates: Vec<_> = outcome
+            .iter_updates()
+            .map(|(m, spec)| {
+                (
+                    m.lhs,
+                    m.rhs.as_ref().expect("updates have a destination").to_string(),
+                    spec.allow_non_fast_forward(),
+                )
+            })
+            .collect();
+        assert_eq!(
+            updates,
+            vec![
+                (
+                    SourceRef::FullName("refs/heads/main".into()),
+                    "refs/remotes/origin/main".to_string(),
+                    true
+                ),
+                (
+                    SourceRef::FullName("refs/tags/v1".into()),
+                    "refs/tags/v1".to_string(),
+                    false
+                ),
+            ]
+        );
+        assert!(!outcome.spec_of(&outcome.mappings[2]).allow_non_fast_forward());
+    }
+}
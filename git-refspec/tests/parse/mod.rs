@@ -1,7 +1,11 @@
 use std::panic::catch_unwind;
 
 use bstr::ByteSlice;
-use git_refspec::parse::Operation;
+use git_refspec::{
+    instruction::{Fetch, Push},
+    parse::Operation,
+    Instruction,
+};
 use git_testtools::scripted_fixture_repo_read_only;
 
 #[test]
@@ -80,6 +84,56 @@ fn local_and_remote() -> crate::Result {
     Ok(())
 }
 
+#[test]
+fn args_with_tag_shorthand() -> crate::Result {
+    let args = ["main", "tag", "v1.0", "+refs/heads/*:refs/remotes/origin/*"].map(b);
+    let specs = git_refspec::parse_args(args, Operation::Fetch)?;
+    assert_eq!(
+        specs.iter().map(|spec| spec.to_ref().instruction()).collect::<Vec<_>>(),
+        vec![
+            Instruction::Fetch(Fetch::Only { src: b("main") }),
+            Instruction::Fetch(Fetch::AndUpdate {
+                src: b("refs/tags/v1.0"),
+                dst: b("refs/tags/v1.0"),
+                allow_non_fast_forward: false,
+            }),
+            Instruction::Fetch(Fetch::AndUpdate {
+                src: b("refs/heads/*"),
+                dst: b("refs/remotes/origin/*"),
+                allow_non_fast_forward: true,
+            }),
+        ]
+    );
+
+    let specs = git_refspec::parse_args(["tag", "v1.0", ":gone"].map(b), Operation::Push)?;
+    assert_eq!(
+        specs.iter().map(|spec| spec.to_ref().instruction()).collect::<Vec<_>>(),
+        vec![
+            Instruction::Push(Push::Matching {
+                src: b("refs/tags/v1.0"),
+                dst: b("refs/tags/v1.0"),
+                allow_non_fast_forward: false,
+            }),
+            Instruction::Push(Push::Delete {
+                ref_or_pattern: b("gone")
+            }),
+        ]
+    );
+
+    assert!(matches!(
+        git_refspec::parse_args(["main", "tag"].map(b), Operation::Fetch).unwrap_err(),
+        git_refspec::parse::Error::TagNameMissing
+    ));
+    assert!(
+        matches!(
+            git_refspec::parse_args(["tag", "v1..0"].map(b), Operation::Fetch).unwrap_err(),
+            git_refspec::parse::Error::ReferenceName(_)
+        ),
+        "the expanded spec is validated"
+    );
+    Ok(())
+}
+
 mod fetch;
 mod invalid;
 mod push;
@@ -104,6 +104,7 @@ impl PrepareFetch {
         };
         let outcome = pending_pack
             .with_write_packed_refs_only(true)
+            .with_shallow(self.shallow)
             .with_reflog_message(RefLogMessage::Override {
                 message: reflog_message.clone(),
             })
@@ -144,6 +145,13 @@ impl PrepareFetch {
         self.fetch_options = opts;
         self
     }
+    /// Define how to shorten the history of the cloned commits, with the default being to receive all of it.
+    #[cfg(any(feature = "async-network-client", feature = "blocking-network-client"))]
+    pub fn with_shallow(mut self, shallow: crate::remote::fetch::Shallow) -> Self {
+        self.shallow = shallow;
+        self
+    }
+
     /// Use `f` to apply arbitrary changes to the remote that is about to be used to fetch a pack.
     ///
     /// The passed in `remote` will be un-named and pre-configured to be a default remote as we know it from git-clone.
@@ -15,6 +15,9 @@ pub struct PrepareFetch {
     /// Options for preparing a fetch operation.
     #[cfg(any(feature = "async-network-client", feature = "blocking-network-client"))]
     fetch_options: crate::remote::ref_map::Options,
+    /// How to shorten the history of the received commits.
+    #[cfg(any(feature = "async-network-client", feature = "blocking-network-client"))]
+    shallow: crate::remote::fetch::Shallow,
     /// The url to clone from
     #[cfg_attr(not(feature = "blocking-network-client"), allow(dead_code))]
     url: git_url::Url,
@@ -65,6 +68,8 @@ impl PrepareFetch {
             url,
             #[cfg(any(feature = "async-network-client", feature = "blocking-network-client"))]
             fetch_options: Default::default(),
+            #[cfg(any(feature = "async-network-client", feature = "blocking-network-client"))]
+            shallow: Default::default(),
             repo: Some(repo),
             remote_name: None,
             configure_remote: None,
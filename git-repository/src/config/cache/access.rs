@@ -3,6 +3,7 @@ use std::{borrow::Cow, convert::TryInto, path::PathBuf, time::Duration};
 use git_lock::acquire::Fail;
 
 use crate::{
+    bstr::BStr,
     config::{cache::util::ApplyLeniencyDefault, checkout_options, Cache},
     remote,
     repository::identity,
@@ -62,9 +63,9 @@ impl Cache {
             .get_or_init(|| identity::Personas::from_config_and_env(&self.resolved, self.git_prefix))
     }
 
-    pub(crate) fn url_rewrite(&self) -> &remote::url::Rewrite {
+    pub(crate) fn url_rewrite(&self) -> &git_url::Rewriter {
         self.url_rewrite
-            .get_or_init(|| remote::url::Rewrite::from_config(&self.resolved, self.filter_config_section))
+            .get_or_init(|| remote::url::rewrite::from_config(&self.resolved, self.filter_config_section))
     }
 
     #[cfg(any(feature = "blocking-network-client", feature = "async-network-client"))]
@@ -180,16 +181,117 @@ impl Cache {
                 .unwrap_or(default))
         }
 
-        fn assemble_attribute_globals(
+        fn assemble_attributes(
             me: &Cache,
-            _git_dir: &std::path::Path,
-        ) -> Result<git_attributes::MatchGroup, checkout_options::Error> {
-            let _attributes_file = match me.trusted_file_path("core", None, "attributesFile").transpose()? {
+            git_dir: &std::path::Path,
+        ) -> Result<git_attributes::Stack, checkout_options::Error> {
+            let attributes_file = match me.trusted_file_path("core", None, "attributesFile").transpose()? {
                 Some(attributes) => Some(attributes.into_owned()),
                 None => me.xdg_config_path("attributes").ok().flatten(),
             };
-            // TODO: implement git_attributes::MatchGroup::<git_attributes::Attributes>::from_git_dir(), similar to what's done for `Ignore`.
-            Ok(Default::default())
+            let mut buf = Vec::new();
+            git_attributes::Stack::from_git_dir(git_dir, attributes_file, &mut buf).map_err(|err| {
+                checkout_options::Error::AttributesFile {
+                    source: err,
+                    git_dir: git_dir.to_owned(),
+                }
+            })
+        }
+
+        fn filter_options(me: &Cache) -> Result<git_worktree::filter::Options, checkout_options::Error> {
+            use git_worktree::filter::{AutoCrlf, Eol, SafeCrlf};
+            fn parse<T>(
+                me: &Cache,
+                full_key: &'static str,
+                message: &'static str,
+                parse: impl FnOnce(&BStr) -> Option<T>,
+            ) -> Result<Option<T>, checkout_options::Error> {
+                let key = full_key.split_once('.').expect("core.<key>").1;
+                me.apply_leniency(me.resolved.string("core", None, key).map(|value| {
+                    parse(value.as_ref()).ok_or_else(|| checkout_options::Error::Configuration {
+                        key: full_key,
+                        source: git_config::value::Error::new(message, value.into_owned()),
+                    })
+                }))
+            }
+            let boolean = |value: &BStr| git_config::Boolean::try_from(value).ok().map(|value| value.0);
+
+            Ok(git_worktree::filter::Options {
+                eol: parse(
+                    me,
+                    "core.eol",
+                    "Expected 'lf', 'crlf' or 'native'",
+                    |value| match value.as_ref() {
+                        b"lf" => Some(Eol::Lf),
+                        b"crlf" => Some(Eol::Crlf),
+                        b"native" => Some(Eol::native()),
+                        _ => None,
+                    },
+                )?
+                .unwrap_or_default(),
+                auto_crlf: parse(me, "core.autocrlf", "Expected a boolean or 'input'", |value| {
+                    if value == "input" {
+                        Some(AutoCrlf::Input)
+                    } else {
+                        boolean(value).map(|enabled| if enabled { AutoCrlf::Enabled } else { AutoCrlf::Disabled })
+                    }
+                })?
+                .unwrap_or_default(),
+                safe_crlf: parse(me, "core.safecrlf", "Expected a boolean or 'warn'", |value| {
+                    if value == "warn" {
+                        Some(SafeCrlf::Warn)
+                    } else {
+                        boolean(value).map(|enabled| if enabled { SafeCrlf::Fail } else { SafeCrlf::Disabled })
+                    }
+                })?
+                .unwrap_or_default(),
+            })
+        }
+
+        fn filter_drivers(me: &Cache) -> Result<Vec<git_worktree::filter::Driver>, checkout_options::Error> {
+            let mut drivers = Vec::<git_worktree::filter::Driver>::new();
+            let sections = match me.resolved.sections_by_name("filter") {
+                Some(sections) => sections,
+                None => return Ok(drivers),
+            };
+            // Drivers run commands, hence they are only read from trusted configuration.
+            for section in sections.filter(|section| (me.filter_config_section)(section.meta())) {
+                let name = match section.header().subsection_name() {
+                    Some(name) => name,
+                    None => continue,
+                };
+                let driver = match drivers.iter().position(|driver| driver.name == name) {
+                    Some(idx) => &mut drivers[idx],
+                    None => {
+                        drivers.push(git_worktree::filter::Driver {
+                            name: name.to_owned(),
+                            ..Default::default()
+                        });
+                        drivers.last_mut().expect("just pushed")
+                    }
+                };
+                for (key, value) in [
+                    ("clean", &mut driver.clean),
+                    ("smudge", &mut driver.smudge),
+                    ("process", &mut driver.process),
+                ] {
+                    if let Some(command) = section.value(key) {
+                        *value = Some(command.into_owned());
+                    }
+                }
+                if let Some(required) = section.value_implicit("required") {
+                    driver.required = me
+                        .apply_leniency(Some(required.map_or(Ok(true), |value| {
+                            git_config::Boolean::try_from(value).map(|value| value.0)
+                        })))
+                        .map_err(|err| checkout_options::Error::Configuration {
+                            key: "filter.<driver>.required",
+                            source: err,
+                        })?
+                        .unwrap_or_default();
+                }
+            }
+            Ok(drivers)
         }
 
         let thread_limit = self.apply_leniency(checkout_thread_limit_from_config(&self.resolved))?;
@@ -209,7 +311,9 @@ impl Cache {
                 .resolved
                 .string("core", None, "checkStat")
                 .map_or(true, |v| v.as_ref() != "minimal"),
-            attribute_globals: assemble_attribute_globals(self, git_dir)?,
+            attributes: assemble_attributes(self, git_dir)?,
+            filters: git_worktree::filter::Pipeline::new(filter_drivers(self)?, filter_options(self)?),
+            protect_ntfs: boolean(self, "core.protectNTFS", true)?,
         })
     }
     pub(crate) fn xdg_config_path(
@@ -1,7 +1,7 @@
 pub use git_config::*;
 use git_features::threading::OnceCell;
 
-use crate::{bstr::BString, remote, repository::identity, revision::spec, Repository};
+use crate::{bstr::BString, repository::identity, revision::spec, Repository};
 
 pub(crate) mod cache;
 mod snapshot;
@@ -104,6 +104,11 @@ pub mod checkout_options {
         },
         #[error("Failed to interpolate the attribute file configured at `core.attributesFile`")]
         AttributesFileInterpolation(#[from] git_config::path::interpolate::Error),
+        #[error("Could not read the attribute files of the repository at '{}' or configured globally", .git_dir.display())]
+        AttributesFile {
+            source: std::io::Error,
+            git_dir: std::path::PathBuf,
+        },
     }
 }
 
@@ -175,10 +180,10 @@ pub(crate) struct Cache {
     /// identities for later use, lazy initialization.
     pub(crate) personas: OnceCell<identity::Personas>,
     /// A lazily loaded rewrite list for remote urls
-    pub(crate) url_rewrite: OnceCell<remote::url::Rewrite>,
+    pub(crate) url_rewrite: OnceCell<git_url::Rewriter>,
     /// A lazily loaded mapping to know which url schemes to allow
     #[cfg(any(feature = "blocking-network-client", feature = "async-network-client"))]
-    pub(crate) url_scheme: OnceCell<remote::url::SchemePermission>,
+    pub(crate) url_scheme: OnceCell<crate::remote::url::SchemePermission>,
     /// The algorithm to use when diffing blobs
     pub(crate) diff_algorithm: OnceCell<git_diff::blob::Algorithm>,
     /// The config section filter from the options used to initialize this instance. Keep these in sync!
@@ -1,5 +1,5 @@
 use std::{
-    convert::TryFrom,
+    convert::{TryFrom, TryInto},
     fs::{self, OpenOptions},
     io::Write,
     path::{Path, PathBuf},
@@ -8,6 +8,8 @@ use std::{
 use git_config::parse::section;
 use git_discover::DOT_GIT_DIR;
 
+use crate::bstr::BString;
+
 /// The error used in [`into()`].
 #[derive(Debug, thiserror::Error)]
 #[allow(missing_docs)]
@@ -24,6 +26,11 @@ pub enum Error {
     DirectoryNotEmpty { path: PathBuf },
     #[error("Could not create directory at '{}'", .path.display())]
     CreateDirectory { source: std::io::Error, path: PathBuf },
+    #[error("Invalid initial branch name: {name:?}")]
+    InvalidBranchName {
+        name: BString,
+        source: git_validate::refname::Error,
+    },
 }
 
 /// The kind of repository to create.
@@ -89,6 +96,7 @@ fn write_file(data: &[u8], path: &Path) -> Result<(), Error> {
     let mut file = OpenOptions::new()
         .write(true)
         .create(true)
+        .truncate(true)
         .append(false)
         .open(path)
         .map_err(|e| Error::IoOpen {
@@ -108,8 +116,36 @@ fn create_dir(p: &Path) -> Result<(), Error> {
     })
 }
 
+fn copy_template_dir(template_dir: &Path, dir: &mut PathBuf) -> Result<(), Error> {
+    let entries = fs::read_dir(template_dir).map_err(|err| Error::IoOpen {
+        source: err,
+        path: template_dir.to_owned(),
+    })?;
+    for entry in entries {
+        let entry = entry.map_err(|err| Error::IoOpen {
+            source: err,
+            path: template_dir.to_owned(),
+        })?;
+        let file_name = entry.file_name();
+        let file_name = file_name.to_string_lossy();
+        if file_name.starts_with('.') {
+            continue;
+        }
+        let src = entry.path();
+        let mut cursor = PathCursor(&mut *dir);
+        let dst = cursor.at(&file_name).to_owned();
+        if src.is_dir() {
+            create_dir(&dst)?;
+            copy_template_dir(&src, &mut *cursor.0)?;
+        } else if !dst.exists() {
+            fs::copy(&src, &dst).map_err(|err| Error::IoWrite { source: err, path: dst })?;
+        }
+    }
+    Ok(())
+}
+
 /// Options for use in [`into()`];
-#[derive(Copy, Clone, Default)]
+#[derive(Clone, Default)]
 pub struct Options {
     /// If true, and the kind of repository to create has a worktree, then the destination directory must be empty.
     ///
@@ -118,6 +154,17 @@ pub struct Options {
     /// If set, use these filesystem capabilities to populate the respective git-config fields.
     /// If `None`, the directory will be probed.
     pub fs_capabilities: Option<git_worktree::fs::Capabilities>,
+    /// If set, the name of the branch `HEAD` points to, like `main`, instead of the default one.
+    ///
+    /// This takes precedence over the `init.defaultBranch` configuration.
+    pub initial_branch: Option<BString>,
+    /// If set, copy the files in this directory into the new git directory instead of using our built-in templates,
+    /// similar to `git init --template`.
+    ///
+    /// Files starting with a `.` are ignored, and `config` and `HEAD` are always written by us.
+    pub template_dir: Option<PathBuf>,
+    /// The kind of hash to use for objects in the new repository.
+    pub object_hash: git_hash::Kind,
 }
 
 /// Create a new `.git` repository of `kind` within the possibly non-existing `directory`
@@ -131,10 +178,25 @@ pub fn into(
     Options {
         fs_capabilities,
         destination_must_be_empty,
+        initial_branch,
+        template_dir,
+        object_hash,
     }: Options,
 ) -> Result<git_discover::repository::Path, Error> {
     let mut dot_git = directory.into();
     let bare = matches!(kind, Kind::Bare);
+    let head = match initial_branch {
+        Some(name) => {
+            let full_name: git_ref::FullName = format!("refs/heads/{name}")
+                .try_into()
+                .map_err(|err| Error::InvalidBranchName { name, source: err })?;
+            let mut head = b"ref: ".to_vec();
+            head.extend_from_slice(full_name.as_bstr());
+            head.push(b'\n');
+            std::borrow::Cow::Owned(head)
+        }
+        None => std::borrow::Cow::Borrowed(TPL_HEAD),
+    };
 
     if bare || destination_must_be_empty {
         let num_entries_in_dot_git = fs::read_dir(&dot_git)
@@ -164,29 +226,35 @@ pub fn into(
     };
     create_dir(&dot_git)?;
 
-    {
-        let mut cursor = NewDir(&mut dot_git).at("info")?;
-        write_file(TPL_INFO_EXCLUDE, PathCursor(cursor.as_mut()).at("exclude"))?;
-    }
+    if let Some(template_dir) = template_dir {
+        copy_template_dir(&template_dir, &mut dot_git)?;
+    } else {
+        {
+            let mut cursor = NewDir(&mut dot_git).at("info")?;
+            write_file(TPL_INFO_EXCLUDE, PathCursor(cursor.as_mut()).at("exclude"))?;
+        }
 
-    {
-        let mut cursor = NewDir(&mut dot_git).at("hooks")?;
-        for (tpl, filename) in &[
-            (TPL_HOOKS_UPDATE, "update.sample"),
-            (TPL_HOOKS_PREPARE_COMMIT_MSG, "prepare-commit-msg.sample"),
-            (TPL_HOOKS_PRE_RECEIVE, "pre-receive.sample"),
-            (TPL_HOOKS_PRE_REBASE, "pre-rebase.sample"),
-            (TPL_HOOKS_PRE_PUSH, "pre-push.sample"),
-            (TPL_HOOKS_PRE_COMMIT, "pre-commit.sample"),
-            (TPL_HOOKS_PRE_MERGE_COMMIT, "pre-merge-commit.sample"),
-            (TPL_HOOKS_PRE_APPLYPATCH, "pre-applypatch.sample"),
-            (TPL_HOOKS_POST_UPDATE, "post-update.sample"),
-            (TPL_HOOKS_FSMONITOR_WATCHMAN, "fsmonitor-watchman.sample"),
-            (TPL_HOOKS_COMMIT_MSG, "commit-msg.sample"),
-            (TPL_HOOKS_APPLYPATCH_MSG, "applypatch-msg.sample"),
-        ] {
-            write_file(tpl, PathCursor(cursor.as_mut()).at(filename))?;
+        {
+            let mut cursor = NewDir(&mut dot_git).at("hooks")?;
+            for (tpl, filename) in &[
+                (TPL_HOOKS_UPDATE, "update.sample"),
+                (TPL_HOOKS_PREPARE_COMMIT_MSG, "prepare-commit-msg.sample"),
+                (TPL_HOOKS_PRE_RECEIVE, "pre-receive.sample"),
+                (TPL_HOOKS_PRE_REBASE, "pre-rebase.sample"),
+                (TPL_HOOKS_PRE_PUSH, "pre-push.sample"),
+                (TPL_HOOKS_PRE_COMMIT, "pre-commit.sample"),
+                (TPL_HOOKS_PRE_MERGE_COMMIT, "pre-merge-commit.sample"),
+                (TPL_HOOKS_PRE_APPLYPATCH, "pre-applypatch.sample"),
+                (TPL_HOOKS_POST_UPDATE, "post-update.sample"),
+                (TPL_HOOKS_FSMONITOR_WATCHMAN, "fsmonitor-watchman.sample"),
+                (TPL_HOOKS_COMMIT_MSG, "commit-msg.sample"),
+                (TPL_HOOKS_APPLYPATCH_MSG, "applypatch-msg.sample"),
+            ] {
+                write_file(tpl, PathCursor(cursor.as_mut()).at(filename))?;
+            }
         }
+
+        write_file(TPL_DESCRIPTION, PathCursor(&mut dot_git).at("description"))?;
     }
 
     {
@@ -201,9 +269,7 @@ pub fn into(
         create_dir(PathCursor(cursor.as_mut()).at("tags"))?;
     }
 
-    for (tpl, filename) in &[(TPL_HEAD, "HEAD"), (TPL_DESCRIPTION, "description")] {
-        write_file(tpl, PathCursor(&mut dot_git).at(filename))?;
-    }
+    write_file(&head, PathCursor(&mut dot_git).at("HEAD"))?;
 
     {
         let mut config = git_config::File::default();
@@ -211,7 +277,8 @@ pub fn into(
             let caps = fs_capabilities.unwrap_or_else(|| git_worktree::fs::Capabilities::probe(&dot_git));
             let mut core = config.new_section("core", None).expect("valid section name");
 
-            core.push(key("repositoryformatversion"), Some("0".into()));
+            let format_version = if object_hash == git_hash::Kind::Sha1 { "0" } else { "1" };
+            core.push(key("repositoryformatversion"), Some(format_version.into()));
             core.push(key("filemode"), Some(bool(caps.executable_bit).into()));
             core.push(key("bare"), Some(bool(bare).into()));
             core.push(key("logallrefupdates"), Some(bool(!bare).into()));
@@ -219,6 +286,13 @@ pub fn into(
             core.push(key("ignorecase"), Some(bool(caps.ignore_case).into()));
             core.push(key("precomposeunicode"), Some(bool(caps.precompose_unicode).into()));
         }
+        if object_hash != git_hash::Kind::Sha1 {
+            let mut extensions = config.new_section("extensions", None).expect("valid section name");
+            extensions.push(
+                key("objectformat"),
+                Some(object_hash.to_string().to_ascii_lowercase().as_str().into()),
+            );
+        }
         let mut cursor = PathCursor(&mut dot_git);
         let config_path = cursor.at("config");
         std::fs::write(config_path, &config.to_bstring()).map_err(|err| Error::IoWrite {
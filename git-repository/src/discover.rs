@@ -2,7 +2,7 @@ use std::path::Path;
 
 pub use git_discover::*;
 
-use crate::{bstr::BString, ThreadSafeRepository};
+use crate::ThreadSafeRepository;
 
 /// The error returned by [`crate::discover()`].
 #[derive(Debug, thiserror::Error)]
@@ -25,6 +25,9 @@ impl ThreadSafeRepository {
     /// while applying `options`. Then use the `trust_map` to determine which of our own repository options to use
     /// for instantiations.
     ///
+    /// If [`safe_directories`][upwards::Options::safe_directories] in `options` are empty, they are read from `safe.directory`
+    /// in the system and global configuration.
+    ///
     /// Note that [trust overrides](crate::open::Options::with()) in the `trust_map` are not effective here and we will
     /// always override it with the determined trust value. This is a precaution as the API user is unable to actually know
     /// if the directory that is discovered can indeed be trusted (or else they'd have to implement the discovery themselves
@@ -35,11 +38,26 @@ impl ThreadSafeRepository {
         options: upwards::Options<'_>,
         trust_map: git_sec::trust::Mapping<crate::open::Options>,
     ) -> Result<Self, Error> {
+        Self::discover_opts_with_work_tree(directory, options, trust_map, None)
+    }
+
+    /// Like [`discover_opts()`][Self::discover_opts()], but use `work_tree` instead of the work tree of the discovered repository.
+    fn discover_opts_with_work_tree(
+        directory: impl AsRef<Path>,
+        mut options: upwards::Options<'_>,
+        trust_map: git_sec::trust::Mapping<crate::open::Options>,
+        work_tree: Option<&Path>,
+    ) -> Result<Self, Error> {
+        if options.safe_directories.is_empty() {
+            options.safe_directories = safe_directories_from_global_config();
+        }
         let (path, trust) = upwards_opts(directory, options)?;
         let (git_dir, worktree_dir) = path.into_repository_and_work_tree_directories();
+        let cwd = std::env::current_dir().map_err(upwards::Error::CurrentDir)?;
+        let worktree_dir = work_tree.map(|work_tree| cwd.join(work_tree)).or(worktree_dir);
         let mut options = trust_map.into_value_by_level(trust);
         options.git_dir_trust = trust.into();
-        options.current_dir = Some(std::env::current_dir().map_err(upwards::Error::CurrentDir)?);
+        options.current_dir = Some(cwd);
         Self::open_from_paths(git_dir, worktree_dir, options).map_err(Into::into)
     }
 
@@ -56,6 +74,7 @@ impl ThreadSafeRepository {
     ///
     /// - `GIT_DISCOVERY_ACROSS_FILESYSTEM`
     /// - `GIT_CEILING_DIRECTORIES`
+    /// - `GIT_WORK_TREE`, which replaces the work tree of the discovered repository
     ///
     /// Finally, use the `trust_map` to determine which of our own repository options to use
     /// based on the trust level of the effective repository directory.
@@ -64,24 +83,22 @@ impl ThreadSafeRepository {
         mut options: upwards::Options<'_>,
         trust_map: git_sec::trust::Mapping<crate::open::Options>,
     ) -> Result<Self, Error> {
-        fn apply_additional_environment(mut opts: upwards::Options<'_>) -> upwards::Options<'_> {
-            use crate::bstr::ByteVec;
-
-            if let Some(cross_fs) = std::env::var_os("GIT_DISCOVERY_ACROSS_FILESYSTEM")
-                .and_then(|v| Vec::from_os_string(v).ok().map(BString::from))
-            {
-                if let Ok(b) = git_config::Boolean::try_from(cross_fs.as_ref()) {
-                    opts.cross_fs = b.into();
-                }
-            }
-            opts
-        }
-
-        if std::env::var_os("GIT_DIR").is_some() {
+        let overrides = environment::Overrides::from_env();
+        if overrides.git_dir.is_some() {
             return Self::open_with_environment_overrides(directory.as_ref(), trust_map).map_err(Error::Open);
         }
 
-        options = apply_additional_environment(options.apply_environment());
-        Self::discover_opts(directory, options, trust_map)
+        options = options.apply_environment();
+        Self::discover_opts_with_work_tree(directory, options, trust_map, overrides.work_tree.as_deref())
     }
 }
+
+/// Read `safe.directory` from the system and global git configuration, or return no directory if it can't be loaded.
+fn safe_directories_from_global_config() -> Vec<std::path::PathBuf> {
+    let home = std::env::var_os("HOME").map(std::path::PathBuf::from);
+    git_config::File::from_globals()
+        .map(|config| {
+            crate::open::safe_directories(&config, crate::path::install_dir().ok().as_deref(), home.as_deref())
+        })
+        .unwrap_or_default()
+}
@@ -54,13 +54,14 @@ impl ThreadSafeRepository {
     /// # Deviation
     ///
     /// Instead of naming the default branch `master`, we name it `main` unless configured explicitly using the `init.defaultBranch`
-    /// configuration key.
+    /// configuration key, or with [`initial_branch`][crate::create::Options::initial_branch].
     pub fn init_opts(
         directory: impl AsRef<Path>,
         kind: crate::create::Kind,
         create_options: crate::create::Options,
         mut open_options: crate::open::Options,
     ) -> Result<Self, Error> {
+        let has_initial_branch = create_options.initial_branch.is_some();
         let path = crate::create::into(directory.as_ref(), kind, create_options)?;
         let (git_dir, worktree_dir) = path.into_repository_and_work_tree_directories();
         open_options.git_dir_trust = Some(git_sec::Trust::Full);
@@ -72,7 +73,7 @@ impl ThreadSafeRepository {
             .resolved
             .string("init", None, "defaultBranch")
             .unwrap_or_else(|| Cow::Borrowed(DEFAULT_BRANCH_NAME.into()));
-        if branch_name.as_ref() != DEFAULT_BRANCH_NAME {
+        if !has_initial_branch && branch_name.as_ref() != DEFAULT_BRANCH_NAME {
             let sym_ref: FullName =
                 format!("refs/heads/{branch_name}")
                     .try_into()
@@ -52,11 +52,9 @@ mod init {
         Ok(())
     }
 }
-use std::{
-    io,
-    sync::atomic::{AtomicBool, Ordering},
-};
+use std::io;
 
+pub use git_features::interrupt::{is_triggered, reset, trigger, IS_INTERRUPTED};
 pub use init::init_handler;
 
 /// A wrapper for an inner iterator which will check for interruptions on each iteration.
@@ -150,21 +148,3 @@ where
         self.inner.consume(amt)
     }
 }
-
-/// The flag behind all utility functions in this module.
-pub static IS_INTERRUPTED: AtomicBool = AtomicBool::new(false);
-
-/// Returns true if an interrupt is requested.
-pub fn is_triggered() -> bool {
-    IS_INTERRUPTED.load(Ordering::Relaxed)
-}
-
-/// Trigger an interrupt, signalling to those checking for [`is_triggered()`] to stop what they are doing.
-pub fn trigger() {
-    IS_INTERRUPTED.store(true, Ordering::SeqCst);
-}
-
-/// Sets the interrupt request to false, thus allowing those checking for [`is_triggered()`] to proceed.
-pub fn reset() {
-    IS_INTERRUPTED.store(false, Ordering::SeqCst);
-}
@@ -142,6 +142,7 @@ pub use git_lock as lock;
 pub use git_object as objs;
 pub use git_object::bstr;
 pub use git_odb as odb;
+pub use git_pathspec as pathspec;
 pub use git_prompt as prompt;
 #[cfg(all(feature = "git-protocol"))]
 pub use git_protocol as protocol;
@@ -8,11 +8,14 @@ pub mod load {
     pub enum Error {
         #[error("The mailmap file declared in `mailmap.file` could not be read")]
         Io(#[from] std::io::Error),
-        #[error("The configured mailmap.blob could not be parsed")]
-        BlobSpec(#[from] git_hash::decode::Error),
         #[error(transparent)]
         PathInterpolate(#[from] git_config::path::interpolate::Error),
         #[error("Could not find object configured in `mailmap.blob`")]
         FindExisting(#[from] crate::object::find::existing::Error),
+        #[error("The object {id} configured in `mailmap.blob` is a {kind}, but needs to be a blob")]
+        NotABlob {
+            id: git_hash::ObjectId,
+            kind: git_object::Kind,
+        },
     }
 }
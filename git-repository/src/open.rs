@@ -97,32 +97,6 @@ impl Default for Options {
     }
 }
 
-#[derive(Default, Clone)]
-pub(crate) struct EnvironmentOverrides {
-    /// An override of the worktree typically from the environment, and overrides even worktree dirs set as parameter.
-    ///
-    /// This emulates the way git handles this override.
-    worktree_dir: Option<PathBuf>,
-    /// An override for the .git directory, typically from the environment.
-    ///
-    /// If set, the passed in `git_dir` parameter will be ignored in favor of this one.
-    git_dir: Option<PathBuf>,
-}
-
-impl EnvironmentOverrides {
-    fn from_env() -> Result<Self, permission::env_var::resource::Error> {
-        let mut worktree_dir = None;
-        if let Some(path) = std::env::var_os("GIT_WORK_TREE") {
-            worktree_dir = PathBuf::from(path).into();
-        }
-        let mut git_dir = None;
-        if let Some(path) = std::env::var_os("GIT_DIR") {
-            git_dir = PathBuf::from(path).into();
-        }
-        Ok(EnvironmentOverrides { worktree_dir, git_dir })
-    }
-}
-
 /// Instantiation
 impl Options {
     /// Options configured to prevent accessing anything else than the repository configuration file, prohibiting
@@ -273,6 +247,8 @@ pub enum Error {
     #[error("The git directory at '{}' is considered unsafe as it's not owned by the current user.", .path.display())]
     UnsafeGitDir { path: PathBuf },
     #[error(transparent)]
+    Environment(#[from] git_discover::environment::Error),
+    #[error(transparent)]
     EnvironmentAccessDenied(#[from] permission::env_var::resource::Error),
 }
 
@@ -315,7 +291,9 @@ impl ThreadSafeRepository {
     /// The `.git` directory whether given or computed is used for trust checks.
     ///
     /// Note that this will read various `GIT_*` environment variables to check for overrides, and is probably most useful when implementing
-    /// custom hooks.
+    /// custom hooks. These are `GIT_DIR`, `GIT_WORK_TREE` and `GIT_COMMON_DIR` as
+    /// [interpreted by `git-discover`][git_discover::environment::Overrides::repository_path()], with `GIT_WORK_TREE` taking
+    /// precedence over the work tree of `fallback_directory` as well.
     // TODO: tests, with hooks, GIT_QUARANTINE for ref-log and transaction control (needs git-sec support to remove write access in git-ref)
     // TODO: The following vars should end up as overrides of the respective configuration values (see git-config).
     //       GIT_HTTP_PROXY_AUTHMETHOD, GIT_PROXY_SSL_CERT, GIT_PROXY_SSL_KEY, GIT_PROXY_SSL_CERT_PASSWORD_PROTECTED.
@@ -326,29 +304,44 @@ impl ThreadSafeRepository {
         fallback_directory: impl Into<PathBuf>,
         trust_map: git_sec::trust::Mapping<Options>,
     ) -> Result<Self, Error> {
-        let overrides = EnvironmentOverrides::from_env()?;
-        let (path, path_kind): (PathBuf, _) = match overrides.git_dir {
-            Some(git_dir) => git_discover::is_git(&git_dir).map(|kind| (git_dir, kind))?,
+        let overrides = git_discover::environment::Overrides::from_env();
+        let cwd = std::env::current_dir()?;
+        let (git_dir, worktree_dir) = match overrides.repository_path(&cwd).transpose()? {
+            Some(path) => path,
             None => {
                 let fallback_directory = fallback_directory.into();
-                git_discover::is_git(&fallback_directory).map(|kind| (fallback_directory, kind))?
+                let kind = git_discover::is_git(&fallback_directory)?;
+                let path = git_discover::repository::Path::from_dot_git_dir(fallback_directory, kind, &cwd)
+                    .expect("we have sanitized path with is_git()");
+                match &overrides.work_tree {
+                    Some(work_tree) => git_discover::repository::Path::LinkedWorkTree {
+                        work_dir: cwd.join(work_tree),
+                        git_dir: path.into_repository_and_work_tree_directories().0,
+                    },
+                    None => path,
+                }
             }
-        };
-
-        let cwd = std::env::current_dir()?;
-        let (git_dir, worktree_dir) = git_discover::repository::Path::from_dot_git_dir(path, path_kind, &cwd)
-            .expect("we have sanitized path with is_git()")
-            .into_repository_and_work_tree_directories();
-        let worktree_dir = worktree_dir.or(overrides.worktree_dir);
+        }
+        .into_repository_and_work_tree_directories();
 
         let git_dir_trust = git_sec::Trust::from_path_ownership(&git_dir)?;
         let mut options = trust_map.into_value_by_level(git_dir_trust);
         options.current_dir = Some(cwd);
-        ThreadSafeRepository::open_from_paths(git_dir, worktree_dir, options)
+        ThreadSafeRepository::open_from_paths_and_common_dir(git_dir, overrides.common_dir, worktree_dir, options)
     }
 
     pub(crate) fn open_from_paths(
         git_dir: PathBuf,
+        worktree_dir: Option<PathBuf>,
+        options: Options,
+    ) -> Result<Self, Error> {
+        Self::open_from_paths_and_common_dir(git_dir, None, worktree_dir, options)
+    }
+
+    /// Like `open_from_paths()`, but with `common_dir` used instead of the `commondir` file in `git_dir` if set.
+    fn open_from_paths_and_common_dir(
+        git_dir: PathBuf,
+        common_dir: Option<PathBuf>,
         mut worktree_dir: Option<PathBuf>,
         options: Options,
     ) -> Result<Self, Error> {
@@ -371,9 +364,12 @@ impl ThreadSafeRepository {
         //       This would be something read in later as have to first check for extensions. Also this means
         //       that each worktree, even if accessible through this instance, has to come in its own Repository instance
         //       as it may have its own configuration. That's fine actually.
-        let common_dir = git_discover::path::from_plain_file(git_dir.join("commondir"))
-            .transpose()?
-            .map(|cd| git_dir.join(cd));
+        let common_dir = match common_dir {
+            Some(common_dir) => Some(current_dir.join(common_dir)),
+            None => git_discover::path::from_plain_file(git_dir.join("commondir"))
+                .transpose()?
+                .map(|cd| git_dir.join(cd)),
+        };
         let common_dir_ref = common_dir.as_deref().unwrap_or(&git_dir);
 
         let repo_config = config::cache::StageOne::new(common_dir_ref, git_dir_trust, lossy_config, lenient_config)?;
@@ -406,7 +402,13 @@ impl ThreadSafeRepository {
         )?;
 
         if bail_if_untrusted && git_dir_trust != git_sec::Trust::Full {
-            check_safe_directories(&git_dir, git_install_dir.as_deref(), home.as_deref(), &config)?;
+            check_safe_directories(
+                &git_dir,
+                worktree_dir.as_deref(),
+                git_install_dir.as_deref(),
+                home.as_deref(),
+                &config,
+            )?;
         }
 
         // core.worktree might be used to overwrite the worktree directory
@@ -519,49 +521,56 @@ fn reflog_or_default(
 
 fn check_safe_directories(
     git_dir: &std::path::Path,
+    worktree_dir: Option<&std::path::Path>,
     git_install_dir: Option<&std::path::Path>,
     home: Option<&std::path::Path>,
     config: &config::Cache,
 ) -> Result<(), Error> {
-    let mut is_safe = false;
-    let git_dir = match git_path::realpath(git_dir) {
-        Ok(p) => p,
-        Err(_) => git_dir.to_owned(),
+    let options = git_discover::upwards::Options {
+        safe_directories: safe_directories(&config.resolved, git_install_dir, home),
+        ..Default::default()
     };
+    if options.is_safe_directory(worktree_dir.unwrap_or(git_dir)) {
+        Ok(())
+    } else {
+        Err(Error::UnsafeGitDir {
+            path: git_dir.to_owned(),
+        })
+    }
+}
+
+/// Return all `safe.directory` values of the system and global configuration in `config`, where an empty value
+/// removes all values seen before it, in a form suitable for [`git_discover::upwards::Options::safe_directories`].
+pub(crate) fn safe_directories(
+    config: &git_config::File<'_>,
+    git_install_dir: Option<&std::path::Path>,
+    home: Option<&std::path::Path>,
+) -> Vec<PathBuf> {
+    let mut out = Vec::new();
     for safe_dir in config
-        .resolved
         .strings_filter("safe", None, "directory", &mut |meta| {
             let kind = meta.source.kind();
             kind == git_config::source::Kind::System || kind == git_config::source::Kind::Global
         })
         .unwrap_or_default()
     {
-        if safe_dir.as_ref() == "*" {
-            is_safe = true;
-            continue;
-        }
         if safe_dir.is_empty() {
-            is_safe = false;
+            out.clear();
             continue;
         }
-        if !is_safe {
-            let safe_dir = match git_config::Path::from(std::borrow::Cow::Borrowed(safe_dir.as_ref()))
-                .interpolate(interpolate_context(git_install_dir, home))
-            {
-                Ok(path) => path,
-                Err(_) => git_path::from_bstr(safe_dir),
-            };
-            if safe_dir == git_dir {
-                is_safe = true;
-                continue;
-            }
+        if safe_dir.as_ref() == "*" {
+            out.push(PathBuf::from("*"));
+            continue;
         }
+        let safe_dir = match git_config::Path::from(std::borrow::Cow::Borrowed(safe_dir.as_ref()))
+            .interpolate(interpolate_context(git_install_dir, home))
+        {
+            Ok(path) => path.into_owned(),
+            Err(_) => git_path::from_bstr(safe_dir).into_owned(),
+        };
+        out.push(safe_dir);
     }
-    if is_safe {
-        Ok(())
-    } else {
-        Err(Error::UnsafeGitDir { path: git_dir })
-    }
+    out
 }
 
 #[cfg(test)]
@@ -46,7 +46,6 @@ impl<'r> Platform<'r> {
         Ok(Iter::new(self.repo, self.platform.prefixed(prefix)?))
     }
 
-    // TODO: tests
     /// Return an iterator over all references that are tags.
     ///
     /// They are all prefixed with `refs/tags`.
@@ -54,7 +53,6 @@ impl<'r> Platform<'r> {
         Ok(Iter::new(self.repo, self.platform.prefixed("refs/tags/")?))
     }
 
-    // TODO: tests
     /// Return an iterator over all local branches.
     ///
     /// They are all prefixed with `refs/heads`.
@@ -62,7 +60,6 @@ impl<'r> Platform<'r> {
         Ok(Iter::new(self.repo, self.platform.prefixed("refs/heads/")?))
     }
 
-    // TODO: tests
     /// Return an iterator over all remote branches.
     ///
     /// They are all prefixed with `refs/remotes`.
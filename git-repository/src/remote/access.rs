@@ -29,7 +29,8 @@ impl<'repo> Remote<'repo> {
     /// Return the url used for the given `direction` with rewrites from `url.<base>.insteadOf|pushInsteadOf`, unless the instance
     /// was created with one of the `_without_url_rewrite()` methods.
     /// For pushing, this is the `remote.<name>.pushUrl` or the `remote.<name>.url` used for fetching, and for fetching it's
-    /// the `remote.<name>.url`.
+    /// the `remote.<name>.url`. Like in git, `pushInsteadOf` rules apply to the fetch url as well if there is no push url,
+    /// falling back to the fetch url rewritten with `insteadOf` if none of them matched.
     /// Note that it's possible to only have the push url set, in which case there will be no way to fetch from the remote as
     /// the push-url isn't used for that.
     pub fn url(&self, direction: remote::Direction) -> Option<&git_url::Url> {
@@ -58,14 +59,17 @@ impl Remote<'_> {
             }
             Err(err) => err.into(),
         };
-        let push_url_err =
-            match remote::init::rewrite_url(&self.repo.config, self.push_url.as_ref(), remote::Direction::Push) {
-                Ok(url) => {
-                    self.push_url_alias = url;
-                    None
-                }
-                Err(err) => err.into(),
-            };
+        let push_url_err = match remote::init::rewrite_url(
+            &self.repo.config,
+            self.push_url.as_ref().or(self.url.as_ref()),
+            remote::Direction::Push,
+        ) {
+            Ok(url) => {
+                self.push_url_alias = url;
+                None
+            }
+            Err(err) => err.into(),
+        };
         url_err.or(push_url_err).map(Err::<&mut Self, _>).transpose()?;
         Ok(self)
     }
@@ -23,6 +23,18 @@ pub enum Error {
     WritePack(#[from] git_pack::bundle::write::Error),
     #[error(transparent)]
     UpdateRefs(#[from] super::refs::update::Error),
+    #[error("The remote doesn't support the {feature:?} capability, which is needed for {description}")]
+    MissingServerFeature {
+        feature: &'static str,
+        description: &'static str,
+    },
+    #[error("Could not lock the shallow file for writing")]
+    LockShallowFile(#[from] git_lock::acquire::Error),
+    #[error("Failed to write the shallow file at \"{}\"", path.display())]
+    WriteShallowFile {
+        path: std::path::PathBuf,
+        source: std::io::Error,
+    },
     #[error("Failed to remove .keep file at \"{}\"", path.display())]
     RemovePackKeepFile {
         path: std::path::PathBuf,
@@ -4,7 +4,7 @@ use crate::{
     bstr::BString,
     remote,
     remote::{
-        fetch::{DryRun, RefMap},
+        fetch::{DryRun, RefMap, Shallow},
         ref_map, Connection,
     },
     Progress,
@@ -115,6 +115,7 @@ where
             dry_run: DryRun::No,
             reflog_message: None,
             write_packed_refs: WritePackedRefs::Never,
+            shallow: Shallow::NoChange,
         })
     }
 }
@@ -145,6 +146,7 @@ where
     dry_run: DryRun,
     reflog_message: Option<RefLogMessage>,
     write_packed_refs: WritePackedRefs,
+    shallow: Shallow,
 }
 
 /// Builder
@@ -174,6 +176,14 @@ where
         self.reflog_message = reflog_message.into();
         self
     }
+
+    /// Define how to shorten the history of the received commits, with the default being to not change it.
+    ///
+    /// Note that this requires the remote to support shallow fetches, or else [`receive()`][Self::receive()] will fail.
+    pub fn with_shallow(mut self, shallow: Shallow) -> Self {
+        self.shallow = shallow;
+        self
+    }
 }
 
 impl<'remote, 'repo, T, P> Drop for Prepare<'remote, 'repo, T, P>
@@ -8,7 +8,7 @@ use crate::{
     remote::{
         connection::fetch::config,
         fetch,
-        fetch::{negotiate, refs, Error, Outcome, Prepare, RefLogMessage, Status},
+        fetch::{negotiate, refs, Error, Outcome, Prepare, RefLogMessage, Shallow, Status},
     },
     Progress,
 };
@@ -90,7 +90,7 @@ where
             });
         }
 
-        let reader = 'negotiation: loop {
+        let (reader, response) = 'negotiation: loop {
             progress.step();
             progress.set_name(format!("negotiate (round {})", round));
 
@@ -115,6 +115,19 @@ where
                     return Err(err.into());
                 }
             };
+            if round == 1 {
+                if let Shallow::DepthAtRemote(depth) = self.shallow {
+                    if !arguments.can_use_shallow() {
+                        git_protocol::indicate_end_of_interaction(&mut con.transport).await.ok();
+                        return Err(Error::MissingServerFeature {
+                            feature: "shallow",
+                            description: "shallow clones or fetches with a limited depth",
+                        });
+                    }
+                    // Retained across rounds, just like the wants.
+                    arguments.deepen(depth.get() as usize);
+                }
+            }
             round += 1;
             let mut reader = arguments.send(&mut con.transport, is_done).await?;
             if sideband_all {
@@ -127,7 +140,7 @@ where
                 if !sideband_all {
                     setup_remote_progress(progress, &mut reader);
                 }
-                break 'negotiation reader;
+                break 'negotiation (reader, response);
             } else {
                 previous_response = Some(response);
             }
@@ -168,6 +181,10 @@ where
             git_protocol::indicate_end_of_interaction(&mut con.transport).await.ok();
         }
 
+        if write_pack_bundle.is_some() && !response.shallow_updates().is_empty() {
+            update_shallow_file(repo, response.shallow_updates())?;
+        }
+
         let update_refs = refs::update(
             repo,
             self.reflog_message
@@ -200,6 +217,53 @@ where
     }
 }
 
+/// Apply `updates` to the `shallow` file of `repo`, which lists the commits whose parents are not present in the repository.
+fn update_shallow_file(
+    repo: &crate::Repository,
+    updates: &[git_protocol::fetch::response::ShallowUpdate],
+) -> Result<(), Error> {
+    use std::io::Write;
+
+    use git_protocol::fetch::response::ShallowUpdate;
+
+    use crate::bstr::ByteSlice;
+
+    let path = repo.common_dir().join("shallow");
+    let io_err = |source| Error::WriteShallowFile {
+        path: path.clone(),
+        source,
+    };
+    let mut shallow_commits = match std::fs::read(&path) {
+        Ok(content) => content
+            .lines()
+            .filter_map(|line| git_hash::ObjectId::from_hex(line.trim()).ok())
+            .collect(),
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => Vec::new(),
+        Err(err) => return Err(io_err(err)),
+    };
+    for update in updates {
+        match update {
+            ShallowUpdate::Shallow(id) => shallow_commits.push(*id),
+            ShallowUpdate::Unshallow(id) => shallow_commits.retain(|shallow| shallow != id),
+        }
+    }
+    shallow_commits.sort();
+    shallow_commits.dedup();
+
+    if shallow_commits.is_empty() {
+        return match std::fs::remove_file(&path) {
+            Err(err) if err.kind() != std::io::ErrorKind::NotFound => Err(io_err(err)),
+            _ => Ok(()),
+        };
+    }
+    let mut file = git_lock::File::acquire_to_update_resource(&path, git_lock::acquire::Fail::Immediately, None)?;
+    for id in shallow_commits {
+        writeln!(file, "{}", id).map_err(io_err)?;
+    }
+    file.commit().map_err(|err| io_err(err.error))?;
+    Ok(())
+}
+
 fn setup_remote_progress<P>(
     progress: &mut P,
     reader: &mut Box<dyn git_protocol::transport::client::ExtendedBufRead + Unpin + '_>,
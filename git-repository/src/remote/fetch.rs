@@ -20,6 +20,24 @@ pub(crate) enum WritePackedRefs {
     Only,
 }
 
+/// Describe how to shorten the history of commits received from the remote when cloning or fetching.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[cfg(any(feature = "blocking-network-client", feature = "async-network-client"))]
+pub enum Shallow {
+    /// Don't change the history of the repository, which means all history is received unless the repository is shallow already.
+    NoChange,
+    /// Receive only the commits that are at most `depth` commits away from the tips of the remote, with a `depth` of `1`
+    /// only receiving the tips themselves. The commits at the boundary are recorded in the `shallow` file of the repository.
+    DepthAtRemote(std::num::NonZeroU32),
+}
+
+#[cfg(any(feature = "blocking-network-client", feature = "async-network-client"))]
+impl Default for Shallow {
+    fn default() -> Self {
+        Shallow::NoChange
+    }
+}
+
 /// Information about the relationship between our refspecs, and remote references with their local counterparts.
 #[derive(Default, Debug, Clone)]
 pub struct RefMap {
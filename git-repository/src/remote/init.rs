@@ -65,7 +65,7 @@ impl<'repo> Remote<'repo> {
         git_url::parse::Error: From<E>,
     {
         let url = url.try_into().map_err(|err| Error::Url(err.into()))?;
-        let (url_alias, _) = should_rewrite_urls
+        let (url_alias, push_url_alias) = should_rewrite_urls
             .then(|| rewrite_urls(&repo.config, Some(&url), None))
             .unwrap_or(Ok((None, None)))?;
         Ok(Remote {
@@ -73,7 +73,7 @@ impl<'repo> Remote<'repo> {
             url: Some(url),
             url_alias,
             push_url: None,
-            push_url_alias: None,
+            push_url_alias,
             fetch_specs: Vec::new(),
             push_specs: Vec::new(),
             repo,
@@ -86,7 +86,11 @@ pub(crate) fn rewrite_url(
     url: Option<&git_url::Url>,
     direction: remote::Direction,
 ) -> Result<Option<git_url::Url>, Error> {
-    url.and_then(|url| config.url_rewrite().longest(url, direction))
+    let rewrite_direction = match direction {
+        remote::Direction::Fetch => git_url::rewrite::Direction::Fetch,
+        remote::Direction::Push => git_url::rewrite::Direction::Push,
+    };
+    url.and_then(|url| config.url_rewrite().rewrite_url(url, rewrite_direction))
         .map(|url| {
             git_url::parse(url.as_ref()).map_err(|err| Error::RewrittenUrlInvalid {
                 kind: match direction {
@@ -106,7 +110,7 @@ pub(crate) fn rewrite_urls(
     push_url: Option<&git_url::Url>,
 ) -> Result<(Option<git_url::Url>, Option<git_url::Url>), Error> {
     let url_alias = rewrite_url(config, url, remote::Direction::Fetch)?;
-    let push_url_alias = rewrite_url(config, push_url, remote::Direction::Push)?;
+    let push_url_alias = rewrite_url(config, push_url.or(url), remote::Direction::Push)?;
 
     Ok((url_alias, push_url_alias))
 }
@@ -69,6 +69,17 @@ impl crate::Repository {
         }
     }
 
+    /// Return true if the object with `id` exists in the object database.
+    ///
+    /// # Performance Note
+    ///
+    /// Unlike [`try_find_object(…)`][crate::Repository::try_find_object()], this only performs index lookups and doesn't
+    /// decode or allocate anything, making it suitable to check for the existence of many objects.
+    pub fn has_object(&self, id: impl AsRef<git_hash::oid>) -> bool {
+        let id = id.as_ref();
+        id == git_hash::ObjectId::empty_tree(self.object_hash()) || git_odb::Contains::contains(&self.objects, id)
+    }
+
     /// Write the given object into the object database and return its object id.
     pub fn write_object(&self, object: impl git_object::WriteTo) -> Result<Id<'_>, object::write::Error> {
         self.objects
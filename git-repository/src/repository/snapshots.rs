@@ -1,5 +1,6 @@
+use crate::bstr::{BString, ByteSlice};
+
 impl crate::Repository {
-    // TODO: tests
     /// Similar to [`open_mailmap_into()`][crate::Repository::open_mailmap_into()], but ignores all errors and returns at worst
     /// an empty mailmap, e.g. if there is no mailmap or if there were errors loading them.
     ///
@@ -11,62 +12,57 @@ impl crate::Repository {
         out
     }
 
-    // TODO: tests
-    /// Try to merge mailmaps from the following locations into `target`:
+    /// Try to merge mailmaps from the following locations into `target`, just like git does:
     ///
     /// - read the `.mailmap` file without following symlinks from the working tree, if present
-    /// - OR read `HEAD:.mailmap` if this repository is bare (i.e. has no working tree), if the `mailmap.blob` is not set.
-    /// - read the mailmap as configured in `mailmap.blob`, if set.
-    /// - read the file as configured by `mailmap.file`, following symlinks, if set.
+    /// - read the blob named by the revision spec in `mailmap.blob`, or `HEAD:.mailmap` if it is not set and this repository
+    ///   is bare (i.e. has no working tree). Specs that can't be resolved, like those of a missing file, are ignored.
+    /// - read the file as configured by `mailmap.file`, following symlinks, if set and present.
     ///
     /// Only the first error will be reported, and as many source mailmaps will be merged into `target` as possible.
     /// Parsing errors will be ignored.
     pub fn open_mailmap_into(&self, target: &mut git_mailmap::Snapshot) -> Result<(), crate::mailmap::load::Error> {
         let mut err = None::<crate::mailmap::load::Error>;
         let mut buf = Vec::new();
-        let mut blob_id = self
-            .config
-            .resolved
-            .raw_value("mailmap", None, "blob")
-            .ok()
-            .and_then(|spec| {
-                // TODO: actually resolve this as spec (once we can do that)
-                git_hash::ObjectId::from_hex(spec.as_ref())
+        if let Some(root) = self.work_dir() {
+            if let Ok(mut file) = git_features::fs::open_options_no_follow()
+                .read(true)
+                .open(root.join(".mailmap"))
+                .map_err(|e| {
+                    if e.kind() != std::io::ErrorKind::NotFound {
+                        err.get_or_insert(e.into());
+                    }
+                })
+            {
+                buf.clear();
+                std::io::copy(&mut file, &mut buf)
                     .map_err(|e| err.get_or_insert(e.into()))
-                    .ok()
-            });
-        match self.work_dir() {
-            None => {
-                // TODO: replace with ref-spec `HEAD:.mailmap` for less verbose way of getting the blob id
-                blob_id = blob_id.or_else(|| {
-                    self.head().ok().and_then(|mut head| {
-                        let commit = head.peel_to_commit_in_place().ok()?;
-                        let tree = commit.tree().ok()?;
-                        tree.lookup_entry(Some(".mailmap")).ok()?.map(|e| e.object_id())
-                    })
-                });
-            }
-            Some(root) => {
-                if let Ok(mut file) = git_features::fs::open_options_no_follow()
-                    .read(true)
-                    .open(root.join(".mailmap"))
-                    .map_err(|e| {
-                        if e.kind() != std::io::ErrorKind::NotFound {
-                            err.get_or_insert(e.into());
-                        }
-                    })
-                {
-                    buf.clear();
-                    std::io::copy(&mut file, &mut buf)
-                        .map_err(|e| err.get_or_insert(e.into()))
-                        .ok();
-                    target.merge(git_mailmap::parse_ignore_errors(&buf));
-                }
+                    .ok();
+                target.merge(git_mailmap::parse_ignore_errors(&buf));
             }
         }
 
-        if let Some(blob) = blob_id.and_then(|id| self.find_object(id).map_err(|e| err.get_or_insert(e.into())).ok()) {
-            target.merge(git_mailmap::parse_ignore_errors(&blob.data));
+        let blob_spec: Option<BString> = self
+            .config
+            .resolved
+            .string("mailmap", None, "blob")
+            .map(|spec| spec.into_owned())
+            .or_else(|| self.is_bare().then(|| "HEAD:.mailmap".into()));
+        if let Some(id) = blob_spec.and_then(|spec| self.rev_parse_single(spec.as_bstr()).ok()) {
+            match id.object() {
+                Ok(object) if object.kind == git_object::Kind::Blob => {
+                    target.merge(git_mailmap::parse_ignore_errors(&object.data));
+                }
+                Ok(object) => {
+                    err.get_or_insert(crate::mailmap::load::Error::NotABlob {
+                        id: object.id,
+                        kind: object.kind,
+                    });
+                }
+                Err(e) => {
+                    err.get_or_insert(e.into());
+                }
+            }
         }
 
         let configured_path = self
@@ -94,9 +90,15 @@ impl crate::Repository {
                 }
             });
 
-        if let Some(mut file) =
-            configured_path.and_then(|path| std::fs::File::open(path).map_err(|e| err.get_or_insert(e.into())).ok())
-        {
+        if let Some(mut file) = configured_path.and_then(|path| {
+            std::fs::File::open(path)
+                .map_err(|e| {
+                    if e.kind() != std::io::ErrorKind::NotFound {
+                        err.get_or_insert(e.into());
+                    }
+                })
+                .ok()
+        }) {
             buf.clear();
             std::io::copy(&mut file, &mut buf)
                 .map_err(|e| err.get_or_insert(e.into()))
@@ -2,9 +2,10 @@ mod access {
     use crate::Kind;
 
     impl crate::ThreadSafeRepository {
-        /// Return the kind of repository, either bare or one with a work tree.
+        /// Return the kind of repository, either bare or one with a work tree, which may be a submodule or a linked work tree.
         pub fn kind(&self) -> Kind {
             match self.work_tree {
+                Some(_) if git_discover::is_submodule_git_dir(self.git_dir()) => Kind::Submodule,
                 Some(_) => Kind::WorkTree {
                     is_linked: crate::worktree::id(self.git_dir(), self.common_dir.is_some()).is_some(),
                 },
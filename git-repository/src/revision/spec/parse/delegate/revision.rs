@@ -9,7 +9,7 @@ use git_revision::spec::parse::{
 use crate::{
     bstr::{BStr, BString, ByteSlice},
     ext::ReferenceExt,
-    object,
+    object, remote,
     revision::spec::parse::{Delegate, Error, RefsHint},
 };
 
@@ -216,11 +216,87 @@ impl<'repo> delegate::Revision for Delegate<'repo> {
         }
     }
 
-    fn sibling_branch(&mut self, _kind: SiblingBranch) -> Option<()> {
+    fn sibling_branch(&mut self, kind: SiblingBranch) -> Option<()> {
         self.unset_disambiguate_call();
-        self.err.push(Error::Planned {
-            dependency: "remote handling and ref-specs are fleshed out more",
-        });
-        None
+        if kind == SiblingBranch::Push {
+            self.err.push(Error::Planned {
+                dependency: "push.default and push ref-specs are handled",
+            });
+            return None;
+        }
+        let r = match &self.refs[self.idx] {
+            Some(r) => r.clone().attach(self.repo),
+            None => match self.repo.head() {
+                Ok(head) => match head.try_into_referent() {
+                    Some(r) => r,
+                    None => {
+                        self.err.push(Error::UpstreamOfNonBranch { name: "HEAD".into() });
+                        return None;
+                    }
+                },
+                Err(err) => {
+                    self.err.push(err.into());
+                    return None;
+                }
+            },
+        };
+        match upstream_tracking_ref_name(&r)
+            .and_then(|name| self.repo.find_reference(name.as_bstr()).map_err(Into::into))
+        {
+            Ok(upstream) => {
+                self.refs[self.idx] = Some(upstream.detach());
+                Some(())
+            }
+            Err(err) => {
+                self.err.push(err);
+                None
+            }
+        }
+    }
+}
+
+/// Return the full name of the local reference tracking the upstream branch of the local branch `r`, as configured by
+/// `branch.<name>.remote` and `branch.<name>.merge`, mapped through the fetch ref-specs of the remote.
+fn upstream_tracking_ref_name(r: &crate::Reference<'_>) -> Result<BString, Error> {
+    let branch = match r.name().category_and_short_name() {
+        Some((git_ref::Category::LocalBranch, short_name)) => short_name,
+        _ => {
+            return Err(Error::UpstreamOfNonBranch {
+                name: r.name().as_bstr().into(),
+            })
+        }
+    };
+    let no_upstream = || Error::NoUpstream { branch: branch.into() };
+    let merge = r
+        .repo
+        .branch_remote_ref(branch.to_str().map_err(|_| no_upstream())?)
+        .ok_or_else(no_upstream)??;
+    match r.remote_name(remote::Direction::Fetch).ok_or_else(no_upstream)? {
+        name if name.as_bstr() == "." => Ok(merge.as_bstr().into()),
+        _ => {
+            let remote = r.remote(remote::Direction::Fetch).ok_or_else(no_upstream)??;
+            let null = ObjectId::null(r.repo.object_hash());
+            git_refspec::MatchGroup::from_fetch_specs(
+                remote
+                    .refspecs(remote::Direction::Fetch)
+                    .iter()
+                    .map(|spec| spec.to_ref()),
+            )
+            .match_remotes(
+                Some(git_refspec::match_group::Item {
+                    full_ref_name: merge.as_bstr(),
+                    target: &null,
+                    object: None,
+                })
+                .into_iter(),
+            )
+            .mappings
+            .into_iter()
+            .find_map(|mapping| mapping.rhs.map(|name| name.into_owned()))
+            .ok_or_else(|| Error::UpstreamNotTracked {
+                branch: branch.into(),
+                remote_ref: merge.as_bstr().into(),
+            })
+        }
     }
 }
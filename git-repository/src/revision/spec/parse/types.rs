@@ -66,6 +66,16 @@ pub enum Error {
     Planned { dependency: &'static str },
     #[error("Reference {reference:?} does not have a reference log, cannot {action}")]
     MissingRefLog { reference: BString, action: &'static str },
+    #[error("{name:?} is not a local branch and thus has no upstream branch")]
+    UpstreamOfNonBranch { name: BString },
+    #[error("Branch {branch:?} has no upstream branch configured")]
+    NoUpstream { branch: BString },
+    #[error("Upstream branch {remote_ref:?} of branch {branch:?} is not stored as a remote-tracking branch")]
+    UpstreamNotTracked { branch: BString, remote_ref: BString },
+    #[error(transparent)]
+    InvalidUpstreamName(#[from] git_validate::reference::name::Error),
+    #[error(transparent)]
+    FindRemote(#[from] crate::remote::find::existing::Error),
     #[error("HEAD has {available} prior checkouts and checkout number {desired} is out of range")]
     PriorCheckoutOutOfRange { desired: usize, available: usize },
     #[error("Reference {:?} has {available} ref-log entries and entry number {desired} is out of range", reference.name.as_bstr())]
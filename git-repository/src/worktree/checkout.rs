@@ -0,0 +1,109 @@
+use std::sync::atomic::AtomicBool;
+
+use git_hash::ObjectId;
+use git_odb::FindExt;
+pub use git_worktree::index::update::{LocalModifications, Outcome};
+
+use crate::Progress;
+
+/// The error returned by [`Worktree::checkout()`][crate::Worktree::checkout()].
+#[derive(Debug, thiserror::Error)]
+#[allow(missing_docs)]
+pub enum Error {
+    #[error(transparent)]
+    FindObject(#[from] crate::object::find::existing::Error),
+    #[error("The object to checkout is not a treeish")]
+    PeelToTree(#[from] crate::object::peel::to_kind::Error),
+    #[error("Could not create index from tree at {id}")]
+    IndexFromTree {
+        id: ObjectId,
+        source: git_traverse::tree::breadthfirst::Error,
+    },
+    #[error(transparent)]
+    OpenIndex(#[from] crate::worktree::open_index::Error),
+    #[error(transparent)]
+    CheckoutOptions(#[from] crate::config::checkout_options::Error),
+    #[error(transparent)]
+    Update(
+        #[from] git_worktree::index::update::Error<git_odb::find::existing_object::Error<git_odb::store::find::Error>>,
+    ),
+    #[error(transparent)]
+    WriteIndex(#[from] git_index::file::write::Error),
+}
+
+/// Options for use in [`Worktree::checkout()`][crate::Worktree::checkout()].
+#[derive(Default, Debug, Clone, Copy)]
+pub struct Options {
+    /// What to do with files that were modified locally, or untracked files that are in the way.
+    pub local_modifications: LocalModifications,
+    /// If true, default false, neither the worktree nor the index are changed, but the returned [`Outcome`] lists
+    /// everything that would have happened.
+    pub dry_run: bool,
+}
+
+impl<'repo> crate::Worktree<'repo> {
+    /// Materialize the tree that `treeish` peels to in this worktree, writing and removing only the files that differ from
+    /// what's recorded in the current index, and write the index to match the tree afterwards.
+    ///
+    /// File modes, symbolic links and content conversions like `core.autocrlf` are handled according to the repository configuration,
+    /// whereas local modifications are handled according to `options`.
+    /// If there is no index yet, all files are written as if checking out into an empty worktree.
+    ///
+    /// Note that `HEAD` isn't changed, and that interruptions via `should_interrupt` leave the index as it was.
+    pub fn checkout(
+        &self,
+        treeish: impl Into<ObjectId>,
+        mut progress: impl Progress,
+        should_interrupt: &AtomicBool,
+        Options {
+            local_modifications,
+            dry_run,
+        }: Options,
+    ) -> Result<Outcome, Error> {
+        let repo = self.parent;
+        let tree_id = repo.find_object(treeish)?.peel_to_tree()?.id;
+        let old = match repo.open_index() {
+            Ok(index) => index.into_state(),
+            Err(crate::worktree::open_index::Error::IndexFile(git_index::file::init::Error::Io(err)))
+                if err.kind() == std::io::ErrorKind::NotFound =>
+            {
+                git_index::State::new(repo.object_hash())
+            }
+            Err(err) => return Err(err.into()),
+        };
+        let new = git_index::State::from_tree(&tree_id, |oid, buf| repo.objects.find_tree_iter(oid, buf).ok())
+            .map_err(|err| Error::IndexFromTree {
+                id: tree_id,
+                source: err,
+            })?;
+        let mut new = git_index::File::from_state(new, repo.index_path());
+
+        let mut files = progress.add_child_with_id("checkout", *b"WTCF"); /* WorkTree Checkout Files */
+        let mut bytes = progress.add_child_with_id("writing", *b"WTCB"); /* WorkTree Checkout Bytes */
+        files.init(Some(new.entries().len()), crate::progress::count("files"));
+        bytes.init(None, crate::progress::bytes());
+
+        let start = std::time::Instant::now();
+        let outcome = git_worktree::index::update(
+            &old,
+            &mut new,
+            self.path,
+            |oid, buf| repo.objects.find_blob(oid, buf),
+            &mut files,
+            &mut bytes,
+            should_interrupt,
+            git_worktree::index::update::Options {
+                checkout: repo.config.checkout_options(repo.git_dir())?,
+                local_modifications,
+                dry_run,
+            },
+        )?;
+        files.show_throughput(start);
+        bytes.show_throughput(start);
+
+        if !dry_run && !should_interrupt.load(std::sync::atomic::Ordering::Relaxed) {
+            new.write(Default::default())?;
+        }
+        Ok(outcome)
+    }
+}
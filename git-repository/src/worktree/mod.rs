@@ -69,6 +69,9 @@ pub(crate) fn id(git_dir: &std::path::Path, has_common_dir: bool) -> Option<&BSt
 ///
 pub mod proxy;
 
+///
+pub mod checkout;
+
 ///
 pub mod open_index {
     use crate::bstr::BString;
@@ -158,3 +161,24 @@ pub mod excludes {
         }
     }
 }
+
+///
+pub mod status_options {
+    pub use crate::config::checkout_options::Error;
+
+    impl<'repo> crate::Worktree<'repo> {
+        /// Collect the options to compare the files of this worktree with the index using [`index::status()`][crate::worktree::index::status()],
+        /// which includes the attributes and filters to convert files like `git add` would before hashing them.
+        pub fn status_options(&self) -> Result<git_worktree::index::status::Options, Error> {
+            let opts = self.parent.config.checkout_options(self.parent.git_dir())?;
+            Ok(git_worktree::index::status::Options {
+                fs: opts.fs,
+                check_stat: opts.check_stat,
+                trust_ctime: opts.trust_ctime,
+                thread_limit: opts.thread_limit,
+                attributes: opts.attributes,
+                filters: opts.filters,
+            })
+        }
+    }
+}
@@ -243,6 +243,35 @@ mod blocking_io {
         Ok(())
     }
 
+    #[test]
+    fn fetch_only_with_depth_records_shallow_commits() -> crate::Result {
+        let tmp = git_testtools::tempfile::TempDir::new()?;
+        let (repo, out) = git::prepare_clone_bare(remote::repo("base").path(), tmp.path())?
+            .with_shallow(git::remote::fetch::Shallow::DepthAtRemote(
+                1.try_into().expect("non-zero"),
+            ))
+            .fetch_only(git::progress::Discard, &std::sync::atomic::AtomicBool::default())?;
+
+        let shallow = std::fs::read(repo.path().join("shallow"))?;
+        let shallow_commits: Vec<_> = shallow.lines().collect();
+        assert!(!shallow_commits.is_empty(), "the tips have no parents in the clone");
+        assert!(
+            shallow_commits.windows(2).all(|ids| ids[0] < ids[1]),
+            "shallow commits are sorted and unique"
+        );
+        let tips: Vec<_> = out
+            .ref_map
+            .remote_refs
+            .iter()
+            .filter_map(|r| r.unpack().1.map(ToOwned::to_owned))
+            .collect();
+        for id in shallow_commits {
+            let id = git::hash::ObjectId::from_hex(id)?;
+            assert!(tips.contains(&id), "only tips are at the boundary with depth 1");
+        }
+        Ok(())
+    }
+
     #[test]
     fn fetch_only_without_configuration() -> crate::Result {
         let tmp = git_testtools::tempfile::TempDir::new()?;
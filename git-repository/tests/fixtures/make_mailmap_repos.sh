@@ -0,0 +1,38 @@
+#!/bin/bash
+set -eu -o pipefail
+
+git init -q main
+(cd main
+  cat <<EOF2 >.mailmap
+Committed <committed@example.com> <old@example.com>
+EOF2
+  echo "Committed <committed@example.com> <from-blob@example.com>" >.mailmap-for-blob-spec
+  git add . && git commit -q -m "c1"
+
+  echo "Worktree <worktree@example.com> <old@example.com>" >.mailmap
+  echo "From file <file@example.com> <from-file@example.com>" >../mailmap-file
+  git config mailmap.file "$PWD/../mailmap-file"
+)
+
+git clone -q --bare main bare
+
+git clone -q --bare main bare-with-blob-spec
+(cd bare-with-blob-spec
+  git config mailmap.blob HEAD:.mailmap-for-blob-spec
+)
+
+git init -q worktree-with-blob-spec
+(cd worktree-with-blob-spec
+  git fetch -q ../main main:other
+  git config mailmap.blob other:.mailmap
+)
+
+git clone -q --bare main bare-with-missing-blob
+(cd bare-with-missing-blob
+  git config mailmap.blob HEAD:does-not-exist
+)
+
+git clone -q --bare main bare-with-tree-spec
+(cd bare-with-tree-spec
+  git config mailmap.blob "HEAD^{tree}"
+)
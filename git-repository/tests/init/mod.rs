@@ -71,6 +71,58 @@ mod non_bare {
         );
         Ok(())
     }
+
+    #[test]
+    fn init_with_initial_branch_and_template_dir() -> crate::Result {
+        let tmp = tempfile::tempdir()?;
+        let template_dir = tmp.path().join("template");
+        std::fs::create_dir_all(template_dir.join("hooks"))?;
+        std::fs::write(template_dir.join("hooks").join("pre-commit"), b"#!/bin/sh\n")?;
+        std::fs::write(template_dir.join(".ignored"), b"")?;
+
+        let repo: git::Repository = git::ThreadSafeRepository::init_opts(
+            tmp.path().join("repo"),
+            git::create::Kind::WithWorktree,
+            git::create::Options {
+                initial_branch: Some("trunk".into()),
+                template_dir: Some(template_dir),
+                ..Default::default()
+            },
+            git::open::Options::isolated().config_overrides(Some("init.defaultBranch=special")),
+        )?
+        .into();
+        assert_eq!(
+            repo.head()?.referent_name().expect("name").as_bstr(),
+            "refs/heads/trunk",
+            "the initial branch overrides the configured default branch"
+        );
+        assert!(repo.git_dir().join("hooks").join("pre-commit").is_file());
+        assert!(
+            !repo.git_dir().join("hooks").join("pre-commit.sample").exists(),
+            "templates replace our built-in ones"
+        );
+        assert!(!repo.git_dir().join(".ignored").exists(), "dot-files are skipped");
+        Ok(())
+    }
+
+    #[test]
+    fn init_with_invalid_initial_branch_fails_without_creating_anything() -> crate::Result {
+        let tmp = tempfile::tempdir()?;
+        let err = git::ThreadSafeRepository::init_opts(
+            tmp.path().join("repo"),
+            git::create::Kind::WithWorktree,
+            git::create::Options {
+                initial_branch: Some("a..b".into()),
+                ..Default::default()
+            },
+            git::open::Options::isolated(),
+        )
+        .unwrap_err();
+        assert!(err.to_string().starts_with("Invalid initial branch name"));
+        assert!(!tmp.path().join("repo").exists());
+        Ok(())
+    }
+
     #[test]
     fn init_into_empty_directory_creates_a_dot_git_dir() -> crate::Result {
         let tmp = tempfile::tempdir()?;
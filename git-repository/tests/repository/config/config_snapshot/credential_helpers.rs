@@ -66,7 +66,7 @@ mod baseline {
         );
     }
 
-    fn agrees_with_inner(url: &str, ignore_expected_prompt_port: bool, lowercase_prompt_host: bool) {
+    fn agrees_with_inner(url: &str, lowercase_prompt_host: bool) {
         let repo = remote::repo("credential-helpers");
         let (cascade, mut action, prompt_options) = repo
             .config_snapshot()
@@ -104,25 +104,15 @@ mod baseline {
         let expected_prompt = lowercase_prompt_host
             .then(|| expected.prompt_url.to_ascii_lowercase())
             .unwrap_or_else(|| expected.prompt_url.to_owned());
-        if ignore_expected_prompt_port {
-            assert_eq!(
-                ctx.to_url().expect("parts complete"),
-                expected_prompt.trim_end_matches(|b: char| b == ':' || b.is_numeric())
-            );
-        } else {
-            assert_eq!(ctx.to_url().expect("parts complete"), expected_prompt);
-        }
+        assert_eq!(ctx.to_url().expect("parts complete"), expected_prompt);
     }
 
     pub fn agrees_with(url: &str) {
-        agrees_with_inner(url, false, false)
+        agrees_with_inner(url, false)
     }
 
-    pub fn agrees_with_but_drops_default_port_in_prompt(url: &str) {
-        agrees_with_inner(url, true, false)
-    }
     pub fn agrees_with_but_lowercases_scheme_and_host(url: &str) {
-        agrees_with_inner(url, false, true)
+        agrees_with_inner(url, true)
     }
 }
 
@@ -135,8 +125,8 @@ fn any_url_calls_global() {
 fn http_port_defaulting() {
     baseline::agrees_with("https://example.com");
     baseline::agrees_with("https://example.com/");
-    baseline::agrees_with_but_drops_default_port_in_prompt("https://example.com:443");
-    baseline::agrees_with_but_drops_default_port_in_prompt("https://example.com:443/");
+    baseline::agrees_with("https://example.com:443");
+    baseline::agrees_with("https://example.com:443/");
 }
 
 #[test]
@@ -168,8 +158,8 @@ fn case_sensitive_host_matching() {
 fn subdomain_globs_match_on_their_level() {
     baseline::agrees_with("http://a.example.com");
     baseline::agrees_with("http://b.example.com/path");
-    baseline::agrees_with_but_drops_default_port_in_prompt("http://c.example.com:80/path");
-    baseline::agrees_with_but_drops_default_port_in_prompt("http://a.a.example.com:80/path");
+    baseline::agrees_with("http://c.example.com:80/path");
+    baseline::agrees_with("http://a.a.example.com:80/path");
     baseline::agrees_with("http://a.b.example.com/path");
     baseline::agrees_with("http://b.a.example.com/path");
 }
@@ -179,8 +169,8 @@ fn subdomain_globs_match_on_their_level() {
 fn http_urls_match_the_host_without_path_as_well() {
     let _env = Env::new().set("GIT_ASKPASS", "foo");
     baseline::agrees_with("http://example.com:8080/other/path");
-    baseline::agrees_with_but_drops_default_port_in_prompt("http://example.com:80/");
-    baseline::agrees_with_but_drops_default_port_in_prompt("http://example.com:80");
+    baseline::agrees_with("http://example.com:80/");
+    baseline::agrees_with("http://example.com:80");
     baseline::agrees_with("http://example.com");
 }
 
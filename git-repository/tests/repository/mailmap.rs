@@ -0,0 +1,83 @@
+use git_repository as git;
+
+use crate::util::restricted;
+
+fn repo(name: &str) -> crate::util::Result<git::Repository> {
+    let dir = git_testtools::scripted_fixture_repo_read_only("make_mailmap_repos.sh")?;
+    Ok(git::open_opts(dir.join(name), restricted())?)
+}
+
+fn resolved_name(mailmap: &git::mailmap::Snapshot, email: &str) -> Option<String> {
+    mailmap
+        .try_resolve(git::actor::SignatureRef {
+            name: "Someone".into(),
+            email: email.into(),
+            time: Default::default(),
+        })
+        .map(|signature| signature.name.to_string())
+}
+
+#[test]
+fn worktree_mailmap_and_mailmap_file_are_read_but_not_the_committed_one() -> crate::util::Result {
+    let repo = repo("main")?;
+    let mut mailmap = git::mailmap::Snapshot::default();
+    repo.open_mailmap_into(&mut mailmap)?;
+    assert_eq!(resolved_name(&mailmap, "old@example.com").as_deref(), Some("Worktree"));
+    assert_eq!(
+        resolved_name(&mailmap, "from-file@example.com").as_deref(),
+        Some("From file")
+    );
+    assert_eq!(resolved_name(&mailmap, "from-blob@example.com"), None);
+    Ok(())
+}
+
+#[test]
+fn bare_repositories_read_the_mailmap_at_head() -> crate::util::Result {
+    let mailmap = repo("bare")?.open_mailmap();
+    assert_eq!(resolved_name(&mailmap, "old@example.com").as_deref(), Some("Committed"));
+    assert_eq!(resolved_name(&mailmap, "from-blob@example.com"), None);
+    Ok(())
+}
+
+#[test]
+fn mailmap_blob_is_resolved_as_revision_spec() -> crate::util::Result {
+    let mailmap = repo("bare-with-blob-spec")?.open_mailmap();
+    assert_eq!(
+        resolved_name(&mailmap, "from-blob@example.com").as_deref(),
+        Some("Committed"),
+        "the spec replaces HEAD:.mailmap"
+    );
+    assert_eq!(resolved_name(&mailmap, "old@example.com"), None);
+
+    let mailmap = repo("worktree-with-blob-spec")?.open_mailmap();
+    assert_eq!(
+        resolved_name(&mailmap, "old@example.com").as_deref(),
+        Some("Committed"),
+        "the blob is also read in repositories with a worktree"
+    );
+    Ok(())
+}
+
+#[test]
+fn mailmap_blob_specs_that_cannot_be_resolved_are_ignored() -> crate::util::Result {
+    let repo = repo("bare-with-missing-blob")?;
+    let mut mailmap = git::mailmap::Snapshot::default();
+    repo.open_mailmap_into(&mut mailmap)?;
+    assert!(mailmap.entries().is_empty());
+    Ok(())
+}
+
+#[test]
+fn mailmap_blob_specs_must_point_to_a_blob() -> crate::util::Result {
+    let repo = repo("bare-with-tree-spec")?;
+    let mut mailmap = git::mailmap::Snapshot::default();
+    let err = repo.open_mailmap_into(&mut mailmap).unwrap_err();
+    assert!(matches!(
+        err,
+        git::mailmap::load::Error::NotABlob {
+            kind: git::object::Kind::Tree,
+            ..
+        }
+    ));
+    Ok(())
+}
@@ -1,6 +1,7 @@
 use git_repository::Repository;
 
 mod config;
+mod mailmap;
 mod object;
 mod open;
 mod reference;
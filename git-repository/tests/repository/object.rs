@@ -113,6 +113,19 @@ mod find {
             repo.objects.try_find(empty_tree, &mut buf)?.is_none(),
             "the lower level has no such special case so one can determine if this object exists or not"
         );
+        assert!(repo.has_object(empty_tree), "the same special case applies here");
+        Ok(())
+    }
+
+    #[test]
+    fn has_object() -> crate::Result {
+        let repo = basic_repo()?;
+        for commit_id in repo.head_id()?.ancestors().all()? {
+            let commit_id = commit_id?;
+            assert!(repo.has_object(&commit_id));
+            assert!(repo.has_object(commit_id.object()?.into_commit().tree_id()?));
+        }
+        assert!(!repo.has_object(git::hash::ObjectId::null(repo.object_hash())));
         Ok(())
     }
 }
@@ -18,7 +18,7 @@ mod submodules {
                 submodule_m1_gitdir.clone(),
             ] {
                 let repo = discover_repo(discover_dir).unwrap();
-                // assert_eq!(repo.kind(), git::Kind::Submodule);
+                assert_eq!(repo.kind(), git::Kind::Submodule);
                 assert_eq!(repo.work_dir().expect("non-bare"), dir.join(&submodule_m1_workdir));
                 assert_eq!(repo.git_dir(), dir.join(&submodule_m1_gitdir));
 
@@ -33,14 +33,43 @@ mod submodules {
     fn discover_repo(name: impl AsRef<Path>) -> crate::Result<git::Repository> {
         let dir = git_testtools::scripted_fixture_repo_read_only("make_submodules.sh")?;
         let repo_dir = dir.join(name);
-        Ok(git::ThreadSafeRepository::discover_opts(
+        let repo = git::ThreadSafeRepository::discover_opts(
             repo_dir,
             Default::default(),
             git_sec::trust::Mapping {
                 full: crate::restricted(),
                 reduced: crate::restricted(),
             },
+        )?;
+        assert_eq!(repo.kind(), git::Kind::Submodule);
+        Ok(repo.to_thread_local())
+    }
+}
+
+mod environment_overrides {
+    use git_repository as git;
+    use serial_test::serial;
+
+    #[test]
+    #[serial]
+    fn work_tree_replaces_the_one_of_the_discovered_repository() -> crate::Result {
+        let dir = git_testtools::scripted_fixture_repo_read_only("make_basic_repo.sh")?;
+        let work_tree = git_testtools::tempfile::TempDir::new()?;
+        let _env = git_testtools::Env::new()
+            .unset("GIT_DIR")
+            .set("GIT_WORK_TREE", work_tree.path().to_str().expect("valid UTF-8"));
+
+        let repo = git::ThreadSafeRepository::discover_with_environment_overrides_opts(
+            &dir,
+            Default::default(),
+            git_sec::trust::Mapping {
+                full: crate::restricted(),
+                reduced: crate::restricted(),
+            },
         )?
-        .to_thread_local())
+        .to_thread_local();
+        assert_eq!(repo.work_dir(), Some(work_tree.path()));
+        assert_eq!(repo.git_dir(), dir.join(".git"));
+        Ok(())
     }
 }
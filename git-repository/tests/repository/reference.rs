@@ -183,6 +183,63 @@ mod iter_references {
         );
         Ok(())
     }
+
+    #[test]
+    fn tags_and_branches() -> crate::Result {
+        let repo = repo()?;
+        let names = |iter: git::reference::iter::Iter<'_>| {
+            iter.filter_map(Result::ok)
+                .map(|r| r.name().as_bstr().to_string())
+                .collect::<Vec<_>>()
+        };
+        let platform = repo.references()?;
+        assert_eq!(
+            names(platform.tags()?),
+            ["refs/tags/dt1", "refs/tags/multi-link-target2", "refs/tags/t1"]
+        );
+        assert_eq!(
+            names(platform.local_branches()?),
+            [
+                "refs/heads/d1",
+                "refs/heads/dt1",
+                "refs/heads/main",
+                "refs/heads/multi-link-target1"
+            ]
+        );
+        assert_eq!(
+            names(platform.remote_branches()?),
+            [
+                "refs/remotes/origin/HEAD",
+                "refs/remotes/origin/main",
+                "refs/remotes/origin/multi-link-target3"
+            ]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn tags_peeled() -> crate::Result {
+        let repo = repo()?;
+        let commit = hex_to_id("134385f6d781b7e97062102c6a483440bfda2a03");
+        assert_eq!(
+            repo.references()?
+                .tags()?
+                .peeled()
+                .filter_map(Result::ok)
+                .map(|r| (
+                    r.name().as_bstr().to_string(),
+                    r.target().try_id().map(ToOwned::to_owned)
+                ))
+                .collect::<Vec<_>>(),
+            vec![
+                ("refs/tags/dt1".to_string(), Some(commit)),
+                ("refs/remotes/origin/multi-link-target3".into(), Some(commit)),
+                ("refs/tags/t1".into(), Some(commit)),
+            ],
+            "annotated tags are peeled to the object they point to, and symbolic refs are followed"
+        );
+        Ok(())
+    }
 }
 
 mod head {
@@ -16,7 +16,7 @@ mod remote_at {
         let mut remote = remote.push_url("user@host.xz:./relative")?;
         assert_eq!(
             remote.url(Direction::Push).unwrap().to_bstring(),
-            "user@host.xz:relative"
+            "user@host.xz:./relative"
         );
         assert_eq!(remote.url(Direction::Fetch).unwrap().to_bstring(), fetch_url);
 
@@ -66,6 +66,18 @@ mod remote_at {
             "ssh://dev/null",
             "push-url rewrite rules are applied"
         );
+
+        let remote = repo.remote_at("ssh://example.com/gitoxide")?;
+        assert_eq!(
+            remote.url(Direction::Fetch).unwrap().to_bstring(),
+            "ssh://example.com/gitoxide",
+            "no fetch rule matches"
+        );
+        assert_eq!(
+            remote.url(Direction::Push).unwrap().to_bstring(),
+            "https://github.com/byron/gitoxide",
+            "push-url rewrite rules apply to the fetch url if there is no push url, like in git"
+        );
         Ok(())
     }
 
@@ -304,3 +304,66 @@ fn run_assertions(main_repo: git::Repository, should_be_bare: bool) {
         );
     }
 }
+
+mod checkout {
+    use std::sync::atomic::AtomicBool;
+
+    use git_repository as git;
+
+    #[test]
+    fn to_parent_commit_and_back() -> crate::Result {
+        let (repo, _keep) = crate::repo_rw("make_basic_repo.sh")?;
+        let worktree = repo.worktree().expect("not bare");
+        let file = worktree.base().join("this");
+        let head_id = repo.head_id()?.detach();
+        let parent_id = repo.head_commit()?.parent_ids().next().expect("one parent").detach();
+
+        let outcome = worktree.checkout(
+            parent_id,
+            git::progress::Discard,
+            &AtomicBool::default(),
+            Default::default(),
+        )?;
+        assert_eq!(outcome.updated, vec!["this"]);
+        assert!(outcome.removed.is_empty());
+        assert_eq!(std::fs::read(&file)?, b"", "the file was emptied in the parent commit");
+
+        let index = repo.open_index()?;
+        assert_eq!(index.entries().len(), 1);
+        assert_eq!(
+            index.entry(0).id,
+            git_testtools::hex_to_id("e69de29bb2d1d6434b8b29ae775ad8c2e48c5391"),
+            "the index matches the checked out tree"
+        );
+        assert_eq!(repo.head_id()?, head_id, "HEAD isn't changed");
+
+        let outcome = worktree.checkout(
+            head_id,
+            git::progress::Discard,
+            &AtomicBool::default(),
+            Default::default(),
+        )?;
+        assert_eq!(outcome.updated, vec!["this"]);
+        assert_eq!(std::fs::read(&file)?, b"hello\n");
+        Ok(())
+    }
+
+    #[test]
+    fn local_modifications_are_kept_by_default() -> crate::Result {
+        let (repo, _keep) = crate::repo_rw("make_basic_repo.sh")?;
+        let worktree = repo.worktree().expect("not bare");
+        let file = worktree.base().join("this");
+        std::fs::write(&file, b"local change")?;
+        let parent_id = repo.head_commit()?.parent_ids().next().expect("one parent").detach();
+
+        let outcome = worktree.checkout(
+            parent_id,
+            git::progress::Discard,
+            &AtomicBool::default(),
+            Default::default(),
+        )?;
+        assert_eq!(outcome.kept, vec!["this"]);
+        assert_eq!(std::fs::read(&file)?, b"local change");
+        Ok(())
+    }
+}
@@ -12,21 +12,77 @@ mod traverse;
 mod peel;
 
 mod sibling_branch {
+    use git_repository as git;
     use git_repository::revision::spec::parse::Error;
 
     use crate::revision::spec::from_bytes::{parse_spec_no_baseline, repo};
 
+    fn repo_with_config(values: &[&str]) -> git::Repository {
+        let base = git_testtools::scripted_fixture_repo_read_only("make_rev_spec_parse_repos.sh").unwrap();
+        git::open_opts(
+            base.join("complex_graph"),
+            git::open::Options::isolated().config_overrides(values.iter().copied()),
+        )
+        .unwrap()
+    }
+
     #[test]
-    fn is_planned_and_delayed_until_remotes_are_sorted() {
+    fn push_is_planned_and_delayed_until_push_defaults_are_handled() {
         let repo = repo("complex_graph").unwrap();
         assert!(matches!(
             parse_spec_no_baseline("main@{push}", &repo).unwrap_err(),
             Error::Planned { .. }
         ));
+    }
 
+    #[test]
+    fn upstream_needs_to_be_configured() {
+        let repo = repo("complex_graph").unwrap();
         assert!(matches!(
             parse_spec_no_baseline("main@{upstream}", &repo).unwrap_err(),
-            Error::Planned { .. }
+            Error::NoUpstream { .. }
+        ));
+        assert!(matches!(
+            parse_spec_no_baseline("i-tag@{u}", &repo).unwrap_err(),
+            Error::UpstreamOfNonBranch { .. }
+        ));
+    }
+
+    #[test]
+    fn upstream_of_local_remote_is_the_merge_branch() {
+        let repo = repo_with_config(&["branch.main.remote=.", "branch.main.merge=refs/heads/g"]);
+        let spec = parse_spec_no_baseline("main@{u}", &repo).unwrap();
+        assert_eq!(spec.first_reference().expect("set").name.as_bstr(), "refs/heads/g");
+        assert_eq!(
+            spec.single().expect("one").detach(),
+            repo.rev_parse_single("g").unwrap().detach()
+        );
+    }
+
+    #[test]
+    fn upstream_of_remote_is_mapped_through_its_fetch_specs() {
+        let repo = repo_with_config(&[
+            "remote.origin.url=https://example.com/repo.git",
+            "remote.origin.fetch=+refs/heads/h:refs/heads/d",
+            "branch.main.remote=origin",
+            "branch.main.merge=refs/heads/h",
+        ]);
+        let spec = parse_spec_no_baseline("main@{upstream}", &repo).unwrap();
+        assert_eq!(
+            spec.first_reference().expect("set").name.as_bstr(),
+            "refs/heads/d",
+            "the local tracking branch is the destination of the fetch spec"
+        );
+
+        let repo = repo_with_config(&[
+            "remote.origin.url=https://example.com/repo.git",
+            "remote.origin.fetch=+refs/heads/other:refs/heads/d",
+            "branch.main.remote=origin",
+            "branch.main.merge=refs/heads/h",
+        ]);
+        assert!(matches!(
+            parse_spec_no_baseline("main@{upstream}", &repo).unwrap_err(),
+            Error::UpstreamNotTracked { .. }
         ));
     }
 }
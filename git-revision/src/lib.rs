@@ -15,6 +15,14 @@ pub use hash_hasher;
 pub mod describe;
 pub use describe::function::describe;
 
+///
+pub mod merge_base;
+pub use merge_base::function::{merge_base, merge_base_all, merge_base_octopus, merge_base_with_commit_graph};
+
+///
+pub mod name_rev;
+pub use name_rev::function::name_rev;
+
 ///
 pub mod spec;
 
@@ -0,0 +1,300 @@
+/// The error returned by the [`merge_base()`][function::merge_base()] family of functions.
+#[derive(Debug, thiserror::Error)]
+#[allow(missing_docs)]
+pub enum Error<E>
+where
+    E: std::error::Error + Send + Sync + 'static,
+{
+    #[error("Commit {} could not be found during graph traversal", .oid.to_hex())]
+    Find {
+        #[source]
+        err: Option<E>,
+        oid: git_hash::ObjectId,
+    },
+    #[error("A commit could not be decoded during traversal")]
+    Decode(#[from] git_object::decode::Error),
+}
+
+type Flags = u8;
+/// The commit is reachable from the first commit.
+const PARENT1: Flags = 1 << 0;
+/// The commit is reachable from any of the other commits.
+const PARENT2: Flags = 1 << 1;
+/// The commit is an ancestor of a merge-base we already found, and isn't interesting anymore.
+const STALE: Flags = 1 << 2;
+/// The commit was already added to the result set.
+const RESULT: Flags = 1 << 3;
+
+pub(crate) mod function {
+    use std::collections::BinaryHeap;
+
+    use git_hash::{oid, ObjectId};
+    use git_object::CommitRefIter;
+
+    use super::{Error, Flags, PARENT1, PARENT2, RESULT, STALE};
+
+    type CommitTime = u32;
+
+    /// Given a `first` commit and a `second` one, find the best common ancestor between them, or `None` if they don't share any history.
+    ///
+    /// A best common ancestor is one that isn't reachable from any other common ancestor, and if there are multiple of these,
+    /// the one with the most recent commit time is returned, similar to what `git merge-base first second` would do.
+    ///
+    /// `find` is used to lookup commits by id, and commits that it can't find while traversing the history are skipped, which is
+    /// common in shallow repositories. If `first` or `second` can't be found however, an error is returned.
+    pub fn merge_base<Find, E>(first: &oid, second: &oid, find: Find) -> Result<Option<ObjectId>, Error<E>>
+    where
+        Find: for<'b> FnMut(&oid, &'b mut Vec<u8>) -> Result<Option<CommitRefIter<'b>>, E>,
+        E: std::error::Error + Send + Sync + 'static,
+    {
+        merge_base_with_commit_graph(first, second, None, find)
+    }
+
+    /// Like [`merge_base()`], but uses the generation numbers stored in `commit_graph` to know when to stop traversing the
+    /// history while discarding common ancestors that are reachable from other common ancestors.
+    ///
+    /// Without generation numbers, commit times are used for this, which may yield incorrect results if clocks were skewed.
+    /// The same happens for commits that aren't contained in `commit_graph`.
+    pub fn merge_base_with_commit_graph<Find, E>(
+        first: &oid,
+        second: &oid,
+        commit_graph: Option<&git_commitgraph::Graph>,
+        find: Find,
+    ) -> Result<Option<ObjectId>, Error<E>>
+    where
+        Find: for<'b> FnMut(&oid, &'b mut Vec<u8>) -> Result<Option<CommitRefIter<'b>>, E>,
+        E: std::error::Error + Send + Sync + 'static,
+    {
+        Ok(merge_base_all(first, &[second.to_owned()], commit_graph, find)?
+            .into_iter()
+            .next())
+    }
+
+    /// Return all best common ancestors between `first` and any of `others`, sorted by commit time with the most recent first,
+    /// similar to what `git merge-base --all first others…` would do.
+    ///
+    /// Best common ancestors are those that aren't reachable from any other common ancestor, and there can be more than one
+    /// if there are criss-cross merges. If `others` contains more than one commit, the common ancestors are computed as
+    /// if `others` were merged into a single commit first.
+    ///
+    /// The generation numbers in `commit_graph` are used if available, see [`merge_base_with_commit_graph()`] for details.
+    pub fn merge_base_all<Find, E>(
+        first: &oid,
+        others: &[ObjectId],
+        commit_graph: Option<&git_commitgraph::Graph>,
+        find: Find,
+    ) -> Result<Vec<ObjectId>, Error<E>>
+    where
+        Find: for<'b> FnMut(&oid, &'b mut Vec<u8>) -> Result<Option<CommitRefIter<'b>>, E>,
+        E: std::error::Error + Send + Sync + 'static,
+    {
+        if others.iter().any(|other| other.as_ref() == first) {
+            return Ok(vec![first.to_owned()]);
+        }
+        let mut graph = Graph::new(find, commit_graph);
+        let bases = graph.paint_down_to_common(first, others)?;
+        graph.remove_redundant(bases)
+    }
+
+    /// Return the best common ancestors of all `commits` for use in an octopus merge of all of them, similar to what
+    /// `git merge-base --octopus --all commits…` would do.
+    ///
+    /// Unlike [`merge_base_all()`], the result is reachable from each of the `commits`. The result is empty if `commits` is
+    /// empty or if any of them doesn't share any history with the others, and it's the commit itself if there is only one.
+    ///
+    /// The generation numbers in `commit_graph` are used if available, see [`merge_base_with_commit_graph()`] for details.
+    pub fn merge_base_octopus<Find, E>(
+        commits: &[ObjectId],
+        commit_graph: Option<&git_commitgraph::Graph>,
+        mut find: Find,
+    ) -> Result<Vec<ObjectId>, Error<E>>
+    where
+        Find: for<'b> FnMut(&oid, &'b mut Vec<u8>) -> Result<Option<CommitRefIter<'b>>, E>,
+        E: std::error::Error + Send + Sync + 'static,
+    {
+        let (first, rest) = match commits.split_first() {
+            Some(split) => split,
+            None => return Ok(Vec::new()),
+        };
+        let mut bases = vec![*first];
+        for commit in rest {
+            let mut next_bases = Vec::new();
+            for base in &bases {
+                for next_base in merge_base_all(commit, &[*base], commit_graph, &mut find)? {
+                    if !next_bases.contains(&next_base) {
+                        next_bases.push(next_base);
+                    }
+                }
+            }
+            bases = next_bases;
+            if bases.is_empty() {
+                break;
+            }
+        }
+        Ok(bases)
+    }
+
+    /// A minimal commit graph which lazily loads commits to keep track of their flags and commit times.
+    struct Graph<'graph, Find> {
+        find: Find,
+        commit_graph: Option<&'graph git_commitgraph::Graph>,
+        buf: Vec<u8>,
+        parent_buf: Vec<u8>,
+        flags: hash_hasher::HashedMap<ObjectId, (Flags, CommitTime)>,
+    }
+
+    impl<'graph, Find, E> Graph<'graph, Find>
+    where
+        Find: for<'b> FnMut(&oid, &'b mut Vec<u8>) -> Result<Option<CommitRefIter<'b>>, E>,
+        E: std::error::Error + Send + Sync + 'static,
+    {
+        fn new(find: Find, commit_graph: Option<&'graph git_commitgraph::Graph>) -> Self {
+            Graph {
+                find,
+                commit_graph,
+                buf: Vec::new(),
+                parent_buf: Vec::new(),
+                flags: Default::default(),
+            }
+        }
+
+        fn commit_time_of_existing(&mut self, id: &oid) -> Result<CommitTime, Error<E>> {
+            let commit = (self.find)(id, &mut self.buf)
+                .map_err(|err| Error::Find {
+                    err: Some(err),
+                    oid: id.to_owned(),
+                })?
+                .ok_or_else(|| Error::Find {
+                    err: None,
+                    oid: id.to_owned(),
+                })?;
+            Ok(commit.committer()?.time.seconds_since_unix_epoch)
+        }
+
+        /// Call `cb(parent_id, parent_commit_time)` for each parent of `id` that can be found.
+        fn for_each_parent(
+            &mut self,
+            id: &oid,
+            mut cb: impl FnMut(ObjectId, CommitTime, &mut hash_hasher::HashedMap<ObjectId, (Flags, CommitTime)>),
+        ) -> Result<(), Error<E>> {
+            let commit = (self.find)(id, &mut self.buf)
+                .map_err(|err| Error::Find {
+                    err: Some(err),
+                    oid: id.to_owned(),
+                })?
+                .ok_or_else(|| Error::Find {
+                    err: None,
+                    oid: id.to_owned(),
+                })?;
+            for token in commit {
+                match token {
+                    Ok(git_object::commit::ref_iter::Token::Tree { .. }) => continue,
+                    Ok(git_object::commit::ref_iter::Token::Parent { id: parent_id }) => {
+                        let parent_time = match self.flags.get(&parent_id) {
+                            Some((_, time)) => *time,
+                            None => match (self.find)(&parent_id, &mut self.parent_buf).map_err(|err| Error::Find {
+                                err: Some(err),
+                                oid: parent_id,
+                            })? {
+                                Some(parent) => parent.committer()?.time.seconds_since_unix_epoch,
+                                None => continue, // skip missing objects, they don't exist.
+                            },
+                        };
+                        cb(parent_id, parent_time, &mut self.flags);
+                    }
+                    Ok(_unused_token) => break,
+                    Err(err) => return Err(err.into()),
+                }
+            }
+            Ok(())
+        }
+
+        fn paint_down_to_common(&mut self, first: &oid, others: &[ObjectId]) -> Result<Vec<ObjectId>, Error<E>> {
+            let mut queue = BinaryHeap::<(CommitTime, ObjectId)>::new();
+            let time = self.commit_time_of_existing(first)?;
+            self.flags.insert(first.to_owned(), (PARENT1, time));
+            queue.push((time, first.to_owned()));
+            for other in others {
+                let time = self.commit_time_of_existing(other)?;
+                self.flags.entry(*other).or_insert((0, time)).0 |= PARENT2;
+                queue.push((time, *other));
+            }
+
+            let mut out = Vec::new();
+            while queue.iter().any(|(_, id)| self.flags[id].0 & STALE == 0) {
+                let (time, id) = queue.pop().expect("at least one non-stale item");
+                let commit_flags = self.flags.get_mut(&id).expect("queued commits are tracked");
+                let mut flags = commit_flags.0 & (PARENT1 | PARENT2 | STALE);
+                if flags == (PARENT1 | PARENT2) {
+                    if commit_flags.0 & RESULT == 0 {
+                        commit_flags.0 |= RESULT;
+                        out.push((time, id));
+                    }
+                    flags |= STALE;
+                }
+                self.for_each_parent(&id, |parent_id, parent_time, graph| {
+                    let parent_flags = &mut graph.entry(parent_id).or_insert((0, parent_time)).0;
+                    if *parent_flags & flags == flags {
+                        return;
+                    }
+                    *parent_flags |= flags;
+                    queue.push((parent_time, parent_id));
+                })?;
+            }
+            out.sort_by(|a, b| a.0.cmp(&b.0).reverse());
+            Ok(out.into_iter().map(|(_, id)| id).collect())
+        }
+
+        fn generation(&self, id: &oid) -> Option<u32> {
+            generation_in(self.commit_graph, id)
+        }
+
+        /// Remove all `bases` that are reachable from any other of the `bases`, keeping their order.
+        fn remove_redundant(&mut self, mut bases: Vec<ObjectId>) -> Result<Vec<ObjectId>, Error<E>> {
+            if bases.len() < 2 {
+                return Ok(bases);
+            }
+            let mut redundant = vec![false; bases.len()];
+            for (idx, base) in bases.iter().enumerate() {
+                if redundant[idx] {
+                    continue;
+                }
+                let base_time = self.flags[base].1;
+                let base_generation = self.generation(base);
+                let mut seen = hash_hasher::HashedSet::<ObjectId>::default();
+                let mut queue: Vec<_> = bases
+                    .iter()
+                    .enumerate()
+                    .filter(|(other_idx, _)| *other_idx != idx && !redundant[*other_idx])
+                    .map(|(_, id)| *id)
+                    .collect();
+                while let Some(id) = queue.pop() {
+                    if &id == base {
+                        redundant[idx] = true;
+                        break;
+                    }
+                    let commit_graph = self.commit_graph;
+                    self.for_each_parent(&id, |parent_id, parent_time, _| {
+                        // Ancestors of commits with a lower generation or commit time than `base` can't lead to it.
+                        let may_lead_to_base = match (base_generation, generation_in(commit_graph, &parent_id)) {
+                            (Some(base_generation), Some(generation)) => generation >= base_generation,
+                            _ => parent_time >= base_time,
+                        };
+                        if may_lead_to_base && seen.insert(parent_id) {
+                            queue.push(parent_id);
+                        }
+                    })?;
+                }
+            }
+            let mut redundant = redundant.into_iter();
+            bases.retain(|_| !redundant.next().expect("same length"));
+            Ok(bases)
+        }
+    }
+
+    /// Return the generation number of `id` if it's contained in `commit_graph` and if it's usable.
+    fn generation_in(commit_graph: Option<&git_commitgraph::Graph>, id: &oid) -> Option<u32> {
+        Some(commit_graph?.commit_by_id(id)?.generation())
+            .filter(|gen| *gen != 0 && *gen != git_commitgraph::GENERATION_NUMBER_INFINITY)
+    }
+}
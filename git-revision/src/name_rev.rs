@@ -0,0 +1,353 @@
+use std::borrow::Cow;
+
+use bstr::{BStr, BString};
+
+/// The kind of reference a [`Tip`] was obtained from, which affects which names are preferred.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum TipKind {
+    /// An annotated tag that was created at `time`, in seconds since the unix epoch, by its tagger.
+    AnnotatedTag {
+        /// The time the tag was created at.
+        time: u32,
+    },
+    /// A tag that points to a commit directly.
+    LightweightTag,
+    /// Any other reference, like a branch.
+    Other,
+}
+
+/// A named commit from which other commits in its past are named, like a tag.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Tip<'name> {
+    /// The name to use for commits in the past of `id`, like `v1.2` for the tag `refs/tags/v1.2`.
+    pub name: Cow<'name, BStr>,
+    /// The commit the reference points to, after peeling annotated tags.
+    pub id: git_hash::ObjectId,
+    /// The kind of reference this tip was created from.
+    pub kind: TipKind,
+}
+
+/// The options required to call [`name_rev()`][function::name_rev()].
+#[derive(Debug, Clone)]
+pub struct Options<'name> {
+    /// The tips to name commits relative to, typically tags.
+    pub tips: Vec<Tip<'name>>,
+    /// If `false`, tips of kind [`TipKind::LightweightTag`] are ignored, similar to `git describe --contains` without `--tags`
+    /// as opposed to `git name-rev --tags`. Default: true.
+    pub lightweight_tags: bool,
+}
+
+impl<'name> Default for Options<'name> {
+    fn default() -> Self {
+        Options {
+            tips: Vec::new(),
+            lightweight_tags: true,
+        }
+    }
+}
+
+/// The positive result produced by [`name_rev()`][function::name_rev()].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Outcome {
+    /// The name of the commit relative to a tip in its future, like `v1.2~3^2`, or `v1.2^0` if the commit is pointed to
+    /// by an annotated tag directly.
+    pub name: BString,
+    /// The id of the commit that was named.
+    pub id: git_hash::ObjectId,
+    /// The amount of commits we traversed.
+    pub commits_seen: u32,
+}
+
+/// The error returned by the [`name_rev()`][function::name_rev()] function.
+#[derive(Debug, thiserror::Error)]
+#[allow(missing_docs)]
+pub enum Error<E>
+where
+    E: std::error::Error + Send + Sync + 'static,
+{
+    #[error("Commit {} could not be found during graph traversal", .oid.to_hex())]
+    Find {
+        #[source]
+        err: Option<E>,
+        oid: git_hash::ObjectId,
+    },
+    #[error("A commit could not be decoded during traversal")]
+    Decode(#[from] git_object::decode::Error),
+}
+
+pub(crate) mod function {
+    use std::{cmp::Ordering, rc::Rc};
+
+    use bstr::{BString, ByteVec};
+    use git_hash::{oid, ObjectId};
+    use git_object::CommitRefIter;
+
+    use super::{Error, Options, Outcome, TipKind};
+
+    /// Only commits that are at most this much older than the commit to name are traversed, to allow for some clock skew.
+    const CUTOFF_DATE_SLOP: u32 = 24 * 60 * 60;
+    /// The distance added when following a parent other than the first one, to prefer names along first parents.
+    const MERGE_TRAVERSAL_WEIGHT: u32 = 65535;
+
+    /// Name `commit` relative to the closest of the `tips` in its future, similar to `git name-rev` and `git describe --contains`,
+    /// or return `None` if `commit` isn't reachable from any of the `tips`.
+    ///
+    /// Names are formed by following first parents from the tip with `~<n>`, like `v1.2~3`, and other parents with `^<n>`, like
+    /// `v1.2~3^2`, preferring paths along first parents. Names based on tags are preferred over other names, and among tags
+    /// the oldest one is chosen even if it's farther away. Annotated tags use the time of tagging for this, and other tips
+    /// use the time of the commit they point to.
+    ///
+    /// `find` is used to lookup commits by id, and commits that it can't find while traversing the history are skipped, which is
+    /// common in shallow repositories. If `commit` can't be found however, an error is returned.
+    pub fn name_rev<Find, E>(
+        commit: &oid,
+        mut find: Find,
+        Options { tips, lightweight_tags }: Options<'_>,
+    ) -> Result<Option<Outcome>, Error<E>>
+    where
+        Find: for<'b> FnMut(&oid, &'b mut Vec<u8>) -> Result<Option<CommitRefIter<'b>>, E>,
+        E: std::error::Error + Send + Sync + 'static,
+    {
+        let mut buf = Vec::new();
+        let commit_time = find(commit, &mut buf)
+            .map_err(|err| Error::Find {
+                err: Some(err),
+                oid: commit.to_owned(),
+            })?
+            .ok_or_else(|| Error::Find {
+                err: None,
+                oid: commit.to_owned(),
+            })?
+            .committer()?
+            .time
+            .seconds_since_unix_epoch;
+        let cutoff = commit_time.saturating_sub(CUTOFF_DATE_SLOP);
+
+        let mut graph = Graph {
+            find,
+            buf,
+            commits: Default::default(),
+            commits_seen: 0,
+        };
+        let mut tips: Vec<_> = tips
+            .into_iter()
+            .filter(|tip| lightweight_tags || tip.kind != TipKind::LightweightTag)
+            .filter_map(|tip| {
+                graph
+                    .commit(&tip.id)
+                    .transpose()
+                    .map(|info| info.map(|info| (tip, info.time)))
+            })
+            .collect::<Result<_, _>>()?;
+        // Tags first, then older tips first, so that ties are won by the preferred tips.
+        tips.sort_by(|(a, a_commit_time), (b, b_commit_time)| {
+            let from_tag = |kind: TipKind| kind != TipKind::Other;
+            from_tag(b.kind)
+                .cmp(&from_tag(a.kind))
+                .then_with(|| tip_time(a.kind, *a_commit_time).cmp(&tip_time(b.kind, *b_commit_time)))
+        });
+
+        for (tip, tip_commit_time) in tips {
+            if tip_commit_time < cutoff {
+                continue;
+            }
+            let mut tip_name: BString = tip.name.into_owned();
+            if let TipKind::AnnotatedTag { .. } = tip.kind {
+                tip_name.push_str("^0");
+            }
+            let start = Name {
+                tip_name: Rc::new(tip_name),
+                tip_time: tip_time(tip.kind, tip_commit_time),
+                generation: 0,
+                distance: 0,
+                from_tag: tip.kind != TipKind::Other,
+            };
+            if !graph.set_name_if_better(tip.id, start) {
+                continue;
+            }
+
+            let mut stack = vec![tip.id];
+            while let Some(id) = stack.pop() {
+                let name = graph.commits[&id]
+                    .name
+                    .clone()
+                    .expect("names are set before commits are queued");
+                let parents = match graph.commit(&id)? {
+                    Some(info) => info.parents.clone(),
+                    None => continue,
+                };
+                let mut parents_to_visit = Vec::new();
+                for (parent_index, parent_id) in parents.iter().enumerate() {
+                    let parent_time = match graph.commit(parent_id)? {
+                        Some(info) => info.time,
+                        None => continue,
+                    };
+                    if parent_time < cutoff {
+                        continue;
+                    }
+                    let parent_name = if parent_index == 0 {
+                        Name {
+                            generation: name.generation + 1,
+                            distance: name.distance.saturating_add(1),
+                            ..name.clone()
+                        }
+                    } else {
+                        let mut tip_name: BString = strip_peel_suffix(name.tip_name.as_ref()).into();
+                        if name.generation > 0 {
+                            tip_name.push_str(format!("~{}", name.generation));
+                        }
+                        tip_name.push_str(format!("^{}", parent_index + 1));
+                        Name {
+                            tip_name: Rc::new(tip_name),
+                            generation: 0,
+                            distance: name.distance.saturating_add(MERGE_TRAVERSAL_WEIGHT),
+                            ..name.clone()
+                        }
+                    };
+                    if graph.set_name_if_better(*parent_id, parent_name) {
+                        parents_to_visit.push(*parent_id);
+                    }
+                }
+                // Visit the first parent first.
+                stack.extend(parents_to_visit.into_iter().rev());
+            }
+        }
+
+        let commits_seen = graph.commits_seen;
+        Ok(graph
+            .commits
+            .remove(commit)
+            .and_then(|info| info.name)
+            .map(|name| Outcome {
+                name: name.to_bstring(),
+                id: commit.to_owned(),
+                commits_seen,
+            }))
+    }
+
+    fn tip_time(kind: TipKind, commit_time: u32) -> u32 {
+        match kind {
+            TipKind::AnnotatedTag { time } => time,
+            TipKind::LightweightTag | TipKind::Other => commit_time,
+        }
+    }
+
+    fn strip_peel_suffix(name: &[u8]) -> &[u8] {
+        match name.strip_suffix(b"^0") {
+            Some(stripped) if !stripped.is_empty() => stripped,
+            _ => name,
+        }
+    }
+
+    #[derive(Debug, Clone)]
+    struct Name {
+        /// The name of the tip, or of the merge commit after which we follow first parents.
+        tip_name: Rc<BString>,
+        tip_time: u32,
+        /// The amount of first parents followed from the commit with `tip_name`.
+        generation: u32,
+        /// The accumulated distance to the tip, with other than first parents contributing heavily.
+        distance: u32,
+        from_tag: bool,
+    }
+
+    impl Name {
+        fn is_better_than(&self, other: &Name) -> bool {
+            // Prefer names based on older tags, even if they are farther away.
+            if self.from_tag && other.from_tag {
+                return match other.tip_time.cmp(&self.tip_time) {
+                    Ordering::Greater => true,
+                    Ordering::Equal => other.distance > self.distance,
+                    Ordering::Less => false,
+                };
+            }
+            if self.from_tag != other.from_tag {
+                return self.from_tag;
+            }
+            if self.distance != other.distance {
+                return other.distance > self.distance;
+            }
+            other.tip_time > self.tip_time
+        }
+
+        fn to_bstring(&self) -> BString {
+            if self.generation == 0 {
+                return self.tip_name.as_ref().clone();
+            }
+            let mut name: BString = strip_peel_suffix(self.tip_name.as_ref()).into();
+            name.push_str(format!("~{}", self.generation));
+            name
+        }
+    }
+
+    struct CommitInfo {
+        time: u32,
+        parents: Rc<[ObjectId]>,
+        name: Option<Name>,
+    }
+
+    struct Graph<Find> {
+        find: Find,
+        buf: Vec<u8>,
+        commits: hash_hasher::HashedMap<ObjectId, CommitInfo>,
+        commits_seen: u32,
+    }
+
+    impl<Find, E> Graph<Find>
+    where
+        Find: for<'b> FnMut(&oid, &'b mut Vec<u8>) -> Result<Option<CommitRefIter<'b>>, E>,
+        E: std::error::Error + Send + Sync + 'static,
+    {
+        /// Return information about the commit with `id`, or `None` if it doesn't exist.
+        fn commit(&mut self, id: &oid) -> Result<Option<&CommitInfo>, Error<E>> {
+            if !self.commits.contains_key(id) {
+                let commit = match (self.find)(id, &mut self.buf).map_err(|err| Error::Find {
+                    err: Some(err),
+                    oid: id.to_owned(),
+                })? {
+                    Some(commit) => commit,
+                    None => return Ok(None),
+                };
+                let mut parents = Vec::new();
+                let mut time = None;
+                for token in commit {
+                    match token? {
+                        git_object::commit::ref_iter::Token::Tree { .. } => continue,
+                        git_object::commit::ref_iter::Token::Parent { id } => parents.push(id),
+                        git_object::commit::ref_iter::Token::Author { .. } => continue,
+                        git_object::commit::ref_iter::Token::Committer { signature } => {
+                            time = Some(signature.time.seconds_since_unix_epoch);
+                            break;
+                        }
+                        _unused_token => break,
+                    }
+                }
+                self.commits_seen += 1;
+                self.commits.insert(
+                    id.to_owned(),
+                    CommitInfo {
+                        time: time.unwrap_or_default(),
+                        parents: parents.into(),
+                        name: None,
+                    },
+                );
+            }
+            Ok(self.commits.get(id))
+        }
+
+        /// Assign `name` to the already known commit `id` if it's better than its current name, and return `true` if so.
+        fn set_name_if_better(&mut self, id: ObjectId, name: Name) -> bool {
+            let info = self
+                .commits
+                .get_mut(&id)
+                .expect("commits are looked up before naming them");
+            match &info.name {
+                Some(existing) if !name.is_better_than(existing) => false,
+                _ => {
+                    info.name = Some(name);
+                    true
+                }
+            }
+        }
+    }
+}
@@ -14,14 +14,11 @@ use crate::{
 /// Returns `Ok(())` if all of `input` was consumed, or the error if either the `revspec` syntax was incorrect or
 /// the `delegate` failed to perform the request.
 pub fn parse(mut input: &BStr, delegate: &mut impl Delegate) -> Result<(), Error> {
-    use delegate::{Kind, Revision};
+    use delegate::Revision;
     let mut delegate = InterceptRev::new(delegate);
-    let mut prev_kind = None;
     if let Some(b'^') = input.first() {
         input = next(input).1;
-        let kind = spec::Kind::ExcludeReachable;
-        delegate.kind(kind).ok_or(Error::Delegate)?;
-        prev_kind = kind.into();
+        delegate.set_kind(spec::Kind::ExcludeReachable)?;
     }
 
     let mut found_revision;
@@ -37,13 +34,13 @@ pub fn parse(mut input: &BStr, delegate: &mut impl Delegate) -> Result<(), Error
         };
     }
     if let Some((rest, kind)) = try_range(input) {
-        if let Some(prev_kind) = prev_kind {
+        if let Some(prev_kind) = delegate.kind {
             return Err(Error::KindSetTwice { prev_kind, kind });
         }
         if !found_revision {
             delegate.find_ref("HEAD".into()).ok_or(Error::Delegate)?;
         }
-        delegate.kind(kind).ok_or(Error::Delegate)?;
+        delegate.set_kind(kind)?;
         (input, found_revision) = {
             let remainder = revision(rest.as_bstr(), &mut delegate)?;
             (remainder, remainder != rest)
@@ -64,7 +61,10 @@ pub fn parse(mut input: &BStr, delegate: &mut impl Delegate) -> Result<(), Error
 mod intercept {
     use bstr::{BStr, BString};
 
-    use crate::spec::parse::{delegate, Delegate};
+    use crate::{
+        spec,
+        spec::parse::{delegate, Delegate, Error},
+    };
 
     #[derive(PartialEq, Eq, Debug, Hash, Ord, PartialOrd, Clone)]
     pub(crate) enum PrefixHintOwned {
@@ -100,6 +100,7 @@ mod intercept {
         pub inner: &'a mut T,
         pub last_ref: Option<BString>, // TODO: smallvec to save the unnecessary allocation? Can't keep ref due to lifetime constraints in traits
         pub last_prefix: Option<(git_hash::Prefix, Option<PrefixHintOwned>)>,
+        pub kind: Option<spec::Kind>,
         pub done: bool,
     }
 
@@ -112,9 +113,19 @@ mod intercept {
                 inner: delegate,
                 last_ref: None,
                 last_prefix: None,
+                kind: None,
                 done: false,
             }
         }
+
+        /// Set the kind of the spec with the delegate, which can only happen once.
+        pub fn set_kind(&mut self, kind: spec::Kind) -> Result<(), Error> {
+            if let Some(prev_kind) = self.kind {
+                return Err(Error::KindSetTwice { prev_kind, kind });
+            }
+            self.kind = Some(kind);
+            self.inner.kind(kind).ok_or(Error::Delegate)
+        }
     }
 
     impl<'a, T> Delegate for InterceptRev<'a, T>
@@ -403,7 +414,7 @@ where
     input = {
         if let Some(b'@') = sep {
             let past_sep = input[sep_pos.map(|pos| pos + 1).unwrap_or(input.len())..].as_bstr();
-            let (nav, rest, _consumed) = parens(past_sep)?.ok_or_else(|| Error::AtNeedsCurlyBrackets {
+            let (nav, mut rest, _consumed) = parens(past_sep)?.ok_or_else(|| Error::AtNeedsCurlyBrackets {
                 input: input[sep_pos.unwrap_or(input.len())..].into(),
             })?;
             let nav = nav.as_ref();
@@ -413,6 +424,19 @@ where
                         delegate
                             .nth_checked_out_branch(n.abs().try_into().expect("non-negative isize fits usize"))
                             .ok_or(Error::Delegate)?;
+                        // The previously checked out branch may be followed by its sibling, as in `@{-1}@{upstream}`.
+                        if let Some((kind, remainder)) = rest
+                            .strip_prefix(b"@")
+                            .map(|past_sep| parens(past_sep.as_bstr()))
+                            .transpose()?
+                            .flatten()
+                            .and_then(|(nav, remainder, _)| {
+                                SiblingBranch::parse(nav.as_ref()).map(|kind| (kind, remainder))
+                            })
+                        {
+                            delegate.sibling_branch(kind).ok_or(Error::Delegate)?;
+                            rest = remainder;
+                        }
                     } else {
                         return Err(Error::RefnameNeedsPositiveReflogEntries { nav: nav.into() });
                     }
@@ -466,7 +490,7 @@ fn navigate<'a, T>(input: &'a BStr, delegate: &mut InterceptRev<'_, T>) -> Resul
 where
     T: Delegate,
 {
-    use delegate::{Kind, Navigate, Revision};
+    use delegate::{Navigate, Revision};
     let mut cursor = 0;
     while let Some(b) = input.get(cursor) {
         cursor += 1;
@@ -502,7 +526,7 @@ where
                                     .expect("non-negative"),
                             ))
                             .ok_or(Error::Delegate)?;
-                        delegate.kind(spec::Kind::RangeBetween).ok_or(Error::Delegate)?;
+                        delegate.set_kind(spec::Kind::RangeBetween)?;
                         if let Some((prefix, hint)) = delegate.last_prefix.take() {
                             match hint {
                                 Some(hint) => delegate.disambiguate_prefix(prefix, hint.to_ref().into()),
@@ -550,15 +574,11 @@ where
                     };
                     delegate.peel_until(target).ok_or(Error::Delegate)?;
                 } else if past_sep.and_then(|i| i.first()) == Some(&b'!') {
-                    delegate
-                        .kind(spec::Kind::ExcludeReachableFromParents)
-                        .ok_or(Error::Delegate)?;
+                    delegate.set_kind(spec::Kind::ExcludeReachableFromParents)?;
                     delegate.done();
                     return Ok(input[cursor + 1..].as_bstr());
                 } else if past_sep.and_then(|i| i.first()) == Some(&b'@') {
-                    delegate
-                        .kind(spec::Kind::IncludeReachableFromParents)
-                        .ok_or(Error::Delegate)?;
+                    delegate.set_kind(spec::Kind::IncludeReachableFromParents)?;
                     delegate.done();
                     return Ok(input[cursor + 1..].as_bstr());
                 } else {
@@ -0,0 +1,32 @@
+#!/bin/bash
+set -eu -o pipefail
+
+git init -q
+git config merge.ff false
+
+function commit() {
+  local message=${1:?first argument is the commit message}
+  local date=${2:?second argument is the commit time in seconds}
+  GIT_COMMITTER_DATE="$date +0000" GIT_AUTHOR_DATE="$date +0000" git commit -q --allow-empty -m "$message"
+}
+
+git checkout -q -b main
+commit c1 1000000000
+commit c2 1000001000
+git checkout -q -b side
+commit s1 1000002000
+commit s2 1000003000
+git checkout -q main
+commit c3 1000004000
+git tag light
+GIT_COMMITTER_DATE="1000005000 +0000" GIT_AUTHOR_DATE="1000005000 +0000" git merge -q side -m merge
+commit c4 1000006000
+GIT_COMMITTER_DATE="1000007000 +0000" git tag -a -m "annotated" v2
+commit c5 1000008000
+git checkout -q -b other side~1
+commit o1 1000009000
+git tag unrelated-lightweight
+
+for commit in $(git rev-list --all); do
+  echo "$commit $(git name-rev --tags --name-only $commit)" >> name-rev.baseline
+done
@@ -0,0 +1,26 @@
+#!/bin/bash
+set -eu -o pipefail
+
+git init -q
+git config merge.ff false
+
+git checkout -q -b main
+git commit -q --allow-empty -m base
+git tag base
+
+git checkout -q -b c
+git commit -q --allow-empty -m c1
+
+git checkout -q -b a base
+git commit -q --allow-empty -m a1
+git tag a1
+
+git checkout -q -b b base
+git commit -q --allow-empty -m b1
+git tag b1
+
+# a2 and b2 both merge a1 and b1, which makes both of them best common ancestors
+git checkout -q a
+git merge -q b1 -m a2
+git checkout -q b
+git merge -q a1 -m b2
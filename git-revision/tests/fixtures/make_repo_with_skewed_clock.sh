@@ -0,0 +1,25 @@
+#!/bin/bash
+set -eu -o pipefail
+
+function commit_at() {
+  local time=${1:?}
+  shift
+  GIT_COMMITTER_DATE="@$time +0000" GIT_AUTHOR_DATE="@$time +0000" git "$@"
+}
+
+git init -q
+
+git checkout -q -b main
+commit_at 1000 commit -q --allow-empty -m root
+commit_at 3000 commit -q --allow-empty -m y
+git tag y
+# the clock of this machine was off, making this commit appear older than its parent
+commit_at 1500 commit -q --allow-empty -m skewed
+commit_at 2000 commit -q --allow-empty -m x
+git tag x
+
+# two merges of x and its ancestor y, so both are common ancestors, but only x is the best one
+git branch first "$(commit_at 4000 commit-tree -p x -p y -m first x^{tree})"
+git branch second "$(commit_at 4000 commit-tree -p x -p y -m second x^{tree})"
+
+git commit-graph write --no-progress --reachable
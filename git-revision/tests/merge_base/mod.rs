@@ -0,0 +1,151 @@
+use git_repository::{odb::FindExt, Repository};
+
+fn repo() -> Repository {
+    let dir = git_testtools::scripted_fixture_repo_read_only("make_repo_with_branches.sh").unwrap();
+    git_repository::open(dir).unwrap()
+}
+
+fn merge_base(repo: &Repository, first: &str, second: &str) -> crate::Result<Option<git_hash::ObjectId>> {
+    let first = repo.rev_parse_single(first)?;
+    let second = repo.rev_parse_single(second)?;
+    Ok(git_revision::merge_base(&first, &second, |id, buf| {
+        repo.objects.find_commit_iter(id, buf).map(Some)
+    })?)
+}
+
+#[test]
+fn diverged_branches_have_the_fork_point_as_merge_base() -> crate::Result {
+    let repo = repo();
+    assert_eq!(
+        merge_base(&repo, "branch1", "main~1")?,
+        Some(repo.rev_parse_single("main~2")?.detach()),
+        "c4 is where branch1 was created, c5 is the commit on main before the merge"
+    );
+    assert_eq!(
+        merge_base(&repo, "main~1", "branch1")?,
+        merge_base(&repo, "branch1", "main~1")?,
+        "the order of arguments doesn't matter"
+    );
+    Ok(())
+}
+
+#[test]
+fn an_ancestor_is_its_own_merge_base() -> crate::Result {
+    let repo = repo();
+    let branch = repo.rev_parse_single("branch1")?.detach();
+    assert_eq!(
+        merge_base(&repo, "main", "branch1")?,
+        Some(branch),
+        "branch1 was merged into main"
+    );
+    assert_eq!(merge_base(&repo, "branch1", "branch1")?, Some(branch));
+    Ok(())
+}
+
+#[test]
+fn generation_numbers_are_used_to_discard_redundant_merge_bases_despite_clock_skew() -> crate::Result {
+    let dir = git_testtools::scripted_fixture_repo_read_only("make_repo_with_skewed_clock.sh")?;
+    let repo = git_repository::open(&dir)?;
+    let commit_graph = git_commitgraph::Graph::from_info_dir(dir.join(".git").join("objects").join("info"))?;
+    let first = repo.rev_parse_single("first")?;
+    let second = repo.rev_parse_single("second")?;
+    assert_eq!(
+        git_revision::merge_base_with_commit_graph(&first, &second, Some(&commit_graph), |id, buf| {
+            repo.objects.find_commit_iter(id, buf).map(Some)
+        })?,
+        Some(repo.rev_parse_single("x")?.detach()),
+        "y is a common ancestor as well, but it's reachable from x through a commit with a commit time lower than its own"
+    );
+    Ok(())
+}
+
+mod all_and_octopus {
+    use git_repository::{odb::FindExt, ObjectId, Repository};
+
+    fn repo() -> crate::Result<Repository> {
+        let dir = git_testtools::scripted_fixture_repo_read_only("make_repo_with_criss_cross_merges.sh")?;
+        Ok(git_repository::open(dir)?)
+    }
+
+    fn ids(repo: &Repository, specs: &[&str]) -> crate::Result<Vec<ObjectId>> {
+        specs
+            .iter()
+            .map(|spec| Ok(repo.rev_parse_single(*spec)?.detach()))
+            .collect()
+    }
+
+    fn sorted(mut ids: Vec<ObjectId>) -> Vec<ObjectId> {
+        ids.sort();
+        ids
+    }
+
+    fn merge_base_all(repo: &Repository, first: &str, others: &[&str]) -> crate::Result<Vec<ObjectId>> {
+        let first = repo.rev_parse_single(first)?;
+        Ok(git_revision::merge_base_all(
+            &first,
+            &ids(repo, others)?,
+            None,
+            |id, buf| repo.objects.find_commit_iter(id, buf).map(Some),
+        )?)
+    }
+
+    fn merge_base_octopus(repo: &Repository, commits: &[&str]) -> crate::Result<Vec<ObjectId>> {
+        Ok(git_revision::merge_base_octopus(
+            &ids(repo, commits)?,
+            None,
+            |id, buf| repo.objects.find_commit_iter(id, buf).map(Some),
+        )?)
+    }
+
+    #[test]
+    fn criss_cross_merges_have_multiple_best_common_ancestors() -> crate::Result {
+        let repo = repo()?;
+        let expected = sorted(ids(&repo, &["a1", "b1"])?);
+        assert_eq!(sorted(merge_base_all(&repo, "a", &["b"])?), expected);
+        assert_eq!(sorted(merge_base_all(&repo, "b", &["a"])?), expected);
+
+        let first = repo.rev_parse_single("a")?;
+        let second = repo.rev_parse_single("b")?;
+        let single = git_revision::merge_base(&first, &second, |id, buf| {
+            repo.objects.find_commit_iter(id, buf).map(Some)
+        })?
+        .expect("present");
+        assert!(expected.contains(&single), "a single merge-base is one of all of them");
+        Ok(())
+    }
+
+    #[test]
+    fn multiple_others_are_treated_like_their_merge() -> crate::Result {
+        let repo = repo()?;
+        assert_eq!(
+            merge_base_all(&repo, "a1", &["b1", "c"])?,
+            ids(&repo, &["base"])?,
+            "neither b1 nor c contain a1"
+        );
+        assert_eq!(
+            merge_base_all(&repo, "b", &["a1", "c"])?,
+            ids(&repo, &["a1"])?,
+            "a1 is reachable from b, and is more recent than base"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn octopus_merge_base_is_reachable_from_all_commits() -> crate::Result {
+        let repo = repo()?;
+        assert_eq!(merge_base_octopus(&repo, &["a", "b", "c"])?, ids(&repo, &["base"])?);
+        assert_eq!(
+            merge_base_octopus(&repo, &["b", "a1", "a"])?,
+            ids(&repo, &["a1"])?,
+            "a1 is reachable from all of them"
+        );
+        assert_eq!(
+            sorted(merge_base_octopus(&repo, &["a", "b"])?),
+            sorted(ids(&repo, &["a1", "b1"])?),
+            "with two commits it's the same as the merge-base of both"
+        );
+        assert_eq!(merge_base_octopus(&repo, &["c"])?, ids(&repo, &["c"])?);
+        assert!(merge_base_octopus(&repo, &[])?.is_empty());
+        Ok(())
+    }
+}
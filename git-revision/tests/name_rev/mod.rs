@@ -0,0 +1,124 @@
+use bstr::ByteSlice;
+use git_repository::{odb::FindExt, Repository};
+use git_revision::name_rev::{Options, Tip, TipKind};
+use git_testtools::hex_to_id;
+
+fn repo() -> Repository {
+    let dir = git_testtools::scripted_fixture_repo_read_only("make_repo_for_name_rev.sh").unwrap();
+    git_repository::open(dir).unwrap()
+}
+
+fn tags(repo: &Repository) -> crate::Result<Vec<Tip<'static>>> {
+    let mut tips = Vec::new();
+    let references = repo.references()?;
+    for reference in references.tags()? {
+        let mut reference = reference.map_err(|err| err as Box<dyn std::error::Error>)?;
+        let object = repo.find_object(reference.id())?;
+        let kind = match object.try_into_tag() {
+            Ok(tag) => TipKind::AnnotatedTag {
+                time: tag.tagger()?.expect("tagger is set").time.seconds_since_unix_epoch,
+            },
+            Err(_) => TipKind::LightweightTag,
+        };
+        tips.push(Tip {
+            name: reference.name().shorten().to_owned().into(),
+            id: reference.peel_to_id_in_place()?.detach(),
+            kind,
+        });
+    }
+    Ok(tips)
+}
+
+fn name_rev(repo: &Repository, id: &git_hash::oid, options: Options<'_>) -> crate::Result<Option<String>> {
+    Ok(
+        git_revision::name_rev(id, |id, buf| repo.objects.find_commit_iter(id, buf).map(Some), options)?
+            .map(|outcome| outcome.name.to_string()),
+    )
+}
+
+#[test]
+fn all_commits_are_named_like_git_does_it() -> crate::Result {
+    let repo = repo();
+    let tips = tags(&repo)?;
+    let baseline = std::fs::read(repo.work_dir().expect("non-bare").join("name-rev.baseline"))?;
+    let mut count = 0;
+    for line in baseline.lines() {
+        let mut tokens = line.splitn_str(2, " ");
+        let id = hex_to_id(tokens.next().expect("id").to_str()?);
+        let expected = tokens.next().expect("name").to_str()?;
+        let actual = name_rev(
+            &repo,
+            &id,
+            Options {
+                tips: tips.clone(),
+                ..Default::default()
+            },
+        )?;
+        assert_eq!(actual.as_deref().unwrap_or("undefined"), expected, "{id}");
+        count += 1;
+    }
+    assert_eq!(count, 9, "all commits are tested");
+    Ok(())
+}
+
+#[test]
+fn lightweight_tags_can_be_ignored() -> crate::Result {
+    let repo = repo();
+    let options = Options {
+        tips: tags(&repo)?,
+        lightweight_tags: false,
+    };
+    let c2 = repo.rev_parse_single("main~4")?;
+    assert_eq!(
+        name_rev(&repo, &c2, options.clone())?.as_deref(),
+        Some("v2~3"),
+        "the lightweight tag `light` would be preferred as it's older"
+    );
+    let o1 = repo.rev_parse_single("other")?;
+    assert_eq!(
+        name_rev(&repo, &o1, options)?,
+        None,
+        "only reachable from a lightweight tag"
+    );
+    Ok(())
+}
+
+#[test]
+fn tags_are_preferred_over_other_tips() -> crate::Result {
+    let repo = repo();
+    let c4 = repo.rev_parse_single("v2^{commit}")?.detach();
+    let mut tips = tags(&repo)?;
+    tips.push(Tip {
+        name: b"main".as_bstr().into(),
+        id: repo.rev_parse_single("main")?.detach(),
+        kind: TipKind::Other,
+    });
+    assert_eq!(
+        name_rev(
+            &repo,
+            &c4,
+            Options {
+                tips: tips.clone(),
+                ..Default::default()
+            }
+        )?
+        .as_deref(),
+        Some("v2^0"),
+        "even though main is closer"
+    );
+    let c5 = repo.rev_parse_single("main")?;
+    assert_eq!(
+        name_rev(
+            &repo,
+            &c5,
+            Options {
+                tips,
+                ..Default::default()
+            }
+        )?
+        .as_deref(),
+        Some("main"),
+        "branches are used if there is no tag"
+    );
+    Ok(())
+}
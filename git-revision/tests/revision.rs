@@ -1,4 +1,6 @@
 mod describe;
+mod merge_base;
+mod name_rev;
 mod spec;
 
 pub type Result<T = ()> = std::result::Result<T, Box<dyn std::error::Error + 'static>>;
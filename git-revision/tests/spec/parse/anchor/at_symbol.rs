@@ -1,4 +1,4 @@
-use git_revision::spec;
+use git_revision::{spec, spec::parse::delegate};
 
 use crate::spec::parse::{parse, try_parse};
 
@@ -178,6 +178,33 @@ fn nth_checked_out_branch() {
     }
 }
 
+#[test]
+fn nth_checked_out_branch_with_sibling_branch() {
+    for (spec, expected_branch, kind_name) in [("@{-1}@{u}", 1, "Upstream"), ("@{-2}@{push}", 2, "Push")] {
+        let rec = parse(spec);
+
+        assert!(rec.kind.is_none());
+        assert_eq!(rec.find_ref[0], None);
+        assert_eq!(rec.nth_checked_out_branch[0], Some(expected_branch));
+        assert_eq!(
+            rec.sibling_branch[0].as_deref(),
+            Some(kind_name),
+            "the sibling of the previously checked out branch"
+        );
+        assert_eq!(rec.calls, 2);
+    }
+
+    let rec = parse("@{-1}@{u}~1");
+    assert_eq!(rec.traversal, vec![delegate::Traversal::NthAncestor(1)]);
+    assert_eq!(rec.calls, 3, "navigation works as usual");
+
+    let err = try_parse("@{-1}@{1}").unwrap_err();
+    assert!(
+        matches!(err, spec::parse::Error::UnconsumedInput { ref input } if input == "@{1}"),
+        "only sibling branches may follow: {err:?}"
+    );
+}
+
 #[test]
 fn numbers_within_braces_cannot_be_negative_zero() {
     let err = try_parse("@{-0}").unwrap_err();
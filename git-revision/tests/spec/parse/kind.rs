@@ -4,9 +4,21 @@ use crate::spec::parse::{try_parse, try_parse_opts, Options};
 
 #[test]
 fn cannot_declare_ranges_multiple_times() {
-    for invalid_spec in ["^HEAD..", "^HEAD..."] {
+    for invalid_spec in [
+        "^HEAD..",
+        "^HEAD...",
+        "^HEAD^!",
+        "^HEAD^@",
+        "^HEAD^-",
+        "HEAD~1..HEAD^!",
+        "HEAD~1...HEAD^@",
+        "HEAD..HEAD^-",
+    ] {
         let err = try_parse(invalid_spec).unwrap_err();
-        assert!(matches!(err, spec::parse::Error::KindSetTwice { .. }));
+        assert!(
+            matches!(err, spec::parse::Error::KindSetTwice { .. }),
+            "{invalid_spec}: {err:?}"
+        );
     }
 }
 
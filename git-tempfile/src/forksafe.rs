@@ -1,12 +1,13 @@
 use std::{io::Write, path::Path};
 
-use tempfile::{NamedTempFile, TempPath};
+use tempfile::{NamedTempFile, TempDir, TempPath};
 
 use crate::{handle, AutoRemove};
 
 enum TempfileOrTemppath {
     Tempfile(NamedTempFile),
     Temppath(TempPath),
+    Tempdir(TempDir),
 }
 
 pub(crate) struct ForksafeTempfile {
@@ -27,13 +28,28 @@ impl ForksafeTempfile {
             owning_process_id: std::process::id(),
         }
     }
+
+    pub fn new_dir(tempdir: TempDir, cleanup: AutoRemove) -> Self {
+        ForksafeTempfile {
+            inner: TempfileOrTemppath::Tempdir(tempdir),
+            cleanup,
+            owning_process_id: std::process::id(),
+        }
+    }
 }
 
 impl ForksafeTempfile {
     pub fn as_mut_tempfile(&mut self) -> Option<&mut NamedTempFile> {
         match &mut self.inner {
             TempfileOrTemppath::Tempfile(file) => Some(file),
-            TempfileOrTemppath::Temppath(_) => None,
+            TempfileOrTemppath::Temppath(_) | TempfileOrTemppath::Tempdir(_) => None,
+        }
+    }
+    pub fn path(&self) -> &Path {
+        match &self.inner {
+            TempfileOrTemppath::Tempfile(file) => file.path(),
+            TempfileOrTemppath::Temppath(path) => path,
+            TempfileOrTemppath::Tempdir(dir) => dir.path(),
         }
     }
     pub fn close(self) -> Self {
@@ -63,6 +79,16 @@ impl ForksafeTempfile {
                     self
                 })),
             },
+            TempfileOrTemppath::Tempdir(dir) => match std::fs::rename(dir.path(), path) {
+                Ok(()) => {
+                    dir.into_path();
+                    Ok(None)
+                }
+                Err(err) => Err((err, {
+                    self.inner = TempfileOrTemppath::Tempdir(dir);
+                    self
+                })),
+            },
         }
     }
 
@@ -70,18 +96,26 @@ impl ForksafeTempfile {
         match self.inner {
             TempfileOrTemppath::Tempfile(file) => file.into_temp_path(),
             TempfileOrTemppath::Temppath(path) => path,
+            TempfileOrTemppath::Tempdir(_) => unreachable!("BUG: directories are never closed"),
         }
     }
     pub fn into_tempfile(self) -> Option<NamedTempFile> {
         match self.inner {
             TempfileOrTemppath::Tempfile(file) => Some(file),
-            TempfileOrTemppath::Temppath(_) => None,
+            TempfileOrTemppath::Temppath(_) | TempfileOrTemppath::Tempdir(_) => None,
+        }
+    }
+    pub fn into_tempdir(self) -> Option<TempDir> {
+        match self.inner {
+            TempfileOrTemppath::Tempdir(dir) => Some(dir),
+            TempfileOrTemppath::Tempfile(_) | TempfileOrTemppath::Temppath(_) => None,
         }
     }
     pub fn drop_impl(self) {
         let file_path = match self.inner {
             TempfileOrTemppath::Tempfile(file) => file.path().to_owned(),
             TempfileOrTemppath::Temppath(path) => path.to_path_buf(),
+            TempfileOrTemppath::Tempdir(dir) => dir.path().to_owned(),
         };
         let parent_directory = file_path.parent().expect("every tempfile has a parent directory");
         self.cleanup.execute_best_effort(parent_directory);
@@ -89,6 +123,16 @@ impl ForksafeTempfile {
 
     pub fn drop_without_deallocation(self) {
         let temppath = match self.inner {
+            TempfileOrTemppath::Tempdir(dir) => {
+                let path = dir.into_path();
+                std::fs::remove_dir_all(&path).ok();
+                std::mem::forget(
+                    self.cleanup
+                        .execute_best_effort(path.parent().expect("every directory has a parent directory")),
+                );
+                std::mem::forget(path); // leak memory to prevent deallocation
+                return;
+            }
             TempfileOrTemppath::Tempfile(file) => {
                 let (mut file, temppath) = file.into_parts();
                 file.flush().ok();
@@ -1,7 +1,10 @@
 //!
-use std::{io, path::Path};
+use std::{
+    io,
+    path::{Path, PathBuf},
+};
 
-use tempfile::{NamedTempFile, TempPath};
+use tempfile::{NamedTempFile, TempDir, TempPath};
 
 use crate::{AutoRemove, ContainingDirectory, ForksafeTempfile, Handle, NEXT_MAP_INDEX, REGISTER};
 
@@ -15,6 +18,10 @@ pub struct Writable;
 #[derive(Debug)]
 pub struct Closed;
 
+/// Marker to signal the Registration is a directory which is removed recursively along with all of its content.
+#[derive(Debug)]
+pub struct Directory;
+
 pub(crate) enum Mode {
     Writable,
     Closed,
@@ -67,6 +74,23 @@ impl Handle<()> {
         ));
         Ok(id)
     }
+
+    fn new_directory_inner(
+        containing_directory: impl AsRef<Path>,
+        directory: ContainingDirectory,
+        cleanup: AutoRemove,
+    ) -> io::Result<usize> {
+        let containing_directory = directory.resolve(containing_directory.as_ref())?;
+        let id = NEXT_MAP_INDEX.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+        expect_none(REGISTER.insert(
+            id,
+            Some(ForksafeTempfile::new_dir(
+                TempDir::new_in(containing_directory)?,
+                cleanup,
+            )),
+        ));
+        Ok(id)
+    }
 }
 
 /// Creation and ownership transfer
@@ -151,10 +175,51 @@ impl Handle<Writable> {
     }
 }
 
+/// Creation and ownership transfer
+impl Handle<Directory> {
+    /// Create a registered temporary directory within `containing_directory` with a name that won't clash, and clean it up
+    /// as specified with `cleanup`. Control how to deal with intermediate directories with `directory`.
+    ///
+    /// The directory and all of its content is removed when the handle is dropped or when the program receives a termination signal.
+    pub fn new(
+        containing_directory: impl AsRef<Path>,
+        directory: ContainingDirectory,
+        cleanup: AutoRemove,
+    ) -> io::Result<Self> {
+        Ok(Handle {
+            id: Handle::<()>::new_directory_inner(containing_directory, directory, cleanup)?,
+            _marker: Default::default(),
+        })
+    }
+
+    /// Take ownership of the temporary directory, which deletes it recursively when dropped.
+    ///
+    /// It's a theoretical possibility that the directory isn't present anymore if signals interfere, hence the `Option`
+    pub fn take(self) -> Option<TempDir> {
+        let res = REGISTER.remove(&self.id);
+        std::mem::forget(self);
+        res.and_then(|(_k, v)| v.map(|v| v.into_tempdir().expect("correct runtime typing")))
+    }
+}
+
+/// Access
+impl Handle<Directory> {
+    /// Return the path to the temporary directory, or `None` if it was removed by a signal handler in the mean time.
+    pub fn path(&self) -> Option<PathBuf> {
+        REGISTER
+            .get(&self.id)
+            .and_then(|entry| entry.value().as_ref().map(|dir| dir.path().to_owned()))
+    }
+}
+
 /// Mutation
 impl Handle<Writable> {
     /// Obtain a mutable handler to the underlying named tempfile and call `f(&mut named_tempfile)` on it.
     ///
+    /// This is what powers the [`Write`][std::io::Write], [`Read`][std::io::Read] and [`Seek`][std::io::Seek] implementations
+    /// of writable handles, which allow to write to the tempfile, for instance to append to a lock file, while it remains
+    /// registered for cleanup in between calls, unlike after [`take()`][Handle::<Writable>::take()].
+    ///
     /// Note that for the duration of the call, a signal interrupting the operation will cause the tempfile not to be cleaned up
     /// as it is not visible anymore to the signal handler.
     ///
@@ -209,7 +274,7 @@ pub mod persist {
     use std::path::Path;
 
     use crate::{
-        handle::{expect_none, Closed, Writable},
+        handle::{expect_none, Closed, Directory, Writable},
         Handle, REGISTER,
     };
 
@@ -292,6 +357,29 @@ pub mod persist {
             }
         }
     }
+
+    impl Handle<Directory> {
+        /// Move this temporary directory to the given `path`, which must not exist or be an empty directory,
+        /// in a way that recovers the original instance on error.
+        pub fn persist(self, path: impl AsRef<Path>) -> Result<(), Error<Directory>> {
+            let res = REGISTER.remove(&self.id);
+
+            match res.and_then(|(_k, v)| v.map(|v| v.persist(path))) {
+                None | Some(Ok(None)) => {
+                    std::mem::forget(self);
+                    Ok(())
+                }
+                Some(Err((err, tempdir))) => {
+                    expect_none(REGISTER.insert(self.id, Some(tempdir)));
+                    Err(Error::<Directory> {
+                        error: err,
+                        handle: self,
+                    })
+                }
+                Some(Ok(Some(_file))) => unreachable!("no open files in a directory handle"),
+            }
+        }
+    }
 }
 
 impl ContainingDirectory {
@@ -66,17 +66,20 @@ mod tests {
         let dir = tempfile::tempdir()?;
         for sig in signal_hook::consts::TERM_SIGNALS {
             let _tempfile = crate::new(dir.path(), ContainingDirectory::Exists, AutoRemove::Tempfile)?;
+            let tempdir = crate::new_dir(dir.path(), ContainingDirectory::Exists, AutoRemove::Tempfile)?;
+            std::fs::write(tempdir.path().expect("present").join("file"), b"content")?;
             assert_eq!(
                 filecount_in(dir.path()),
-                1,
-                "only one tempfile exists no matter the iteration"
+                2,
+                "only one tempfile and one temporary directory exist no matter the iteration"
             );
             signal_hook::low_level::raise(*sig)?;
             assert_eq!(
                 filecount_in(dir.path()),
                 0,
-                "the signal triggers removal but won't terminate the process (anymore)"
+                "the signal triggers removal, recursively for directories, but won't terminate the process (anymore)"
             );
+            assert!(tempdir.path().is_none(), "the directory isn't registered anymore");
         }
         Ok(())
     }
@@ -1,4 +1,4 @@
-//! git-style registered tempfiles that are removed upon typical termination signals.
+//! git-style registered tempfiles and temporary directories that are removed upon typical termination signals.
 //!
 //! To register signal handlers in a typical application that doesn't have its own, call
 //! [`git_tempfile::setup(Default::default())`][setup()] before creating the first tempfile.
@@ -45,7 +45,7 @@ mod forksafe;
 use forksafe::ForksafeTempfile;
 
 pub mod handle;
-use crate::handle::{Closed, Writable};
+use crate::handle::{Closed, Directory, Writable};
 
 static SIGNAL_HANDLER_MODE: AtomicUsize = AtomicUsize::new(SignalHandlerMode::None as usize);
 static NEXT_MAP_INDEX: AtomicUsize = AtomicUsize::new(0);
@@ -146,6 +146,8 @@ impl AutoRemove {
 /// state forever.
 ///
 /// This kind of raciness exists whenever [`take()`][Handle::take()] is used and can't be circumvented.
+/// To put a tempfile into place, prefer [`persist()`][Handle::persist()] which renames it and removes it from the registry
+/// in one step, keeping it registered for cleanup if the rename fails.
 #[derive(Debug)]
 #[must_use = "A handle that is immediately dropped doesn't lock a resource meaningfully"]
 pub struct Handle<Marker: std::fmt::Debug> {
@@ -180,6 +182,16 @@ pub fn mark_at(
     Handle::<Closed>::at(path, directory, cleanup)
 }
 
+/// A shortcut to [`Handle::<Directory>::new()`], creating a temporary directory with non-clashing name in a directory,
+/// which is removed recursively on drop or on termination.
+pub fn new_dir(
+    containing_directory: impl AsRef<Path>,
+    directory: ContainingDirectory,
+    cleanup: AutoRemove,
+) -> io::Result<Handle<Directory>> {
+    Handle::<Directory>::new(containing_directory, directory, cleanup)
+}
+
 /// Initialize signal handlers and other state to keep track of tempfiles, and **must be called before the first tempfile is created**,
 /// allowing to set the `mode` in which signal handlers are installed.
 ///
@@ -206,6 +206,27 @@ mod new {
         Ok(())
     }
 
+    #[test]
+    fn it_can_be_written_through_the_handle_and_is_removed_if_it_goes_out_of_scope() -> crate::Result {
+        let dir = tempfile::tempdir()?;
+        let path = {
+            let mut writable = git_tempfile::new(dir.path(), ContainingDirectory::Exists, AutoRemove::Tempfile)?;
+            writable.write_all(b"hello ")?;
+            writable.write_all(b"world")?;
+            writable.flush()?;
+            let path = writable.with_mut(|tf| tf.path().to_owned())?;
+            assert_eq!(
+                std::fs::read(&path)?,
+                &b"hello world"[..],
+                "writes go to the tempfile on disk while it's registered"
+            );
+            path
+        };
+        assert!(!path.exists(), "the written tempfile was removed");
+        assert_eq!(filecount_in(&dir), 0);
+        Ok(())
+    }
+
     #[test]
     fn it_can_create_the_containing_directory_and_remove_it_when_dropped() -> crate::Result {
         let dir = tempfile::tempdir()?;
@@ -242,3 +263,73 @@ mod new {
         Ok(())
     }
 }
+
+mod new_dir {
+    use std::path::Path;
+
+    use git_tempfile::{AutoRemove, ContainingDirectory};
+
+    fn filecount_in(path: impl AsRef<Path>) -> usize {
+        std::fs::read_dir(path).expect("valid dir").count()
+    }
+
+    #[test]
+    fn it_is_removed_recursively_if_it_goes_out_of_scope() -> crate::Result {
+        let dir = tempfile::tempdir()?;
+        {
+            let tempdir = git_tempfile::new_dir(dir.path(), ContainingDirectory::Exists, AutoRemove::Tempfile)?;
+            let path = tempdir.path().expect("still registered");
+            assert!(path.is_dir());
+            std::fs::create_dir(path.join("subdir"))?;
+            std::fs::write(path.join("subdir").join("file"), b"content")?;
+            assert_eq!(filecount_in(&dir), 1, "a temp directory was created");
+        }
+        assert_eq!(filecount_in(&dir), 0, "the directory and its content were removed");
+        Ok(())
+    }
+
+    #[test]
+    fn it_can_create_the_containing_directory_and_remove_it_when_dropped() -> crate::Result {
+        let dir = tempfile::tempdir()?;
+        let containing_dir = dir.path().join("a").join("b");
+        let tempdir = git_tempfile::new_dir(
+            &containing_dir,
+            ContainingDirectory::CreateAllRaceProof(Default::default()),
+            AutoRemove::TempfileAndEmptyParentDirectoriesUntil {
+                boundary_directory: dir.path().into(),
+            },
+        )?;
+        std::fs::write(tempdir.path().expect("present").join("file"), b"content")?;
+        drop(tempdir);
+        assert!(
+            !dir.path().join("a").is_dir(),
+            "the now empty containing directories were deleted as well"
+        );
+        assert!(dir.path().is_dir(), "it won't touch the boundary directory");
+        Ok(())
+    }
+
+    #[test]
+    fn it_can_be_persisted_or_taken() -> crate::Result {
+        let dir = tempfile::tempdir()?;
+        let tempdir = git_tempfile::new_dir(dir.path(), ContainingDirectory::Exists, AutoRemove::Tempfile)?;
+        std::fs::write(tempdir.path().expect("present").join("file"), b"content")?;
+
+        let target = dir.path().join("persisted");
+        std::fs::create_dir(&target)?;
+        std::fs::write(target.join("other"), b"")?;
+        let tempdir = tempdir
+            .persist(&target)
+            .expect_err("cannot persist onto non-empty directory")
+            .handle;
+        std::fs::remove_dir_all(&target)?;
+        tempdir.persist(&target)?;
+        assert_eq!(std::fs::read(target.join("file"))?, b"content");
+        assert_eq!(filecount_in(&dir), 1, "only the persisted directory is left");
+
+        let tempdir = git_tempfile::new_dir(dir.path(), ContainingDirectory::Exists, AutoRemove::Tempfile)?;
+        let path = tempdir.take().expect("not taken yet").into_path();
+        assert!(path.is_dir(), "ownership was transferred and the directory was kept");
+        Ok(())
+    }
+}
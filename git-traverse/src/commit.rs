@@ -52,6 +52,14 @@ impl Default for Sorting {
     }
 }
 
+///
+pub mod topo;
+
+pub mod is_ancestor;
+pub use is_ancestor::function::is_ancestor;
+
+mod commit_graph;
+
 ///
 pub mod ancestors {
     use std::{
@@ -0,0 +1,49 @@
+use git_hash::{oid, ObjectId};
+
+/// Information about a commit as stored in a commit-graph.
+pub(crate) struct Commit {
+    /// The ids of the parents, or only the first one if `first_parent_only` was set.
+    pub parents: Vec<ObjectId>,
+    pub tree_id: ObjectId,
+    pub commit_time: u32,
+    /// The generation number of the commit, or `None` if the commit-graph doesn't provide a usable one.
+    pub generation: Option<u32>,
+}
+
+/// Obtain information about the commit with `id` from `graph`, or return `None` if it isn't contained in it or if its data
+/// can't be decoded, in which case the object database should be used instead.
+pub(crate) fn lookup(graph: Option<&git_commitgraph::Graph>, id: &oid, first_parent_only: bool) -> Option<Commit> {
+    let graph = graph?;
+    let commit = graph.commit_by_id(id)?;
+    let mut parents = Vec::new();
+    for pos in commit.iter_parents() {
+        parents.push(graph.id_at(pos.ok()?).to_owned());
+        if first_parent_only {
+            break;
+        }
+    }
+    Some(Commit {
+        parents,
+        tree_id: commit.root_tree_id().to_owned(),
+        commit_time: commit.committer_timestamp() as u32,
+        generation: generation_of(&commit),
+    })
+}
+
+/// Return the generation number of `id` in `graph`, if it is known.
+pub(crate) fn generation(graph: Option<&git_commitgraph::Graph>, id: &oid) -> Option<u32> {
+    generation_of(&graph?.commit_by_id(id)?)
+}
+
+/// Return the changed-path Bloom filter of the commit with `id` in `graph`, if there is one.
+pub(crate) fn bloom_filter<'a>(
+    graph: Option<&'a git_commitgraph::Graph>,
+    id: &oid,
+) -> Option<git_commitgraph::bloom::Filter<'a>> {
+    graph?.commit_by_id(id)?.bloom_filter()
+}
+
+fn generation_of(commit: &git_commitgraph::file::Commit<'_>) -> Option<u32> {
+    // Commit-graphs written by old versions of git store zero as generation, which isn't usable.
+    Some(commit.generation()).filter(|gen| *gen != 0 && *gen != git_commitgraph::GENERATION_NUMBER_INFINITY)
+}
@@ -0,0 +1,85 @@
+//! Determine if a commit can be reached from another commit.
+use git_hash::{oid, ObjectId};
+use git_object::CommitRefIter;
+
+use crate::commit::commit_graph;
+
+/// The error returned by [`is_ancestor()`][function::is_ancestor()].
+#[derive(Debug, thiserror::Error)]
+#[allow(missing_docs)]
+pub enum Error {
+    #[error("The commit {oid} could not be found")]
+    FindExisting {
+        oid: ObjectId,
+        source: Box<dyn std::error::Error + Send + Sync + 'static>,
+    },
+    #[error(transparent)]
+    ObjectDecode(#[from] git_object::decode::Error),
+}
+
+pub(crate) mod function {
+    use super::*;
+
+    /// Return `true` if `ancestor` can be reached from `descendant` by following its parents, similar to
+    /// `git merge-base --is-ancestor ancestor descendant`, or in other words, if `descendant` contains `ancestor`.
+    ///
+    /// A commit is considered its own ancestor.
+    ///
+    /// If a `commit_graph` is given, parents of commits contained in it are obtained from the graph, and the traversal
+    /// stops early at commits whose generation number is too low for them to lead to `ancestor`. Commits that are not part
+    /// of the graph are looked up with `find` instead, which also happens if there is no commit-graph at all.
+    /// Parents that can't be found are skipped, which is common in shallow repositories.
+    pub fn is_ancestor<Find, E>(
+        ancestor: &oid,
+        descendant: &oid,
+        commit_graph: Option<&git_commitgraph::Graph>,
+        mut find: Find,
+    ) -> Result<bool, Error>
+    where
+        Find: for<'a> FnMut(&oid, &'a mut Vec<u8>) -> Result<CommitRefIter<'a>, E>,
+        E: std::error::Error + Send + Sync + 'static,
+    {
+        if ancestor == descendant {
+            return Ok(true);
+        }
+        let ancestor_generation = commit_graph::generation(commit_graph, ancestor);
+        let mut seen = hash_hasher::HashedSet::<ObjectId>::default();
+        let mut stack = vec![descendant.to_owned()];
+        let mut buf = Vec::new();
+        let mut is_tip = true;
+        while let Some(id) = stack.pop() {
+            let parents = match commit_graph::lookup(commit_graph, &id, false) {
+                Some(commit) => {
+                    if let (Some(ancestor_generation), Some(generation)) = (ancestor_generation, commit.generation) {
+                        // all ancestors of this commit have a lower generation than itself.
+                        if generation <= ancestor_generation {
+                            is_tip = false;
+                            continue;
+                        }
+                    }
+                    commit.parents
+                }
+                None => match find(&id, &mut buf) {
+                    Ok(commit) => commit.parent_ids().collect(),
+                    Err(_) if !is_tip => continue,
+                    Err(err) => {
+                        return Err(Error::FindExisting {
+                            oid: id,
+                            source: err.into(),
+                        })
+                    }
+                },
+            };
+            is_tip = false;
+            for parent_id in parents {
+                if parent_id == ancestor {
+                    return Ok(true);
+                }
+                if seen.insert(parent_id) {
+                    stack.push(parent_id);
+                }
+            }
+        }
+        Ok(false)
+    }
+}
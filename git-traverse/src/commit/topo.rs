@@ -0,0 +1,818 @@
+//! Traverse commits in topological or date order, with support for hidden commits and boundary reporting.
+//!
+//! As opposed to [`Ancestors`][crate::commit::Ancestors], this traversal needs to know all commits it will return upfront,
+//! which is done when [building][Builder::build()] it.
+use std::{
+    cmp::Reverse,
+    collections::{BinaryHeap, VecDeque},
+};
+
+use git_hash::{oid, ObjectId};
+use git_object::{bstr::BString, CommitRefIter, TreeRefIter};
+
+use crate::commit::{commit_graph, Parents};
+
+mod paths;
+
+/// The error returned when [building][Builder::build()] a [`Topo`] traversal.
+#[derive(Debug, thiserror::Error)]
+#[allow(missing_docs)]
+pub enum Error {
+    #[error("The commit {oid} could not be found")]
+    FindExisting {
+        oid: ObjectId,
+        source: Box<dyn std::error::Error + Send + Sync + 'static>,
+    },
+    #[error("The tree {oid} could not be found")]
+    FindExistingTree {
+        oid: ObjectId,
+        source: Box<dyn std::error::Error + Send + Sync + 'static>,
+    },
+    #[error(transparent)]
+    ObjectDecode(#[from] git_object::decode::Error),
+}
+
+/// Specify how to sort commits during a [`Topo`] traversal.
+///
+/// In any case, no parent is returned before all of its children were returned.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, Ord, PartialOrd)]
+pub enum Sorting {
+    /// Avoid showing commits of multiple lines of history intermixed, similar to `git log --topo-order`.
+    TopoOrder,
+    /// Return commits in commit timestamp order, newest first, similar to `git log --date-order`.
+    DateOrder,
+}
+
+impl Default for Sorting {
+    fn default() -> Self {
+        Sorting::TopoOrder
+    }
+}
+
+/// Information about a commit returned by the [`Topo`] traversal.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Info {
+    /// The id of the commit.
+    pub id: ObjectId,
+    /// The ids of the parents of the commit that were traversed, which is only the first one in [`Parents::First`] mode.
+    ///
+    /// If the traversal is [restricted to paths][Builder::with_paths()], these are the closest ancestors that are returned
+    /// as well, or hidden ones, similar to what `git log --parents -- <paths>` shows.
+    pub parent_ids: Vec<ObjectId>,
+    /// The time at which the commit was committed, in seconds since the unix epoch.
+    pub commit_time: u32,
+    /// If true, this commit is not part of the selected range of commits but a parent of one of them,
+    /// similar to what `git log --boundary` shows prefixed with `-`.
+    ///
+    /// Boundary commits are returned after all other commits.
+    pub is_boundary: bool,
+    /// The smallest amount of parent-links between any of the tips and this commit, along the parents that are traversed,
+    /// with tips having a depth of 0.
+    ///
+    /// It's the `depth` a shallow clone of the tips would need to be deepened by to include this commit, minus one.
+    pub depth: usize,
+}
+
+/// A traversal returning commits reachable from a set of tips but not from a set of hidden commits,
+/// created and configured using a [`Builder`].
+pub struct Topo {
+    commits: VecDeque<Info>,
+}
+
+impl Iterator for Topo {
+    type Item = Info;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.commits.pop_front()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.commits.len(), Some(self.commits.len()))
+    }
+}
+
+/// The type of function to lookup trees with if the traversal isn't [restricted to paths][Builder::with_paths()].
+pub type NoTrees = for<'a> fn(&oid, &'a mut Vec<u8>) -> Result<TreeRefIter<'a>, std::convert::Infallible>;
+
+/// A way to configure and [build][Builder::build()] a [`Topo`] traversal.
+pub struct Builder<Find, FindTree = NoTrees> {
+    find: Find,
+    paths: Option<paths::Filter<FindTree>>,
+    tips: Vec<ObjectId>,
+    hidden: Vec<ObjectId>,
+    sorting: Sorting,
+    parents: Parents,
+    boundary: bool,
+    commit_graph: Option<git_commitgraph::Graph>,
+    max_count: Option<usize>,
+    max_depth: Option<usize>,
+    since: Option<u32>,
+    until: Option<u32>,
+}
+
+/// Initialization
+impl<Find, E> Builder<Find>
+where
+    Find: for<'a> FnMut(&oid, &'a mut Vec<u8>) -> Result<CommitRefIter<'a>, E>,
+    E: std::error::Error + Send + Sync + 'static,
+{
+    /// Create a new instance to traverse all commits reachable from `tips`, using `find` to lookup commits by id.
+    ///
+    /// `find` writes the commit data into the given buffer and returns an iterator over its tokens. Caching should be implemented
+    /// within this function as needed.
+    pub fn new(tips: impl IntoIterator<Item = impl Into<ObjectId>>, find: Find) -> Self {
+        Builder {
+            find,
+            tips: tips.into_iter().map(Into::into).collect(),
+            hidden: Vec::new(),
+            sorting: Default::default(),
+            parents: Default::default(),
+            boundary: false,
+            commit_graph: None,
+            paths: None,
+            max_count: None,
+            max_depth: None,
+            since: None,
+            until: None,
+        }
+    }
+}
+
+/// Builder
+impl<Find, FindTree> Builder<Find, FindTree> {
+    /// Hide all commits reachable from `hidden`, similar to `^rev` on the `git log` command-line.
+    pub fn with_hidden(mut self, hidden: impl IntoIterator<Item = impl Into<ObjectId>>) -> Self {
+        self.hidden.extend(hidden.into_iter().map(Into::into));
+        self
+    }
+
+    /// Set the sorting method to use when returning commits.
+    pub fn sorting(mut self, sorting: Sorting) -> Self {
+        self.sorting = sorting;
+        self
+    }
+
+    /// Change our commit parent handling mode to the given one.
+    pub fn parents(mut self, mode: Parents) -> Self {
+        self.parents = mode;
+        self
+    }
+
+    /// If `toggle` is true, also return the hidden parents of the returned commits after all other commits,
+    /// see [`Info::is_boundary`].
+    pub fn boundary(mut self, toggle: bool) -> Self {
+        self.boundary = toggle;
+        self
+    }
+
+    /// Return at most `count` commits, not counting boundary commits, similar to `git log --max-count`.
+    ///
+    /// If [boundary commits][Builder::boundary()] are returned, these are the parents of the returned commits.
+    pub fn max_count(mut self, count: Option<usize>) -> Self {
+        self.max_count = count;
+        self
+    }
+
+    /// Only return commits with a [depth][Info::depth] of at most `depth`, without traversing the parents of commits at that depth.
+    ///
+    /// This yields the commits a shallow clone of the tips with a depth of `depth + 1` would contain, similar to `git clone --depth`.
+    pub fn max_depth(mut self, depth: Option<usize>) -> Self {
+        self.max_depth = depth;
+        self
+    }
+
+    /// Hide commits that were committed before `time` in seconds since the unix epoch, along with all of their ancestors,
+    /// similar to `git log --since`.
+    ///
+    /// Like with [hidden commits][Builder::with_hidden()], this stops the traversal early.
+    pub fn since(mut self, time: Option<u32>) -> Self {
+        self.since = time;
+        self
+    }
+
+    /// Don't return commits that were committed after `time` in seconds since the unix epoch, similar to `git log --until`.
+    ///
+    /// Their ancestors are still returned, so all commits need to be traversed up to the tips.
+    pub fn until(mut self, time: Option<u32>) -> Self {
+        self.until = time;
+        self
+    }
+
+    /// Use `commit_graph` to speed up the traversal, see [`Graph`][git_commitgraph::Graph] for details.
+    ///
+    /// Commits contained in the graph don't have to be read from the object database, and their generation numbers allow
+    /// to stop the traversal of hidden commits as soon as it's certain that no more returned commits can be reached from them.
+    /// Commits that aren't contained in the graph are looked up as usual.
+    pub fn with_commit_graph(mut self, commit_graph: Option<git_commitgraph::Graph>) -> Self {
+        self.commit_graph = commit_graph;
+        self
+    }
+
+    /// Only return commits that change any of the given `paths`, similar to `git log -- <paths>`, using `find_tree` to
+    /// lookup trees by id.
+    ///
+    /// The first parent of a merge commit that has the same tree entries at `paths` is the only one that is followed,
+    /// and parents of commits are rewritten to the ancestors that changed `paths`, see [`Info::parent_ids`].
+    /// Paths are relative to the root of the repository and use slashes as separators. Paths to directories match all
+    /// paths below them, and an empty path matches everything.
+    pub fn with_paths<FindTreeFn, E>(
+        self,
+        paths: impl IntoIterator<Item = impl Into<BString>>,
+        find_tree: FindTreeFn,
+    ) -> Builder<Find, FindTreeFn>
+    where
+        FindTreeFn: for<'a> FnMut(&oid, &'a mut Vec<u8>) -> Result<TreeRefIter<'a>, E>,
+        E: std::error::Error + Send + Sync + 'static,
+    {
+        Builder {
+            find: self.find,
+            paths: Some(paths::Filter::new(
+                paths.into_iter().map(Into::into).collect(),
+                find_tree,
+            )),
+            tips: self.tips,
+            hidden: self.hidden,
+            sorting: self.sorting,
+            parents: self.parents,
+            boundary: self.boundary,
+            commit_graph: self.commit_graph,
+            max_count: self.max_count,
+            max_depth: self.max_depth,
+            since: self.since,
+            until: self.until,
+        }
+    }
+}
+
+type Flags = u8;
+/// The commit is reachable from a hidden commit.
+const UNINTERESTING: Flags = 1 << 0;
+/// The commit was pushed onto the queue of commits to process.
+const SEEN: Flags = 1 << 1;
+/// The commit is part of the output as regular commit.
+const SHOWN: Flags = 1 << 2;
+/// The commit is part of the output as boundary commit.
+const BOUNDARY: Flags = 1 << 3;
+/// The commit doesn't change any of the paths we are interested in compared to one of its parents, and isn't returned.
+const TREESAME: Flags = 1 << 4;
+/// The commit is at the maximum depth, so its parents weren't traversed.
+const DEPTH_LIMITED: Flags = 1 << 5;
+
+/// The amount of uninteresting commits we keep processing after everything seems to be uninteresting, to allow for clock skew.
+const SLOP: usize = 5;
+
+struct Commit {
+    flags: Flags,
+    commit_time: u32,
+    tree_id: ObjectId,
+    /// The generation number as obtained from the commit-graph, if the commit is contained in it.
+    generation: Option<u32>,
+    /// The smallest amount of parent-links to any of the tips, or `usize::MAX` if unknown.
+    depth: usize,
+    /// `None` if the commit wasn't parsed yet.
+    parents: Option<Vec<ObjectId>>,
+}
+
+/// A queue to pop the newest commits first, and commits with the same time in the order they were inserted.
+#[derive(Default)]
+struct DateQueue {
+    heap: BinaryHeap<(u32, Reverse<usize>, ObjectId)>,
+    count: usize,
+}
+
+impl DateQueue {
+    fn push(&mut self, time: u32, id: ObjectId) {
+        self.heap.push((time, Reverse(self.count), id));
+        self.count += 1;
+    }
+    fn pop(&mut self) -> Option<(u32, ObjectId)> {
+        self.heap.pop().map(|(time, _, id)| (time, id))
+    }
+}
+
+/// Building
+impl<Find, E, FindTree, TreeError> Builder<Find, FindTree>
+where
+    Find: for<'a> FnMut(&oid, &'a mut Vec<u8>) -> Result<CommitRefIter<'a>, E>,
+    E: std::error::Error + Send + Sync + 'static,
+    FindTree: for<'a> FnMut(&oid, &'a mut Vec<u8>) -> Result<TreeRefIter<'a>, TreeError>,
+    TreeError: std::error::Error + Send + Sync + 'static,
+{
+    /// Traverse the commit graph to find all commits to return, and sort them according to our configuration.
+    pub fn build(mut self) -> Result<Topo, Error> {
+        let commit_graph = self.commit_graph.take();
+        let mut graph = Graph {
+            commits: Default::default(),
+            buf: Vec::new(),
+            first_parent_only: matches!(self.parents, Parents::First),
+            commit_graph: commit_graph.as_ref(),
+        };
+        let interesting = self.limit(&mut graph)?;
+        let rewrite_parents = self.paths.is_some();
+        let mut shown = sort_in_topological_order(&interesting, &graph, self.sorting);
+        shown.retain(|id| {
+            let commit = &graph.commits[id];
+            commit.flags & TREESAME == 0 && self.until.map_or(true, |until| commit.commit_time <= until)
+        });
+        if let Some(max_count) = self.max_count {
+            shown.truncate(max_count);
+        }
+        // Only parents of returned commits can be boundary commits.
+        for commit in graph.commits.values_mut() {
+            commit.flags &= !SHOWN;
+        }
+        for id in &shown {
+            graph.commits.get_mut(id).expect("known").flags |= SHOWN;
+        }
+        let mut commits: VecDeque<_> = shown
+            .into_iter()
+            .map(|id| {
+                let mut info = graph.info(id, false);
+                if rewrite_parents {
+                    info.parent_ids = graph.rewritten_parents(&id);
+                }
+                info
+            })
+            .collect();
+
+        if self.boundary {
+            let mut boundary = Vec::new();
+            for info in commits.iter() {
+                for parent_id in &info.parent_ids {
+                    let parent = match graph.commits.get_mut(parent_id) {
+                        Some(parent) => parent,
+                        None => continue,
+                    };
+                    if parent.flags & (SHOWN | BOUNDARY) == 0 {
+                        parent.flags |= BOUNDARY;
+                        boundary.push(*parent_id);
+                    }
+                }
+            }
+            boundary.reverse();
+            for id in &boundary {
+                graph.load(id, &mut self.find)?;
+            }
+            commits.extend(
+                sort_in_topological_order(&boundary, &graph, self.sorting)
+                    .into_iter()
+                    .map(|id| graph.info(id, true)),
+            );
+        }
+        Ok(Topo { commits })
+    }
+
+    /// Find all commits reachable from our tips but not from hidden commits, in the order in which they were discovered.
+    fn limit(&mut self, graph: &mut Graph<'_>) -> Result<Vec<ObjectId>, Error> {
+        let mut queue = DateQueue::default();
+        for (id, flags) in self
+            .tips
+            .iter()
+            .map(|id| (id, SEEN))
+            .chain(self.hidden.iter().map(|id| (id, SEEN | UNINTERESTING)))
+        {
+            let commit_time = graph.load(id, &mut self.find)?;
+            let commit = graph.commits.get_mut(id).expect("just loaded");
+            let needs_push = commit.flags & SEEN == 0;
+            commit.flags |= flags;
+            if needs_push {
+                queue.push(commit_time, *id);
+            }
+        }
+        for id in &self.tips {
+            graph.commits.get_mut(id).expect("loaded").depth = 0;
+        }
+        // Sort commits by date, but keep the given order for commits with the same date.
+        let mut sorted = DateQueue::default();
+        while let Some((time, id)) = queue.pop() {
+            sorted.push(time, id);
+        }
+        let mut queue = sorted;
+
+        let mut out = Vec::new();
+        let mut slop = SLOP;
+        let mut min_generation = Some(u32::MAX);
+        let mut uncut = Vec::new();
+        while let Some((commit_time, id)) = queue.pop() {
+            graph.load(&id, &mut self.find)?;
+            if graph.commits[&id].flags & UNINTERESTING == 0 && self.since.map_or(false, |since| commit_time < since) {
+                graph.mark_uninteresting(id);
+            }
+            let is_uninteresting = graph.commits[&id].flags & UNINTERESTING != 0;
+            if !is_uninteresting {
+                if let Some(paths) = self.paths.as_mut() {
+                    graph.simplify(&id, paths, &mut self.find)?;
+                }
+            }
+            let commit = graph.commits.get_mut(&id).expect("loaded");
+            let depth = commit.depth.saturating_add(1);
+            let parent_ids = if !is_uninteresting && self.max_depth.map_or(false, |max| commit.depth >= max) {
+                commit.flags |= DEPTH_LIMITED;
+                Vec::new()
+            } else {
+                commit.parents.clone().unwrap_or_default()
+            };
+            for parent_id in parent_ids {
+                let parent_time = match graph.try_load(&parent_id, &mut self.find)? {
+                    Some(time) => time,
+                    None => continue, // skip missing parents, which is common in shallow repositories.
+                };
+                let parent = graph.commits.get_mut(&parent_id).expect("just loaded");
+                let needs_push = parent.flags & SEEN == 0;
+                parent.flags |= SEEN;
+                if is_uninteresting {
+                    graph.mark_uninteresting(parent_id);
+                } else {
+                    graph.lower_depth(parent_id, depth, self.max_depth, &mut uncut);
+                }
+                if needs_push {
+                    queue.push(parent_time, parent_id);
+                }
+            }
+            // Commits that now have a shorter path to the tips need their parents traversed after all.
+            for id in uncut.drain(..) {
+                queue.push(graph.commits[&id].commit_time, id);
+            }
+
+            if is_uninteresting {
+                if let Some(min_generation) = min_generation {
+                    match everything_interesting_found(&queue, graph, min_generation) {
+                        Some(true) => break,
+                        Some(false) => continue,
+                        None => {}
+                    }
+                }
+                slop = still_interesting(&queue, graph, commit_time, slop);
+                if slop == 0 {
+                    break;
+                }
+                continue;
+            }
+            min_generation = min_generation
+                .zip(graph.commits[&id].generation)
+                .map(|(min, generation)| min.min(generation));
+            let commit = graph.commits.get_mut(&id).expect("loaded");
+            if commit.flags & SHOWN == 0 {
+                commit.flags |= SHOWN;
+                out.push(id);
+            }
+        }
+
+        out.retain(|id| graph.commits[id].flags & UNINTERESTING == 0);
+        Ok(out)
+    }
+}
+
+/// Return `Some(true)` if none of the commits in `queue` can reach any of the commits we want to return, using generation numbers
+/// to know for sure, with `min_generation` being the lowest generation of these commits.
+/// Return `None` if generation numbers aren't available for all commits in `queue`, so heuristics have to be used instead.
+fn everything_interesting_found(queue: &DateQueue, graph: &Graph<'_>, min_generation: u32) -> Option<bool> {
+    let mut max_generation = 0;
+    for (_, _, id) in queue.heap.iter() {
+        let commit = &graph.commits[id];
+        if commit.flags & UNINTERESTING == 0 {
+            return Some(false);
+        }
+        max_generation = max_generation.max(commit.generation?);
+    }
+    // All ancestors of the commits in the queue have a lower generation than they have.
+    Some(max_generation < min_generation)
+}
+
+/// Return the amount of uninteresting commits left to process before we can be sure that there are no interesting ones in the queue.
+fn still_interesting(queue: &DateQueue, graph: &Graph<'_>, commit_time: u32, slop: usize) -> usize {
+    let newest = match queue.heap.peek() {
+        Some((time, _, _)) => *time,
+        None => return 0,
+    };
+    if commit_time <= newest {
+        return SLOP;
+    }
+    if queue
+        .heap
+        .iter()
+        .any(|(_, _, id)| graph.commits[id].flags & UNINTERESTING == 0)
+    {
+        return SLOP;
+    }
+    slop - 1
+}
+
+/// Sort `commits` so that no parent comes before any of its children, while resolving ties according to `sorting`.
+fn sort_in_topological_order(commits: &[ObjectId], graph: &Graph<'_>, sorting: Sorting) -> Vec<ObjectId> {
+    let mut indegree = hash_hasher::HashedMap::<ObjectId, usize>::default();
+    indegree.extend(commits.iter().map(|id| (*id, 1)));
+    for id in commits {
+        for parent_id in graph.parents(id) {
+            if let Some(count) = indegree.get_mut(&parent_id) {
+                *count += 1;
+            }
+        }
+    }
+
+    let mut queue = TopoQueue::new(sorting);
+    for id in commits.iter().filter(|id| indegree[*id] == 1) {
+        queue.push(graph.commits[id].commit_time, *id);
+    }
+    queue.reverse_lifo();
+
+    let mut out = Vec::with_capacity(commits.len());
+    while let Some(id) = queue.pop() {
+        for parent_id in graph.parents(&id) {
+            if let Some(count) = indegree.get_mut(&parent_id) {
+                if *count == 0 {
+                    continue;
+                }
+                *count -= 1;
+                if *count == 1 {
+                    queue.push(graph.commits[&parent_id].commit_time, parent_id);
+                }
+            }
+        }
+        *indegree.get_mut(&id).expect("known") = 0;
+        out.push(id);
+    }
+    out
+}
+
+enum TopoQueue {
+    Lifo(Vec<ObjectId>),
+    Date(DateQueue),
+}
+
+impl TopoQueue {
+    fn new(sorting: Sorting) -> Self {
+        match sorting {
+            Sorting::TopoOrder => TopoQueue::Lifo(Vec::new()),
+            Sorting::DateOrder => TopoQueue::Date(DateQueue::default()),
+        }
+    }
+    fn push(&mut self, commit_time: u32, id: ObjectId) {
+        match self {
+            TopoQueue::Lifo(stack) => stack.push(id),
+            TopoQueue::Date(queue) => queue.push(commit_time, id),
+        }
+    }
+    fn pop(&mut self) -> Option<ObjectId> {
+        match self {
+            TopoQueue::Lifo(stack) => stack.pop(),
+            TopoQueue::Date(queue) => queue.pop().map(|(_, id)| id),
+        }
+    }
+    /// Assure the initial items of a stack are returned in the order in which they were pushed.
+    fn reverse_lifo(&mut self) {
+        if let TopoQueue::Lifo(stack) = self {
+            stack.reverse();
+        }
+    }
+}
+
+struct Graph<'graph> {
+    commits: hash_hasher::HashedMap<ObjectId, Commit>,
+    buf: Vec<u8>,
+    first_parent_only: bool,
+    commit_graph: Option<&'graph git_commitgraph::Graph>,
+}
+
+impl Graph<'_> {
+    /// Return the commit time of the commit with `id`, after making sure it's parsed.
+    fn load<Find, E>(&mut self, id: &oid, find: &mut Find) -> Result<u32, Error>
+    where
+        Find: for<'a> FnMut(&oid, &'a mut Vec<u8>) -> Result<CommitRefIter<'a>, E>,
+        E: std::error::Error + Send + Sync + 'static,
+    {
+        if let Some(Commit {
+            commit_time,
+            parents: Some(_),
+            ..
+        }) = self.commits.get(id)
+        {
+            return Ok(*commit_time);
+        }
+        if let Some(commit) = commit_graph::lookup(self.commit_graph, id, self.first_parent_only) {
+            return Ok(self.insert(
+                id,
+                commit.tree_id,
+                commit.commit_time,
+                commit.generation,
+                commit.parents,
+            ));
+        }
+        let commit_iter = find(id, &mut self.buf).map_err(|err| Error::FindExisting {
+            oid: id.to_owned(),
+            source: err.into(),
+        })?;
+        let mut parents = Vec::new();
+        let mut commit_time = 0;
+        let mut tree_id = None;
+        for token in commit_iter {
+            match token? {
+                git_object::commit::ref_iter::Token::Tree { id } => tree_id = Some(id),
+                git_object::commit::ref_iter::Token::Parent { id } => {
+                    if !self.first_parent_only || parents.is_empty() {
+                        parents.push(id)
+                    }
+                }
+                git_object::commit::ref_iter::Token::Author { .. } => continue,
+                git_object::commit::ref_iter::Token::Committer { signature } => {
+                    commit_time = signature.time.seconds_since_unix_epoch;
+                    break;
+                }
+                _unused_token => break,
+            }
+        }
+        // the tree is always the first token, so decoding fails if it's missing.
+        let tree_id = tree_id.unwrap_or_else(|| ObjectId::null(id.kind()));
+        Ok(self.insert(id, tree_id, commit_time, None, parents))
+    }
+
+    fn insert(
+        &mut self,
+        id: &oid,
+        tree_id: ObjectId,
+        commit_time: u32,
+        generation: Option<u32>,
+        parents: Vec<ObjectId>,
+    ) -> u32 {
+        let commit = self.commits.entry(id.to_owned()).or_insert(Commit {
+            flags: 0,
+            tree_id,
+            commit_time,
+            generation,
+            depth: usize::MAX,
+            parents: None,
+        });
+        commit.commit_time = commit_time;
+        commit.generation = generation;
+        commit.parents = Some(parents);
+        commit_time
+    }
+
+    /// Like [`load()`][Self::load()], but returns `None` if the commit doesn't exist.
+    fn try_load<Find, E>(&mut self, id: &oid, find: &mut Find) -> Result<Option<u32>, Error>
+    where
+        Find: for<'a> FnMut(&oid, &'a mut Vec<u8>) -> Result<CommitRefIter<'a>, E>,
+        E: std::error::Error + Send + Sync + 'static,
+    {
+        match self.load(id, find) {
+            Ok(time) => Ok(Some(time)),
+            Err(Error::FindExisting { .. }) => Ok(None),
+            Err(err) => Err(err),
+        }
+    }
+
+    /// Return the parents of `id` that are known to us, which excludes parents that don't exist (anymore) or that weren't parsed yet.
+    fn parents(&self, id: &oid) -> Vec<ObjectId> {
+        self.commits
+            .get(id)
+            .and_then(|c| c.parents.as_ref())
+            .map(|parents| {
+                parents
+                    .iter()
+                    .filter(|id| self.commits.contains_key(*id))
+                    .copied()
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    /// Mark `id` and all of its known ancestors uninteresting.
+    fn mark_uninteresting(&mut self, id: ObjectId) {
+        let mut stack = vec![id];
+        while let Some(id) = stack.pop() {
+            let commit = match self.commits.get_mut(&id) {
+                Some(c) if c.flags & UNINTERESTING == 0 => c,
+                _ => continue,
+            };
+            commit.flags |= UNINTERESTING;
+            if let Some(parents) = &commit.parents {
+                stack.extend(parents.iter().copied());
+            }
+        }
+    }
+
+    /// Set the depth of `id` to `depth` if it's smaller than its current one, along with the depth of all of its known ancestors.
+    /// Commits whose parents weren't traversed due to `max_depth` but now are below it are put into `uncut`.
+    fn lower_depth(&mut self, id: ObjectId, depth: usize, max_depth: Option<usize>, uncut: &mut Vec<ObjectId>) {
+        let mut stack = vec![(id, depth)];
+        while let Some((id, depth)) = stack.pop() {
+            let commit = match self.commits.get_mut(&id) {
+                Some(c) if depth < c.depth => c,
+                _ => continue,
+            };
+            commit.depth = depth;
+            if commit.flags & DEPTH_LIMITED != 0 && max_depth.map_or(true, |max| depth < max) {
+                commit.flags &= !DEPTH_LIMITED;
+                uncut.push(id);
+            }
+            if let Some(parents) = &commit.parents {
+                stack.extend(parents.iter().map(|parent_id| (*parent_id, depth + 1)));
+            }
+        }
+    }
+
+    /// Compare the commit `id` to its parents to see if it changes any of the `paths`. If it doesn't, mark it as [`TREESAME`]
+    /// and only keep the first of its parents it's the same as, similar to the default history simplification of `git log`.
+    fn simplify<Find, E, FindTree, TreeError>(
+        &mut self,
+        id: &oid,
+        paths: &mut paths::Filter<FindTree>,
+        find: &mut Find,
+    ) -> Result<(), Error>
+    where
+        Find: for<'a> FnMut(&oid, &'a mut Vec<u8>) -> Result<CommitRefIter<'a>, E>,
+        E: std::error::Error + Send + Sync + 'static,
+        FindTree: for<'a> FnMut(&oid, &'a mut Vec<u8>) -> Result<TreeRefIter<'a>, TreeError>,
+        TreeError: std::error::Error + Send + Sync + 'static,
+    {
+        let tree_id = self.commits[id].tree_id;
+        let parent_ids = self.commits[id].parents.clone().unwrap_or_default();
+        // Bloom filters contain the changes compared to the first parent, or to the empty tree if there is none.
+        let unchanged_according_to_bloom_filter =
+            commit_graph::bloom_filter(self.commit_graph, id).map_or(false, |filter| !paths.maybe_changed(&filter));
+        if parent_ids.is_empty() {
+            if unchanged_according_to_bloom_filter || paths.is_same(None, Some(&tree_id))? {
+                self.commits.get_mut(id).expect("loaded").flags |= TREESAME;
+            }
+            return Ok(());
+        }
+
+        let mut relevant_parents = 0;
+        let (mut relevant_change, mut irrelevant_change) = (false, false);
+        for (parent_index, parent_id) in parent_ids.into_iter().enumerate() {
+            if self.try_load(&parent_id, find)?.is_none() {
+                continue;
+            }
+            let parent = &self.commits[&parent_id];
+            let is_relevant = parent.flags & UNINTERESTING == 0;
+            let is_same = (parent_index == 0 && unchanged_according_to_bloom_filter)
+                || paths.is_same(Some(&parent.tree_id), Some(&tree_id))?;
+            match (is_same, is_relevant) {
+                (true, true) => {
+                    let commit = self.commits.get_mut(id).expect("loaded");
+                    commit.parents = Some(vec![parent_id]);
+                    commit.flags |= TREESAME;
+                    return Ok(());
+                }
+                // Keep all parents even if a hidden one brought all changes, to not lose the other lines of history.
+                (true, false) => {}
+                (false, true) => relevant_change = true,
+                (false, false) => irrelevant_change = true,
+            }
+            if is_relevant {
+                relevant_parents += 1;
+            }
+        }
+        let is_changed = if relevant_parents > 0 {
+            relevant_change
+        } else {
+            irrelevant_change
+        };
+        if !is_changed {
+            self.commits.get_mut(id).expect("loaded").flags |= TREESAME;
+        }
+        Ok(())
+    }
+
+    /// Return the parents of `id`, rewritten to skip all commits that don't change our paths.
+    fn rewritten_parents(&self, id: &oid) -> Vec<ObjectId> {
+        let mut out = Vec::new();
+        for parent_id in self.parents(id) {
+            if let Some(parent_id) = self.rewrite_parent(parent_id) {
+                if !out.contains(&parent_id) {
+                    out.push(parent_id);
+                }
+            }
+        }
+        out
+    }
+
+    /// Follow `id` to the closest ancestor that changed our paths or is hidden, or return `None` if there is none.
+    fn rewrite_parent(&self, mut id: ObjectId) -> Option<ObjectId> {
+        loop {
+            let commit = &self.commits[&id];
+            if commit.flags & UNINTERESTING != 0 || commit.flags & TREESAME == 0 {
+                return Some(id);
+            }
+            let parents = self.parents(&id);
+            let mut relevant_parents = parents.iter().filter(|id| self.commits[*id].flags & UNINTERESTING == 0);
+            id = match (parents.as_slice(), relevant_parents.next(), relevant_parents.next()) {
+                ([], _, _) => return None,
+                ([parent], _, _) => *parent,
+                (_, Some(sole_relevant_parent), None) => *sole_relevant_parent,
+                _ => return Some(id),
+            };
+        }
+    }
+
+    fn info(&self, id: ObjectId, is_boundary: bool) -> Info {
+        let commit = &self.commits[&id];
+        Info {
+            id,
+            parent_ids: commit.parents.clone().unwrap_or_default(),
+            commit_time: commit.commit_time,
+            is_boundary,
+            depth: commit.depth,
+        }
+    }
+}
@@ -0,0 +1,116 @@
+use git_hash::{oid, ObjectId};
+use git_object::{
+    bstr::{BStr, BString, ByteSlice},
+    tree::EntryMode,
+    TreeRefIter,
+};
+
+use crate::commit::topo::Error;
+
+type Entry = Option<(EntryMode, ObjectId)>;
+
+/// The paths to restrict a traversal to, along with a way to lookup trees to see if commits changed them.
+pub(crate) struct Filter<FindTree> {
+    paths: Vec<BString>,
+    find_tree: FindTree,
+    buf: Vec<u8>,
+    /// The entries at each of our `paths`, by the id of the tree they are contained in.
+    entries_by_tree: hash_hasher::HashedMap<ObjectId, Vec<Entry>>,
+    /// The keys to lookup each of our `paths` in changed-path Bloom filters, created with the settings of the last filter we saw.
+    bloom_keys: Option<(git_commitgraph::bloom::Settings, Vec<Vec<git_commitgraph::bloom::Key>>)>,
+}
+
+impl<FindTree> Filter<FindTree> {
+    pub fn new(paths: Vec<BString>, find_tree: FindTree) -> Self {
+        Filter {
+            paths,
+            find_tree,
+            buf: Vec::new(),
+            entries_by_tree: Default::default(),
+            bloom_keys: None,
+        }
+    }
+
+    /// Return `false` if the commit of the changed-path Bloom `filter` definitely didn't change any of our paths compared to its
+    /// first parent, or `true` if it possibly did.
+    pub fn maybe_changed(&mut self, filter: &git_commitgraph::bloom::Filter<'_>) -> bool {
+        let paths = &self.paths;
+        let keys = match &mut self.bloom_keys {
+            Some((settings, keys)) if *settings == filter.settings => keys,
+            bloom_keys => {
+                let keys = paths
+                    .iter()
+                    .map(|path| {
+                        git_commitgraph::bloom::Key::for_path_and_leading_directories(path.as_ref(), filter.settings)
+                    })
+                    .collect();
+                &mut bloom_keys.insert((filter.settings, keys)).1
+            }
+        };
+        keys.iter().any(|keys| filter.contains_all(keys))
+    }
+}
+
+impl<FindTree, E> Filter<FindTree>
+where
+    FindTree: for<'a> FnMut(&oid, &'a mut Vec<u8>) -> Result<TreeRefIter<'a>, E>,
+    E: std::error::Error + Send + Sync + 'static,
+{
+    /// Return true if the trees `lhs` and `rhs` have the same entries at all of our paths, with `None` being the empty tree.
+    pub fn is_same(&mut self, lhs: Option<&oid>, rhs: Option<&oid>) -> Result<bool, Error> {
+        if lhs == rhs {
+            return Ok(true);
+        }
+        let lhs = self.entries(lhs)?;
+        let rhs = self.entries(rhs)?;
+        Ok(lhs == rhs)
+    }
+
+    fn entries(&mut self, tree: Option<&oid>) -> Result<Vec<Entry>, Error> {
+        let tree = match tree {
+            Some(tree) => tree,
+            None => return Ok(vec![None; self.paths.len()]),
+        };
+        if let Some(entries) = self.entries_by_tree.get(tree) {
+            return Ok(entries.clone());
+        }
+        let mut entries = Vec::with_capacity(self.paths.len());
+        for path in &self.paths {
+            entries.push(entry_at(tree, path.as_ref(), &mut self.find_tree, &mut self.buf)?);
+        }
+        self.entries_by_tree.insert(tree.to_owned(), entries.clone());
+        Ok(entries)
+    }
+}
+
+/// Return the entry at `path` within `tree`, or `None` if there is none, with an empty `path` referring to `tree` itself.
+fn entry_at<FindTree, E>(tree: &oid, path: &BStr, find_tree: &mut FindTree, buf: &mut Vec<u8>) -> Result<Entry, Error>
+where
+    FindTree: for<'a> FnMut(&oid, &'a mut Vec<u8>) -> Result<TreeRefIter<'a>, E>,
+    E: std::error::Error + Send + Sync + 'static,
+{
+    let mut tree_id = tree.to_owned();
+    let mut components = path.split_str("/").filter(|c| !c.is_empty()).peekable();
+    if components.peek().is_none() {
+        return Ok(Some((EntryMode::Tree, tree_id)));
+    }
+    while let Some(component) = components.next() {
+        let mut entry = None;
+        for item in find_tree(&tree_id, buf).map_err(|err| Error::FindExistingTree {
+            oid: tree_id,
+            source: err.into(),
+        })? {
+            let item = item?;
+            if item.filename == component {
+                entry = Some((item.mode, item.oid.to_owned()));
+                break;
+            }
+        }
+        match entry {
+            Some((mode, id)) if components.peek().is_none() => return Ok(Some((mode, id))),
+            Some((EntryMode::Tree, id)) => tree_id = id,
+            _ => return Ok(None),
+        }
+    }
+    unreachable!("we return when handling the last component")
+}
@@ -0,0 +1,205 @@
+use git_hash::{oid, ObjectId};
+use git_object::{Data, Kind};
+
+use crate::set::Seen;
+
+/// The error returned by [`State::check()`].
+#[derive(Debug, thiserror::Error)]
+#[allow(missing_docs)]
+pub enum Error {
+    #[error("The object {oid} could not be looked up")]
+    Find {
+        oid: ObjectId,
+        source: Box<dyn std::error::Error + Send + Sync + 'static>,
+    },
+    #[error("Object {oid} was expected to be a {expected} as referenced by {referenced_by}, but it's a {actual}")]
+    ObjectKind {
+        oid: ObjectId,
+        referenced_by: ObjectId,
+        expected: Kind,
+        actual: Kind,
+    },
+    #[error(transparent)]
+    ObjectDecode(#[from] git_object::decode::Error),
+}
+
+/// An object that is referenced by another object, or given as tip, but that doesn't exist.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Ord, PartialOrd)]
+pub struct Missing {
+    /// The id of the missing object.
+    pub id: ObjectId,
+    /// The kind of object we expected based on the way it's referenced, or `None` if it was a tip of unknown kind.
+    pub expected_kind: Option<Kind>,
+    /// The object referencing the missing one, or `None` if it was a tip of the traversal.
+    pub referenced_by: Option<ObjectId>,
+}
+
+/// Statistics about the connectivity check performed by [`State::check()`].
+#[derive(Default, Debug, Clone, PartialEq, Eq, Hash, Ord, PartialOrd)]
+pub struct Outcome {
+    /// The amount of commits that were visited.
+    pub commits: usize,
+    /// The amount of trees that were visited.
+    pub trees: usize,
+    /// The amount of blobs that were visited.
+    pub blobs: usize,
+    /// The amount of tags that were visited.
+    pub tags: usize,
+    /// All objects that were referenced, but that couldn't be found, in the order in which they were encountered.
+    pub missing: Vec<Missing>,
+}
+
+/// The state used to check the connectivity of objects, which remembers all objects it ever saw.
+///
+/// Reusing it for multiple calls to [`check()`][State::check()] will skip objects that were checked before, which can be used
+/// to only check objects not reachable from existing references, which are known to be connected, like during `receive-pack`.
+///
+/// The set of seen objects can be any [`Seen`] implementation, like an [indexed set][crate::set::Indexed] to need less memory
+/// than the default hash set in large repositories.
+#[derive(Default, Clone)]
+pub struct State<S = hash_hasher::HashedSet<ObjectId>> {
+    seen: S,
+    stack: Vec<(ObjectId, Option<Kind>, Option<ObjectId>)>,
+    buf: Vec<u8>,
+}
+
+impl<S: Seen> State<S> {
+    /// Create a new instance that keeps track of seen objects using `seen`.
+    pub fn with_seen(seen: S) -> Self {
+        State {
+            seen,
+            stack: Vec::new(),
+            buf: Vec::new(),
+        }
+    }
+
+    /// Return the set of seen objects.
+    pub fn seen(&self) -> &S {
+        &self.seen
+    }
+
+    /// Forget all seen objects to start over, while keeping allocated memory.
+    pub fn clear(&mut self) {
+        self.seen.clear();
+    }
+
+    /// Consider all `ids` as checked, so they and all objects only reachable through them won't be visited.
+    ///
+    /// This is useful for commits at the boundary of a shallow clone, whose parents are known to be missing.
+    pub fn mark_seen(&mut self, ids: impl IntoIterator<Item = impl Into<ObjectId>>) {
+        for id in ids {
+            self.seen.insert(&id.into());
+        }
+    }
+
+    /// Return true if the object `id` was seen before, either because it was checked or [marked][State::mark_seen()].
+    pub fn has_seen(&self, id: &oid) -> bool {
+        self.seen.contains(id)
+    }
+
+    /// Visit all objects reachable from `tips`, using `find` to look them up, and report all objects that are missing.
+    ///
+    /// `find` returns `None` for objects that don't exist. Every object is obtained in full with `find` to verify its kind,
+    /// including blobs, which makes blobs the most costly part of the check even though their content isn't used otherwise.
+    /// Commits, trees and tags are decoded to find the objects they refer to. Submodule entries in trees aren't followed
+    /// as they refer to commits in other repositories.
+    ///
+    /// Objects reachable from `tips` that were seen in previous calls aren't visited again. Their counts are not
+    /// part of the returned [`Outcome`].
+    pub fn check<Find, E>(
+        &mut self,
+        tips: impl IntoIterator<Item = impl Into<ObjectId>>,
+        mut find: Find,
+    ) -> Result<Outcome, Error>
+    where
+        Find: for<'a> FnMut(&oid, &'a mut Vec<u8>) -> Result<Option<Data<'a>>, E>,
+        E: std::error::Error + Send + Sync + 'static,
+    {
+        let mut outcome = Outcome::default();
+        self.stack.clear();
+        self.stack
+            .extend(tips.into_iter().map(|id| (id.into(), None::<Kind>, None::<ObjectId>)));
+
+        while let Some((id, expected_kind, referenced_by)) = self.stack.pop() {
+            if !self.seen.insert(&id) {
+                continue;
+            }
+            let data = match find(&id, &mut self.buf).map_err(|err| Error::Find {
+                oid: id,
+                source: err.into(),
+            })? {
+                Some(data) => data,
+                None => {
+                    outcome.missing.push(Missing {
+                        id,
+                        expected_kind,
+                        referenced_by,
+                    });
+                    continue;
+                }
+            };
+            if let (Some(expected), Some(referenced_by)) = (expected_kind, referenced_by) {
+                if expected != data.kind {
+                    return Err(Error::ObjectKind {
+                        oid: id,
+                        referenced_by,
+                        expected,
+                        actual: data.kind,
+                    });
+                }
+            }
+
+            let referenced_by = Some(id);
+            match data.kind {
+                Kind::Commit => {
+                    outcome.commits += 1;
+                    for token in git_object::CommitRefIter::from_bytes(data.data) {
+                        match token? {
+                            git_object::commit::ref_iter::Token::Tree { id } => {
+                                self.stack.push((id, Some(Kind::Tree), referenced_by))
+                            }
+                            git_object::commit::ref_iter::Token::Parent { id } => {
+                                self.stack.push((id, Some(Kind::Commit), referenced_by))
+                            }
+                            _ => break,
+                        }
+                    }
+                }
+                Kind::Tree => {
+                    outcome.trees += 1;
+                    for entry in git_object::TreeRefIter::from_bytes(data.data) {
+                        let entry = entry?;
+                        let kind = match entry.mode {
+                            git_object::tree::EntryMode::Tree => Kind::Tree,
+                            git_object::tree::EntryMode::Commit => continue,
+                            git_object::tree::EntryMode::Blob
+                            | git_object::tree::EntryMode::BlobExecutable
+                            | git_object::tree::EntryMode::Link => Kind::Blob,
+                        };
+                        if !self.seen.contains(entry.oid) {
+                            self.stack.push((entry.oid.to_owned(), Some(kind), referenced_by));
+                        }
+                    }
+                }
+                Kind::Tag => {
+                    outcome.tags += 1;
+                    let mut target = None;
+                    for token in git_object::TagRefIter::from_bytes(data.data) {
+                        match token? {
+                            git_object::tag::ref_iter::Token::Target { id } => target = Some(id),
+                            git_object::tag::ref_iter::Token::TargetKind(kind) => {
+                                if let Some(target) = target.take() {
+                                    self.stack.push((target, Some(kind), referenced_by));
+                                }
+                                break;
+                            }
+                            _ => break,
+                        }
+                    }
+                }
+                Kind::Blob => outcome.blobs += 1,
+            }
+        }
+        Ok(outcome)
+    }
+}
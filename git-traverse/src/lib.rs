@@ -7,3 +7,12 @@ pub mod commit;
 
 /// Tree traversal
 pub mod tree;
+
+/// Sets of object ids to keep track of seen objects
+pub mod set;
+
+/// Check the connectivity of objects by visiting everything reachable from a set of tips
+pub mod connectivity;
+
+/// Find all objects reachable from a set of tips, accelerated by bitmaps if available
+pub mod reachable;
@@ -0,0 +1,230 @@
+use git_hash::{oid, ObjectId};
+use git_object::{Data, Kind};
+
+/// The error returned by [`objects()`].
+#[derive(Debug, thiserror::Error)]
+#[allow(missing_docs)]
+pub enum Error {
+    #[error("The object {oid} could not be found")]
+    FindExisting {
+        oid: ObjectId,
+        source: Box<dyn std::error::Error + Send + Sync + 'static>,
+    },
+    #[error(transparent)]
+    ObjectDecode(#[from] git_object::decode::Error),
+}
+
+/// Access to bitmaps of commits, each storing all objects reachable from a commit, typically obtained from pack bitmaps.
+///
+/// Objects are identified by their position, with all positions being smaller than [`num_objects()`][Bitmaps::num_objects()].
+pub trait Bitmaps {
+    /// The amount of objects that can be referred to by position.
+    fn num_objects(&self) -> usize;
+    /// Return the position of the object with `id`, or `None` if it's not covered by bitmaps.
+    fn position(&self, id: &oid) -> Option<usize>;
+    /// Return the id of the object at `position`.
+    fn id_at(&self, position: usize) -> ObjectId;
+    /// Return all objects reachable from `commit` as bits in words, with bit `n % 64` of word `n / 64` being set if the
+    /// object at position `n` is reachable, or `None` if there is no bitmap for `commit`.
+    fn reachable(&self, commit: &oid) -> Option<Vec<u64>>;
+}
+
+/// Information about how the reachable objects were obtained.
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq, Hash, Ord, PartialOrd)]
+pub struct Statistics {
+    /// The amount of bitmaps that were used instead of traversing the commits they were stored for.
+    pub bitmaps: usize,
+    /// The amount of commits that were traversed as they weren't covered by bitmaps.
+    pub commits: usize,
+    /// The amount of trees that were traversed as they weren't covered by bitmaps.
+    pub trees: usize,
+    /// The amount of tags that were traversed as they weren't covered by bitmaps.
+    pub tags: usize,
+}
+
+/// The result of [`objects()`].
+#[derive(Default, Debug, Clone, PartialEq, Eq, Hash, Ord, PartialOrd)]
+pub struct Outcome {
+    /// All objects reachable from the tips but not from the hidden tips, with all objects covered by bitmaps coming
+    /// first, in order of their position, followed by all other objects in the order in which they were encountered.
+    pub objects: Vec<ObjectId>,
+    /// Information about how `objects` were obtained.
+    pub statistics: Statistics,
+}
+
+/// Return all objects reachable from `tips` that aren't reachable from `hidden`, similar to `git rev-list --objects <tips> ^<hidden>`.
+///
+/// If `bitmaps` are available, the objects reachable from commits that have a bitmap are obtained from it without traversing
+/// their history, and only commits without one are traversed along with their trees, which is what makes serving clones fast.
+/// Without `bitmaps`, all commits, trees and tags are traversed.
+///
+/// `find` is used to lookup commits, trees and tags, along with tips of unknown kind, and must find all of them.
+/// Blobs are never looked up. Submodule entries in trees are ignored as they refer to commits in other repositories.
+pub fn objects<Find, E>(
+    tips: impl IntoIterator<Item = impl Into<ObjectId>>,
+    hidden: impl IntoIterator<Item = impl Into<ObjectId>>,
+    bitmaps: Option<&dyn Bitmaps>,
+    mut find: Find,
+) -> Result<Outcome, Error>
+where
+    Find: for<'a> FnMut(&oid, &'a mut Vec<u8>) -> Result<Data<'a>, E>,
+    E: std::error::Error + Send + Sync + 'static,
+{
+    let mut statistics = Statistics::default();
+    let mut buf = Vec::new();
+    let mut hidden_set = Set::new(bitmaps);
+    walk(hidden, &mut hidden_set, None, &mut find, &mut buf, &mut statistics)?;
+    let mut set = Set::new(bitmaps);
+    walk(tips, &mut set, Some(&hidden_set), &mut find, &mut buf, &mut statistics)?;
+
+    let mut objects = Vec::new();
+    if let Some(bitmaps) = bitmaps {
+        for (word_index, (word, hidden_word)) in set.words.iter().zip(hidden_set.words.iter()).enumerate() {
+            let mut word = word & !hidden_word;
+            while word != 0 {
+                let bit = word.trailing_zeros() as usize;
+                objects.push(bitmaps.id_at(word_index * 64 + bit));
+                word &= word - 1;
+            }
+        }
+    }
+    objects.extend(set.others.into_iter().filter(|id| !hidden_set.others_seen.contains(id)));
+    Ok(Outcome { objects, statistics })
+}
+
+/// A set of objects, with those covered by bitmaps kept as bits for fast unions with bitmaps.
+struct Set<'a> {
+    bitmaps: Option<&'a dyn Bitmaps>,
+    words: Vec<u64>,
+    /// Objects without a position, in order of insertion.
+    others: Vec<ObjectId>,
+    others_seen: hash_hasher::HashedSet<ObjectId>,
+}
+
+impl<'a> Set<'a> {
+    fn new(bitmaps: Option<&'a dyn Bitmaps>) -> Self {
+        Set {
+            bitmaps,
+            words: vec![0; bitmaps.map_or(0, |b| (b.num_objects() + 63) / 64)],
+            others: Vec::new(),
+            others_seen: Default::default(),
+        }
+    }
+
+    fn contains(&self, id: &oid) -> bool {
+        match self.bitmaps.and_then(|b| b.position(id)) {
+            Some(position) => self.words[position / 64] & (1 << (position % 64)) != 0,
+            None => self.others_seen.contains(id),
+        }
+    }
+
+    /// Return true if `id` wasn't contained yet.
+    fn insert(&mut self, id: &oid) -> bool {
+        match self.bitmaps.and_then(|b| b.position(id)) {
+            Some(position) => {
+                let (word, mask) = (&mut self.words[position / 64], 1 << (position % 64));
+                let inserted = *word & mask == 0;
+                *word |= mask;
+                inserted
+            }
+            None => {
+                let inserted = self.others_seen.insert(id.to_owned());
+                if inserted {
+                    self.others.push(id.to_owned());
+                }
+                inserted
+            }
+        }
+    }
+
+    /// Add all objects of the bitmap of `commit` to this set, or return false if there is none.
+    fn union_with_bitmap(&mut self, commit: &oid) -> bool {
+        match self.bitmaps.and_then(|b| b.reachable(commit)) {
+            Some(words) => {
+                for (word, other) in self.words.iter_mut().zip(words) {
+                    *word |= other;
+                }
+                true
+            }
+            None => false,
+        }
+    }
+}
+
+/// Add all objects reachable from `tips` to `set`, without traversing objects in `hidden`.
+fn walk<Find, E>(
+    tips: impl IntoIterator<Item = impl Into<ObjectId>>,
+    set: &mut Set<'_>,
+    hidden: Option<&Set<'_>>,
+    find: &mut Find,
+    buf: &mut Vec<u8>,
+    statistics: &mut Statistics,
+) -> Result<(), Error>
+where
+    Find: for<'a> FnMut(&oid, &'a mut Vec<u8>) -> Result<Data<'a>, E>,
+    E: std::error::Error + Send + Sync + 'static,
+{
+    let mut stack: Vec<(ObjectId, Option<Kind>)> = tips.into_iter().map(|id| (id.into(), None)).collect();
+    while let Some((id, kind)) = stack.pop() {
+        if hidden.map_or(false, |hidden| hidden.contains(&id)) || set.contains(&id) {
+            continue;
+        }
+        if matches!(kind, None | Some(Kind::Commit)) && set.union_with_bitmap(&id) {
+            statistics.bitmaps += 1;
+            continue;
+        }
+        set.insert(&id);
+        if kind == Some(Kind::Blob) {
+            continue;
+        }
+
+        let data = find(&id, buf).map_err(|err| Error::FindExisting {
+            oid: id,
+            source: err.into(),
+        })?;
+        match data.kind {
+            Kind::Commit => {
+                statistics.commits += 1;
+                for token in git_object::CommitRefIter::from_bytes(data.data) {
+                    match token? {
+                        git_object::commit::ref_iter::Token::Tree { id } => stack.push((id, Some(Kind::Tree))),
+                        git_object::commit::ref_iter::Token::Parent { id } => stack.push((id, Some(Kind::Commit))),
+                        _ => break,
+                    }
+                }
+            }
+            Kind::Tree => {
+                statistics.trees += 1;
+                for entry in git_object::TreeRefIter::from_bytes(data.data) {
+                    let entry = entry?;
+                    let kind = match entry.mode {
+                        git_object::tree::EntryMode::Tree => Kind::Tree,
+                        git_object::tree::EntryMode::Commit => continue,
+                        git_object::tree::EntryMode::Blob
+                        | git_object::tree::EntryMode::BlobExecutable
+                        | git_object::tree::EntryMode::Link => Kind::Blob,
+                    };
+                    stack.push((entry.oid.to_owned(), Some(kind)));
+                }
+            }
+            Kind::Tag => {
+                statistics.tags += 1;
+                let mut target = None;
+                for token in git_object::TagRefIter::from_bytes(data.data) {
+                    match token? {
+                        git_object::tag::ref_iter::Token::Target { id } => target = Some(id),
+                        git_object::tag::ref_iter::Token::TargetKind(kind) => {
+                            if let Some(target) = target.take() {
+                                stack.push((target, Some(kind)));
+                            }
+                            break;
+                        }
+                        _ => break,
+                    }
+                }
+            }
+            Kind::Blob => {}
+        }
+    }
+    Ok(())
+}
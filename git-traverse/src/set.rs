@@ -0,0 +1,240 @@
+use std::hash::BuildHasher;
+
+use git_hash::{oid, ObjectId};
+
+/// A set of object ids to remember which objects were seen during a traversal.
+pub trait Seen {
+    /// Insert `id` and return true if it wasn't contained before.
+    fn insert(&mut self, id: &oid) -> bool;
+    /// Return true if `id` is contained in the set.
+    fn contains(&self, id: &oid) -> bool;
+    /// Remove all ids, while keeping allocated memory for reuse if possible.
+    fn clear(&mut self);
+}
+
+impl<S: BuildHasher> Seen for std::collections::HashSet<ObjectId, S> {
+    fn insert(&mut self, id: &oid) -> bool {
+        std::collections::HashSet::insert(self, id.to_owned())
+    }
+
+    fn contains(&self, id: &oid) -> bool {
+        std::collections::HashSet::contains(self, id)
+    }
+
+    fn clear(&mut self) {
+        std::collections::HashSet::clear(self)
+    }
+}
+
+/// A way to obtain a unique position for objects, like their position in a pack as obtained from a pack bitmap
+/// or their index within a pack index.
+///
+/// Positions should be dense, that is close to each other, to keep the memory needed for the [`Indexed`] set low.
+pub trait Positions {
+    /// Return the position of `id`, or `None` if it doesn't have one.
+    fn position(&self, id: &oid) -> Option<u32>;
+}
+
+impl<F> Positions for F
+where
+    F: Fn(&oid) -> Option<u32>,
+{
+    fn position(&self, id: &oid) -> Option<u32> {
+        self(id)
+    }
+}
+
+/// A set that keeps objects with a [position][Positions] as bits in a [`Bitmap`], and all other objects in a hash set.
+///
+/// As opposed to a hash set, it needs little memory per object even for large repositories, and objects close to each other
+/// in a pack are close to each other in memory as well. When [cleared][Seen::clear()], all memory is kept for reuse in the
+/// next traversal of the same repository.
+pub struct Indexed<P> {
+    positions: P,
+    bits: Bitmap,
+    others: hash_hasher::HashedSet<ObjectId>,
+}
+
+impl<P> Indexed<P> {
+    /// Create a new empty set, using `positions` to learn where each object is stored.
+    pub fn new(positions: P) -> Self {
+        Indexed {
+            positions,
+            bits: Bitmap::default(),
+            others: Default::default(),
+        }
+    }
+
+    /// Return the amount of objects in the set.
+    pub fn len(&self) -> usize {
+        self.bits.len() + self.others.len()
+    }
+
+    /// Return true if there is no object in this set.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Return the bitmap with the positions of all contained objects that have one.
+    pub fn bitmap(&self) -> &Bitmap {
+        &self.bits
+    }
+}
+
+impl<P: Positions> Seen for Indexed<P> {
+    fn insert(&mut self, id: &oid) -> bool {
+        match self.positions.position(id) {
+            Some(position) => self.bits.insert(position),
+            None => self.others.insert(id.to_owned()),
+        }
+    }
+
+    fn contains(&self, id: &oid) -> bool {
+        match self.positions.position(id) {
+            Some(position) => self.bits.contains(position),
+            None => self.others.contains(id),
+        }
+    }
+
+    fn clear(&mut self) {
+        self.bits.clear();
+        self.others.clear();
+    }
+}
+
+/// The most values an array container may hold before it's turned into a bits container, at which point both need the same memory.
+const ARRAY_LIMIT: usize = 4096;
+/// The amount of words in a bits container to hold `2^16` bits.
+const WORDS: usize = (1 << 16) / 64;
+
+/// A compressed set of `u32` values, similar to a [roaring bitmap](https://roaringbitmap.org).
+///
+/// Values are grouped by their upper 16 bits into containers which either store the lower 16 bits of each value in a sorted array
+/// if there are only few of them, or as bits if there are many. This keeps memory usage low for sparse and dense sets alike.
+///
+/// Bitmaps compare equal if they contain the same values, independently of how these are stored.
+#[derive(Default, Clone, Debug)]
+pub struct Bitmap {
+    /// Containers sorted by the upper 16 bits of their values.
+    containers: Vec<(u16, Container)>,
+}
+
+#[derive(Clone, Debug)]
+enum Container {
+    /// The sorted lower 16 bits of up to [`ARRAY_LIMIT`] values.
+    Array(Vec<u16>),
+    /// One bit for each lower 16 bits, along with the amount of set bits.
+    Bits(Box<[u64; WORDS]>, usize),
+}
+
+impl Container {
+    fn len(&self) -> usize {
+        match self {
+            Container::Array(values) => values.len(),
+            Container::Bits(_, len) => *len,
+        }
+    }
+}
+
+impl Bitmap {
+    /// Insert `value` and return true if it wasn't contained before.
+    pub fn insert(&mut self, value: u32) -> bool {
+        let (key, low) = split(value);
+        let index = match self.containers.binary_search_by_key(&key, |(key, _)| *key) {
+            Ok(index) => index,
+            Err(index) => {
+                self.containers.insert(index, (key, Container::Array(Vec::new())));
+                index
+            }
+        };
+        let container = &mut self.containers[index].1;
+        match container {
+            Container::Array(values) => match values.binary_search(&low) {
+                Ok(_) => false,
+                Err(pos) => {
+                    if values.len() < ARRAY_LIMIT {
+                        values.insert(pos, low);
+                    } else {
+                        let mut words = Box::new([0u64; WORDS]);
+                        for value in values.iter().copied().chain(Some(low)) {
+                            words[value as usize / 64] |= 1 << (value % 64);
+                        }
+                        *container = Container::Bits(words, ARRAY_LIMIT + 1);
+                    }
+                    true
+                }
+            },
+            Container::Bits(words, len) => {
+                let (word, mask) = (&mut words[low as usize / 64], 1 << (low % 64));
+                let inserted = *word & mask == 0;
+                *word |= mask;
+                *len += usize::from(inserted);
+                inserted
+            }
+        }
+    }
+
+    /// Return true if `value` is contained in the set.
+    pub fn contains(&self, value: u32) -> bool {
+        let (key, low) = split(value);
+        match self.containers.binary_search_by_key(&key, |(key, _)| *key) {
+            Ok(index) => match &self.containers[index].1 {
+                Container::Array(values) => values.binary_search(&low).is_ok(),
+                Container::Bits(words, _) => words[low as usize / 64] & (1 << (low % 64)) != 0,
+            },
+            Err(_) => false,
+        }
+    }
+
+    /// Remove all values, but keep all containers and their memory for reuse.
+    pub fn clear(&mut self) {
+        for (_, container) in self.containers.iter_mut() {
+            match container {
+                Container::Array(values) => values.clear(),
+                Container::Bits(words, len) => {
+                    words.iter_mut().for_each(|word| *word = 0);
+                    *len = 0;
+                }
+            }
+        }
+    }
+
+    /// Return the amount of values in the set.
+    pub fn len(&self) -> usize {
+        self.containers.iter().map(|(_, container)| container.len()).sum()
+    }
+
+    /// Return true if there is no value in the set.
+    pub fn is_empty(&self) -> bool {
+        self.containers.iter().all(|(_, container)| container.len() == 0)
+    }
+
+    /// Return all values in ascending order.
+    pub fn iter(&self) -> impl Iterator<Item = u32> + '_ {
+        self.containers.iter().flat_map(|(key, container)| {
+            let high = u32::from(*key) << 16;
+            let values: Box<dyn Iterator<Item = u32> + '_> = match container {
+                Container::Array(values) => Box::new(values.iter().map(move |low| high | u32::from(*low))),
+                Container::Bits(words, _) => Box::new(words.iter().enumerate().flat_map(move |(word_index, word)| {
+                    let word = *word;
+                    (0..64u32)
+                        .filter(move |bit| word & (1 << bit) != 0)
+                        .map(move |bit| high | (word_index as u32 * 64 + bit))
+                })),
+            };
+            values
+        })
+    }
+}
+
+impl PartialEq for Bitmap {
+    fn eq(&self, other: &Self) -> bool {
+        self.len() == other.len() && self.iter().eq(other.iter())
+    }
+}
+
+impl Eq for Bitmap {}
+
+fn split(value: u32) -> (u16, u16) {
+    ((value >> 16) as u16, value as u16)
+}
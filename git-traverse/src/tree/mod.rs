@@ -63,3 +63,7 @@ pub mod recorder;
 ///
 pub mod breadthfirst;
 pub use breadthfirst::impl_::traverse as breadthfirst;
+
+///
+pub mod parallel;
+pub use parallel::impl_::traverse as parallel;
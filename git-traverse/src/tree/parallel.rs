@@ -0,0 +1,173 @@
+use git_hash::{oid, ObjectId};
+use git_object::{
+    bstr::{BStr, BString, ByteVec},
+    tree::{Entry, EntryMode, EntryRef},
+    TreeRefIter,
+};
+
+use crate::tree::{breadthfirst::Error, visit::Action, Recorder};
+
+/// A trait to allow responding to entries observed during a [parallel traversal][impl_::traverse()].
+///
+/// As opposed to [`Visit`][crate::tree::Visit], the path of the tree containing each entry is passed explicitly as trees
+/// may be observed in any order.
+pub trait Delegate {
+    /// Observe an `entry` of the tree at `tree_path`, which is empty for the root tree, and return an instruction whether
+    /// to continue or not. [`Action::Skip`] prevents trees from being traversed, and has no effect on any other entry.
+    fn visit(&mut self, tree_path: &BStr, entry: &EntryRef<'_>) -> Action;
+}
+
+/// Options for use in [`traverse()`][impl_::traverse()].
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Options {
+    /// The amount of threads to use at most, or `None` to use all logical cores.
+    pub thread_limit: Option<usize>,
+    /// If true, the delegate observes entries in exactly the same order as it would when using
+    /// [`breadthfirst()`][crate::tree::breadthfirst()], at the cost of holding back entries of trees until all trees before them
+    /// were observed.
+    ///
+    /// Otherwise trees are observed in the order in which they were decoded, which is the same only if a single thread is used.
+    pub deterministic: bool,
+}
+
+impl Delegate for Recorder {
+    fn visit(&mut self, tree_path: &BStr, entry: &EntryRef<'_>) -> Action {
+        self.records.push(crate::tree::recorder::Entry {
+            mode: entry.mode,
+            filepath: join(tree_path, entry.filename),
+            oid: entry.oid.to_owned(),
+        });
+        Action::Continue
+    }
+}
+
+pub(crate) mod impl_ {
+    use super::*;
+
+    /// Traverse the tree with id `root` and all of its subtrees breadth-first, decoding trees on multiple threads and passing
+    /// all of their entries to `delegate` on the calling thread.
+    ///
+    /// * `find` is cloned on each thread to lookup trees by id, writing their data into the given buffer and returning an iterator
+    ///   over their entries. Not finding a tree is an error.
+    /// * `delegate` observes all entries in the order specified by [`Options::deterministic`].
+    ///
+    /// All trees of the same depth are distributed among the threads, with idle threads picking up the next tree that wasn't decoded
+    /// yet, so that large trees don't hold up the others. Note that without the `parallel` feature of `git-features`, everything happens
+    /// on the calling thread.
+    pub fn traverse<Find, D>(
+        root: &oid,
+        find: Find,
+        delegate: &mut D,
+        Options {
+            thread_limit,
+            deterministic,
+        }: Options,
+    ) -> Result<(), Error>
+    where
+        Find: for<'a> FnMut(&oid, &'a mut Vec<u8>) -> Option<TreeRefIter<'a>> + Send + Clone,
+        D: Delegate,
+    {
+        let mut trees = vec![(root.to_owned(), BString::default())];
+        while !trees.is_empty() {
+            let mut next = Vec::new();
+            let buffered = git_features::parallel::in_parallel(
+                std::mem::take(&mut trees).into_iter().enumerate(),
+                thread_limit,
+                {
+                    let find = find.clone();
+                    move |_thread_id| (find.clone(), Vec::new())
+                },
+                |(index, (id, path)), (find, buf)| -> Result<Tree, Error> {
+                    let entries = find(&id, buf)
+                        .ok_or(Error::NotFound { oid: id })?
+                        .map(|entry| entry.map(Entry::from))
+                        .collect::<Result<Vec<_>, _>>()?;
+                    Ok(Tree { index, path, entries })
+                },
+                Reducer {
+                    delegate: &mut *delegate,
+                    next: &mut next,
+                    buffered: deterministic.then(Vec::new),
+                },
+            )?;
+            if let Some(mut buffered) = buffered {
+                buffered.sort_by_key(|tree| tree.index);
+                for tree in buffered {
+                    observe(tree, delegate, &mut next)?;
+                }
+            }
+            trees = next;
+        }
+        Ok(())
+    }
+}
+
+/// A decoded tree, along with its position in the list of trees to decode.
+struct Tree {
+    index: usize,
+    path: BString,
+    entries: Vec<Entry>,
+}
+
+struct Reducer<'a, D> {
+    delegate: &'a mut D,
+    next: &'a mut Vec<(ObjectId, BString)>,
+    /// Trees to observe once all of them were decoded, if the order matters.
+    buffered: Option<Vec<Tree>>,
+}
+
+impl<'a, D> git_features::parallel::Reduce for Reducer<'a, D>
+where
+    D: Delegate,
+{
+    type Input = Result<Tree, Error>;
+    type FeedProduce = ();
+    type Output = Option<Vec<Tree>>;
+    type Error = Error;
+
+    fn feed(&mut self, item: Self::Input) -> Result<Self::FeedProduce, Self::Error> {
+        let tree = item?;
+        match self.buffered.as_mut() {
+            Some(buffered) => {
+                buffered.push(tree);
+                Ok(())
+            }
+            None => observe(tree, self.delegate, self.next),
+        }
+    }
+
+    fn finalize(self) -> Result<Self::Output, Self::Error> {
+        Ok(self.buffered)
+    }
+}
+
+/// Pass all entries of `tree` to `delegate` and remember all trees to traverse in `next`.
+fn observe(tree: Tree, delegate: &mut impl Delegate, next: &mut Vec<(ObjectId, BString)>) -> Result<(), Error> {
+    for entry in &tree.entries {
+        let action = delegate.visit(
+            tree.path.as_ref(),
+            &EntryRef {
+                mode: entry.mode,
+                filename: entry.filename.as_ref(),
+                oid: &entry.oid,
+            },
+        );
+        match action {
+            Action::Cancel => return Err(Error::Cancelled),
+            Action::Continue if entry.mode == EntryMode::Tree => {
+                next.push((entry.oid, join(tree.path.as_ref(), entry.filename.as_ref())))
+            }
+            Action::Continue | Action::Skip => {}
+        }
+    }
+    Ok(())
+}
+
+fn join(tree_path: &BStr, name: &BStr) -> BString {
+    let mut path = tree_path.to_owned();
+    if !path.is_empty() {
+        path.push(b'/');
+    }
+    path.push_str(name);
+    path
+}
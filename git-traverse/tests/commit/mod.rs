@@ -270,3 +270,649 @@ mod ancestor {
         .check()
     }
 }
+
+mod topo {
+    use git_hash::ObjectId;
+    use git_odb::pack::FindExt;
+    use git_traverse::commit::{self, topo};
+
+    use crate::hex_to_id;
+
+    struct Options {
+        sorting: topo::Sorting,
+        parents: commit::Parents,
+        boundary: bool,
+        commit_graph: bool,
+        max_count: Option<usize>,
+        max_depth: Option<usize>,
+        since: Option<u32>,
+        until: Option<u32>,
+    }
+
+    impl Default for Options {
+        fn default() -> Self {
+            Options {
+                sorting: topo::Sorting::TopoOrder,
+                parents: commit::Parents::All,
+                boundary: false,
+                commit_graph: false,
+                max_count: None,
+                max_depth: None,
+                since: None,
+                until: None,
+            }
+        }
+    }
+
+    fn traverse(
+        init_script: &str,
+        tips: &[&str],
+        hidden: &[&str],
+        options: Options,
+    ) -> crate::Result<Vec<(ObjectId, bool)>> {
+        Ok(infos(init_script, tips, hidden, options)?
+            .into_iter()
+            .map(|info| (info.id, info.is_boundary))
+            .collect())
+    }
+
+    fn infos(
+        init_script: &str,
+        tips: &[&str],
+        hidden: &[&str],
+        Options {
+            sorting,
+            parents,
+            boundary,
+            commit_graph,
+            max_count,
+            max_depth,
+            since,
+            until,
+        }: Options,
+    ) -> crate::Result<Vec<topo::Info>> {
+        let dir = git_testtools::scripted_fixture_repo_read_only(init_script)?;
+        let store = git_odb::at(dir.join(".git").join("objects"))?;
+        let commit_graph = commit_graph
+            .then(|| git_commitgraph::Graph::from_info_dir(dir.join(".git").join("objects").join("info")))
+            .transpose()?;
+        Ok(topo::Builder::new(tips.iter().copied().map(hex_to_id), |oid, buf| {
+            store.find_commit_iter(oid, buf).map(|t| t.0)
+        })
+        .with_hidden(hidden.iter().copied().map(hex_to_id))
+        .sorting(sorting)
+        .parents(parents)
+        .boundary(boundary)
+        .with_commit_graph(commit_graph)
+        .max_count(max_count)
+        .max_depth(max_depth)
+        .since(since)
+        .until(until)
+        .build()?
+        .collect())
+    }
+
+    fn expected(ids: &[&str], boundary: &[&str]) -> Vec<(ObjectId, bool)> {
+        ids.iter()
+            .map(|id| (hex_to_id(id), false))
+            .chain(boundary.iter().map(|id| (hex_to_id(id), true)))
+            .collect()
+    }
+
+    const M1B1: &str = "01ec18a3ebf2855708ad3c9d244306bc1fae3e9b";
+    const C5: &str = "efd9a841189668f1bab5b8ebade9cd0a1b139a37";
+    const B1C2: &str = "ce2e8ffaa9608a26f7b21afc1db89cadb54fd353";
+    const B1C1: &str = "9152eeee2328073cf23dcf8e90c949170b711659";
+    const C4: &str = "9556057aee5abb06912922e9f26c46386a816822";
+    const C3: &str = "17d78c64cef6c33a10a604573fd2c429e477fd63";
+    const C2: &str = "9902e3c3e8f0c569b4ab295ddf473e6de763e1e7";
+    const C1: &str = "134385f6d781b7e97062102c6a483440bfda2a03";
+
+    #[test]
+    fn topo_order_keeps_lines_of_history_together() -> crate::Result {
+        let actual = traverse("make_traversal_repo_for_commits.sh", &[M1B1], &[], Default::default())?;
+        assert_eq!(actual, expected(&[M1B1, B1C2, B1C1, C5, C4, C3, C2, C1], &[]));
+        Ok(())
+    }
+
+    #[test]
+    fn topo_order_with_multiple_tips_returns_each_commit_once() -> crate::Result {
+        let actual = traverse(
+            "make_traversal_repo_for_commits.sh",
+            &[M1B1, C5, B1C1],
+            &[],
+            Default::default(),
+        )?;
+        assert_eq!(actual, expected(&[M1B1, B1C2, B1C1, C5, C4, C3, C2, C1], &[]));
+        Ok(())
+    }
+
+    #[test]
+    fn date_order_intermixes_lines_of_history() -> crate::Result {
+        let actual = traverse(
+            "make_traversal_repo_for_commits.sh",
+            &[M1B1],
+            &[],
+            Options {
+                sorting: topo::Sorting::DateOrder,
+                ..Default::default()
+            },
+        )?;
+        assert_eq!(actual, expected(&[M1B1, C5, B1C2, B1C1, C4, C3, C2, C1], &[]));
+        Ok(())
+    }
+
+    #[test]
+    fn hidden_commits_and_their_ancestors_are_excluded() -> crate::Result {
+        let actual = traverse("make_traversal_repo_for_commits.sh", &[M1B1], &[C4], Default::default())?;
+        assert_eq!(actual, expected(&[M1B1, B1C2, B1C1, C5], &[]));
+        Ok(())
+    }
+
+    #[test]
+    fn boundary_commits_are_returned_last() -> crate::Result {
+        let actual = traverse(
+            "make_traversal_repo_for_commits.sh",
+            &[M1B1],
+            &[C4],
+            Options {
+                boundary: true,
+                ..Default::default()
+            },
+        )?;
+        assert_eq!(actual, expected(&[M1B1, B1C2, B1C1, C5], &[C4]));
+
+        let actual = traverse(
+            "make_traversal_repo_for_commits.sh",
+            &[M1B1],
+            &[B1C2],
+            Options {
+                sorting: topo::Sorting::DateOrder,
+                boundary: true,
+                ..Default::default()
+            },
+        )?;
+        assert_eq!(actual, expected(&[M1B1, C5], &[C4, B1C2]));
+        Ok(())
+    }
+
+    #[test]
+    fn hiding_a_tip_yields_nothing() -> crate::Result {
+        let actual = traverse(
+            "make_traversal_repo_for_commits.sh",
+            &[C4],
+            &[M1B1],
+            Options {
+                boundary: true,
+                ..Default::default()
+            },
+        )?;
+        assert_eq!(actual, expected(&[], &[]));
+        Ok(())
+    }
+
+    #[test]
+    fn first_parent_only() -> crate::Result {
+        let actual = traverse(
+            "make_traversal_repo_for_commits.sh",
+            &[M1B1],
+            &[],
+            Options {
+                parents: commit::Parents::First,
+                ..Default::default()
+            },
+        )?;
+        assert_eq!(actual, expected(&[M1B1, C5, C4, C3, C2, C1], &[]));
+        Ok(())
+    }
+
+    #[test]
+    fn date_order_with_dates_and_boundary() -> crate::Result {
+        let actual = traverse(
+            "make_traversal_repo_for_commits_with_dates.sh",
+            &["288e509293165cb5630d08f4185bdf2445bf6170"],
+            &[C2],
+            Options {
+                sorting: topo::Sorting::DateOrder,
+                boundary: true,
+                ..Default::default()
+            },
+        )?;
+        assert_eq!(
+            actual,
+            expected(
+                &[
+                    "288e509293165cb5630d08f4185bdf2445bf6170",
+                    "bcb05040a6925f2ff5e10d3ae1f9264f2e8c43ac"
+                ],
+                &[C2, C1]
+            )
+        );
+        Ok(())
+    }
+
+    mod limits {
+        use super::*;
+
+        #[test]
+        fn depth_is_the_smallest_distance_to_any_tip() -> crate::Result {
+            let actual: Vec<_> = infos("make_traversal_repo_for_commits.sh", &[M1B1], &[], Default::default())?
+                .into_iter()
+                .map(|info| (info.id, info.depth))
+                .collect();
+            let expected: Vec<_> = [
+                (M1B1, 0),
+                (B1C2, 1),
+                (B1C1, 2),
+                (C5, 1),
+                (C4, 2),
+                (C3, 3),
+                (C2, 4),
+                (C1, 5),
+            ]
+            .iter()
+            .map(|(id, depth)| (hex_to_id(id), *depth))
+            .collect();
+            assert_eq!(actual, expected);
+
+            let actual: Vec<_> = infos(
+                "make_traversal_repo_for_commits.sh",
+                &[M1B1, C3],
+                &[],
+                Default::default(),
+            )?
+            .into_iter()
+            .map(|info| info.depth)
+            .collect();
+            assert_eq!(actual, [0, 1, 2, 1, 2, 0, 1, 2], "all tips have a depth of 0");
+            Ok(())
+        }
+
+        #[test]
+        fn max_depth_stops_the_traversal_like_a_shallow_clone() -> crate::Result {
+            let actual = traverse(
+                "make_traversal_repo_for_commits.sh",
+                &[M1B1],
+                &[],
+                Options {
+                    max_depth: Some(2),
+                    boundary: true,
+                    ..Default::default()
+                },
+            )?;
+            assert_eq!(
+                actual,
+                expected(&[M1B1, B1C2, B1C1, C5, C4], &[]),
+                "the same as `git clone --depth 3`, without boundary as parents of the deepest commits aren't traversed"
+            );
+
+            let actual = traverse(
+                "make_traversal_repo_for_commits.sh",
+                &[M1B1],
+                &[],
+                Options {
+                    max_depth: Some(2),
+                    parents: commit::Parents::First,
+                    ..Default::default()
+                },
+            )?;
+            assert_eq!(actual, expected(&[M1B1, C5, C4], &[]));
+
+            let actual = traverse(
+                "make_traversal_repo_for_commits.sh",
+                &[M1B1],
+                &[],
+                Options {
+                    max_depth: Some(0),
+                    ..Default::default()
+                },
+            )?;
+            assert_eq!(actual, expected(&[M1B1], &[]));
+            Ok(())
+        }
+
+        #[test]
+        fn max_count_with_boundary() -> crate::Result {
+            let actual = traverse(
+                "make_traversal_repo_for_commits.sh",
+                &[M1B1],
+                &[],
+                Options {
+                    max_count: Some(3),
+                    boundary: true,
+                    ..Default::default()
+                },
+            )?;
+            assert_eq!(
+                actual,
+                expected(&[M1B1, B1C2, B1C1], &[C5, C4]),
+                "like `git log --topo-order --boundary -n3`"
+            );
+            Ok(())
+        }
+
+        const DATES_M1B1: &str = "288e509293165cb5630d08f4185bdf2445bf6170";
+        const DATES_B1C1: &str = "bcb05040a6925f2ff5e10d3ae1f9264f2e8c43ac";
+        const DATES_C2: &str = "9902e3c3e8f0c569b4ab295ddf473e6de763e1e7";
+        const DATES_C1: &str = "134385f6d781b7e97062102c6a483440bfda2a03";
+
+        #[test]
+        fn since_hides_older_commits_and_their_ancestors() -> crate::Result {
+            let actual = traverse(
+                "make_traversal_repo_for_commits_with_dates.sh",
+                &[DATES_M1B1],
+                &[],
+                Options {
+                    since: Some(978307200), /* 2001-01-01 */
+                    boundary: true,
+                    ..Default::default()
+                },
+            )?;
+            assert_eq!(
+                actual,
+                expected(&[DATES_M1B1, DATES_B1C1], &[DATES_C2, DATES_C1]),
+                "like `git log --topo-order --boundary --since=2001-01-01`"
+            );
+            Ok(())
+        }
+
+        #[test]
+        fn until_only_skips_newer_commits() -> crate::Result {
+            let actual = traverse(
+                "make_traversal_repo_for_commits_with_dates.sh",
+                &[DATES_M1B1],
+                &[],
+                Options {
+                    until: Some(991353600), /* 2001-06-01 */
+                    sorting: topo::Sorting::DateOrder,
+                    ..Default::default()
+                },
+            )?;
+            assert_eq!(
+                actual,
+                expected(&[DATES_B1C1, DATES_C2, DATES_C1], &[]),
+                "like `git log --date-order --until=2001-06-01`"
+            );
+            Ok(())
+        }
+    }
+
+    mod with_commit_graph {
+        use super::*;
+
+        const N1: &str = "5df8c11a639855141f1e2696f629e49324a931a2";
+
+        fn options() -> Options {
+            Options {
+                commit_graph: true,
+                ..Default::default()
+            }
+        }
+
+        #[test]
+        fn yields_the_same_result_as_without_it() -> crate::Result {
+            for sorting in [topo::Sorting::TopoOrder, topo::Sorting::DateOrder] {
+                for (tips, hidden) in [
+                    (&[M1B1][..], &[][..]),
+                    (&[M1B1], &[C4]),
+                    (&[M1B1], &[B1C2]),
+                    (&[N1], &[C5]),
+                    (&[C4], &[M1B1]),
+                ] {
+                    for parents in [commit::Parents::All, commit::Parents::First] {
+                        let options = || Options {
+                            sorting,
+                            parents,
+                            boundary: true,
+                            ..Default::default()
+                        };
+                        assert_eq!(
+                            traverse(
+                                "make_traversal_repo_for_commits_with_graph.sh",
+                                tips,
+                                hidden,
+                                Options {
+                                    commit_graph: true,
+                                    ..options()
+                                }
+                            )?,
+                            traverse("make_traversal_repo_for_commits_with_graph.sh", tips, hidden, options())?,
+                            "{:?} first-parent: {} {:?} {:?}",
+                            sorting,
+                            matches!(parents, commit::Parents::First),
+                            tips,
+                            hidden
+                        );
+                    }
+                }
+            }
+            Ok(())
+        }
+
+        #[test]
+        fn commits_outside_of_the_graph_are_read_from_the_object_database() -> crate::Result {
+            let actual = traverse(
+                "make_traversal_repo_for_commits_with_graph.sh",
+                &[N1],
+                &[B1C1],
+                options(),
+            )?;
+            assert_eq!(actual, expected(&[N1, M1B1, B1C2, C5], &[]));
+            Ok(())
+        }
+    }
+
+    mod with_paths {
+        use git_odb::pack::FindExt;
+        use git_traverse::commit::topo;
+
+        use crate::hex_to_id;
+
+        const NAMES: &[(&str, &str)] = &[
+            ("c2", "7c2d235a5da4b0addc8ee25dc2c89569eba43d23"),
+            ("m1", "fe3bad63f8a1443027f7edac32d0336f3b0e772a"),
+            ("c1", "28406dd44b1511b81a73f29faa08a3acf82f8c6e"),
+            ("a3", "bf801e2ef2afde4028dfe2074c3b493f6de217c8"),
+            ("x3", "517457c1ac272ecc477ad996fbe8b762e0f01a7d"),
+            ("b2", "8da25324728e01cc5895ff7bc69192bcf09d0aef"),
+            ("x2", "488465dc65c1225cf7f883d91c317eedd2676a49"),
+            ("a2", "ef701f985b0282a33bd64a587f4cfd5c86205c78"),
+            ("root", "b9010c955c7b6127086e51cc285370ff3173a462"),
+        ];
+
+        fn id(name: &str) -> git_hash::ObjectId {
+            hex_to_id(NAMES.iter().find(|(n, _)| *n == name).expect("known name").1)
+        }
+
+        fn name(id: &git_hash::oid) -> &'static str {
+            NAMES.iter().find(|(_, hex)| hex_to_id(hex) == id).expect("known id").0
+        }
+
+        /// Return lines similar to `git log --parents --boundary --format='%m %s | %P' <tip> ^<hidden> -- <paths>`.
+        fn traverse(tip: &str, hidden: &[&str], paths: &[&str], sorting: topo::Sorting) -> crate::Result<Vec<String>> {
+            let dir = git_testtools::scripted_fixture_repo_read_only("make_traversal_repo_for_commits_with_paths.sh")?;
+            let store = git_odb::at(dir.join(".git").join("objects"))?;
+            Ok(
+                topo::Builder::new(Some(id(tip)), |oid, buf| store.find_commit_iter(oid, buf).map(|t| t.0))
+                    .with_hidden(hidden.iter().copied().map(id))
+                    .sorting(sorting)
+                    .boundary(true)
+                    .with_paths(paths.iter().copied(), |oid, buf| {
+                        store.find_tree_iter(oid, buf).map(|t| t.0)
+                    })
+                    .build()?
+                    .map(|info| {
+                        format!(
+                            "{} {} | {}",
+                            if info.is_boundary { "-" } else { ">" },
+                            name(&info.id),
+                            info.parent_ids.iter().map(|id| name(id)).collect::<Vec<_>>().join(" ")
+                        )
+                    })
+                    .collect(),
+            )
+        }
+
+        #[test]
+        fn merges_are_simplified_to_the_parent_with_the_same_content() -> crate::Result {
+            for sorting in [topo::Sorting::TopoOrder, topo::Sorting::DateOrder] {
+                assert_eq!(
+                    traverse("c2", &[], &["a"], sorting)?,
+                    ["> a3 | a2", "> a2 | root", "> root | "]
+                );
+                assert_eq!(traverse("c2", &[], &["b"], sorting)?, ["> b2 | root", "> root | "]);
+                assert_eq!(
+                    traverse("c2", &[], &["dir"], sorting)?,
+                    ["> x3 | x2", "> x2 | root", "> root | "]
+                );
+                assert_eq!(
+                    traverse("c2", &[], &["dir/x"], sorting)?,
+                    traverse("c2", &[], &["dir"], sorting)?,
+                    "files and their directories are equivalent here"
+                );
+            }
+            Ok(())
+        }
+
+        #[test]
+        fn merges_that_change_paths_relative_to_all_parents_are_kept() -> crate::Result {
+            for sorting in [topo::Sorting::TopoOrder, topo::Sorting::DateOrder] {
+                assert_eq!(
+                    traverse("c2", &[], &["a", "dir/"], sorting)?,
+                    [
+                        "> m1 | x3 a3",
+                        "> a3 | x2",
+                        "> x3 | x2",
+                        "> x2 | a2",
+                        "> a2 | root",
+                        "> root | "
+                    ]
+                );
+            }
+            Ok(())
+        }
+
+        #[test]
+        fn parents_are_removed_if_no_ancestor_changed_paths() -> crate::Result {
+            assert_eq!(
+                traverse("c2", &[], &["c"], topo::Sorting::TopoOrder)?,
+                ["> c2 | c1", "> c1 | "]
+            );
+            assert!(traverse("c2", &[], &["does-not-exist"], topo::Sorting::TopoOrder)?.is_empty());
+            Ok(())
+        }
+
+        #[test]
+        fn bloom_filters_of_the_commit_graph_avoid_most_tree_lookups() -> crate::Result {
+            let dir = git_testtools::scripted_fixture_repo_read_only("make_traversal_repo_for_commits_with_paths.sh")?;
+            let store = git_odb::at(dir.join(".git").join("objects"))?;
+            for paths in [
+                &["a"][..],
+                &["dir/x"],
+                &["a", "dir/"],
+                &["c"],
+                &["does-not-exist"],
+                &[""],
+            ] {
+                let mut results = Vec::new();
+                for use_commit_graph in [false, true] {
+                    let commit_graph = use_commit_graph
+                        .then(|| git_commitgraph::Graph::from_info_dir(dir.join(".git").join("objects").join("info")))
+                        .transpose()?;
+                    let tree_lookups = std::cell::Cell::new(0);
+                    let infos: Vec<_> =
+                        topo::Builder::new(Some(id("c2")), |oid, buf| store.find_commit_iter(oid, buf).map(|t| t.0))
+                            .with_commit_graph(commit_graph)
+                            .with_paths(paths.iter().copied(), |oid, buf| {
+                                tree_lookups.set(tree_lookups.get() + 1);
+                                store.find_tree_iter(oid, buf).map(|t| t.0)
+                            })
+                            .build()?
+                            .collect();
+                    results.push((infos, tree_lookups.get()));
+                }
+                let (without_graph, with_graph) = (&results[0], &results[1]);
+                assert_eq!(with_graph.0, without_graph.0, "{:?}: the result is the same", paths);
+                if paths != [""] {
+                    assert!(
+                        with_graph.1 < without_graph.1,
+                        "{:?}: {} tree lookups with bloom filters, {} without",
+                        paths,
+                        with_graph.1,
+                        without_graph.1
+                    );
+                }
+            }
+            Ok(())
+        }
+
+        #[test]
+        fn parents_are_rewritten_up_to_hidden_commits() -> crate::Result {
+            for sorting in [topo::Sorting::TopoOrder, topo::Sorting::DateOrder] {
+                assert_eq!(
+                    traverse("c2", &["x3"], &["a", "dir"], sorting)?,
+                    ["> m1 | x3 a3", "> a3 | x2", "- x3 | x2", "- x2 | a2"]
+                );
+                assert_eq!(traverse("c2", &["x3"], &["a"], sorting)?, ["> a3 | x2", "- x2 | a2"]);
+                assert!(traverse("c2", &["x3"], &["dir"], sorting)?.is_empty());
+                assert_eq!(
+                    traverse("c2", &["b2"], &["a", "dir"], sorting)?,
+                    ["> m1 | x3 a3", "> a3 | b2", "> x3 | x2", "- b2 | x2", "- x2 | a2"]
+                );
+                assert_eq!(traverse("c2", &["b2"], &["a"], sorting)?, ["> a3 | b2", "- b2 | x2"]);
+                assert_eq!(
+                    traverse("c2", &["a2"], &["dir"], sorting)?,
+                    ["> x3 | x2", "> x2 | a2", "- a2 | root"]
+                );
+            }
+            Ok(())
+        }
+    }
+}
+
+mod is_ancestor {
+    use git_odb::pack::FindExt;
+    use git_traverse::commit;
+
+    use crate::hex_to_id;
+
+    const M1B1: &str = "01ec18a3ebf2855708ad3c9d244306bc1fae3e9b";
+    const C5: &str = "efd9a841189668f1bab5b8ebade9cd0a1b139a37";
+    const B1C2: &str = "ce2e8ffaa9608a26f7b21afc1db89cadb54fd353";
+    const C4: &str = "9556057aee5abb06912922e9f26c46386a816822";
+    const C1: &str = "134385f6d781b7e97062102c6a483440bfda2a03";
+    const N1: &str = "5df8c11a639855141f1e2696f629e49324a931a2";
+
+    #[test]
+    fn with_and_without_commit_graph() -> crate::Result {
+        let dir = git_testtools::scripted_fixture_repo_read_only("make_traversal_repo_for_commits_with_graph.sh")?;
+        let store = git_odb::at(dir.join(".git").join("objects"))?;
+        let commit_graph = git_commitgraph::Graph::from_info_dir(dir.join(".git").join("objects").join("info"))?;
+        for graph in [None, Some(&commit_graph)] {
+            for (ancestor, descendant, expected) in [
+                (M1B1, M1B1, true),
+                (C1, M1B1, true),
+                (B1C2, M1B1, true),
+                (C4, C5, true),
+                (C4, N1, true),
+                (M1B1, N1, true),
+                (M1B1, C1, false),
+                (C5, B1C2, false),
+                (B1C2, C5, false),
+                (N1, M1B1, false),
+            ] {
+                assert_eq!(
+                    commit::is_ancestor(&hex_to_id(ancestor), &hex_to_id(descendant), graph, |oid, buf| {
+                        store.find_commit_iter(oid, buf).map(|t| t.0)
+                    })?,
+                    expected,
+                    "{} is ancestor of {}, with commit-graph: {}",
+                    ancestor,
+                    descendant,
+                    graph.is_some()
+                );
+            }
+        }
+        Ok(())
+    }
+}
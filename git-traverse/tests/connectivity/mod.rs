@@ -0,0 +1,136 @@
+use git_hash::ObjectId;
+use git_object::Kind;
+use git_odb::pack::Find;
+use git_traverse::{
+    connectivity::{self, Missing},
+    set::{self, Seen},
+};
+
+use crate::hex_to_id;
+
+fn check(state: &mut connectivity::State, tips: &[&str]) -> crate::Result<connectivity::Outcome> {
+    check_with(state, tips)
+}
+
+fn check_with<S: Seen>(state: &mut connectivity::State<S>, tips: &[&str]) -> crate::Result<connectivity::Outcome> {
+    let dir = git_testtools::scripted_fixture_repo_read_only("make_repo_with_missing_objects.sh")?;
+    let store = git_odb::at(dir.join(".git").join("objects"))?;
+    Ok(state.check(tips.iter().copied().map(hex_to_id), |id, buf| {
+        store.try_find(id, buf).map(|obj| obj.map(|(data, _location)| data))
+    })?)
+}
+
+const MAIN: &str = "29b42758880e0979cd81ae690688255efea5424d";
+const TAG: &str = "d6c4446353eba934cb10ee54379120af02def0d2";
+const MISSING_BLOB: &str = "b287eaa18bb819aa8ebe8d7adfeff4720aa0bd6a";
+const MISSING_PARENT: &str = "2a4553a755cfb24de2717989d0aa2e591db400e3";
+
+#[test]
+fn complete_history_has_no_missing_objects() -> crate::Result {
+    let outcome = check(&mut Default::default(), &[TAG])?;
+    assert_eq!(
+        outcome,
+        connectivity::Outcome {
+            commits: 2,
+            trees: 3,
+            blobs: 3,
+            tags: 1,
+            missing: Vec::new(),
+        },
+        "the tree of the first commit is reused in the second one except for `b`"
+    );
+    Ok(())
+}
+
+#[test]
+fn missing_objects_are_reported_with_the_object_referring_to_them() -> crate::Result {
+    let outcome = check(&mut Default::default(), &[MISSING_BLOB, MISSING_PARENT])?;
+    assert_eq!(
+        outcome.missing,
+        vec![
+            Missing {
+                id: hex_to_id("591da4534003affbcb6a7ca29da88dccf1378d6e"),
+                expected_kind: Some(Kind::Commit),
+                referenced_by: Some(hex_to_id(MISSING_PARENT)),
+            },
+            Missing {
+                id: hex_to_id("33e45d56f88993aae6a0198013efa80716fd8919"),
+                expected_kind: Some(Kind::Blob),
+                referenced_by: Some(hex_to_id("9e2c4e2713880c51ab87c5ce23b574ffaced3d00")),
+            },
+        ]
+    );
+
+    let missing_tip = ObjectId::from_hex(b"0000000000000000000000000000000000000001")?;
+    let outcome = check(&mut Default::default(), &["0000000000000000000000000000000000000001"])?;
+    assert_eq!(
+        outcome.missing,
+        vec![Missing {
+            id: missing_tip,
+            expected_kind: None,
+            referenced_by: None,
+        }],
+        "tips may be missing as well"
+    );
+    Ok(())
+}
+
+#[test]
+fn the_state_remembers_what_was_checked_before() -> crate::Result {
+    let mut state = connectivity::State::default();
+    let outcome = check(&mut state, &[MAIN])?;
+    assert_eq!(outcome.commits, 2);
+    assert!(state.has_seen(&hex_to_id(MAIN)));
+
+    let outcome = check(&mut state, &[MISSING_BLOB])?;
+    assert_eq!(
+        (outcome.commits, outcome.trees, outcome.blobs),
+        (1, 2, 0),
+        "only the new commit and the trees leading to the missing blob are visited"
+    );
+    assert_eq!(outcome.missing.len(), 1);
+
+    let mut state = connectivity::State::default();
+    state.mark_seen(Some(hex_to_id("591da4534003affbcb6a7ca29da88dccf1378d6e")));
+    let outcome = check(&mut state, &[MISSING_PARENT])?;
+    assert!(
+        outcome.missing.is_empty(),
+        "objects known to be missing, like parents of shallow commits, can be excluded"
+    );
+    assert_eq!(outcome.commits, 1);
+    Ok(())
+}
+
+#[test]
+fn indexed_sets_yield_the_same_result_as_hash_sets() -> crate::Result {
+    let tips = [TAG, MISSING_BLOB, MISSING_PARENT];
+    let mut state = connectivity::State::default();
+    let expected = check(&mut state, &tips)?;
+
+    let mut ids: Vec<_> = state.seen().iter().copied().collect();
+    ids.sort();
+    let positions: std::collections::HashMap<_, _> = ids
+        .iter()
+        .step_by(2)
+        .enumerate()
+        .map(|(index, id)| (*id, index as u32 * 70_000))
+        .collect();
+    let mut state =
+        connectivity::State::with_seen(set::Indexed::new(move |id: &git_hash::oid| positions.get(id).copied()));
+    assert_eq!(
+        check_with(&mut state, &tips)?,
+        expected,
+        "objects without position are kept separately"
+    );
+    assert_eq!(state.seen().len(), ids.len());
+    assert_eq!(state.seen().bitmap().len(), (ids.len() + 1) / 2);
+
+    state.clear();
+    assert!(state.seen().is_empty());
+    assert_eq!(
+        check_with(&mut state, &tips)?,
+        expected,
+        "after clearing, everything is visited again"
+    );
+    Ok(())
+}
@@ -0,0 +1,50 @@
+#!/bin/bash
+set -eu -o pipefail
+
+function baseline() {
+  local name=${1:?}
+  shift
+  git rev-parse "$@" > "../$name.tips"
+  git rev-list --objects "$@" | cut -d' ' -f1 | sort > "../$name.baseline"
+}
+
+mkdir repo
+cd repo
+git init -q
+
+git checkout -q -b main
+mkdir dir
+for round in $(seq 5); do
+  echo "$round" > "file$round"
+  echo "$round" > "dir/file$round"
+  git add .
+  git commit -q -m "c$round"
+done
+git tag -m "annotated" v1 HEAD~2
+git branch other HEAD~3
+
+git checkout -q -b side other
+echo side > dir/side
+git add .
+git commit -q -m "side"
+git checkout -q main
+git merge -q --no-ff -m "merge side" side
+
+git repack -adbq
+
+# these objects are not in the pack, hence the commits can't have bitmaps
+echo after > dir/after
+git add .
+git commit -q -m "after repack"
+git checkout -q -b loose-side side
+echo loose > loose
+git add .
+git commit -q -m "loose side"
+git checkout -q main
+
+baseline main main
+baseline loose-side loose-side
+baseline v1 v1
+baseline main-without-other main ^other
+baseline main-without-loose-side main ^loose-side
+baseline tags-and-branches v1 loose-side main ^other
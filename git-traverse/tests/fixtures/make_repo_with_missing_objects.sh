@@ -0,0 +1,31 @@
+#!/bin/bash
+set -eu -o pipefail
+
+function remove_object() {
+  local id=${1:?}
+  rm ".git/objects/${id:0:2}/${id:2}"
+}
+
+git init -q
+
+git checkout -q -b main
+mkdir dir
+echo a > dir/a
+echo b > b
+git add .
+git commit -q -m c1
+echo c > b
+git add b
+git commit -q -m c2
+git tag -m "annotated" v1
+
+git checkout -q -b missing-blob
+echo missing > dir/missing
+git add dir/missing
+git commit -q -m "with missing blob"
+remove_object "$(git rev-parse HEAD:dir/missing)"
+
+git checkout -q -b missing-parent main
+git commit -q --allow-empty -m "parent to be removed"
+git commit -q --allow-empty -m "child of removed parent"
+remove_object "$(git rev-parse HEAD~1)"
@@ -0,0 +1,33 @@
+#!/bin/bash
+set -eu -o pipefail
+
+function write_and_commit() {
+  local path=${1:?}
+  local content=${2:?}
+  mkdir -p "$(dirname "$path")"
+  echo "$content" > "$path"
+  git add "$path"
+  git commit -q -m "$path: $content"
+}
+
+git init -q
+git config merge.ff false
+
+git checkout -q -b main
+mkdir dir
+echo 1 > a && echo 1 > b && echo 1 > dir/x
+git add .
+git commit -q -m c1
+write_and_commit a 2
+write_and_commit dir/x 2
+
+git checkout -q -b branch1
+write_and_commit b 2
+write_and_commit a 3
+
+git checkout -q main
+write_and_commit dir/x 3
+write_and_commit c 1
+git merge -q branch1 -m m1
+write_and_commit c 2
+git commit-graph write --no-progress --reachable --changed-paths
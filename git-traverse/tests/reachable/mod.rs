@@ -0,0 +1,103 @@
+use std::path::{Path, PathBuf};
+
+use git_hash::ObjectId;
+use git_odb::pack::FindExt;
+use git_traverse::reachable;
+
+fn fixture() -> crate::Result<PathBuf> {
+    git_testtools::scripted_fixture_repo_read_only("make_repo_with_bitmaps.sh")
+}
+
+fn bitmaps(dir: &Path) -> crate::Result<git_odb::pack::bitmap::File> {
+    let pack_dir = dir.join("repo").join(".git").join("objects").join("pack");
+    let index_path = std::fs::read_dir(&pack_dir)?
+        .map(|entry| entry.map(|entry| entry.path()))
+        .collect::<Result<Vec<_>, _>>()?
+        .into_iter()
+        .find(|path| path.extension().map_or(false, |ext| ext == "idx"))
+        .expect("one pack was written");
+    let index = git_odb::pack::index::File::at(&index_path, git_hash::Kind::Sha1)?;
+    Ok(git_odb::pack::bitmap::File::at(
+        index_path.with_extension("bitmap"),
+        &index,
+    )?)
+}
+
+/// Return the tips and hidden tips, along with all objects expected to be reachable, as sorted list.
+fn baseline(dir: &Path, name: &str) -> crate::Result<(Vec<ObjectId>, Vec<ObjectId>, Vec<ObjectId>)> {
+    let (mut tips, mut hidden) = (Vec::new(), Vec::new());
+    for line in std::fs::read_to_string(dir.join(format!("{}.tips", name)))?.lines() {
+        match line.strip_prefix('^') {
+            Some(id) => hidden.push(ObjectId::from_hex(id.as_bytes())?),
+            None => tips.push(ObjectId::from_hex(line.as_bytes())?),
+        }
+    }
+    let expected = std::fs::read_to_string(dir.join(format!("{}.baseline", name)))?
+        .lines()
+        .map(|line| ObjectId::from_hex(line.as_bytes()))
+        .collect::<Result<_, _>>()?;
+    Ok((tips, hidden, expected))
+}
+
+fn objects(
+    dir: &Path,
+    name: &str,
+    bitmaps: Option<&dyn reachable::Bitmaps>,
+) -> crate::Result<(reachable::Outcome, Vec<ObjectId>)> {
+    let db = git_odb::at(dir.join("repo").join(".git").join("objects"))?;
+    let (tips, hidden, expected) = baseline(dir, name)?;
+    let outcome = reachable::objects(tips, hidden, bitmaps, |id, buf| {
+        db.find(id, buf).map(|(data, _location)| data)
+    })?;
+    let mut actual = outcome.objects.clone();
+    actual.sort();
+    assert_eq!(
+        actual.len(),
+        outcome.objects.len(),
+        "{}: no object is listed twice",
+        name
+    );
+    assert_eq!(actual, expected, "{}: matches `git rev-list --objects`", name);
+    Ok((outcome, actual))
+}
+
+const BASELINES: &[&str] = &[
+    "main",
+    "loose-side",
+    "v1",
+    "main-without-other",
+    "main-without-loose-side",
+    "tags-and-branches",
+];
+
+#[test]
+fn without_bitmaps_all_objects_are_traversed() -> crate::Result {
+    let dir = fixture()?;
+    for name in BASELINES {
+        let (outcome, _) = objects(&dir, name, None)?;
+        assert_eq!(outcome.statistics.bitmaps, 0);
+    }
+    Ok(())
+}
+
+#[test]
+fn with_bitmaps_only_commits_without_bitmap_are_traversed() -> crate::Result {
+    let dir = fixture()?;
+    let bitmaps = bitmaps(&dir)?;
+    for name in BASELINES {
+        objects(&dir, name, Some(&bitmaps))?;
+    }
+
+    let (outcome, _) = objects(&dir, "main", Some(&bitmaps))?;
+    assert_eq!(
+        outcome.statistics,
+        reachable::Statistics {
+            bitmaps: 1,
+            commits: 1,
+            trees: 2,
+            tags: 0
+        },
+        "only the commit after the repack and its changed trees are traversed, the rest comes from the bitmap of its parent"
+    );
+    Ok(())
+}
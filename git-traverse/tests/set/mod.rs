@@ -0,0 +1,57 @@
+use std::collections::BTreeSet;
+
+use git_traverse::set::Bitmap;
+
+#[test]
+fn bitmap_behaves_like_a_set_for_sparse_and_dense_values() {
+    let mut bitmap = Bitmap::default();
+    let mut expected = BTreeSet::new();
+    assert!(bitmap.is_empty());
+
+    let dense = (0..10_000u32).map(|value| value * 3 % 20_000);
+    let sparse = (0..100u32).map(|value| (value * 7_919) << 12);
+    for value in dense.chain(sparse).chain(Some(u32::MAX)) {
+        assert_eq!(bitmap.insert(value), expected.insert(value), "{}", value);
+    }
+    for value in [0, 3, 19_998, u32::MAX, 7_919 << 12] {
+        assert!(!bitmap.insert(value), "{} is already contained", value);
+    }
+
+    assert_eq!(bitmap.len(), expected.len());
+    assert!(!bitmap.is_empty());
+    assert!(
+        bitmap.iter().eq(expected.iter().copied()),
+        "values are returned in order"
+    );
+    for value in [1, 2, 20_001, 65_536, u32::MAX - 1] {
+        assert_eq!(bitmap.contains(value), expected.contains(&value), "{}", value);
+    }
+
+    bitmap.clear();
+    assert!(bitmap.is_empty());
+    assert_eq!(bitmap.len(), 0);
+    assert_eq!(bitmap.iter().count(), 0);
+    assert!(!bitmap.contains(3));
+    assert!(bitmap.insert(3), "cleared bitmaps can be reused");
+    assert!(bitmap.contains(3));
+}
+
+#[test]
+fn bitmaps_are_equal_if_their_values_are() {
+    let mut dense = Bitmap::default();
+    for value in 0..5_000u32 {
+        dense.insert(value);
+    }
+    dense.clear();
+    let mut sparse = Bitmap::default();
+    for value in [3, 70_000] {
+        dense.insert(value);
+        sparse.insert(value);
+    }
+    assert_eq!(dense, sparse, "the way values are stored doesn't matter");
+
+    sparse.insert(4);
+    assert_ne!(dense, sparse);
+    dense.insert(5);
+    assert_ne!(dense, sparse, "same amount of values, but different ones");
+}
@@ -95,3 +95,177 @@ fn basic_nesting() -> crate::Result<()> {
     );
     Ok(())
 }
+
+mod parallel {
+    use git_object::bstr::BStr;
+    use git_odb::pack::FindExt;
+    use git_traverse::tree;
+
+    use crate::{hex_to_id, tree::db};
+
+    fn root_tree(db: &git_odb::Handle) -> crate::Result<git_hash::ObjectId> {
+        let mut buf = Vec::new();
+        Ok(db
+            .find_commit_iter(hex_to_id("85df34aa34848b8138b2b3dcff5fb5c2b734e0ce"), &mut buf)?
+            .0
+            .tree_id()
+            .expect("a tree is available in a commit"))
+    }
+
+    fn breadthfirst(db: &git_odb::Handle, root: &git_hash::oid) -> crate::Result<Vec<tree::recorder::Entry>> {
+        let mut buf = Vec::new();
+        let mut recorder = tree::Recorder::default();
+        tree::breadthfirst(
+            db.find_tree_iter(root, &mut buf)?.0,
+            tree::breadthfirst::State::default(),
+            |oid, buf| db.find_tree_iter(oid, buf).ok().map(|t| t.0),
+            &mut recorder,
+        )?;
+        Ok(recorder.records)
+    }
+
+    /// Read all trees reachable from `root` up-front, as the object database handle may not be shareable between threads.
+    fn parallel(
+        db: &git_odb::Handle,
+        root: &git_hash::oid,
+        delegate: &mut impl tree::parallel::Delegate,
+        options: tree::parallel::Options,
+    ) -> Result<(), tree::breadthfirst::Error> {
+        let mut trees = std::collections::HashMap::new();
+        let mut stack = vec![root.to_owned()];
+        while let Some(id) = stack.pop() {
+            let mut buf = Vec::new();
+            let tree = db.find_tree(id, &mut buf).expect("tree exists").0;
+            stack.extend(
+                tree.entries
+                    .iter()
+                    .filter(|entry| entry.mode == git_object::tree::EntryMode::Tree)
+                    .map(|entry| entry.oid.to_owned()),
+            );
+            trees.insert(id, buf);
+        }
+        let trees = std::sync::Arc::new(trees);
+        tree::parallel(
+            root,
+            move |oid, buf| {
+                let data = trees.get(oid)?;
+                buf.clear();
+                buf.extend_from_slice(data);
+                Some(git_object::TreeRefIter::from_bytes(buf))
+            },
+            delegate,
+            options,
+        )
+    }
+
+    #[test]
+    fn deterministic_order_matches_breadthfirst_traversal() -> crate::Result<()> {
+        let db = db()?;
+        let root = root_tree(&db)?;
+        for thread_limit in [Some(1), Some(4), None] {
+            let mut recorder = tree::Recorder::default();
+            parallel(
+                &db,
+                &root,
+                &mut recorder,
+                tree::parallel::Options {
+                    thread_limit,
+                    deterministic: true,
+                },
+            )?;
+            assert_eq!(recorder.records, breadthfirst(&db, &root)?, "{:?}", thread_limit);
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn any_order_observes_the_same_entries() -> crate::Result<()> {
+        let db = db()?;
+        let root = root_tree(&db)?;
+        let mut recorder = tree::Recorder::default();
+        parallel(
+            &db,
+            &root,
+            &mut recorder,
+            tree::parallel::Options {
+                thread_limit: None,
+                deterministic: false,
+            },
+        )?;
+        let sorted = |mut records: Vec<tree::recorder::Entry>| {
+            records.sort_by(|a, b| a.filepath.cmp(&b.filepath));
+            records
+        };
+        assert_eq!(sorted(recorder.records), sorted(breadthfirst(&db, &root)?));
+        Ok(())
+    }
+
+    struct Deny {
+        action: tree::visit::Action,
+        path: &'static str,
+        seen: Vec<String>,
+    }
+
+    impl tree::parallel::Delegate for Deny {
+        fn visit(&mut self, tree_path: &BStr, entry: &git_object::tree::EntryRef<'_>) -> tree::visit::Action {
+            let path = if tree_path.is_empty() {
+                entry.filename.to_string()
+            } else {
+                format!("{}/{}", tree_path, entry.filename)
+            };
+            let action = if path == self.path {
+                self.action
+            } else {
+                tree::visit::Action::Continue
+            };
+            self.seen.push(path);
+            action
+        }
+    }
+
+    #[test]
+    fn skipped_trees_are_not_traversed() -> crate::Result<()> {
+        let db = db()?;
+        let root = root_tree(&db)?;
+        let mut delegate = Deny {
+            action: tree::visit::Action::Skip,
+            path: "f",
+            seen: Vec::new(),
+        };
+        parallel(
+            &db,
+            &root,
+            &mut delegate,
+            tree::parallel::Options {
+                thread_limit: None,
+                deterministic: true,
+            },
+        )?;
+        assert_eq!(delegate.seen, ["a", "b", "c", "d", "e", "f", "d/a", "e/b"]);
+        Ok(())
+    }
+
+    #[test]
+    fn cancellation_stops_the_traversal() -> crate::Result<()> {
+        let db = db()?;
+        let root = root_tree(&db)?;
+        let mut delegate = Deny {
+            action: tree::visit::Action::Cancel,
+            path: "e",
+            seen: Vec::new(),
+        };
+        let err = parallel(
+            &db,
+            &root,
+            &mut delegate,
+            tree::parallel::Options {
+                thread_limit: None,
+                deterministic: true,
+            },
+        )
+        .unwrap_err();
+        assert!(matches!(err, tree::breadthfirst::Error::Cancelled));
+        assert_eq!(delegate.seen, ["a", "b", "c", "d", "e"]);
+        Ok(())
+    }
+}
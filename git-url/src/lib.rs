@@ -9,6 +9,7 @@
 #![forbid(unsafe_code)]
 
 use bstr::{BStr, BString};
+use percent_encoding::{percent_encode, utf8_percent_encode, AsciiSet, CONTROLS};
 
 ///
 pub mod parse;
@@ -23,10 +24,15 @@ pub use expand_path::expand_path;
 mod scheme;
 pub use scheme::Scheme;
 
+///
+pub mod rewrite;
+#[doc(inline)]
+pub use rewrite::Rewriter;
+
 /// A URL with support for specialized git related capabilities.
 ///
 /// Additionally there is support for [deserialization][Url::from_bytes()] and serialization
-/// (_see the `Display::fmt()` implementation_).
+/// (_see the `Display::fmt()` implementation_), which round-trip for URLs in their canonical form.
 ///
 /// # Deviation
 ///
@@ -36,15 +42,17 @@ pub use scheme::Scheme;
 pub struct Url {
     /// The URL scheme.
     pub scheme: Scheme,
-    /// The user to impersonate on the remote.
+    /// The user to impersonate on the remote, percent-decoded.
     user: Option<String>,
-    /// The host to which to connect. Localhost is implied if `None`.
+    /// The host to which to connect, without the brackets of IPv6 addresses. Localhost is implied if `None`.
     host: Option<String>,
     /// When serializing, use the alternative forms as it was parsed as such.
     serialize_alternative_form: bool,
     /// The port to use when connecting to a host. If `None`, standard ports depending on `scheme` will be used.
     pub port: Option<u16>,
-    /// The path portion of the URL, usually the location of the git repository.
+    /// The path portion of the URL, usually the location of the git repository, percent-decoded.
+    ///
+    /// Paths of scp-like URLs like `host:path` are stored with a leading slash, as in `/path`.
     pub path: bstr::BString,
 }
 
@@ -127,7 +135,7 @@ impl Url {
             Some(match self.scheme {
                 Http => 80,
                 Https => 443,
-                Ssh => 21,
+                Ssh => 22,
                 Git => 9418,
                 File | Ext(_) => return None,
             })
@@ -137,30 +145,84 @@ impl Url {
 
 /// Transformation
 impl Url {
-    /// Turn a file url like `file://relative` into `file:///root/relative`, hence it assures the url's path component is absolute.
+    /// Return a copy of this url in a canonical form that makes it comparable to other urls pointing to the same repository,
+    /// i.e. with file paths made absolute [like `canonicalize()` does][Url::canonicalize()], host names in lower case,
+    /// default ports removed and without trailing slashes or `.git` suffix.
+    ///
+    /// Note that the result is meant for comparisons, like when matching remotes with each other, and not for connecting as
+    /// not all servers accept paths without `.git` suffix.
     pub fn canonicalized(&self) -> Result<Self, git_path::realpath::Error> {
         let mut res = self.clone();
         res.canonicalize()?;
+        res.normalize();
         Ok(res)
     }
+
+    /// Return true if this url points to the same repository as `other` once both are [canonicalized][Url::canonicalized()],
+    /// regardless of the form they were written in, like `host:path` and `ssh://host/path`.
+    ///
+    /// File paths that can't be made absolute are compared as they are.
+    pub fn is_equivalent_to(&self, other: &Url) -> bool {
+        let canonicalized = |url: &Url| {
+            url.canonicalized().unwrap_or_else(|_| {
+                let mut url = url.clone();
+                url.normalize();
+                url
+            })
+        };
+        let (a, b) = (canonicalized(self), canonicalized(other));
+        a.scheme == b.scheme && a.user == b.user && a.host == b.host && a.port == b.port && a.path == b.path
+    }
+
+    fn normalize(&mut self) {
+        if let Some(host) = self.host.as_mut() {
+            host.make_ascii_lowercase();
+        }
+        let port = self.port.take();
+        if port != self.port_or_default() {
+            self.port = port;
+        }
+        let path = self.path.as_slice();
+        let path = trim_trailing_slashes(path);
+        let path = trim_trailing_slashes(path.strip_suffix(b".git").unwrap_or(path));
+        self.path = if path.is_empty() && self.path.starts_with(b"/") {
+            "/".into()
+        } else {
+            path.into()
+        };
+    }
+}
+
+fn trim_trailing_slashes(path: &[u8]) -> &[u8] {
+    let end = path.iter().rposition(|b| *b != b'/').map_or(0, |pos| pos + 1);
+    &path[..end]
 }
 
 /// Serialization
 impl Url {
     /// Write this URL losslessly to `out`, ready to be parsed again.
+    ///
+    /// Users and paths of URLs with a scheme other than `file` are percent-encoded, and IPv6 hosts are put into brackets.
     pub fn write_to(&self, mut out: impl std::io::Write) -> std::io::Result<()> {
-        if !(self.serialize_alternative_form && (self.scheme == Scheme::File || self.scheme == Scheme::Ssh)) {
+        let alternative_form =
+            self.serialize_alternative_form && (self.scheme == Scheme::File || self.scheme == Scheme::Ssh);
+        let encode = !alternative_form && self.scheme != Scheme::File;
+        if !alternative_form {
             out.write_all(self.scheme.as_str().as_bytes())?;
             out.write_all(b"://")?;
         }
         match (&self.user, &self.host) {
             (Some(user), Some(host)) => {
-                out.write_all(user.as_bytes())?;
-                out.write_all(&[b'@'])?;
-                out.write_all(host.as_bytes())?;
+                if encode {
+                    write!(&mut out, "{}", utf8_percent_encode(user, USERINFO))?;
+                } else {
+                    out.write_all(user.as_bytes())?;
+                }
+                out.write_all(b"@")?;
+                write_host(&mut out, host)?;
             }
             (None, Some(host)) => {
-                out.write_all(host.as_bytes())?;
+                write_host(&mut out, host)?;
             }
             (None, None) => {}
             (Some(_user), None) => unreachable!("BUG: should not be possible to have a user but no host"),
@@ -168,9 +230,13 @@ impl Url {
         if let Some(port) = &self.port {
             write!(&mut out, ":{}", port)?;
         }
-        if self.serialize_alternative_form && self.scheme == Scheme::Ssh {
+        if alternative_form && self.scheme == Scheme::Ssh {
             out.write_all(b":")?;
             out.write_all(&self.path[1..])?;
+        } else if encode {
+            for chunk in percent_encode(&self.path, PATH) {
+                out.write_all(chunk.as_bytes())?;
+            }
         } else {
             out.write_all(&self.path)?;
         }
@@ -201,3 +267,36 @@ impl Url {
 }
 
 mod impls;
+
+/// Characters that are percent-encoded in paths, which includes `%` to allow decoding them losslessly.
+const PATH: &AsciiSet = &CONTROLS
+    .add(b' ')
+    .add(b'"')
+    .add(b'#')
+    .add(b'%')
+    .add(b'<')
+    .add(b'>')
+    .add(b'?')
+    .add(b'`')
+    .add(b'{')
+    .add(b'}');
+/// Characters that are percent-encoded in the user portion of URLs.
+const USERINFO: &AsciiSet = &PATH
+    .add(b'/')
+    .add(b':')
+    .add(b';')
+    .add(b'=')
+    .add(b'@')
+    .add(b'[')
+    .add(b'\\')
+    .add(b']')
+    .add(b'^')
+    .add(b'|');
+
+fn write_host(mut out: impl std::io::Write, host: &str) -> std::io::Result<()> {
+    if host.contains(':') {
+        write!(out, "[{}]", host)
+    } else {
+        out.write_all(host.as_bytes())
+    }
+}
@@ -5,6 +5,7 @@ use std::{
 
 pub use bstr;
 use bstr::{BStr, ByteSlice};
+use percent_encoding::percent_decode_str;
 
 use crate::Scheme;
 
@@ -36,90 +37,151 @@ fn str_to_protocol(s: &str) -> Result<Scheme, Error> {
     })
 }
 
-fn guess_protocol(url: &[u8]) -> &str {
-    match url.find_byte(b':') {
-        Some(colon_pos) => {
-            if url[..colon_pos].find_byte(b'.').is_some() {
-                "ssh"
-            } else {
-                "file"
-            }
+/// Returns true if `url` starts with a scheme followed by `://`, similar to `is_url()` in git.
+fn has_explicit_protocol(url: &[u8]) -> bool {
+    match url.find(b"://") {
+        Some(pos) => {
+            pos > 0
+                && url[..pos]
+                    .iter()
+                    .all(|b| b.is_ascii_alphanumeric() || matches!(b, b'+' | b'-' | b'.'))
         }
-        None => "file",
+        None => false,
     }
 }
 
-fn sanitize_for_protocol<'a>(protocol: &str, url: &'a str) -> Cow<'a, str> {
-    match protocol {
-        "ssh" => url.replacen(':', "/", 1).into(),
-        _ => url.into(),
+/// Returns true if `url` is a path with a drive letter, like `c:/path` or `c:\path`.
+fn has_dos_drive_prefix(url: &[u8]) -> bool {
+    url.len() >= 2 && url[0].is_ascii_alphabetic() && url[1] == b':'
+}
+
+/// Returns true if `url` is to be interpreted as local path, which is the case if there is no colon or if a slash comes first.
+fn is_local_path(url: &[u8]) -> bool {
+    match url.find_byte(b':') {
+        Some(colon) => url[..colon].contains(&b'/') || has_dos_drive_prefix(url),
+        None => true,
     }
 }
 
-fn has_no_explicit_protocol(url: &[u8]) -> bool {
-    url.find(b"://").is_none()
+fn decode_utf8(bytes: Cow<'_, [u8]>) -> Result<String, Error> {
+    Ok(match bytes {
+        Cow::Borrowed(bytes) => std::str::from_utf8(bytes)?.to_owned(),
+        Cow::Owned(bytes) => String::from_utf8(bytes).map_err(|err| err.utf8_error())?,
+    })
 }
 
-fn try_strip_file_protocol(url: &[u8]) -> Option<&[u8]> {
-    url.strip_prefix(b"file://")
+/// Parse `[user@]host:path` or `[user@][ipv6]:path` into an ssh URL, without any percent-decoding, or return `None`
+/// if it doesn't look like that.
+fn parse_scp_like(input: &[u8]) -> Result<Option<crate::Url>, Error> {
+    let colon = match input.find_byte(b':') {
+        Some(pos) => pos,
+        None => return Ok(None),
+    };
+    let (user, host_and_path) = match input.find_byte(b'@') {
+        Some(at) if at < colon => (Some(&input[..at]), &input[at + 1..]),
+        _ => (None, input),
+    };
+    let (host, path) = match host_and_path
+        .strip_prefix(b"[")
+        .and_then(|ipv6| ipv6.find(b"]:").map(|end| (&ipv6[..end], &ipv6[end + 2..])))
+    {
+        Some(host_and_path) => host_and_path,
+        None => {
+            let colon = host_and_path.find_byte(b':').expect("colon exists past user");
+            (&host_and_path[..colon], &host_and_path[colon + 1..])
+        }
+    };
+    if host.is_empty() {
+        return Ok(None);
+    }
+    if path.is_empty() {
+        return Err(Error::EmptyPath);
+    }
+
+    let mut url_path = bstr::BString::from("/");
+    url_path.extend_from_slice(path);
+    Ok(Some(crate::Url {
+        serialize_alternative_form: true,
+        scheme: Scheme::Ssh,
+        user: user.map(|user| decode_utf8(user.into())).transpose()?,
+        host: Some(decode_utf8(host.into())?),
+        port: None,
+        path: url_path,
+    }))
 }
 
-fn to_owned_url(url: url::Url) -> Result<crate::Url, Error> {
+/// Return the port in the authority of `url` if it was explicitly specified, as the `url` crate omits ports that are
+/// the default of the scheme.
+fn explicit_port(url: &str) -> Option<u16> {
+    let (_scheme, rest) = url.split_once("://")?;
+    let authority = rest.split(&['/', '?', '#'][..]).next()?;
+    let host_and_port = authority
+        .rsplit_once('@')
+        .map_or(authority, |(_user, host_and_port)| host_and_port);
+    let (_host, port) = host_and_port.rsplit_once(':')?;
+    port.parse().ok()
+}
+
+fn to_owned_url(url: url::Url, url_str: &str) -> Result<crate::Url, Error> {
     Ok(crate::Url {
         serialize_alternative_form: false,
         scheme: str_to_protocol(url.scheme())?,
         user: if url.username().is_empty() {
             None
         } else {
-            Some(url.username().into())
+            Some(decode_utf8(percent_decode_str(url.username()).into())?)
         },
-        host: url.host_str().map(Into::into),
-        port: url.port(),
-        path: url.path().into(),
+        host: url.host().map(|host| match host {
+            url::Host::Ipv6(addr) => addr.to_string(),
+            url::Host::Domain(_) | url::Host::Ipv4(_) => {
+                url.host_str().expect("host_str is available if there is a host").into()
+            }
+        }),
+        port: url.port().or_else(|| explicit_port(url_str)),
+        path: Cow::<'_, [u8]>::from(percent_decode_str(url.path()))
+            .into_owned()
+            .into(),
     })
 }
 
 /// Parse the given `bytes` as git url.
 ///
+/// The following forms are supported, similar to what git supports:
+///
+/// * `<scheme>://[<user>@]<host>[:<port>]/<path>` with percent-encoded user and path, and IPv6 hosts in brackets,
+///   like `ssh://[::1]:2222/repo`.
+/// * `[<user>@]<host>:<path>` as scp-like syntax for ssh, with IPv6 hosts in brackets, like `user@[::1]:repo`.
+/// * `file://<path>` and plain paths, including paths with drive letters like `c:/path` or `c:\path`.
+///   Paths are taken verbatim and are not percent-decoded.
+///
 /// # Note
 ///
 /// We cannot and should never have to deal with UTF-16 encoded windows strings, so bytes input is acceptable.
 /// For file-paths, we don't expect UTF8 encoding either.
 pub fn parse(input: &BStr) -> Result<crate::Url, Error> {
-    let guessed_protocol = guess_protocol(input);
-    let path_without_protocol = try_strip_file_protocol(input);
-    if path_without_protocol.is_some() || (has_no_explicit_protocol(input) && guessed_protocol == "file") {
+    if let Some(path) = input.strip_prefix(b"file://") {
         return Ok(crate::Url {
             scheme: Scheme::File,
-            path: path_without_protocol.unwrap_or(input).into(),
-            serialize_alternative_form: !input.starts_with(b"file://"),
+            path: path.into(),
             ..Default::default()
         });
     }
-
-    let url_str = std::str::from_utf8(input)?;
-    let (mut url, mut sanitized_scp) = match url::Url::parse(url_str) {
-        Ok(url) => (url, false),
-        Err(url::ParseError::RelativeUrlWithoutBase) => {
-            // happens with bare paths as well as scp like paths. The latter contain a ':' past the host portion,
-            // which we are trying to detect.
-            (
-                url::Url::parse(&format!(
-                    "{}://{}",
-                    guessed_protocol,
-                    sanitize_for_protocol(guessed_protocol, url_str)
-                ))?,
-                true,
-            )
+    if !has_explicit_protocol(input) {
+        if !is_local_path(input) {
+            if let Some(url) = parse_scp_like(input)? {
+                return Ok(url);
+            }
         }
-        Err(err) => return Err(err.into()),
-    };
-    // SCP like URLs without user parse as 'something' with the scheme being the 'host'. Hosts always have dots.
-    if url.scheme().find('.').is_some() {
-        // try again with prefixed protocol
-        url = url::Url::parse(&format!("ssh://{}", sanitize_for_protocol("ssh", url_str)))?;
-        sanitized_scp = true;
+        return Ok(crate::Url {
+            scheme: Scheme::File,
+            path: input.into(),
+            serialize_alternative_form: true,
+            ..Default::default()
+        });
     }
+
+    let url_str = std::str::from_utf8(input)?;
+    let url = url::Url::parse(url_str)?;
     if url.scheme() != "rad" && url.path().is_empty() {
         return Err(Error::EmptyPath);
     }
@@ -127,5 +189,5 @@ pub fn parse(input: &BStr) -> Result<crate::Url, Error> {
         return Err(Error::RelativeUrl { url: url.into() });
     }
 
-    to_owned_url(url).map(|url| url.serialize_alternate_form(sanitized_scp))
+    to_owned_url(url, url_str)
 }
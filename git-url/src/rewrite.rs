@@ -0,0 +1,104 @@
+use bstr::{BStr, BString, ByteVec};
+
+use crate::Url;
+
+/// The direction in which a URL is used, which determines the rules to use when [rewriting][Rewriter] it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Direction {
+    /// The URL is used to fetch from a remote, which uses rules from `url.<base>.insteadOf`.
+    Fetch,
+    /// The URL is used to push to a remote, which uses rules from `url.<base>.pushInsteadOf`.
+    Push,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Replace {
+    find: BString,
+    with: BString,
+}
+
+/// A set of rules to rewrite URLs by replacing their prefix, as configured by `url.<base>.insteadOf` and
+/// `url.<base>.pushInsteadOf`.
+#[derive(Default, Debug, Clone, PartialEq, Eq)]
+pub struct Rewriter {
+    url_rewrite: Vec<Replace>,
+    push_url_rewrite: Vec<Replace>,
+}
+
+/// Init
+impl Rewriter {
+    /// Add a rule to replace the prefix `instead_of` with `base` in URLs used in `direction`, just like `url.<base>.insteadOf`
+    /// does when fetching, and `url.<base>.pushInsteadOf` does when pushing.
+    ///
+    /// Rules should be added in the order in which they appear in the configuration.
+    pub fn add_rule(
+        &mut self,
+        base: impl Into<BString>,
+        instead_of: impl Into<BString>,
+        direction: Direction,
+    ) -> &mut Self {
+        let replace = Replace {
+            find: instead_of.into(),
+            with: base.into(),
+        };
+        match direction {
+            Direction::Fetch => self.url_rewrite.push(replace),
+            Direction::Push => self.push_url_rewrite.push(replace),
+        }
+        self
+    }
+}
+
+/// Access
+impl Rewriter {
+    /// Return true if there are no rules for `direction`.
+    pub fn is_empty(&self, direction: Direction) -> bool {
+        self.replacements_for(direction).is_empty()
+    }
+
+    fn replacements_for(&self, direction: Direction) -> &[Replace] {
+        match direction {
+            Direction::Fetch => &self.url_rewrite,
+            Direction::Push => &self.push_url_rewrite,
+        }
+    }
+
+    /// Rewrite `url` with the rule for `direction` that matches the longest prefix and return the result, or `None`
+    /// if no rule matched.
+    ///
+    /// Note that the result must be parsed again, it might not be a valid URL as we do a syntax-unaware replacement.
+    pub fn rewrite_url(&self, url: &Url, direction: Direction) -> Option<BString> {
+        if self.is_empty(direction) {
+            None
+        } else {
+            let mut url = url.to_bstring();
+            self.rewrite_url_in_place(&mut url, direction).then(|| url)
+        }
+    }
+
+    /// Rewrite the given `url` of `direction` and return `true` if a replacement happened.
+    ///
+    /// The rule with the longest matching prefix wins, and if there are multiple of the same length the one added first is used,
+    /// similar to git.
+    ///
+    /// Note that the result must still be checked for validity, it might not be a valid URL as we do a syntax-unaware replacement.
+    pub fn rewrite_url_in_place(&self, url: &mut BString, direction: Direction) -> bool {
+        self.replacements_for(direction)
+            .iter()
+            .fold(None::<(usize, &BStr)>, |mut acc, replace| {
+                if url.starts_with(replace.find.as_ref()) {
+                    let (bytes_matched, prev_rewrite_with) =
+                        acc.get_or_insert((replace.find.len(), replace.with.as_slice().into()));
+                    if *bytes_matched < replace.find.len() {
+                        *bytes_matched = replace.find.len();
+                        *prev_rewrite_with = replace.with.as_slice().into();
+                    }
+                };
+                acc
+            })
+            .map(|(bytes_matched, replace_with)| {
+                url.replace_range(..bytes_matched, replace_with);
+            })
+            .is_some()
+    }
+}
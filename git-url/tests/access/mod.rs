@@ -16,6 +16,32 @@ mod canonicalized {
         Ok(())
     }
 
+    #[test]
+    fn host_case_default_ports_trailing_slashes_and_git_suffix_are_normalized() -> crate::Result {
+        for (url, expected) in [
+            (
+                "https://GitHub.com:443/byron/gitoxide.git",
+                "https://github.com/byron/gitoxide",
+            ),
+            (
+                "https://github.com:8443/byron/gitoxide.git/",
+                "https://github.com:8443/byron/gitoxide",
+            ),
+            ("ssh://git@Host.xz:22/repo.git//", "ssh://git@host.xz/repo"),
+            ("git://host.xz:9418/", "git://host.xz/"),
+            ("git@host.xz:repo.git", "git@host.xz:repo"),
+            ("/path/to/repo.git/", "/path/to/repo"),
+        ] {
+            assert_eq!(
+                git_url::parse(url.into())?.canonicalized()?.to_bstring(),
+                expected,
+                "{}",
+                url
+            );
+        }
+        Ok(())
+    }
+
     #[test]
     fn file_that_is_current_dir_is_absolutized() -> crate::Result {
         let url = git_url::parse(".".into())?;
@@ -24,3 +50,67 @@ mod canonicalized {
         Ok(())
     }
 }
+
+mod is_equivalent_to {
+    fn equivalent(a: &str, b: &str) -> Result<bool, git_url::parse::Error> {
+        Ok(git_url::parse(a.into())?.is_equivalent_to(&git_url::parse(b.into())?))
+    }
+
+    #[test]
+    fn urls_differing_only_in_canonicalized_parts_are_equivalent() -> crate::Result {
+        for (a, b) in [
+            (
+                "https://github.com/byron/gitoxide",
+                "https://GITHUB.com:443/byron/gitoxide.git/",
+            ),
+            (
+                "ssh://git@github.com/byron/gitoxide.git",
+                "git@github.com:byron/gitoxide",
+            ),
+            ("file:///path/to/repo.git", "/path/to/repo"),
+        ] {
+            assert!(equivalent(a, b)?, "{} == {}", a, b);
+            assert!(equivalent(b, a)?, "{} == {}", b, a);
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn urls_pointing_elsewhere_are_not_equivalent() -> crate::Result {
+        for (a, b) in [
+            ("https://github.com/byron/gitoxide", "http://github.com/byron/gitoxide"),
+            (
+                "https://github.com/byron/gitoxide",
+                "https://github.com:8443/byron/gitoxide",
+            ),
+            (
+                "https://github.com/byron/gitoxide",
+                "https://user@github.com/byron/gitoxide",
+            ),
+            ("https://github.com/byron/gitoxide", "https://github.com/Byron/gitoxide"),
+            (
+                "https://github.com/byron/gitoxide",
+                "https://github.com/byron/gitoxide-core",
+            ),
+        ] {
+            assert!(!equivalent(a, b)?, "{} != {}", a, b);
+        }
+        Ok(())
+    }
+}
+
+#[test]
+fn port_or_default() -> crate::Result {
+    for (url, expected) in [
+        ("ssh://host.xz/repo", Some(22)),
+        ("host.xz:repo", Some(22)),
+        ("ssh://host.xz:2222/repo", Some(2222)),
+        ("git://host.xz/repo", Some(9418)),
+        ("http://host.xz/repo", Some(80)),
+        ("https://host.xz/repo", Some(443)),
+        ("file:///repo", None),
+    ] {
+        assert_eq!(git_url::parse(url.into())?.port_or_default(), expected, "{}", url);
+    }
+    Ok(())
+}
@@ -40,6 +40,25 @@ fn no_username_expansion_for_file_paths_with_protocol() -> crate::Result {
     )
 }
 
+#[test]
+fn file_paths_are_not_percent_decoded() -> crate::Result {
+    assert_url_roundtrip(
+        "file:///path/to%20git",
+        url(Scheme::File, None, None, None, b"/path/to%20git"),
+    )
+}
+
+#[test]
+fn colons_after_a_slash_indicate_a_file_path() -> crate::Result {
+    let url = assert_url_and(
+        "./host.xz:path",
+        url_alternate(Scheme::File, None, None, None, b"./host.xz:path"),
+    )?
+    .to_bstring();
+    assert_eq!(url, "./host.xz:path");
+    Ok(())
+}
+
 #[test]
 fn non_utf8_file_path_without_protocol() -> crate::Result {
     let parsed = git_url::parse(b"/path/to\xff/git".as_bstr())?;
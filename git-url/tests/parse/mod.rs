@@ -96,6 +96,23 @@ mod http {
             url(Scheme::Https, None, "github.com", None, b"/byron/gitoxide"),
         )
     }
+
+    #[test]
+    fn explicit_default_ports_are_kept() -> crate::Result {
+        assert_url_roundtrip(
+            "https://github.com:443/byron/gitoxide",
+            url(Scheme::Https, None, "github.com", 443, b"/byron/gitoxide"),
+        )?;
+        assert_url_roundtrip("http://[::1]:80/repo", url(Scheme::Http, None, "::1", 80, b"/repo"))
+    }
+
+    #[test]
+    fn non_ascii_paths_are_percent_decoded() -> crate::Result {
+        assert_url_roundtrip(
+            "https://example.com/caf%C3%A9/repo%3F",
+            url(Scheme::Https, None, "example.com", None, "/café/repo?".as_bytes()),
+        )
+    }
 }
 mod git {
     use git_url::Scheme;
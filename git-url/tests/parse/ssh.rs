@@ -81,19 +81,88 @@ fn scp_like_without_user_and_username_expansion_with_username() -> crate::Result
 }
 
 #[test]
-fn scp_like_with_user_and_relative_path_turns_into_absolute_path() -> crate::Result {
+fn scp_like_with_user_and_relative_path_keeps_it_verbatim() -> crate::Result {
+    for (input, expected_path) in [
+        ("user@host.xz:./relative", "/./relative"),
+        ("user@host.xz:../relative", "/../relative"),
+    ] {
+        let url = git_url::parse(input.into())?;
+        assert_eq!(url.scheme, Scheme::Ssh);
+        assert_eq!(url.user(), Some("user"));
+        assert_eq!(url.host(), Some("host.xz"));
+        assert_eq!(url.path, expected_path, "scp-like paths are not normalized");
+        assert_eq!(url.to_bstring(), input);
+    }
+    Ok(())
+}
+
+#[test]
+fn scp_like_with_absolute_path() -> crate::Result {
+    let url = assert_url_and(
+        "host.xz:/absolute/path",
+        url_alternate(Scheme::Ssh, None, "host.xz", None, b"//absolute/path"),
+    )?
+    .to_bstring();
+    assert_eq!(url, "host.xz:/absolute/path");
+    Ok(())
+}
+
+#[test]
+fn scp_like_without_dot_in_host() -> crate::Result {
+    let url = assert_url_and(
+        "git@github:byron/gitoxide",
+        url_alternate(Scheme::Ssh, "git", "github", None, b"/byron/gitoxide"),
+    )?
+    .to_bstring();
+    assert_eq!(url, "git@github:byron/gitoxide");
+
+    let url = assert_url_and(
+        "localhost:repo",
+        url_alternate(Scheme::Ssh, None, "localhost", None, b"/repo"),
+    )?
+    .to_bstring();
+    assert_eq!(url, "localhost:repo");
+    Ok(())
+}
+
+#[test]
+fn scp_like_is_not_percent_decoded() -> crate::Result {
     let url = assert_url_and(
-        "user@host.xz:./relative",
-        url_alternate(Scheme::Ssh, "user", "host.xz", None, b"/relative"),
+        "host.xz:path%20with/spaces and more",
+        url_alternate(Scheme::Ssh, None, "host.xz", None, b"/path%20with/spaces and more"),
     )?
     .to_bstring();
-    assert_eq!(url, "user@host.xz:relative");
+    assert_eq!(url, "host.xz:path%20with/spaces and more");
+    Ok(())
+}
+
+#[test]
+fn scp_like_with_ipv6_host() -> crate::Result {
+    let url = assert_url_and("[::1]:repo", url_alternate(Scheme::Ssh, None, "::1", None, b"/repo"))?.to_bstring();
+    assert_eq!(url, "[::1]:repo");
 
     let url = assert_url_and(
-        "user@host.xz:../relative",
-        url_alternate(Scheme::Ssh, "user", "host.xz", None, b"/../relative"),
+        "user@[fe80::1]:~/repo.git",
+        url_alternate(Scheme::Ssh, "user", "fe80::1", None, b"/~/repo.git"),
     )?
     .to_bstring();
-    assert_eq!(url, "user@host.xz:relative");
+    assert_eq!(url, "user@[fe80::1]:~/repo.git");
     Ok(())
 }
+
+#[test]
+fn host_is_ipv6() -> crate::Result {
+    assert_url_roundtrip("ssh://[::1]/repo", url(Scheme::Ssh, None, "::1", None, b"/repo"))?;
+    assert_url_roundtrip(
+        "ssh://user@[fe80::1]:2222/repo",
+        url(Scheme::Ssh, "user", "fe80::1", 2222, b"/repo"),
+    )
+}
+
+#[test]
+fn user_and_path_are_percent_decoded() -> crate::Result {
+    assert_url_roundtrip(
+        "ssh://us%40er@host.xz/path%20with/spaces%25",
+        url(Scheme::Ssh, "us@er", "host.xz", None, b"/path with/spaces%"),
+    )
+}
@@ -0,0 +1,77 @@
+use git_url::{rewrite::Direction, Rewriter};
+
+fn url(url: &str) -> git_url::Url {
+    git_url::parse(url.into()).expect("valid")
+}
+
+fn rewriter() -> Rewriter {
+    let mut rewriter = Rewriter::default();
+    rewriter
+        .add_rule("ssh://", "https://", Direction::Fetch)
+        .add_rule(
+            "https://github.com/byron/",
+            "https://github.com/foobar/",
+            Direction::Fetch,
+        )
+        .add_rule(
+            "https://mirror.example.com/",
+            "https://github.com/foobar/",
+            Direction::Fetch,
+        )
+        .add_rule("ssh://git@github.com/", "https://github.com/", Direction::Push);
+    rewriter
+}
+
+#[test]
+fn empty_rewriter_does_nothing() {
+    let rewriter = Rewriter::default();
+    assert!(rewriter.is_empty(Direction::Fetch));
+    assert!(rewriter.is_empty(Direction::Push));
+    assert_eq!(
+        rewriter.rewrite_url(&url("https://github.com/byron/gitoxide"), Direction::Fetch),
+        None
+    );
+}
+
+#[test]
+fn longest_prefix_wins_and_first_rule_wins_ties() {
+    let rewriter = rewriter();
+    assert_eq!(
+        rewriter
+            .rewrite_url(&url("https://github.com/foobar/gitoxide"), Direction::Fetch)
+            .expect("rewritten"),
+        "https://github.com/byron/gitoxide",
+        "the mirror rule has a prefix of the same length and was added later"
+    );
+    assert_eq!(
+        rewriter
+            .rewrite_url(&url("https://example.com/repo"), Direction::Fetch)
+            .expect("rewritten"),
+        "ssh://example.com/repo"
+    );
+}
+
+#[test]
+fn rules_are_separate_for_fetch_and_push() {
+    let rewriter = rewriter();
+    let url = url("https://github.com/foobar/gitoxide");
+    assert_eq!(
+        rewriter.rewrite_url(&url, Direction::Push).expect("rewritten"),
+        "ssh://git@github.com/foobar/gitoxide"
+    );
+    assert_eq!(
+        rewriter.rewrite_url(&self::url("ssh://host/repo"), Direction::Push),
+        None,
+        "fetch rules don't apply to pushes"
+    );
+}
+
+#[test]
+fn rewriting_in_place_is_syntax_unaware() {
+    let mut rewriter = Rewriter::default();
+    rewriter.add_rule("foo://", "file://", Direction::Push);
+    let mut url = "file://dev/null".into();
+    assert!(rewriter.rewrite_url_in_place(&mut url, Direction::Push));
+    assert_eq!(url, "foo://dev/null", "the result isn't necessarily valid");
+    assert!(!rewriter.rewrite_url_in_place(&mut url, Direction::Fetch));
+}
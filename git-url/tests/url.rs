@@ -4,3 +4,4 @@ pub type Result = std::result::Result<(), Error>;
 mod access;
 mod expand_path;
 mod parse;
+mod rewrite;
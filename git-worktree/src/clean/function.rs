@@ -0,0 +1,222 @@
+use std::{
+    path::{Path, PathBuf},
+    sync::atomic::{AtomicBool, Ordering},
+};
+
+use bstr::{BStr, ByteSlice};
+use git_hash::oid;
+
+use crate::{
+    clean::{Error, Ignored, Options, Outcome},
+    fs, untracked,
+    untracked::{
+        function::{is_repository, join, read_dir},
+        Kind, Status,
+    },
+};
+
+/// Remove untracked files from the worktree at the root of `cache` as [found][crate::untracked::walk()] with `index`,
+/// `cache` and `find`, and as configured by `options`, and return a list of everything that was removed.
+///
+/// Like git, files are only removed from tracked directories unless [`directories`][Options::directories] is set, and
+/// only untracked files are removed unless [`ignored`][Options::ignored] says differently. Nested repositories are never
+/// removed unless this is [explicitly requested][Options::repositories].
+///
+/// Note that interruption still produce an `Ok(…)` value, so the caller should look at `should_interrupt` to communicate the outcome.
+pub fn clean<Find, E>(
+    index: &git_index::State,
+    cache: &fs::Cache<'_>,
+    find: Find,
+    should_interrupt: &AtomicBool,
+    options: Options,
+) -> Result<Outcome, Error>
+where
+    Find: for<'a> FnMut(&oid, &'a mut Vec<u8>) -> Result<git_object::BlobRef<'a>, E> + Send + Clone,
+    E: std::error::Error + Send + Sync + 'static,
+{
+    let walk = untracked::walk(
+        index,
+        cache,
+        find,
+        None,
+        should_interrupt,
+        untracked::Options {
+            mode: untracked::Mode::Normal,
+            emit_ignored: true,
+            thread_limit: options.thread_limit,
+            fs: options.fs,
+        },
+    )?;
+    // Ignored files in untracked directories have to stay if ignored files are kept.
+    let keep: Vec<&BStr> = walk
+        .entries
+        .iter()
+        .filter(|entry| entry.status == Status::Ignored && options.ignored == Ignored::Keep)
+        .map(|entry| entry.rela_path.as_ref())
+        .collect();
+
+    let mut clean = Clean {
+        root: cache.base(),
+        options,
+        keep: &keep,
+        out: Outcome::default(),
+    };
+    let mut removed_dir = None::<&BStr>;
+    for entry in &walk.entries {
+        if should_interrupt.load(Ordering::Relaxed) {
+            break;
+        }
+        let path = entry.rela_path.as_ref();
+        if removed_dir.map_or(false, |dir| is_inside(dir, path)) {
+            continue;
+        }
+        let remove = match entry.status {
+            Status::Untracked => options.ignored != Ignored::RemoveOnly,
+            Status::Ignored => options.ignored != Ignored::Keep,
+        };
+        if !remove {
+            continue;
+        }
+        match entry.kind {
+            Kind::File | Kind::Symlink => {
+                if is_in_tracked_directory(index, path) {
+                    clean.remove_file(entry.clone())?;
+                } else if options.directories {
+                    clean.remove_file(entry.clone())?;
+                    clean.remove_empty_untracked_parents(index, path);
+                }
+            }
+            Kind::Directory if options.directories => {
+                clean.remove_directory(path, entry.status)?;
+                removed_dir = Some(path);
+            }
+            Kind::Directory => {}
+            Kind::Repository => {
+                if options.directories && options.repositories {
+                    clean.remove_repository(entry.clone())?;
+                } else {
+                    clean.out.skipped_repositories.push(entry.clone());
+                }
+            }
+        }
+    }
+    let mut out = clean.out;
+    out.removed.sort();
+    out.skipped_repositories.sort();
+    Ok(out)
+}
+
+struct Clean<'a> {
+    root: &'a Path,
+    options: Options,
+    /// Sorted paths of entries to keep.
+    keep: &'a [&'a BStr],
+    out: Outcome,
+}
+
+impl<'a> Clean<'a> {
+    fn remove_file(&mut self, entry: untracked::Entry) -> Result<(), Error> {
+        if !self.options.dry_run {
+            let path = self.path(entry.rela_path.as_ref());
+            std::fs::remove_file(&path).map_err(|source| Error::Remove { path, source })?;
+        }
+        self.out.removed.push(entry);
+        Ok(())
+    }
+
+    fn remove_repository(&mut self, entry: untracked::Entry) -> Result<(), Error> {
+        if !self.options.dry_run {
+            let path = self.path(entry.rela_path.as_ref());
+            std::fs::remove_dir_all(&path).map_err(|source| Error::Remove { path, source })?;
+        }
+        self.out.removed.push(entry);
+        Ok(())
+    }
+
+    /// Remove the content of the directory at `rela_dir` except for entries to keep and nested repositories, and remove the
+    /// directory itself if it became empty. Return true if it was removed, in which case it's listed instead of its content.
+    fn remove_directory(&mut self, rela_dir: &BStr, status: Status) -> Result<bool, Error> {
+        let path = self.path(rela_dir);
+        let num_removed = self.out.removed.len();
+        let mut removed_all = true;
+        for (name, file_type) in read_dir(&path, false)? {
+            let rela_path = join(rela_dir, name.as_ref());
+            if self.keep.binary_search(&rela_path.as_bstr()).is_ok() {
+                removed_all = false;
+                continue;
+            }
+            let entry = |kind| untracked::Entry {
+                rela_path: rela_path.clone(),
+                kind,
+                status,
+            };
+            if file_type.is_dir() {
+                if is_repository(&path.join(git_path::from_bstr(name.as_bstr()))) {
+                    if self.options.repositories {
+                        self.remove_repository(entry(Kind::Repository))?;
+                    } else {
+                        self.out.skipped_repositories.push(entry(Kind::Repository));
+                        removed_all = false;
+                    }
+                } else {
+                    removed_all &= self.remove_directory(rela_path.as_ref(), status)?;
+                }
+            } else {
+                self.remove_file(entry(if file_type.is_symlink() {
+                    Kind::Symlink
+                } else {
+                    Kind::File
+                }))?;
+            }
+        }
+        if removed_all {
+            if !self.options.dry_run {
+                std::fs::remove_dir(&path).map_err(|source| Error::Remove { path, source })?;
+            }
+            self.out.removed.truncate(num_removed);
+            self.out.removed.push(untracked::Entry {
+                rela_path: rela_dir.to_owned(),
+                kind: Kind::Directory,
+                status,
+            });
+        }
+        Ok(removed_all)
+    }
+
+    /// Remove all leading directories of `rela_path` that became empty, up to the first one that contains tracked files.
+    fn remove_empty_untracked_parents(&self, index: &git_index::State, rela_path: &BStr) {
+        if self.options.dry_run {
+            return;
+        }
+        let mut rela_path = rela_path;
+        while !is_in_tracked_directory(index, rela_path) {
+            let pos = rela_path.rfind_byte(b'/').expect("not at the top-level");
+            rela_path = rela_path[..pos].as_bstr();
+            if std::fs::remove_dir(self.path(rela_path)).is_err() {
+                break;
+            }
+        }
+    }
+
+    fn path(&self, rela_path: &BStr) -> PathBuf {
+        self.root.join(git_path::from_bstr(rela_path))
+    }
+}
+
+/// Return true if `path` is inside of the directory `dir`.
+fn is_inside(dir: &BStr, path: &BStr) -> bool {
+    path.len() > dir.len() && path.starts_with(dir) && path[dir.len()] == b'/'
+}
+
+/// Return true if the parent directory of `rela_path` is the worktree root or contains tracked files.
+fn is_in_tracked_directory(index: &git_index::State, rela_path: &BStr) -> bool {
+    let dir = match rela_path.rfind_byte(b'/') {
+        Some(pos) => &rela_path[..=pos],
+        None => return true,
+    };
+    let entries = index.entries();
+    let start = entries.partition_point(|entry| entry.path(index) < dir);
+    entries
+        .get(start)
+        .map_or(false, |entry| entry.path(index).starts_with(dir))
+}
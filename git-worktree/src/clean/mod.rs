@@ -0,0 +1,66 @@
+//! Removal of untracked and ignored files from the worktree, similar to `git clean`.
+use std::path::PathBuf;
+
+use crate::untracked;
+
+pub(crate) mod function;
+
+/// Which ignored files to remove.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Ignored {
+    /// Keep ignored files and only remove untracked ones, which is the default.
+    Keep,
+    /// Remove ignored files along with untracked ones, similar to `git clean -x`.
+    Remove,
+    /// Only remove ignored files and keep untracked ones, similar to `git clean -X`.
+    RemoveOnly,
+}
+
+impl Default for Ignored {
+    fn default() -> Self {
+        Ignored::Keep
+    }
+}
+
+/// Options for use in [`clean()`][crate::clean()].
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Options {
+    /// If true, don't remove anything but produce an [`Outcome`] that lists everything that would have been removed.
+    pub dry_run: bool,
+    /// If true, remove untracked directories as well instead of only files in tracked directories, similar to `git clean -d`.
+    ///
+    /// Note that ignored files and nested repositories in untracked directories are kept unless they are to be removed,
+    /// along with the directories leading to them. Empty untracked directories aren't removed as they aren't considered
+    /// untracked.
+    pub directories: bool,
+    /// Which ignored files to remove.
+    pub ignored: Ignored,
+    /// If true, nested repositories in untracked directories are removed as well if [`directories`][Options::directories]
+    /// is set, similar to passing `--force` twice to `git clean`.
+    pub repositories: bool,
+    /// If set, don't use more than this amount of threads when looking for untracked files.
+    /// Otherwise, usually use as many threads as there are logical cores.
+    /// A value of 0 is interpreted as no-limit
+    pub thread_limit: Option<usize>,
+    /// Capabilities of the file system, which affect how names of files on disk are matched with paths in the index.
+    pub fs: crate::fs::Capabilities,
+}
+
+/// The outcome of [`clean()`][crate::clean()].
+#[derive(Debug, Default, Clone)]
+pub struct Outcome {
+    /// All removed entries, sorted by path. Directories that were removed entirely are listed without their content.
+    pub removed: Vec<untracked::Entry>,
+    /// The nested repositories that were not removed as [`Options::repositories`] wasn't set.
+    pub skipped_repositories: Vec<untracked::Entry>,
+}
+
+/// The error returned by [`clean()`][crate::clean()].
+#[derive(Debug, thiserror::Error)]
+#[allow(missing_docs)]
+pub enum Error {
+    #[error(transparent)]
+    Walk(#[from] untracked::Error),
+    #[error("Could not remove '{}'", path.display())]
+    Remove { path: PathBuf, source: std::io::Error },
+}
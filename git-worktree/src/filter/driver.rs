@@ -0,0 +1,73 @@
+use std::{
+    io::{self, Read, Write},
+    process::Stdio,
+};
+
+use bstr::{BStr, BString, ByteSlice};
+
+/// Run the filter `command` of a driver once for the file at `rela_path`, feeding it `data` and collecting its output in `out`.
+///
+/// Like git, `%f` in `command` is replaced with the quoted `rela_path`.
+pub fn run(command: &BStr, rela_path: &BStr, data: &[u8], out: &mut Vec<u8>) -> io::Result<()> {
+    let command = substitute_path(command, rela_path);
+    let mut child = spawn(command.as_ref())?;
+
+    let mut stdin = child.stdin.take().expect("configured");
+    let writer = std::thread::spawn({
+        let data = data.to_vec();
+        move || match stdin.write_all(&data) {
+            // Filters may decide not to consume their input, which is fine as long as they succeed.
+            Err(err) if err.kind() == io::ErrorKind::BrokenPipe => Ok(()),
+            res => res,
+        }
+    });
+    let read_res = child.stdout.take().expect("configured").read_to_end(out);
+    let write_res = writer.join().expect("no panic");
+    let status = child.wait()?;
+    read_res?;
+    write_res?;
+    if !status.success() {
+        return Err(io::Error::new(
+            io::ErrorKind::Other,
+            format!("Filter command '{}' failed with {}", command, status),
+        ));
+    }
+    Ok(())
+}
+
+/// Spawn `command` with a shell, with piped stdin and stdout.
+pub(crate) fn spawn(command: &BStr) -> io::Result<std::process::Child> {
+    git_command::prepare(git_path::from_bstr(command).into_owned().into_os_string())
+        .with_shell()
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .spawn()
+}
+
+fn substitute_path(command: &BStr, rela_path: &BStr) -> BString {
+    let mut out = BString::default();
+    let mut last = 0;
+    for pos in command.find_iter("%f") {
+        out.extend_from_slice(&command[last..pos]);
+        quote(rela_path, &mut out);
+        last = pos + 2;
+    }
+    out.extend_from_slice(&command[last..]);
+    out
+}
+
+/// Quote `input` for use in a shell the way git does, by putting it in single quotes and escaping `'` and `!`.
+fn quote(input: &BStr, out: &mut BString) {
+    out.push(b'\'');
+    for b in input.iter().copied() {
+        match b {
+            b'\'' | b'!' => {
+                out.extend_from_slice(b"'\\");
+                out.push(b);
+                out.push(b'\'');
+            }
+            _ => out.push(b),
+        }
+    }
+    out.push(b'\'');
+}
@@ -0,0 +1,195 @@
+use bstr::BStr;
+
+/// An encoding of files in the worktree as configured with the `working-tree-encoding` attribute.
+///
+/// Content in git is always UTF-8, which is why it's converted from and to this encoding.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Encoding {
+    /// UTF-8, which means no conversion is necessary.
+    Utf8,
+    /// UTF-16 with a byte-order mark, written in little-endian byte order.
+    Utf16,
+    /// UTF-16 in little-endian byte order without byte-order mark.
+    Utf16Le,
+    /// UTF-16 in big-endian byte order without byte-order mark.
+    Utf16Be,
+    /// UTF-32 with a byte-order mark, written in little-endian byte order.
+    Utf32,
+    /// UTF-32 in little-endian byte order without byte-order mark.
+    Utf32Le,
+    /// UTF-32 in big-endian byte order without byte-order mark.
+    Utf32Be,
+    /// ISO-8859-1, which can represent the first 256 unicode code points only.
+    Latin1,
+}
+
+impl Encoding {
+    /// Return the encoding known by `name`, compared case-insensitively, or `None` if it isn't supported.
+    pub fn from_name(name: &BStr) -> Option<Self> {
+        let name = name.to_ascii_uppercase();
+        Some(match name.as_slice() {
+            b"UTF-8" | b"UTF8" => Encoding::Utf8,
+            b"UTF-16" | b"UTF16" => Encoding::Utf16,
+            b"UTF-16LE" | b"UTF16LE" => Encoding::Utf16Le,
+            b"UTF-16BE" | b"UTF16BE" => Encoding::Utf16Be,
+            b"UTF-32" | b"UTF32" => Encoding::Utf32,
+            b"UTF-32LE" | b"UTF32LE" => Encoding::Utf32Le,
+            b"UTF-32BE" | b"UTF32BE" => Encoding::Utf32Be,
+            b"ISO-8859-1" | b"ISO8859-1" | b"LATIN1" | b"LATIN-1" => Encoding::Latin1,
+            _ => return None,
+        })
+    }
+
+    fn unit_size(&self) -> usize {
+        match self {
+            Encoding::Utf8 | Encoding::Latin1 => 1,
+            Encoding::Utf16 | Encoding::Utf16Le | Encoding::Utf16Be => 2,
+            Encoding::Utf32 | Encoding::Utf32Le | Encoding::Utf32Be => 4,
+        }
+    }
+}
+
+/// The error returned by [`encode()`] and [`decode()`].
+#[derive(Debug, thiserror::Error)]
+#[allow(missing_docs)]
+pub enum Error {
+    #[error("The content isn't valid UTF-8")]
+    InvalidUtf8(#[from] std::str::Utf8Error),
+    #[error("The character {character:?} can't be represented")]
+    Unrepresentable { character: char },
+    #[error("The content isn't valid in its encoding at byte {position}")]
+    Invalid { position: usize },
+    #[error("A byte-order mark is required")]
+    MissingByteOrderMark,
+    #[error("A byte-order mark is prohibited as the byte order is implied by the encoding")]
+    ProhibitedByteOrderMark,
+}
+
+const BOM_UTF16_LE: &[u8] = &[0xff, 0xfe];
+const BOM_UTF16_BE: &[u8] = &[0xfe, 0xff];
+const BOM_UTF32_LE: &[u8] = &[0xff, 0xfe, 0, 0];
+const BOM_UTF32_BE: &[u8] = &[0, 0, 0xfe, 0xff];
+
+/// Encode the UTF-8 `input` as stored in git to `encoding` for use in the worktree, and write the result into `out`.
+/// Return `true` if `out` was written, or `false` if there was nothing to do.
+pub fn encode(input: &[u8], encoding: Encoding, out: &mut Vec<u8>) -> Result<bool, Error> {
+    if encoding == Encoding::Utf8 || input.is_empty() {
+        return Ok(false);
+    }
+    let input = std::str::from_utf8(input)?;
+    out.reserve(input.len() * encoding.unit_size());
+    match encoding {
+        Encoding::Utf8 => unreachable!("handled above"),
+        Encoding::Latin1 => {
+            for character in input.chars() {
+                let byte = u8::try_from(u32::from(character)).map_err(|_| Error::Unrepresentable { character })?;
+                out.push(byte);
+            }
+        }
+        Encoding::Utf16 | Encoding::Utf16Le | Encoding::Utf16Be => {
+            if encoding == Encoding::Utf16 {
+                out.extend_from_slice(BOM_UTF16_LE);
+            }
+            for unit in input.encode_utf16() {
+                out.extend_from_slice(&match encoding {
+                    Encoding::Utf16Be => unit.to_be_bytes(),
+                    _ => unit.to_le_bytes(),
+                });
+            }
+        }
+        Encoding::Utf32 | Encoding::Utf32Le | Encoding::Utf32Be => {
+            if encoding == Encoding::Utf32 {
+                out.extend_from_slice(BOM_UTF32_LE);
+            }
+            for character in input.chars() {
+                let unit = u32::from(character);
+                out.extend_from_slice(&match encoding {
+                    Encoding::Utf32Be => unit.to_be_bytes(),
+                    _ => unit.to_le_bytes(),
+                });
+            }
+        }
+    }
+    Ok(true)
+}
+
+/// Decode `input` from the worktree in `encoding` into UTF-8 for storage in git, and write the result into `out`.
+/// Return `true` if `out` was written, or `false` if there was nothing to do.
+///
+/// Like git, we require a byte-order mark for encodings that don't imply a byte order, and prohibit it for those that do.
+pub fn decode(input: &[u8], encoding: Encoding, out: &mut Vec<u8>) -> Result<bool, Error> {
+    if encoding == Encoding::Utf8 || input.is_empty() {
+        return Ok(false);
+    }
+    let (data, big_endian) = match encoding {
+        Encoding::Utf8 => unreachable!("handled above"),
+        Encoding::Latin1 => {
+            out.reserve(input.len());
+            for byte in input {
+                let mut buf = [0; 4];
+                out.extend_from_slice(char::from(*byte).encode_utf8(&mut buf).as_bytes());
+            }
+            return Ok(true);
+        }
+        Encoding::Utf16 => match input.get(..2) {
+            Some(BOM_UTF16_LE) => (&input[2..], false),
+            Some(BOM_UTF16_BE) => (&input[2..], true),
+            _ => return Err(Error::MissingByteOrderMark),
+        },
+        Encoding::Utf32 => match input.get(..4) {
+            Some(BOM_UTF32_LE) => (&input[4..], false),
+            Some(BOM_UTF32_BE) => (&input[4..], true),
+            _ => return Err(Error::MissingByteOrderMark),
+        },
+        Encoding::Utf16Le | Encoding::Utf16Be | Encoding::Utf32Le | Encoding::Utf32Be => {
+            if [BOM_UTF16_LE, BOM_UTF16_BE, BOM_UTF32_BE]
+                .iter()
+                .any(|bom| input.starts_with(bom))
+            {
+                return Err(Error::ProhibitedByteOrderMark);
+            }
+            (input, matches!(encoding, Encoding::Utf16Be | Encoding::Utf32Be))
+        }
+    };
+    let unit_size = encoding.unit_size();
+    let bom_size = input.len() - data.len();
+    let units = data.chunks(unit_size);
+    if data.len() % unit_size != 0 {
+        return Err(Error::Invalid {
+            position: input.len() - data.len() % unit_size,
+        });
+    }
+
+    out.reserve(data.len() / unit_size);
+    let mut buf = [0; 4];
+    if unit_size == 2 {
+        let units = units.map(|unit| {
+            let unit = [unit[0], unit[1]];
+            if big_endian {
+                u16::from_be_bytes(unit)
+            } else {
+                u16::from_le_bytes(unit)
+            }
+        });
+        let mut position = bom_size;
+        for character in char::decode_utf16(units) {
+            let character = character.map_err(|_| Error::Invalid { position })?;
+            position += character.len_utf16() * 2;
+            out.extend_from_slice(character.encode_utf8(&mut buf).as_bytes());
+        }
+    } else {
+        for (idx, unit) in units.enumerate() {
+            let unit = [unit[0], unit[1], unit[2], unit[3]];
+            let unit = if big_endian {
+                u32::from_be_bytes(unit)
+            } else {
+                u32::from_le_bytes(unit)
+            };
+            let character = char::from_u32(unit).ok_or(Error::Invalid {
+                position: bom_size + idx * 4,
+            })?;
+            out.extend_from_slice(character.encode_utf8(&mut buf).as_bytes());
+        }
+    }
+    Ok(true)
+}
@@ -0,0 +1,160 @@
+use bstr::ByteSlice;
+
+use crate::filter::{Eol, Text};
+
+/// Statistics about the content of a buffer, similar to what git collects to decide if content is text, and which line endings it uses.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Stats {
+    /// The amount of null bytes.
+    pub null: usize,
+    /// The amount of carriage returns not followed by a line feed.
+    pub lone_cr: usize,
+    /// The amount of line feeds not preceded by a carriage return.
+    pub lone_lf: usize,
+    /// The amount of carriage returns followed by a line feed.
+    pub crlf: usize,
+    /// The amount of printable characters.
+    pub printable: usize,
+    /// The amount of non-printable characters.
+    pub non_printable: usize,
+}
+
+impl Stats {
+    /// Gather statistics about `data`.
+    pub fn from_bytes(data: &[u8]) -> Self {
+        let mut stats = Stats::default();
+        let mut bytes = data.iter().peekable();
+        while let Some(&b) = bytes.next() {
+            match b {
+                b'\r' => {
+                    if bytes.peek() == Some(&&b'\n') {
+                        bytes.next();
+                        stats.crlf += 1;
+                    } else {
+                        stats.lone_cr += 1;
+                    }
+                }
+                b'\n' => stats.lone_lf += 1,
+                127 => stats.non_printable += 1,
+                0 => {
+                    stats.null += 1;
+                    stats.non_printable += 1;
+                }
+                // Backspace, tab, escape and form feed are common in text files.
+                b'\x08' | b'\t' | b'\x1b' | b'\x0c' => stats.printable += 1,
+                b if b < 32 => stats.non_printable += 1,
+                _ => stats.printable += 1,
+            }
+        }
+        // A trailing end-of-file marker as written by some DOS tools is ignored.
+        if data.last() == Some(&b'\x1a') {
+            stats.non_printable -= 1;
+        }
+        stats
+    }
+
+    /// Return true if this content is considered binary, in which case automatic line-ending conversions don't apply.
+    pub fn is_binary(&self) -> bool {
+        self.lone_cr > 0 || self.null > 0 || (self.printable >> 7) < self.non_printable
+    }
+}
+
+/// Convert LF line endings in `input` to `eol` if the path is known to be text according to `text` or `has_eol_attribute`,
+/// and write the result into `out`.
+/// Return `true` if `out` was written, or `false` if there was nothing to do.
+pub fn convert_to_worktree(input: &[u8], text: Text, has_eol_attribute: bool, eol: Eol, out: &mut Vec<u8>) -> bool {
+    if eol != Eol::Crlf || !is_text(text, has_eol_attribute) {
+        return false;
+    }
+    let stats = Stats::from_bytes(input);
+    if stats.lone_lf == 0 {
+        return false;
+    }
+    // Files that already contain carriage returns in git were committed that way on purpose, so we don't touch them.
+    if text == Text::Auto && (stats.lone_cr > 0 || stats.crlf > 0 || stats.is_binary()) {
+        return false;
+    }
+    out.reserve(input.len() + stats.lone_lf);
+    let mut last = 0;
+    for pos in input.find_iter(b"\n") {
+        if pos == 0 || input[pos - 1] != b'\r' {
+            out.extend_from_slice(&input[last..pos]);
+            out.extend_from_slice(b"\r\n");
+            last = pos + 1;
+        }
+    }
+    out.extend_from_slice(&input[last..]);
+    true
+}
+
+/// Convert CRLF line endings in `input` to LF if the path is known to be text according to `text` or `has_eol_attribute`,
+/// and write the result into `out`.
+/// Return `true` if `out` was written, or `false` if there was nothing to do.
+pub fn convert_to_git(input: &[u8], text: Text, has_eol_attribute: bool, out: &mut Vec<u8>) -> bool {
+    if !is_text(text, has_eol_attribute) {
+        return false;
+    }
+    let stats = Stats::from_bytes(input);
+    if stats.crlf == 0 || (text == Text::Auto && stats.is_binary()) {
+        return false;
+    }
+    out.reserve(input.len() - stats.crlf);
+    let mut last = 0;
+    for pos in input.find_iter(b"\r\n") {
+        out.extend_from_slice(&input[last..pos]);
+        last = pos + 1;
+    }
+    out.extend_from_slice(&input[last..]);
+    true
+}
+
+/// A line-ending conversion of a file that isn't undone when storing it in git and checking it out again.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Irreversible {
+    /// CRLF line endings in the worktree would be replaced by LF.
+    CrlfToLf,
+    /// LF line endings in the worktree would be replaced by CRLF.
+    LfToCrlf,
+}
+
+impl std::fmt::Display for Irreversible {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            Irreversible::CrlfToLf => "CRLF would be replaced by LF",
+            Irreversible::LfToCrlf => "LF would be replaced by CRLF",
+        })
+    }
+}
+
+/// Return the kind of irreversible conversion that happens if worktree content `input` is stored in git with
+/// [`convert_to_git()`] and checked out again with [`convert_to_worktree()`] with `eol`, or `None` if the content would be
+/// unchanged after the round-trip.
+pub fn check_round_trip(input: &[u8], text: Text, has_eol_attribute: bool, eol: Eol) -> Option<Irreversible> {
+    if !is_text(text, has_eol_attribute) {
+        return None;
+    }
+    let stats = Stats::from_bytes(input);
+    if text == Text::Auto && stats.is_binary() {
+        return None;
+    }
+    let (mut lone_lf, mut crlf) = (stats.lone_lf + stats.crlf, 0);
+    if eol == Eol::Crlf {
+        crlf = lone_lf;
+        lone_lf = 0;
+    }
+    if stats.crlf > 0 && crlf == 0 {
+        Some(Irreversible::CrlfToLf)
+    } else if stats.lone_lf > 0 && lone_lf == 0 {
+        Some(Irreversible::LfToCrlf)
+    } else {
+        None
+    }
+}
+
+fn is_text(text: Text, has_eol_attribute: bool) -> bool {
+    match text {
+        Text::Set | Text::Auto => true,
+        Text::Unset => false,
+        Text::Unspecified => has_eol_attribute,
+    }
+}
@@ -0,0 +1,60 @@
+use bstr::ByteSlice;
+use git_hash::oid;
+
+/// Find all `$Id$` and `$Id: <anything> $` keywords in `input`, returning the start of each and the position past its closing `$`.
+fn keywords(input: &[u8]) -> impl Iterator<Item = (usize, usize)> + '_ {
+    let mut pos = 0;
+    std::iter::from_fn(move || loop {
+        let start = pos + input[pos..].find(b"$Id")?;
+        pos = start + 3;
+        match input.get(pos) {
+            Some(b'$') => {
+                pos += 1;
+                return Some((start, pos));
+            }
+            Some(b':') => {
+                let end = pos
+                    + input[pos..]
+                        .iter()
+                        .position(|b| *b == b'$' || *b == b'\n')
+                        .unwrap_or(input.len() - pos);
+                if input.get(end) == Some(&b'$') {
+                    pos = end + 1;
+                    return Some((start, pos));
+                }
+                pos = end;
+            }
+            _ => {}
+        }
+    })
+}
+
+/// Replace all `$Id$` and `$Id: <anything> $` keywords in `input` with `$Id: <id> $` and write the result into `out`.
+/// Return `true` if `out` was written, or `false` if there was nothing to do.
+pub fn apply(input: &[u8], id: &oid, out: &mut Vec<u8>) -> bool {
+    replace(input, out, |out| {
+        out.extend_from_slice(b"$Id: ");
+        out.extend_from_slice(id.to_hex().to_string().as_bytes());
+        out.extend_from_slice(b" $");
+    })
+}
+
+/// Replace all `$Id: <anything> $` keywords in `input` with `$Id$` and write the result into `out`.
+/// Return `true` if `out` was written, or `false` if there was nothing to do.
+pub fn undo(input: &[u8], out: &mut Vec<u8>) -> bool {
+    replace(input, out, |out| out.extend_from_slice(b"$Id$"))
+}
+
+fn replace(input: &[u8], out: &mut Vec<u8>, mut write_keyword: impl FnMut(&mut Vec<u8>)) -> bool {
+    let mut last_end = 0;
+    for (start, end) in keywords(input) {
+        out.extend_from_slice(&input[last_end..start]);
+        write_keyword(out);
+        last_end = end;
+    }
+    if last_end == 0 {
+        return false;
+    }
+    out.extend_from_slice(&input[last_end..]);
+    true
+}
@@ -0,0 +1,555 @@
+//! Conversions of blob content when it is written into the worktree (_smudge_) or read from it to be stored in git (_clean_),
+//! configured per path using the `text`, `eol`, `ident`, `working-tree-encoding` and `filter` attributes.
+//!
+//! Built-in conversions are line-ending normalization, re-encoding and the expansion of `$Id$` keywords, while external conversions are
+//! performed by filter drivers, which are either invoked once per file or run as long-running process.
+use bstr::{BStr, BString, ByteSlice};
+use git_hash::oid;
+
+pub(crate) mod driver;
+///
+pub mod encoding;
+///
+pub mod eol;
+///
+pub mod ident;
+pub(crate) mod process;
+
+/// The kind of line-ending normalization to apply, as configured by the `text` attribute.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Text {
+    /// The attribute isn't mentioned, which means no conversion is performed.
+    Unspecified,
+    /// The path is a text file, line endings are normalized to LF in git and converted to the configured line ending in the worktree.
+    Set,
+    /// The path isn't a text file and no conversion is performed.
+    Unset,
+    /// Like [`Set`][Text::Set], but only if git detects that the content is text and doesn't know any CRLF in git.
+    Auto,
+}
+
+impl Default for Text {
+    fn default() -> Self {
+        Text::Unspecified
+    }
+}
+
+/// A line ending.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Eol {
+    /// A line feed, `\n`.
+    Lf,
+    /// A carriage return followed by a line feed, `\r\n`.
+    Crlf,
+}
+
+impl Eol {
+    /// The line ending native to the current platform.
+    pub fn native() -> Self {
+        if cfg!(windows) {
+            Eol::Crlf
+        } else {
+            Eol::Lf
+        }
+    }
+}
+
+impl Default for Eol {
+    fn default() -> Self {
+        Eol::native()
+    }
+}
+
+/// The attributes of a path that are relevant for its conversion.
+#[derive(Debug, Default, Clone, PartialEq, Eq, Hash)]
+pub struct Attributes {
+    /// The state of the `text` attribute, or of the legacy `crlf` attribute if `text` isn't mentioned.
+    pub text: Text,
+    /// The value of the `eol` attribute, which implies [`Text::Set`] unless the path is [`auto`][Text::Auto] or not a text file.
+    pub eol: Option<Eol>,
+    /// If true, the `ident` attribute is set and `$Id$` is expanded with the id of the blob.
+    pub ident: bool,
+    /// The name of the filter driver to use as set with `filter=<name>`.
+    pub filter: Option<BString>,
+    /// The name of the encoding of the file in the worktree as set with `working-tree-encoding=<encoding>`, with content
+    /// in git always being UTF-8.
+    pub working_tree_encoding: Option<BString>,
+}
+
+impl Attributes {
+    /// Extract all attributes relevant for conversion from `assignments`, like the ones obtained with
+    /// [`Platform::matching_attributes()`][crate::fs::cache::Platform::matching_attributes()].
+    pub fn from_assignments(assignments: &[git_attributes::Assignment]) -> Self {
+        use git_attributes::State;
+        let state_of = |name: &str| {
+            assignments
+                .iter()
+                .find(|assignment| assignment.name.as_str() == name)
+                .map(|assignment| &assignment.state)
+        };
+        let text_from_state = |state: &State| match state {
+            State::Set => Some(Text::Set),
+            State::Unset => Some(Text::Unset),
+            State::Value(value) if value.as_str() == "auto" => Some(Text::Auto),
+            State::Value(value) if value.as_str() == "input" => Some(Text::Set),
+            State::Value(_) | State::Unspecified => None,
+        };
+        let text = state_of("text")
+            .and_then(text_from_state)
+            .or_else(|| state_of("crlf").and_then(text_from_state))
+            .unwrap_or_default();
+        let eol = match state_of("eol") {
+            Some(State::Value(value)) if value.as_str() == "lf" => Some(Eol::Lf),
+            Some(State::Value(value)) if value.as_str() == "crlf" => Some(Eol::Crlf),
+            _ if state_of("text").is_none()
+                && matches!(state_of("crlf"), Some(State::Value(value)) if value.as_str() == "input") =>
+            {
+                Some(Eol::Lf)
+            }
+            _ => None,
+        };
+        Attributes {
+            text,
+            eol,
+            ident: matches!(state_of("ident"), Some(State::Set)),
+            filter: match state_of("filter") {
+                Some(State::Value(name)) => Some(name.as_str().into()),
+                _ => None,
+            },
+            working_tree_encoding: match state_of("working-tree-encoding") {
+                Some(State::Value(name)) => Some(name.as_str().into()),
+                _ => None,
+            },
+        }
+    }
+}
+
+/// A filter driver as configured in the `filter.<name>` section of the git configuration.
+#[derive(Debug, Default, Clone, PartialEq, Eq, Hash)]
+pub struct Driver {
+    /// The name of the driver as used in the `filter` attribute, like `lfs`.
+    pub name: BString,
+    /// The command to run to convert worktree content into its form in git, with `%f` substituted with the quoted path.
+    pub clean: Option<BString>,
+    /// The command to run to convert content in git into its form in the worktree, with `%f` substituted with the quoted path.
+    pub smudge: Option<BString>,
+    /// The command to start a long-running filter process speaking the filter protocol in version 2, which is
+    /// preferred over `clean` and `smudge`.
+    pub process: Option<BString>,
+    /// If true, failures of the driver are errors, and the content is passed through unchanged otherwise.
+    pub required: bool,
+}
+
+/// How line endings of paths without `text` attribute are handled, similar to `core.autocrlf`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum AutoCrlf {
+    /// Paths are only converted if their attributes say so.
+    Disabled,
+    /// Paths without `text` attribute are treated as [`auto`][Text::Auto] and use CRLF in the worktree.
+    Enabled,
+    /// Paths without `text` attribute are treated as [`auto`][Text::Auto], but only normalized to LF when stored in git.
+    Input,
+}
+
+impl Default for AutoCrlf {
+    fn default() -> Self {
+        AutoCrlf::Disabled
+    }
+}
+
+/// What to do if normalizing line endings when storing a file in git can't be undone by checking it out again,
+/// similar to `core.safecrlf`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum SafeCrlf {
+    /// Don't check for irreversible conversions.
+    Disabled,
+    /// Convert anyway, but keep a warning for the caller to obtain with [`Pipeline::take_eol_warnings()`].
+    Warn,
+    /// Fail the conversion with an error.
+    Fail,
+}
+
+impl Default for SafeCrlf {
+    fn default() -> Self {
+        SafeCrlf::Warn
+    }
+}
+
+/// Options for use in a [`Pipeline`].
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Options {
+    /// The line ending to use in the worktree for text files that don't specify one with the `eol` attribute,
+    /// similar to `core.eol`. It's ignored unless `auto_crlf` is [disabled][AutoCrlf::Disabled].
+    pub eol: Eol,
+    /// How to treat paths without `text` attribute, similar to `core.autocrlf`.
+    pub auto_crlf: AutoCrlf,
+    /// What to do if line-ending conversions when storing files in git are irreversible, similar to `core.safecrlf`.
+    pub safe_crlf: SafeCrlf,
+}
+
+impl Options {
+    /// Return the effective state of the `text` attribute for a path with `attributes` along with the line ending it
+    /// should have in the worktree, taking `core.autocrlf` and `core.eol` into account like git does.
+    pub fn text_and_eol(&self, attributes: &Attributes) -> (Text, Eol) {
+        let text = match attributes.text {
+            Text::Unspecified if attributes.eol.is_some() => Text::Set,
+            Text::Unspecified => match self.auto_crlf {
+                AutoCrlf::Disabled => Text::Unspecified,
+                AutoCrlf::Enabled | AutoCrlf::Input => Text::Auto,
+            },
+            text => text,
+        };
+        let eol = attributes.eol.unwrap_or(match self.auto_crlf {
+            AutoCrlf::Enabled => Eol::Crlf,
+            AutoCrlf::Input => Eol::Lf,
+            AutoCrlf::Disabled => self.eol,
+        });
+        (text, eol)
+    }
+}
+
+/// The operation a filter driver is used for.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Operation {
+    /// Convert the content of a blob for use in the worktree.
+    Smudge,
+    /// Convert the content of a worktree file for storage in git.
+    Clean,
+}
+
+impl Operation {
+    /// Return the name of the operation as used in configuration and the filter protocol.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Operation::Smudge => "smudge",
+            Operation::Clean => "clean",
+        }
+    }
+}
+
+impl std::fmt::Display for Operation {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// The error returned by [`Pipeline::convert_to_worktree()`] and [`Pipeline::convert_to_git()`].
+#[derive(Debug, thiserror::Error)]
+#[allow(missing_docs)]
+pub enum Error {
+    #[error("The required filter driver '{name}' has no command to {operation}")]
+    MissingCommand { name: BString, operation: Operation },
+    #[error("The required filter driver '{name}' failed to {operation} '{path}'")]
+    Driver {
+        name: BString,
+        operation: Operation,
+        path: BString,
+        source: std::io::Error,
+    },
+    #[error("The working-tree-encoding '{encoding}' of '{path}' isn't supported")]
+    UnsupportedEncoding { encoding: BString, path: BString },
+    #[error("Could not convert '{path}' with working-tree-encoding '{encoding}'")]
+    Encoding {
+        encoding: BString,
+        path: BString,
+        source: encoding::Error,
+    },
+    #[error("{conversion} in '{path}'")]
+    IrreversibleEol {
+        path: BString,
+        conversion: eol::Irreversible,
+    },
+}
+
+/// A utility to apply all conversions configured for paths to their content, keeping long-running filter processes alive
+/// for as long as the pipeline exists.
+///
+/// Note that cloning a pipeline clones its configuration, but not its running processes.
+pub struct Pipeline {
+    drivers: Vec<Driver>,
+    options: Options,
+    /// Long-running processes along with the index of their driver, or `None` if they couldn't be started.
+    processes: Vec<(usize, Option<process::Client>)>,
+    /// A buffer for intermediate results if multiple conversions apply.
+    buf: Vec<u8>,
+    /// Paths whose line-ending conversion can't be undone, if we only warn about it.
+    eol_warnings: Vec<(BString, eol::Irreversible)>,
+}
+
+impl Clone for Pipeline {
+    fn clone(&self) -> Self {
+        Pipeline::new(self.drivers.clone(), self.options)
+    }
+}
+
+impl Default for Pipeline {
+    fn default() -> Self {
+        Pipeline::new(Vec::new(), Default::default())
+    }
+}
+
+impl std::fmt::Debug for Pipeline {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Pipeline")
+            .field("drivers", &self.drivers)
+            .field("options", &self.options)
+            .finish()
+    }
+}
+
+/// Lifecycle
+impl Pipeline {
+    /// Create a new instance to use `drivers` as referred to by the `filter` attribute and `options` for built-in conversions.
+    pub fn new(drivers: Vec<Driver>, options: Options) -> Self {
+        Pipeline {
+            drivers,
+            options,
+            processes: Vec::new(),
+            buf: Vec::new(),
+            eol_warnings: Vec::new(),
+        }
+    }
+}
+
+/// Access
+impl Pipeline {
+    /// Return all drivers we know.
+    pub fn drivers(&self) -> &[Driver] {
+        &self.drivers
+    }
+
+    /// Return the options for built-in conversions.
+    pub fn options(&self) -> Options {
+        self.options
+    }
+
+    /// Return all paths along with their line-ending conversion that [`convert_to_git()`][Pipeline::convert_to_git()]
+    /// found to be irreversible since the last call, if [`SafeCrlf::Warn`] is configured.
+    pub fn take_eol_warnings(&mut self) -> Vec<(BString, eol::Irreversible)> {
+        std::mem::take(&mut self.eol_warnings)
+    }
+}
+
+/// Conversion
+impl Pipeline {
+    /// Convert `data` of the blob with `id` at `rela_path` with its conversion-relevant `attributes` for use in the worktree
+    /// by expanding `$Id$`, converting line endings, encoding it with the `working-tree-encoding` and running the smudge filter,
+    /// in that order.
+    ///
+    /// Return `true` if `out` was cleared and filled with the converted content, or `false` if `data` is to be used as is.
+    pub fn convert_to_worktree(
+        &mut self,
+        data: &[u8],
+        rela_path: &BStr,
+        attributes: &Attributes,
+        id: &oid,
+        out: &mut Vec<u8>,
+    ) -> Result<bool, Error> {
+        let mut converted = false;
+        let (text, eol) = self.options.text_and_eol(attributes);
+        let Pipeline {
+            drivers,
+            processes,
+            buf,
+            ..
+        } = self;
+        if attributes.ident {
+            apply(&mut converted, data, out, buf, |input, out| {
+                Ok(ident::apply(input, id, out))
+            })?;
+        }
+        apply(&mut converted, data, out, buf, |input, out| {
+            Ok(eol::convert_to_worktree(
+                input,
+                text,
+                attributes.eol.is_some(),
+                eol,
+                out,
+            ))
+        })?;
+        if let Some(name) = &attributes.working_tree_encoding {
+            let encoding = encoding_by_name(name.as_ref(), rela_path)?;
+            apply(&mut converted, data, out, buf, |input, out| {
+                encoding::encode(input, encoding, out).map_err(|source| Error::Encoding {
+                    encoding: name.clone(),
+                    path: rela_path.to_owned(),
+                    source,
+                })
+            })?;
+        }
+        if let Some(name) = &attributes.filter {
+            apply(&mut converted, data, out, buf, |input, out| {
+                run_driver(
+                    drivers,
+                    processes,
+                    name.as_ref(),
+                    Operation::Smudge,
+                    rela_path,
+                    input,
+                    out,
+                )
+            })?;
+        }
+        Ok(converted)
+    }
+
+    /// Convert `data` of the worktree file at `rela_path` with its conversion-relevant `attributes` for storage in git
+    /// by running the clean filter, decoding it from its `working-tree-encoding`, normalizing line endings and collapsing
+    /// `$Id$` keywords, in that order.
+    ///
+    /// Return `true` if `out` was cleared and filled with the converted content, or `false` if `data` is to be used as is.
+    /// This is what makes hashing worktree files for comparison with the index match what `git add` would store.
+    /// Irreversible line-ending conversions are handled according to [`Options::safe_crlf`].
+    pub fn convert_to_git(
+        &mut self,
+        data: &[u8],
+        rela_path: &BStr,
+        attributes: &Attributes,
+        out: &mut Vec<u8>,
+    ) -> Result<bool, Error> {
+        let mut converted = false;
+        let (text, eol) = self.options.text_and_eol(attributes);
+        let safe_crlf = self.options.safe_crlf;
+        let Pipeline {
+            drivers,
+            processes,
+            buf,
+            eol_warnings,
+            ..
+        } = self;
+        if let Some(name) = &attributes.filter {
+            apply(&mut converted, data, out, buf, |input, out| {
+                run_driver(
+                    drivers,
+                    processes,
+                    name.as_ref(),
+                    Operation::Clean,
+                    rela_path,
+                    input,
+                    out,
+                )
+            })?;
+        }
+        if let Some(name) = &attributes.working_tree_encoding {
+            let encoding = encoding_by_name(name.as_ref(), rela_path)?;
+            apply(&mut converted, data, out, buf, |input, out| {
+                encoding::decode(input, encoding, out).map_err(|source| Error::Encoding {
+                    encoding: name.clone(),
+                    path: rela_path.to_owned(),
+                    source,
+                })
+            })?;
+        }
+        apply(&mut converted, data, out, buf, |input, out| {
+            if safe_crlf != SafeCrlf::Disabled {
+                if let Some(conversion) = eol::check_round_trip(input, text, attributes.eol.is_some(), eol) {
+                    if safe_crlf == SafeCrlf::Fail {
+                        return Err(Error::IrreversibleEol {
+                            path: rela_path.to_owned(),
+                            conversion,
+                        });
+                    }
+                    eol_warnings.push((rela_path.to_owned(), conversion));
+                }
+            }
+            Ok(eol::convert_to_git(input, text, attributes.eol.is_some(), out))
+        })?;
+        if attributes.ident {
+            apply(&mut converted, data, out, buf, |input, out| Ok(ident::undo(input, out)))?;
+        }
+        Ok(converted)
+    }
+}
+
+/// Run `convert` on the latest version of the content, which is `data` unless a previous conversion already put its output
+/// into `out`, and make sure `out` holds the result if `convert` returned true.
+fn apply(
+    converted: &mut bool,
+    data: &[u8],
+    out: &mut Vec<u8>,
+    buf: &mut Vec<u8>,
+    convert: impl FnOnce(&[u8], &mut Vec<u8>) -> Result<bool, Error>,
+) -> Result<(), Error> {
+    if *converted {
+        buf.clear();
+        if convert(out, buf)? {
+            std::mem::swap(out, buf);
+        }
+    } else {
+        out.clear();
+        *converted = convert(data, out)?;
+    }
+    Ok(())
+}
+
+fn encoding_by_name(name: &BStr, rela_path: &BStr) -> Result<encoding::Encoding, Error> {
+    encoding::Encoding::from_name(name).ok_or_else(|| Error::UnsupportedEncoding {
+        encoding: name.to_owned(),
+        path: rela_path.to_owned(),
+    })
+}
+
+fn run_driver(
+    drivers: &[Driver],
+    processes: &mut Vec<(usize, Option<process::Client>)>,
+    name: &BStr,
+    operation: Operation,
+    rela_path: &BStr,
+    data: &[u8],
+    out: &mut Vec<u8>,
+) -> Result<bool, Error> {
+    let (driver_idx, driver) = match drivers.iter().enumerate().find(|(_, driver)| driver.name == name) {
+        Some(driver) => driver,
+        // Like git, we ignore unknown drivers unless there is configuration that says they are required.
+        None => return Ok(false),
+    };
+    let fail = |source: std::io::Error| {
+        if driver.required {
+            Err(Error::Driver {
+                name: driver.name.clone(),
+                operation,
+                path: rela_path.to_owned(),
+                source,
+            })
+        } else {
+            Ok(false)
+        }
+    };
+
+    if let Some(command) = &driver.process {
+        let client = match processes.iter_mut().find(|(idx, _)| *idx == driver_idx) {
+            Some((_, client)) => client,
+            None => {
+                let client = process::Client::start(command.as_bstr()).ok();
+                processes.push((driver_idx, client));
+                &mut processes.last_mut().expect("just pushed").1
+            }
+        };
+        return match client {
+            Some(client) if client.supports(operation) => match client.invoke(operation, rela_path, data, out) {
+                Ok(()) => Ok(true),
+                Err(err) => fail(err),
+            },
+            _ => fail(std::io::Error::new(
+                std::io::ErrorKind::Other,
+                format!("The filter process '{}' is unavailable for {}", command, operation),
+            )),
+        };
+    }
+
+    let command = match operation {
+        Operation::Smudge => driver.smudge.as_ref(),
+        Operation::Clean => driver.clean.as_ref(),
+    };
+    match command {
+        Some(command) => match driver::run(command.as_bstr(), rela_path, data, out) {
+            Ok(()) => Ok(true),
+            Err(err) => fail(err),
+        },
+        None if driver.required => Err(Error::MissingCommand {
+            name: driver.name.clone(),
+            operation,
+        }),
+        None => Ok(false),
+    }
+}
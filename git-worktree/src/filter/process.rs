@@ -0,0 +1,197 @@
+//! A client for the long-running filter process protocol in version 2, using pkt-lines for framing.
+use std::{
+    io::{self, BufReader, Read, Write},
+    process::{Child, ChildStdin, ChildStdout},
+};
+
+use bstr::{BStr, BString, ByteSlice};
+
+use crate::filter::{driver, Operation};
+
+/// The largest amount of data a single pkt-line can carry.
+const MAX_DATA_LEN: usize = 65516;
+
+/// A running filter process that completed the handshake.
+pub struct Client {
+    child: Child,
+    /// Only `None` when dropping.
+    stdin: Option<io::BufWriter<ChildStdin>>,
+    stdout: BufReader<ChildStdout>,
+    /// The capabilities the process supports, which are removed if the process aborts them.
+    capabilities: Vec<BString>,
+    line: Vec<u8>,
+}
+
+impl Client {
+    /// Start the process with `command` and perform the handshake with it.
+    pub fn start(command: &BStr) -> io::Result<Self> {
+        let mut child = driver::spawn(command)?;
+        let mut client = Client {
+            stdin: Some(io::BufWriter::new(child.stdin.take().expect("configured"))),
+            stdout: BufReader::new(child.stdout.take().expect("configured")),
+            child,
+            capabilities: Vec::new(),
+            line: Vec::new(),
+        };
+        client.handshake()?;
+        Ok(client)
+    }
+
+    /// Return true if the process can perform `operation`.
+    pub fn supports(&self, operation: Operation) -> bool {
+        self.capabilities.iter().any(|cap| cap == operation.as_str())
+    }
+
+    /// Ask the process to perform `operation` on `data` of the file at `rela_path` and put the result into `out`.
+    pub fn invoke(&mut self, operation: Operation, rela_path: &BStr, data: &[u8], out: &mut Vec<u8>) -> io::Result<()> {
+        {
+            let stdin = self.stdin.as_mut().expect("set unless dropped");
+            write_text(stdin, format!("command={}", operation).as_bytes())?;
+            let mut pathname = BString::from("pathname=");
+            pathname.extend_from_slice(rela_path);
+            write_text(stdin, &pathname)?;
+            write_flush(stdin)?;
+            for chunk in data.chunks(MAX_DATA_LEN) {
+                write_packet(stdin, chunk)?;
+            }
+            write_flush(stdin)?;
+            stdin.flush()?;
+        }
+
+        let mut status = self.read_status()?;
+        if status == "success" {
+            out.clear();
+            while self.read_line()? {
+                out.extend_from_slice(&self.line);
+            }
+            // The process may change its mind after sending the content, an empty list keeps the previous status.
+            if let Some(new_status) = self.read_status_list()? {
+                status = new_status;
+            }
+        }
+        match status.as_slice() {
+            b"success" => Ok(()),
+            b"abort" => {
+                self.capabilities.retain(|cap| cap != operation.as_str());
+                Err(io::Error::new(
+                    io::ErrorKind::Other,
+                    format!("Filter process aborted {} for all further files", operation),
+                ))
+            }
+            _ => Err(io::Error::new(
+                io::ErrorKind::Other,
+                format!(
+                    "Filter process failed to {} '{}' with status '{}'",
+                    operation, rela_path, status
+                ),
+            )),
+        }
+    }
+
+    fn handshake(&mut self) -> io::Result<()> {
+        {
+            let stdin = self.stdin.as_mut().expect("set unless dropped");
+            write_text(stdin, b"git-filter-client")?;
+            write_text(stdin, b"version=2")?;
+            write_flush(stdin)?;
+            stdin.flush()?;
+        }
+        let mut lines = Vec::new();
+        while self.read_line()? {
+            lines.push(BString::from(trim_line(&self.line)));
+        }
+        if lines.first().map_or(true, |line| line != "git-filter-server")
+            || !lines.iter().any(|line| line == "version=2")
+        {
+            return Err(protocol_error(format!(
+                "Unexpected handshake from filter process: {:?}",
+                lines
+            )));
+        }
+
+        {
+            let stdin = self.stdin.as_mut().expect("set unless dropped");
+            for operation in [Operation::Clean, Operation::Smudge] {
+                write_text(stdin, format!("capability={}", operation).as_bytes())?;
+            }
+            write_flush(stdin)?;
+            stdin.flush()?;
+        }
+        while self.read_line()? {
+            if let Some(capability) = trim_line(&self.line).strip_prefix(b"capability=") {
+                self.capabilities.push(capability.into());
+            }
+        }
+        Ok(())
+    }
+
+    /// Read the status of a list of `key=value` lines that is expected to contain it.
+    fn read_status(&mut self) -> io::Result<BString> {
+        self.read_status_list()?
+            .ok_or_else(|| protocol_error("Filter process didn't send a status".into()))
+    }
+
+    /// Read a list of `key=value` lines up to the next flush packet, and return the value of the `status` key if present.
+    fn read_status_list(&mut self) -> io::Result<Option<BString>> {
+        let mut status = None;
+        while self.read_line()? {
+            if let Some(value) = trim_line(&self.line).strip_prefix(b"status=") {
+                status = Some(value.into());
+            }
+        }
+        Ok(status)
+    }
+
+    /// Read the next packet into our line buffer and return `true`, or return `false` if it was a flush packet.
+    fn read_line(&mut self) -> io::Result<bool> {
+        let mut hex_len = [0u8; 4];
+        self.stdout.read_exact(&mut hex_len)?;
+        let len = std::str::from_utf8(&hex_len)
+            .ok()
+            .and_then(|hex| usize::from_str_radix(hex, 16).ok())
+            .ok_or_else(|| protocol_error(format!("Invalid packet line length {:?}", hex_len.as_bstr())))?;
+        self.line.clear();
+        match len {
+            0 => Ok(false),
+            1..=4 => Err(protocol_error(format!("Invalid packet line length {}", len))),
+            _ => {
+                self.line.resize(len - 4, 0);
+                self.stdout.read_exact(&mut self.line)?;
+                Ok(true)
+            }
+        }
+    }
+}
+
+impl Drop for Client {
+    fn drop(&mut self) {
+        // Closing stdin signals the process to shut down, and waiting for it assures it can finish its work.
+        drop(self.stdin.take());
+        // Make sure the process can't block on writing to us anymore.
+        let _ = io::copy(&mut self.stdout, &mut io::sink());
+        let _ = self.child.wait();
+    }
+}
+
+fn trim_line(line: &[u8]) -> &[u8] {
+    line.strip_suffix(b"\n").unwrap_or(line)
+}
+
+fn protocol_error(msg: String) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, msg)
+}
+
+fn write_packet(out: &mut impl Write, data: &[u8]) -> io::Result<()> {
+    write!(out, "{:04x}", data.len() + 4)?;
+    out.write_all(data)
+}
+
+fn write_text(out: &mut impl Write, text: &[u8]) -> io::Result<()> {
+    write!(out, "{:04x}", text.len() + 5)?;
+    out.write_all(text)?;
+    out.write_all(b"\n")
+}
+
+fn write_flush(out: &mut impl Write) -> io::Result<()> {
+    out.write_all(b"0000")
+}
@@ -29,6 +29,8 @@ pub enum State {
     },
     /// Used when providing worktree status information.
     IgnoreStack(state::Ignore),
+    /// Used when reading files from the worktree to hash them like they would be stored in git, requiring only attribute information.
+    AttributesStack(state::Attributes),
 }
 
 #[cfg(debug_assertions)]
@@ -135,6 +137,15 @@ impl<'paths> Cache<'paths> {
         )
     }
 
+    /// Return the name of the per-directory exclude files, usually `.gitignore`.
+    ///
+    /// # Panics
+    ///
+    /// If the cache was configured without exclude patterns.
+    pub fn exclude_file_name_for_directories(&self) -> &BStr {
+        self.state.ignore_or_panic().exclude_file_name_for_directories()
+    }
+
     /// Return the base path against which all entries or paths should be relative to when querying.
     ///
     /// Note that this path _may_ not be canonicalized.
@@ -40,6 +40,19 @@ impl<'a, 'paths> Platform<'a, 'paths> {
             git_path::to_unix_separators_on_windows(git_path::into_bstr(self.parent.stack.current_relative.as_path()));
         ignore.matching_exclude_pattern(relative_path.as_bstr(), self.is_dir, self.parent.case)
     }
+
+    /// Collect all attributes assigned to the currently set path into `out`, each with the state of the highest priority
+    /// pattern that mentions it. Attributes that aren't mentioned for the path aren't contained.
+    ///
+    /// # Panics
+    ///
+    /// If the cache was configured without attributes.
+    pub fn matching_attributes(&self, out: &mut Vec<git_attributes::Assignment>) {
+        let attributes = self.parent.state.attributes_or_panic();
+        let relative_path =
+            git_path::to_unix_separators_on_windows(git_path::into_bstr(self.parent.stack.current_relative.as_path()));
+        attributes.matching_attributes(relative_path.as_bstr(), self.is_dir, self.parent.case, out)
+    }
 }
 
 impl<'a, 'paths> std::fmt::Debug for Platform<'a, 'paths> {
@@ -63,11 +76,23 @@ where
 {
     fn push_directory(&mut self, stack: &fs::Stack) -> std::io::Result<()> {
         match &mut self.state {
-            State::CreateDirectoryAndAttributesStack { attributes: _, .. } => {
-                // TODO: attributes
-            }
-            State::AttributesAndIgnoreStack { ignore, attributes: _ } => {
-                // TODO: attributes
+            State::CreateDirectoryAndAttributesStack { attributes, .. } => attributes.push_directory(
+                &stack.root,
+                &stack.current,
+                self.buf,
+                self.attribute_files_in_index,
+                true,
+                &mut self.find,
+            )?,
+            State::AttributesAndIgnoreStack { ignore, attributes } => {
+                attributes.push_directory(
+                    &stack.root,
+                    &stack.current,
+                    self.buf,
+                    self.attribute_files_in_index,
+                    false,
+                    &mut self.find,
+                )?;
                 ignore.push_directory(
                     &stack.root,
                     &stack.current,
@@ -83,6 +108,14 @@ where
                 self.attribute_files_in_index,
                 &mut self.find,
             )?,
+            State::AttributesStack(attributes) => attributes.push_directory(
+                &stack.root,
+                &stack.current,
+                self.buf,
+                self.attribute_files_in_index,
+                false,
+                &mut self.find,
+            )?,
         }
         Ok(())
     }
@@ -110,23 +143,26 @@ where
                     create_leading_directory(is_last_component, stack, self.is_dir, *unlink_on_collision)?
                 }
             }
-            State::AttributesAndIgnoreStack { .. } | State::IgnoreStack(_) => {}
+            State::AttributesAndIgnoreStack { .. } | State::IgnoreStack(_) | State::AttributesStack(_) => {}
         }
         Ok(())
     }
 
     fn pop_directory(&mut self) {
         match &mut self.state {
-            State::CreateDirectoryAndAttributesStack { attributes: _, .. } => {
-                // TODO: attributes
+            State::CreateDirectoryAndAttributesStack { attributes, .. } => {
+                attributes.pop_directory();
             }
-            State::AttributesAndIgnoreStack { attributes: _, ignore } => {
-                // TODO: attributes
+            State::AttributesAndIgnoreStack { attributes, ignore } => {
+                attributes.pop_directory();
                 ignore.pop_directory();
             }
             State::IgnoreStack(ignore) => {
                 ignore.pop_directory();
             }
+            State::AttributesStack(attributes) => {
+                attributes.pop_directory();
+            }
         }
     }
 }
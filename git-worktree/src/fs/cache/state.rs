@@ -6,17 +6,14 @@ use git_hash::oid;
 
 use crate::fs::{cache::State, PathOidMapping};
 
-type AttributeMatchGroup = git_attributes::MatchGroup<git_attributes::Attributes>;
 type IgnoreMatchGroup = git_attributes::MatchGroup<git_attributes::Ignore>;
 
 /// State related to attributes associated with files in the repository.
 #[derive(Default, Clone)]
-#[allow(unused)]
 pub struct Attributes {
-    /// Attribute patterns that match the currently set directory (in the stack).
-    pub stack: AttributeMatchGroup,
-    /// Attribute patterns which aren't tied to the repository root, hence are global. They are consulted last.
-    pub globals: AttributeMatchGroup,
+    /// Attribute patterns of all sources, with the ones of the `.gitattributes` files leading up to the currently set
+    /// directory on its stack.
+    pub stack: git_attributes::Stack,
 }
 
 /// State related to the exclusion of files.
@@ -65,6 +62,11 @@ impl Ignore {
 }
 
 impl Ignore {
+    /// Return the name of the file to look for in directories, usually `.gitignore`.
+    pub fn exclude_file_name_for_directories(&self) -> &BStr {
+        self.exclude_file_name_for_directories.as_ref()
+    }
+
     pub(crate) fn pop_directory(&mut self) {
         self.matched_directory_patterns_stack.pop().expect("something to pop");
         self.stack.patterns.pop().expect("something to pop");
@@ -158,15 +160,17 @@ impl Ignore {
         self.matched_directory_patterns_stack
             .push(self.matching_exclude_pattern_no_dir(git_path::into_bstr(rela_dir).as_ref(), Some(true), self.case));
 
-        let ignore_path_relative = rela_dir.join(".gitignore");
+        let ignore_path_relative = rela_dir.join(git_path::from_bstr(self.exclude_file_name_for_directories.as_bstr()));
         let ignore_path_relative = git_path::to_unix_separators_on_windows(git_path::into_bstr(ignore_path_relative));
         let ignore_file_in_index =
             attribute_files_in_index.binary_search_by(|t| t.0.cmp(ignore_path_relative.as_ref()));
         let follow_symlinks = ignore_file_in_index.is_err();
-        if !self
-            .stack
-            .add_patterns_file(dir.join(".gitignore"), follow_symlinks, Some(root), buf)?
-        {
+        if !self.stack.add_patterns_file(
+            dir.join(git_path::from_bstr(self.exclude_file_name_for_directories.as_bstr())),
+            follow_symlinks,
+            Some(root),
+            buf,
+        )? {
             match ignore_file_in_index {
                 Ok(idx) => {
                     let ignore_blob = find(&attribute_files_in_index[idx].1, buf)
@@ -186,20 +190,92 @@ impl Ignore {
 }
 
 impl Attributes {
-    /// Create a new instance from an attribute match group that represents `globals`.
+    /// Create a new instance from `stack`, which typically contains the attributes that aren't part of the worktree.
     ///
-    /// A stack of attributes will be applied on top of it later.
-    pub fn new(globals: AttributeMatchGroup) -> Self {
-        Attributes {
-            globals,
-            stack: Default::default(),
-        }
+    /// The `.gitattributes` files of directories will be pushed onto it later.
+    pub fn new(stack: git_attributes::Stack) -> Self {
+        Attributes { stack }
     }
 }
 
-impl From<AttributeMatchGroup> for Attributes {
-    fn from(group: AttributeMatchGroup) -> Self {
-        Attributes::new(group)
+impl From<git_attributes::Stack> for Attributes {
+    fn from(stack: git_attributes::Stack) -> Self {
+        Attributes::new(stack)
+    }
+}
+
+impl Attributes {
+    pub(crate) fn pop_directory(&mut self) {
+        self.stack.pop_directory().expect("something to pop");
+    }
+
+    /// Push the patterns of the `.gitattributes` file in `dir`. If `index_first` is true, like during checkout, the file is
+    /// looked up in the index first and read from disk only if it's not there, and the other way around otherwise.
+    pub(crate) fn push_directory<Find, E>(
+        &mut self,
+        root: &Path,
+        dir: &Path,
+        buf: &mut Vec<u8>,
+        attribute_files_in_index: &[PathOidMapping<'_>],
+        index_first: bool,
+        mut find: Find,
+    ) -> std::io::Result<()>
+    where
+        Find: for<'b> FnMut(&oid, &'b mut Vec<u8>) -> Result<git_object::BlobRef<'b>, E>,
+        E: std::error::Error + Send + Sync + 'static,
+    {
+        let rela_dir = dir.strip_prefix(root).expect("dir in root");
+        let attr_path_relative =
+            git_path::to_unix_separators_on_windows(git_path::into_bstr(rela_dir.join(".gitattributes")));
+        let mut from_index = |buf: &mut Vec<u8>| -> std::io::Result<_> {
+            Ok(
+                match attribute_files_in_index.binary_search_by(|t| t.0.cmp(attr_path_relative.as_ref())) {
+                    Ok(idx) => {
+                        let blob = find(&attribute_files_in_index[idx].1, buf)
+                            .map_err(|err| std::io::Error::new(std::io::ErrorKind::Other, err))?;
+                        let path = git_path::from_bstr(attr_path_relative.as_ref()).into_owned();
+                        Some(git_attributes::PatternList::from_bytes(blob.data, path, None))
+                    }
+                    Err(_) => None,
+                },
+            )
+        };
+        let follow_symlinks = false;
+        let list = if index_first {
+            match from_index(buf)? {
+                Some(list) => Some(list),
+                None => git_attributes::PatternList::from_file(
+                    dir.join(".gitattributes"),
+                    Some(root),
+                    follow_symlinks,
+                    buf,
+                )?,
+            }
+        } else {
+            match git_attributes::PatternList::from_file(dir.join(".gitattributes"), Some(root), follow_symlinks, buf)?
+            {
+                Some(list) => Some(list),
+                None => from_index(buf)?,
+            }
+        };
+        // Need one stack level per component so push and pop matches.
+        self.stack.push_directory(
+            git_path::to_unix_separators_on_windows(git_path::into_bstr(rela_dir)).as_ref(),
+            list,
+        );
+        Ok(())
+    }
+
+    /// Collect all attributes that are assigned to `relative_path` into `out`, see
+    /// [`Stack::matching_attributes()`][git_attributes::Stack::matching_attributes()] for details.
+    pub(crate) fn matching_attributes(
+        &self,
+        relative_path: &BStr,
+        is_dir: Option<bool>,
+        case: Case,
+        out: &mut Vec<git_attributes::Assignment>,
+    ) {
+        self.stack.matching_attributes(relative_path, is_dir, case, out)
     }
 }
 
@@ -223,6 +299,11 @@ impl State {
     pub fn for_status(ignore: Ignore) -> Self {
         State::IgnoreStack(ignore)
     }
+
+    /// Configure a state for hashing files in the worktree after converting them for storage in git, which only needs attributes.
+    pub fn for_hashing(attributes: Attributes) -> Self {
+        State::AttributesStack(attributes)
+    }
 }
 
 impl State {
@@ -252,7 +333,13 @@ impl State {
                 a2_backing.as_ref()
             }
             State::CreateDirectoryAndAttributesStack { .. } => {
-                a1_backing = [(".gitattributes".into(), true)];
+                // All of them as during checkout, attributes are read from the index first.
+                a1_backing = [(".gitattributes".into(), false)];
+                a1_backing.as_ref()
+            }
+            State::AttributesStack(_) => {
+                // All of them to be able to fall back to the index if they aren't in the worktree.
+                a1_backing = [(".gitattributes".into(), false)];
                 a1_backing.as_ref()
             }
         };
@@ -289,11 +376,22 @@ impl State {
             .collect()
     }
 
+    pub(crate) fn attributes_or_panic(&self) -> &Attributes {
+        match self {
+            State::AttributesAndIgnoreStack { attributes, .. }
+            | State::CreateDirectoryAndAttributesStack { attributes, .. }
+            | State::AttributesStack(attributes) => attributes,
+            State::IgnoreStack(_) => {
+                unreachable!("BUG: must not try to check attributes without it being setup")
+            }
+        }
+    }
+
     pub(crate) fn ignore_or_panic(&self) -> &Ignore {
         match self {
             State::IgnoreStack(v) => v,
             State::AttributesAndIgnoreStack { ignore, .. } => ignore,
-            State::CreateDirectoryAndAttributesStack { .. } => {
+            State::CreateDirectoryAndAttributesStack { .. } | State::AttributesStack(_) => {
                 unreachable!("BUG: must not try to check excludes without it being setup")
             }
         }
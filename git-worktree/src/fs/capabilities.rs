@@ -39,6 +39,9 @@ impl Default for Capabilities {
 }
 
 impl Capabilities {
+    /// The name of the file in the git directory to [store][Capabilities::store()] probed capabilities in.
+    pub const CACHE_FILE_NAME: &'static str = "gitoxide-fs-capabilities";
+
     /// try to determine all values in this context by probing them in the given `git_dir`, which
     /// should be on the file system the git repository is located on.
     /// `git_dir` is a typical git repository, expected to be populated with the typical files like `config`.
@@ -55,6 +58,80 @@ impl Capabilities {
         }
     }
 
+    /// Like [`probe()`][Capabilities::probe()], but use the results of a previous probe of `git_dir` if they were stored
+    /// with [`store()`][Capabilities::store()], or store the results of probing for the next time.
+    ///
+    /// Note that `git_dir` is expected to be the private git directory of a worktree, which is on the same filesystem as the
+    /// worktree. Storage errors are ignored and just cause probing to happen next time as well.
+    pub fn probe_cached(git_dir: impl AsRef<Path>) -> Self {
+        let git_dir = git_dir.as_ref();
+        Self::load(git_dir).unwrap_or_else(|| {
+            let caps = Self::probe(git_dir);
+            caps.store(git_dir).ok();
+            caps
+        })
+    }
+
+    /// Load capabilities previously stored in `git_dir` with [`store()`][Capabilities::store()], or `None` if there are none,
+    /// they are unreadable or if `git_dir` was moved to a different filesystem since.
+    pub fn load(git_dir: impl AsRef<Path>) -> Option<Self> {
+        let git_dir = git_dir.as_ref();
+        let data = std::fs::read(git_dir.join(Self::CACHE_FILE_NAME)).ok()?;
+        let data = std::str::from_utf8(&data).ok()?;
+        let mut caps = Capabilities::default();
+        let mut device = None;
+        for line in data.lines().filter(|line| !line.starts_with('#')) {
+            let (key, value) = line.split_once('=')?;
+            let flag = || match value {
+                "true" => Some(true),
+                "false" => Some(false),
+                _ => None,
+            };
+            match key {
+                "precomposeUnicode" => caps.precompose_unicode = flag()?,
+                "ignoreCase" => caps.ignore_case = flag()?,
+                "executableBit" => caps.executable_bit = flag()?,
+                "symlink" => caps.symlink = flag()?,
+                "device" => device = Some(value.parse::<u64>().ok()?),
+                _ => {}
+            }
+        }
+        (device == Self::device(git_dir)).then(|| caps)
+    }
+
+    /// Store these capabilities in `git_dir` so future calls to [`probe_cached()`][Capabilities::probe_cached()] don't have
+    /// to probe again.
+    ///
+    /// The file is written with a temporary name first and renamed into place, so concurrent readers never see partial data.
+    pub fn store(&self, git_dir: impl AsRef<Path>) -> std::io::Result<()> {
+        let git_dir = git_dir.as_ref();
+        let mut data = format!(
+            "# filesystem capabilities as probed by gitoxide, delete to probe again\n\
+            precomposeUnicode={}\nignoreCase={}\nexecutableBit={}\nsymlink={}\n",
+            self.precompose_unicode, self.ignore_case, self.executable_bit, self.symlink
+        );
+        if let Some(device) = Self::device(git_dir) {
+            data.push_str(&format!("device={}\n", device));
+        }
+        let tmp_path = git_dir.join(format!("{}.{}.tmp", Self::CACHE_FILE_NAME, std::process::id()));
+        std::fs::write(&tmp_path, data)?;
+        std::fs::rename(&tmp_path, git_dir.join(Self::CACHE_FILE_NAME)).map_err(|err| {
+            std::fs::remove_file(&tmp_path).ok();
+            err
+        })
+    }
+
+    #[cfg(unix)]
+    fn device(git_dir: &Path) -> Option<u64> {
+        use std::os::unix::fs::MetadataExt;
+        std::fs::metadata(git_dir).ok().map(|meta| meta.dev())
+    }
+
+    #[cfg(not(unix))]
+    fn device(_git_dir: &Path) -> Option<u64> {
+        None
+    }
+
     #[cfg(unix)]
     fn probe_file_mode(root: &Path) -> std::io::Result<bool> {
         use std::os::unix::fs::{MetadataExt, OpenOptionsExt};
@@ -0,0 +1,133 @@
+use std::{path::PathBuf, process::Stdio};
+
+use bstr::{BStr, BString, ByteSlice};
+
+use crate::fs_monitor::{Changes, Monitor, Response};
+
+/// The version of the hook protocol, as configured with `core.fsmonitorHookVersion`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Version {
+    /// The hook receives a timestamp in nanoseconds since the unix epoch and outputs the changed paths.
+    V1,
+    /// The hook receives an opaque token and outputs a new token, followed by the changed paths.
+    V2,
+}
+
+impl Default for Version {
+    fn default() -> Self {
+        Version::V2
+    }
+}
+
+/// The error returned by [`Hook::query_changes()`][Monitor::query_changes()].
+#[derive(Debug, thiserror::Error)]
+#[allow(missing_docs)]
+pub enum Error {
+    #[error("Could not run filesystem monitor hook '{command}'")]
+    Spawn { command: BString, source: std::io::Error },
+    #[error("Filesystem monitor hook '{command}' failed with {status}")]
+    Failed {
+        command: BString,
+        status: std::process::ExitStatus,
+    },
+    #[error("Filesystem monitor hook '{command}' didn't provide a token")]
+    MissingToken { command: BString },
+}
+
+/// A filesystem monitor that is queried by running a hook program, the value of `core.fsmonitor`, in the worktree.
+///
+/// Its output is a list of null-terminated paths that changed, which is prefixed by a new null-terminated
+/// token in [`Version::V2`]. A single `/` indicates that all paths have to be considered changed.
+#[derive(Debug, Clone)]
+pub struct Hook {
+    command: BString,
+    worktree: PathBuf,
+    version: Version,
+}
+
+impl Hook {
+    /// Create a new instance to run `command` in the `worktree` directory using the hook protocol `version`.
+    pub fn new(command: impl Into<BString>, worktree: impl Into<PathBuf>, version: Version) -> Self {
+        Hook {
+            command: command.into(),
+            worktree: worktree.into(),
+            version,
+        }
+    }
+
+    fn run(&self, version: u8, token: &BStr) -> Result<Vec<u8>, Error> {
+        let mut cmd: std::process::Command = git_command::prepare(
+            git_path::from_bstr(self.command.as_bstr())
+                .into_owned()
+                .into_os_string(),
+        )
+        .with_shell()
+        .stdout(Stdio::piped())
+        .arg(version.to_string())
+        .arg(git_path::from_bstr(token).into_owned().into_os_string())
+        .into();
+        let out = cmd.current_dir(&self.worktree).output().map_err(|err| Error::Spawn {
+            command: self.command.clone(),
+            source: err,
+        })?;
+        if !out.status.success() {
+            return Err(Error::Failed {
+                command: self.command.clone(),
+                status: out.status,
+            });
+        }
+        Ok(out.stdout)
+    }
+}
+
+impl Monitor for Hook {
+    type Error = Error;
+
+    fn query_changes(&mut self, token: Option<&BStr>) -> Result<Response, Self::Error> {
+        match self.version {
+            Version::V1 => {
+                let now = std::time::SystemTime::now()
+                    .duration_since(std::time::UNIX_EPOCH)
+                    .map_or(0, |time| time.as_nanos());
+                let token_now = now.to_string().into();
+                Ok(match token {
+                    Some(token) => Response {
+                        token: token_now,
+                        changes: parse_paths(&self.run(1, token)?),
+                    },
+                    // Without previous timestamp, everything changed since the beginning of time, no need to ask.
+                    None => Response {
+                        token: token_now,
+                        changes: Changes::All,
+                    },
+                })
+            }
+            Version::V2 => {
+                // Like git, pass a token the hook can't know to receive a new one, which forces it to report everything.
+                let out = self.run(2, token.unwrap_or_else(|| "builtin:fake".into()))?;
+                let (new_token, paths) = out.split_once_str(b"\0").ok_or_else(|| Error::MissingToken {
+                    command: self.command.clone(),
+                })?;
+                Ok(Response {
+                    token: new_token.into(),
+                    changes: if token.is_some() {
+                        parse_paths(paths)
+                    } else {
+                        Changes::All
+                    },
+                })
+            }
+        }
+    }
+}
+
+fn parse_paths(out: &[u8]) -> Changes {
+    let mut paths = Vec::new();
+    for path in out.split_str(b"\0").filter(|path| !path.is_empty()) {
+        if path == b"/" {
+            return Changes::All;
+        }
+        paths.push(path.into());
+    }
+    Changes::Paths(paths)
+}
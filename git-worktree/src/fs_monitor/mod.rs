@@ -0,0 +1,54 @@
+//! Integration with filesystem monitors as configured with `core.fsmonitor`, which know the paths that changed since
+//! a previous query and thus allow to avoid looking at unchanged files in large worktrees.
+use bstr::{BStr, BString, ByteSlice};
+
+///
+pub mod hook;
+pub use hook::Hook;
+
+/// A filesystem monitor, like a daemon or a hook, which can be queried for changes in the worktree.
+pub trait Monitor {
+    /// The error returned by [`query_changes()`][Monitor::query_changes()].
+    type Error: std::error::Error + Send + Sync + 'static;
+
+    /// Return all paths that changed since the point in time identified by `token`, along with a new token to use in the
+    /// next query. If `token` is `None`, there was no previous query and all paths have to be considered changed.
+    fn query_changes(&mut self, token: Option<&BStr>) -> Result<Response, Self::Error>;
+}
+
+/// The paths a filesystem monitor reported as changed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Changes {
+    /// Every path may have changed, which happens if the monitor doesn't know the token it was queried with.
+    All,
+    /// Only the given paths, relative to the worktree root, have changed. Paths may also denote directories, in which case
+    /// everything inside of them may have changed.
+    Paths(Vec<BString>),
+}
+
+impl Changes {
+    /// Return true if `rela_path` may have changed.
+    ///
+    /// Note that this is a linear search, which is why [`status()`][crate::index::status()] prepares a lookup table instead.
+    pub fn contains(&self, rela_path: &BStr) -> bool {
+        match self {
+            Changes::All => true,
+            Changes::Paths(paths) => paths.iter().any(|path| is_prefix_or_equal(path.as_ref(), rela_path)),
+        }
+    }
+}
+
+/// The response of a [`Monitor`] to a query.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Response {
+    /// The token to pass to the next query, identifying the point in time of this query.
+    pub token: BString,
+    /// The paths that changed since the token that was passed to the query.
+    pub changes: Changes,
+}
+
+/// Return true if `path` is `rela_path`, or a directory containing it.
+pub(crate) fn is_prefix_or_equal(path: &BStr, rela_path: &BStr) -> bool {
+    let path = path.trim_end_with(|c| c == '/');
+    rela_path.starts_with(path) && (rela_path.len() == path.len() || rela_path[path.len()] == b'/')
+}
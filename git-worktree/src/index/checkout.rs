@@ -1,6 +1,5 @@
 #![allow(missing_docs)]
-use bstr::BString;
-use git_attributes::Attributes;
+use bstr::{BStr, BString};
 
 #[derive(Clone, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
 pub struct Collision {
@@ -10,6 +9,104 @@ pub struct Collision {
     pub error_kind: std::io::ErrorKind,
 }
 
+/// The reason for a path to be rejected as it can't be checked out without writing outside of its intended location.
+#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub enum PathViolation {
+    /// The path is empty, ends with a slash or contains consecutive slashes.
+    EmptyComponent,
+    /// The path starts with a slash and would be written relative to the root of the file system.
+    Absolute,
+    /// A component is `.` or `..`, which could place the entry outside of the worktree.
+    RelativeComponent,
+    /// A component is `.git` or could be interpreted as such by the file system, which would allow to write into the repository itself.
+    DotGit,
+    /// The path starts with a windows drive letter like `C:`. Only checked on windows.
+    DriveLetter,
+    /// A component contains a colon, which designates an alternate data stream on NTFS. Only checked on windows.
+    AlternateDataStream,
+}
+
+/// An index entry whose path can't be checked out safely.
+#[derive(Clone, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct InvalidPath {
+    /// The path of the index entry.
+    pub path: BString,
+    /// The reason for the path to be rejected.
+    pub violation: PathViolation,
+}
+
+/// Return `Ok(())` if `path` of an index entry can be checked out safely, or the reason for it to be rejected.
+///
+/// If `protect_ntfs` is true, paths that would resolve to something else on NTFS, like `.git.` or `GIT~1`, are rejected as well,
+/// similar to `core.protectNTFS`. On windows, this also treats backslashes as path separators and rejects drive letters and
+/// alternate data streams, which are valid file names on other platforms.
+pub fn validate_path(path: &BStr, protect_ntfs: bool) -> Result<(), PathViolation> {
+    let protect_windows_paths = protect_ntfs && cfg!(windows);
+    let is_separator = |b: u8| b == b'/' || (protect_windows_paths && b == b'\\');
+    match path.first() {
+        None => return Err(PathViolation::EmptyComponent),
+        Some(&b) if is_separator(b) => return Err(PathViolation::Absolute),
+        _ => {}
+    }
+    if protect_windows_paths {
+        if path.len() > 1 && path[0].is_ascii_alphabetic() && path[1] == b':' {
+            return Err(PathViolation::DriveLetter);
+        }
+        if path.contains(&b':') {
+            return Err(PathViolation::AlternateDataStream);
+        }
+    }
+    for component in path.split(|b| is_separator(*b)) {
+        if component.is_empty() {
+            return Err(PathViolation::EmptyComponent);
+        }
+        let is_relative = if protect_ntfs {
+            // Trailing dots and spaces are ignored on windows, making `.. ` a reference to the parent directory.
+            component[0] == b'.' && component.iter().all(|b| *b == b'.' || *b == b' ')
+        } else {
+            component == b"." || component == b".."
+        };
+        if is_relative {
+            return Err(PathViolation::RelativeComponent);
+        }
+        if component.eq_ignore_ascii_case(b".git") || (protect_ntfs && is_ntfs_dot_git(component)) {
+            return Err(PathViolation::DotGit);
+        }
+    }
+    Ok(())
+}
+
+/// Return all entries that would be written to the worktree but whose paths can't be checked out safely.
+pub(crate) fn invalid_paths<'a>(
+    entries: impl Iterator<Item = (&'a git_index::Entry, &'a BStr)>,
+    protect_ntfs: bool,
+) -> Vec<InvalidPath> {
+    entries
+        .filter(|(entry, _)| {
+            !entry.flags.contains(git_index::entry::Flags::SKIP_WORKTREE) && entry.mode != git_index::entry::Mode::DIR
+        })
+        .filter_map(|(_, path)| {
+            validate_path(path, protect_ntfs).err().map(|violation| InvalidPath {
+                path: path.to_owned(),
+                violation,
+            })
+        })
+        .collect()
+}
+
+/// Return true if `component` refers to `.git` on NTFS, either with trailing dots and spaces or by its 8.3 short name.
+fn is_ntfs_dot_git(component: &[u8]) -> bool {
+    let lowercase = component.to_ascii_lowercase();
+    let rest = match lowercase
+        .strip_prefix(b".git")
+        .or_else(|| lowercase.strip_prefix(b"git~1"))
+    {
+        Some(rest) => rest,
+        None => return false,
+    };
+    rest.iter().all(|b| *b == b'.' || *b == b' ')
+}
+
 pub struct ErrorRecord {
     /// the path that encountered the error.
     pub path: BString,
@@ -59,8 +156,16 @@ pub struct Options {
     ///
     /// Default true.
     pub check_stat: bool,
-    /// A group of attribute patterns that are applied globally, i.e. aren't rooted within the repository itself.
-    pub attribute_globals: git_attributes::MatchGroup<Attributes>,
+    /// The attribute patterns that aren't part of the worktree, like `$GIT_DIR/info/attributes` and the ones that apply globally.
+    /// The `.gitattributes` files of the worktree are added to it as needed.
+    pub attributes: git_attributes::Stack,
+    /// The conversions to apply to the content of files before writing them to disk, as configured by their attributes.
+    pub filters: crate::filter::Pipeline,
+    /// If true, paths that are harmless on most file systems but would be interpreted differently on NTFS are rejected,
+    /// similar to `core.protectNTFS`. See [`validate_path()`] for details.
+    ///
+    /// Default true.
+    pub protect_ntfs: bool,
 }
 
 impl Default for Options {
@@ -73,18 +178,24 @@ impl Default for Options {
             trust_ctime: true,
             check_stat: true,
             overwrite_existing: false,
-            attribute_globals: Default::default(),
+            attributes: Default::default(),
+            filters: Default::default(),
+            protect_ntfs: true,
         }
     }
 }
 #[derive(Debug, thiserror::Error)]
 pub enum Error<E: std::error::Error + Send + Sync + 'static> {
+    #[error("{} index entries have paths that can't be checked out safely, starting with '{}'", .entries.len(), .entries[0].path)]
+    InvalidPaths { entries: Vec<InvalidPath> },
     #[error("Could not convert path to UTF8: {}", .path)]
     IllformedUtf8 { path: BString },
     #[error("The clock was off when reading file related metadata after updating a file on disk")]
     Time(#[from] std::time::SystemTimeError),
     #[error("IO error while writing blob or reading file metadata or changing filetype")]
     Io(#[from] std::io::Error),
+    #[error(transparent)]
+    Filter(#[from] crate::filter::Error),
     #[error("object {} for checkout at {} could not be retrieved from object database", .oid.to_hex(), .path.display())]
     Find {
         #[source]
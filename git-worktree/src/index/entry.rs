@@ -5,19 +5,29 @@ use git_hash::oid;
 use git_index::Entry;
 use io_close::Close;
 
-use crate::{fs, index, os};
+use crate::{filter, fs, index, os};
 
 pub struct Context<'a, 'paths, Find> {
     pub find: &'a mut Find,
     pub path_cache: &'a mut fs::Cache<'paths>,
     pub buf: &'a mut Vec<u8>,
+    pub filters: &'a mut filter::Pipeline,
+    pub attributes: &'a mut Vec<git_attributes::Assignment>,
+    pub filter_buf: &'a mut Vec<u8>,
 }
 
 #[cfg_attr(not(unix), allow(unused_variables))]
 pub fn checkout<Find, E>(
     entry: &mut Entry,
     entry_path: &BStr,
-    Context { find, path_cache, buf }: Context<'_, '_, Find>,
+    Context {
+        find,
+        path_cache,
+        buf,
+        filters,
+        attributes,
+        filter_buf,
+    }: Context<'_, '_, Find>,
     index::checkout::Options {
         fs: fs::Capabilities {
             symlink,
@@ -37,7 +47,8 @@ where
         path: entry_path.to_owned(),
     })?;
     let is_dir = Some(entry.mode == git_index::entry::Mode::COMMIT || entry.mode == git_index::entry::Mode::DIR);
-    let dest = path_cache.at_path(dest_relative, is_dir, &mut *find)?.path();
+    let platform = path_cache.at_path(dest_relative, is_dir, &mut *find)?;
+    let dest = platform.path();
 
     let object_size = match entry.mode {
         git_index::entry::Mode::FILE | git_index::entry::Mode::FILE_EXECUTABLE => {
@@ -46,6 +57,18 @@ where
                 oid: entry.id,
                 path: dest.to_path_buf(),
             })?;
+            platform.matching_attributes(attributes);
+            let data = if filters.convert_to_worktree(
+                obj.data,
+                entry_path,
+                &filter::Attributes::from_assignments(attributes),
+                &entry.id,
+                filter_buf,
+            )? {
+                filter_buf.as_slice()
+            } else {
+                obj.data
+            };
 
             #[cfg_attr(not(unix), allow(unused_mut))]
             let mut options = open_options(dest, destination_is_initially_empty, overwrite_existing);
@@ -59,7 +82,7 @@ where
             }
 
             let mut file = try_write_or_unlink(dest, overwrite_existing, |p| options.open(p))?;
-            file.write_all(obj.data)?;
+            file.write_all(data)?;
 
             // For possibly existing, overwritten files, we must change the file mode explicitly.
             #[cfg(unix)]
@@ -73,7 +96,7 @@ where
             //       revisit this once there is a bug to fix.
             update_fstat(entry, file.metadata()?)?;
             file.close()?;
-            obj.data.len()
+            data.len()
         }
         git_index::entry::Mode::SYMLINK => {
             let obj = find(&entry.id, buf).map_err(|err| index::checkout::Error::Find {
@@ -97,8 +120,13 @@ where
             update_fstat(entry, std::fs::symlink_metadata(dest)?)?;
             obj.data.len()
         }
-        git_index::entry::Mode::DIR => todo!(),
-        git_index::entry::Mode::COMMIT => todo!(),
+        git_index::entry::Mode::COMMIT => {
+            // Like git, we leave an empty directory for the submodule to be checked out into later. It was already created
+            // by the path cache, which also leaves existing directories alone as they may contain a checked out submodule.
+            update_fstat(entry, std::fs::symlink_metadata(dest)?)?;
+            0
+        }
+        git_index::entry::Mode::DIR => unreachable!("BUG: sparse directories are always skipped"),
         _ => unreachable!(),
     };
     Ok(object_size)
@@ -159,6 +187,7 @@ fn open_options(path: &Path, destination_is_initially_empty: bool, overwrite_exi
     options
         .create_new(destination_is_initially_empty && !overwrite_existing)
         .create(!destination_is_initially_empty || overwrite_existing)
+        .truncate(!destination_is_initially_empty || overwrite_existing)
         .write(true);
     options
 }
@@ -185,5 +214,6 @@ where
         .try_into()
         .expect("by 2038 we found a solution for this");
     stat.ctime.nsecs = ctime.subsec_nanos();
+    stat.size = meta.len() as u32;
     Ok(())
 }
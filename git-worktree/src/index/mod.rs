@@ -7,6 +7,10 @@ use crate::fs;
 
 pub mod checkout;
 pub(crate) mod entry;
+///
+pub mod status;
+///
+pub mod update;
 
 /// Note that interruption still produce an `Ok(…)` value, so the caller should look at `should_interrupt` to communicate the outcome.
 /// `dir` is the directory into which to checkout the `index`.
@@ -30,6 +34,82 @@ where
     index.return_path_backing(paths);
     res
 }
+
+/// Update the worktree at `dir`, which is assumed to contain a checkout of the `old` index, to match the `new` index by
+/// writing and removing only the files that changed between both.
+///
+/// Locally modified files, which don't match their entry in `old`, and untracked files in the way of new files are handled
+/// according to [`Options::local_modifications`][update::Options::local_modifications], and nothing is changed at all in a
+/// [dry-run][update::Options::dry_run]. Entries in `new` that didn't change receive the file metadata of their counterpart in `old`.
+///
+/// Note that interruption still produce an `Ok(…)` value, so the caller should look at `should_interrupt` to communicate the outcome.
+#[allow(clippy::too_many_arguments)]
+pub fn update<Find, E>(
+    old: &git_index::State,
+    new: &mut git_index::State,
+    dir: impl Into<std::path::PathBuf>,
+    find: Find,
+    files: &mut impl Progress,
+    bytes: &mut impl Progress,
+    should_interrupt: &AtomicBool,
+    options: update::Options,
+) -> Result<update::Outcome, update::Error<E>>
+where
+    Find: for<'a> FnMut(&oid, &'a mut Vec<u8>) -> Result<git_object::BlobRef<'a>, E> + Clone,
+    E: std::error::Error + Send + Sync + 'static,
+{
+    let paths = new.take_path_backing();
+    let res = update::function::update(
+        old,
+        new,
+        &paths,
+        dir.into(),
+        find,
+        files,
+        bytes,
+        should_interrupt,
+        options,
+    );
+    new.return_path_backing(paths);
+    res
+}
+
+/// Compare the files in the worktree at `dir` with the entries of `index` and return all that were removed or modified.
+///
+/// Files whose stat information matches their entry are considered unchanged, unless the entry is
+/// [racily clean][git_index::Entry::is_racy()] as it was modified around the time the index was written, in which case
+/// its content is hashed and compared. Before hashing, files are converted according to their attributes with the
+/// [filters][status::Options::filters] like `git add` would, and `find` is used to read `.gitattributes` files from the
+/// object database if they aren't in the worktree. Entries are checked on multiple threads as
+/// [configured][status::Options::thread_limit], and changes are returned in index order.
+///
+/// If a filesystem `monitor` is given, it's queried for the paths that changed since the token stored in the fsmonitor
+/// extension of `index`, and entries that the extension lists as clean and that the monitor didn't report aren't looked at
+/// on disk. This avoids most file system calls in huge worktrees. Then all unchanged entries are marked as
+/// [valid][git_index::entry::Flags::FSMONITOR_VALID] as per the monitor, and the new token is returned
+/// in the [`Outcome`][status::Outcome] for storage in the index.
+///
+/// Entries that skip the worktree, are assumed to be unchanged, are submodules or conflicts are ignored.
+/// Note that interruption still produce an `Ok(…)` value, so the caller should look at `should_interrupt` to communicate the outcome.
+pub fn status<M, Find, E>(
+    index: &mut git_index::State,
+    dir: impl Into<std::path::PathBuf>,
+    monitor: Option<&mut M>,
+    find: Find,
+    should_interrupt: &AtomicBool,
+    options: status::Options,
+) -> Result<status::Outcome, status::Error<M::Error>>
+where
+    M: crate::fs_monitor::Monitor,
+    Find: for<'a> FnMut(&oid, &'a mut Vec<u8>) -> Result<git_object::BlobRef<'a>, E> + Send + Clone,
+    E: std::error::Error + Send + Sync + 'static,
+{
+    let paths = index.take_path_backing();
+    let res = status::function::status(index, &paths, dir.into(), monitor, find, should_interrupt, options);
+    index.return_path_backing(paths);
+    res
+}
+
 #[allow(clippy::too_many_arguments)]
 fn checkout_inner<Find, E>(
     index: &mut git_index::State,
@@ -39,12 +119,19 @@ fn checkout_inner<Find, E>(
     files: &mut impl Progress,
     bytes: &mut impl Progress,
     should_interrupt: &AtomicBool,
-    options: checkout::Options,
+    mut options: checkout::Options,
 ) -> Result<checkout::Outcome, checkout::Error<E>>
 where
     Find: for<'a> FnMut(&oid, &'a mut Vec<u8>) -> Result<git_object::BlobRef<'a>, E> + Send + Clone,
     E: std::error::Error + Send + Sync + 'static,
 {
+    let invalid = checkout::invalid_paths(
+        index.entries().iter().map(|entry| (entry, entry.path_in(paths))),
+        options.protect_ntfs,
+    );
+    if !invalid.is_empty() {
+        return Err(checkout::Error::InvalidPaths { entries: invalid });
+    }
     let num_files = AtomicUsize::default();
     let dir = dir.into();
     let case = if options.fs.ignore_case {
@@ -59,12 +146,15 @@ where
         None,
     );
 
-    let state = fs::cache::State::for_checkout(options.overwrite_existing, options.attribute_globals.clone().into());
+    let state = fs::cache::State::for_checkout(options.overwrite_existing, options.attributes.clone().into());
     let attribute_files = state.build_attribute_list(index, paths, case);
     let mut ctx = chunk::Context {
         buf: Vec::new(),
         path_cache: fs::Cache::new(dir, state, case, Vec::with_capacity(512), attribute_files),
         find,
+        filters: std::mem::take(&mut options.filters),
+        attributes: Vec::new(),
+        filter_buf: Vec::new(),
         options,
         num_files: &num_files,
     };
@@ -128,7 +218,7 @@ mod chunk {
     use git_hash::oid;
 
     use crate::{
-        fs, index,
+        filter, fs, index,
         index::{checkout, entry},
         os,
     };
@@ -201,6 +291,12 @@ mod chunk {
         pub find: Find,
         pub path_cache: fs::Cache<'paths>,
         pub buf: Vec<u8>,
+        /// The filters to apply, kept separate from `options` to avoid cloning them for each entry.
+        pub filters: filter::Pipeline,
+        /// Attributes of the current entry.
+        pub attributes: Vec<git_attributes::Assignment>,
+        /// The output of the filters, if they changed the content.
+        pub filter_buf: Vec<u8>,
         pub options: checkout::Options,
         /// We keep these shared so that there is the chance for printing numbers that aren't looking like
         /// multiple of chunk sizes. Purely cosmetic. Otherwise it's the same as `files`.
@@ -223,8 +319,10 @@ mod chunk {
         let mut bytes_written = 0;
 
         for (entry, entry_path) in entries_with_paths {
-            // TODO: write test for that
-            if entry.flags.contains(git_index::entry::Flags::SKIP_WORKTREE) {
+            // Sparse directories are always marked to skip the worktree, but we check for them nonetheless as there is
+            // nothing to checkout for them.
+            if entry.flags.contains(git_index::entry::Flags::SKIP_WORKTREE) || entry.mode == git_index::entry::Mode::DIR
+            {
                 files.inc();
                 continue;
             }
@@ -264,6 +362,9 @@ mod chunk {
             find,
             path_cache,
             buf,
+            filters,
+            attributes,
+            filter_buf,
             options,
             num_files,
         }: &mut Context<'_, '_, Find>,
@@ -275,7 +376,14 @@ mod chunk {
         let res = entry::checkout(
             entry,
             entry_path,
-            entry::Context { find, path_cache, buf },
+            entry::Context {
+                find,
+                path_cache,
+                buf,
+                filters,
+                attributes,
+                filter_buf,
+            },
             options.clone(),
         );
         files.inc();
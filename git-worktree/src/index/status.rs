@@ -0,0 +1,365 @@
+use bstr::BString;
+
+use crate::fs;
+
+/// Options for use in [`status()`][crate::index::status()].
+#[derive(Clone)]
+pub struct Options {
+    /// Capabilities of the file system, which affect how changes of the kind of file, like executables becoming
+    /// regular files, are detected.
+    pub fs: fs::Capabilities,
+    /// If true, all stat fields will be used when checking for up-to-date'ness of the entry, similar to `core.checkStat`.
+    ///
+    /// Default true.
+    pub check_stat: bool,
+    /// If true, the creation time of files is compared as well, similar to `core.trustCTime`.
+    ///
+    /// Default true.
+    pub trust_ctime: bool,
+    /// If set, don't use more than this amount of threads.
+    /// Otherwise, usually use as many threads as there are logical cores.
+    pub thread_limit: Option<usize>,
+    /// The attribute patterns that aren't part of the worktree, like `$GIT_DIR/info/attributes` and the ones that apply globally.
+    /// The `.gitattributes` files of the worktree are added to it as needed.
+    pub attributes: git_attributes::Stack,
+    /// The conversions to apply to the content of files before hashing them, as configured by their attributes, to compare
+    /// them to what's stored in the index.
+    pub filters: crate::filter::Pipeline,
+}
+
+impl Default for Options {
+    fn default() -> Self {
+        Options {
+            fs: Default::default(),
+            check_stat: true,
+            trust_ctime: true,
+            thread_limit: None,
+            attributes: Default::default(),
+            filters: Default::default(),
+        }
+    }
+}
+
+/// The way a tracked file in the worktree differs from its entry in the index.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Ord, PartialOrd)]
+pub enum Change {
+    /// The file doesn't exist anymore, or a directory took its place.
+    Removed,
+    /// The content of the file or its kind changed.
+    Modified,
+}
+
+/// A tracked file in the worktree that differs from its entry in the index.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Ord, PartialOrd)]
+pub struct Entry {
+    /// The path relative to the worktree root.
+    pub rela_path: BString,
+    /// How the file changed.
+    pub change: Change,
+}
+
+/// The outcome of [`status()`][crate::index::status()].
+#[derive(Debug, Default, Clone)]
+pub struct Outcome {
+    /// All changed files, in index order.
+    pub changes: Vec<Entry>,
+    /// The amount of entries whose files were looked at on disk.
+    pub entries_checked: usize,
+    /// The amount of entries that weren't looked at as the filesystem monitor knew them to be unchanged.
+    pub entries_skipped: usize,
+    /// The new token of the filesystem monitor, if one was used, which should be stored in the index for the next query.
+    pub fs_monitor_token: Option<BString>,
+}
+
+/// The error returned by [`status()`][crate::index::status()].
+#[derive(Debug, thiserror::Error)]
+#[allow(missing_docs)]
+pub enum Error<E: std::error::Error + Send + Sync + 'static> {
+    #[error("Could not obtain the status of '{}'", path.display())]
+    Io {
+        path: std::path::PathBuf,
+        source: std::io::Error,
+    },
+    #[error("The filesystem monitor could not be queried")]
+    FsMonitor(#[source] E),
+    #[error("The path '{path}' could not be converted to a platform path")]
+    IllformedUtf8 { path: BString },
+    #[error("Could not convert '{path}' for comparison with the index")]
+    Filter {
+        path: BString,
+        source: crate::filter::Error,
+    },
+}
+
+pub(crate) mod function {
+    use std::{
+        collections::BTreeSet,
+        path::{Path, PathBuf},
+        sync::atomic::AtomicBool,
+    };
+
+    use bstr::{BStr, ByteSlice};
+    use git_features::{interrupt, parallel::in_parallel};
+    use git_hash::oid;
+    use git_index::entry::{Flags, Mode};
+
+    use super::{Change, Entry, Error, Options, Outcome};
+    use crate::{
+        fs,
+        fs_monitor::{Changes, Monitor},
+        index::update::function::{kind_matches, metadata, stat_matches, HashError, Hasher},
+    };
+
+    pub fn status<M, Find, E>(
+        index: &mut git_index::State,
+        paths: &git_index::PathStorage,
+        dir: PathBuf,
+        monitor: Option<&mut M>,
+        find: Find,
+        should_interrupt: &AtomicBool,
+        options: Options,
+    ) -> Result<Outcome, Error<M::Error>>
+    where
+        M: Monitor,
+        Find: for<'a> FnMut(&oid, &'a mut Vec<u8>) -> Result<git_object::BlobRef<'a>, E> + Send + Clone,
+        E: std::error::Error + Send + Sync + 'static,
+    {
+        let mut out = Outcome::default();
+        let num_entries = index.entries().len();
+        let mut unchanged = Vec::new();
+        if let Some(monitor) = monitor {
+            let extension = index.fs_monitor();
+            let token = extension.map(|ext| ext.token().to_bstring());
+            let response = monitor
+                .query_changes(token.as_ref().map(|token| token.as_bstr()))
+                .map_err(Error::FsMonitor)?;
+            if let (Some(extension), Changes::Paths(changed_paths)) = (extension, &response.changes) {
+                unchanged = vec![true; num_entries];
+                for idx in extension.dirty_entries() {
+                    if let Some(unchanged) = unchanged.get_mut(idx) {
+                        *unchanged = false;
+                    }
+                }
+                let changed: BTreeSet<&BStr> = changed_paths
+                    .iter()
+                    .map(|path| path.trim_end_with(|c| c == '/').as_bstr())
+                    .collect();
+                for (entry, unchanged) in index.entries().iter().zip(unchanged.iter_mut()) {
+                    let path = entry.path_in(paths);
+                    if *unchanged && is_contained(&changed, path) {
+                        *unchanged = false;
+                    }
+                }
+            }
+            out.fs_monitor_token = Some(response.token);
+        }
+
+        let case = if options.fs.ignore_case {
+            git_glob::pattern::Case::Fold
+        } else {
+            git_glob::pattern::Case::Sensitive
+        };
+        let attribute_files =
+            fs::cache::State::for_hashing(options.attributes.clone().into()).build_attribute_list(index, paths, case);
+        let mut hasher = Hasher::new(
+            dir.clone(),
+            options.attributes.clone(),
+            attribute_files,
+            case,
+            &options.filters,
+            find,
+        );
+        let ctx = Context {
+            dir: &dir,
+            unchanged: &unchanged,
+            use_monitor: out.fs_monitor_token.is_some(),
+            timestamp: index.timestamp(),
+            options: &options,
+        };
+        let (chunk_size, thread_limit, num_threads) = git_features::parallel::optimize_chunk_size_and_thread_limit(
+            500,
+            num_entries.into(),
+            options.thread_limit,
+            None,
+        );
+        let entries = interrupt::Iter::new(index.entries_mut_with_paths_in(paths).enumerate(), should_interrupt);
+        let mut chunk = if num_threads == 1 {
+            ctx.process(entries, &mut hasher)?
+        } else {
+            in_parallel(
+                git_features::iter::Chunks {
+                    inner: entries,
+                    size: chunk_size,
+                },
+                thread_limit,
+                move |_| hasher.clone(),
+                |chunk, hasher| ctx.process(chunk.into_iter(), hasher),
+                Reduce::default(),
+            )?
+        };
+
+        chunk.changes.sort_by_key(|(idx, _)| *idx);
+        out.changes = chunk.changes.into_iter().map(|(_, change)| change).collect();
+        out.entries_checked = chunk.entries_checked;
+        out.entries_skipped = chunk.entries_skipped;
+        Ok(out)
+    }
+
+    /// Everything needed to check entries independently of each other.
+    struct Context<'a> {
+        dir: &'a Path,
+        /// For each entry, true if the filesystem monitor knows it to be unchanged.
+        unchanged: &'a [bool],
+        use_monitor: bool,
+        /// The time at which the index was written, to detect racily clean entries.
+        timestamp: filetime::FileTime,
+        options: &'a Options,
+    }
+
+    /// The changes found in a portion of the entries, each along with the index of its entry.
+    #[derive(Default)]
+    struct Chunk {
+        changes: Vec<(usize, Entry)>,
+        entries_checked: usize,
+        entries_skipped: usize,
+    }
+
+    impl Context<'_> {
+        fn process<'entry, Find, E, FindErr>(
+            &self,
+            entries: impl Iterator<Item = (usize, (&'entry mut git_index::Entry, &'entry BStr))>,
+            hasher: &mut Hasher<'_, Find>,
+        ) -> Result<Chunk, Error<E>>
+        where
+            E: std::error::Error + Send + Sync + 'static,
+            Find: for<'a> FnMut(&oid, &'a mut Vec<u8>) -> Result<git_object::BlobRef<'a>, FindErr>,
+            FindErr: std::error::Error + Send + Sync + 'static,
+        {
+            let mut out = Chunk::default();
+            for (idx, (entry, path)) in entries {
+                if entry.stage() != 0
+                    || matches!(entry.mode, Mode::DIR | Mode::COMMIT)
+                    || entry.flags.intersects(Flags::SKIP_WORKTREE | Flags::ASSUME_VALID)
+                {
+                    continue;
+                }
+                if self.unchanged.get(idx).copied().unwrap_or_default() {
+                    entry.flags.insert(Flags::FSMONITOR_VALID);
+                    out.entries_skipped += 1;
+                    continue;
+                }
+
+                out.entries_checked += 1;
+                match self.change(entry, path, hasher)? {
+                    Some(change) => {
+                        entry.flags.remove(Flags::FSMONITOR_VALID);
+                        out.changes.push((
+                            idx,
+                            Entry {
+                                rela_path: path.to_owned(),
+                                change,
+                            },
+                        ));
+                    }
+                    None if self.use_monitor => entry.flags.insert(Flags::FSMONITOR_VALID),
+                    None => {}
+                }
+            }
+            Ok(out)
+        }
+
+        /// Determine how the file of `entry` at `path` changed, if at all.
+        ///
+        /// Matching stat information is trusted unless the entry is racily clean, in which case the content is compared.
+        fn change<Find, E, FindErr>(
+            &self,
+            entry: &git_index::Entry,
+            path: &BStr,
+            hasher: &mut Hasher<'_, Find>,
+        ) -> Result<Option<Change>, Error<E>>
+        where
+            E: std::error::Error + Send + Sync + 'static,
+            Find: for<'a> FnMut(&oid, &'a mut Vec<u8>) -> Result<git_object::BlobRef<'a>, FindErr>,
+            FindErr: std::error::Error + Send + Sync + 'static,
+        {
+            let location = self
+                .dir
+                .join(git_path::try_from_bstr(path).map_err(|_| Error::IllformedUtf8 { path: path.to_owned() })?);
+            let options = self.options;
+            Ok(
+                match metadata(&location).map_err(|err| Error::Io {
+                    path: location.clone(),
+                    source: err,
+                })? {
+                    None => Some(Change::Removed),
+                    Some(meta) if meta.is_dir() => Some(Change::Removed),
+                    Some(meta) if !kind_matches(entry.mode, &meta, &options.fs) => Some(Change::Modified),
+                    Some(meta)
+                        if entry.stat.size == meta.len() as u32
+                            && stat_matches(entry, &meta, options.check_stat, options.trust_ctime)
+                            && !entry.is_racy(self.timestamp) =>
+                    {
+                        None
+                    }
+                    Some(meta) => {
+                        let id = hasher
+                            .hash(path, &location, &meta, entry.id.kind())
+                            .map_err(|err| match err {
+                                HashError::Io(source) => Error::Io {
+                                    path: location.clone(),
+                                    source,
+                                },
+                                HashError::Filter(source) => Error::Filter {
+                                    path: path.to_owned(),
+                                    source,
+                                },
+                            })?;
+                        (id != entry.id).then(|| Change::Modified)
+                    }
+                },
+            )
+        }
+    }
+
+    /// Merge the changes found in all chunks.
+    struct Reduce<E> {
+        aggregate: Chunk,
+        marker: std::marker::PhantomData<E>,
+    }
+
+    impl<E> Default for Reduce<E> {
+        fn default() -> Self {
+            Reduce {
+                aggregate: Default::default(),
+                marker: Default::default(),
+            }
+        }
+    }
+
+    impl<E> git_features::parallel::Reduce for Reduce<E>
+    where
+        E: std::error::Error + Send + Sync + 'static,
+    {
+        type Input = Result<Chunk, Error<E>>;
+        type FeedProduce = ();
+        type Output = Chunk;
+        type Error = Error<E>;
+
+        fn feed(&mut self, item: Self::Input) -> Result<Self::FeedProduce, Self::Error> {
+            let chunk = item?;
+            self.aggregate.changes.extend(chunk.changes);
+            self.aggregate.entries_checked += chunk.entries_checked;
+            self.aggregate.entries_skipped += chunk.entries_skipped;
+            Ok(())
+        }
+
+        fn finalize(self) -> Result<Self::Output, Self::Error> {
+            Ok(self.aggregate)
+        }
+    }
+
+    /// Return true if `path` or one of its leading directories is in `changed`.
+    fn is_contained(changed: &BTreeSet<&BStr>, path: &BStr) -> bool {
+        changed.contains(path) || path.rfind_iter("/").any(|pos| changed.contains(path[..pos].as_bstr()))
+    }
+}
@@ -0,0 +1,516 @@
+#![allow(missing_docs)]
+use bstr::BString;
+
+use crate::index::checkout;
+
+/// What to do with files in the worktree that would be changed or removed by an update, but that don't match the state of
+/// the index the worktree was checked out from, i.e. that were modified locally or aren't tracked at all.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Ord, PartialOrd)]
+pub enum LocalModifications {
+    /// Leave locally modified files alone and [record][Outcome::kept] their paths. Their entries in the new index
+    /// will be marked as not up-to-date so they show up as modified.
+    Keep,
+    /// Replace or remove locally modified files and [record][Outcome::overwritten] their paths, losing all local changes.
+    /// This also removes untracked files and directories that are in the way of files to be written.
+    ///
+    /// This is similar in behaviour as `git checkout --force`.
+    Overwrite,
+}
+
+impl Default for LocalModifications {
+    fn default() -> Self {
+        LocalModifications::Keep
+    }
+}
+
+#[derive(Default, Clone)]
+pub struct Options {
+    /// Options to control how entries are checked out. Note that `destination_is_initially_empty` and `overwrite_existing`
+    /// are ignored as the worktree is assumed to contain the checkout of the old index, and `local_modifications` controls
+    /// what's overwritten.
+    pub checkout: checkout::Options,
+    /// What to do with locally modified files that would be affected by the update.
+    pub local_modifications: LocalModifications,
+    /// If true, default false, don't change anything on disk or in the new index, but produce an [`Outcome`] that
+    /// lists everything that would have happened.
+    ///
+    /// This is useful to learn which local modifications would be overwritten before making a decision.
+    pub dry_run: bool,
+}
+
+#[derive(Default)]
+pub struct Outcome {
+    /// The paths of all files added or changed on disk, in order.
+    pub updated: Vec<BString>,
+    /// The paths of all files removed from disk, in order.
+    pub removed: Vec<BString>,
+    /// The amount of entries that didn't change between the old and the new index, and that weren't touched on disk.
+    pub unchanged: usize,
+    /// The paths of locally modified or untracked files that were replaced or removed as requested by
+    /// [`LocalModifications::Overwrite`]. These are also contained in `updated` or `removed`.
+    pub overwritten: Vec<BString>,
+    /// The paths of locally modified or untracked files that were left as is as requested by [`LocalModifications::Keep`].
+    pub kept: Vec<BString>,
+    /// The amount of bytes written to disk,
+    pub bytes_written: u64,
+    pub collisions: Vec<checkout::Collision>,
+    pub errors: Vec<checkout::ErrorRecord>,
+}
+
+pub type Error<E> = checkout::Error<E>;
+
+pub(crate) mod function {
+    use std::{
+        cmp::Ordering,
+        io,
+        path::{Path, PathBuf},
+        sync::atomic::AtomicUsize,
+    };
+
+    use bstr::BStr;
+    use git_features::progress::Progress;
+    use git_hash::oid;
+    use git_index::{entry::Mode, Entry};
+
+    use super::{Error, LocalModifications, Options, Outcome};
+    use crate::{
+        filter, fs,
+        index::{checkout, chunk},
+    };
+
+    #[allow(clippy::too_many_arguments)]
+    pub fn update<Find, E>(
+        old: &git_index::State,
+        new: &mut git_index::State,
+        paths: &git_index::PathStorage,
+        dir: PathBuf,
+        find: Find,
+        files: &mut impl Progress,
+        bytes: &mut impl Progress,
+        should_interrupt: &std::sync::atomic::AtomicBool,
+        Options {
+            checkout: mut checkout_options,
+            local_modifications,
+            dry_run,
+        }: Options,
+    ) -> Result<Outcome, Error<E>>
+    where
+        Find: for<'a> FnMut(&oid, &'a mut Vec<u8>) -> Result<git_object::BlobRef<'a>, E> + Clone,
+        E: std::error::Error + Send + Sync + 'static,
+    {
+        let overwrite = local_modifications == LocalModifications::Overwrite;
+        checkout_options.destination_is_initially_empty = false;
+        checkout_options.overwrite_existing = overwrite;
+        let case = if checkout_options.fs.ignore_case {
+            git_glob::pattern::Case::Fold
+        } else {
+            git_glob::pattern::Case::Sensitive
+        };
+        let state = fs::cache::State::for_checkout(overwrite, checkout_options.attributes.clone().into());
+        let attribute_files = state.build_attribute_list(new, paths, case);
+        // Local modifications are determined by hashing the files of the old index, hence its attributes apply.
+        let mut hasher = {
+            let attributes = checkout_options.attributes.clone();
+            let attribute_files = fs::cache::State::for_hashing(attributes.clone().into()).build_attribute_list(
+                old,
+                old.path_backing(),
+                case,
+            );
+            Hasher::new(
+                dir.clone(),
+                attributes,
+                attribute_files,
+                case,
+                &checkout_options.filters,
+                find.clone(),
+            )
+        };
+
+        let old_entries: Vec<_> = old
+            .entries()
+            .iter()
+            .filter(|entry| is_in_worktree(entry))
+            .map(|entry| (entry, entry.path(old)))
+            .collect();
+        let mut new_entries: Vec<_> = new
+            .entries_mut_with_paths_in(paths)
+            .filter(|(entry, _)| is_in_worktree(entry))
+            .collect();
+        let invalid = checkout::invalid_paths(
+            new_entries.iter().map(|(entry, path)| (&**entry, *path)),
+            checkout_options.protect_ntfs,
+        );
+        if !invalid.is_empty() {
+            return Err(checkout::Error::InvalidPaths { entries: invalid });
+        }
+
+        let mut out = Outcome::default();
+        // Changes are the positions of entries in `old_entries` and `new_entries`, with deletions having no new entry and
+        // additions having no old one.
+        let mut changes = Vec::new();
+        let (mut old_idx, mut new_idx) = (0, 0);
+        loop {
+            let change = match (old_entries.get(old_idx), new_entries.get(new_idx)) {
+                (None, None) => break,
+                (Some(_), None) => (Some(old_idx), None),
+                (None, Some(_)) => (None, Some(new_idx)),
+                (Some((_, old_path)), Some((_, new_path))) => match old_path.cmp(new_path) {
+                    Ordering::Less => (Some(old_idx), None),
+                    Ordering::Greater => (None, Some(new_idx)),
+                    Ordering::Equal => (Some(old_idx), Some(new_idx)),
+                },
+            };
+            old_idx += usize::from(change.0.is_some());
+            new_idx += usize::from(change.1.is_some());
+            if let (Some(old), Some(new)) = change {
+                let (old_entry, new_entry) = (old_entries[old].0, &mut new_entries[new].0);
+                if old_entry.id == new_entry.id && old_entry.mode == new_entry.mode {
+                    if !dry_run {
+                        new_entry.stat = old_entry.stat;
+                    }
+                    out.unchanged += 1;
+                    continue;
+                }
+            }
+            changes.push(change);
+        }
+        // Handle deletions first to make room for the directories or files that may take their place.
+        changes.sort_by_key(|(_, new)| new.is_some());
+
+        let mut writes = Vec::new();
+        for (old, new) in changes {
+            if should_interrupt.load(std::sync::atomic::Ordering::Relaxed) {
+                return Ok(out);
+            }
+            let (path, mode) = match new {
+                Some(new) => (new_entries[new].1, new_entries[new].0.mode),
+                None => (old_entries[old.expect("one is always set")].1, Mode::empty()),
+            };
+            let location = dir.join(
+                git_path::try_from_bstr(path).map_err(|_| checkout::Error::IllformedUtf8 { path: path.to_owned() })?,
+            );
+
+            let is_modified = match old {
+                Some(old) if old_entries[old].0.mode == Mode::COMMIT => false,
+                Some(old) => is_locally_modified(
+                    old_entries[old].0,
+                    new.map(|new| &new_entries[new].0.id),
+                    path,
+                    &location,
+                    &checkout_options,
+                    &mut hasher,
+                )?,
+                None => {
+                    mode != Mode::COMMIT
+                        && !contains_tracked_entries(&old_entries, path)
+                        && is_untracked(&new_entries[new.expect("set")].0.id, path, &location, &mut hasher)?
+                }
+            };
+            if is_modified {
+                if overwrite {
+                    out.overwritten.push(path.to_owned());
+                } else {
+                    out.kept.push(path.to_owned());
+                    if let Some(new) = new {
+                        if !dry_run {
+                            new_entries[new].0.stat = Default::default();
+                        }
+                    }
+                    continue;
+                }
+            }
+
+            let old_mode = old.map(|old| old_entries[old].0.mode);
+            match new {
+                Some(new) => {
+                    if let (Some(old_mode), false, false) = (old_mode, is_modified, dry_run) {
+                        // Make room for entries that change their type, like files that become symlinks.
+                        remove_entry(&location, old_mode)?;
+                    }
+                    writes.push(new)
+                }
+                None => {
+                    if !dry_run {
+                        remove_entry(&location, old_mode.expect("set for deletions"))?;
+                        remove_empty_parents(&location, &dir);
+                    }
+                    files.inc();
+                    out.removed.push(path.to_owned());
+                }
+            }
+        }
+
+        if dry_run {
+            out.updated = writes.into_iter().map(|new| new_entries[new].1.to_owned()).collect();
+            return Ok(out);
+        }
+
+        let num_files = AtomicUsize::default();
+        let mut ctx = chunk::Context {
+            find,
+            path_cache: fs::Cache::new(dir, state, case, Vec::with_capacity(512), attribute_files),
+            buf: Vec::new(),
+            filters: std::mem::take(&mut checkout_options.filters),
+            attributes: Vec::new(),
+            filter_buf: Vec::new(),
+            options: checkout_options,
+            num_files: &num_files,
+        };
+        // Like during checkout, symlinks are written last to be sure they don't point to something we have yet to write.
+        let (symlinks, others): (Vec<_>, Vec<_>) = writes
+            .into_iter()
+            .partition(|new| new_entries[*new].0.mode == Mode::SYMLINK);
+        for new in others.into_iter().chain(symlinks) {
+            if should_interrupt.load(std::sync::atomic::Ordering::Relaxed) {
+                break;
+            }
+            let (entry, path) = &mut new_entries[new];
+            let path: &BStr = path;
+            let num_collisions = out.collisions.len();
+            out.bytes_written += chunk::checkout_entry_handle_result(
+                entry,
+                path,
+                &mut out.errors,
+                &mut out.collisions,
+                files,
+                bytes,
+                &mut ctx,
+            )? as u64;
+            if out.collisions.len() == num_collisions {
+                out.updated.push(path.to_owned());
+            }
+        }
+        Ok(out)
+    }
+
+    /// Entries that skip the worktree are considered absent as they aren't supposed to be on disk.
+    fn is_in_worktree(entry: &Entry) -> bool {
+        entry.stage() == 0 && entry.mode != Mode::DIR && !entry.flags.contains(git_index::entry::Flags::SKIP_WORKTREE)
+    }
+
+    /// Return true if the file at `location` doesn't match `entry` anymore, so changing it would lose information.
+    /// It's fine if it already matches `new_id`, which is what we are about to write.
+    fn is_locally_modified<Find, E>(
+        entry: &Entry,
+        new_id: Option<&git_hash::ObjectId>,
+        rela_path: &BStr,
+        location: &Path,
+        options: &checkout::Options,
+        hasher: &mut Hasher<'_, Find>,
+    ) -> Result<bool, HashError>
+    where
+        Find: for<'a> FnMut(&oid, &'a mut Vec<u8>) -> Result<git_object::BlobRef<'a>, E>,
+        E: std::error::Error + Send + Sync + 'static,
+    {
+        let meta = match metadata(location)? {
+            Some(meta) => meta,
+            None => return Ok(false),
+        };
+        if meta.is_dir() || !kind_matches(entry.mode, &meta, &options.fs) {
+            return Ok(true);
+        }
+        if entry.stat.size == meta.len() as u32 && stat_matches(entry, &meta, options.check_stat, options.trust_ctime) {
+            return Ok(false);
+        }
+        let id = hasher.hash(rela_path, location, &meta, entry.id.kind())?;
+        Ok(id != entry.id && Some(&id) != new_id)
+    }
+
+    /// Return true if `old_entries` contain entries inside of the directory at `path`, which are handled by their own deletion.
+    fn contains_tracked_entries(old_entries: &[(&Entry, &BStr)], path: &BStr) -> bool {
+        let start = old_entries.partition_point(|(_, old_path)| *old_path <= path);
+        old_entries.get(start).map_or(false, |(_, old_path)| {
+            old_path.len() > path.len() && old_path.starts_with(path) && old_path[path.len()] == b'/'
+        })
+    }
+
+    /// Return true if there is something at `location` which isn't a file with the content we are about to write.
+    fn is_untracked<Find, E>(
+        new_id: &git_hash::ObjectId,
+        rela_path: &BStr,
+        location: &Path,
+        hasher: &mut Hasher<'_, Find>,
+    ) -> Result<bool, HashError>
+    where
+        Find: for<'a> FnMut(&oid, &'a mut Vec<u8>) -> Result<git_object::BlobRef<'a>, E>,
+        E: std::error::Error + Send + Sync + 'static,
+    {
+        Ok(match metadata(location)? {
+            Some(meta) if meta.is_dir() => true,
+            Some(meta) => &hasher.hash(rela_path, location, &meta, new_id.kind())? != new_id,
+            None => false,
+        })
+    }
+
+    /// Return the metadata of `location` without following symlinks, or `None` if there is nothing, which includes
+    /// a file being in place of one of its leading directories.
+    pub(crate) fn metadata(location: &Path) -> io::Result<Option<std::fs::Metadata>> {
+        match std::fs::symlink_metadata(location) {
+            Ok(meta) => Ok(Some(meta)),
+            Err(err) if err.kind() == io::ErrorKind::NotFound => Ok(None),
+            Err(_)
+                if location
+                    .ancestors()
+                    .skip(1)
+                    .any(|dir| std::fs::symlink_metadata(dir).map_or(false, |meta| !meta.is_dir())) =>
+            {
+                Ok(None)
+            }
+            Err(err) => Err(err),
+        }
+    }
+
+    /// Return true if the kind of file described by `meta` matches `mode`, as far as the capabilities of the file system
+    /// allow us to tell. For instance, without symlink support these are checked out as files, and without
+    /// executable bit we can't know if a file is executable.
+    pub(crate) fn kind_matches(mode: Mode, meta: &std::fs::Metadata, fs: &crate::fs::Capabilities) -> bool {
+        let is_symlink = meta.file_type().is_symlink();
+        match mode {
+            Mode::SYMLINK => is_symlink || !fs.symlink,
+            Mode::FILE | Mode::FILE_EXECUTABLE if is_symlink => false,
+            Mode::FILE | Mode::FILE_EXECUTABLE if fs.executable_bit => {
+                (mode == Mode::FILE_EXECUTABLE) == crate::os::is_executable(meta)
+            }
+            _ => true,
+        }
+    }
+
+    pub(crate) fn stat_matches(entry: &Entry, meta: &std::fs::Metadata, check_stat: bool, trust_ctime: bool) -> bool {
+        let matches = |stored: git_index::entry::Time, actual: io::Result<std::time::SystemTime>| {
+            actual
+                .ok()
+                .and_then(|time| time.duration_since(std::time::UNIX_EPOCH).ok())
+                .map_or(false, |time| {
+                    u64::from(stored.secs) == time.as_secs() && (!check_stat || stored.nsecs == time.subsec_nanos())
+                })
+        };
+        matches(entry.stat.mtime, meta.modified()) && (!trust_ctime || matches(entry.stat.ctime, meta.created()))
+    }
+
+    /// The error returned by [`Hasher::hash()`].
+    #[derive(Debug, thiserror::Error)]
+    pub(crate) enum HashError {
+        #[error(transparent)]
+        Io(#[from] io::Error),
+        #[error(transparent)]
+        Filter(#[from] filter::Error),
+    }
+
+    impl<E> From<HashError> for Error<E>
+    where
+        E: std::error::Error + Send + Sync + 'static,
+    {
+        fn from(err: HashError) -> Self {
+            match err {
+                HashError::Io(err) => err.into(),
+                HashError::Filter(err) => err.into(),
+            }
+        }
+    }
+
+    /// Everything needed to hash files in the worktree like `git add` would store them, which means to convert their content
+    /// according to their attributes first.
+    #[derive(Clone)]
+    pub(crate) struct Hasher<'paths, Find> {
+        path_cache: fs::Cache<'paths>,
+        find: Find,
+        filters: filter::Pipeline,
+        attributes: Vec<git_attributes::Assignment>,
+        buf: Vec<u8>,
+    }
+
+    impl<'paths, Find, E> Hasher<'paths, Find>
+    where
+        Find: for<'a> FnMut(&oid, &'a mut Vec<u8>) -> Result<git_object::BlobRef<'a>, E>,
+        E: std::error::Error + Send + Sync + 'static,
+    {
+        /// Create a new instance for the worktree at `dir` to read `.gitattributes` files from, falling back to the ones in
+        /// `attribute_files` of the index which are read with `find`, and to convert files with the configuration of `filters`.
+        pub(crate) fn new(
+            dir: PathBuf,
+            attributes: git_attributes::Stack,
+            attribute_files: Vec<fs::PathOidMapping<'paths>>,
+            case: git_glob::pattern::Case,
+            filters: &filter::Pipeline,
+            find: Find,
+        ) -> Self {
+            // Like git, don't check if line-ending conversions are reversible as nothing is stored.
+            let options = filter::Options {
+                safe_crlf: filter::SafeCrlf::Disabled,
+                ..filters.options()
+            };
+            Hasher {
+                path_cache: fs::Cache::new(
+                    dir,
+                    fs::cache::State::for_hashing(attributes.into()),
+                    case,
+                    Vec::with_capacity(512),
+                    attribute_files,
+                ),
+                find,
+                filters: filter::Pipeline::new(filters.drivers().to_vec(), options),
+                attributes: Vec::new(),
+                buf: Vec::new(),
+            }
+        }
+
+        /// Return the id of the blob git would store for the file at `location`, which is at `rela_path` in the worktree and
+        /// described by `meta`. Symlinks are hashed by their target without conversion.
+        pub(crate) fn hash(
+            &mut self,
+            rela_path: &BStr,
+            location: &Path,
+            meta: &std::fs::Metadata,
+            kind: git_hash::Kind,
+        ) -> Result<git_hash::ObjectId, HashError> {
+            let mut data = if meta.file_type().is_symlink() {
+                git_path::into_bstr(std::fs::read_link(location)?).into_owned().into()
+            } else {
+                let data = std::fs::read(location)?;
+                self.path_cache
+                    .at_entry(rela_path, Some(false), &mut self.find)?
+                    .matching_attributes(&mut self.attributes);
+                let attributes = filter::Attributes::from_assignments(&self.attributes);
+                if self
+                    .filters
+                    .convert_to_git(&data, rela_path, &attributes, &mut self.buf)?
+                {
+                    std::mem::take(&mut self.buf)
+                } else {
+                    data
+                }
+            };
+            let mut hasher = git_features::hash::hasher(kind);
+            hasher.update(&git_object::encode::loose_header(git_object::Kind::Blob, data.len()));
+            hasher.update(&data);
+            data.clear();
+            self.buf = data;
+            Ok(hasher.digest().into())
+        }
+    }
+
+    /// Remove the file at `location`, or the directory if it took its place. Submodule directories are only removed if they are empty.
+    fn remove_entry(location: &Path, mode: Mode) -> io::Result<()> {
+        let res = match std::fs::symlink_metadata(location) {
+            Ok(_) if mode == Mode::COMMIT => std::fs::remove_dir(location).or(Ok(())),
+            Ok(meta) if meta.is_dir() => std::fs::remove_dir_all(location),
+            Ok(_) => std::fs::remove_file(location),
+            Err(err) => Err(err),
+        };
+        match res {
+            Err(err) if err.kind() == io::ErrorKind::NotFound => Ok(()),
+            res => res,
+        }
+    }
+
+    /// Remove all leading directories of `location` that became empty, up to `root`.
+    fn remove_empty_parents(location: &Path, root: &Path) {
+        let mut dir = location.parent();
+        while let Some(parent) = dir.filter(|dir| *dir != root) {
+            if std::fs::remove_dir(parent).is_err() {
+                break;
+            }
+            dir = parent.parent();
+        }
+    }
+}
@@ -6,10 +6,26 @@
 #![cfg_attr(docsrs, feature(doc_cfg, doc_auto_cfg))]
 #![deny(missing_docs, rust_2018_idioms, unsafe_code)]
 
+///
+pub mod clean;
+pub use clean::function::clean;
+
 /// file system related utilities
 pub mod fs;
 
+///
+pub mod filter;
+
+///
+pub mod fs_monitor;
+
 ///
 pub mod index;
 
+///
+pub mod sparse;
+
+///
+pub mod untracked;
+
 pub(crate) mod os;
@@ -48,3 +48,14 @@ pub fn indicates_collision(err: &std::io::Error) -> bool {
 pub fn indicates_collision(err: &std::io::Error) -> bool {
     err.kind() == AlreadyExists || err.kind() == std::io::ErrorKind::PermissionDenied
 }
+
+#[cfg(unix)]
+pub fn is_executable(meta: &std::fs::Metadata) -> bool {
+    use std::os::unix::fs::MetadataExt;
+    meta.mode() & 0o100 == 0o100
+}
+
+#[cfg(not(unix))]
+pub fn is_executable(_meta: &std::fs::Metadata) -> bool {
+    false
+}
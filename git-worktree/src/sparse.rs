@@ -0,0 +1,343 @@
+//! Application of sparse-checkout patterns to the index and the worktree, determining which tracked files are
+//! present on disk and which ones [skip the worktree][git_index::entry::Flags::SKIP_WORKTREE].
+use std::{collections::BTreeSet, path::Path, sync::atomic::AtomicBool};
+
+use bstr::{BStr, BString, ByteSlice};
+use git_features::progress::Progress;
+use git_glob::pattern::Case;
+use git_hash::oid;
+use git_index::entry::{Flags, Mode};
+
+use crate::index::update;
+
+/// The patterns of a `.git/info/sparse-checkout` file that decide which tracked paths are in the worktree.
+#[derive(Debug, Clone)]
+pub struct Patterns {
+    inner: Inner,
+}
+
+#[derive(Debug, Clone)]
+enum Inner {
+    /// Cone mode, which only knows directories.
+    Cone {
+        /// If true, all files and directories are included as the pattern file didn't exclude directories.
+        all: bool,
+        /// Directories whose entire content is included.
+        recursive: BTreeSet<BString>,
+        /// Directories whose files are included, but not their sub-directories unless they are recursive themselves.
+        parents: BTreeSet<BString>,
+    },
+    /// Pattern mode, which uses the full syntax of `.gitignore` files but with inverted meaning.
+    Pattern {
+        list: git_attributes::PatternList<git_attributes::Ignore>,
+        source: BString,
+    },
+}
+
+impl Patterns {
+    /// The path to the sparse-checkout file relative to the `.git` directory.
+    pub const PATH: &'static str = "info/sparse-checkout";
+
+    /// Parse the sparse-checkout `input` in cone mode if `cone` is true, typically the value of `core.sparseCheckoutCone`,
+    /// or in pattern mode otherwise.
+    ///
+    /// Like git, we fall back to pattern mode if `input` contains patterns that aren't valid in cone mode.
+    pub fn from_bytes(input: &[u8], cone: bool) -> Self {
+        if cone {
+            if let Some(inner) = parse_cone(input) {
+                return Patterns { inner };
+            }
+        }
+        Patterns {
+            inner: Inner::Pattern {
+                list: git_attributes::PatternList::from_bytes(input, Self::PATH, None),
+                source: input.into(),
+            },
+        }
+    }
+
+    /// Read the sparse-checkout file from `git_dir` and parse it like [`from_bytes()`][Self::from_bytes()], or return `None`
+    /// if it doesn't exist.
+    pub fn from_git_dir(git_dir: &Path, cone: bool) -> std::io::Result<Option<Self>> {
+        match std::fs::read(git_dir.join(Self::PATH)) {
+            Ok(input) => Ok(Some(Self::from_bytes(&input, cone))),
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => Ok(None),
+            Err(err) => Err(err),
+        }
+    }
+
+    /// Create cone mode patterns that include all files at the top-level along with all content of `directories`,
+    /// similar to `git sparse-checkout set --cone`.
+    pub fn cone_from_directories<'a>(directories: impl IntoIterator<Item = &'a BStr>) -> Self {
+        let mut recursive = BTreeSet::new();
+        for dir in directories {
+            let dir = dir.trim_with(|c| c == '/');
+            if !dir.is_empty() {
+                recursive.insert(dir.as_bstr().to_owned());
+            }
+        }
+        let recursive: BTreeSet<_> = recursive
+            .iter()
+            .filter(|dir| !ancestors(dir.as_ref()).any(|ancestor| recursive.contains(ancestor)))
+            .cloned()
+            .collect();
+        let parents = recursive
+            .iter()
+            .flat_map(|dir| ancestors(dir.as_ref()).map(ToOwned::to_owned))
+            .collect();
+        Patterns {
+            inner: Inner::Cone {
+                all: false,
+                recursive,
+                parents,
+            },
+        }
+    }
+
+    /// Return true if these patterns are interpreted in cone mode.
+    pub fn is_cone(&self) -> bool {
+        matches!(self.inner, Inner::Cone { .. })
+    }
+
+    /// Return true if the file at `rela_path` is in the sparse set and thus should be present in the worktree,
+    /// matching with respect to `case`.
+    pub fn contains(&self, rela_path: &BStr, case: Case) -> bool {
+        match &self.inner {
+            Inner::Cone {
+                all,
+                recursive,
+                parents,
+            } => {
+                let parent = match rela_path.rfind_byte(b'/') {
+                    Some(pos) => rela_path[..pos].as_bstr(),
+                    None => return true,
+                };
+                *all || contains_dir(parents, parent, case)
+                    || std::iter::once(parent)
+                        .chain(ancestors(parent))
+                        .any(|dir| contains_dir(recursive, dir, case))
+            }
+            Inner::Pattern { list, .. } => {
+                let (mut path, mut is_dir) = (rela_path, false);
+                loop {
+                    if let Some(m) = list.pattern_matching_relative_path(
+                        path,
+                        path.rfind_byte(b'/').map(|pos| pos + 1),
+                        Some(is_dir),
+                        case,
+                    ) {
+                        return !m.pattern.is_negative();
+                    }
+                    match path.rfind_byte(b'/') {
+                        Some(pos) => {
+                            path = path[..pos].as_bstr();
+                            is_dir = true;
+                        }
+                        None => return false,
+                    }
+                }
+            }
+        }
+    }
+
+    /// Write these patterns to `out` in the format of the sparse-checkout file.
+    ///
+    /// Cone mode patterns are written in their canonical form, whereas pattern mode patterns are written as they were read.
+    pub fn write_to(&self, mut out: impl std::io::Write) -> std::io::Result<()> {
+        match &self.inner {
+            Inner::Cone {
+                all,
+                recursive,
+                parents,
+            } => {
+                out.write_all(b"/*\n")?;
+                if *all {
+                    return Ok(());
+                }
+                out.write_all(b"!/*/\n")?;
+                let dirs = recursive.iter().map(|dir| (dir, true));
+                let mut dirs: Vec<_> = parents.iter().map(|dir| (dir, false)).chain(dirs).collect();
+                dirs.sort();
+                for (dir, is_recursive) in dirs {
+                    out.write_all(b"/")?;
+                    out.write_all(&escape(dir.as_ref()))?;
+                    out.write_all(b"/\n")?;
+                    if !is_recursive {
+                        out.write_all(b"!/")?;
+                        out.write_all(&escape(dir.as_ref()))?;
+                        out.write_all(b"/*/\n")?;
+                    }
+                }
+                Ok(())
+            }
+            Inner::Pattern { source, .. } => out.write_all(source),
+        }
+    }
+}
+
+/// The outcome of [`mark_skip_worktree()`].
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct Statistics {
+    /// The amount of entries that entered the sparse set and had their skip-worktree flag removed.
+    pub included: usize,
+    /// The amount of entries that left the sparse set and received the skip-worktree flag.
+    pub excluded: usize,
+}
+
+/// Set or clear the [`SKIP_WORKTREE`][Flags::SKIP_WORKTREE] flag of all entries in `index` depending on whether
+/// or not they are contained in `patterns`, matching with respect to `case`, without touching the worktree.
+///
+/// This is useful prior to a [checkout][crate::index::checkout()] which skips all entries with the flag set.
+/// Conflicting entries are always kept in the worktree, and sparse directory entries are left as is.
+pub fn mark_skip_worktree(index: &mut git_index::State, patterns: &Patterns, case: Case) -> Statistics {
+    let mut out = Statistics::default();
+    for (entry, path) in index.entries_mut_with_paths() {
+        if entry.mode == Mode::DIR {
+            continue;
+        }
+        let include = entry.stage() != 0 || patterns.contains(path, case);
+        let is_skipped = entry.flags.contains(Flags::SKIP_WORKTREE);
+        if include && is_skipped {
+            clear_skip_worktree(&mut entry.flags);
+            out.included += 1;
+        } else if !include && !is_skipped {
+            entry.flags.insert(Flags::SKIP_WORKTREE | Flags::EXTENDED);
+            out.excluded += 1;
+        }
+    }
+    out
+}
+
+/// Apply `patterns` to `index` and to the worktree at `dir`, which is assumed to contain a checkout of `index`, similar
+/// to `git sparse-checkout reapply`.
+///
+/// Entries are [marked][mark_skip_worktree()] according to `patterns` and the worktree is [updated][crate::index::update()]
+/// accordingly, with `find`, `files`, `bytes`, `should_interrupt` and `options` used as described there. This removes the files
+/// of entries that left the sparse set and writes those that entered it.
+/// In line with git, locally modified files that left the sparse set are [kept][update::Outcome::kept] unless they
+/// are to be overwritten, and their entries lose their skip-worktree flag to remain visible.
+///
+/// In a [dry-run][update::Options::dry_run], `index` isn't changed.
+#[allow(clippy::too_many_arguments)]
+pub fn apply<Find, E>(
+    index: &mut git_index::State,
+    patterns: &Patterns,
+    dir: impl Into<std::path::PathBuf>,
+    find: Find,
+    files: &mut impl Progress,
+    bytes: &mut impl Progress,
+    should_interrupt: &AtomicBool,
+    options: update::Options,
+) -> Result<update::Outcome, update::Error<E>>
+where
+    Find: for<'a> FnMut(&oid, &'a mut Vec<u8>) -> Result<git_object::BlobRef<'a>, E> + Clone,
+    E: std::error::Error + Send + Sync + 'static,
+{
+    let case = if options.checkout.fs.ignore_case {
+        Case::Fold
+    } else {
+        Case::Sensitive
+    };
+    let dry_run = options.dry_run;
+    let old = index.clone();
+    mark_skip_worktree(index, patterns, case);
+    let out = crate::index::update(&old, index, dir, find, files, bytes, should_interrupt, options)?;
+    if dry_run {
+        *index = old;
+        return Ok(out);
+    }
+    for path in &out.kept {
+        if let Some(idx) = index.entry_index_by_path_and_stage(path.as_ref(), 0) {
+            clear_skip_worktree(&mut index.entries_mut()[idx].flags);
+        }
+    }
+    Ok(out)
+}
+
+fn clear_skip_worktree(flags: &mut Flags) {
+    flags.remove(Flags::SKIP_WORKTREE);
+    if !flags.contains(Flags::INTENT_TO_ADD) {
+        flags.remove(Flags::EXTENDED);
+    }
+}
+
+fn contains_dir(dirs: &BTreeSet<BString>, dir: &BStr, case: Case) -> bool {
+    match case {
+        Case::Sensitive => dirs.contains(dir),
+        Case::Fold => dirs.iter().any(|candidate| candidate.eq_ignore_ascii_case(dir)),
+    }
+}
+
+/// Return all parent directories of `dir`, from the deepest to the top-level one.
+fn ancestors(dir: &BStr) -> impl Iterator<Item = &BStr> {
+    dir.rfind_iter("/").map(move |pos| dir[..pos].as_bstr())
+}
+
+/// Parse `input` as cone mode patterns, or return `None` if there are patterns that aren't valid in cone mode.
+fn parse_cone(input: &[u8]) -> Option<Inner> {
+    let mut all = false;
+    let mut recursive = BTreeSet::new();
+    let mut parents = BTreeSet::new();
+    for line in input.lines() {
+        let line = line.trim_end();
+        if line.is_empty() || line.starts_with(b"#") {
+            continue;
+        }
+        match line {
+            b"/*" => all = true,
+            b"!/*/" => all = false,
+            _ => {
+                let (negative, line) = match line.strip_prefix(b"!") {
+                    Some(line) => (true, line),
+                    None => (false, line),
+                };
+                let dir = line.strip_prefix(b"/")?.strip_suffix(b"/")?;
+                let dir = if negative { dir.strip_suffix(b"/*")? } else { dir };
+                let dir = unescape(dir)?;
+                if dir.is_empty() {
+                    return None;
+                }
+                if negative {
+                    if !recursive.remove(&dir) {
+                        return None;
+                    }
+                    parents.insert(dir);
+                } else {
+                    parents.extend(ancestors(dir.as_ref()).map(ToOwned::to_owned));
+                    recursive.insert(dir);
+                }
+            }
+        }
+    }
+    parents.retain(|dir| !recursive.contains(dir));
+    Some(Inner::Cone {
+        all,
+        recursive,
+        parents,
+    })
+}
+
+/// Remove backslash escapes from `dir`, returning `None` if it contains glob characters that are not escaped.
+fn unescape(dir: &[u8]) -> Option<BString> {
+    let mut out = BString::from(Vec::with_capacity(dir.len()));
+    let mut bytes = dir.iter();
+    while let Some(b) = bytes.next() {
+        match b {
+            b'\\' => out.push(*bytes.next()?),
+            b'*' | b'?' | b'[' => return None,
+            b => out.push(*b),
+        }
+    }
+    Some(out)
+}
+
+fn escape(dir: &BStr) -> BString {
+    let mut out = BString::from(Vec::with_capacity(dir.len()));
+    for b in dir.iter() {
+        if matches!(b, b'\\' | b'*' | b'?' | b'[') {
+            out.push(b'\\');
+        }
+        out.push(*b);
+    }
+    out
+}
@@ -0,0 +1,549 @@
+use std::{
+    borrow::Cow,
+    io,
+    path::{Path, PathBuf},
+    sync::atomic::{AtomicBool, Ordering},
+};
+
+use bstr::{BStr, BString, ByteSlice};
+use git_features::parallel::{in_parallel, Reduce};
+use git_hash::{oid, ObjectId};
+use git_index::extension::{untracked_cache::Directory, UntrackedCache};
+
+use crate::{
+    fs,
+    untracked::{Entry, Error, Kind, Mode, Options, Outcome, Status},
+};
+
+/// Find all files and directories in the worktree at the root of `cache` which aren't tracked in `index`, and classify
+/// them as untracked or ignored using the exclude patterns of `cache`, which must be configured for exclusion queries.
+/// `find` is used to read exclude files from the object database if they are marked as skipping the worktree.
+///
+/// If `previous` is set, which should be the untracked cache of `index`, its untracked entries are used for all directories
+/// that didn't change, avoiding reading them. It's the caller's responsibility to only pass a cache whose identifier and
+/// global exclude files match, and which was invalidated for paths that changed in the index.
+/// Top-level directories are walked in parallel if [`thread_limit`][Options::thread_limit] allows it.
+///
+/// Note that interruption still produce an `Ok(…)` value, so the caller should look at `should_interrupt` to communicate the outcome.
+pub fn walk<Find, E>(
+    index: &git_index::State,
+    cache: &fs::Cache<'_>,
+    find: Find,
+    previous: Option<&UntrackedCache>,
+    should_interrupt: &AtomicBool,
+    options: Options,
+) -> Result<Outcome, Error>
+where
+    Find: for<'a> FnMut(&oid, &'a mut Vec<u8>) -> Result<git_object::BlobRef<'a>, E> + Send + Clone,
+    E: std::error::Error + Send + Sync + 'static,
+{
+    let mut out = Outcome {
+        dir_flags: options.mode.dir_flags(),
+        exclude_file_name: cache.exclude_file_name_for_directories().to_owned(),
+        ..Default::default()
+    };
+    if options.mode == Mode::No {
+        return Ok(out);
+    }
+    let previous = previous.filter(|previous| {
+        !options.emit_ignored
+            && previous.dir_flags() == out.dir_flags
+            && previous.exclude_filename_per_dir() == out.exclude_file_name
+            && !previous.directories().is_empty()
+    });
+    let ignore_case = options.fs.ignore_case;
+    let mut tracked: Vec<_> = index
+        .entries()
+        .iter()
+        .map(|entry| tracked_key(entry.path(index), ignore_case))
+        .collect();
+    if ignore_case {
+        tracked.sort();
+    }
+    tracked.dedup();
+
+    let mut walk = Walk {
+        root: cache.base().to_owned(),
+        tracked: &tracked,
+        object_hash: index.object_hash(),
+        previous,
+        options,
+        find,
+        cache: cache.clone(),
+        should_interrupt,
+        entries: Vec::new(),
+        directories_read: 0,
+        directories_from_cache: 0,
+    };
+    let (mut root, sub_directories) = walk.read("".into(), previous.map(|previous| &previous.directories()[0]))?;
+
+    let results = if git_features::parallel::num_threads(options.thread_limit) > 1 && sub_directories.len() > 1 {
+        in_parallel(
+            sub_directories.into_iter(),
+            options.thread_limit,
+            {
+                let walk = walk.clone();
+                move |_| walk.clone()
+            },
+            |(rela_path, cached), walk| walk.visit_and_take(rela_path.as_ref(), cached),
+            Collect::default(),
+        )?
+    } else {
+        sub_directories
+            .into_iter()
+            .map(|(rela_path, cached)| walk.visit_and_take(rela_path.as_ref(), cached))
+            .collect::<Result<_, _>>()?
+    };
+
+    out.entries = walk.entries;
+    out.directories_read = walk.directories_read;
+    out.directories_from_cache = walk.directories_from_cache;
+    for (dir, entries, directories_read, directories_from_cache) in results {
+        root.sub_directories.push(dir);
+        out.entries.extend(entries);
+        out.directories_read += directories_read;
+        out.directories_from_cache += directories_from_cache;
+    }
+    root.sub_directories.sort_by(|a, b| a.name.cmp(&b.name));
+    out.entries.sort_by(|a, b| a.rela_path.cmp(&b.rela_path));
+    flatten(root, &mut out.untracked_cache);
+    Ok(out)
+}
+
+/// A directory as stored in the untracked cache, but with its sub-directories inline.
+struct Dir {
+    name: BString,
+    untracked_entries: Vec<BString>,
+    stat: git_index::entry::Stat,
+    exclude_file_oid: Option<ObjectId>,
+    sub_directories: Vec<Dir>,
+}
+
+/// A directory to visit by its path relative to the worktree root, along with its cached counterpart.
+type SubDirectory<'a> = (BString, Option<&'a Directory>);
+
+#[derive(Clone)]
+struct Walk<'a, 'paths, Find> {
+    root: PathBuf,
+    /// The sorted paths of all entries in the index, in lower-case if the case is to be ignored.
+    tracked: &'a [Cow<'a, BStr>],
+    object_hash: git_hash::Kind,
+    previous: Option<&'a UntrackedCache>,
+    options: Options,
+    find: Find,
+    cache: fs::Cache<'paths>,
+    should_interrupt: &'a AtomicBool,
+    entries: Vec<Entry>,
+    directories_read: usize,
+    directories_from_cache: usize,
+}
+
+impl<'a, 'paths, Find, E> Walk<'a, 'paths, Find>
+where
+    Find: for<'b> FnMut(&oid, &'b mut Vec<u8>) -> Result<git_object::BlobRef<'b>, E>,
+    E: std::error::Error + Send + Sync + 'static,
+{
+    /// Visit the directory at `rela_dir` and all of its sub-directories, and return it along with all entries found in it.
+    fn visit_and_take(
+        &mut self,
+        rela_dir: &BStr,
+        cached: Option<&'a Directory>,
+    ) -> Result<(Dir, Vec<Entry>, usize, usize), Error> {
+        let dir = self.visit(rela_dir, cached)?;
+        Ok((
+            dir,
+            std::mem::take(&mut self.entries),
+            std::mem::take(&mut self.directories_read),
+            std::mem::take(&mut self.directories_from_cache),
+        ))
+    }
+
+    fn visit(&mut self, rela_dir: &BStr, cached: Option<&'a Directory>) -> Result<Dir, Error> {
+        let (mut dir, sub_directories) = self.read(rela_dir, cached)?;
+        for (rela_path, cached) in sub_directories {
+            if self.should_interrupt.load(Ordering::Relaxed) {
+                break;
+            }
+            dir.sub_directories.push(self.visit(rela_path.as_ref(), cached)?);
+        }
+        Ok(dir)
+    }
+
+    /// Emit all untracked entries of the directory at `rela_dir` and return it along with the sub-directories to visit.
+    fn read(&mut self, rela_dir: &BStr, cached: Option<&'a Directory>) -> Result<(Dir, Vec<SubDirectory<'a>>), Error> {
+        let path = self.path(rela_dir);
+        let stat = std::fs::metadata(&path)
+            .map(|meta| stat(&meta))
+            .map_err(|source| Error::Metadata {
+                path: path.clone(),
+                source,
+            })?;
+        let exclude_file = path.join(git_path::from_bstr(self.cache.exclude_file_name_for_directories()));
+        let exclude_file_oid = match std::fs::read(&exclude_file) {
+            Ok(data) => {
+                let mut hasher = git_features::hash::hasher(self.object_hash);
+                hasher.update(&git_object::encode::loose_header(git_object::Kind::Blob, data.len()));
+                hasher.update(&data);
+                Some(hasher.digest().into())
+            }
+            Err(err) if err.kind() == io::ErrorKind::NotFound => None,
+            Err(source) => {
+                return Err(Error::Excludes {
+                    path: exclude_file,
+                    source,
+                })
+            }
+        };
+        let mut dir = Dir {
+            name: rela_dir
+                .rfind_byte(b'/')
+                .map_or(rela_dir, |pos| rela_dir[pos + 1..].as_bstr())
+                .to_owned(),
+            untracked_entries: Vec::new(),
+            stat,
+            exclude_file_oid,
+            sub_directories: Vec::new(),
+        };
+
+        if let Some(cached) = cached.filter(|cached| {
+            !cached.check_only && cached.stat == Some(dir.stat) && cached.exclude_file_oid == dir.exclude_file_oid
+        }) {
+            if let Some((entries, sub_directories)) = self.read_from_cache(rela_dir, cached) {
+                self.directories_from_cache += 1;
+                self.entries.extend(entries);
+                dir.untracked_entries = cached.untracked_entries.clone();
+                return Ok((dir, sub_directories));
+            }
+        }
+        self.directories_read += 1;
+
+        let mut sub_directories = Vec::new();
+        for (name, file_type) in read_dir(&path, self.options.fs.precompose_unicode)? {
+            let rela_path = join(rela_dir, name.as_ref());
+            if file_type.is_dir() {
+                if self.is_tracked(rela_path.as_ref(), true) {
+                    // Submodules or sparse directories.
+                    continue;
+                }
+                let previous = self.previous;
+                let cached_sub_directory = || {
+                    cached.and_then(|cached| {
+                        let directories = previous.expect("set if there is a cached directory").directories();
+                        cached
+                            .sub_directories
+                            .iter()
+                            .map(|idx| &directories[*idx])
+                            .find(|dir| dir.name == name)
+                    })
+                };
+                if self.contains_tracked(rela_path.as_ref()) {
+                    sub_directories.push((rela_path, cached_sub_directory()));
+                    continue;
+                }
+                if self.is_excluded(rela_path.as_ref(), true)? {
+                    self.emit_ignored(rela_path, Kind::Directory);
+                    continue;
+                }
+                if is_repository(&path.join(git_path::from_bstr(name.as_bstr()))) {
+                    dir.untracked_entries.push(directory_name(name));
+                    self.emit_untracked(rela_path, Kind::Repository);
+                    continue;
+                }
+                match self.options.mode {
+                    Mode::All => sub_directories.push((rela_path, cached_sub_directory())),
+                    Mode::Normal => {
+                        if self.contains_untracked(rela_path.as_ref())? {
+                            dir.untracked_entries.push(directory_name(name));
+                            self.emit_untracked(rela_path, Kind::Directory);
+                        }
+                    }
+                    Mode::No => unreachable!("we don't walk at all"),
+                }
+            } else {
+                if self.is_tracked(rela_path.as_ref(), false) {
+                    continue;
+                }
+                let kind = if file_type.is_symlink() {
+                    Kind::Symlink
+                } else {
+                    Kind::File
+                };
+                if self.is_excluded(rela_path.as_ref(), false)? {
+                    self.emit_ignored(rela_path, kind);
+                } else {
+                    dir.untracked_entries.push(name);
+                    self.emit_untracked(rela_path, kind);
+                }
+            }
+        }
+        Ok((dir, sub_directories))
+    }
+
+    /// Return the entries and sub-directories of the directory at `rela_dir` from its `cached` version, or `None` if
+    /// the cache turns out to be outdated.
+    fn read_from_cache(&self, rela_dir: &BStr, cached: &'a Directory) -> Option<(Vec<Entry>, Vec<SubDirectory<'a>>)> {
+        let mut entries = Vec::with_capacity(cached.untracked_entries.len());
+        for name in &cached.untracked_entries {
+            let (name, is_dir) = match name.strip_suffix(b"/") {
+                Some(name) => (name.as_bstr(), true),
+                None => (name.as_bstr(), false),
+            };
+            let rela_path = join(rela_dir, name);
+            if self.is_tracked(rela_path.as_ref(), is_dir) || (is_dir && self.contains_tracked(rela_path.as_ref())) {
+                return None;
+            }
+            let path = self.path(rela_path.as_ref());
+            let meta = std::fs::symlink_metadata(&path).ok()?;
+            let kind = match (is_dir, meta.is_dir()) {
+                (true, true) if is_repository(&path) => Kind::Repository,
+                (true, true) => Kind::Directory,
+                (false, false) if meta.file_type().is_symlink() => Kind::Symlink,
+                (false, false) => Kind::File,
+                _ => return None,
+            };
+            entries.push(Entry {
+                rela_path,
+                kind,
+                status: Status::Untracked,
+            });
+        }
+        let directories = self.previous.expect("set if there is a cached directory").directories();
+        let sub_directories = cached
+            .sub_directories
+            .iter()
+            .map(|idx| {
+                let dir = &directories[*idx];
+                (join(rela_dir, dir.name.as_ref()), Some(dir))
+            })
+            .collect();
+        Some((entries, sub_directories))
+    }
+
+    /// Return true if the untracked directory at `rela_dir` contains at least one untracked file, while emitting all
+    /// ignored entries in it if needed.
+    fn contains_untracked(&mut self, rela_dir: &BStr) -> Result<bool, Error> {
+        let path = self.path(rela_dir);
+        self.directories_read += 1;
+        let mut found = false;
+        for (name, file_type) in read_dir(&path, self.options.fs.precompose_unicode)? {
+            let rela_path = join(rela_dir, name.as_ref());
+            let is_dir = file_type.is_dir();
+            if self.is_excluded(rela_path.as_ref(), is_dir)? {
+                let kind = if is_dir {
+                    Kind::Directory
+                } else if file_type.is_symlink() {
+                    Kind::Symlink
+                } else {
+                    Kind::File
+                };
+                self.emit_ignored(rela_path, kind);
+                continue;
+            }
+            found |= !is_dir
+                || is_repository(&path.join(git_path::from_bstr(name.as_bstr())))
+                || self.contains_untracked(rela_path.as_ref())?;
+            if found && !self.options.emit_ignored {
+                break;
+            }
+        }
+        Ok(found)
+    }
+
+    fn is_excluded(&mut self, rela_path: &BStr, is_dir: bool) -> Result<bool, Error> {
+        let root = &self.root;
+        Ok(self
+            .cache
+            .at_entry(rela_path, Some(is_dir), &mut self.find)
+            .map_err(|source| Error::Excludes {
+                path: root.join(git_path::from_bstr(rela_path)),
+                source,
+            })?
+            .is_excluded())
+    }
+
+    /// Return true if `rela_path` is in the index, also as sparse directory if it `is_dir`.
+    fn is_tracked(&self, rela_path: &BStr, is_dir: bool) -> bool {
+        let rela_path = tracked_key(rela_path, self.options.fs.ignore_case);
+        let find = |path: &BStr| {
+            self.tracked
+                .binary_search_by(|tracked| tracked.as_ref().cmp(path))
+                .is_ok()
+        };
+        find(rela_path.as_ref())
+            || (is_dir && {
+                let mut sparse_dir = rela_path.into_owned();
+                sparse_dir.push(b'/');
+                find(sparse_dir.as_ref())
+            })
+    }
+
+    /// Return true if the directory at `rela_dir` contains tracked entries.
+    fn contains_tracked(&self, rela_dir: &BStr) -> bool {
+        let mut prefix = tracked_key(rela_dir, self.options.fs.ignore_case).into_owned();
+        prefix.push(b'/');
+        let start = self.tracked.partition_point(|path| path.as_ref() < prefix.as_bstr());
+        self.tracked
+            .get(start)
+            .map_or(false, |path| path.starts_with(prefix.as_slice()))
+    }
+
+    fn path(&self, rela_path: &BStr) -> PathBuf {
+        self.root.join(git_path::from_bstr(rela_path))
+    }
+
+    fn emit_untracked(&mut self, rela_path: BString, kind: Kind) {
+        self.entries.push(Entry {
+            rela_path,
+            kind,
+            status: Status::Untracked,
+        });
+    }
+
+    fn emit_ignored(&mut self, rela_path: BString, kind: Kind) {
+        if self.options.emit_ignored {
+            self.entries.push(Entry {
+                rela_path,
+                kind,
+                status: Status::Ignored,
+            });
+        }
+    }
+}
+
+/// Return the names and types of all entries in the directory at `path`, sorted by name and without the `.git` directory.
+/// If `precompose_unicode` is set, names are turned into their precomposed form.
+pub(crate) fn read_dir(path: &Path, precompose_unicode: bool) -> Result<Vec<(BString, std::fs::FileType)>, Error> {
+    let err = |source: git_features::fs::walkdir::Error| Error::ReadDir {
+        path: path.to_owned(),
+        source: source.into(),
+    };
+    let mut entries = Vec::new();
+    for entry in git_features::fs::walkdir_with(path, Default::default())
+        .min_depth(1)
+        .max_depth(1)
+    {
+        let entry = entry.map_err(err)?;
+        let mut name = git_path::into_bstr(PathBuf::from(entry.file_name())).into_owned();
+        if precompose_unicode {
+            name = precompose(name);
+        }
+        if name == ".git" {
+            continue;
+        }
+        entries.push((name, entry.file_type()));
+    }
+    // Sort after precomposing names, which may change their order.
+    entries.sort_by(|a, b| a.0.cmp(&b.0));
+    Ok(entries)
+}
+
+fn precompose(name: BString) -> BString {
+    use unicode_normalization::UnicodeNormalization;
+    match name.to_str() {
+        Ok(name) if !name.is_ascii() => name.nfc().collect::<String>().into(),
+        _ => name,
+    }
+}
+
+/// Return `path` in the form used to look it up among tracked paths, which is lower-case if the case is to be ignored.
+fn tracked_key(path: &BStr, ignore_case: bool) -> Cow<'_, BStr> {
+    if ignore_case && path.iter().any(u8::is_ascii_uppercase) {
+        Cow::Owned(path.to_ascii_lowercase().into())
+    } else {
+        Cow::Borrowed(path)
+    }
+}
+
+/// Nested repositories are considered untracked as a whole, like git does unless they are submodules.
+pub(crate) fn is_repository(dir: &Path) -> bool {
+    dir.join(".git").symlink_metadata().is_ok()
+}
+
+pub(crate) fn join(rela_dir: &BStr, name: &BStr) -> BString {
+    if rela_dir.is_empty() {
+        name.to_owned()
+    } else {
+        let mut path = rela_dir.to_owned();
+        path.push(b'/');
+        path.extend_from_slice(name);
+        path
+    }
+}
+
+/// Untracked directories are stored with a trailing slash in the untracked cache.
+fn directory_name(mut name: BString) -> BString {
+    name.push(b'/');
+    name
+}
+
+fn stat(meta: &std::fs::Metadata) -> git_index::entry::Stat {
+    let time = |time: io::Result<std::time::SystemTime>| {
+        time.ok()
+            .and_then(|time| time.duration_since(std::time::UNIX_EPOCH).ok())
+            .map(|time| git_index::entry::Time {
+                secs: time.as_secs() as u32,
+                nsecs: time.subsec_nanos(),
+            })
+            .unwrap_or_default()
+    };
+    #[cfg_attr(not(unix), allow(unused_mut))]
+    let mut stat = git_index::entry::Stat {
+        mtime: time(meta.modified()),
+        ctime: time(meta.created()),
+        size: meta.len() as u32,
+        ..Default::default()
+    };
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::MetadataExt;
+        stat.dev = meta.dev() as u32;
+        stat.ino = meta.ino() as u32;
+        stat.uid = meta.uid();
+        stat.gid = meta.gid();
+    }
+    stat
+}
+
+/// Store all directories in `dir` depth-first, with each directory before its sub-directories, like the untracked cache does.
+fn flatten(dir: Dir, out: &mut Vec<Directory>) {
+    let idx = out.len();
+    out.push(Directory {
+        name: dir.name,
+        untracked_entries: dir.untracked_entries,
+        sub_directories: Vec::with_capacity(dir.sub_directories.len()),
+        stat: Some(dir.stat),
+        exclude_file_oid: dir.exclude_file_oid,
+        check_only: false,
+    });
+    for sub_directory in dir.sub_directories {
+        let sub_idx = out.len();
+        flatten(sub_directory, out);
+        out[idx].sub_directories.push(sub_idx);
+    }
+}
+
+struct Collect<T> {
+    items: Vec<T>,
+}
+
+impl<T> Default for Collect<T> {
+    fn default() -> Self {
+        Collect { items: Vec::new() }
+    }
+}
+
+impl<T> Reduce for Collect<T> {
+    type Input = Result<T, Error>;
+    type FeedProduce = ();
+    type Output = Vec<T>;
+    type Error = Error;
+
+    fn feed(&mut self, item: Self::Input) -> Result<Self::FeedProduce, Self::Error> {
+        self.items.push(item?);
+        Ok(())
+    }
+
+    fn finalize(self) -> Result<Self::Output, Self::Error> {
+        Ok(self.items)
+    }
+}
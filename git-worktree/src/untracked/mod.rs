@@ -0,0 +1,150 @@
+//! Discovery of untracked and ignored files in the worktree, along with the information needed to maintain
+//! the untracked cache of the index.
+use std::path::PathBuf;
+
+use bstr::BString;
+
+pub(crate) mod function;
+pub use function::walk;
+
+/// Flags as used by git to describe how untracked entries were collected, stored in the untracked cache.
+mod dir_flags {
+    /// Untracked directories are listed instead of their content.
+    pub const SHOW_OTHER_DIRECTORIES: u32 = 1 << 1;
+    /// Untracked directories without untracked files aren't listed.
+    pub const HIDE_EMPTY_DIRECTORIES: u32 = 1 << 2;
+}
+
+/// How to report untracked files, similar to `status.showUntrackedFiles`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Mode {
+    /// Don't look for untracked files at all.
+    No,
+    /// Report untracked files, but report untracked directories as a whole instead of their content.
+    /// Untracked directories without any untracked file in them aren't reported.
+    Normal,
+    /// Report all untracked files individually, which requires recursing into all untracked directories.
+    All,
+}
+
+impl Default for Mode {
+    fn default() -> Self {
+        Mode::Normal
+    }
+}
+
+impl Mode {
+    /// Return the flags git stores in the untracked cache to indicate how its untracked entries were collected.
+    pub fn dir_flags(&self) -> u32 {
+        match self {
+            Mode::Normal => dir_flags::SHOW_OTHER_DIRECTORIES | dir_flags::HIDE_EMPTY_DIRECTORIES,
+            Mode::All | Mode::No => 0,
+        }
+    }
+}
+
+/// Options for use in [`walk()`].
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Options {
+    /// How to report untracked files.
+    pub mode: Mode,
+    /// If true, ignored files and directories are reported as well, which prevents the use of an untracked cache as it
+    /// doesn't know about them.
+    pub emit_ignored: bool,
+    /// If set, don't use more than this amount of threads.
+    /// Otherwise, usually use as many threads as there are logical cores.
+    /// A value of 0 is interpreted as no-limit
+    pub thread_limit: Option<usize>,
+    /// Capabilities of the file system, which affect how names of files on disk are matched with paths in the index.
+    ///
+    /// With `ignore_case`, files are tracked if their path matches a path in the index when ignoring case, while with
+    /// `precompose_unicode` decomposed unicode in file names is turned into its precomposed form first.
+    pub fs: crate::fs::Capabilities,
+}
+
+/// The kind of an untracked or ignored entry.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Ord, PartialOrd)]
+pub enum Kind {
+    /// A file, which may be executable.
+    File,
+    /// A symbolic link, which is never followed.
+    Symlink,
+    /// A directory, which is reported as a whole.
+    Directory,
+    /// A directory which contains a `.git` directory or file, and thus is a nested repository that we don't look into.
+    Repository,
+}
+
+/// The state of an entry in relation to the index and exclude patterns.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Ord, PartialOrd)]
+pub enum Status {
+    /// The entry isn't tracked and isn't excluded.
+    Untracked,
+    /// The entry isn't tracked and is excluded by exclude patterns.
+    Ignored,
+}
+
+/// An untracked or ignored entry in the worktree.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Ord, PartialOrd)]
+pub struct Entry {
+    /// The path relative to the worktree root, without trailing slash even for directories.
+    pub rela_path: BString,
+    /// The kind of entry.
+    pub kind: Kind,
+    /// Whether the entry is untracked, or ignored.
+    pub status: Status,
+}
+
+/// The outcome of a [`walk()`].
+#[derive(Clone, Default)]
+pub struct Outcome {
+    /// All untracked or ignored entries, sorted by path.
+    pub entries: Vec<Entry>,
+    /// All directories that were visited in the format of the untracked cache, with the root directory first.
+    pub untracked_cache: Vec<git_index::extension::untracked_cache::Directory>,
+    /// The flags describing how untracked entries were collected, as stored in the untracked cache.
+    pub dir_flags: u32,
+    /// The name of the per-directory exclude files.
+    pub exclude_file_name: BString,
+    /// The amount of directories whose content was read from disk.
+    pub directories_read: usize,
+    /// The amount of directories whose untracked entries were taken from the untracked cache.
+    pub directories_from_cache: usize,
+}
+
+impl Outcome {
+    /// Create an untracked cache extension for the index from our directories, with `identifier` identifying the
+    /// machine and location, along with the state of the `info_exclude` and `excludes_file` files that were used
+    /// to determine which files are ignored.
+    ///
+    /// Return `None` if there is no information to cache.
+    pub fn to_untracked_cache(
+        &self,
+        identifier: BString,
+        info_exclude: Option<git_index::extension::untracked_cache::OidStat>,
+        excludes_file: Option<git_index::extension::untracked_cache::OidStat>,
+    ) -> Option<git_index::extension::UntrackedCache> {
+        (!self.untracked_cache.is_empty()).then(|| {
+            git_index::extension::UntrackedCache::new(
+                identifier,
+                info_exclude,
+                excludes_file,
+                self.exclude_file_name.clone(),
+                self.dir_flags,
+                self.untracked_cache.clone(),
+            )
+        })
+    }
+}
+
+/// The error returned by [`walk()`].
+#[derive(Debug, thiserror::Error)]
+#[allow(missing_docs)]
+pub enum Error {
+    #[error("Could not read directory at '{}'", path.display())]
+    ReadDir { path: PathBuf, source: std::io::Error },
+    #[error("Could not obtain metadata for '{}'", path.display())]
+    Metadata { path: PathBuf, source: std::io::Error },
+    #[error("Could not read exclude patterns for '{}'", path.display())]
+    Excludes { path: PathBuf, source: std::io::Error },
+}
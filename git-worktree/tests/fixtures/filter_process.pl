@@ -0,0 +1,77 @@
+#!/usr/bin/perl
+# A long-running filter process which upper-cases content on smudge and lower-cases it on clean.
+# It fails for all paths containing 'fail', and aborts the current command for paths containing 'abort'.
+use strict;
+use warnings;
+
+binmode STDIN;
+binmode STDOUT;
+$| = 1;
+
+sub packet {
+    my $n = read(STDIN, my $len, 4);
+    exit 0 if !$n;
+    $len = hex($len);
+    return undef if $len == 0;
+    read(STDIN, my $data, $len - 4) == $len - 4 or die "short read";
+    return $data;
+}
+
+sub lines {
+    my @lines;
+    while (defined(my $line = packet())) {
+        chomp $line;
+        push @lines, $line;
+    }
+    return @lines;
+}
+
+sub write_text {
+    my $text = "$_[0]\n";
+    printf "%04x%s", length($text) + 4, $text;
+}
+
+sub write_data {
+    for (my $i = 0; $i < length $_[0]; $i += 65516) {
+        my $chunk = substr($_[0], $i, 65516);
+        printf "%04x%s", length($chunk) + 4, $chunk;
+    }
+}
+
+sub flush {
+    print "0000";
+}
+
+my @hello = lines();
+die "bad handshake" unless $hello[0] eq "git-filter-client" && grep { $_ eq "version=2" } @hello;
+write_text("git-filter-server");
+write_text("version=2");
+flush();
+
+my %capabilities = map { $_ => 1 } lines();
+for my $capability ("clean", "smudge") {
+    write_text("capability=$capability") if $capabilities{"capability=$capability"};
+}
+flush();
+
+while (1) {
+    my %meta = map { split /=/, $_, 2 } lines();
+    my $content = "";
+    while (defined(my $data = packet())) {
+        $content .= $data;
+    }
+    if ($meta{pathname} =~ /fail/) {
+        write_text("status=error");
+        flush();
+    } elsif ($meta{pathname} =~ /abort/) {
+        write_text("status=abort");
+        flush();
+    } else {
+        write_text("status=success");
+        flush();
+        write_data($meta{command} eq "smudge" ? uc $content : lc $content);
+        flush();
+        # An empty list keeps the status.
+        flush();
+    }
+}
@@ -0,0 +1,23 @@
+#!/bin/bash
+set -eu -o pipefail
+
+git init -q
+
+cat <<EOF_ATTRS > .gitattributes
+*.txt text eol=crlf
+*.bin -text
+ident.c ident
+*.up filter=upper
+EOF_ATTRS
+
+mkdir sub
+echo "*.txt -text" > sub/.gitattributes
+
+printf 'a\nb\n' > file.txt
+printf 'a\nb\n' > sub/file.txt
+printf 'a\nb\n' > file.bin
+printf '$Id$\n' > ident.c
+printf 'lower\n' > file.up
+
+git add -A
+git commit -q -m init
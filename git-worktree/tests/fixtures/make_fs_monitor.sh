@@ -0,0 +1,23 @@
+#!/bin/bash
+set -eu -o pipefail
+
+git init -q
+
+mkdir dir
+echo a > a
+echo b > b
+echo c > dir/c
+
+git add -A
+git commit -q -m "init"
+
+cat <<EOF >.git/fsmonitor-hook
+#!/bin/sh
+printf "token-1\0/\0"
+EOF
+chmod +x .git/fsmonitor-hook
+
+git config core.fsmonitor .git/fsmonitor-hook
+git config core.fsmonitorHookVersion 2
+git update-index --fsmonitor
+git status >/dev/null
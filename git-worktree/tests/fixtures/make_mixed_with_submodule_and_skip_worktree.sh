@@ -0,0 +1,16 @@
+#!/bin/bash
+set -eu -o pipefail
+
+git init -q
+
+echo -n "content" > file
+mkdir dir
+echo -n "other content" > dir/content
+echo -n "not checked out" > dir/skipped
+git add -A
+git commit -q -m "Commit"
+
+# a submodule whose commit doesn't exist in this repository, just like it would be the case in a fresh clone
+git update-index --add --cacheinfo 160000,"$(git rev-parse HEAD)",dir/sub
+git update-index --skip-worktree dir/skipped
+git commit -q -m "add submodule"
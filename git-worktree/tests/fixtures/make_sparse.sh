@@ -0,0 +1,10 @@
+#!/bin/bash
+set -eu -o pipefail
+
+git init -q
+
+mkdir -p a/b/c d/e
+touch top a/file a/b/file a/b/c/file d/file d/e/file
+
+git add -A
+git commit -q -m "init"
@@ -0,0 +1,23 @@
+#!/bin/bash
+set -eu -o pipefail
+
+git init -q
+
+# Git refuses to put paths like these into the index, but they can still be part of trees received from a remote.
+blob=$(echo -n 'content' | git hash-object -w --stdin)
+file_tree=$(printf "100644 blob $blob\tfile\n" | git mktree)
+sub_tree=$(printf "100644 blob $blob\tfile\n040000 tree $file_tree\t..\n" | git mktree)
+
+printf "100644 blob $blob\tgood
+040000 tree $file_tree\t.GIT
+040000 tree $file_tree\t.git.
+040000 tree $file_tree\tGIT~1
+100644 blob $blob\tc:
+100644 blob $blob\tfile:stream
+040000 tree $sub_tree\tsub
+" | git mktree > .git/unsafe-tree
+
+printf "100644 blob $blob\tgood
+040000 tree $file_tree\tgit~1
+100644 blob $blob\tfile:stream
+" | git mktree > .git/ntfs-only-tree
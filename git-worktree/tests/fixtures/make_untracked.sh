@@ -0,0 +1,35 @@
+#!/bin/bash
+set -eu -o pipefail
+
+git init -q
+
+cat <<EOF >.gitignore
+*.log
+ignored-dir/
+EOF
+
+mkdir -p tracked-dir/sub
+touch tracked tracked-dir/file tracked-dir/sub/file
+echo "local-ignored" > tracked-dir/.gitignore
+
+git add -A
+git commit -q -m "init"
+
+echo "from-info-exclude" >> .git/info/exclude
+
+touch untracked a.log from-info-exclude
+ln -s tracked link
+touch tracked-dir/untracked tracked-dir/file.log tracked-dir/local-ignored tracked-dir/sub/untracked
+
+mkdir -p untracked-dir/nested
+touch untracked-dir/file untracked-dir/nested/file untracked-dir/nested/file.log
+
+mkdir -p only-ignored/nested
+touch only-ignored/a.log only-ignored/nested/b.log
+
+mkdir ignored-dir
+touch ignored-dir/file
+
+mkdir -p empty-dir/empty-nested
+
+git init -q nested-repo
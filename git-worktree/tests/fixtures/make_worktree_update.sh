@@ -0,0 +1,32 @@
+#!/bin/bash
+set -eu -o pipefail
+
+git init -q
+
+echo -n "same" > same
+echo -n "old" > changed
+echo -n "removed" > removed
+mkdir removed-dir
+echo -n "removed" > removed-dir/file
+echo -n "file" > file-becomes-dir
+mkdir dir-becomes-file
+echo -n "file in dir" > dir-becomes-file/file
+echo -n "a file" > file-becomes-link
+echo -n "old" > locally-modified
+echo -n "old" > locally-modified-and-removed
+git add -A
+git commit -q -m "old"
+git rev-parse @^{tree} > .git/old-tree
+
+git rm -q -r removed removed-dir file-becomes-dir dir-becomes-file locally-modified-and-removed file-becomes-link
+echo -n "new" > changed
+echo -n "new" > locally-modified
+echo -n "added" > added
+echo -n "new content" > untracked-in-the-way
+mkdir file-becomes-dir
+echo -n "file in dir" > file-becomes-dir/file
+echo -n "file" > dir-becomes-file
+ln -s same file-becomes-link
+git add -A
+git commit -q -m "new"
+git rev-parse @^{tree} > .git/new-tree
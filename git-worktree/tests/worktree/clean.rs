@@ -0,0 +1,195 @@
+use std::{path::Path, sync::atomic::AtomicBool};
+
+use bstr::BString;
+use git_odb::FindExt;
+use git_worktree::{
+    clean::{self, Ignored},
+    fs,
+    untracked::{Kind, Status},
+};
+
+fn clean(worktree: &Path, options: clean::Options) -> crate::Result<clean::Outcome> {
+    let git_dir = worktree.join(".git");
+    let mut buf = Vec::new();
+    let index = git_index::File::at(git_dir.join("index"), git_hash::Kind::Sha1, Default::default())?;
+    let odb = git_odb::at(git_dir.join("objects"))?.into_arc()?;
+    let case = git_glob::pattern::Case::Sensitive;
+    let state = fs::cache::State::for_status(fs::cache::state::Ignore::new(
+        Default::default(),
+        git_attributes::MatchGroup::from_git_dir(&git_dir, None, &mut buf)?,
+        None,
+        case,
+    ));
+    let attribute_files_in_index = state.build_attribute_list(&index, index.path_backing(), case);
+    let cache = fs::Cache::new(worktree, state, case, buf, attribute_files_in_index);
+    Ok(git_worktree::clean(
+        &index,
+        &cache,
+        move |oid, buf| odb.find_blob(oid, buf),
+        &AtomicBool::default(),
+        options,
+    )?)
+}
+
+fn paths(entries: &[git_worktree::untracked::Entry]) -> Vec<(BString, Kind, Status)> {
+    entries
+        .iter()
+        .map(|e| (e.rela_path.clone(), e.kind, e.status))
+        .collect()
+}
+
+fn untracked(path: &str, kind: Kind) -> (BString, Kind, Status) {
+    (path.into(), kind, Status::Untracked)
+}
+
+fn ignored(path: &str, kind: Kind) -> (BString, Kind, Status) {
+    (path.into(), kind, Status::Ignored)
+}
+
+/// Writable copies of fixtures may not retain symlinks.
+fn link_kind(tmp: &tempfile::TempDir) -> Kind {
+    if tmp
+        .path()
+        .join("link")
+        .symlink_metadata()
+        .map_or(false, |m| m.file_type().is_symlink())
+    {
+        Kind::Symlink
+    } else {
+        Kind::File
+    }
+}
+
+fn worktree() -> crate::Result<tempfile::TempDir> {
+    git_testtools::scripted_fixture_repo_writable("make_untracked.sh")
+}
+
+#[test]
+fn by_default_only_untracked_files_in_tracked_directories_are_removed() -> crate::Result {
+    let tmp = worktree()?;
+    let expected = vec![
+        untracked("link", link_kind(&tmp)),
+        untracked("tracked-dir/sub/untracked", Kind::File),
+        untracked("tracked-dir/untracked", Kind::File),
+        untracked("untracked", Kind::File),
+    ];
+
+    let out = clean(
+        tmp.path(),
+        clean::Options {
+            dry_run: true,
+            ..Default::default()
+        },
+    )?;
+    assert_eq!(paths(&out.removed), expected);
+    assert_eq!(
+        paths(&out.skipped_repositories),
+        vec![untracked("nested-repo", Kind::Repository)]
+    );
+    assert!(
+        tmp.path().join("untracked").is_file(),
+        "nothing is removed in a dry-run"
+    );
+
+    let out = clean(tmp.path(), Default::default())?;
+    assert_eq!(paths(&out.removed), expected);
+    for (path, _, _) in expected {
+        assert!(tmp.path().join(path.to_string()).symlink_metadata().is_err());
+    }
+    assert!(tmp.path().join("untracked-dir").is_dir());
+    assert!(tmp.path().join("a.log").is_file());
+    assert!(tmp.path().join("tracked").is_file());
+    Ok(())
+}
+
+#[test]
+fn untracked_directories_are_removed_without_their_ignored_files() -> crate::Result {
+    let tmp = worktree()?;
+    let out = clean(
+        tmp.path(),
+        clean::Options {
+            directories: true,
+            ..Default::default()
+        },
+    )?;
+    assert_eq!(
+        paths(&out.removed),
+        vec![
+            untracked("link", link_kind(&tmp)),
+            untracked("tracked-dir/sub/untracked", Kind::File),
+            untracked("tracked-dir/untracked", Kind::File),
+            untracked("untracked", Kind::File),
+            untracked("untracked-dir/file", Kind::File),
+            untracked("untracked-dir/nested/file", Kind::File),
+        ],
+        "the directory keeps its ignored file, so it can't be removed"
+    );
+    assert!(tmp.path().join("untracked-dir/nested/file.log").is_file());
+    assert!(tmp.path().join("nested-repo").is_dir(), "repositories are kept");
+    Ok(())
+}
+
+#[test]
+fn ignored_files_can_be_removed_along_with_untracked_ones() -> crate::Result {
+    let tmp = worktree()?;
+    let out = clean(
+        tmp.path(),
+        clean::Options {
+            directories: true,
+            ignored: Ignored::Remove,
+            repositories: true,
+            ..Default::default()
+        },
+    )?;
+    assert_eq!(
+        paths(&out.removed),
+        vec![
+            ignored("a.log", Kind::File),
+            ignored("from-info-exclude", Kind::File),
+            ignored("ignored-dir", Kind::Directory),
+            untracked("link", link_kind(&tmp)),
+            untracked("nested-repo", Kind::Repository),
+            ignored("only-ignored/a.log", Kind::File),
+            ignored("only-ignored/nested/b.log", Kind::File),
+            ignored("tracked-dir/file.log", Kind::File),
+            ignored("tracked-dir/local-ignored", Kind::File),
+            untracked("tracked-dir/sub/untracked", Kind::File),
+            untracked("tracked-dir/untracked", Kind::File),
+            untracked("untracked", Kind::File),
+            untracked("untracked-dir", Kind::Directory),
+        ]
+    );
+    assert!(out.skipped_repositories.is_empty());
+    assert!(
+        !tmp.path().join("only-ignored").exists(),
+        "directories that become empty are removed"
+    );
+    assert!(!tmp.path().join("untracked-dir").exists());
+    assert!(tmp.path().join("empty-dir").is_dir(), "empty directories aren't seen");
+    assert!(tmp.path().join("tracked-dir/sub/file").is_file());
+    Ok(())
+}
+
+#[test]
+fn only_ignored_files_can_be_removed() -> crate::Result {
+    let tmp = worktree()?;
+    let out = clean(
+        tmp.path(),
+        clean::Options {
+            ignored: Ignored::RemoveOnly,
+            ..Default::default()
+        },
+    )?;
+    assert_eq!(
+        paths(&out.removed),
+        vec![
+            ignored("a.log", Kind::File),
+            ignored("from-info-exclude", Kind::File),
+            ignored("tracked-dir/file.log", Kind::File),
+            ignored("tracked-dir/local-ignored", Kind::File),
+        ],
+        "ignored directories and files in untracked directories need `directories` to be set"
+    );
+    assert!(tmp.path().join("untracked").is_file());
+    Ok(())
+}
@@ -0,0 +1,148 @@
+use git_object::bstr::ByteSlice;
+use git_worktree::filter::{Attributes, Driver, Error, Operation, Pipeline};
+
+use super::path;
+
+fn pipeline(driver: Driver) -> Pipeline {
+    Pipeline::new(vec![driver], Default::default())
+}
+
+fn attributes() -> Attributes {
+    Attributes {
+        filter: Some("driver".into()),
+        ..Default::default()
+    }
+}
+
+fn process_command() -> String {
+    format!(
+        "perl {}",
+        git_testtools::fixture_path("filter_process.pl")
+            .canonicalize()
+            .expect("exists")
+            .display()
+    )
+}
+
+fn id() -> git_hash::ObjectId {
+    git_hash::ObjectId::null(git_hash::Kind::Sha1)
+}
+
+#[test]
+fn clean_and_smudge_commands_receive_the_quoted_path() -> crate::Result {
+    let mut pipeline = pipeline(Driver {
+        name: "driver".into(),
+        smudge: Some("cat && echo %f".into()),
+        clean: Some("tr a-z A-Z".into()),
+        ..Default::default()
+    });
+    let mut out = Vec::new();
+    assert!(pipeline.convert_to_worktree(b"content\n", "it's a file".into(), &attributes(), &id(), &mut out)?);
+    assert_eq!(out.as_bstr(), "content\nit's a file\n");
+
+    assert!(pipeline.convert_to_git(b"content", path(), &attributes(), &mut out)?);
+    assert_eq!(out.as_bstr(), "CONTENT");
+    Ok(())
+}
+
+#[test]
+fn failing_or_missing_commands_pass_content_through_unless_required() -> crate::Result {
+    let mut driver = Driver {
+        name: "driver".into(),
+        smudge: Some("exit 1".into()),
+        ..Default::default()
+    };
+    let mut out = Vec::new();
+    assert!(!pipeline(driver.clone()).convert_to_worktree(b"content", path(), &attributes(), &id(), &mut out)?);
+    assert!(!pipeline(driver.clone()).convert_to_git(b"content", path(), &attributes(), &mut out)?);
+    assert!(
+        !Pipeline::default().convert_to_git(b"content", path(), &attributes(), &mut out)?,
+        "unknown drivers are ignored"
+    );
+
+    driver.required = true;
+    assert!(matches!(
+        pipeline(driver.clone()).convert_to_worktree(b"content", path(), &attributes(), &id(), &mut out),
+        Err(Error::Driver {
+            operation: Operation::Smudge,
+            ..
+        })
+    ));
+    assert!(matches!(
+        pipeline(driver).convert_to_git(b"content", path(), &attributes(), &mut out),
+        Err(Error::MissingCommand {
+            operation: Operation::Clean,
+            ..
+        })
+    ));
+    Ok(())
+}
+
+#[test]
+fn long_running_process_is_reused_and_preferred_over_commands() -> crate::Result {
+    let mut pipeline = pipeline(Driver {
+        name: "driver".into(),
+        smudge: Some("exit 1".into()),
+        process: Some(process_command().into()),
+        required: true,
+        ..Default::default()
+    });
+    let mut out = Vec::new();
+    for _ in 0..2 {
+        assert!(pipeline.convert_to_worktree(b"content", path(), &attributes(), &id(), &mut out)?);
+        assert_eq!(out.as_bstr(), "CONTENT");
+        assert!(pipeline.convert_to_git(b"CONTENT", path(), &attributes(), &mut out)?);
+        assert_eq!(out.as_bstr(), "content");
+    }
+
+    let large = vec![b'a'; 200_000];
+    assert!(pipeline.convert_to_worktree(&large, path(), &attributes(), &id(), &mut out)?);
+    assert_eq!(out, vec![b'A'; 200_000], "content is split into multiple packets");
+
+    assert!(pipeline.convert_to_worktree(b"", path(), &attributes(), &id(), &mut out)?);
+    assert!(out.is_empty());
+
+    assert!(
+        matches!(
+            pipeline.convert_to_worktree(b"content", "fail".into(), &attributes(), &id(), &mut out),
+            Err(Error::Driver { .. })
+        ),
+        "errors are reported per file if the driver is required"
+    );
+    assert!(pipeline.convert_to_worktree(b"content", path(), &attributes(), &id(), &mut out)?);
+    assert_eq!(out.as_bstr(), "CONTENT", "the process keeps working after errors");
+    Ok(())
+}
+
+#[test]
+fn aborted_process_commands_pass_content_through_unless_required() -> crate::Result {
+    let mut pipeline = pipeline(Driver {
+        name: "driver".into(),
+        process: Some(process_command().into()),
+        ..Default::default()
+    });
+    let mut out = Vec::new();
+    assert!(!pipeline.convert_to_worktree(b"content", "abort".into(), &attributes(), &id(), &mut out)?);
+    assert!(
+        !pipeline.convert_to_worktree(b"content", path(), &attributes(), &id(), &mut out)?,
+        "smudging was aborted for all files"
+    );
+    assert!(pipeline.convert_to_git(b"CONTENT", path(), &attributes(), &mut out)?);
+    assert_eq!(out.as_bstr(), "content", "but cleaning still works");
+    Ok(())
+}
+
+#[test]
+fn processes_that_cannot_start_fail_if_required() {
+    let mut pipeline = pipeline(Driver {
+        name: "driver".into(),
+        process: Some("exit 0".into()),
+        required: true,
+        ..Default::default()
+    });
+    let mut out = Vec::new();
+    assert!(matches!(
+        pipeline.convert_to_worktree(b"content", path(), &attributes(), &id(), &mut out),
+        Err(Error::Driver { .. })
+    ));
+}
@@ -0,0 +1,131 @@
+use git_object::bstr::ByteSlice;
+use git_worktree::filter::encoding::{decode, encode, Encoding, Error};
+
+#[test]
+fn from_name() {
+    for (name, expected) in [
+        ("utf-8", Encoding::Utf8),
+        ("UTF16", Encoding::Utf16),
+        ("utf-16le", Encoding::Utf16Le),
+        ("UTF-16BE", Encoding::Utf16Be),
+        ("UTF-32", Encoding::Utf32),
+        ("utf-32le", Encoding::Utf32Le),
+        ("UTF32BE", Encoding::Utf32Be),
+        ("latin1", Encoding::Latin1),
+        ("ISO-8859-1", Encoding::Latin1),
+    ] {
+        assert_eq!(Encoding::from_name(name.into()), Some(expected), "{}", name);
+    }
+    assert_eq!(Encoding::from_name("SHIFT-JIS".into()), None);
+}
+
+#[test]
+fn round_trips() -> crate::Result {
+    let input = "a\n€ \u{1f600}";
+    for (encoding, expected) in [
+        (
+            Encoding::Utf16,
+            &[
+                0xff, 0xfe, b'a', 0, b'\n', 0, 0xac, 0x20, b' ', 0, 0x3d, 0xd8, 0x00, 0xde,
+            ][..],
+        ),
+        (
+            Encoding::Utf16Be,
+            &[0, b'a', 0, b'\n', 0x20, 0xac, 0, b' ', 0xd8, 0x3d, 0xde, 0x00],
+        ),
+        (
+            Encoding::Utf32Le,
+            &[
+                b'a', 0, 0, 0, b'\n', 0, 0, 0, 0xac, 0x20, 0, 0, b' ', 0, 0, 0, 0x00, 0xf6, 0x01, 0,
+            ],
+        ),
+    ] {
+        let mut out = Vec::new();
+        assert!(encode(input.as_bytes(), encoding, &mut out)?);
+        assert_eq!(out, expected, "{:?}", encoding);
+
+        let mut back = Vec::new();
+        assert!(decode(&out, encoding, &mut back)?);
+        assert_eq!(back.as_bstr(), input, "{:?}", encoding);
+    }
+
+    for encoding in [Encoding::Utf16Le, Encoding::Utf32, Encoding::Utf32Be] {
+        let mut out = Vec::new();
+        let mut back = Vec::new();
+        assert!(encode(input.as_bytes(), encoding, &mut out)?);
+        assert!(decode(&out, encoding, &mut back)?);
+        assert_eq!(back.as_bstr(), input, "{:?}", encoding);
+    }
+    Ok(())
+}
+
+#[test]
+fn latin1() -> crate::Result {
+    let mut out = Vec::new();
+    assert!(encode("äb".as_bytes(), Encoding::Latin1, &mut out)?);
+    assert_eq!(out, [0xe4, b'b']);
+    let mut back = Vec::new();
+    assert!(decode(&out, Encoding::Latin1, &mut back)?);
+    assert_eq!(back.as_bstr(), "äb");
+
+    assert!(matches!(
+        encode("€".as_bytes(), Encoding::Latin1, &mut out),
+        Err(Error::Unrepresentable { character: '€' })
+    ));
+    Ok(())
+}
+
+#[test]
+fn nothing_to_do_for_utf8_or_empty_input() -> crate::Result {
+    let mut out = Vec::new();
+    assert!(!encode(b"a", Encoding::Utf8, &mut out)?);
+    assert!(!decode(b"a", Encoding::Utf8, &mut out)?);
+    assert!(!encode(b"", Encoding::Utf16, &mut out)?);
+    assert!(!decode(b"", Encoding::Utf16, &mut out)?);
+    Ok(())
+}
+
+#[test]
+fn byte_order_marks_are_required_or_prohibited_depending_on_the_encoding() {
+    let mut out = Vec::new();
+    assert!(matches!(
+        decode(&[b'a', 0], Encoding::Utf16, &mut out),
+        Err(Error::MissingByteOrderMark)
+    ));
+    assert!(matches!(
+        decode(&[b'a', 0, 0, 0], Encoding::Utf32, &mut out),
+        Err(Error::MissingByteOrderMark)
+    ));
+    assert!(matches!(
+        decode(&[0xff, 0xfe, b'a', 0], Encoding::Utf16Le, &mut out),
+        Err(Error::ProhibitedByteOrderMark)
+    ));
+    assert!(matches!(
+        decode(&[0, 0, 0xfe, 0xff, 0, 0, 0, b'a'], Encoding::Utf32Be, &mut out),
+        Err(Error::ProhibitedByteOrderMark)
+    ));
+}
+
+#[test]
+fn invalid_input() {
+    let mut out = Vec::new();
+    assert!(matches!(
+        encode(b"\xff", Encoding::Utf16, &mut out),
+        Err(Error::InvalidUtf8(_))
+    ));
+    assert!(matches!(
+        decode(&[0xff, 0xfe, b'a'], Encoding::Utf16, &mut out),
+        Err(Error::Invalid { position: 2 })
+    ));
+    assert!(
+        matches!(
+            decode(&[b'a', 0, 0x3d, 0xd8], Encoding::Utf16Le, &mut out),
+            Err(Error::Invalid { position: 2 })
+        ),
+        "unpaired surrogate"
+    );
+    assert!(matches!(
+        decode(&[0, 0, 0x11, 0], Encoding::Utf32Le, &mut out),
+        Err(Error::Invalid { position: 0 })
+    ));
+}
@@ -0,0 +1,120 @@
+use git_object::bstr::ByteSlice;
+use git_worktree::filter::{eol, Eol, Text};
+
+#[test]
+fn stats() {
+    let stats = eol::Stats::from_bytes(b"a\r\nb\nc\rd\0\x1a");
+    assert_eq!(
+        stats,
+        eol::Stats {
+            null: 1,
+            lone_cr: 1,
+            lone_lf: 1,
+            crlf: 1,
+            printable: 4,
+            non_printable: 1
+        },
+        "a trailing end-of-file marker isn't counted"
+    );
+    assert!(stats.is_binary());
+    assert!(!eol::Stats::from_bytes(b"a\r\nb\n\t").is_binary());
+}
+
+#[test]
+fn to_worktree() {
+    let mut out = Vec::new();
+    assert!(eol::convert_to_worktree(
+        b"a\nb\r\n\n",
+        Text::Set,
+        false,
+        Eol::Crlf,
+        &mut out
+    ));
+    assert_eq!(out.as_bstr(), "a\r\nb\r\n\r\n", "existing CRLF remain untouched");
+
+    for text in [Text::Unset, Text::Unspecified] {
+        assert!(
+            !eol::convert_to_worktree(b"a\n", text, false, Eol::Crlf, &mut out),
+            "no text, no conversion"
+        );
+    }
+    assert!(
+        eol::convert_to_worktree(b"a\n", Text::Unspecified, true, Eol::Crlf, &mut Vec::new()),
+        "the eol attribute makes it text"
+    );
+    assert!(!eol::convert_to_worktree(b"a\n", Text::Set, false, Eol::Lf, &mut out));
+}
+
+#[test]
+fn to_worktree_with_auto_only_converts_text_without_carriage_returns() {
+    let mut out = Vec::new();
+    assert!(eol::convert_to_worktree(
+        b"a\nb\n",
+        Text::Auto,
+        false,
+        Eol::Crlf,
+        &mut out
+    ));
+    assert_eq!(out.as_bstr(), "a\r\nb\r\n");
+    assert!(!eol::convert_to_worktree(
+        b"a\nb\r\n",
+        Text::Auto,
+        false,
+        Eol::Crlf,
+        &mut out
+    ));
+    assert!(!eol::convert_to_worktree(
+        b"a\nb\0",
+        Text::Auto,
+        false,
+        Eol::Crlf,
+        &mut out
+    ));
+}
+
+#[test]
+fn to_git() {
+    let mut out = Vec::new();
+    assert!(eol::convert_to_git(b"a\r\nb\r\r\nc\r", Text::Set, false, &mut out));
+    assert_eq!(out.as_bstr(), "a\nb\r\nc\r", "lone carriage returns are kept");
+
+    assert!(!eol::convert_to_git(b"a\r\n", Text::Unset, true, &mut out));
+    assert!(
+        !eol::convert_to_git(b"a\n", Text::Set, false, &mut out),
+        "nothing to do"
+    );
+    assert!(
+        !eol::convert_to_git(b"a\r\n\0", Text::Auto, false, &mut out),
+        "binary files are left alone"
+    );
+}
+
+#[test]
+fn round_trip_check() {
+    use eol::Irreversible;
+    assert_eq!(
+        eol::check_round_trip(b"a\r\n", Text::Set, false, Eol::Lf),
+        Some(Irreversible::CrlfToLf)
+    );
+    assert_eq!(
+        eol::check_round_trip(b"a\n", Text::Set, false, Eol::Crlf),
+        Some(Irreversible::LfToCrlf)
+    );
+    assert_eq!(
+        eol::check_round_trip(b"a\r\nb\n", Text::Set, false, Eol::Crlf),
+        Some(Irreversible::LfToCrlf),
+        "mixed line endings are all CRLF after the round-trip"
+    );
+    assert_eq!(eol::check_round_trip(b"a\r\n", Text::Set, false, Eol::Crlf), None);
+    assert_eq!(eol::check_round_trip(b"a\n", Text::Auto, false, Eol::Lf), None);
+    assert_eq!(
+        eol::check_round_trip(b"a\r\n\0", Text::Auto, false, Eol::Lf),
+        None,
+        "binary files aren't converted"
+    );
+    assert_eq!(eol::check_round_trip(b"a\r\n", Text::Unspecified, false, Eol::Lf), None);
+    assert_eq!(
+        eol::check_round_trip(b"a\r\n", Text::Unspecified, true, Eol::Lf),
+        Some(Irreversible::CrlfToLf)
+    );
+}
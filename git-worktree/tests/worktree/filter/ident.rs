@@ -0,0 +1,18 @@
+use git_object::bstr::ByteSlice;
+use git_worktree::filter::ident;
+
+#[test]
+fn apply_and_undo() {
+    let id = git_hash::ObjectId::from_hex(b"e69de29bb2d1d6434b8b29ae775ad8c2e48c5391").expect("valid");
+    let mut out = Vec::new();
+    assert!(ident::apply(b"$Id$ $Id: old $ $Id: no\n$ $I $Id", &id, &mut out));
+    let expanded = format!("$Id: {id} $ $Id: {id} $ $Id: no\n$ $I $Id", id = id);
+    assert_eq!(out.as_bstr(), expanded.as_bytes().as_bstr());
+
+    let mut back = Vec::new();
+    assert!(ident::undo(&out, &mut back));
+    assert_eq!(back.as_bstr(), "$Id$ $Id$ $Id: no\n$ $I $Id");
+
+    assert!(!ident::apply(b"no keyword", &id, &mut out));
+    assert!(!ident::undo(b"no keyword", &mut out));
+}
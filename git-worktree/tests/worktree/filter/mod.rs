@@ -0,0 +1,253 @@
+use git_object::bstr::{BStr, ByteSlice};
+use git_worktree::filter::{Attributes, AutoCrlf, Driver, Eol, Options, Pipeline, SafeCrlf, Text};
+
+mod driver;
+mod encoding;
+mod eol;
+mod ident;
+
+#[test]
+fn attributes_from_assignments() {
+    let assignments = |line: &str| -> Vec<git_attributes::Assignment> {
+        git_attributes::parse::Iter::new(line.into())
+            .map(|assignment| assignment.expect("valid").to_owned())
+            .collect()
+    };
+    assert_eq!(
+        Attributes::from_assignments(&assignments(
+            "text eol=crlf ident filter=lfs working-tree-encoding=UTF-16"
+        )),
+        Attributes {
+            text: Text::Set,
+            eol: Some(Eol::Crlf),
+            ident: true,
+            filter: Some("lfs".into()),
+            working_tree_encoding: Some("UTF-16".into()),
+        }
+    );
+    assert_eq!(
+        Attributes::from_assignments(&assignments("text=auto -ident")),
+        Attributes {
+            text: Text::Auto,
+            ..Default::default()
+        }
+    );
+    assert_eq!(
+        Attributes::from_assignments(&assignments("crlf=input")),
+        Attributes {
+            text: Text::Set,
+            eol: Some(Eol::Lf),
+            ..Default::default()
+        },
+        "the legacy crlf attribute is used if text isn't mentioned"
+    );
+    assert_eq!(
+        Attributes::from_assignments(&assignments("-text crlf")),
+        Attributes {
+            text: Text::Unset,
+            ..Default::default()
+        },
+        "text has precedence over crlf"
+    );
+    assert_eq!(Attributes::from_assignments(&[]), Attributes::default());
+}
+
+#[test]
+fn conversions_are_applied_in_order_and_undone_in_reverse() -> crate::Result {
+    let mut pipeline = Pipeline::new(
+        vec![Driver {
+            name: "double".into(),
+            smudge: Some("sed 's/x/xx/'".into()),
+            clean: Some("sed 's/xx/x/'".into()),
+            ..Default::default()
+        }],
+        Default::default(),
+    );
+    let attributes = Attributes {
+        text: Text::Set,
+        eol: Some(Eol::Crlf),
+        ident: true,
+        filter: Some("double".into()),
+        working_tree_encoding: None,
+    };
+    let id = git_hash::ObjectId::null(git_hash::Kind::Sha1);
+    let mut out = Vec::new();
+    let input = b"x $Id$\nb\n";
+    assert!(pipeline.convert_to_worktree(input, path(), &attributes, &id, &mut out)?);
+    assert_eq!(out.as_bstr(), format!("xx $Id: {} $\r\nb\r\n", id).as_bytes().as_bstr());
+
+    let mut back = Vec::new();
+    assert!(pipeline.convert_to_git(&out, path(), &attributes, &mut back)?);
+    assert_eq!(back.as_bstr(), input.as_bstr());
+    Ok(())
+}
+
+#[test]
+fn nothing_is_converted_without_attributes() -> crate::Result {
+    let mut pipeline = Pipeline::default();
+    let mut out = Vec::new();
+    let id = git_hash::ObjectId::null(git_hash::Kind::Sha1);
+    assert!(!pipeline.convert_to_worktree(b"$Id$\n", path(), &Default::default(), &id, &mut out)?);
+    assert!(!pipeline.convert_to_git(b"$Id: foo $\r\n", path(), &Default::default(), &mut out)?);
+    Ok(())
+}
+
+#[test]
+fn autocrlf_applies_to_paths_without_text_attribute() {
+    let options = |auto_crlf| Options {
+        eol: Eol::Lf,
+        auto_crlf,
+        ..Default::default()
+    };
+    let unspecified = Attributes::default();
+    assert_eq!(
+        options(AutoCrlf::Disabled).text_and_eol(&unspecified),
+        (Text::Unspecified, Eol::Lf)
+    );
+    assert_eq!(
+        options(AutoCrlf::Enabled).text_and_eol(&unspecified),
+        (Text::Auto, Eol::Crlf)
+    );
+    assert_eq!(
+        options(AutoCrlf::Input).text_and_eol(&unspecified),
+        (Text::Auto, Eol::Lf)
+    );
+
+    let text = Attributes {
+        text: Text::Set,
+        ..Default::default()
+    };
+    assert_eq!(
+        options(AutoCrlf::Enabled).text_and_eol(&text),
+        (Text::Set, Eol::Crlf),
+        "core.autocrlf overrides core.eol"
+    );
+    assert_eq!(
+        Options {
+            eol: Eol::Crlf,
+            ..Default::default()
+        }
+        .text_and_eol(&text),
+        (Text::Set, Eol::Crlf),
+        "core.eol is used without core.autocrlf"
+    );
+
+    let binary = Attributes {
+        text: Text::Unset,
+        ..Default::default()
+    };
+    assert_eq!(
+        options(AutoCrlf::Enabled).text_and_eol(&binary).0,
+        Text::Unset,
+        "binary files stay binary"
+    );
+    let eol = Attributes {
+        eol: Some(Eol::Lf),
+        ..Default::default()
+    };
+    assert_eq!(
+        options(AutoCrlf::Enabled).text_and_eol(&eol),
+        (Text::Set, Eol::Lf),
+        "the eol attribute implies text and has precedence"
+    );
+}
+
+#[test]
+fn autocrlf_converts_line_endings_in_both_directions() -> crate::Result {
+    let mut pipeline = Pipeline::new(
+        Vec::new(),
+        Options {
+            auto_crlf: AutoCrlf::Enabled,
+            ..Default::default()
+        },
+    );
+    let id = git_hash::ObjectId::null(git_hash::Kind::Sha1);
+    let mut out = Vec::new();
+    assert!(pipeline.convert_to_worktree(b"a\nb\n", path(), &Default::default(), &id, &mut out)?);
+    assert_eq!(out.as_bstr(), "a\r\nb\r\n");
+
+    let mut back = Vec::new();
+    assert!(pipeline.convert_to_git(&out, path(), &Default::default(), &mut back)?);
+    assert_eq!(back.as_bstr(), "a\nb\n");
+    assert!(pipeline.take_eol_warnings().is_empty(), "the round-trip is lossless");
+
+    assert!(
+        !pipeline.convert_to_worktree(b"a\0\n", path(), &Default::default(), &id, &mut out)?,
+        "binary content is left alone"
+    );
+    Ok(())
+}
+
+#[test]
+fn safecrlf_warns_or_fails_on_irreversible_conversions() -> crate::Result {
+    let input_only = |safe_crlf| {
+        Pipeline::new(
+            Vec::new(),
+            Options {
+                auto_crlf: AutoCrlf::Input,
+                safe_crlf,
+                ..Default::default()
+            },
+        )
+    };
+    let mut out = Vec::new();
+    let mut pipeline = input_only(SafeCrlf::Warn);
+    assert!(pipeline.convert_to_git(b"a\r\n", path(), &Default::default(), &mut out)?);
+    assert_eq!(out.as_bstr(), "a\n", "it converts anyway");
+    assert_eq!(
+        pipeline.take_eol_warnings(),
+        vec![(path().to_owned(), git_worktree::filter::eol::Irreversible::CrlfToLf)]
+    );
+    assert!(pipeline.take_eol_warnings().is_empty(), "warnings are taken");
+
+    let err = input_only(SafeCrlf::Fail)
+        .convert_to_git(b"a\r\n", path(), &Default::default(), &mut out)
+        .unwrap_err();
+    assert_eq!(err.to_string(), "CRLF would be replaced by LF in 'dir/file'");
+
+    let mut pipeline = input_only(SafeCrlf::Disabled);
+    assert!(pipeline.convert_to_git(b"a\r\n", path(), &Default::default(), &mut out)?);
+    assert!(pipeline.take_eol_warnings().is_empty());
+    Ok(())
+}
+
+#[test]
+fn working_tree_encoding_is_applied_in_both_directions() -> crate::Result {
+    let mut pipeline = Pipeline::default();
+    let attributes = Attributes {
+        working_tree_encoding: Some("utf-16".into()),
+        ..Default::default()
+    };
+    let id = git_hash::ObjectId::null(git_hash::Kind::Sha1);
+    let mut out = Vec::new();
+    assert!(pipeline.convert_to_worktree("ä".as_bytes(), path(), &attributes, &id, &mut out)?);
+    assert_eq!(out, [0xff, 0xfe, 0xe4, 0]);
+    let mut back = Vec::new();
+    assert!(pipeline.convert_to_git(&out, path(), &attributes, &mut back)?);
+    assert_eq!(back.as_bstr(), "ä");
+
+    let err = pipeline
+        .convert_to_git(&[0xe4, 0], path(), &attributes, &mut back)
+        .unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "Could not convert 'dir/file' with working-tree-encoding 'utf-16'"
+    );
+
+    let unknown = Attributes {
+        working_tree_encoding: Some("EBCDIC".into()),
+        ..Default::default()
+    };
+    let err = pipeline
+        .convert_to_worktree(b"a", path(), &unknown, &id, &mut out)
+        .unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "The working-tree-encoding 'EBCDIC' of 'dir/file' isn't supported"
+    );
+    Ok(())
+}
+
+fn path() -> &'static BStr {
+    "dir/file".into()
+}
@@ -160,3 +160,36 @@ fn check_against_baseline() -> crate::Result {
     assert_eq!(m.pattern.text, "user-file-anywhere");
     Ok(())
 }
+
+#[test]
+fn per_directory_exclude_files_use_the_configured_name() -> crate::Result {
+    let dir = tempdir()?;
+    std::fs::create_dir(dir.path().join("sub"))?;
+    std::fs::write(dir.path().join("sub").join(".customignore"), "custom\n")?;
+    std::fs::write(dir.path().join("sub").join(".gitignore"), "default\n")?;
+
+    let case = Case::Sensitive;
+    let state = fs::cache::State::for_status(fs::cache::state::Ignore::new(
+        Default::default(),
+        Default::default(),
+        Some(".customignore".into()),
+        case,
+    ));
+    let mut cache = fs::Cache::new(dir.path(), state, case, Vec::new(), Default::default());
+    assert_eq!(cache.exclude_file_name_for_directories(), ".customignore");
+
+    fn no_blobs<'a>(_oid: &git_hash::oid, _buf: &'a mut Vec<u8>) -> Result<git_object::BlobRef<'a>, std::io::Error> {
+        unreachable!("there is no index to read from")
+    }
+    let platform = cache.at_entry("sub/custom", Some(false), no_blobs)?;
+    let m = platform.matching_exclude_pattern().expect("matched by the custom file");
+    assert_eq!(m.pattern.to_string(), "custom");
+    assert_eq!(m.sequence_number, 1);
+    assert_eq!(m.source, Some(dir.path().join("sub").join(".customignore").as_path()));
+
+    assert!(
+        !cache.at_entry("sub/default", Some(false), no_blobs)?.is_excluded(),
+        ".gitignore files aren't read if another name is configured"
+    );
+    Ok(())
+}
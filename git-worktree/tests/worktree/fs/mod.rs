@@ -18,5 +18,48 @@ fn from_probing_cwd() {
     );
 }
 
+#[test]
+fn probe_cached_stores_results_and_uses_them_next_time() -> crate::Result {
+    use git_worktree::fs::Capabilities;
+    let dir = tempfile::tempdir()?;
+    std::fs::File::create(dir.path().join("config"))?;
+    assert_eq!(Capabilities::load(dir.path()), None, "nothing stored yet");
+
+    let probed = Capabilities::probe_cached(dir.path());
+    assert_eq!(probed, Capabilities::probe(dir.path()));
+    assert_eq!(
+        Capabilities::load(dir.path()),
+        Some(probed),
+        "the probe results were stored"
+    );
+    let entries: Vec<_> = std::fs::read_dir(dir.path())?
+        .map(|entry| entry.map(|entry| entry.file_name()))
+        .collect::<Result<_, _>>()?;
+    assert_eq!(entries.len(), 2, "only the cache file was added: {:?}", entries);
+
+    let flipped = Capabilities {
+        symlink: !probed.symlink,
+        executable_bit: !probed.executable_bit,
+        ..probed
+    };
+    flipped.store(dir.path())?;
+    assert_eq!(
+        Capabilities::probe_cached(dir.path()),
+        flipped,
+        "stored values are used without probing"
+    );
+
+    let cache_file = dir.path().join(Capabilities::CACHE_FILE_NAME);
+    std::fs::write(&cache_file, "symlink=maybe\n")?;
+    assert_eq!(Capabilities::load(dir.path()), None, "invalid values are ignored");
+    assert_eq!(
+        Capabilities::probe_cached(dir.path()),
+        probed,
+        "which causes probing and storing again"
+    );
+    assert_eq!(Capabilities::load(dir.path()), Some(probed));
+    Ok(())
+}
+
 mod cache;
 mod stack;
@@ -0,0 +1,112 @@
+use bstr::BString;
+use git_worktree::fs_monitor::{hook, Changes, Hook, Monitor, Response};
+
+fn hook_in_tmp_dir(script: &str, version: hook::Version) -> crate::Result<(tempfile::TempDir, Hook)> {
+    let dir = tempfile::tempdir()?;
+    let path = dir.path().join("hook");
+    std::fs::write(&path, format!("#!/bin/sh\n{}\n", script))?;
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o755))?;
+    }
+    let hook = Hook::new(git_path::into_bstr(path).into_owned(), dir.path().to_owned(), version);
+    Ok((dir, hook))
+}
+
+fn paths(paths: &[&str]) -> Changes {
+    Changes::Paths(paths.iter().map(|path| BString::from(*path)).collect())
+}
+
+#[test]
+fn hook_v2_receives_the_token_and_returns_a_new_one_along_with_changed_paths() -> crate::Result {
+    let (_dir, mut hook) = hook_in_tmp_dir(
+        r#"test "$1" = 2 || exit 1; printf "token-of-$2\0a\0dir/b\0dir/\0""#,
+        hook::Version::V2,
+    )?;
+    assert_eq!(
+        hook.query_changes(Some("previous".into()))?,
+        Response {
+            token: "token-of-previous".into(),
+            changes: paths(&["a", "dir/b", "dir/"]),
+        }
+    );
+
+    let res = hook.query_changes(None)?;
+    assert_eq!(
+        res,
+        Response {
+            token: "token-of-builtin:fake".into(),
+            changes: Changes::All,
+        },
+        "without token, everything has to be considered changed but we get a token for next time"
+    );
+    Ok(())
+}
+
+#[test]
+fn hook_v2_can_indicate_that_everything_changed() -> crate::Result {
+    let (_dir, mut hook) = hook_in_tmp_dir(r#"printf "new\0/\0""#, hook::Version::V2)?;
+    assert_eq!(hook.query_changes(Some("unknown".into()))?.changes, Changes::All);
+    Ok(())
+}
+
+#[test]
+fn hook_v2_without_token_is_an_error() -> crate::Result {
+    let (_dir, mut hook) = hook_in_tmp_dir("printf no-token", hook::Version::V2)?;
+    assert!(matches!(
+        hook.query_changes(Some("token".into())),
+        Err(hook::Error::MissingToken { .. })
+    ));
+    Ok(())
+}
+
+#[test]
+fn hook_v1_receives_a_timestamp_and_returns_changed_paths() -> crate::Result {
+    let (_dir, mut hook) = hook_in_tmp_dir(
+        r#"test "$1" = 1 && test "$2" = 42 || exit 1; printf "a\0b\0""#,
+        hook::Version::V1,
+    )?;
+    let res = hook.query_changes(Some("42".into()))?;
+    assert_eq!(res.changes, paths(&["a", "b"]));
+    let now = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)?
+        .as_nanos();
+    let token: u128 = res.token.to_string().parse()?;
+    assert!(
+        token <= now && token > 42,
+        "the token is the time at which the query was made"
+    );
+
+    let res = hook.query_changes(None)?;
+    assert_eq!(
+        res.changes,
+        Changes::All,
+        "the hook isn't run without a timestamp, as everything changed"
+    );
+    Ok(())
+}
+
+#[test]
+fn failing_hooks_are_reported() -> crate::Result {
+    let (_dir, mut hook) = hook_in_tmp_dir("exit 1", hook::Version::V1)?;
+    assert!(matches!(
+        hook.query_changes(Some("0".into())),
+        Err(hook::Error::Failed { .. })
+    ));
+    Ok(())
+}
+
+#[test]
+fn changes_contain_paths_and_everything_in_directories() {
+    let changes = paths(&["a", "dir/"]);
+    assert!(changes.contains("a".into()));
+    assert!(changes.contains("dir/b".into()));
+    assert!(!changes.contains("ab".into()));
+    assert!(
+        changes.contains("a/b".into()),
+        "paths without trailing slash may denote directories as well"
+    );
+    assert!(!changes.contains("dirb".into()));
+    assert!(Changes::All.contains("anything".into()));
+}
@@ -10,7 +10,11 @@ use std::{
 use git_features::progress;
 use git_object::bstr::ByteSlice;
 use git_odb::FindExt;
-use git_worktree::{fs::Capabilities, index, index::checkout::Collision};
+use git_worktree::{
+    fs::Capabilities,
+    index,
+    index::checkout::{Collision, InvalidPath, PathViolation},
+};
 use tempfile::TempDir;
 
 use crate::fixture_path;
@@ -158,6 +162,93 @@ fn symlinks_become_files_if_disabled() -> crate::Result {
     Ok(())
 }
 
+#[test]
+fn submodules_become_empty_directories_and_skipped_entries_are_not_written() -> crate::Result {
+    let opts = opts_from_probe();
+    let (_source_tree, destination, _index, outcome) = checkout_index_in_tmp_dir_opts(
+        opts.clone(),
+        "make_mixed_with_submodule_and_skip_worktree",
+        |_| true,
+        |_| Ok(()),
+    )?;
+
+    assert_eq!(outcome.files_updated, 3, "the skipped file isn't counted");
+    assert!(outcome.collisions.is_empty());
+    assert_eq!(fs::read(destination.path().join("file"))?, b"content");
+    assert_eq!(fs::read(destination.path().join("dir/content"))?, b"other content");
+    assert!(!destination.path().join("dir/skipped").exists());
+    let submodule = destination.path().join("dir/sub");
+    assert!(submodule.is_dir());
+    assert_eq!(
+        fs::read_dir(&submodule)?.count(),
+        0,
+        "submodules are checked out separately"
+    );
+    fs::write(submodule.join("from-submodule"), b"")?;
+
+    let mut opts = opts;
+    opts.destination_is_initially_empty = false;
+    opts.overwrite_existing = true;
+    let source_tree = fixture_path("make_mixed_with_submodule_and_skip_worktree");
+    let mut index = git_index::File::at(source_tree.join(".git/index"), git_hash::Kind::Sha1, Default::default())?;
+    let odb = git_odb::at(source_tree.join(".git/objects"))?.into_inner().into_arc()?;
+    let outcome = index::checkout(
+        &mut index,
+        destination.path(),
+        move |oid, buf| odb.find_blob(oid, buf),
+        &mut progress::Discard,
+        &mut progress::Discard,
+        &AtomicBool::default(),
+        opts,
+    )?;
+    assert_eq!(outcome.files_updated, 3);
+    assert!(
+        submodule.join("from-submodule").is_file(),
+        "existing submodule directories are left alone even when overwriting"
+    );
+    Ok(())
+}
+
+#[test]
+fn attributes_from_the_index_configure_conversions_and_filters() -> crate::Result {
+    let mut opts = opts_from_probe();
+    opts.filters = git_worktree::filter::Pipeline::new(
+        vec![git_worktree::filter::Driver {
+            name: "upper".into(),
+            smudge: Some("tr a-z A-Z".into()),
+            ..Default::default()
+        }],
+        Default::default(),
+    );
+    let (_source_tree, destination, index, outcome) = checkout_index_in_tmp_dir(opts, "make_attributes_and_filters")?;
+    assert!(outcome.collisions.is_empty() && outcome.errors.is_empty());
+
+    let dest = destination.path();
+    assert_eq!(fs::read(dest.join("file.txt"))?, b"a\r\nb\r\n");
+    assert_eq!(
+        fs::read(dest.join("sub/file.txt"))?,
+        b"a\nb\n",
+        "attributes further down the tree take precedence"
+    );
+    assert_eq!(fs::read(dest.join("file.bin"))?, b"a\nb\n");
+    assert_eq!(fs::read(dest.join("file.up"))?, b"LOWER\n");
+    let ident = index
+        .entries()
+        .iter()
+        .find(|entry| entry.path(&index) == "ident.c")
+        .expect("present");
+    assert_eq!(
+        fs::read(dest.join("ident.c"))?.as_bstr(),
+        format!("$Id: {} $\n", ident.id.to_hex()).as_bytes().as_bstr()
+    );
+    assert_eq!(
+        ident.stat.size,
+        5 + 40 + 3,
+        "the metadata of the index entry is the one of the converted file"
+    );
+    Ok(())
+}
+
 #[test]
 fn allow_or_disallow_symlinks() -> crate::Result {
     let mut opts = opts_from_probe();
@@ -321,6 +412,154 @@ fn collisions_are_detected_on_a_case_insensitive_filesystem() {
     }
 }
 
+#[test]
+fn unsafe_paths_are_rejected_before_anything_is_written() -> crate::Result {
+    let destination = tempfile::tempdir()?;
+    let err = checkout_tree_in_dir(opts_from_probe(), "unsafe-tree", destination.path())
+        .err()
+        .expect("unsafe paths are rejected");
+    let mut expected = vec![
+        invalid(".GIT/file", PathViolation::DotGit),
+        invalid(".git./file", PathViolation::DotGit),
+        invalid("GIT~1/file", PathViolation::DotGit),
+        invalid("c:", PathViolation::DriveLetter),
+        invalid("file:stream", PathViolation::AlternateDataStream),
+        invalid("sub/../file", PathViolation::RelativeComponent),
+    ];
+    if cfg!(not(windows)) {
+        expected.retain(|p| {
+            !matches!(
+                p.violation,
+                PathViolation::DriveLetter | PathViolation::AlternateDataStream
+            )
+        });
+    }
+    match err {
+        index::checkout::Error::InvalidPaths { entries } => assert_eq!(entries, expected),
+        err => panic!("unexpected error: {err}"),
+    }
+    assert!(dir_structure(destination.path()).is_empty(), "nothing was checked out");
+    Ok(())
+}
+
+#[test]
+fn paths_that_are_only_unsafe_on_ntfs_are_rejected_by_default() -> crate::Result {
+    let destination = tempfile::tempdir()?;
+    let err = checkout_tree_in_dir(opts_from_probe(), "ntfs-only-tree", destination.path())
+        .err()
+        .expect("unsafe paths are rejected");
+    let expected_invalid = if cfg!(windows) {
+        vec![
+            invalid("file:stream", PathViolation::AlternateDataStream),
+            invalid("git~1/file", PathViolation::DotGit),
+        ]
+    } else {
+        vec![invalid("git~1/file", PathViolation::DotGit)]
+    };
+    assert!(
+        matches!(err, index::checkout::Error::InvalidPaths { entries } if entries == expected_invalid),
+        "'git~1/file' is rejected everywhere, 'file:stream' only on windows"
+    );
+
+    if cfg!(not(windows)) {
+        let mut opts = opts_from_probe();
+        opts.protect_ntfs = false;
+        let outcome = checkout_tree_in_dir(opts, "ntfs-only-tree", destination.path())?;
+        assert!(outcome.errors.is_empty());
+        assert_eq!(
+            stripped_prefix(destination.path(), &dir_structure(destination.path())),
+            paths(["file:stream", "git~1/file", "good"])
+        );
+    }
+    Ok(())
+}
+
+#[test]
+fn path_validation() {
+    for (path, protect_ntfs, expected) in [
+        ("a/b/c", true, Ok(())),
+        ("a/.gitignore", true, Ok(())),
+        ("a/..b/c.", false, Ok(())),
+        ("", false, Err(PathViolation::EmptyComponent)),
+        ("a//b", false, Err(PathViolation::EmptyComponent)),
+        ("a/", false, Err(PathViolation::EmptyComponent)),
+        ("/etc/passwd", false, Err(PathViolation::Absolute)),
+        ("a/./b", false, Err(PathViolation::RelativeComponent)),
+        ("../a", false, Err(PathViolation::RelativeComponent)),
+        ("a/.. /b", true, Err(PathViolation::RelativeComponent)),
+        (".git/config", false, Err(PathViolation::DotGit)),
+        ("a/.Git/hooks/post-checkout", false, Err(PathViolation::DotGit)),
+        (".git ./config", true, Err(PathViolation::DotGit)),
+        ("git~1/config", true, Err(PathViolation::DotGit)),
+    ] {
+        assert_eq!(
+            index::checkout::validate_path(path.into(), protect_ntfs),
+            expected,
+            "{path:?} with protect_ntfs = {protect_ntfs}"
+        );
+    }
+    for path in [".git ./config", "git~1/config", "C:/Windows", "a/b:c", "a\\..\\b"] {
+        assert_eq!(
+            index::checkout::validate_path(path.into(), false),
+            Ok(()),
+            "{path:?} is valid without NTFS protection"
+        );
+    }
+}
+
+#[test]
+fn path_validation_of_windows_specific_paths() {
+    for (path, windows_violation) in [
+        ("\\etc\\passwd", PathViolation::Absolute),
+        ("a\\..\\b", PathViolation::RelativeComponent),
+        ("C:/Windows", PathViolation::DriveLetter),
+        ("a/b:c", PathViolation::AlternateDataStream),
+        ("a:b", PathViolation::DriveLetter),
+        ("dir/a:b", PathViolation::AlternateDataStream),
+    ] {
+        let expected = if cfg!(windows) { Err(windows_violation) } else { Ok(()) };
+        assert_eq!(
+            index::checkout::validate_path(path.into(), true),
+            expected,
+            "{path:?} is only rejected on windows, even with NTFS protection"
+        );
+    }
+}
+
+fn invalid(path: &str, violation: PathViolation) -> InvalidPath {
+    InvalidPath {
+        path: path.into(),
+        violation,
+    }
+}
+
+fn checkout_tree_in_dir(
+    opts: index::checkout::Options,
+    tree_name: &str,
+    destination: &Path,
+) -> Result<
+    index::checkout::Outcome,
+    index::checkout::Error<git_odb::find::existing_object::Error<git_odb::store::find::Error>>,
+> {
+    let git_dir = fixture_path("make_unsafe_paths").join(".git");
+    let odb = git_odb::at(git_dir.join("objects"))
+        .unwrap()
+        .into_inner()
+        .into_arc()
+        .unwrap();
+    let tree = git_hash::ObjectId::from_hex(fs::read(git_dir.join(tree_name)).unwrap().trim_end()).unwrap();
+    let mut index = git_index::State::from_tree(&tree, |oid, buf| odb.find_tree_iter(oid, buf).ok()).unwrap();
+    index::checkout(
+        &mut index,
+        destination,
+        move |oid, buf| odb.find_blob(oid, buf),
+        &mut progress::Discard,
+        &mut progress::Discard,
+        &AtomicBool::default(),
+        opts,
+    )
+}
+
 fn multi_threaded() -> bool {
     git_features::parallel::num_threads(None) > 1
 }
@@ -424,7 +663,7 @@ fn probe_gitoxide_dir() -> crate::Result<Capabilities> {
     ))
 }
 
-fn opts_from_probe() -> index::checkout::Options {
+pub fn opts_from_probe() -> index::checkout::Options {
     index::checkout::Options {
         fs: probe_gitoxide_dir().unwrap(),
         destination_is_initially_empty: true,
@@ -1 +1,3 @@
-mod checkout;
+pub(crate) mod checkout;
+mod status;
+mod update;
@@ -0,0 +1,270 @@
+use std::sync::atomic::AtomicBool;
+
+use bstr::BStr;
+use git_worktree::{
+    fs_monitor::{self, Changes, Monitor, Response},
+    index,
+    index::status::{Change, Entry},
+};
+
+/// A monitor that always responds with the same changes, and remembers the token it was queried with.
+struct Fixed {
+    changes: Changes,
+    queried_token: Option<Option<String>>,
+}
+
+impl Fixed {
+    fn new(changes: Changes) -> Self {
+        Fixed {
+            changes,
+            queried_token: None,
+        }
+    }
+}
+
+impl Monitor for Fixed {
+    type Error = std::convert::Infallible;
+
+    fn query_changes(&mut self, token: Option<&BStr>) -> Result<Response, Self::Error> {
+        self.queried_token = Some(token.map(ToString::to_string));
+        Ok(Response {
+            token: "token-2".into(),
+            changes: self.changes.clone(),
+        })
+    }
+}
+
+fn fixture() -> crate::Result<(tempfile::TempDir, git_index::File)> {
+    let tmp = git_testtools::scripted_fixture_repo_writable("make_fs_monitor.sh")?;
+    std::fs::write(tmp.path().join("a"), "modified")?;
+    std::fs::write(tmp.path().join("dir").join("c"), "modified")?;
+    std::fs::remove_file(tmp.path().join("b"))?;
+    let index = git_index::File::at(
+        tmp.path().join(".git").join("index"),
+        git_hash::Kind::Sha1,
+        Default::default(),
+    )?;
+    Ok((tmp, index))
+}
+
+fn no_attribute_files_in_index<'a>(
+    _oid: &git_hash::oid,
+    _buf: &'a mut Vec<u8>,
+) -> Result<git_object::BlobRef<'a>, std::io::Error> {
+    unreachable!("all attribute files are in the worktree")
+}
+
+fn status<M: Monitor>(
+    index: &mut git_index::State,
+    dir: &std::path::Path,
+    monitor: Option<&mut M>,
+) -> crate::Result<index::status::Outcome> {
+    Ok(index::status(
+        index,
+        dir,
+        monitor,
+        no_attribute_files_in_index,
+        &AtomicBool::default(),
+        index::status::Options {
+            fs: git_worktree::fs::Capabilities::probe(dir),
+            ..Default::default()
+        },
+    )?)
+}
+
+fn change(path: &str, change: Change) -> Entry {
+    Entry {
+        rela_path: path.into(),
+        change,
+    }
+}
+
+fn is_fs_monitor_valid(index: &git_index::State, path: &str) -> bool {
+    index
+        .entry_by_path_and_stage(path.into(), 0)
+        .expect("present")
+        .flags
+        .contains(git_index::entry::Flags::FSMONITOR_VALID)
+}
+
+#[test]
+fn without_monitor_all_files_are_checked() -> crate::Result {
+    let (tmp, mut index) = fixture()?;
+    let out = status(&mut index, tmp.path(), None::<&mut fs_monitor::Hook>)?;
+    assert_eq!(
+        out.changes,
+        [
+            change("a", Change::Modified),
+            change("b", Change::Removed),
+            change("dir/c", Change::Modified)
+        ]
+    );
+    assert_eq!(out.entries_checked, 3);
+    assert_eq!(out.entries_skipped, 0);
+    assert!(out.fs_monitor_token.is_none());
+    Ok(())
+}
+
+#[test]
+fn with_monitor_only_reported_files_are_checked() -> crate::Result {
+    let (tmp, mut index) = fixture()?;
+    let mut monitor = Fixed::new(Changes::Paths(vec!["dir".into()]));
+    let out = status(&mut index, tmp.path(), Some(&mut monitor))?;
+    assert_eq!(
+        monitor.queried_token,
+        Some(Some("token-1".into())),
+        "the token stored in the index is used"
+    );
+    assert_eq!(
+        out.changes,
+        [change("dir/c", Change::Modified)],
+        "changes that aren't reported by the monitor aren't seen, as we don't look"
+    );
+    assert_eq!(out.entries_checked, 1);
+    assert_eq!(out.entries_skipped, 2);
+    assert_eq!(out.fs_monitor_token.as_ref().expect("set"), "token-2");
+    assert!(is_fs_monitor_valid(&index, "a"));
+    assert!(!is_fs_monitor_valid(&index, "dir/c"), "changed entries aren't valid");
+    Ok(())
+}
+
+#[test]
+fn with_monitor_reporting_everything_all_files_are_checked() -> crate::Result {
+    let (tmp, mut index) = fixture()?;
+    std::fs::write(tmp.path().join("b"), "b\n")?;
+    let out = status(&mut index, tmp.path(), Some(&mut Fixed::new(Changes::All)))?;
+    assert_eq!(
+        out.changes,
+        [change("a", Change::Modified), change("dir/c", Change::Modified)]
+    );
+    assert_eq!(out.entries_checked, 3);
+    assert_eq!(out.entries_skipped, 0);
+    assert!(
+        is_fs_monitor_valid(&index, "b"),
+        "unchanged entries are marked valid when using a monitor"
+    );
+    Ok(())
+}
+
+#[test]
+fn racily_clean_entries_have_their_content_compared() -> crate::Result {
+    let (tmp, index) = fixture()?;
+    let stat = index.entry_by_path_and_stage("b".into(), 0).expect("present").stat;
+    let path = tmp.path().join("b");
+    std::fs::write(&path, "B\n")?;
+    let mtime = filetime::FileTime::from_unix_time(stat.mtime.secs.into(), stat.mtime.nsecs);
+    filetime::set_file_mtime(&path, mtime)?;
+
+    let options = index::status::Options {
+        fs: git_worktree::fs::Capabilities::probe(tmp.path()),
+        trust_ctime: false,
+        ..Default::default()
+    };
+    let mut buf = Vec::new();
+    index.write_to(&mut buf, Default::default())?;
+    for (timestamp, expected_changes, comment) in [
+        (
+            filetime::FileTime::from_unix_time(mtime.unix_seconds() + 1, 0),
+            vec![change("a", Change::Modified), change("dir/c", Change::Modified)],
+            "stat information is trusted if the index was written after the file was changed",
+        ),
+        (
+            mtime,
+            vec![
+                change("a", Change::Modified),
+                change("b", Change::Modified),
+                change("dir/c", Change::Modified),
+            ],
+            "racily clean entries are hashed, even though their stat information matches",
+        ),
+    ] {
+        let (mut state, _) = git_index::State::from_bytes(&buf, timestamp, git_hash::Kind::Sha1, Default::default())?;
+        let out = index::status(
+            &mut state,
+            tmp.path(),
+            None::<&mut fs_monitor::Hook>,
+            no_attribute_files_in_index,
+            &AtomicBool::default(),
+            options.clone(),
+        )?;
+        assert_eq!(out.changes, expected_changes, "{}", comment);
+    }
+    Ok(())
+}
+
+#[test]
+fn results_are_in_index_order_when_using_multiple_threads() -> crate::Result {
+    let (tmp, mut index) = fixture()?;
+    let out = index::status(
+        &mut index,
+        tmp.path(),
+        None::<&mut fs_monitor::Hook>,
+        no_attribute_files_in_index,
+        &AtomicBool::default(),
+        index::status::Options {
+            fs: git_worktree::fs::Capabilities::probe(tmp.path()),
+            thread_limit: Some(4),
+            ..Default::default()
+        },
+    )?;
+    assert_eq!(
+        out.changes,
+        [
+            change("a", Change::Modified),
+            change("b", Change::Removed),
+            change("dir/c", Change::Modified)
+        ]
+    );
+    assert_eq!(out.entries_checked, 3);
+    Ok(())
+}
+
+#[test]
+fn files_are_converted_according_to_their_attributes_before_comparing_them() -> crate::Result {
+    let (tmp, mut index) = fixture()?;
+    std::fs::write(tmp.path().join("b"), "b\r\n")?;
+    std::fs::write(tmp.path().join(".gitattributes"), "b eol=crlf\n")?;
+    let out = status(&mut index, tmp.path(), None::<&mut fs_monitor::Hook>)?;
+    assert_eq!(
+        out.changes,
+        [change("a", Change::Modified), change("dir/c", Change::Modified)],
+        "'b' only differs in line endings, which are normalized as its attributes say it's a text file"
+    );
+
+    std::fs::remove_file(tmp.path().join(".gitattributes"))?;
+    let out = index::status(
+        &mut index,
+        tmp.path(),
+        None::<&mut fs_monitor::Hook>,
+        no_attribute_files_in_index,
+        &AtomicBool::default(),
+        index::status::Options {
+            fs: git_worktree::fs::Capabilities::probe(tmp.path()),
+            filters: git_worktree::filter::Pipeline::new(
+                Vec::new(),
+                git_worktree::filter::Options {
+                    auto_crlf: git_worktree::filter::AutoCrlf::Enabled,
+                    ..Default::default()
+                },
+            ),
+            ..Default::default()
+        },
+    )?;
+    assert_eq!(
+        out.changes,
+        [change("a", Change::Modified), change("dir/c", Change::Modified)],
+        "the same is true for text files if core.autocrlf is enabled"
+    );
+
+    let out = status(&mut index, tmp.path(), None::<&mut fs_monitor::Hook>)?;
+    assert_eq!(
+        out.changes,
+        [
+            change("a", Change::Modified),
+            change("b", Change::Modified),
+            change("dir/c", Change::Modified)
+        ],
+        "without conversion, the line endings make the difference"
+    );
+    Ok(())
+}
@@ -0,0 +1,345 @@
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    sync::atomic::AtomicBool,
+};
+
+use git_features::progress;
+use git_object::bstr::ByteSlice;
+use git_odb::FindExt;
+use git_worktree::{index, index::update::LocalModifications};
+use tempfile::TempDir;
+
+use super::checkout::{dir_structure, opts_from_probe};
+use crate::fixture_path;
+
+#[test]
+fn only_changed_paths_are_touched_and_the_result_matches_a_checkout_of_the_new_index() -> crate::Result {
+    let fixture = Fixture::new(|_| Ok(()))?;
+    let unchanged_stat = fixture.stat_of(&fixture.old, "same");
+    let (outcome, new) = fixture.update(Default::default())?;
+
+    assert_eq!(outcome.unchanged, 1, "only 'same' stays the same");
+    assert_eq!(
+        outcome.removed,
+        [
+            "dir-becomes-file/file",
+            "file-becomes-dir",
+            "locally-modified-and-removed",
+            "removed",
+            "removed-dir/file"
+        ]
+    );
+    assert_eq!(
+        outcome.updated,
+        [
+            "added",
+            "changed",
+            "dir-becomes-file",
+            "file-becomes-dir/file",
+            "locally-modified",
+            "untracked-in-the-way",
+            "file-becomes-link"
+        ],
+        "symlinks are written last"
+    );
+    assert!(outcome.kept.is_empty() && outcome.overwritten.is_empty());
+    assert!(outcome.collisions.is_empty() && outcome.errors.is_empty());
+    assert_eq!(
+        fixture.stat_of(&new, "same"),
+        unchanged_stat,
+        "unchanged entries receive the file metadata of the old index"
+    );
+    assert!(
+        !fixture.destination.path().join("removed-dir").exists(),
+        "directories that become empty are removed"
+    );
+
+    let expected = tempfile::tempdir()?;
+    let mut new = fixture.new_index()?;
+    index::checkout(
+        &mut new,
+        expected.path(),
+        fixture.find(),
+        &mut progress::Discard,
+        &mut progress::Discard,
+        &AtomicBool::default(),
+        opts_from_probe(),
+    )?;
+    assert_eq!(contents(expected.path()), contents(fixture.destination.path()));
+    Ok(())
+}
+
+#[test]
+fn locally_modified_and_untracked_files_are_kept_by_default() -> crate::Result {
+    let fixture = Fixture::new(modify_locally)?;
+    let (outcome, new) = fixture.update(Default::default())?;
+
+    assert_eq!(
+        outcome.kept,
+        [
+            "locally-modified-and-removed",
+            "locally-modified",
+            "untracked-in-the-way"
+        ],
+        "deletions are handled first"
+    );
+    assert!(outcome.overwritten.is_empty());
+    for path in &outcome.kept {
+        assert!(!outcome.updated.contains(path) && !outcome.removed.contains(path));
+        assert_eq!(fs::read(fixture.destination.path().join(path.to_string()))?, b"local");
+    }
+    assert_eq!(
+        fixture.stat_of(&new, "locally-modified"),
+        Default::default(),
+        "kept files aren't up-to-date with the new index"
+    );
+    assert_eq!(
+        fs::read(fixture.destination.path().join("changed"))?,
+        b"new",
+        "everything else is updated"
+    );
+    Ok(())
+}
+
+#[test]
+fn locally_modified_and_untracked_files_can_be_overwritten() -> crate::Result {
+    let fixture = Fixture::new(modify_locally)?;
+    let (outcome, _new) = fixture.update(index::update::Options {
+        local_modifications: LocalModifications::Overwrite,
+        ..Default::default()
+    })?;
+
+    assert_eq!(
+        outcome.overwritten,
+        [
+            "locally-modified-and-removed",
+            "locally-modified",
+            "untracked-in-the-way"
+        ]
+    );
+    assert!(outcome.kept.is_empty());
+    let dest = fixture.destination.path();
+    assert!(!dest.join("locally-modified-and-removed").exists());
+    assert_eq!(fs::read(dest.join("locally-modified"))?, b"new");
+    assert_eq!(fs::read(dest.join("untracked-in-the-way"))?, b"new content");
+    Ok(())
+}
+
+#[test]
+fn dry_run_lists_would_be_overwritten_paths_without_changing_anything() -> crate::Result {
+    let fixture = Fixture::new(modify_locally)?;
+    let before = contents(fixture.destination.path());
+    let (outcome, new) = fixture.update(index::update::Options {
+        local_modifications: LocalModifications::Overwrite,
+        dry_run: true,
+        ..Default::default()
+    })?;
+
+    assert_eq!(
+        outcome.overwritten,
+        [
+            "locally-modified-and-removed",
+            "locally-modified",
+            "untracked-in-the-way"
+        ]
+    );
+    assert_eq!(outcome.removed.len(), 5);
+    assert_eq!(
+        outcome.updated.len(),
+        7,
+        "all paths are listed, even though nothing was written"
+    );
+    assert_eq!(outcome.bytes_written, 0);
+    assert_eq!(contents(fixture.destination.path()), before, "nothing changed on disk");
+    assert_eq!(
+        fixture.stat_of(&new, "same"),
+        Default::default(),
+        "the new index isn't changed either"
+    );
+    Ok(())
+}
+
+#[test]
+#[cfg(unix)]
+fn type_and_executable_bit_changes_are_local_modifications_if_the_filesystem_supports_them() -> crate::Result {
+    use std::os::unix::fs::PermissionsExt;
+    let fixture = Fixture::new(|dest| {
+        fs::set_permissions(dest.join("changed"), fs::Permissions::from_mode(0o755))?;
+        fs::remove_file(dest.join("same"))?;
+        std::os::unix::fs::symlink("changed", dest.join("same"))
+    })?;
+    let supported = opts_from_probe().fs;
+    let (outcome, _new) = fixture.update_with_fs(Default::default(), supported)?;
+    assert_eq!(outcome.kept, ["changed"], "the executable bit was set locally");
+    assert!(
+        fs::symlink_metadata(fixture.destination.path().join("same"))?
+            .file_type()
+            .is_symlink(),
+        "unchanged entries aren't touched, even if they changed locally"
+    );
+
+    let fixture = Fixture::new(|dest| fs::set_permissions(dest.join("changed"), fs::Permissions::from_mode(0o755)))?;
+    let (outcome, _new) = fixture.update_with_fs(
+        Default::default(),
+        git_worktree::fs::Capabilities {
+            executable_bit: false,
+            ..supported
+        },
+    )?;
+    assert!(
+        outcome.kept.is_empty(),
+        "without support for the executable bit, it's not a modification"
+    );
+    assert_eq!(fs::read(fixture.destination.path().join("changed"))?, b"new");
+    Ok(())
+}
+
+#[test]
+#[cfg(unix)]
+fn files_are_cleaned_according_to_their_attributes_before_checking_for_local_modifications() -> crate::Result {
+    let fixture = Fixture::new(|dest| {
+        fs::write(dest.join(".gitattributes"), "locally-modified filter=lower")?;
+        fs::write(dest.join("locally-modified"), b"OLD")
+    })?;
+    let (outcome, _new) = fixture.update(index::update::Options {
+        checkout: index::checkout::Options {
+            filters: git_worktree::filter::Pipeline::new(
+                vec![git_worktree::filter::Driver {
+                    name: "lower".into(),
+                    clean: Some("tr A-Z a-z".into()),
+                    ..Default::default()
+                }],
+                Default::default(),
+            ),
+            ..Default::default()
+        },
+        ..Default::default()
+    })?;
+    assert!(
+        outcome.kept.is_empty(),
+        "the clean filter turns the file into what's in the old index"
+    );
+    assert_eq!(fs::read(fixture.destination.path().join("locally-modified"))?, b"new");
+    Ok(())
+}
+
+fn modify_locally(dest: &Path) -> std::io::Result<()> {
+    fs::write(dest.join("locally-modified"), b"local")?;
+    fs::write(dest.join("locally-modified-and-removed"), b"local")?;
+    fs::write(dest.join("untracked-in-the-way"), b"local")
+}
+
+struct Fixture {
+    source_tree: PathBuf,
+    odb: git_odb::store::Handle<std::sync::Arc<git_odb::Store>>,
+    destination: TempDir,
+    old: git_index::State,
+}
+
+impl Fixture {
+    /// Checkout the old index and call `modify` on the destination directory.
+    fn new(modify: impl FnOnce(&Path) -> std::io::Result<()>) -> crate::Result<Self> {
+        let source_tree = fixture_path("make_worktree_update");
+        let odb = git_odb::at(source_tree.join(".git/objects"))?.into_inner().into_arc()?;
+        let destination = tempfile::tempdir_in(std::env::current_dir()?)?;
+        let mut fixture = Fixture {
+            old: index_from_tree(&odb, &source_tree, "old-tree")?,
+            source_tree,
+            odb,
+            destination,
+        };
+        let find = fixture.find();
+        index::checkout(
+            &mut fixture.old,
+            fixture.destination.path(),
+            find,
+            &mut progress::Discard,
+            &mut progress::Discard,
+            &AtomicBool::default(),
+            opts_from_probe(),
+        )?;
+        modify(fixture.destination.path())?;
+        Ok(fixture)
+    }
+
+    fn new_index(&self) -> crate::Result<git_index::State> {
+        index_from_tree(&self.odb, &self.source_tree, "new-tree")
+    }
+
+    fn update(&self, opts: index::update::Options) -> crate::Result<(index::update::Outcome, git_index::State)> {
+        self.update_with_fs(opts, opts_from_probe().fs)
+    }
+
+    fn update_with_fs(
+        &self,
+        mut opts: index::update::Options,
+        fs: git_worktree::fs::Capabilities,
+    ) -> crate::Result<(index::update::Outcome, git_index::State)> {
+        opts.checkout = index::checkout::Options {
+            fs,
+            filters: std::mem::take(&mut opts.checkout.filters),
+            ..opts_from_probe()
+        };
+        let mut new = self.new_index()?;
+        let outcome = index::update(
+            &self.old,
+            &mut new,
+            self.destination.path(),
+            self.find(),
+            &mut progress::Discard,
+            &mut progress::Discard,
+            &AtomicBool::default(),
+            opts,
+        )?;
+        Ok((outcome, new))
+    }
+
+    fn find(
+        &self,
+    ) -> impl for<'a> FnMut(
+        &git_hash::oid,
+        &'a mut Vec<u8>,
+    ) -> Result<
+        git_object::BlobRef<'a>,
+        git_odb::find::existing_object::Error<git_odb::store::find::Error>,
+    > + Send
+           + Clone {
+        let odb = self.odb.clone();
+        move |oid, buf| odb.find_blob(oid, buf)
+    }
+
+    fn stat_of(&self, index: &git_index::State, path: &str) -> git_index::entry::Stat {
+        index
+            .entries()
+            .iter()
+            .find(|entry| entry.path(index) == path)
+            .expect("entry exists")
+            .stat
+    }
+}
+
+fn index_from_tree(
+    odb: &git_odb::store::Handle<std::sync::Arc<git_odb::Store>>,
+    source_tree: &Path,
+    name: &str,
+) -> crate::Result<git_index::State> {
+    let tree = git_hash::ObjectId::from_hex(fs::read(source_tree.join(".git").join(name))?.trim_end())?;
+    Ok(git_index::State::from_tree(&tree, |oid, buf| {
+        odb.find_tree_iter(oid, buf).ok()
+    })?)
+}
+
+/// Return all paths below `dir` along with their content, or the target of symlinks.
+fn contents(dir: &Path) -> Vec<(PathBuf, Vec<u8>)> {
+    dir_structure(dir)
+        .into_iter()
+        .map(|path| {
+            let content = match fs::read_link(&path) {
+                Ok(target) => git_path::into_bstr(target).into_owned().into(),
+                Err(_) => fs::read(&path).expect("readable"),
+            };
+            (path.strip_prefix(dir).expect("below dir").to_owned(), content)
+        })
+        .collect()
+}
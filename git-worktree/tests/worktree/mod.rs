@@ -1,5 +1,10 @@
+mod clean;
+mod filter;
 mod fs;
+mod fs_monitor;
 mod index;
+mod sparse;
+mod untracked;
 
 use std::path::{Path, PathBuf};
 pub type Result<T = ()> = std::result::Result<T, Box<dyn std::error::Error>>;
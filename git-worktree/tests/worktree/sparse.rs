@@ -0,0 +1,176 @@
+use std::sync::atomic::AtomicBool;
+
+use bstr::{BStr, ByteSlice};
+use git_features::progress;
+use git_glob::pattern::Case;
+use git_odb::FindExt;
+use git_worktree::{index, sparse};
+
+use crate::worktree::index::checkout::opts_from_probe;
+
+const PATHS: &[&str] = &["top", "a/file", "a/b/file", "a/b/c/file", "d/file", "d/e/file"];
+
+fn included(patterns: &sparse::Patterns) -> Vec<&'static str> {
+    PATHS
+        .iter()
+        .copied()
+        .filter(|path| patterns.contains(path.as_bytes().as_bstr(), Case::Sensitive))
+        .collect()
+}
+
+fn written(patterns: &sparse::Patterns) -> String {
+    let mut buf = Vec::new();
+    patterns.write_to(&mut buf).expect("in-memory writes don't fail");
+    buf.to_str().expect("valid UTF-8").into()
+}
+
+#[test]
+fn cone_mode_includes_top_level_files_recursive_directories_and_files_of_parents() {
+    let patterns = sparse::Patterns::from_bytes(b"/*\n!/*/\n/a/\n!/a/*/\n/a/b/\n", true);
+    assert!(patterns.is_cone());
+    assert_eq!(included(&patterns), ["top", "a/file", "a/b/file", "a/b/c/file"]);
+    assert_eq!(written(&patterns), "/*\n!/*/\n/a/\n!/a/*/\n/a/b/\n");
+    assert!(
+        !patterns.contains("A/B/file".into(), Case::Sensitive) && patterns.contains("A/B/file".into(), Case::Fold),
+        "case is respected"
+    );
+
+    let all = sparse::Patterns::from_bytes(b"/*\n", true);
+    assert!(all.is_cone());
+    assert_eq!(
+        included(&all),
+        PATHS,
+        "without excluding directories, everything is included"
+    );
+}
+
+#[test]
+fn cone_mode_can_be_created_from_directories() {
+    let patterns =
+        sparse::Patterns::cone_from_directories(["a/b/", "d", "a/b/c"].iter().map(|dir| -> &BStr { (*dir).into() }));
+    assert_eq!(
+        included(&patterns),
+        ["top", "a/file", "a/b/file", "a/b/c/file", "d/file", "d/e/file"]
+    );
+    assert_eq!(
+        written(&patterns),
+        "/*\n!/*/\n/a/\n!/a/*/\n/a/b/\n/d/\n",
+        "directories within recursive ones are redundant, and parents only include their files"
+    );
+
+    let reparsed = sparse::Patterns::from_bytes(written(&patterns).as_bytes(), true);
+    assert_eq!(written(&reparsed), written(&patterns), "the output round-trips");
+}
+
+#[test]
+fn cone_mode_falls_back_to_pattern_mode_if_patterns_are_incompatible() {
+    let patterns = sparse::Patterns::from_bytes(b"/*\n!/*/\n*.txt\n", true);
+    assert!(!patterns.is_cone());
+    assert_eq!(written(&patterns), "/*\n!/*/\n*.txt\n", "the original is retained");
+}
+
+#[test]
+fn pattern_mode_uses_the_last_matching_pattern_of_the_path_or_its_parent_directories() {
+    let patterns = sparse::Patterns::from_bytes(b"/top\na/\n!a/b/c/\nfile\n!d/file\n", false);
+    assert!(!patterns.is_cone());
+    assert_eq!(
+        included(&patterns),
+        ["top", "a/file", "a/b/file", "a/b/c/file", "d/e/file"],
+        "'file' matches everywhere as it's the last pattern, except for 'd/file'"
+    );
+
+    let patterns = sparse::Patterns::from_bytes(b"/*\n!d/\n!/a/b/c/\n", true);
+    assert!(
+        !patterns.is_cone(),
+        "negations without the directory glob aren't valid in cone mode"
+    );
+    assert_eq!(
+        included(&patterns),
+        ["top", "a/file", "a/b/file"],
+        "without a match of their own, files are decided by the closest matching parent directory"
+    );
+}
+
+#[test]
+fn apply_removes_files_leaving_the_sparse_set_and_writes_those_entering_it() -> crate::Result {
+    let source = crate::fixture_path("make_sparse");
+    let mut index = git_index::File::at(
+        source.join(".git").join("index"),
+        git_hash::Kind::Sha1,
+        Default::default(),
+    )?;
+    let odb = git_odb::at(source.join(".git").join("objects"))?.into_arc()?;
+    let destination = tempfile::tempdir()?;
+    index::checkout(
+        &mut index,
+        destination.path(),
+        {
+            let odb = odb.clone();
+            move |oid, buf| odb.find_blob(oid, buf)
+        },
+        &mut progress::Discard,
+        &mut progress::Discard,
+        &AtomicBool::default(),
+        opts_from_probe(),
+    )?;
+    std::fs::write(destination.path().join("d/e/file"), "modified")?;
+
+    let apply = |index: &mut git_index::State, patterns: &sparse::Patterns, dry_run: bool| {
+        sparse::apply(
+            index,
+            patterns,
+            destination.path(),
+            {
+                let odb = odb.clone();
+                move |oid, buf| odb.find_blob(oid, buf)
+            },
+            &mut progress::Discard,
+            &mut progress::Discard,
+            &AtomicBool::default(),
+            index::update::Options {
+                checkout: opts_from_probe(),
+                dry_run,
+                ..Default::default()
+            },
+        )
+    };
+    let skipped = |index: &git_index::State| -> Vec<String> {
+        index
+            .entries()
+            .iter()
+            .filter(|e| e.flags.contains(git_index::entry::Flags::SKIP_WORKTREE))
+            .map(|e| e.path(index).to_string())
+            .collect()
+    };
+
+    let patterns = sparse::Patterns::cone_from_directories(Some("a/b".into()));
+    let out = apply(&mut index, &patterns, true)?;
+    assert_eq!(out.removed, ["d/file"]);
+    assert_eq!(out.kept, ["d/e/file"]);
+    assert!(skipped(&index).is_empty(), "dry-runs don't change the index");
+    assert!(destination.path().join("d/file").is_file(), "nor the worktree");
+
+    let out = apply(&mut index, &patterns, false)?;
+    assert_eq!(out.removed, ["d/file"]);
+    assert_eq!(out.kept, ["d/e/file"], "local modifications are kept");
+    assert_eq!(
+        skipped(&index),
+        ["d/file"],
+        "kept files remain visible in the worktree and lose their flag"
+    );
+    assert!(!destination.path().join("d/file").exists());
+
+    let patterns = sparse::Patterns::cone_from_directories(Some("d".into()));
+    let out = apply(&mut index, &patterns, false)?;
+    assert_eq!(out.removed, ["a/b/c/file", "a/b/file", "a/file"]);
+    assert_eq!(out.updated, ["d/file"], "files entering the sparse set are written");
+    assert!(out.kept.is_empty());
+    assert_eq!(skipped(&index), ["a/b/c/file", "a/b/file", "a/file"]);
+    assert!(
+        !destination.path().join("a").exists(),
+        "directories that become empty are removed"
+    );
+    assert!(destination.path().join("d/file").is_file());
+    assert_eq!(std::fs::read(destination.path().join("d/e/file"))?, b"modified");
+    Ok(())
+}
@@ -0,0 +1,279 @@
+use std::{path::Path, sync::atomic::AtomicBool};
+
+use bstr::BString;
+use git_odb::FindExt;
+use git_worktree::{
+    fs,
+    untracked::{self, Kind, Mode, Status},
+};
+
+fn walk(
+    worktree: &Path,
+    previous: Option<&git_index::extension::UntrackedCache>,
+    options: untracked::Options,
+) -> crate::Result<untracked::Outcome> {
+    let git_dir = worktree.join(".git");
+    let mut buf = Vec::new();
+    let index = git_index::File::at(git_dir.join("index"), git_hash::Kind::Sha1, Default::default())?;
+    let odb = git_odb::at(git_dir.join("objects"))?.into_arc()?;
+    let case = git_glob::pattern::Case::Sensitive;
+    let state = fs::cache::State::for_status(fs::cache::state::Ignore::new(
+        Default::default(),
+        git_attributes::MatchGroup::from_git_dir(&git_dir, None, &mut buf)?,
+        None,
+        case,
+    ));
+    let attribute_files_in_index = state.build_attribute_list(&index, index.path_backing(), case);
+    let cache = fs::Cache::new(worktree, state, case, buf, attribute_files_in_index);
+    Ok(untracked::walk(
+        &index,
+        &cache,
+        move |oid, buf| odb.find_blob(oid, buf),
+        previous,
+        &AtomicBool::default(),
+        options,
+    )?)
+}
+
+fn entries(outcome: &untracked::Outcome) -> Vec<(BString, Kind, Status)> {
+    outcome
+        .entries
+        .iter()
+        .map(|e| (e.rela_path.clone(), e.kind, e.status))
+        .collect()
+}
+
+fn untracked(path: &str, kind: Kind) -> (BString, Kind, Status) {
+    (path.into(), kind, Status::Untracked)
+}
+
+fn ignored(path: &str, kind: Kind) -> (BString, Kind, Status) {
+    (path.into(), kind, Status::Ignored)
+}
+
+fn options(mode: Mode, emit_ignored: bool) -> untracked::Options {
+    untracked::Options {
+        mode,
+        emit_ignored,
+        thread_limit: None,
+        fs: Default::default(),
+    }
+}
+
+fn worktree() -> std::path::PathBuf {
+    git_testtools::scripted_fixture_repo_read_only("make_untracked.sh").unwrap()
+}
+
+#[test]
+fn normal_mode_reports_untracked_directories_as_a_whole() -> crate::Result {
+    let out = walk(&worktree(), None, options(Mode::Normal, false))?;
+    assert_eq!(
+        entries(&out),
+        vec![
+            untracked("link", Kind::Symlink),
+            untracked("nested-repo", Kind::Repository),
+            untracked("tracked-dir/sub/untracked", Kind::File),
+            untracked("tracked-dir/untracked", Kind::File),
+            untracked("untracked", Kind::File),
+            untracked("untracked-dir", Kind::Directory),
+        ],
+        "empty directories and directories with only ignored files aren't listed"
+    );
+    assert_eq!(out.dir_flags, Mode::Normal.dir_flags());
+    assert_eq!(out.exclude_file_name, ".gitignore");
+    Ok(())
+}
+
+#[test]
+fn all_mode_reports_all_untracked_files() -> crate::Result {
+    let out = walk(&worktree(), None, options(Mode::All, false))?;
+    assert_eq!(
+        entries(&out),
+        vec![
+            untracked("link", Kind::Symlink),
+            untracked("nested-repo", Kind::Repository),
+            untracked("tracked-dir/sub/untracked", Kind::File),
+            untracked("tracked-dir/untracked", Kind::File),
+            untracked("untracked", Kind::File),
+            untracked("untracked-dir/file", Kind::File),
+            untracked("untracked-dir/nested/file", Kind::File),
+        ]
+    );
+    Ok(())
+}
+
+#[test]
+fn ignored_entries_can_be_emitted_as_well() -> crate::Result {
+    let out = walk(&worktree(), None, options(Mode::Normal, true))?;
+    assert_eq!(
+        entries(&out),
+        vec![
+            ignored("a.log", Kind::File),
+            ignored("from-info-exclude", Kind::File),
+            ignored("ignored-dir", Kind::Directory),
+            untracked("link", Kind::Symlink),
+            untracked("nested-repo", Kind::Repository),
+            ignored("only-ignored/a.log", Kind::File),
+            ignored("only-ignored/nested/b.log", Kind::File),
+            ignored("tracked-dir/file.log", Kind::File),
+            ignored("tracked-dir/local-ignored", Kind::File),
+            untracked("tracked-dir/sub/untracked", Kind::File),
+            untracked("tracked-dir/untracked", Kind::File),
+            untracked("untracked", Kind::File),
+            untracked("untracked-dir", Kind::Directory),
+            ignored("untracked-dir/nested/file.log", Kind::File),
+        ]
+    );
+    Ok(())
+}
+
+#[test]
+fn mode_no_does_not_look_at_the_worktree() -> crate::Result {
+    let out = walk(&worktree(), None, options(Mode::No, true))?;
+    assert!(out.entries.is_empty());
+    assert!(out.untracked_cache.is_empty());
+    assert_eq!(out.directories_read, 0);
+    Ok(())
+}
+
+#[test]
+fn parallel_and_serial_walks_have_the_same_outcome() -> crate::Result {
+    let worktree = worktree();
+    for mode in [Mode::Normal, Mode::All] {
+        let parallel = walk(&worktree, None, options(mode, true))?;
+        let serial = walk(
+            &worktree,
+            None,
+            untracked::Options {
+                thread_limit: Some(1),
+                ..options(mode, true)
+            },
+        )?;
+        assert_eq!(parallel.entries, serial.entries);
+        assert_eq!(parallel.untracked_cache, serial.untracked_cache);
+    }
+    Ok(())
+}
+
+#[test]
+fn untracked_cache_contains_all_visited_directories() -> crate::Result {
+    let out = walk(&worktree(), None, options(Mode::Normal, false))?;
+    let names: Vec<_> = out.untracked_cache.iter().map(|d| d.name.clone()).collect();
+    assert_eq!(
+        names,
+        vec!["", "tracked-dir", "sub"],
+        "only directories with tracked files are visited"
+    );
+    let root = &out.untracked_cache[0];
+    assert_eq!(
+        root.untracked_entries,
+        vec!["link", "nested-repo/", "untracked", "untracked-dir/"],
+        "directories are marked with a trailing slash"
+    );
+    assert_eq!(root.sub_directories, vec![1]);
+    assert!(root.exclude_file_oid.is_some(), "the root has a .gitignore file");
+    assert!(out.untracked_cache[2].exclude_file_oid.is_none());
+
+    let cache = out
+        .to_untracked_cache("identifier".into(), None, None)
+        .expect("there are directories");
+    assert_eq!(cache.directories(), out.untracked_cache.as_slice());
+    assert_eq!(cache.dir_flags(), out.dir_flags);
+    Ok(())
+}
+
+#[test]
+fn untracked_cache_of_previous_walk_avoids_reading_directories() -> crate::Result {
+    let worktree = worktree();
+    let first = walk(&worktree, None, options(Mode::Normal, false))?;
+    assert_eq!(first.directories_from_cache, 0);
+    let cache = first.to_untracked_cache("id".into(), None, None).expect("present");
+
+    let second = walk(&worktree, Some(&cache), options(Mode::Normal, false))?;
+    assert_eq!(second.entries, first.entries);
+    assert_eq!(second.untracked_cache, first.untracked_cache);
+    assert_eq!(second.directories_from_cache, 3, "all directories are unchanged");
+    assert!(second.directories_read < first.directories_read);
+
+    let ignored_cache = walk(&worktree, Some(&cache), options(Mode::Normal, true))?;
+    assert_eq!(
+        ignored_cache.directories_from_cache, 0,
+        "the cache doesn't know about ignored files"
+    );
+    let other_mode = walk(&worktree, Some(&cache), options(Mode::All, false))?;
+    assert_eq!(
+        other_mode.directories_from_cache, 0,
+        "the cache was created with different flags"
+    );
+    Ok(())
+}
+
+#[test]
+fn untracked_cache_is_not_used_for_changed_directories() -> crate::Result {
+    let tmp = git_testtools::scripted_fixture_repo_writable("make_untracked.sh")?;
+    let worktree = tmp.path();
+    let first = walk(worktree, None, options(Mode::Normal, false))?;
+    let cache = first.to_untracked_cache("id".into(), None, None).expect("present");
+
+    std::fs::write(worktree.join("tracked-dir").join("new"), b"")?;
+    let second = walk(worktree, Some(&cache), options(Mode::Normal, false))?;
+    assert_eq!(second.directories_from_cache, 2, "the changed directory was read");
+    assert!(second
+        .entries
+        .iter()
+        .any(|e| e.rela_path == "tracked-dir/new" && e.status == Status::Untracked));
+    Ok(())
+}
+
+#[test]
+fn filesystem_capabilities_affect_how_names_are_matched() -> crate::Result {
+    let tmp = git_testtools::scripted_fixture_repo_writable("make_untracked.sh")?;
+    let worktree = tmp.path();
+    std::fs::rename(worktree.join("tracked"), worktree.join("TRACKED"))?;
+    std::fs::write(worktree.join("tracked-dir").join("a\u{308}"), b"")?;
+
+    let case_sensitive = walk(
+        worktree,
+        None,
+        untracked::Options {
+            mode: Mode::All,
+            fs: git_worktree::fs::Capabilities {
+                ignore_case: false,
+                precompose_unicode: false,
+                ..Default::default()
+            },
+            ..Default::default()
+        },
+    )?;
+    let paths: Vec<_> = case_sensitive.entries.iter().map(|e| e.rela_path.clone()).collect();
+    assert!(paths.contains(&"TRACKED".into()));
+    assert!(paths.contains(&"tracked-dir/a\u{308}".into()));
+
+    let ignore_case_and_precompose = walk(
+        worktree,
+        None,
+        untracked::Options {
+            mode: Mode::All,
+            fs: git_worktree::fs::Capabilities {
+                ignore_case: true,
+                precompose_unicode: true,
+                ..Default::default()
+            },
+            ..Default::default()
+        },
+    )?;
+    let paths: Vec<_> = ignore_case_and_precompose
+        .entries
+        .iter()
+        .map(|e| e.rela_path.clone())
+        .collect();
+    assert!(
+        !paths.contains(&"TRACKED".into()),
+        "it matches the tracked file when ignoring case"
+    );
+    assert!(
+        paths.contains(&"tracked-dir/ä".into()),
+        "decomposed names are precomposed"
+    );
+    Ok(())
+}
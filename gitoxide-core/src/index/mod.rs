@@ -7,7 +7,7 @@ pub struct Options {
     pub format: crate::OutputFormat,
 }
 
-mod entries;
+pub(crate) mod entries;
 pub use entries::entries;
 
 pub mod information;
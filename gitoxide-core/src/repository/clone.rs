@@ -4,6 +4,8 @@ pub struct Options {
     pub format: OutputFormat,
     pub bare: bool,
     pub handshake_info: bool,
+    pub no_checkout: bool,
+    pub shallow: git_repository::remote::fetch::Shallow,
 }
 
 pub const PROGRESS_RANGE: std::ops::RangeInclusive<u8> = 1..=3;
@@ -29,6 +31,8 @@ pub(crate) mod function {
             format,
             handshake_info,
             bare,
+            no_checkout,
+            shallow,
         }: Options,
     ) -> anyhow::Result<()>
     where
@@ -50,11 +54,12 @@ pub(crate) mod function {
                 opts.permissions.config.git_binary = true;
                 opts
             },
-        )?;
+        )?
+        .with_shallow(shallow);
         let (mut checkout, fetch_outcome) =
             prepare.fetch_then_checkout(&mut progress, &git::interrupt::IS_INTERRUPTED)?;
 
-        let (repo, outcome) = if bare {
+        let (repo, outcome) = if bare || no_checkout {
             (checkout.persist(), None)
         } else {
             let (repo, outcome) = checkout.main_worktree(progress, &git::interrupt::IS_INTERRUPTED)?;
@@ -0,0 +1,50 @@
+use std::{io, sync::atomic::AtomicBool};
+
+use anyhow::{bail, Context};
+use git_repository as git;
+use git_repository::prelude::FindExt;
+
+/// Verify the commit-graph of `repo` against its commit objects using at most `thread_limit` threads,
+/// printing each issue along with a suggestion on how to resolve it to `out`.
+pub fn verify(
+    repo: git::Repository,
+    mut out: impl io::Write,
+    thread_limit: Option<usize>,
+    should_interrupt: &AtomicBool,
+) -> anyhow::Result<()> {
+    let info_dir = repo.objects.store_ref().path().join("info");
+    let graph = git_commitgraph::Graph::from_info_dir(&info_dir)
+        .with_context(|| format!("Could not open commit-graph in {:?}", info_dir))?;
+    graph
+        .verify_integrity(|_commit| Ok::<_, std::convert::Infallible>(()))
+        .with_context(|| "Commit-graph files are corrupt")?;
+
+    let objects = repo.objects.into_arc()?;
+    let outcome = graph.verify_objects(
+        move |id, buf| objects.find_commit_iter(id, buf).ok(),
+        thread_limit,
+        should_interrupt,
+    )?;
+
+    let mut suggestions = Vec::new();
+    for issue in &outcome.issues {
+        writeln!(out, "{}", issue)?;
+        let suggestion = issue.suggestion();
+        if !suggestions.contains(&suggestion) {
+            suggestions.push(suggestion);
+        }
+    }
+    if !suggestions.is_empty() {
+        writeln!(out)?;
+        for suggestion in suggestions {
+            writeln!(out, "{}", suggestion)?;
+        }
+        bail!(
+            "Found {} issue(s) in the commit-graph with {} commits",
+            outcome.issues.len(),
+            outcome.num_commits
+        );
+    }
+    writeln!(out, "Verified {} commits in the commit-graph", outcome.num_commits)?;
+    Ok(())
+}
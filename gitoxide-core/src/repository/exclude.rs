@@ -2,9 +2,21 @@ use std::io;
 
 use anyhow::{bail, Context};
 use git_repository as git;
+use git_repository::bstr::ByteSlice;
 use git_repository::prelude::FindExt;
 
-use crate::OutputFormat;
+/// A path along with the exclude pattern matching it, as serialized when querying excludes.
+#[cfg_attr(feature = "serde1", derive(serde::Serialize))]
+pub struct JsonMatch {
+    /// The path as it was given, or with the prefix applied in case of queries.
+    pub path: String,
+    /// The file the pattern is from, or `None` if it was provided as override or if there was no match.
+    pub source: Option<String>,
+    /// The line number of the pattern within its source, or `None` if there was no match.
+    pub sequence_number: Option<usize>,
+    /// The pattern that matched, or `None` if there was no match.
+    pub pattern: Option<String>,
+}
 
 pub mod query {
     use std::ffi::OsString;
@@ -18,6 +30,7 @@ pub mod query {
     }
 }
 
+#[cfg_attr(not(feature = "serde1"), allow(unused_variables))]
 pub fn query(
     repo: git::Repository,
     pathspecs: impl Iterator<Item = git::path::Spec>,
@@ -28,10 +41,6 @@ pub fn query(
         show_ignore_patterns,
     }: query::Options,
 ) -> anyhow::Result<()> {
-    if format != OutputFormat::Human {
-        bail!("JSON output isn't implemented yet");
-    }
-
     let worktree = repo
         .worktree()
         .with_context(|| "Cannot check excludes without a current worktree")?;
@@ -42,6 +51,8 @@ pub fn query(
     )?;
 
     let prefix = repo.prefix().expect("worktree - we have an index by now")?;
+    #[cfg_attr(not(feature = "serde1"), allow(unused_mut))]
+    let mut json_matches = Vec::<JsonMatch>::new();
 
     for mut spec in pathspecs {
         for path in spec.apply_prefix(&prefix).items() {
@@ -52,6 +63,18 @@ pub fn query(
             let match_ = entry
                 .matching_exclude_pattern()
                 .and_then(|m| (show_ignore_patterns || !m.pattern.is_negative()).then(|| m));
+            #[cfg(feature = "serde1")]
+            if let crate::OutputFormat::Json = format {
+                json_matches.push(JsonMatch {
+                    path: path.to_string(),
+                    source: match_
+                        .as_ref()
+                        .and_then(|m| m.source.map(|p| p.to_string_lossy().into_owned())),
+                    sequence_number: match_.as_ref().map(|m| m.sequence_number),
+                    pattern: match_.as_ref().map(|m| m.pattern.to_string()),
+                });
+                continue;
+            }
             match match_ {
                 Some(m) => writeln!(
                     out,
@@ -65,5 +88,128 @@ pub fn query(
             }
         }
     }
+
+    #[cfg(feature = "serde1")]
+    if let crate::OutputFormat::Json = format {
+        serde_json::to_writer_pretty(out, &json_matches)?;
+    }
+    Ok(())
+}
+
+pub mod check_ignore {
+    use std::ffi::OsString;
+
+    use crate::OutputFormat;
+
+    pub struct Options {
+        pub format: OutputFormat,
+        pub overrides: Vec<OsString>,
+        /// Print the pattern that matched along with its source and line number, which includes negated patterns.
+        pub verbose: bool,
+        /// Also print paths that didn't match any pattern, which is only effective in verbose mode.
+        pub non_matching: bool,
+        /// If false, paths that are tracked in the index are never considered ignored.
+        pub no_index: bool,
+    }
+}
+
+/// Print the given `pathspecs` if they are ignored, similar to `git check-ignore`.
+#[cfg_attr(not(feature = "serde1"), allow(unused_variables))]
+pub fn check_ignore(
+    repo: git::Repository,
+    pathspecs: impl Iterator<Item = git::path::Spec>,
+    mut out: impl io::Write,
+    check_ignore::Options {
+        format,
+        overrides,
+        verbose,
+        non_matching,
+        no_index,
+    }: check_ignore::Options,
+) -> anyhow::Result<()> {
+    if non_matching && !verbose {
+        bail!("--non-matching is only valid with --verbose");
+    }
+
+    let worktree = repo
+        .worktree()
+        .with_context(|| "Cannot check excludes without a current worktree")?;
+    let index = worktree.index()?;
+    let mut cache = worktree.excludes(
+        &index,
+        Some(git::attrs::MatchGroup::<git::attrs::Ignore>::from_overrides(overrides)),
+    )?;
+
+    let prefix = repo.prefix().expect("worktree - we have an index by now")?;
+    let workdir = worktree.base();
+    #[cfg_attr(not(feature = "serde1"), allow(unused_mut))]
+    let mut json_matches = Vec::<JsonMatch>::new();
+
+    for mut spec in pathspecs {
+        // Paths are printed as given, just like git does.
+        let input = spec.clone();
+        for (path, input) in spec.apply_prefix(&prefix).items().zip(input.items()) {
+            let is_tracked = !no_index
+                && (0..=3).any(|stage| {
+                    index
+                        .entry_by_path_and_stage(path.trim_end_with(|c| c == '/').as_bstr(), stage)
+                        .is_some()
+                });
+            let entry;
+            let match_ = if is_tracked {
+                None
+            } else {
+                let is_dir = workdir
+                    .join(git::path::from_bstr(path))
+                    .metadata()
+                    .ok()
+                    .map(|m| m.is_dir());
+                entry = cache.at_entry(path, is_dir, |oid, buf| repo.objects.find_blob(oid, buf))?;
+                entry.matching_exclude_pattern()
+            };
+            #[cfg(feature = "serde1")]
+            if let crate::OutputFormat::Json = format {
+                let is_shown = match &match_ {
+                    Some(m) => verbose || !m.pattern.is_negative(),
+                    None => non_matching,
+                };
+                if is_shown {
+                    json_matches.push(JsonMatch {
+                        path: input.to_string(),
+                        source: match_
+                            .as_ref()
+                            .filter(|_| verbose)
+                            .and_then(|m| m.source)
+                            .map(|source| source.strip_prefix(workdir).unwrap_or(source))
+                            .map(|source| source.to_string_lossy().into_owned()),
+                        sequence_number: match_.as_ref().filter(|_| verbose).map(|m| m.sequence_number),
+                        pattern: match_.as_ref().filter(|_| verbose).map(|m| m.pattern.to_string()),
+                    });
+                }
+                continue;
+            }
+            match match_ {
+                Some(m) if verbose => {
+                    let source = m.source.map(|path| path.strip_prefix(workdir).unwrap_or(path));
+                    writeln!(
+                        out,
+                        "{}:{}:{}\t{}",
+                        source.map(|p| p.to_string_lossy()).unwrap_or_default(),
+                        m.sequence_number,
+                        m.pattern,
+                        input
+                    )?
+                }
+                Some(m) if !m.pattern.is_negative() => writeln!(out, "{}", input)?,
+                None if non_matching => writeln!(out, "::\t{}", input)?,
+                _ => {}
+            }
+        }
+    }
+
+    #[cfg(feature = "serde1")]
+    if let crate::OutputFormat::Json = format {
+        serde_json::to_writer_pretty(out, &json_matches)?;
+    }
     Ok(())
 }
@@ -1,8 +1,89 @@
 use std::{ffi::OsString, path::PathBuf};
 
-use git::prelude::FindExt;
+use anyhow::{bail, Context};
+use git::{bstr::BString, prelude::FindExt};
 use git_repository as git;
 
+use crate::OutputFormat;
+
+pub mod ls_files {
+    use crate::OutputFormat;
+
+    pub struct Options {
+        pub format: OutputFormat,
+        /// Print the mode, id and stage of each entry, similar to `git ls-files --stage`.
+        pub stage: bool,
+        /// Print the flags of each entry as well.
+        pub flags: bool,
+    }
+}
+
+/// List all entries of the index that match `pathspecs`, or all of them if there are none, similar to `git ls-files`.
+pub fn ls_files(
+    repo: git::Repository,
+    pathspecs: Vec<BString>,
+    mut out: impl std::io::Write,
+    ls_files::Options { format, stage, flags }: ls_files::Options,
+) -> anyhow::Result<()> {
+    let worktree = repo
+        .worktree()
+        .with_context(|| "Cannot list index entries without a current worktree")?;
+    let index = worktree.open_index()?;
+    let search = pathspec_search(&repo, pathspecs)?;
+
+    #[cfg(feature = "serde1")]
+    if let OutputFormat::Json = format {
+        out.write_all(b"[\n")?;
+    }
+
+    let mut entries = index
+        .entries()
+        .iter()
+        .filter(|entry| search.is_included(entry.path(&index), |_, _| {}))
+        .peekable();
+    while let Some(entry) = entries.next() {
+        match format {
+            OutputFormat::Human => {
+                if stage {
+                    write!(out, "{:06o} {} {}\t", entry.mode.bits(), entry.id, entry.stage())?;
+                }
+                if flags {
+                    write!(out, "{:?}\t", entry.flags)?;
+                }
+                writeln!(out, "{}", entry.path(&index))?;
+            }
+            #[cfg(feature = "serde1")]
+            OutputFormat::Json => crate::index::entries::to_json(&mut out, &index, entry, entries.peek().is_none())?,
+        }
+    }
+
+    #[cfg(feature = "serde1")]
+    if let OutputFormat::Json = format {
+        out.write_all(b"]\n")?;
+    }
+    Ok(())
+}
+
+/// Parse all `pathspecs` and prepare them for matching paths relative to the worktree root of `repo`, as if they were
+/// given in the current working directory.
+pub(crate) fn pathspec_search(
+    repo: &git::Repository,
+    pathspecs: Vec<BString>,
+) -> anyhow::Result<git::pathspec::Search> {
+    let root = repo
+        .work_dir()
+        .with_context(|| "Pathspecs can only be used with a worktree")?;
+    let prefix = repo.prefix().expect("worktree - we have a work dir")?;
+    let patterns = pathspecs
+        .iter()
+        .map(|spec| git::pathspec::parse(spec))
+        .collect::<Result<Vec<_>, _>>()?;
+    if patterns.iter().any(|pattern| !pattern.attributes.is_empty()) {
+        bail!("Pathspecs with attributes aren't supported yet");
+    }
+    Ok(git::pathspec::Search::from_specs_with_prefix(patterns, &prefix, root)?)
+}
+
 pub fn from_tree(
     mut spec: OsString,
     index_path: Option<PathBuf>,
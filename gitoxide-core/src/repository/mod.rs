@@ -13,6 +13,7 @@ pub fn init(directory: Option<PathBuf>) -> Result<git::discover::repository::Pat
 }
 
 pub mod commit;
+pub mod commitgraph;
 pub mod config;
 mod credential;
 pub use credential::function as credential;
@@ -30,5 +31,6 @@ pub mod mailmap;
 pub mod odb;
 pub mod remote;
 pub mod revision;
+pub mod status;
 pub mod tree;
 pub mod verify;
@@ -1,6 +1,5 @@
 use std::io;
 
-use anyhow::bail;
 use git_repository as git;
 
 use crate::OutputFormat;
@@ -49,13 +48,22 @@ pub fn info(
 }
 
 pub fn entries(repo: git::Repository, format: OutputFormat, mut out: impl io::Write) -> anyhow::Result<()> {
-    if format != OutputFormat::Human {
-        bail!("Only human output format is supported at the moment");
-    }
-
-    for object in repo.objects.iter()? {
-        let object = object?;
-        writeln!(out, "{}", object)?;
+    match format {
+        OutputFormat::Human => {
+            for object in repo.objects.iter()? {
+                let object = object?;
+                writeln!(out, "{}", object)?;
+            }
+        }
+        #[cfg(feature = "serde1")]
+        OutputFormat::Json => {
+            let ids = repo
+                .objects
+                .iter()?
+                .map(|id| id.map(|id| id.to_string()))
+                .collect::<Result<Vec<_>, _>>()?;
+            serde_json::to_writer_pretty(out, &ids)?;
+        }
     }
 
     Ok(())
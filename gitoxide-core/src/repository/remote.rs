@@ -55,9 +55,6 @@ mod refs_impl {
             show_unmapped_remote_refs,
         } = &kind
         {
-            if format != OutputFormat::Human {
-                bail!("JSON output isn't yet supported for listing ref-mappings.");
-            }
             if !ref_specs.is_empty() {
                 remote.replace_refspecs(ref_specs.iter(), git::remote::Direction::Fetch)?;
             }
@@ -86,14 +83,27 @@ mod refs_impl {
             writeln!(out, "\t{:?}", map.handshake)?;
         }
         match kind {
-            refs::Kind::Tracking { .. } => print_refmap(
-                &repo,
-                remote.refspecs(git::remote::Direction::Fetch),
-                map,
-                show_unmapped,
-                out,
-                err,
-            ),
+            refs::Kind::Tracking { .. } => match format {
+                OutputFormat::Human => print_refmap(
+                    &repo,
+                    remote.refspecs(git::remote::Direction::Fetch),
+                    map,
+                    show_unmapped,
+                    out,
+                    err,
+                ),
+                #[cfg(feature = "serde1")]
+                OutputFormat::Json => {
+                    let refspecs = remote.refspecs(git::remote::Direction::Fetch);
+                    let mappings = map
+                        .mappings
+                        .into_iter()
+                        .map(|mapping| JsonMapping::new(&repo, refspecs, mapping))
+                        .collect::<Result<Vec<_>, _>>()?;
+                    serde_json::to_writer_pretty(out, &mappings)?;
+                    Ok(())
+                }
+            },
             refs::Kind::Remote => {
                 match format {
                     OutputFormat::Human => drop(print(out, &map.remote_refs)),
@@ -137,18 +147,7 @@ mod refs_impl {
             match &mapping.local {
                 Some(local) => {
                     write!(out, " -> {local} ")?;
-                    match repo.try_find_reference(local)? {
-                        Some(tracking) => {
-                            let msg = match tracking.try_id() {
-                                Some(id) => (id.as_ref() == target_id)
-                                    .then(|| "[up-to-date]")
-                                    .unwrap_or("[changed]"),
-                                None => "[skipped]",
-                            };
-                            writeln!(out, "{msg}")
-                        }
-                        None => writeln!(out, "[new]"),
-                    }
+                    writeln!(out, "[{}]", tracking_status(repo, local.as_ref(), target_id)?)
                 }
                 None => writeln!(out, " (fetch only)"),
             }?;
@@ -205,6 +204,64 @@ mod refs_impl {
         Ok(())
     }
 
+    /// Return how the local tracking ref `local` relates to `target_id`, the object the remote ref points to.
+    fn tracking_status(
+        repo: &git::Repository,
+        local: &git::bstr::BStr,
+        target_id: &git::hash::oid,
+    ) -> anyhow::Result<&'static str> {
+        Ok(match repo.try_find_reference(local)? {
+            Some(tracking) => match tracking.try_id() {
+                Some(id) if id.as_ref() == target_id => "up-to-date",
+                Some(_) => "changed",
+                None => "skipped",
+            },
+            None => "new",
+        })
+    }
+
+    /// A mapping of a remote ref to a local tracking ref, as serialized when listing ref-mappings.
+    #[cfg_attr(feature = "serde1", derive(serde::Serialize, serde::Deserialize))]
+    pub struct JsonMapping {
+        /// The ref-spec that produced the mapping.
+        pub spec: String,
+        /// The remote ref, or `None` if the ref-spec named an object id.
+        pub remote: Option<JsonRef>,
+        /// The object the remote ref points to, or `None` if it's unborn.
+        pub object: Option<String>,
+        /// The local tracking ref, or `None` if the object is only fetched.
+        pub local: Option<String>,
+        /// How the local tracking ref relates to `object`, one of `new`, `up-to-date`, `changed` or `skipped`.
+        pub status: Option<String>,
+    }
+
+    #[cfg(feature = "serde1")]
+    impl JsonMapping {
+        fn new(
+            repo: &git::Repository,
+            refspecs: &[RefSpec],
+            mapping: git::remote::fetch::Mapping,
+        ) -> anyhow::Result<Self> {
+            let object = mapping.remote.as_id().map(ToOwned::to_owned);
+            let status = match (&mapping.local, &object) {
+                (Some(local), Some(object)) => Some(tracking_status(repo, local.as_ref(), object)?.to_owned()),
+                _ => None,
+            };
+            let mut spec = Vec::new();
+            refspecs[mapping.spec_index].to_ref().write_to(&mut spec)?;
+            Ok(JsonMapping {
+                spec: git::bstr::BString::from(spec).to_string(),
+                remote: match mapping.remote {
+                    Source::ObjectId(_) => None,
+                    Source::Ref(r) => Some(r.into()),
+                },
+                object: object.map(|id| id.to_string()),
+                local: mapping.local.map(|local| local.to_string()),
+                status,
+            })
+        }
+    }
+
     #[cfg_attr(feature = "serde1", derive(serde::Serialize, serde::Deserialize))]
     pub enum JsonRef {
         Peeled {
@@ -295,7 +352,7 @@ mod refs_impl {
     }
 }
 #[cfg(any(feature = "blocking-client", feature = "async-client"))]
-pub use refs_impl::{refs, refs_fn as refs, JsonRef};
+pub use refs_impl::{refs, refs_fn as refs, JsonMapping, JsonRef};
 
 #[cfg(any(feature = "blocking-client", feature = "async-client"))]
 pub(crate) fn by_name_or_url<'repo>(
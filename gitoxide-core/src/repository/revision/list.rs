@@ -1,6 +1,6 @@
 use std::ffi::OsString;
 
-use anyhow::{bail, Context};
+use anyhow::Context;
 use git_repository as git;
 use git_repository::prelude::ObjectIdExt;
 
@@ -12,9 +12,6 @@ pub fn list(
     mut out: impl std::io::Write,
     format: OutputFormat,
 ) -> anyhow::Result<()> {
-    if format != OutputFormat::Human {
-        bail!("Only human output is currently supported");
-    }
     repo.object_cache_size_if_unset(4 * 1024 * 1024);
 
     let spec = git::path::os_str_into_bstr(&spec)?;
@@ -27,8 +24,21 @@ pub fn list(
         .context("Need commitish as starting point")?
         .id
         .attach(&repo);
-    for commit in commit_id.ancestors().all()? {
-        writeln!(out, "{}", commit?.to_hex())?;
+    match format {
+        OutputFormat::Human => {
+            for commit in commit_id.ancestors().all()? {
+                writeln!(out, "{}", commit?.to_hex())?;
+            }
+        }
+        #[cfg(feature = "serde1")]
+        OutputFormat::Json => {
+            let ids = commit_id
+                .ancestors()
+                .all()?
+                .map(|id| id.map(|id| id.to_hex().to_string()))
+                .collect::<Result<Vec<_>, _>>()?;
+            serde_json::to_writer_pretty(out, &ids)?;
+        }
     }
     Ok(())
 }
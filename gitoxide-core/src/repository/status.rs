@@ -0,0 +1,152 @@
+use std::{io, sync::atomic::AtomicBool};
+
+use anyhow::Context;
+use git_repository as git;
+use git_repository::{
+    bstr::{BString, ByteSlice},
+    prelude::FindExt,
+};
+
+use crate::OutputFormat;
+
+/// How a file in the worktree differs from the index.
+#[cfg_attr(feature = "serde1", derive(serde::Serialize))]
+#[cfg_attr(feature = "serde1", serde(rename_all = "kebab-case"))]
+pub enum Status {
+    Removed,
+    Modified,
+    Untracked,
+    Ignored,
+}
+
+/// A file in the worktree that differs from the index, as serialized when printing the status.
+#[cfg_attr(feature = "serde1", derive(serde::Serialize))]
+pub struct Entry {
+    pub status: Status,
+    /// The path relative to the worktree root, with a trailing slash for directories.
+    pub path: String,
+}
+
+pub struct Options {
+    pub format: OutputFormat,
+    /// How to report untracked files, if at all.
+    pub untracked: git::worktree::untracked::Mode,
+    /// Report ignored files as well.
+    pub ignored: bool,
+    /// If set, don't use more than this amount of threads.
+    /// Otherwise, usually use as many threads as there are logical cores.
+    /// A value of 0 is interpreted as no-limit
+    pub thread_limit: Option<usize>,
+}
+
+/// Compare the worktree with the index and print all changed files matching `pathspecs`, similar to `git status --porcelain`
+/// but without comparing the index to `HEAD`.
+///
+/// Removed and modified files are printed first in index order, followed by untracked and then ignored files
+/// sorted by path.
+pub fn status(
+    repo: git::Repository,
+    pathspecs: Vec<BString>,
+    mut out: impl io::Write,
+    mut err: impl io::Write,
+    should_interrupt: &AtomicBool,
+    Options {
+        format,
+        untracked,
+        ignored,
+        thread_limit,
+    }: Options,
+) -> anyhow::Result<()> {
+    let worktree = repo
+        .worktree()
+        .with_context(|| "Cannot obtain the status without a current worktree")?;
+    let search = super::index::pathspec_search(&repo, pathspecs)?;
+    let mut index = worktree.open_index()?;
+    let mut status_options = worktree.status_options()?;
+    if thread_limit.is_some() {
+        status_options.thread_limit = thread_limit;
+    }
+    let fs = status_options.fs;
+    let objects = repo.objects.clone().into_arc()?;
+
+    let outcome = git::worktree::index::status(
+        &mut index,
+        worktree.base(),
+        None::<&mut git::worktree::fs_monitor::Hook>,
+        move |oid, buf| objects.find_blob(oid, buf),
+        should_interrupt,
+        status_options,
+    )?;
+    let mut entries: Vec<_> = outcome
+        .changes
+        .into_iter()
+        .filter(|change| search.is_included(change.rela_path.as_bstr(), |_, _| {}))
+        .map(|change| Entry {
+            status: match change.change {
+                git::worktree::index::status::Change::Removed => Status::Removed,
+                git::worktree::index::status::Change::Modified => Status::Modified,
+            },
+            path: change.rela_path.to_string(),
+        })
+        .collect();
+
+    if untracked != git::worktree::untracked::Mode::No {
+        let cache = worktree.excludes(&index, None)?;
+        let objects = repo.objects.clone().into_arc()?;
+        let outcome = git::worktree::untracked::walk(
+            &index,
+            &cache,
+            move |oid, buf| objects.find_blob(oid, buf),
+            None,
+            should_interrupt,
+            git::worktree::untracked::Options {
+                mode: untracked,
+                emit_ignored: ignored,
+                thread_limit,
+                fs,
+            },
+        )?;
+        let mut untracked_entries = outcome.entries;
+        // Like git, show all untracked entries before the ignored ones.
+        untracked_entries.sort_by_key(|entry| entry.status);
+        entries.extend(
+            untracked_entries
+                .into_iter()
+                .filter(|entry| search.is_included(entry.rela_path.as_bstr(), |_, _| {}))
+                .map(|entry| {
+                    let trailing_slash = match entry.kind {
+                        git::worktree::untracked::Kind::Directory | git::worktree::untracked::Kind::Repository => "/",
+                        git::worktree::untracked::Kind::File | git::worktree::untracked::Kind::Symlink => "",
+                    };
+                    Entry {
+                        status: match entry.status {
+                            git::worktree::untracked::Status::Untracked => Status::Untracked,
+                            git::worktree::untracked::Status::Ignored => Status::Ignored,
+                        },
+                        path: format!("{}{}", entry.rela_path, trailing_slash),
+                    }
+                }),
+        );
+    }
+
+    match format {
+        OutputFormat::Human => {
+            for entry in &entries {
+                let status = match entry.status {
+                    Status::Removed => " D",
+                    Status::Modified => " M",
+                    Status::Untracked => "??",
+                    Status::Ignored => "!!",
+                };
+                writeln!(out, "{} {}", status, entry.path)?;
+            }
+        }
+        #[cfg(feature = "serde1")]
+        OutputFormat::Json => serde_json::to_writer_pretty(&mut out, &entries)?,
+    }
+
+    if should_interrupt.load(std::sync::atomic::Ordering::Relaxed) {
+        writeln!(err, "Interrupted - the status is incomplete").ok();
+    }
+    Ok(())
+}
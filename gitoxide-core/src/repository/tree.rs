@@ -1,6 +1,5 @@
 use std::{borrow::Cow, io};
 
-use anyhow::bail;
 use git_repository as git;
 use git_repository::{prelude::ObjectIdExt, Tree};
 
@@ -33,10 +32,36 @@ mod entries {
         pub num_bytes: u64,
     }
 
+    /// A tree entry along with its path, as serialized when listing entries.
+    #[cfg(feature = "serde1")]
+    #[derive(serde::Serialize)]
+    pub struct Entry {
+        pub mode: git::objs::tree::EntryMode,
+        pub id: String,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        pub size: Option<usize>,
+        pub path: String,
+    }
+
+    #[cfg(feature = "serde1")]
+    impl Entry {
+        pub fn new(entry: &EntryRef<'_>, path: &BStr, size: Option<usize>) -> Self {
+            Entry {
+                mode: entry.mode,
+                id: entry.oid.to_hex().to_string(),
+                size,
+                path: path.to_string(),
+            }
+        }
+    }
+
     pub struct Traverse<'repo, 'a> {
         pub stats: Statistics,
         repo: Option<&'repo git::Repository>,
         out: Option<&'a mut dyn std::io::Write>,
+        /// If set, all non-tree entries are collected here instead of being written to `out`.
+        #[cfg(feature = "serde1")]
+        pub entries: Option<Vec<Entry>>,
         path: BString,
         path_deque: VecDeque<BString>,
     }
@@ -47,6 +72,8 @@ mod entries {
                 stats: Default::default(),
                 repo,
                 out,
+                #[cfg(feature = "serde1")]
+                entries: None,
                 path: BString::default(),
                 path_deque: VecDeque::new(),
             }
@@ -96,6 +123,10 @@ mod entries {
             let size = self
                 .repo
                 .and_then(|repo| repo.find_object(entry.oid).map(|o| o.data.len()).ok());
+            #[cfg(feature = "serde1")]
+            if let Some(entries) = &mut self.entries {
+                entries.push(Entry::new(entry, self.path.as_bstr(), size));
+            }
             if let Some(out) = &mut self.out {
                 format_entry(out, entry, self.path.as_bstr(), size).ok();
             }
@@ -142,6 +173,7 @@ pub fn info(
     Ok(())
 }
 
+#[cfg_attr(not(feature = "serde1"), allow(unused_variables))]
 pub fn entries(
     repo: git::Repository,
     treeish: Option<&str>,
@@ -150,12 +182,30 @@ pub fn entries(
     format: OutputFormat,
     mut out: impl io::Write,
 ) -> anyhow::Result<()> {
-    if format != OutputFormat::Human {
-        bail!("Only human output format is supported at the moment");
-    }
-
     let tree = treeish_to_tree(treeish, &repo)?;
 
+    #[cfg(feature = "serde1")]
+    if let OutputFormat::Json = format {
+        let entries = if recursive {
+            let mut delegate = entries::Traverse::new(extended.then(|| &repo), None);
+            delegate.entries = Some(Vec::new());
+            tree.traverse().breadthfirst(&mut delegate)?;
+            delegate.entries.expect("set above")
+        } else {
+            let mut entries = Vec::new();
+            for entry in tree.iter() {
+                let entry = entry?;
+                let size = extended
+                    .then(|| entry.id().object().map(|o| o.data.len()))
+                    .transpose()?;
+                entries.push(entries::Entry::new(&entry.inner, entry.inner.filename, size));
+            }
+            entries
+        };
+        serde_json::to_writer_pretty(out, &entries)?;
+        return Ok(());
+    }
+
     if recursive {
         let mut delegate = entries::Traverse::new(extended.then(|| &repo), Some(&mut out));
         tree.traverse().breadthfirst(&mut delegate)?;
@@ -15,7 +15,10 @@ use gitoxide_core::pack::verify;
 
 use crate::{
     plumbing::{
-        options::{commit, config, credential, exclude, free, index, mailmap, odb, revision, tree, Args, Subcommands},
+        options::{
+            check_ignore, commit, commitgraph, config, credential, exclude, free, index, ls_files, mailmap, odb,
+            revision, status, tree, Args, Subcommands,
+        },
         show_progress,
     },
     shared::pretty::prepare_and_run,
@@ -33,7 +36,7 @@ pub mod async_util {
         name: &str,
         range: impl Into<Option<ProgressRange>>,
     ) -> (
-        Option<prodash::render::line::JoinHandle>,
+        Option<git_features::progress::render::Handle>,
         git_features::progress::DoOrDiscard<prodash::tree::Item>,
     ) {
         use crate::shared::{self, STANDARD_RANGE};
@@ -116,6 +119,8 @@ pub fn main() -> Result<()> {
         Subcommands::Clone(crate::plumbing::options::clone::Platform {
             handshake_info,
             bare,
+            no_checkout,
+            depth,
             remote,
             directory,
         }) => {
@@ -123,6 +128,10 @@ pub fn main() -> Result<()> {
                 format,
                 bare,
                 handshake_info,
+                no_checkout,
+                shallow: depth
+                    .map(git::remote::fetch::Shallow::DepthAtRemote)
+                    .unwrap_or_default(),
             };
             prepare_and_run(
                 "clone",
@@ -781,6 +790,23 @@ pub fn main() -> Result<()> {
                 move |_progress, out, err| core::repository::odb::info(repository(Mode::Strict)?, format, out, err),
             ),
         },
+        Subcommands::CommitGraph(cmd) => match cmd {
+            commitgraph::Subcommands::Verify => prepare_and_run(
+                "commitgraph-verify",
+                verbose,
+                progress,
+                progress_keep_open,
+                None,
+                move |_progress, out, _err| {
+                    core::repository::commitgraph::verify(
+                        repository(Mode::Strict)?,
+                        out,
+                        thread_limit,
+                        &should_interrupt,
+                    )
+                },
+            ),
+        },
         Subcommands::Mailmap(cmd) => match cmd {
             mailmap::Subcommands::Entries => prepare_and_run(
                 "mailmap-entries",
@@ -828,6 +854,43 @@ pub fn main() -> Result<()> {
                 },
             ),
         },
+        Subcommands::CheckIgnore(check_ignore::Platform {
+            verbose: check_verbose,
+            non_matching,
+            no_index,
+            patterns,
+            pathspecs,
+        }) => prepare_and_run(
+            "check-ignore",
+            verbose,
+            progress,
+            progress_keep_open,
+            None,
+            move |_progress, out, _err| {
+                use git::bstr::ByteSlice;
+                core::repository::exclude::check_ignore(
+                    repository(Mode::Strict)?,
+                    if pathspecs.is_empty() {
+                        Box::new(
+                            stdin_or_bail()?
+                                .byte_lines()
+                                .filter_map(Result::ok)
+                                .filter_map(|line| git::path::Spec::from_bytes(line.as_bstr())),
+                        ) as Box<dyn Iterator<Item = git::path::Spec>>
+                    } else {
+                        Box::new(pathspecs.into_iter())
+                    },
+                    out,
+                    core::repository::exclude::check_ignore::Options {
+                        format,
+                        overrides: patterns,
+                        verbose: check_verbose,
+                        non_matching,
+                        no_index,
+                    },
+                )
+            },
+        ),
         Subcommands::Index(cmd) => match cmd {
             index::Subcommands::FromTree {
                 force,
@@ -844,10 +907,70 @@ pub fn main() -> Result<()> {
                 },
             ),
         },
+        Subcommands::LsFiles(ls_files::Platform {
+            stage,
+            flags,
+            pathspecs,
+        }) => prepare_and_run(
+            "ls-files",
+            verbose,
+            progress,
+            progress_keep_open,
+            None,
+            move |_progress, out, _err| {
+                core::repository::index::ls_files(
+                    repository(Mode::Strict)?,
+                    pathspecs_to_bstr(pathspecs)?,
+                    out,
+                    core::repository::index::ls_files::Options { format, stage, flags },
+                )
+            },
+        ),
+        Subcommands::Status(status::Platform {
+            untracked_files,
+            ignored,
+            pathspecs,
+        }) => prepare_and_run(
+            "status",
+            verbose,
+            progress,
+            progress_keep_open,
+            None,
+            move |_progress, out, err| {
+                core::repository::status::status(
+                    repository(Mode::Strict)?,
+                    pathspecs_to_bstr(pathspecs)?,
+                    out,
+                    err,
+                    &should_interrupt,
+                    core::repository::status::Options {
+                        format,
+                        untracked: match untracked_files {
+                            status::Untracked::No => git::worktree::untracked::Mode::No,
+                            status::Untracked::Normal => git::worktree::untracked::Mode::Normal,
+                            status::Untracked::All => git::worktree::untracked::Mode::All,
+                        },
+                        ignored,
+                        thread_limit,
+                    },
+                )
+            },
+        ),
     }?;
     Ok(())
 }
 
+fn pathspecs_to_bstr(pathspecs: Vec<std::ffi::OsString>) -> Result<Vec<git_repository::bstr::BString>> {
+    pathspecs
+        .iter()
+        .map(|spec| {
+            git_repository::path::os_str_into_bstr(spec)
+                .map(ToOwned::to_owned)
+                .map_err(Into::into)
+        })
+        .collect()
+}
+
 fn stdin_or_bail() -> Result<std::io::BufReader<std::io::Stdin>> {
     if atty::is(atty::Stream::Stdin) {
         anyhow::bail!("Refusing to read from standard input while a terminal is connected")
@@ -70,6 +70,9 @@ pub enum Subcommands {
     /// Interact with commit objects.
     #[clap(subcommand)]
     Commit(commit::Subcommands),
+    /// Interact with the commit-graph.
+    #[clap(subcommand)]
+    CommitGraph(commitgraph::Subcommands),
     /// Verify the integrity of the entire repository
     Verify {
         #[clap(flatten)]
@@ -95,8 +98,14 @@ pub enum Subcommands {
     /// Interact with the exclude files like .gitignore.
     #[clap(subcommand)]
     Exclude(exclude::Subcommands),
+    /// Print paths that are ignored, just like `git check-ignore`.
+    CheckIgnore(check_ignore::Platform),
     #[clap(subcommand)]
     Index(index::Subcommands),
+    /// List the entries of the index, similar to `git ls-files`.
+    LsFiles(ls_files::Platform),
+    /// Show files in the worktree that differ from the index or aren't tracked, similar to `git status --porcelain`.
+    Status(status::Platform),
     /// Display overall progress of the gitoxide project as seen from the perspective of git-config.
     Progress,
     Config(config::Platform),
@@ -158,6 +167,14 @@ pub mod clone {
         #[clap(long)]
         pub bare: bool,
 
+        /// If set, the working tree won't be checked out after fetching, leaving an index and work tree that don't match `HEAD`.
+        #[clap(long)]
+        pub no_checkout: bool,
+
+        /// Only fetch the given amount of commits from the tip of each remote branch, creating a shallow repository.
+        #[clap(long, value_name = "DEPTH")]
+        pub depth: Option<std::num::NonZeroU32>,
+
         /// The url of the remote to connect to, like `https://github.com/byron/gitoxide`.
         pub remote: OsString,
 
@@ -204,6 +221,14 @@ pub mod remote {
     }
 }
 
+pub mod commitgraph {
+    #[derive(Debug, clap::Subcommand)]
+    pub enum Subcommands {
+        /// Verify the commit-graph against the commit objects it was created from, suggesting how to fix issues.
+        Verify,
+    }
+}
+
 pub mod mailmap {
     #[derive(Debug, clap::Subcommand)]
     pub enum Subcommands {
@@ -360,6 +385,94 @@ pub mod exclude {
     }
 }
 
+pub mod check_ignore {
+    use std::ffi::OsString;
+
+    use git_repository as git;
+
+    #[derive(Debug, clap::Parser)]
+    pub struct Platform {
+        /// Print the pattern that matched each path along with the file and line it is from.
+        ///
+        /// Note that patterns that un-exclude a path are shown as well.
+        #[clap(long, short = 'v')]
+        pub verbose: bool,
+        /// Also print paths that don't match any pattern, which requires `--verbose`.
+        #[clap(long, short = 'n', requires = "verbose")]
+        pub non_matching: bool,
+        /// Don't consult the index, which otherwise prevents tracked paths from being considered ignored.
+        #[clap(long)]
+        pub no_index: bool,
+        /// Additional patterns to use for exclusions. They have the highest priority.
+        #[clap(long, short = 'p')]
+        pub patterns: Vec<OsString>,
+        /// The git path specifications to check, or unset to read from stdin one per line.
+        #[clap(parse(try_from_os_str = std::convert::TryFrom::try_from))]
+        pub pathspecs: Vec<git::path::Spec>,
+    }
+}
+
+pub mod ls_files {
+    use std::ffi::OsString;
+
+    #[derive(Debug, clap::Parser)]
+    pub struct Platform {
+        /// Print the mode, object id and stage of each entry.
+        #[clap(long, short = 's')]
+        pub stage: bool,
+        /// Print the flags of each entry.
+        #[clap(long, short = 'f')]
+        pub flags: bool,
+        /// The git path specifications to list entries for, or unset to list all of them.
+        pub pathspecs: Vec<OsString>,
+    }
+}
+
+pub mod status {
+    use std::ffi::OsString;
+
+    #[derive(Debug, Clone, Copy)]
+    pub enum Untracked {
+        /// Don't show untracked files.
+        No,
+        /// Show untracked files, but show untracked directories as a whole instead of their content.
+        Normal,
+        /// Show all untracked files individually.
+        All,
+    }
+
+    impl Untracked {
+        pub fn variants() -> &'static [&'static str] {
+            &["no", "normal", "all"]
+        }
+    }
+
+    impl std::str::FromStr for Untracked {
+        type Err = String;
+
+        fn from_str(s: &str) -> Result<Self, Self::Err> {
+            Ok(match s {
+                "no" => Untracked::No,
+                "normal" => Untracked::Normal,
+                "all" => Untracked::All,
+                _ => return Err(format!("Invalid value for untracked files: '{}'", s)),
+            })
+        }
+    }
+
+    #[derive(Debug, clap::Parser)]
+    pub struct Platform {
+        /// How to show untracked files.
+        #[clap(long, short = 'u', default_value = "normal", possible_values(Untracked::variants()))]
+        pub untracked_files: Untracked,
+        /// Show ignored files as well.
+        #[clap(long)]
+        pub ignored: bool,
+        /// The git path specifications to show the status of, or unset to show the status of all files.
+        pub pathspecs: Vec<OsString>,
+    }
+}
+
 pub mod index {
     use std::path::PathBuf;
 
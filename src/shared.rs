@@ -20,8 +20,8 @@ pub fn init_env_logger() {
 }
 
 #[cfg(feature = "prodash-render-line")]
-pub fn progress_tree() -> std::sync::Arc<prodash::Tree> {
-    prodash::TreeOptions {
+pub fn progress_tree() -> std::sync::Arc<git_features::progress::Tree> {
+    git_features::progress::TreeOptions {
         message_buffer_capacity: 200,
         ..Default::default()
     }
@@ -201,22 +201,18 @@ pub mod pretty {
 #[allow(unused)]
 #[cfg(feature = "prodash-render-line")]
 pub fn setup_line_renderer_range(
-    progress: &std::sync::Arc<prodash::Tree>,
+    progress: &std::sync::Arc<git_features::progress::Tree>,
     levels: std::ops::RangeInclusive<prodash::progress::key::Level>,
-) -> prodash::render::line::JoinHandle {
-    prodash::render::line(
+) -> git_features::progress::render::Handle {
+    git_features::progress::render::line(
+        progress,
         std::io::stderr(),
-        std::sync::Arc::downgrade(progress),
-        prodash::render::line::Options {
+        git_features::progress::render::Options {
             level_filter: Some(levels),
             frames_per_second: DEFAULT_FRAME_RATE,
             initial_delay: Some(std::time::Duration::from_millis(1000)),
-            timestamp: true,
-            throughput: true,
-            hide_cursor: true,
-            ..prodash::render::line::Options::default()
-        }
-        .auto_configure(prodash::render::line::StreamKind::Stderr),
+            ..Default::default()
+        },
     )
 }
 